@@ -1,5 +1,11 @@
 use crate::controller::generate_handler_code;
-use crate::controller::get_base_path::get_base_path;
+use crate::controller::get_base_path::parse_controller_args;
+use crate::controller::metadata::parse_metadata;
+use crate::controller::pipes::parse_use_pipes;
+use crate::controller::providers::{parse_use_guards, parse_use_interceptors};
+use crate::controller::route_args::RouteArgs;
+use crate::controller::throttle::parse_throttle;
+use crate::controller::timeout::parse_timeout;
 use crate::core::{
 	analyze_parameter, gen_constructor, has_self, implments_impl_mur_dependencies, is_constructor,
 	normalize_path,
@@ -21,6 +27,8 @@ impl Fold for StripUsePipeAttrs {
 					&& !attr.path().is_ident("param")
 					&& !attr.path().is_ident("query")
 					&& !attr.path().is_ident("queryparam")
+					&& !attr.path().is_ident("session")
+					&& !attr.path().is_ident("validate")
 			});
 		}
 		fold::fold_fn_arg(self, arg)
@@ -28,11 +36,38 @@ impl Fold for StripUsePipeAttrs {
 }
 
 pub fn controller_impl(args: proc_macro::TokenStream, input: ItemImpl) -> TokenStream {
-	let base_path = get_base_path(args);
+	let (base_path, host, controller_versions) = match parse_controller_args(args) {
+		Ok(args) => (args.base_path, args.host, args.versions),
+		Err(e) => return e.to_compile_error(),
+	};
 	let impl_type = &input.self_ty;
 	let generics = &input.generics;
 	let (impl_generics, _ty_generics, where_clause) = generics.split_for_impl();
 	let mut route_registrations: Vec<TokenStream> = Vec::new();
+	let controller_throttle = match parse_throttle(&input.attrs) {
+		Ok(throttle) => throttle,
+		Err(e) => return e.to_compile_error(),
+	};
+	let controller_timeout = match parse_timeout(&input.attrs) {
+		Ok(timeout) => timeout,
+		Err(e) => return e.to_compile_error(),
+	};
+	let controller_pipes = match parse_use_pipes(&input.attrs) {
+		Ok(pipes) => pipes,
+		Err(e) => return e.to_compile_error(),
+	};
+	let controller_metadata = match parse_metadata(&input.attrs) {
+		Ok(calls) => calls,
+		Err(e) => return e.to_compile_error(),
+	};
+	let controller_guards = match parse_use_guards(&input.attrs) {
+		Ok(guards) => guards,
+		Err(e) => return e.to_compile_error(),
+	};
+	let controller_interceptors = match parse_use_interceptors(&input.attrs) {
+		Ok(interceptors) => interceptors,
+		Err(e) => return e.to_compile_error(),
+	};
 
 	for item in &input.items {
 		let syn::ImplItem::Fn(method) = item else {
@@ -42,9 +77,38 @@ pub fn controller_impl(args: proc_macro::TokenStream, input: ItemImpl) -> TokenS
 		let method_inputs = &method.sig.inputs;
 		let mut is_public = false;
 		let mut allowed_roles: Vec<syn::Path> = Vec::new();
+		let throttle = match parse_throttle(&method.attrs) {
+			Ok(throttle) => throttle
+				.or_else(|| controller_throttle.clone())
+				.unwrap_or_else(|| quote! { None }),
+			Err(e) => return e.to_compile_error(),
+		};
+		let timeout = match parse_timeout(&method.attrs) {
+			Ok(timeout) => timeout
+				.or_else(|| controller_timeout.clone())
+				.unwrap_or_else(|| quote! { None }),
+			Err(e) => return e.to_compile_error(),
+		};
+		let scoped_pipes = match parse_use_pipes(&method.attrs) {
+			Ok(pipes) => [controller_pipes.clone(), pipes].concat(),
+			Err(e) => return e.to_compile_error(),
+		};
+		// Method entries come last so they replace the controller's.
+		let metadata = match parse_metadata(&method.attrs) {
+			Ok(calls) => [controller_metadata.clone(), calls].concat(),
+			Err(e) => return e.to_compile_error(),
+		};
+		let guards = match parse_use_guards(&method.attrs) {
+			Ok(guards) => [controller_guards.clone(), guards].concat(),
+			Err(e) => return e.to_compile_error(),
+		};
+		let interceptors = match parse_use_interceptors(&method.attrs) {
+			Ok(interceptors) => [controller_interceptors.clone(), interceptors].concat(),
+			Err(e) => return e.to_compile_error(),
+		};
+		let method_name_str = method_name.to_string();
 
 		if !is_constructor(&method.sig.output) && !has_self(method_inputs) {
-			let method_name_str = method_name.to_string();
 			return quote! {
 				compile_error!(concat!(
 					"Invalid controller method `",
@@ -71,22 +135,9 @@ pub fn controller_impl(args: proc_macro::TokenStream, input: ItemImpl) -> TokenS
 				}
 			}
 
-			let (http_method, tokens_str) = match &attr.meta {
-				Meta::Path(p) => {
-					let Some(ident) = p.get_ident() else {
-						continue;
-					};
-					(ident.to_string(), String::new())
-				}
-				Meta::List(MetaList { path, tokens, .. }) => {
-					let Some(ident) = path.get_ident() else {
-						continue;
-					};
-					(ident.to_string(), tokens.to_string())
-				}
-				_ => continue,
+			let Some(http_method) = attr.path().get_ident().map(|ident| ident.to_string()) else {
+				continue;
 			};
-
 			if !matches!(
 				http_method.as_str(),
 				"get" | "post" | "put" | "delete" | "patch" | "head" | "options"
@@ -94,7 +145,22 @@ pub fn controller_impl(args: proc_macro::TokenStream, input: ItemImpl) -> TokenS
 				continue;
 			}
 
+			let (tokens_str, route_versions) = match &attr.meta {
+				Meta::Path(_) => (String::new(), None),
+				// Read the literal's value so escapes in constraints such as
+				// `r":id(\d+)"` survive.
+				Meta::List(MetaList { tokens, .. }) => {
+					match syn::parse2::<RouteArgs>(tokens.clone()) {
+						Ok(args) => (args.path, args.versions),
+						Err(e) if tokens.to_string().contains('=') => return e.to_compile_error(),
+						Err(_) => (tokens.to_string(), None),
+					}
+				}
+				_ => continue,
+			};
+
 			let http_method_upper = http_method.to_uppercase();
+			let versions = route_versions.unwrap_or_else(|| controller_versions.clone());
 			let route_path = normalize_path(tokens_str.trim_matches('"'));
 			let full_path = merge_paths(&base_path, &route_path);
 			let params: Vec<ParamInfo> = method_inputs
@@ -107,7 +173,7 @@ pub fn controller_impl(args: proc_macro::TokenStream, input: ItemImpl) -> TokenS
 					}
 				})
 				.collect();
-			let handler_code = generate_handler_code(method_name, &params);
+			let handler_code = generate_handler_code(method_name, &params, &scoped_pipes);
 
 			route_registrations.push(quote! {
 				routes.push(murgamu::MurRouteDefinition {
@@ -116,6 +182,14 @@ pub fn controller_impl(args: proc_macro::TokenStream, input: ItemImpl) -> TokenS
 					handler: #handler_code,
 					is_public: #is_public,
 					allowed_roles: vec![#(stringify!(#allowed_roles).to_string()),*],
+					throttle: #throttle,
+					timeout: #timeout,
+					metadata: murgamu::MurRouteMetadata::new()
+						.handler(#method_name_str)
+						#(#metadata)*,
+					guards: vec![#(#guards),*],
+					interceptors: vec![#(#interceptors),*],
+					versions: vec![#(#versions.to_string()),*],
 				});
 			});
 		}
@@ -124,6 +198,14 @@ pub fn controller_impl(args: proc_macro::TokenStream, input: ItemImpl) -> TokenS
 	let (ensure_constructor, ctor_lets, ctor_args, required_container_typeids) =
 		gen_constructor(&input);
 
+	let host_fn = host.map(|host| {
+		quote! {
+			fn host(&self) -> Option<&str> {
+				Some(#host)
+			}
+		}
+	});
+
 	let controller_trait_impl = quote! {
 		impl #impl_generics murgamu::MurController for #impl_type #where_clause {
 			fn routes(
@@ -158,6 +240,8 @@ pub fn controller_impl(args: proc_macro::TokenStream, input: ItemImpl) -> TokenS
 			fn base_path(&self) -> &str {
 				#base_path
 			}
+
+			#host_fn
 		}
 	};
 
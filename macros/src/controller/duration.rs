@@ -0,0 +1,18 @@
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::LitStr;
+
+/// Tokens of a `Duration` parsed from `value` such as `"500ms"`, `"30s"` or
+/// `"1h"`. Parsing runs in a const block through `MurTime::parse_duration`, so
+/// the macros and the runtime share one grammar and an invalid or overflowing
+/// value fails the build with `message`.
+pub(crate) fn parse_duration(value: &LitStr, message: &str) -> TokenStream {
+	quote_spanned! {value.span()=>
+		const {
+			match murgamu::core::utils::MurTime::parse_duration(#value) {
+				Some(duration) => duration,
+				None => panic!(#message),
+			}
+		}
+	}
+}
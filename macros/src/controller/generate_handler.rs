@@ -1,14 +1,28 @@
 use crate::types::{ParamInfo, ParamKind};
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::Ident;
+use quote::{format_ident, quote};
+use syn::{Expr, Ident};
 
-pub fn generate_handler_code(method_name: &Ident, params: &[ParamInfo]) -> TokenStream {
+/// Builds the handler closure for one route. `scoped_pipes` are the
+/// controller and method `#[use_pipes(..)]` entries, in that order.
+pub fn generate_handler_code(
+	method_name: &Ident,
+	params: &[ParamInfo],
+	scoped_pipes: &[Expr],
+) -> TokenStream {
 	let mut extractions = Vec::new();
 	let mut call_args = Vec::new();
+	let mut pipelines = Vec::new();
 
 	for param in params {
 		let name = &param.name;
+		let pipeline = format_ident!("__mur_pipeline_{}", name);
+		let mut with_pipeline = |kind: TokenStream, validated: Option<&TokenStream>| {
+			pipelines.push((
+				pipeline.clone(),
+				pipeline_setup(param, kind, validated, scoped_pipes),
+			));
+		};
 		let extraction = match &param.kind {
 			ParamKind::SelfRef | ParamKind::Unknown => continue,
 
@@ -63,106 +77,126 @@ pub fn generate_handler_code(method_name: &Ident, params: &[ParamInfo]) -> Token
 
 			ParamKind::Json(inner_ty) => {
 				call_args.push(quote!(#name));
+				with_pipeline(quote!(Body), Some(inner_ty));
 				quote! {
-					let #name: MurJson<#inner_ty> = match ctx.json() {
+					let #name: MurJson<#inner_ty> = match #pipeline.body(&ctx).await {
 						Ok(data) => MurJson(data),
-						Err(e) => return MurResponder::error(&format!("Failed to parse JSON: {}", e)),
+						Err(res) => return res,
+					};
+				}
+			}
+
+			ParamKind::Payload(inner_ty) => {
+				call_args.push(quote!(#name));
+				with_pipeline(quote!(Body), Some(inner_ty));
+				quote! {
+					let #name: murgamu::MurPayload<#inner_ty> = match #pipeline.payload(&ctx).await {
+						Ok(data) => murgamu::MurPayload(data),
+						Err(res) => return res,
+					};
+				}
+			}
+
+			ParamKind::Session => {
+				call_args.push(quote!(#name));
+				quote! {
+					let #name = match murgamu::MurSession::from_ctx(&ctx) {
+						Ok(session) => session,
+						Err(e) => return e.into(),
+					};
+				}
+			}
+
+			ParamKind::CsrfToken => {
+				call_args.push(quote!(#name));
+				quote! {
+					let #name = match murgamu::MurCsrfToken::from_ctx(&ctx) {
+						Ok(token) => token,
+						Err(e) => return e.into(),
+					};
+				}
+			}
+
+			ParamKind::ApiKey => {
+				call_args.push(quote!(#name));
+				quote! {
+					let #name = match murgamu::MurApiKeyIdentity::from_ctx(&ctx) {
+						Ok(key) => key,
+						Err(e) => return e.into(),
 					};
 				}
 			}
 
 			ParamKind::CustomJson(ty) => {
 				call_args.push(quote!(#name));
+				with_pipeline(quote!(Body), Some(ty));
 				quote! {
-					let #name: #ty = match ctx.json() {
+					let #name: #ty = match #pipeline.body(&ctx).await {
 						Ok(data) => data,
-						Err(e) => return MurResponder::error(&format!("Failed to parse body: {}", e)),
+						Err(res) => return res,
 					};
 				}
 			}
 
 			ParamKind::CustomQuery(ty) => {
 				call_args.push(quote!(#name));
+				with_pipeline(quote!(Query), Some(ty));
 				quote! {
-					let #name: #ty = {
-						let qs = ctx.parts.uri.query().unwrap_or("");
-						match serde_urlencoded::from_str(qs) {
-							Ok(data) => data,
-							Err(e) => return MurResponder::error(&format!("Failed to parse query: {}", e)),
-						}
+					let #name: #ty = match #pipeline.query(&ctx).await {
+						Ok(data) => data,
+						Err(res) => return res,
 					};
 				}
 			}
 
 			ParamKind::Query(inner_ty) => {
 				call_args.push(quote!(#name));
+				with_pipeline(quote!(Query), Some(inner_ty));
 				quote! {
-					let #name: MurQuery<#inner_ty> = {
-						let qs = ctx.parts.uri.query().unwrap_or("");
-						match serde_urlencoded::from_str(qs) {
-							Ok(data) => MurQuery(data),
-							Err(e) => return MurResponder::error(&format!("Failed to parse query: {}", e)),
-						}
+					let #name: MurQuery<#inner_ty> = match #pipeline.query(&ctx).await {
+						Ok(data) => MurQuery(data),
+						Err(res) => return res,
 					};
 				}
 			}
 
 			ParamKind::Path(inner_ty) => {
 				call_args.push(quote!(#name));
+				with_pipeline(quote!(Path), Some(inner_ty));
 				quote! {
-					let #name: MurPath<#inner_ty> = {
-						let json_value = serde_json::to_value(&ctx.path_params)
-							.unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
-						match serde_json::from_value(json_value) {
-							Ok(data) => MurPath(data),
-							Err(e) => return MurResponder::error(&format!("Failed to parse path: {}", e)),
-						}
+					let #name: MurPath<#inner_ty> = match #pipeline.path(&ctx).await {
+						Ok(data) => MurPath(data),
+						Err(res) => return res,
 					};
 				}
 			}
 
 			ParamKind::Param(inner_ty) => {
 				call_args.push(quote!(#name));
-				let param_name_str = name.to_string();
+				with_pipeline(quote!(Param), None);
 				quote! {
-					let #name: murgamu::Param<#inner_ty> = {
-						use std::str::FromStr;
-						match ctx.path_params.get(#param_name_str) {
-							Some(value) => match <#inner_ty>::from_str(value) {
-								Ok(parsed) => murgamu::Param(parsed),
-								Err(e) => return MurResponder::error(&format!(
-									"Failed to parse parameter '{}': {}", #param_name_str, e
-								)),
-							},
-							None => return MurResponder::error(&format!(
-								"Missing path parameter: {}", #param_name_str
-							)),
-						}
+					let #name: murgamu::Param<#inner_ty> = match #pipeline.param(&ctx).await {
+						Ok(parsed) => murgamu::Param(parsed),
+						Err(res) => return res,
 					};
 				}
 			}
 
 			ParamKind::RawParam(ty, inner_ty) => {
 				call_args.push(quote!(#name));
-				let param_name_str = name.to_string();
+				with_pipeline(quote!(Param), None);
 				if let Some(inner) = inner_ty {
 					quote! {
-						let #name: Option<#inner> = ctx.path_params
-							.get(#param_name_str)
-							.and_then(|val| val.parse().ok());
+						let #name: Option<#inner> = match #pipeline.param_opt(&ctx).await {
+							Ok(value) => value,
+							Err(res) => return res,
+						};
 					}
 				} else {
 					quote! {
-						let #name: #ty = match ctx.path_params.get(#param_name_str) {
-							Some(val) => match val.parse() {
-								Ok(parsed) => parsed,
-								Err(_) => return MurResponder::error(&format!(
-									"Invalid value for path parameter '{}'", #param_name_str
-								)),
-							},
-							None => return MurResponder::error(&format!(
-								"Missing path parameter: {}", #param_name_str
-							)),
+						let #name: #ty = match #pipeline.param(&ctx).await {
+							Ok(value) => value,
+							Err(res) => return res,
 						};
 					}
 				}
@@ -170,18 +204,19 @@ pub fn generate_handler_code(method_name: &Ident, params: &[ParamInfo]) -> Token
 
 			ParamKind::RawQueryParam(ty, inner_ty) => {
 				call_args.push(quote!(#name));
-				let param_name_str = name.to_string();
+				with_pipeline(quote!(QueryParam), None);
 				if let Some(inner) = inner_ty {
 					quote! {
-						let #name: Option<#inner> = ctx.query_param_as::<#inner>(#param_name_str);
+						let #name: Option<#inner> = match #pipeline.query_param_opt(&ctx).await {
+							Ok(value) => value,
+							Err(res) => return res,
+						};
 					}
 				} else {
 					quote! {
-						let #name: #ty = match ctx.query_param_as::<#ty>(#param_name_str) {
-							Some(val) => val,
-							None => return MurResponder::error(&format!(
-								"Missing query parameter: {}", #param_name_str
-							)),
+						let #name: #ty = match #pipeline.query_param(&ctx).await {
+							Ok(value) => value,
+							Err(res) => return res,
 						};
 					}
 				}
@@ -214,11 +249,16 @@ pub fn generate_handler_code(method_name: &Ident, params: &[ParamInfo]) -> Token
 		extractions.push(extraction);
 	}
 
+	let pipeline_names: Vec<&Ident> = pipelines.iter().map(|(name, _)| name).collect();
+	let pipeline_setups = pipelines.iter().map(|(_, setup)| setup);
+
 	quote! {
 		{
 			let controller_clone = controller.clone();
+			#(#pipeline_setups)*
 			Arc::new(move |ctx: MurRequestContext| -> MurFuture {
 				let controller = controller_clone.clone();
+				#(let #pipeline_names = #pipeline_names.clone();)*
 				Box::pin(async move {
 					#(#extractions)*
 					controller.#method_name(#(#call_args),*).await
@@ -227,3 +267,65 @@ pub fn generate_handler_code(method_name: &Ident, params: &[ParamInfo]) -> Token
 		}
 	}
 }
+
+/// Builds the `MurArgumentPipeline` for one parameter. `validated` is the
+/// decoded type for body, query and path arguments, which `ValidationPipe`
+/// can check when it implements `MurValidate`.
+fn pipeline_setup(
+	param: &ParamInfo,
+	kind: TokenStream,
+	validated: Option<&TokenStream>,
+	scoped_pipes: &[Expr],
+) -> TokenStream {
+	let pipeline = format_ident!("__mur_pipeline_{}", param.name);
+	let name_str = param.name.to_string();
+	let ty = &param.ty;
+	let validator = match validated {
+		Some(value_ty) => quote! {
+			{
+				#[allow(unused_imports)]
+				use murgamu::server::pipe::{MurProbeFallback as _, MurProbeValidate as _};
+				(&&murgamu::server::pipe::MurValidatorProbe::<#value_ty>::new()).validator()
+			}
+		},
+		None => quote!(None),
+	};
+	let mut pipes: Vec<TokenStream> = scoped_pipes
+		.iter()
+		.chain(&param.pipes)
+		.map(pipe_instance)
+		.collect();
+	if param.validate {
+		pipes.push(quote! {
+			Arc::new(murgamu::ValidationPipe::new()) as Arc<dyn murgamu::MurPipeDyn>
+		});
+	}
+
+	quote! {
+		let #pipeline = murgamu::MurArgumentPipeline::new(
+			murgamu::MurArgument::new(
+				murgamu::MurArgumentKind::#kind,
+				#name_str,
+				stringify!(#ty),
+			)
+			.validator(#validator),
+			vec![#(#pipes),*],
+		);
+	}
+}
+
+/// A pipe type is built through its `MurPipeFactory` with the module's
+/// container; any other expression is used as the pipe value itself.
+fn pipe_instance(pipe: &Expr) -> TokenStream {
+	match pipe {
+		Expr::Path(path) => quote! {
+			Arc::new(<#path as murgamu::MurPipeFactory>::__create_factory(
+				&murgamu::MurInjects::new(),
+				container,
+			)) as Arc<dyn murgamu::MurPipeDyn>
+		},
+		other => quote! {
+			Arc::new(#other) as Arc<dyn murgamu::MurPipeDyn>
+		},
+	}
+}
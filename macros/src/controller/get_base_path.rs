@@ -1,19 +1,59 @@
+use super::route_args::parse_versions;
 use crate::core::normalize_path;
-use syn::Lit;
-
-pub fn get_base_path(args: proc_macro::TokenStream) -> String {
-	if !args.is_empty() {
-		let lit: Lit = syn::parse(args).unwrap_or(Lit::Str(syn::LitStr::new(
-			"/",
-			proc_macro2::Span::call_site(),
-		)));
-
-		if let Lit::Str(lit_str) = lit {
-			normalize_path(&lit_str.value())
-		} else {
-			String::from("/")
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitStr, Token};
+
+/// The arguments of
+/// `#[controller("/path", host = ":tenant.example.com", version = "2")]`.
+pub struct ControllerArgs {
+	pub base_path: String,
+	pub host: Option<String>,
+	pub versions: Vec<String>,
+}
+
+impl Parse for ControllerArgs {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let mut base_path = String::from("/");
+		let mut host = None;
+		let mut versions = Vec::new();
+
+		if input.peek(LitStr) {
+			let lit: LitStr = input.parse()?;
+			base_path = normalize_path(&lit.value());
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
 		}
-	} else {
-		String::from("/")
+
+		while !input.is_empty() {
+			let key: Ident = input.parse()?;
+			input.parse::<Token![=]>()?;
+
+			match key.to_string().as_str() {
+				"path" => base_path = normalize_path(&input.parse::<LitStr>()?.value()),
+				"host" => host = Some(input.parse::<LitStr>()?.value()),
+				"version" => versions = parse_versions(input)?,
+				_ => {
+					return Err(syn::Error::new_spanned(
+						key,
+						"unknown controller argument. Allowed keys: path, host, version",
+					));
+				}
+			}
+
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		Ok(Self {
+			base_path,
+			host,
+			versions,
+		})
 	}
 }
+
+pub fn parse_controller_args(args: proc_macro::TokenStream) -> syn::Result<ControllerArgs> {
+	syn::parse(args)
+}
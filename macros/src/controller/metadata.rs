@@ -0,0 +1,64 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Lit, Token};
+
+/// Reads `#[set_metadata(..)]`, `#[decorate(..)]` and `#[skip_csrf]` from
+/// `attrs` and returns the `MurRouteMetadata` builder calls they stand for, in
+/// declaration order.
+///
+/// `key = value` entries become string values; any other expression is
+/// stored as a typed value.
+pub fn parse_metadata(attrs: &[Attribute]) -> syn::Result<Vec<TokenStream>> {
+	let mut calls = Vec::new();
+
+	for attr in attrs {
+		if attr.path().is_ident("skip_csrf") {
+			calls.push(quote! { .typed(murgamu::MurCsrfExempt) });
+			continue;
+		}
+
+		let is_decorate = attr.path().is_ident("decorate");
+		if !is_decorate && !attr.path().is_ident("set_metadata") {
+			continue;
+		}
+
+		let entries = attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+		for entry in entries {
+			if is_decorate {
+				calls.push(quote! { .decorate(#entry) });
+				continue;
+			}
+
+			match entry {
+				Expr::Assign(assign) => {
+					let key = metadata_key(&assign.left)?;
+					let value = &assign.right;
+					calls.push(quote! { .set(#key, #value) });
+				}
+				typed => calls.push(quote! { .typed(#typed) }),
+			}
+		}
+	}
+
+	Ok(calls)
+}
+
+fn metadata_key(expr: &Expr) -> syn::Result<String> {
+	match expr {
+		Expr::Path(path) if path.path.get_ident().is_some() => {
+			Ok(path.path.get_ident().unwrap().to_string())
+		}
+		Expr::Lit(lit) => match &lit.lit {
+			Lit::Str(key) => Ok(key.value()),
+			other => Err(syn::Error::new_spanned(
+				other,
+				"metadata key must be an identifier or a string",
+			)),
+		},
+		other => Err(syn::Error::new_spanned(
+			other,
+			"metadata key must be an identifier or a string",
+		)),
+	}
+}
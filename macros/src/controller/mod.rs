@@ -1,7 +1,14 @@
 mod controller_impl;
+mod duration;
 mod generate_handler;
 mod get_base_path;
+mod metadata;
 mod methods;
+mod pipes;
+mod providers;
+mod route_args;
+mod throttle;
+mod timeout;
 
 pub use controller_impl::controller_impl;
 pub use generate_handler::generate_handler_code;
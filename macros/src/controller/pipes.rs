@@ -0,0 +1,19 @@
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Token};
+
+/// Collects the pipes listed in every `#[use_pipes(..)]` on `attrs`, in
+/// declaration order.
+pub fn parse_use_pipes(attrs: &[Attribute]) -> syn::Result<Vec<Expr>> {
+	let mut pipes = Vec::new();
+
+	for attr in attrs {
+		if !attr.path().is_ident("use_pipes") {
+			continue;
+		}
+
+		let parsed = attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+		pipes.extend(parsed);
+	}
+
+	Ok(pipes)
+}
@@ -0,0 +1,37 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Path, Token};
+
+/// Collects the guards listed in every `#[use_guards(..)]` on `attrs` as
+/// `MurGuardProvider` expressions, in declaration order.
+pub fn parse_use_guards(attrs: &[Attribute]) -> syn::Result<Vec<TokenStream>> {
+	Ok(parse_types(attrs, "use_guards")?
+		.into_iter()
+		.map(|ty| quote! { murgamu::mur_guard_provider::<#ty>() })
+		.collect())
+}
+
+/// Collects the interceptors listed in every `#[use_interceptors(..)]` on
+/// `attrs` as `MurInterceptorProvider` expressions, in declaration order.
+pub fn parse_use_interceptors(attrs: &[Attribute]) -> syn::Result<Vec<TokenStream>> {
+	Ok(parse_types(attrs, "use_interceptors")?
+		.into_iter()
+		.map(|ty| quote! { murgamu::mur_interceptor_provider::<#ty>() })
+		.collect())
+}
+
+fn parse_types(attrs: &[Attribute], name: &str) -> syn::Result<Vec<Path>> {
+	let mut types = Vec::new();
+
+	for attr in attrs {
+		if !attr.path().is_ident(name) {
+			continue;
+		}
+
+		let parsed = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+		types.extend(parsed);
+	}
+
+	Ok(types)
+}
@@ -0,0 +1,56 @@
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Ident, LitStr, Token};
+
+/// The arguments of a route attribute such as
+/// `#[get("/users", version = ["1", "2"])]`.
+pub struct RouteArgs {
+	pub path: String,
+	pub versions: Option<Vec<String>>,
+}
+
+impl Parse for RouteArgs {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let mut path = String::new();
+		let mut versions = None;
+
+		if input.peek(LitStr) {
+			path = input.parse::<LitStr>()?.value();
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		while !input.is_empty() {
+			let key: Ident = input.parse()?;
+			input.parse::<Token![=]>()?;
+			if key != "version" {
+				return Err(syn::Error::new_spanned(
+					key,
+					"unknown route argument. Allowed keys: version",
+				));
+			}
+			versions = Some(parse_versions(input)?);
+
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		Ok(Self { path, versions })
+	}
+}
+
+/// Parses `"2"` or `["1", "2"]`. An empty list makes the route
+/// version-neutral.
+pub fn parse_versions(input: ParseStream) -> syn::Result<Vec<String>> {
+	if input.peek(LitStr) {
+		return Ok(vec![input.parse::<LitStr>()?.value()]);
+	}
+
+	let content;
+	syn::bracketed!(content in input);
+	let versions: Punctuated<LitStr, Token![,]> =
+		content.parse_terminated(|input| input.parse::<LitStr>(), Token![,])?;
+	Ok(versions.iter().map(LitStr::value).collect())
+}
@@ -0,0 +1,107 @@
+use super::duration::parse_duration;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Lit, LitStr, Meta, Token};
+
+/// Reads `#[throttle(..)]` / `#[skip_throttle]` from `attrs` and returns the
+/// tokens of an `Option<MurRouteThrottle>` expression, or `None` when neither
+/// attribute is present.
+pub fn parse_throttle(attrs: &[Attribute]) -> syn::Result<Option<TokenStream>> {
+	let mut result = None;
+
+	for attr in attrs {
+		if attr.path().is_ident("skip_throttle") {
+			result = Some(quote! {
+				Some(murgamu::MurRouteThrottle::Skip)
+			});
+		} else if attr.path().is_ident("throttle") {
+			result = Some(parse_throttle_args(attr)?);
+		}
+	}
+
+	Ok(result)
+}
+
+fn parse_throttle_args(attr: &Attribute) -> syn::Result<TokenStream> {
+	if let Ok(name) = attr.parse_args::<LitStr>() {
+		return Ok(named(&name));
+	}
+
+	let args = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+	let mut limit: Option<u64> = None;
+	let mut window = quote! { std::time::Duration::from_secs(60) };
+	let mut key = quote! { murgamu::MurThrottlerKey::Ip };
+
+	for arg in args {
+		let Meta::NameValue(nv) = &arg else {
+			return Err(syn::Error::new_spanned(
+				arg,
+				"expected `limit = ..`, `per = \"..\"`, `key = \"..\"` or `policy = \"..\"`",
+			));
+		};
+		let Expr::Lit(expr) = &nv.value else {
+			return Err(syn::Error::new_spanned(&nv.value, "expected a literal"));
+		};
+		let name = nv
+			.path
+			.get_ident()
+			.map(|i| i.to_string())
+			.unwrap_or_default();
+
+		match (name.as_str(), &expr.lit) {
+			("policy", Lit::Str(s)) => return Ok(named(s)),
+			("limit", Lit::Int(i)) => limit = Some(i.base10_parse()?),
+			("per", Lit::Str(s)) => {
+				window = parse_duration(s, "invalid window, expected e.g. \"30s\", \"1m\", \"1h\"");
+			}
+			("key", Lit::Str(s)) => key = parse_key(s)?,
+			_ => {
+				return Err(syn::Error::new_spanned(
+					&nv.path,
+					"unknown or mistyped `throttle` argument",
+				));
+			}
+		}
+	}
+
+	let limit = limit.ok_or_else(|| {
+		syn::Error::new_spanned(attr, "`throttle` requires `limit = ..` or a policy name")
+	})?;
+
+	Ok(quote! {
+		Some(murgamu::MurRouteThrottle::Policy(
+			murgamu::MurThrottlePolicy::new(#limit, #window)
+			.key(#key),
+		))
+	})
+}
+
+fn named(name: &LitStr) -> TokenStream {
+	quote! {
+		Some(murgamu::MurRouteThrottle::Named(#name.to_string()))
+	}
+}
+
+fn parse_key(key: &LitStr) -> syn::Result<TokenStream> {
+	let value = key.value();
+	let tokens = match value.as_str() {
+		"ip" => quote! { murgamu::MurThrottlerKey::Ip },
+		"global" => quote! { murgamu::MurThrottlerKey::Global },
+		"bearer" | "token" => quote! { murgamu::MurThrottlerKey::BearerToken },
+		"api_key" | "apikey" => quote! { murgamu::MurThrottlerKey::ApiKey },
+		other => {
+			if let Some(name) = other.strip_prefix("ip+header:").filter(|n| !n.is_empty()) {
+				quote! { murgamu::MurThrottlerKey::IpAndHeader(#name.to_string()) }
+			} else if let Some(name) = other.strip_prefix("header:").filter(|n| !n.is_empty()) {
+				quote! { murgamu::MurThrottlerKey::Header(#name.to_string()) }
+			} else {
+				return Err(syn::Error::new_spanned(
+					key,
+					"invalid key, expected \"ip\", \"global\", \"bearer\", \"api_key\", \"header:<name>\" or \"ip+header:<name>\"",
+				));
+			}
+		}
+	};
+	Ok(tokens)
+}
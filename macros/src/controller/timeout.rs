@@ -0,0 +1,28 @@
+use super::duration::parse_duration;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, LitStr};
+
+/// Reads `#[timeout("..")]` from `attrs` and returns the tokens of an
+/// `Option<Duration>` expression, or `None` when the attribute is absent.
+pub fn parse_timeout(attrs: &[Attribute]) -> syn::Result<Option<TokenStream>> {
+	let mut result = None;
+
+	for attr in attrs {
+		if !attr.path().is_ident("timeout") {
+			continue;
+		}
+
+		let value = attr.parse_args::<LitStr>()?;
+		let timeout = parse_duration(
+			&value,
+			"invalid timeout, expected e.g. \"500ms\", \"30s\", \"2m\"",
+		);
+
+		result = Some(quote! {
+			Some(#timeout)
+		});
+	}
+
+	Ok(result)
+}
@@ -15,32 +15,30 @@ pub fn analyze_parameter(pat_type: &PatType) -> ParamInfo {
 	let ty_tokens = quote!(#ty);
 	let is_optional = ty_str.starts_with("Option<");
 
+	let mut pipes: Vec<syn::Expr> = Vec::new();
+	let mut validate = false;
+	let mut source: Option<ParamKind> = None;
+
 	for attr in &pat_type.attrs {
 		if attr.path().is_ident("use_pipe")
-			&& let Ok(pipe_type) = attr.parse_args::<syn::Path>()
+			&& let Ok(pipe) = attr.parse_args::<syn::Expr>()
 		{
-			return ParamInfo {
-				name,
-				kind: ParamKind::Pipe(pipe_type, *ty.clone()),
-				ty: ty_tokens,
-				is_optional,
-			};
+			pipes.push(pipe);
+		}
+		if attr.path().is_ident("validate") {
+			validate = true;
+		}
+		if source.is_some() {
+			continue;
 		}
 		if attr.path().is_ident("body") {
-			return ParamInfo {
-				name,
-				kind: ParamKind::CustomJson(ty_tokens.clone()),
-				ty: ty_tokens,
-				is_optional,
-			};
+			source = Some(ParamKind::CustomJson(ty_tokens.clone()));
 		}
 		if attr.path().is_ident("query") {
-			return ParamInfo {
-				name,
-				kind: ParamKind::CustomQuery(ty_tokens.clone()),
-				ty: ty_tokens,
-				is_optional,
-			};
+			source = Some(ParamKind::CustomQuery(ty_tokens.clone()));
+		}
+		if attr.path().is_ident("session") {
+			source = Some(ParamKind::Session);
 		}
 		if attr.path().is_ident("param") {
 			let inner_ty = if is_optional {
@@ -48,12 +46,7 @@ pub fn analyze_parameter(pat_type: &PatType) -> ParamInfo {
 			} else {
 				None
 			};
-			return ParamInfo {
-				name,
-				kind: ParamKind::RawParam(ty_tokens.clone(), inner_ty),
-				ty: ty_tokens,
-				is_optional,
-			};
+			source = Some(ParamKind::RawParam(ty_tokens.clone(), inner_ty));
 		}
 		if attr.path().is_ident("queryparam") {
 			let inner_ty = if is_optional {
@@ -61,20 +54,51 @@ pub fn analyze_parameter(pat_type: &PatType) -> ParamInfo {
 			} else {
 				None
 			};
-			return ParamInfo {
-				name,
-				kind: ParamKind::RawQueryParam(ty_tokens.clone(), inner_ty),
-				ty: ty_tokens,
-				is_optional,
-			};
+			source = Some(ParamKind::RawQueryParam(ty_tokens.clone(), inner_ty));
 		}
 	}
 
+	// `#[use_pipe(P)]` without a source attribute keeps its original meaning:
+	// `P` builds the whole argument from the request context.
+	if source.is_none()
+		&& !validate
+		&& let [syn::Expr::Path(pipe)] = pipes.as_slice()
+	{
+		return ParamInfo {
+			name,
+			kind: ParamKind::Pipe(pipe.path.clone(), ty.clone()),
+			ty: ty_tokens,
+			is_optional,
+			pipes: Vec::new(),
+			validate,
+		};
+	}
+
+	if let Some(kind) = source {
+		return ParamInfo {
+			name,
+			kind,
+			ty: ty_tokens,
+			is_optional,
+			pipes,
+			validate,
+		};
+	}
+
 	let kind = if ty_str.contains("MurRequestContext") || ty_str.contains("MurReq") {
 		ParamKind::Context
 	} else if ty_str.starts_with("MurJson<") || ty_str.starts_with("murgamu::MurJson<") {
 		let inner = extract_generic_type(&ty_str, "MurJson");
 		ParamKind::Json(inner.parse().unwrap_or(quote!(serde_json::Value)))
+	} else if ty_str.starts_with("MurPayload<") || ty_str.starts_with("murgamu::MurPayload<") {
+		let inner = extract_generic_type(&ty_str, "MurPayload");
+		ParamKind::Payload(inner.parse().unwrap_or(quote!(serde_json::Value)))
+	} else if ty_str == "MurSession" || ty_str == "murgamu::MurSession" {
+		ParamKind::Session
+	} else if ty_str == "MurCsrfToken" || ty_str == "murgamu::MurCsrfToken" {
+		ParamKind::CsrfToken
+	} else if ty_str == "MurApiKeyIdentity" || ty_str == "murgamu::MurApiKeyIdentity" {
+		ParamKind::ApiKey
 	} else if ty_str.starts_with("MurQuery<") || ty_str.starts_with("murgamu::MurQuery<") {
 		let inner = extract_generic_type(&ty_str, "MurQuery");
 		ParamKind::Query(
@@ -112,5 +136,7 @@ pub fn analyze_parameter(pat_type: &PatType) -> ParamInfo {
 		kind,
 		ty: ty_tokens,
 		is_optional,
+		pipes,
+		validate,
 	}
 }
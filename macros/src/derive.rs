@@ -16,11 +16,7 @@ fn generate_dto_impl(input: &DeriveInput) -> TokenStream2 {
 	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
 	quote! {
-		impl #impl_generics #name #ty_generics #where_clause {
-			pub fn validate(&self) -> Result<(), String> {
-				Ok(())
-			}
-		}
+		impl #impl_generics murgamu::MurValidate for #name #ty_generics #where_clause {}
 	}
 }
 
@@ -33,7 +33,12 @@ use syn::{ItemImpl, parse_macro_input};
 /// Marks an `impl` block as a Murgamu controller and registers its route handlers.
 ///
 /// The optional argument sets the base path prefix for all routes in the block.
-/// If omitted, the base path defaults to `"/"`.
+/// If omitted, the base path defaults to `"/"`. A `host = "…"` argument binds
+/// the routes to matching `Host` headers, capturing `:name` labels:
+/// `#[controller("/billing", host = ":tenant.example.com")]` exposes the
+/// tenant through `ctx.host_param("tenant")`.
+/// A `version = "2"` or `version = ["1", "2"]` argument sets the API versions
+/// its routes serve under `MurServer::versioning`.
 ///
 /// # Example
 ///
@@ -66,7 +71,9 @@ pub fn controller(args: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// The optional argument is the route path relative to the controller's base
 /// path. Omitting it (or using `""` / `"/"`) makes the handler respond to
-/// the base path itself.
+/// the base path itself. A `version = "1"` or `version = ["1", "2"]`
+/// argument sets the API versions served, overriding the controller's;
+/// `version = []` makes the handler version-neutral.
 ///
 /// # Examples
 ///
@@ -230,6 +237,7 @@ pub fn injectable(args: TokenStream, input: TokenStream) -> TokenStream {
 /// - `providers: [T, …]` — services available within this module.
 /// - `imports: [M::new(), …]` — other modules whose exports are visible here.
 /// - `exports: [T, …]` — services made available to importing modules.
+/// - `prefix: "/path"` — path prefix applied to every controller in this module.
 ///
 /// # Example
 ///
@@ -278,7 +286,13 @@ pub fn guard(args: TokenStream, input: TokenStream) -> TokenStream {
 /// Marks a struct as a Murgamu transformation pipe.
 ///
 /// Pipes transform handler parameters before they reach the handler body.
-/// Generates the `MurPipe` and `MurPipeFactory` trait implementations.
+/// Generates the `MurPipe`, `MurPipeDyn` and `MurPipeFactory` trait
+/// implementations.
+///
+/// When the input type is not `MurRequestContext`, the pipe can also be used
+/// with `#[use_pipe]`, `#[use_pipes]` and `MurServer::pipe`: the extracted
+/// argument is deserialized into the input type, transformed, and serialized
+/// back, so both types must implement serde's `Deserialize` and `Serialize`.
 ///
 /// # Example
 ///
@@ -301,9 +315,10 @@ pub fn pipe(args: TokenStream, input: TokenStream) -> TokenStream {
 
 /// Marks a struct as a Murgamu interceptor.
 ///
-/// Interceptors wrap individual route handler invocations, running logic
-/// before and/or after the handler. Generates the `MurInterceptor` and
-/// `MurInterceptorFactory` trait implementations.
+/// Interceptors wrap individual route handler invocations, either around the
+/// rest of the chain with `intercept` or with `before`/`after` hooks.
+/// Generates the `MurInterceptorFactory` implementation; `MurInterceptor` is
+/// implemented by hand.
 ///
 /// # Example
 ///
@@ -312,8 +327,12 @@ pub fn pipe(args: TokenStream, input: TokenStream) -> TokenStream {
 /// struct TimingInterceptor;
 ///
 /// impl MurInterceptor for TimingInterceptor {
-///     fn before(&self, _ctx: &MurRequestContext) -> MurInterceptorFuture {
-///         Box::pin(async { Ok(()) })
+///     fn intercept<'a>(&'a self, ctx: MurRequestContext, next: MurCallHandler) -> MurInterceptFuture<'a> {
+///         Box::pin(async move {
+///             let started = std::time::Instant::now();
+///             let response = next.run(ctx).await;
+///             response.with_header("X-Elapsed-Ms", started.elapsed().as_millis().to_string())
+///         })
 ///     }
 /// }
 /// ```
@@ -436,7 +455,8 @@ pub fn header(args: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// The body bytes are deserialized directly into the annotated type using
 /// `serde_json` (no `MurJson<T>` wrapper). The type must implement
-/// `serde::Deserialize`.
+/// `serde::Deserialize`. Use `MurPayload<T>` instead to read bodies in the
+/// format named by their `Content-Type`.
 ///
 /// # Example
 ///
@@ -449,23 +469,47 @@ pub fn body(args: TokenStream, input: TokenStream) -> TokenStream {
 	main_entry::body_impl(args, input)
 }
 
+/// Marks a handler parameter as the request's server-side session.
+///
+/// The parameter must be a `MurSession`, and `MurSessionModule` must be
+/// registered; otherwise the request fails with `500 Internal Server Error`.
+/// Changes are saved after the handler returns.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/cart")]
+/// async fn add(&self, #[session] session: MurSession, #[body] item: Item) -> MurRes { /* … */ }
+/// ```
+#[proc_macro_attribute]
+pub fn session(args: TokenStream, input: TokenStream) -> TokenStream {
+	main_entry::session_impl(args, input)
+}
+
 /// Triggers automatic validation of a handler parameter before execution.
 ///
-/// The annotated type must implement a `validate(&self) -> Result<(), String>`
-/// method (generated automatically by `#[derive(MurDto)]`). If validation
-/// fails, a `400 Bad Request` response is returned before the handler runs.
+/// Runs `ValidationPipe` on the parameter after its other pipes. The
+/// annotated type should implement `MurValidate` (with no rules when derived
+/// by `#[derive(MurDto)]`). If validation fails, a `400 Bad Request` is
+/// returned through the exception filters before the handler runs.
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// #[derive(MurDto, Deserialize)]
+/// #[derive(Deserialize)]
 /// struct CreateUserDto {
 ///     pub name: String,
 ///     pub email: String,
 /// }
 ///
+/// impl MurValidate for CreateUserDto {
+///     fn validate(&self) -> Result<(), String> {
+///         if self.email.contains('@') { Ok(()) } else { Err("invalid email".into()) }
+///     }
+/// }
+///
 /// #[post("/users")]
-/// async fn create(&self, #[validate] dto: CreateUserDto) -> MurRes { /* … */ }
+/// async fn create(&self, #[body] #[validate] dto: CreateUserDto) -> MurRes { /* … */ }
 /// ```
 #[proc_macro_attribute]
 pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -558,14 +602,14 @@ pub fn no_content_response(input: TokenStream) -> TokenStream {
 	response::no_content_impl(input)
 }
 
-/// Derives a default `validate` method on a DTO struct.
+/// Derives `MurValidate` on a DTO struct with no rules.
 ///
-/// The generated implementation returns `Ok(())` unconditionally. Override it
-/// manually or use custom validation logic when field-level constraints are
-/// needed.
+/// The generated `validate` returns `Ok(())` unconditionally. Implement
+/// `MurValidate` by hand instead of deriving when field-level constraints
+/// are needed.
 ///
 /// The derive also enables the struct to be used with the `#[validate]`
-/// parameter attribute on route handlers.
+/// parameter attribute and `ValidationPipe`.
 ///
 /// # Example
 ///
@@ -639,15 +683,142 @@ pub fn role(_args: TokenStream, input: TokenStream) -> TokenStream {
 	input
 }
 
+/// Rate limits a route handler, or every handler of a controller when placed
+/// below `#[controller]` on the `impl` block. Handler attributes take
+/// precedence over the controller's.
+///
+/// Accepts either the name of a policy registered with
+/// `MurServer::throttle_policy`, or an inline policy. `per` defaults to
+/// `"1m"` and `key` to `"ip"`; keys may also be `"global"`, `"bearer"`,
+/// `"header:<name>"` or `"ip+header:<name>"`.
+///
+/// A route with its own policy is not counted by the global `MurThrottler`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/login")]
+/// #[throttle(limit = 5, per = "1m", key = "ip")]
+/// async fn login(&self, #[body] dto: LoginDto) -> MurRes { /* … */ }
+///
+/// #[get("/search")]
+/// #[throttle("search")]
+/// async fn search(&self) -> MurRes { /* … */ }
+/// ```
+#[proc_macro_attribute]
+pub fn throttle(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
+/// Exempts a route handler (or a whole controller) from rate limiting, both
+/// from `#[throttle]` policies and from the global `MurThrottler`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/health")]
+/// #[skip_throttle]
+/// async fn health(&self) -> MurRes { /* … */ }
+/// ```
+#[proc_macro_attribute]
+pub fn skip_throttle(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
+/// Exempts a route handler (or a whole controller) from `MurCsrf`
+/// verification, for endpoints authenticated by other means such as
+/// webhooks with signed payloads.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/webhooks/billing")]
+/// #[skip_csrf]
+/// async fn billing(&self, ctx: MurRequestContext) -> MurRes { /* … */ }
+/// ```
+#[proc_macro_attribute]
+pub fn skip_csrf(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
+/// Sets the time a route handler (or every handler of a controller) may run
+/// before the request is cancelled. A method-level attribute takes precedence
+/// over the controller's.
+///
+/// With `MurTimeout` installed the value replaces its configured timeout for
+/// the route; otherwise the router enforces it on its own and answers
+/// `408 Request Timeout`. Handlers can read the deadline with
+/// `ctx.deadline()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/report")]
+/// #[timeout("2m")]
+/// async fn report(&self) -> MurRes { /* … */ }
+/// ```
+#[proc_macro_attribute]
+pub fn timeout(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
+/// Attaches metadata to a route handler, or to every handler when placed on
+/// a controller (below `#[controller]`). Method entries replace controller
+/// entries with the same key or type.
+///
+/// `key = value` entries are stored as strings; any other expression is
+/// stored as a typed value. Guards, interceptors and handlers read them with
+/// `ctx.route_metadata_value("key")` and `ctx.route_metadata::<T>()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// pub struct RequiredScope(pub &'static str);
+///
+/// #[get("/reports")]
+/// #[set_metadata(feature = "reports-v2", RequiredScope("reports:read"))]
+/// async fn reports(&self) -> MurRes { /* … */ }
+/// ```
+#[proc_macro_attribute]
+pub fn set_metadata(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
+/// Applies custom decorators (types implementing `MurDecorator`) to a route
+/// handler or controller. The decorator's `metadata()` entries are merged
+/// into the route metadata and the decorator is stored as a typed value.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/beta")]
+/// #[decorate(FeatureFlag::new("beta"))]
+/// async fn beta(&self) -> MurRes { /* … */ }
+/// ```
+#[proc_macro_attribute]
+pub fn decorate(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
 /// Applies a transformation pipe to a handler parameter.
 ///
-/// The argument must be the concrete pipe type. The pipe's `apply_transform`
-/// is called with the [`MurRequestContext`](murgamu::MurRequestContext) before
-/// the handler runs, and the transformed value is injected as the parameter.
+/// Alongside `#[body]`, `#[query]`, `#[param]` or `#[queryparam]`, the pipe
+/// transforms the extracted value after the global, controller and method
+/// pipes. The argument is either a pipe type, built through its factory, or
+/// an expression such as `DefaultValuePipe::new(10)`. The attribute may be
+/// repeated.
+///
+/// Without a source attribute, the argument must be the concrete pipe type.
+/// Its `apply_transform` is called with the
+/// [`MurRequestContext`](murgamu::MurRequestContext) before the handler runs,
+/// and the transformed value is injected as the parameter.
 ///
 /// # Example
 ///
 /// ```rust,ignore
+/// #[get("/users/:id")]
+/// async fn find(&self, #[param] #[use_pipe(ParseIntPipe)] id: i64) -> MurRes { /* … */ }
+///
 /// #[post("/upload")]
 /// async fn upload(&self, #[use_pipe(FileSizePipe)] file: FileData) -> MurRes { /* … */ }
 /// ```
@@ -655,3 +826,73 @@ pub fn role(_args: TokenStream, input: TokenStream) -> TokenStream {
 pub fn use_pipe(args: TokenStream, input: TokenStream) -> TokenStream {
 	use_pipe::use_pipe_impl(args, input)
 }
+
+/// Applies pipes to every extracted parameter of a route handler, or of
+/// every handler when placed on a controller (below `#[controller]`).
+/// Controller pipes run before method pipes, and both run after global
+/// pipes and before parameter pipes.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[controller("/users")]
+/// #[use_pipes(TrimPipe)]
+/// impl UserController {
+///     #[post("/")]
+///     #[use_pipes(ValidationPipe)]
+///     async fn create(&self, #[body] dto: CreateUserDto) -> MurRes { /* … */ }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn use_pipes(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
+/// Applies guards to a route handler, or to every handler when placed on a
+/// controller (below `#[controller]`). Each guard must be a `#[guard]` type;
+/// it is built through `MurGuardFactory` from the DI scope of the module
+/// that declares the controller.
+///
+/// Route guards run after the global guards, controller guards before
+/// method guards, each in declaration order. A route with its own guards is
+/// left to them when no global guard is registered.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[controller("/admin")]
+/// #[use_guards(AuthGuard)]
+/// impl AdminController {
+///     #[delete("/tenants/:id")]
+///     #[use_guards(AdminGuard, TenantGuard)]
+///     async fn remove(&self, #[param] id: String) -> MurRes { /* … */ }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn use_guards(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
+/// Applies interceptors to a route handler, or to every handler when placed
+/// on a controller (below `#[controller]`). Each interceptor must be an
+/// `#[interceptor]` type; it is built through `MurInterceptorFactory` from
+/// the DI scope of the module that declares the controller.
+///
+/// Global interceptors wrap controller interceptors, which wrap method
+/// interceptors.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[controller("/orders")]
+/// #[use_interceptors(AuditInterceptor)]
+/// impl OrderController {
+///     #[get("/")]
+///     #[use_interceptors(TimingInterceptor)]
+///     async fn list(&self) -> MurRes { /* … */ }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn use_interceptors(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
@@ -179,21 +179,37 @@ pub fn body_impl(_args: TokenStream, input: TokenStream) -> TokenStream {
 	input
 }
 
+/// Macro to mark a parameter as the request's session.
+///
+/// The parameter must be a `MurSession`, and `MurSessionModule` must be
+/// registered.
+///
+/// # Example
+/// ```ignore
+/// #[get("/cart")]
+/// async fn cart(&self, #[session] session: MurSession) -> MurRes {
+///     let items: Vec<String> = session.get("cart").unwrap_or_default();
+/// }
+/// ```
+pub fn session_impl(_args: TokenStream, input: TokenStream) -> TokenStream {
+	input
+}
+
 /// Macro to mark a parameter for automatic validation.
 ///
 /// The `#[validate]` macro enables automatic validation of request data.
-/// The type must implement a `validate()` method.
+/// The type must implement `MurValidate`.
 ///
 /// # Example
 /// ```ignore
-/// #[derive(MurDto, Deserialize)]
+/// #[derive(Deserialize)]
 /// pub struct CreateUserDto {
 ///     pub name: String,
 ///     pub email: String,
 /// }
 ///
-/// impl CreateUserDto {
-///     pub fn validate(&self) -> Result<(), String> {
+/// impl MurValidate for CreateUserDto {
+///     fn validate(&self) -> Result<(), String> {
 ///         if self.name.is_empty() {
 ///             return Err("Name cannot be empty".to_string());
 ///         }
@@ -2,7 +2,7 @@ use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::{
-	Ident, ItemStruct, Token,
+	Ident, ItemStruct, LitStr, Token,
 	parse::{Parse, ParseStream},
 	parse_macro_input,
 	punctuated::Punctuated,
@@ -14,6 +14,7 @@ struct ModuleArgs {
 	controllers: Vec<Ident>,
 	services: Vec<Ident>,
 	exports: Vec<Ident>,
+	prefix: Option<String>,
 }
 
 impl Parse for ModuleArgs {
@@ -22,11 +23,20 @@ impl Parse for ModuleArgs {
 		let mut controllers = Vec::new();
 		let mut services = Vec::new();
 		let mut exports = Vec::new();
+		let mut prefix = None;
 
 		while !input.is_empty() {
 			let key: Ident = input.parse()?;
 			input.parse::<Token![:]>()?;
 
+			if key == "prefix" {
+				prefix = Some(input.parse::<LitStr>()?.value());
+				if input.peek(Token![,]) {
+					input.parse::<Token![,]>()?;
+				}
+				continue;
+			}
+
 			let content;
 			syn::bracketed!(content in input);
 			let items: Punctuated<Ident, Token![,]> =
@@ -40,7 +50,7 @@ impl Parse for ModuleArgs {
 				_ => {
 					return Err(syn::Error::new_spanned(
 						key,
-						"unknown module attribute key. Allowed keys: imports, controllers, providers, exports",
+						"unknown module attribute key. Allowed keys: imports, controllers, providers, exports, prefix",
 					));
 				}
 			}
@@ -55,6 +65,7 @@ impl Parse for ModuleArgs {
 			controllers,
 			services,
 			exports,
+			prefix,
 		})
 	}
 }
@@ -138,6 +149,12 @@ fn generate_module_impl(input: &ItemStruct, args: &ModuleArgs) -> TokenStream2 {
 		})
 		.collect();
 
+	let prefix_fn = args.prefix.as_ref().map(|prefix| {
+		quote! {
+			fn prefix(&self) -> &str { #prefix }
+		}
+	});
+
 	let module_service_typeids: Vec<TokenStream2> = args
 		.services
 		.iter()
@@ -180,6 +197,8 @@ fn generate_module_impl(input: &ItemStruct, args: &ModuleArgs) -> TokenStream2 {
 		impl murgamu::MurModule for #module_name {
 			fn name(&self) -> &str { #module_name_str }
 
+			#prefix_fn
+
 			fn imports(&self) -> Vec<std::sync::Arc<dyn murgamu::MurModule>> {
 				vec![#(#import_insts),*]
 			}
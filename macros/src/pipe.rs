@@ -177,6 +177,26 @@ pub fn pipe_impl(args: TokenStream, input: TokenStream) -> TokenStream {
 		}
 	};
 
+	// Context pipes build the whole argument themselves; other pipes can also
+	// transform extracted arguments when their types are serde types.
+	let transform_argument = if is_context_type(&input_ty) {
+		quote! {}
+	} else {
+		quote! {
+			fn transform_argument<'a>(
+				&'a self,
+				ctx: &'a murgamu::MurRequestContext,
+				value: murgamu::prelude::serde_json::Value,
+				argument: &'a murgamu::MurArgument,
+			) -> murgamu::MurPipeFuture<'a> {
+				#[allow(unused_imports)]
+				use murgamu::server::pipe::{MurBridgeFallback as _, MurBridgeSerde as _};
+				(&&murgamu::server::pipe::MurPipeBridge::<Self, #input_ty>::new())
+					.transform(self, ctx, value, argument)
+			}
+		}
+	};
+
 	let pipe_dyn_impl = quote! {
 		impl #impl_generics murgamu::MurPipeDyn for #impl_type #where_clause {
 			fn name(&self) -> &str {
@@ -186,6 +206,8 @@ pub fn pipe_impl(args: TokenStream, input: TokenStream) -> TokenStream {
 			fn as_any(&self) -> &dyn std::any::Any {
 				<Self as murgamu::MurPipe<#input_ty>>::as_any(self)
 			}
+
+			#transform_argument
 		}
 	};
 
@@ -5,6 +5,10 @@ pub enum ParamKind {
 	SelfRef,
 	Context,
 	Json(TokenStream),
+	Payload(TokenStream),
+	Session,
+	CsrfToken,
+	ApiKey,
 	Query(TokenStream),
 	Path(TokenStream),
 	Param(TokenStream),
@@ -14,7 +18,7 @@ pub enum ParamKind {
 	Parts,
 	RawBody,
 	Container,
-	Pipe(syn::Path, syn::Type),
+	Pipe(syn::Path, Box<syn::Type>),
 	CustomJson(TokenStream),
 	CustomQuery(TokenStream),
 	RawParam(TokenStream, Option<TokenStream>),
@@ -29,4 +33,6 @@ pub struct ParamInfo {
 	pub kind: ParamKind,
 	pub ty: TokenStream,
 	pub is_optional: bool,
+	pub pipes: Vec<syn::Expr>,
+	pub validate: bool,
 }
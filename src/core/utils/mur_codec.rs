@@ -90,4 +90,20 @@ impl MurCodec {
 
 		Ok(result)
 	}
+
+	/// Unpadded URL-safe base64 (RFC 4648 §5), as used in cookies, tokens
+	/// and PKCE challenges.
+	pub fn base64_url_encode(data: &[u8]) -> String {
+		Self::base64_encode(data)
+			.trim_end_matches('=')
+			.replace('+', "-")
+			.replace('/', "_")
+	}
+
+	pub fn base64_url_decode(s: &str) -> Result<Vec<u8>, MurError> {
+		if s.contains(['+', '/']) {
+			return Err(MurError::BadRequest(String::from("Invalid base64")));
+		}
+		Self::base64_decode(&s.replace('-', "+").replace('_', "/"))
+	}
 }
@@ -1,3 +1,4 @@
+use super::MurCodec;
 use super::MurTime;
 
 pub struct MurRand;
@@ -9,6 +10,19 @@ impl MurRand {
 		format!("{:x}{:08x}", timestamp, random)
 	}
 
+	/// `len` bytes from the operating system's secure random generator.
+	pub fn secure_bytes(len: usize) -> Vec<u8> {
+		let mut bytes = vec![0; len];
+		aws_lc_rs::rand::fill(&mut bytes).expect("secure random generator unavailable");
+		bytes
+	}
+
+	/// `len` secure random bytes as unpadded URL-safe base64, for session
+	/// IDs, CSRF tokens and the like.
+	pub fn secure_token(len: usize) -> String {
+		MurCodec::base64_url_encode(&Self::secure_bytes(len))
+	}
+
 	fn rand_u32() -> u32 {
 		use std::collections::hash_map::DefaultHasher;
 		use std::hash::{Hash, Hasher};
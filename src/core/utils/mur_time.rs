@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 pub struct MurTime;
 
 impl MurTime {
@@ -22,4 +24,55 @@ impl MurTime {
 	pub fn timestamp_iso() -> String {
 		chrono::Utc::now().to_rfc3339()
 	}
+
+	/// Parses durations such as `"500ms"`, `"30s"`, `"1m"`, `"1h"` or `"1d"`.
+	/// A bare number is read as seconds; zero and overflowing values are rejected.
+	///
+	/// This is a `const fn` so `#[throttle(per = "..")]` and `#[timeout("..")]`
+	/// check their durations at compile time with the same grammar.
+	pub const fn parse_duration(value: &str) -> Option<Duration> {
+		let bytes = value.as_bytes();
+		let (mut start, mut end) = (0, bytes.len());
+		while start < end && bytes[start].is_ascii_whitespace() {
+			start += 1;
+		}
+		while end > start && bytes[end - 1].is_ascii_whitespace() {
+			end -= 1;
+		}
+
+		let mut amount: u64 = 0;
+		let mut i = start;
+		while i < end && bytes[i].is_ascii_digit() {
+			amount = match amount.checked_mul(10) {
+				Some(shifted) => match shifted.checked_add((bytes[i] - b'0') as u64) {
+					Some(amount) => amount,
+					None => return None,
+				},
+				None => return None,
+			};
+			i += 1;
+		}
+		if i == start {
+			return None;
+		}
+		while i < end && bytes[i].is_ascii_whitespace() {
+			i += 1;
+		}
+
+		let (_, unit) = bytes.split_at(i);
+		let (unit, _) = unit.split_at(end - i);
+		let unit_ms = match unit {
+			b"ms" => 1,
+			b"" | b"s" | b"sec" => 1_000,
+			b"m" | b"min" => 60_000,
+			b"h" => 3_600_000,
+			b"d" => 86_400_000,
+			_ => return None,
+		};
+
+		match amount.checked_mul(unit_ms) {
+			Some(0) | None => None,
+			Some(ms) => Some(Duration::from_millis(ms)),
+		}
+	}
 }
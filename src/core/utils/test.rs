@@ -1,6 +1,8 @@
 // TODO: create tests
 use super::MurFmt;
+use super::MurTime;
 use super::mur_codec::MurCodec;
+use std::time::Duration;
 
 #[test]
 fn test_slugify() {
@@ -33,3 +35,41 @@ fn test_decode() {
 		b"world!".to_vec()
 	);
 }
+
+#[test]
+fn test_base64_url() {
+	assert_eq!(MurCodec::base64_url_encode(&[0xfb, 0xff, 0xfe]), "-__-");
+	assert_eq!(MurCodec::base64_url_encode(b"hello"), "aGVsbG8");
+	assert_eq!(
+		MurCodec::base64_url_decode("-__-").unwrap(),
+		vec![0xfb, 0xff, 0xfe]
+	);
+	assert!(MurCodec::base64_url_decode("+//+").is_err());
+}
+
+#[test]
+fn test_parse_duration() {
+	assert_eq!(
+		MurTime::parse_duration("500ms"),
+		Some(Duration::from_millis(500))
+	);
+	assert_eq!(
+		MurTime::parse_duration("30s"),
+		Some(Duration::from_secs(30))
+	);
+	assert_eq!(MurTime::parse_duration("1m"), Some(Duration::from_secs(60)));
+	assert_eq!(
+		MurTime::parse_duration("2h"),
+		Some(Duration::from_secs(7200))
+	);
+	assert_eq!(MurTime::parse_duration("10"), Some(Duration::from_secs(10)));
+	assert_eq!(
+		MurTime::parse_duration(" 5 min "),
+		Some(Duration::from_secs(300))
+	);
+	assert_eq!(MurTime::parse_duration("0s"), None);
+	assert_eq!(MurTime::parse_duration("1w"), None);
+	assert_eq!(MurTime::parse_duration("m"), None);
+	assert_eq!(MurTime::parse_duration("300000000000000d"), None);
+	assert_eq!(MurTime::parse_duration("99999999999999999999s"), None);
+}
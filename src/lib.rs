@@ -27,6 +27,7 @@ pub use murgamu_macros::MurEntity;
 pub use murgamu_macros::api;
 pub use murgamu_macros::body;
 pub use murgamu_macros::controller;
+pub use murgamu_macros::decorate;
 pub use murgamu_macros::delete;
 pub use murgamu_macros::get;
 pub use murgamu_macros::guard;
@@ -52,14 +53,27 @@ pub use murgamu_macros::queryparam;
 pub use murgamu_macros::role;
 pub use murgamu_macros::route;
 pub use murgamu_macros::service;
+pub use murgamu_macros::session;
+pub use murgamu_macros::set_metadata;
+pub use murgamu_macros::skip_csrf;
+pub use murgamu_macros::skip_throttle;
 pub use murgamu_macros::text_response;
+pub use murgamu_macros::throttle;
+pub use murgamu_macros::timeout;
 pub use murgamu_macros::use_pipe;
+pub use murgamu_macros::use_guards;
+pub use murgamu_macros::use_interceptors;
+pub use murgamu_macros::use_pipes;
 pub use murgamu_macros::validate;
 
 pub use core::utils::MurResponder;
 
+pub use server::MurConnectionStats;
+pub use server::MurListenAddr;
+pub use server::MurListener;
 pub use server::MurServer;
 pub use server::MurServerRunner;
+pub use server::MurShutdownToken;
 pub use server::aliases::MurCookie;
 pub use server::aliases::MurFuture;
 pub use server::aliases::MurMainResult;
@@ -99,6 +113,8 @@ pub use server::config::mur_is_production;
 pub use server::config::mur_is_test;
 pub use server::config::mur_load_config;
 pub use server::config::mur_load_config_required;
+pub use server::logging::mur_current_request_id;
+pub use server::logging::mur_with_request_id;
 pub use server::controller::IntoController;
 pub use server::controller::MurCloneController;
 pub use server::controller::MurController;
@@ -107,11 +123,16 @@ pub use server::controller::MurControllers;
 pub use server::controller::controllers;
 pub use server::decorator::MurDecorator;
 pub use server::error::MurError;
+pub use server::error::MurErrorFormat;
 pub use server::error::MurExceptionFilter;
+pub use server::error::MurFieldError;
+pub use server::error::MurProblem;
 pub use server::guard::MurGuard;
 pub use server::guard::MurGuardFactory;
 pub use server::guard::MurGuardFuture;
+pub use server::guard::MurGuardProvider;
 pub use server::guard::MurGuardSync;
+pub use server::guard::mur_guard_provider;
 pub use server::http::MurBody;
 pub use server::http::MurExtractor;
 pub use server::http::MurExtractorSync;
@@ -121,41 +142,121 @@ pub use server::http::MurIntoResponse;
 pub use server::http::MurJson;
 pub use server::http::MurMethod;
 pub use server::http::MurPath;
+pub use server::http::MurPayload;
 pub use server::http::MurQuery;
 pub use server::http::MurQueryParam;
 pub use server::http::MurRequestContext;
 pub use server::http::MurResExt;
 pub use server::http::MurResponseBuilder;
 pub use server::http::MurText;
+pub use server::http::cache::MurCacheControl;
+pub use server::http::cache::MurEntityTag;
+pub use server::http::codec::MurBodyCodec;
+pub use server::http::codec::MurBodyCodecs;
+pub use server::http::codec::MurBodyFormat;
+pub use server::http::codec::MurMediaRange;
 pub use server::http::extractors::Param;
 pub use server::http::multipart::MurFormField;
 pub use server::http::multipart::MurMultipart;
 pub use server::http::multipart::MurMultipartConfig;
 pub use server::http::multipart::MurUploadedFile;
+pub use server::interceptor::MurCallHandler;
+pub use server::interceptor::MurInterceptFuture;
 pub use server::interceptor::MurInterceptor;
 pub use server::interceptor::MurInterceptorFactory;
 pub use server::interceptor::MurInterceptorFuture;
+pub use server::interceptor::MurInterceptorProvider;
+pub use server::interceptor::mur_interceptor_provider;
+pub use server::interceptor::cache::MurCache;
+pub use server::interceptor::cache::MurCacheInterceptor;
+pub use server::metrics::MUR_DEFAULT_BUCKETS;
+pub use server::metrics::MurCounter;
+pub use server::metrics::MurGauge;
+pub use server::metrics::MurHistogram;
+pub use server::metrics::MurMetrics;
+pub use server::metrics::MurMetricsConfig;
+pub use server::metrics::MurMetricsController;
+pub use server::metrics::MurMetricsMiddleware;
+pub use server::metrics::MurMetricsModule;
 pub use server::middleware::MurMiddleware;
 pub use server::middleware::MurNext;
+pub use server::middleware::csrf::MurCsrf;
+pub use server::middleware::csrf::MurCsrfConfig;
+pub use server::middleware::csrf::MurCsrfExempt;
+pub use server::middleware::csrf::MurCsrfMode;
+pub use server::middleware::csrf::MurCsrfToken;
+pub use server::middleware::etag::MurETag;
+pub use server::middleware::rate_limit::MurRouteThrottle;
+pub use server::middleware::rate_limit::MurThrottlePolicy;
 pub use server::middleware::rate_limit::MurThrottler;
 pub use server::middleware::rate_limit::MurThrottlerAlgorithm;
 pub use server::middleware::rate_limit::MurThrottlerConfig;
 pub use server::middleware::rate_limit::MurThrottlerKey;
+pub use server::middleware::rate_limit::MurThrottlerResult;
+pub use server::middleware::rate_limit::MurThrottlerStore;
+pub use server::middleware::rate_limit::RedisThrottlerStore;
+pub use server::middleware::request_id::MurRequestId;
+pub use server::middleware::request_id::MurRequestIdGenerator;
+pub use server::middleware::timeout::MurDeadline;
 pub use server::middleware::timeout::MurTimeout;
+pub use server::middleware::timeout::MurTimeoutMetrics;
 pub use server::middleware::timeout::TimeoutConfig;
 pub use server::module::MurModule;
 pub use server::module::MurModuleConfig;
+pub use server::oidc::MurIdTokenClaims;
+pub use server::oidc::MurJwk;
+pub use server::oidc::MurJwkSet;
+pub use server::oidc::MurOidcClient;
+pub use server::oidc::MurOidcConfig;
+pub use server::oidc::MurOidcController;
+pub use server::oidc::MurOidcDiscovery;
+pub use server::oidc::MurOidcIdentity;
+pub use server::oidc::MurOidcModule;
+pub use server::oidc::MurOidcTokens;
+pub use server::pipe::DefaultValuePipe;
+pub use server::pipe::MurArgument;
+pub use server::pipe::MurArgumentKind;
+pub use server::pipe::MurArgumentPipeline;
+pub use server::pipe::MurAsyncPipe;
 pub use server::pipe::MurPipe;
 pub use server::pipe::MurPipeAsync;
 pub use server::pipe::MurPipeDyn;
 pub use server::pipe::MurPipeFactory;
+pub use server::pipe::MurPipeFuture;
+pub use server::pipe::MurSyncPipe;
+pub use server::pipe::MurValidate;
+pub use server::pipe::ParseIntPipe;
+pub use server::pipe::ParseUuidPipe;
+pub use server::pipe::TrimPipe;
+pub use server::pipe::ValidationPipe;
 pub use server::provider::MurProvider;
 pub use server::provider::MurProviderScope;
 pub use server::router::MurRouteBuilder;
 pub use server::router::MurRouteDefinition;
 pub use server::router::MurRouteInfo;
+pub use server::router::MurRouteMetadata;
 pub use server::router::MurRoutePattern;
 pub use server::router::MurRouter;
+pub use server::router::MurTrailingSlash;
+pub use server::router::MurVersionDeprecation;
+pub use server::router::MurVersioning;
+pub use server::router::MurVersioningStrategy;
+pub use server::security::api_key::MurApiKey;
+pub use server::security::api_key::MurApiKeyAuth;
+pub use server::security::api_key::MurApiKeyFuture;
+pub use server::security::api_key::MurApiKeyGuard;
+pub use server::security::api_key::MurApiKeyIdentity;
+pub use server::security::api_key::MurApiKeyScopeGuard;
+pub use server::security::api_key::MurApiKeyScopes;
+pub use server::security::api_key::MurApiKeySource;
+pub use server::security::api_key::MurApiKeyStore;
+pub use server::security::api_key::MurFileApiKeyStore;
+pub use server::security::api_key::MurMemoryApiKeyStore;
+pub use server::security::cookie::MurCookieJar;
+pub use server::security::cookie::MurCookieKeys;
+pub use server::security::tls::MurClientCert;
+pub use server::security::tls::MurClientCertGuard;
+pub use server::security::tls::MurClientCertPatterns;
 pub use server::service::MurDependencies;
 pub use server::service::MurInjectable;
 pub use server::service::MurInjects;
@@ -164,6 +265,15 @@ pub use server::service::MurServiceContainer;
 pub use server::service::MurServiceContainerBuilder;
 pub use server::service::MurServiceFactory;
 pub use server::service::MurServices;
+pub use server::session::MurFileSessionStore;
+pub use server::session::MurMemorySessionStore;
+pub use server::session::MurRedisSessionStore;
+pub use server::session::MurSession;
+pub use server::session::MurSessionConfig;
+pub use server::session::MurSessionFuture;
+pub use server::session::MurSessionModule;
+pub use server::session::MurSessionRecord;
+pub use server::session::MurSessionStore;
 
 pub mod prelude {
 	pub use http;
@@ -180,23 +290,39 @@ pub mod prelude {
 	pub use std::sync::Arc;
 	pub use tokio;
 
+	pub use crate::DefaultValuePipe;
 	pub use crate::IntoController;
+	pub use crate::MurApiKeyGuard;
+	pub use crate::MurApiKeyIdentity;
+	pub use crate::MurApiKeyScopeGuard;
+	pub use crate::MurApiKeyScopes;
 	pub use crate::MurBody;
+	pub use crate::MurBodyCodec;
+	pub use crate::MurBodyCodecs;
+	pub use crate::MurBodyFormat;
+	pub use crate::MurCallHandler;
+	pub use crate::MurClientCert;
+	pub use crate::MurClientCertGuard;
+	pub use crate::MurClientCertPatterns;
 	pub use crate::MurCloneController;
 	pub use crate::MurConfig;
 	pub use crate::MurConfigBuilder;
 	pub use crate::MurConfigService;
 	pub use crate::MurController;
 	pub use crate::MurControllers;
+	pub use crate::MurCsrf;
+	pub use crate::MurCsrfToken;
 	pub use crate::MurDecorator;
 	pub use crate::MurDto;
 	pub use crate::MurEntity;
 	pub use crate::MurEnv;
 	pub use crate::MurEnvProfile;
 	pub use crate::MurError;
+	pub use crate::MurErrorFormat;
 	pub use crate::MurExceptionFilter;
 	pub use crate::MurExtractor;
 	pub use crate::MurExtractorSync;
+	pub use crate::MurFieldError;
 	pub use crate::MurFuture;
 	pub use crate::MurGuard;
 	pub use crate::MurGuardFuture;
@@ -205,22 +331,33 @@ pub mod prelude {
 	pub use crate::MurHttpResponse;
 	pub use crate::MurInjectable;
 	pub use crate::MurInjects;
+	pub use crate::MurInterceptFuture;
 	pub use crate::MurInterceptor;
 	pub use crate::MurInterceptorFactory;
 	pub use crate::MurInterceptorFuture;
 	pub use crate::MurIntoResponse;
 	pub use crate::MurJson;
+	pub use crate::MurListenAddr;
+	pub use crate::MurListener;
 	pub use crate::MurMethod;
+	pub use crate::MurMetrics;
+	pub use crate::MurMetricsModule;
 	pub use crate::MurMiddleware;
 	pub use crate::MurModule;
 	pub use crate::MurModuleConfig;
 	pub use crate::MurNext;
+	pub use crate::MurOidcClient;
+	pub use crate::MurOidcIdentity;
+	pub use crate::MurOidcModule;
 	pub use crate::MurPath;
+	pub use crate::MurPayload;
 	pub use crate::MurPathParams;
 	pub use crate::MurPipe;
 	pub use crate::MurPipeAsync;
 	pub use crate::MurPipeDyn;
 	pub use crate::MurPipeFactory;
+	pub use crate::MurPipeFuture;
+	pub use crate::MurProblem;
 	pub use crate::MurProvider;
 	pub use crate::MurProviderScope;
 	pub use crate::MurQuery;
@@ -238,15 +375,31 @@ pub mod prelude {
 	pub use crate::MurRouteDefinition;
 	pub use crate::MurRouteHandler;
 	pub use crate::MurRouteInfo;
+	pub use crate::MurRouteMetadata;
 	pub use crate::MurRoutePattern;
 	pub use crate::MurRouter;
 	pub use crate::MurService;
 	pub use crate::MurServiceContainer;
 	pub use crate::MurServiceFactory;
+	pub use crate::MurSession;
+	pub use crate::MurSessionModule;
+	pub use crate::MurSessionStore;
+	pub use crate::MurShutdownToken;
+	pub use crate::MurSyncPipe;
+	pub use crate::MurTrailingSlash;
+	pub use crate::MurValidate;
+	pub use crate::MurVersionDeprecation;
+	pub use crate::MurVersioning;
+	pub use crate::MurVersioningStrategy;
+	pub use crate::ParseIntPipe;
+	pub use crate::ParseUuidPipe;
+	pub use crate::TrimPipe;
+	pub use crate::ValidationPipe;
 	pub use crate::api;
 	pub use crate::body;
 	pub use crate::controller;
 	pub use crate::controllers;
+	pub use crate::decorate;
 	pub use crate::delete;
 	pub use crate::get;
 	pub use crate::guard;
@@ -293,7 +446,17 @@ pub mod prelude {
 	pub use crate::server::http::sse::mur_sse_headers;
 	pub use crate::server::http::sse::mur_sse_json;
 	pub use crate::service;
+	pub use crate::session;
+	pub use crate::set_metadata;
+	pub use crate::skip_csrf;
+	pub use crate::skip_throttle;
 	pub use crate::text_response;
+	pub use crate::throttle;
+	pub use crate::timeout;
+	pub use crate::use_pipe;
+	pub use crate::use_guards;
+	pub use crate::use_interceptors;
+	pub use crate::use_pipes;
 	pub use crate::validate;
 }
 
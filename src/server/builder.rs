@@ -3,19 +3,31 @@ use crate::MurPipeDyn;
 use crate::server::interceptor::MurInterceptorFactory;
 use crate::server::pipe::MurPipeFactory;
 
+use super::aliases::{MurRes, MurRouteHandler};
 use super::config::MurServerConfig;
+use super::error::{MurErrorFormat, MurExceptionFilter};
 use super::guard::MurGuard;
 use super::interceptor::MurInterceptor;
+use super::listener::{MurListenAddr, MurListener};
 use super::middleware::MurMiddleware;
 use super::middleware::cors::MurCors;
+use super::middleware::rate_limit::{MurThrottlePolicy, MurThrottlerStore};
 use super::module::MurModule;
-use super::router::MurRouter;
-use super::runner::MurServerRunner;
-use super::security::tls::{MurTlsAcceptor, MurTlsConfig};
+use super::router::open_api::controller::MurOpenApiController;
+use super::router::open_api::mur_open_api::MurOpenApi;
+use super::router::{MurRouter, MurTrailingSlash, MurVersioning};
+use super::runner::{MurConnectionStats, MurServerRunner};
+use super::security::cookie::MurCookieKeys;
+use super::security::tls::{MurTlsAcceptor, MurTlsConfig, MurTlsError};
+use super::shutdown::MurShutdownToken;
 use super::service::{MurInjectable, MurInjects, MurService, MurServiceContainer};
-use std::collections::HashSet;
+use super::http::MurRequestContext;
+use super::http::codec::MurBodyCodec;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::net::{SocketAddr, ToSocketAddrs};
 use std::sync::Arc;
+use std::time::Duration;
 
 type GuardFactory =
 	Box<dyn Fn(&MurInjects, &MurServiceContainer) -> Box<dyn MurGuard + Send + Sync> + Send + Sync>;
@@ -56,17 +68,33 @@ type InterceptorFactory = Box<
 /// works for the rest of the application startup.
 pub struct MurServer {
 	modules: Vec<Box<dyn MurModule + Send + Sync>>,
+	in_place_modules: HashSet<usize>,
 	container: MurServiceContainer,
 	injects: MurInjects,
 	guards: Vec<GuardFactory>,
 	pipes: Vec<PipeFactory>,
 	interceptor_factories: Vec<InterceptorFactory>,
+	pipe_instances: Vec<Box<dyn MurPipeDyn>>,
 	interceptor_instances: Vec<Box<dyn MurInterceptor + Send + Sync>>,
 	middleware: Vec<Box<dyn MurMiddleware + Sync + Send>>,
+	exception_filters: Vec<Arc<dyn MurExceptionFilter + Send + Sync>>,
+	error_format: MurErrorFormat,
+	not_found_handlers: Vec<(String, MurRouteHandler)>,
+	trailing_slash: MurTrailingSlash,
+	global_prefix: String,
+	global_prefix_exclusions: Vec<String>,
+	versioning: Option<MurVersioning>,
+	body_codecs: Vec<Arc<dyn MurBodyCodec>>,
+	cookie_keys: Option<MurCookieKeys>,
+	open_api: Option<(MurOpenApi, String)>,
 	config: MurServerConfig,
 	on_startup: Vec<Box<dyn Fn() + Send + Sync>>,
 	on_shutdown: Vec<Box<dyn Fn() + Send + Sync>>,
 	default_public: bool,
+	throttle_policies: HashMap<String, MurThrottlePolicy>,
+	throttle_store: Option<Arc<dyn MurThrottlerStore>>,
+	listeners: Vec<MurListener>,
+	shutdown: MurShutdownToken,
 }
 
 impl Default for MurServer {
@@ -86,17 +114,33 @@ impl MurServer {
 
 		Self {
 			modules: Vec::new(),
+			in_place_modules: HashSet::new(),
 			container: MurServiceContainer::new(),
 			injects: MurInjects::new(),
 			guards: Vec::new(),
 			pipes: Vec::new(),
 			interceptor_factories: Vec::new(),
+			pipe_instances: Vec::new(),
 			interceptor_instances: Vec::new(),
 			middleware: Vec::new(),
+			exception_filters: Vec::new(),
+			error_format: MurErrorFormat::default(),
+			not_found_handlers: Vec::new(),
+			trailing_slash: MurTrailingSlash::default(),
+			global_prefix: String::new(),
+			global_prefix_exclusions: Vec::new(),
+			versioning: None,
+			body_codecs: Vec::new(),
+			cookie_keys: None,
+			open_api: None,
 			config: MurServerConfig::default(),
 			on_startup: Vec::new(),
 			on_shutdown: Vec::new(),
 			default_public: false,
+			throttle_policies: HashMap::new(),
+			throttle_store: None,
+			listeners: Vec::new(),
+			shutdown: MurShutdownToken::new(),
 		}
 	}
 
@@ -132,6 +176,33 @@ impl MurServer {
 		self
 	}
 
+	/// Keeps accepting for `delay` after a shutdown signal while readiness
+	/// reports `DOWN`, giving load balancers time to stop routing here.
+	pub fn pre_stop_delay(mut self, delay: Duration) -> Self {
+		self.config = self.config.pre_stop_delay(delay);
+		self
+	}
+
+	/// The token cancelled when this server shuts down; also registered as
+	/// a service.
+	pub fn shutdown_token(&self) -> MurShutdownToken {
+		self.shutdown.clone()
+	}
+
+	/// Binds the primary socket with `SO_REUSEPORT`, so the next release can
+	/// start listening before this one stops.
+	pub fn reuse_port(mut self) -> Self {
+		self.config = self.config.reuse_port();
+		self
+	}
+
+	/// Hands the sockets over between releases through a pid file; see
+	/// [`MurServerConfig::handoff_pid_file`].
+	pub fn handoff_pid_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+		self.config = self.config.handoff_pid_file(path);
+		self
+	}
+
 	/// Registers a callback invoked once when the server finishes starting up.
 	pub fn on_startup(mut self, hook: impl Fn() + Send + Sync + 'static) -> Self {
 		self.on_startup.push(Box::new(hook));
@@ -152,6 +223,19 @@ impl MurServer {
 		self
 	}
 
+	/// Adds a [`MurModule`] whose middleware joins the chain at this point.
+	///
+	/// With [`module`](Self::module) a module's middleware runs after all of
+	/// the server's global middleware. Use this instead when global middleware
+	/// registered later depends on it, e.g. CSRF protection reading the
+	/// session that [`MurSessionModule`](crate::MurSessionModule) loads.
+	pub fn module_in_place(mut self, module: impl MurModule + 'static) -> Self {
+		self.middleware.extend(module.middleware());
+		self.in_place_modules.insert(self.modules.len());
+		self.modules.push(Box::new(module));
+		self
+	}
+
 	/// Registers a manually constructed injectable value.
 	///
 	/// Use this for values that are created outside the DI container (e.g.
@@ -203,6 +287,13 @@ impl MurServer {
 		self
 	}
 
+	/// Registers an already-built global pipe, such as
+	/// `DefaultValuePipe::new(..)` or a configured `ParseUuidPipe`.
+	pub fn global_pipe(mut self, pipe: impl MurPipeDyn) -> Self {
+		self.pipe_instances.push(Box::new(pipe));
+		self
+	}
+
 	/// Registers a DI-enabled global interceptor.
 	///
 	/// `T` must implement [`MurInterceptorFactory`] (generated by `#[interceptor]`).
@@ -230,6 +321,152 @@ impl MurServer {
 		self
 	}
 
+	/// Registers an exception filter. Filters are consulted in registration
+	/// order for errors returned by handlers, guards and interceptors.
+	pub fn exception_filter(mut self, filter: impl MurExceptionFilter) -> Self {
+		self.exception_filters.push(Arc::new(filter));
+		self
+	}
+
+	/// Sets how errors are written when no exception filter takes them:
+	/// JSON, RFC 9457 `application/problem+json`, or negotiated from the
+	/// `Accept` header (the default). Routes override it with
+	/// `#[set_metadata(MurErrorFormat::Problem)]`.
+	pub fn error_format(mut self, format: MurErrorFormat) -> Self {
+		self.error_format = format;
+		self
+	}
+
+	/// Sets the handler for requests that match no route. It runs inside the
+	/// middleware chain, with the request's context.
+	///
+	/// ```rust,ignore
+	/// MurServer::new().not_found(|ctx: MurRequestContext| async move {
+	///     MurHttpResponse::not_found().json(json!({ "missing": ctx.path() }))
+	/// })
+	/// ```
+	pub fn not_found<F, Fut>(self, handler: F) -> Self
+	where
+		F: Fn(MurRequestContext) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = MurRes> + Send + 'static,
+	{
+		self.not_found_for("/", handler)
+	}
+
+	/// Sets the not-found handler for the paths under `prefix`, e.g. a JSON
+	/// body under `/api` and an HTML page elsewhere. The longest matching
+	/// prefix wins.
+	pub fn not_found_for<F, Fut>(mut self, prefix: &str, handler: F) -> Self
+	where
+		F: Fn(MurRequestContext) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = MurRes> + Send + 'static,
+	{
+		let handler: MurRouteHandler = Arc::new(move |ctx| Box::pin(handler(ctx)));
+		self.not_found_handlers.push((prefix.to_string(), handler));
+		self
+	}
+
+	/// Sets how paths with a trailing slash are routed. Defaults to
+	/// [`MurTrailingSlash::Lenient`].
+	pub fn trailing_slash(mut self, policy: MurTrailingSlash) -> Self {
+		self.trailing_slash = policy;
+		self
+	}
+
+	/// Prefixes every controller route with `prefix`, after any module
+	/// prefix. Routes registered directly on the router are not prefixed.
+	///
+	/// ```rust,ignore
+	/// MurServer::new()
+	///     .global_prefix("/api")
+	///     .exclude_from_global_prefix(["/health", "/docs"])
+	/// ```
+	pub fn global_prefix(mut self, prefix: &str) -> Self {
+		self.global_prefix = prefix.to_string();
+		self
+	}
+
+	/// Keeps the controller routes at or below each of `paths` out of the
+	/// global prefix. Paths are matched segment-wise before prefixing.
+	pub fn exclude_from_global_prefix<I, S>(mut self, paths: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self
+			.global_prefix_exclusions
+			.extend(paths.into_iter().map(Into::into));
+		self
+	}
+
+	/// Selects controller routes by API version, for controllers and
+	/// handlers declared with `version = …`.
+	///
+	/// ```rust,ignore
+	/// MurServer::new().versioning(MurVersioning::uri().default_version("1"))
+	/// ```
+	pub fn versioning(mut self, versioning: MurVersioning) -> Self {
+		self.versioning = Some(versioning);
+		self
+	}
+
+	/// Adds a body format, read by `MurPayload` and `ctx.body_as()` and
+	/// written by `MurRes::negotiated`. A codec takes the place of the
+	/// built-in format with the same media type.
+	///
+	/// ```rust,ignore
+	/// MurServer::new().body_codec(YamlCodec)
+	/// ```
+	pub fn body_codec(mut self, codec: impl MurBodyCodec) -> Self {
+		self.body_codecs.push(Arc::new(codec));
+		self
+	}
+
+	/// Signs and encrypts cookies with `keys`, for `ctx.cookie_jar()`,
+	/// `ctx.signed_cookie()` and session cookies.
+	///
+	/// ```rust,ignore
+	/// MurServer::new().cookie_keys(MurCookieKeys::from_config(&config)?)
+	/// ```
+	pub fn cookie_keys(mut self, keys: MurCookieKeys) -> Self {
+		self.cookie_keys = Some(keys);
+		self
+	}
+
+	/// Serves the OpenAPI document at `/api-docs/openapi.json` and Swagger UI
+	/// at `/api-docs`. Routes the document does not describe are added from
+	/// the route table, with the API versions they serve.
+	pub fn open_api(self, api: MurOpenApi) -> Self {
+		self.open_api_at(api, "/api-docs")
+	}
+
+	/// Like [`open_api`](Self::open_api), served under `path`.
+	pub fn open_api_at(mut self, api: MurOpenApi, path: impl Into<String>) -> Self {
+		self.open_api = Some((api, path.into()));
+		self
+	}
+
+	/// Registers a named rate limit policy, applied to handlers or controllers
+	/// annotated with `#[throttle("name")]`.
+	///
+	/// ```rust,ignore
+	/// MurServer::new()
+	///     .throttle_policy("login", MurThrottlePolicy::per_minute(5).by_ip())
+	///     .throttle_policy("search", MurThrottlePolicy::per_minute(100).by_bearer_token())
+	/// ```
+	pub fn throttle_policy(mut self, name: impl Into<String>, policy: MurThrottlePolicy) -> Self {
+		self.throttle_policies.insert(name.into(), policy);
+		self
+	}
+
+	/// Sets the store backing route-level throttle policies. Defaults to an
+	/// in-memory store; use a shared store such as
+	/// [`RedisThrottlerStore`](crate::RedisThrottlerStore) when running replicas.
+	pub fn throttle_store(mut self, store: impl MurThrottlerStore) -> Self {
+		self.throttle_store = Some(Arc::new(store));
+		self
+	}
+
 	/// Resolves the bind address and finalises the server, returning a
 	/// [`MurServerRunner`] ready to call `.run().await` on.
 	pub fn bind(self, addr: impl ToSocketAddrs) -> Result<MurServerRunner, std::io::Error> {
@@ -251,26 +488,93 @@ impl MurServer {
 		self.bind_addr(addr)
 	}
 
+	/// Adds a listener served alongside the one passed to `bind`, e.g. an
+	/// internal admin port, a Unix socket or an HTTP→HTTPS redirect.
+	pub fn listener(mut self, listener: MurListener) -> Self {
+		self.listeners.push(listener);
+		self
+	}
+
 	/// Binds to a pre-resolved [`SocketAddr`] and finalises the server.
 	pub fn bind_addr(mut self, addr: SocketAddr) -> Result<MurServerRunner, std::io::Error> {
+		let mut primary = MurListener::new(MurListenAddr::Tcp(addr));
+		primary.tls = self.config.tls.clone();
+		primary.reuse_port = self.config.reuse_port;
+		self.listeners.insert(0, primary);
 		self.config.addr = addr;
+		self.finish()
+	}
+
+	/// Finalises the server on `listeners` plus any added with
+	/// [`listener`](Self::listener), without a primary bind address.
+	pub fn bind_listeners(
+		mut self,
+		listeners: impl IntoIterator<Item = MurListener>,
+	) -> Result<MurServerRunner, std::io::Error> {
+		self.listeners.extend(listeners);
+		if self.listeners.is_empty() {
+			return Err(std::io::Error::new(
+				std::io::ErrorKind::InvalidInput,
+				"No listeners configured",
+			));
+		}
+		if let Some(addr) = self.listeners.iter().find_map(|l| match l.addr {
+			MurListenAddr::Tcp(addr) => Some(addr),
+			MurListenAddr::Unix(_) | MurListenAddr::Inherited(_) => None,
+		}) {
+			self.config.addr = addr;
+		}
+		self.finish()
+	}
+
+	fn finish(self) -> Result<MurServerRunner, std::io::Error> {
 		self.injects.on_init();
 
 		let mut global = MurServiceContainer::new();
 		global.merge(self.container);
+		global.register(self.shutdown.clone());
+		let connections = Arc::new(MurConnectionStats::default());
+		global.register_arc(Arc::clone(&connections));
+
+		// Global modules are built first so the others can resolve their exports.
+		let mut prebuilt = Vec::with_capacity(self.modules.len());
+		for module in &self.modules {
+			if !module.is_global() {
+				prebuilt.push(None);
+				continue;
+			}
+			module.on_init();
+
+			let mut visible =
+				Self::resolve_module_container(module.as_ref(), &self.injects, &global);
+			visible.merge(global.clone());
+			let services = module.services_with_injects(&self.injects, &visible);
+			let export_ids = module.exports();
+			for (tid, svc) in &services {
+				if export_ids.contains(tid) {
+					global.register_dyn_with_id(*tid, svc.clone());
+				}
+			}
+			prebuilt.push(Some(services));
+		}
 
 		let mut runtime = global.clone();
 		let mut module_containers: Vec<MurServiceContainer> =
 			Vec::with_capacity(self.modules.len());
 
-		for module in &self.modules {
-			module.on_init();
+		for (module, prebuilt) in self.modules.iter().zip(prebuilt) {
+			if prebuilt.is_none() {
+				module.on_init();
+			}
 
 			let mut visible =
 				Self::resolve_module_container(module.as_ref(), &self.injects, &global);
 			visible.merge(global.clone());
 
-			let local_services = module.services_with_injects(&self.injects, &visible);
+			let local_services = match prebuilt {
+				Some(services) => services,
+				None => module.services_with_injects(&self.injects, &visible),
+			};
 			for (tid, svc) in &local_services {
 				visible.register_dyn_with_id(*tid, svc.clone());
 			}
@@ -288,6 +592,31 @@ impl MurServer {
 		let container = Arc::new(runtime);
 		let mut router = MurRouter::new(Arc::clone(&container));
 		router.default_public = self.default_public;
+		for (name, policy) in self.throttle_policies {
+			router.throttle_policy(name, policy);
+		}
+		if let Some(store) = self.throttle_store {
+			router.throttle_store(store);
+		}
+		router.exception_filters = self.exception_filters;
+		router.set_error_format(self.error_format);
+		router.set_trailing_slash(self.trailing_slash);
+		router.set_global_prefix(&self.global_prefix);
+		if let Some(versioning) = self.versioning {
+			router.set_versioning(versioning);
+		}
+		if let Some(keys) = self.cookie_keys {
+			router.set_cookie_keys(keys);
+		}
+		for codec in self.body_codecs {
+			router.body_codec(codec);
+		}
+		for path in &self.global_prefix_exclusions {
+			router.exclude_from_global_prefix(path);
+		}
+		for (prefix, handler) in self.not_found_handlers {
+			router.set_not_found_handler_for(&prefix, handler);
+		}
 
 		for factory in self.guards {
 			router.guard_boxed(factory(&self.injects, &container));
@@ -295,6 +624,9 @@ impl MurServer {
 		for factory in self.pipes {
 			router.pipe_boxed(factory(&self.injects, &container));
 		}
+		for instance in self.pipe_instances {
+			router.pipe_boxed(instance);
+		}
 		for factory in self.interceptor_factories {
 			router.interceptor_boxed(factory(&self.injects, &container));
 		}
@@ -315,26 +647,51 @@ impl MurServer {
 				};
 			router.prepend_middleware(cors);
 		}
+		for (index, module) in self.modules.iter().enumerate() {
+			if self.in_place_modules.contains(&index) {
+				continue;
+			}
+			for mw in module.middleware() {
+				router.middleware_boxed(mw);
+			}
+		}
 
 		for (module, module_container) in self.modules.iter().zip(module_containers.iter()) {
 			if self.config.enable_logging {
 				println!("Loading module: {}", module.name());
 			}
 			for controller in module.controllers_with_injects(&self.injects, module_container) {
-				router.register_controller(controller);
+				router.register_controller_in(
+					controller,
+					module.prefix(),
+					&self.injects,
+					module_container,
+				);
 			}
 		}
 
+		if let Some((api, path)) = self.open_api {
+			let spec = api.routes(router.route_info()).build();
+			let docs = Arc::new(MurOpenApiController::with_path(spec, path));
+			router.register_controller_in(docs, "/", &self.injects, &container);
+		}
+
+		router
+			.validate()
+			.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
 		if self.config.enable_logging {
 			router.print_routes();
 		}
 
-		let tls_acceptor = self
-			.config
-			.tls
-			.as_ref()
-			.map(MurTlsAcceptor::new)
-			.transpose()?;
+		let listeners = self
+			.listeners
+			.into_iter()
+			.map(|listener| {
+				let tls = listener.tls.as_ref().map(MurTlsAcceptor::new).transpose()?;
+				Ok((listener, tls))
+			})
+			.collect::<Result<Vec<_>, MurTlsError>>()?;
 
 		Ok(MurServerRunner {
 			router: Arc::new(router),
@@ -343,7 +700,9 @@ impl MurServer {
 			injects: self.injects,
 			on_startup: self.on_startup,
 			on_shutdown: self.on_shutdown,
-			tls_acceptor,
+			listeners,
+			shutdown: self.shutdown,
+			connections,
 		})
 	}
 
@@ -388,7 +747,7 @@ impl MurServer {
 	}
 }
 
-fn resolve_addr(addr: impl ToSocketAddrs) -> Result<SocketAddr, std::io::Error> {
+pub(crate) fn resolve_addr(addr: impl ToSocketAddrs) -> Result<SocketAddr, std::io::Error> {
 	addr.to_socket_addrs()?
 		.next()
 		.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid address"))
@@ -1,6 +1,7 @@
 use crate::MurThrottler;
 use crate::server::security::tls::MurTlsConfig;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -12,11 +13,18 @@ pub struct MurServerConfig {
 	pub http2: bool,
 	pub graceful_shutdown: bool,
 	pub shutdown_timeout: Duration,
+	/// How long the server keeps accepting, with readiness `DOWN`, after a
+	/// shutdown signal before it stops accepting connections.
+	pub pre_stop_delay: Duration,
 	pub enable_logging: bool,
 	pub server_name: String,
 	pub enable_cors: bool,
 	pub cors_origins: Vec<String>,
 	pub tls: Option<MurTlsConfig>,
+	/// Binds the primary TCP socket with `SO_REUSEPORT`.
+	pub reuse_port: bool,
+	/// Pid file used to hand the sockets over to a new process.
+	pub handoff_pid_file: Option<PathBuf>,
 }
 
 impl Default for MurServerConfig {
@@ -29,11 +37,14 @@ impl Default for MurServerConfig {
 			http2: false,
 			graceful_shutdown: true,
 			shutdown_timeout: Duration::from_secs(30),
+			pre_stop_delay: Duration::ZERO,
 			enable_logging: true,
 			server_name: String::from("Murgamü"),
 			enable_cors: false,
 			cors_origins: vec![String::from("*")],
 			tls: None,
+			reuse_port: false,
+			handoff_pid_file: None,
 		}
 	}
 }
@@ -78,6 +89,11 @@ impl MurServerConfig {
 		self
 	}
 
+	pub fn pre_stop_delay(mut self, delay: Duration) -> Self {
+		self.pre_stop_delay = delay;
+		self
+	}
+
 	pub fn no_logging(mut self) -> Self {
 		self.enable_logging = false;
 		self
@@ -104,6 +120,20 @@ impl MurServerConfig {
 		self.tls = Some(config);
 		self
 	}
+
+	/// Lets a new process bind the same address while this one still runs.
+	pub fn reuse_port(mut self) -> Self {
+		self.reuse_port = true;
+		self
+	}
+
+	/// Enables the zero-downtime hand-off: once listening, the server writes
+	/// its pid to `path` and sends `SIGUSR2` to the pid found there before,
+	/// and it starts a graceful shutdown itself when it receives `SIGUSR2`.
+	pub fn handoff_pid_file(mut self, path: impl Into<PathBuf>) -> Self {
+		self.handoff_pid_file = Some(path.into());
+		self
+	}
 }
 
 #[cfg(test)]
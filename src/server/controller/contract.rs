@@ -59,6 +59,15 @@ pub trait MurController: Send + Sync + 'static {
 		"/"
 	}
 
+	/// Returns the `Host` pattern the controller's routes are bound to, such
+	/// as `:tenant.example.com`.
+	///
+	/// Defaults to `None`, serving every host. The `#[controller(host = "…")]`
+	/// argument overrides this.
+	fn host(&self) -> Option<&str> {
+		None
+	}
+
 	/// Returns a human-readable identifier for the controller (defaults to the type name).
 	fn name(&self) -> &str {
 		std::any::type_name::<Self>()
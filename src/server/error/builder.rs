@@ -1,5 +1,7 @@
+use super::problem::{MurFieldError, MurProblem};
 use crate::MurResponse;
 use crate::server::aliases::MurRes;
+use crate::server::logging::mur_current_request_id;
 use http::StatusCode;
 use http_body_util::Full;
 use hyper::Response;
@@ -60,6 +62,10 @@ pub enum MurError {
 	PayloadTooLarge(String),
 	/// An error with an arbitrary HTTP status code and message.
 	Custom(StatusCode, String),
+	/// Field-level validation failures (`400 Bad Request`).
+	Validation(Vec<MurFieldError>),
+	/// An RFC 9457 problem detail, always rendered as `application/problem+json`.
+	Problem(Box<MurProblem>),
 }
 
 impl std::fmt::Display for MurError {
@@ -75,6 +81,15 @@ impl std::fmt::Display for MurError {
 			MurError::NoEnv(e) => write!(f, "No Environment Internal error: {}", e),
 			MurError::PayloadTooLarge(e) => write!(f, "Payload too large: {}", e),
 			MurError::Custom(status, e) => write!(f, "Error {}: {}", status.as_u16(), e),
+			MurError::Validation(errors) => {
+				write!(f, "Validation failed")?;
+				for (i, error) in errors.iter().enumerate() {
+					let sep = if i == 0 { ": " } else { ", " };
+					write!(f, "{}{} {}", sep, error.field, error.detail)?;
+				}
+				Ok(())
+			}
+			MurError::Problem(problem) => write!(f, "Error {}: {}", problem.status.as_u16(), problem),
 		}
 	}
 }
@@ -200,6 +215,8 @@ impl MurError {
 			MurError::NoEnv(_) => StatusCode::INTERNAL_SERVER_ERROR,
 			MurError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
 			MurError::Custom(status, _) => *status,
+			MurError::Validation(_) => StatusCode::BAD_REQUEST,
+			MurError::Problem(problem) => problem.status,
 		}
 	}
 
@@ -216,6 +233,8 @@ impl MurError {
 			MurError::NoEnv(msg) => msg,
 			MurError::PayloadTooLarge(_) => "Request body exceeds the maximum allowed size",
 			MurError::Custom(_, msg) => msg,
+			MurError::Validation(_) => "Validation failed",
+			MurError::Problem(problem) => problem.detail.as_deref().unwrap_or(&problem.title),
 		}
 	}
 
@@ -232,6 +251,16 @@ impl MurError {
 			MurError::NoEnv(_) => "internal",
 			MurError::PayloadTooLarge(_) => "too_large",
 			MurError::Custom(_, _) => "custom",
+			MurError::Validation(_) => "validation",
+			MurError::Problem(_) => "problem",
+		}
+	}
+
+	/// Returns the field-level errors of a [`Validation`](MurError::Validation) error.
+	pub fn field_errors(&self) -> &[MurFieldError] {
+		match self {
+			MurError::Validation(errors) => errors,
+			_ => &[],
 		}
 	}
 
@@ -265,6 +294,11 @@ impl MurError {
 		MurError::BadRequest(msg.into())
 	}
 
+	/// Creates a `400 Bad Request` error listing the fields that failed validation.
+	pub fn invalid(errors: impl IntoIterator<Item = MurFieldError>) -> Self {
+		MurError::Validation(errors.into_iter().collect())
+	}
+
 	/// Creates an error with an arbitrary HTTP status code.
 	pub fn custom(status: StatusCode, msg: impl Into<String>) -> Self {
 		MurError::Custom(status, msg.into())
@@ -280,6 +314,16 @@ impl MurError {
 		MurError::PayloadTooLarge(msg.into())
 	}
 
+	/// Creates a `406 Not Acceptable` error.
+	pub fn not_acceptable(msg: impl Into<String>) -> Self {
+		MurError::Custom(StatusCode::NOT_ACCEPTABLE, msg.into())
+	}
+
+	/// Creates a `415 Unsupported Media Type` error.
+	pub fn unsupported_media_type(msg: impl Into<String>) -> Self {
+		MurError::Custom(StatusCode::UNSUPPORTED_MEDIA_TYPE, msg.into())
+	}
+
 	/// Creates a `410 Gone` error.
 	pub fn gone(msg: impl Into<String>) -> Self {
 		MurError::Custom(StatusCode::GONE, msg.into())
@@ -300,21 +344,63 @@ impl MurError {
 		MurError::Custom(StatusCode::SERVICE_UNAVAILABLE, msg.into())
 	}
 
+	/// The message shown to clients; server-side details are hidden.
+	fn public_message(&self) -> String {
+		match self {
+			MurError::Internal(_) | MurError::Hyper(_) => "Internal Server Error".to_string(),
+			_ => self.message().to_string(),
+		}
+	}
+
+	/// Converts this error into an RFC 9457 problem detail.
+	///
+	/// The detail is the client-facing message, field errors become the
+	/// `errors` extension and the request ID, when in scope, the `request_id`
+	/// extension.
+	pub fn to_problem(&self) -> MurProblem {
+		let mut problem = match self {
+			MurError::Problem(problem) => return (**problem).clone(),
+			_ => MurProblem::new(self.status_code()).detail(self.public_message()),
+		};
+		if let MurError::Validation(errors) = self {
+			problem = problem.errors(errors.clone());
+		}
+		if let Some(id) = mur_current_request_id() {
+			problem = problem.extension("request_id", id);
+		}
+		problem
+	}
+
+	/// Converts this error into an `application/problem+json` response.
+	pub fn into_problem_response(self) -> MurResponse {
+		self.to_problem().into_response()
+	}
+
 	/// Converts this error into an HTTP response with a JSON body.
 	///
-	/// The response body has the shape `{ "error": "...", "status": 404, "kind": "not_found" }`.
+	/// The response body has the shape `{ "error": "...", "status": 404, "kind": "not_found" }`,
+	/// plus `"errors"` for validation errors and `"request_id"` when called
+	/// while a request ID is in scope. [`Problem`](MurError::Problem) errors
+	/// are rendered as `application/problem+json`.
 	pub fn into_response(self) -> MurResponse {
+		if let MurError::Problem(problem) = self {
+			return problem.into_response();
+		}
+
 		let status = self.status_code();
 		let kind = self.kind();
-		let message = match &self {
-			MurError::Internal(_) | MurError::Hyper(_) => "Internal Server Error".to_string(),
-			_ => self.message().to_string(),
-		};
-		let body = serde_json::json!({
+		let message = self.public_message();
+		let mut body = serde_json::json!({
 			"error": message,
 			"status": status.as_u16(),
 			"kind": kind
 		});
+		if let MurError::Validation(errors) = &self {
+			body["errors"] = serde_json::json!(errors);
+		}
+		if let Some(id) = mur_current_request_id() {
+			body["request_id"] = id.into();
+		}
 
 		Response::builder()
 			.status(status)
@@ -332,12 +418,15 @@ impl MurError {
 		let status = self.status_code();
 		let kind = self.kind();
 		let message = self.to_string();
-		let body = serde_json::json!({
+		let mut body = serde_json::json!({
 			"error": message,
 			"status": status.as_u16(),
 			"kind": kind,
 			"context": context
 		});
+		if let Some(id) = mur_current_request_id() {
+			body["request_id"] = id.into();
+		}
 
 		MurRes::from(
 			Response::builder()
@@ -392,6 +481,33 @@ mod tests {
 		assert_eq!(MurError::internal("").kind(), "internal");
 	}
 
+	#[test]
+	fn test_to_problem() {
+		let problem = MurError::internal("DB password is hunter2").to_problem();
+		assert_eq!(problem.status, StatusCode::INTERNAL_SERVER_ERROR);
+		assert_eq!(problem.title, "Internal Server Error");
+		assert_eq!(problem.detail.as_deref(), Some("Internal Server Error"));
+
+		let err = MurError::invalid([MurFieldError::new("age", "must be at least 18")]);
+		assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+		assert_eq!(err.to_string(), "Validation failed: age must be at least 18");
+		let problem = err.to_problem();
+		assert_eq!(problem.extensions["errors"][0]["field"], "age");
+	}
+
+	#[test]
+	fn test_problem_standard_members_win() {
+		let problem = MurProblem::new(StatusCode::CONFLICT)
+			.extension("status", 200)
+			.extension("retry", true);
+		let body = problem.to_json();
+
+		assert_eq!(body["status"], 409);
+		assert_eq!(body["title"], "Conflict");
+		assert_eq!(body["retry"], true);
+		assert!(body.get("detail").is_none());
+	}
+
 	#[test]
 	fn test_from_string() {
 		let err: MurError = "Something went wrong".into();
@@ -0,0 +1,47 @@
+use super::MurProblem;
+use crate::server::http::MurRequestContext;
+
+/// How unhandled errors are written to the client.
+///
+/// Set the server-wide default with `MurServer::error_format`, and override
+/// it per route with route metadata:
+///
+/// ```rust,ignore
+/// #[get("/orders/:id")]
+/// #[set_metadata(MurErrorFormat::Problem)]
+/// async fn find(&self, #[param] id: u64) -> MurRes { /* … */ }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MurErrorFormat {
+	/// `{ "error", "status", "kind" }` JSON bodies.
+	Json,
+	/// RFC 9457 `application/problem+json` bodies.
+	Problem,
+	/// `Problem` when the request accepts `application/problem+json`,
+	/// `Json` otherwise.
+	#[default]
+	Negotiate,
+}
+
+impl MurErrorFormat {
+	/// Resolves [`Negotiate`](Self::Negotiate) against the request's
+	/// `Accept` header.
+	pub fn resolve(self, ctx: &MurRequestContext) -> Self {
+		match self {
+			Self::Negotiate => {
+				let accepts_problem = ctx.header("accept").is_some_and(|accept| {
+					accept.split(',').any(|range| {
+						let media = range.split(';').next().unwrap_or_default().trim();
+						media.eq_ignore_ascii_case(MurProblem::CONTENT_TYPE)
+					})
+				});
+				if accepts_problem {
+					Self::Problem
+				} else {
+					Self::Json
+				}
+			}
+			format => format,
+		}
+	}
+}
@@ -1,5 +1,10 @@
 pub mod builder;
 pub mod contract;
+mod format;
+mod problem;
 
 pub use builder::MurError;
 pub use contract::MurExceptionFilter;
+pub use format::MurErrorFormat;
+pub use problem::MurFieldError;
+pub use problem::MurProblem;
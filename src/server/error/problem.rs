@@ -0,0 +1,153 @@
+use super::MurError;
+use crate::MurResponse;
+use crate::server::aliases::MurRes;
+use http::StatusCode;
+use http_body_util::Full;
+use hyper::Response;
+use hyper::body::Bytes;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// An RFC 9457 problem detail, rendered as `application/problem+json`.
+///
+/// Handlers can return one directly; it goes through the exception filters
+/// like any other [`MurError`]:
+///
+/// ```rust,ignore
+/// return MurProblem::new(StatusCode::FORBIDDEN)
+///     .type_uri("https://example.com/probs/out-of-credit")
+///     .title("You do not have enough credit.")
+///     .detail("Your current balance is 30, but that costs 50.")
+///     .extension("balance", 30)
+///     .into();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MurProblem {
+	/// The `type` member; `about:blank` unless set.
+	pub problem_type: String,
+	pub title: String,
+	pub status: StatusCode,
+	pub detail: Option<String>,
+	pub instance: Option<String>,
+	pub extensions: Map<String, Value>,
+}
+
+impl MurProblem {
+	pub const CONTENT_TYPE: &'static str = "application/problem+json";
+
+	/// Creates an `about:blank` problem titled with the status' reason phrase.
+	pub fn new(status: StatusCode) -> Self {
+		Self {
+			problem_type: "about:blank".to_string(),
+			title: status.canonical_reason().unwrap_or("Error").to_string(),
+			status,
+			detail: None,
+			instance: None,
+			extensions: Map::new(),
+		}
+	}
+
+	/// Sets the URI identifying the problem type.
+	pub fn type_uri(mut self, uri: impl Into<String>) -> Self {
+		self.problem_type = uri.into();
+		self
+	}
+
+	pub fn title(mut self, title: impl Into<String>) -> Self {
+		self.title = title.into();
+		self
+	}
+
+	pub fn detail(mut self, detail: impl Into<String>) -> Self {
+		self.detail = Some(detail.into());
+		self
+	}
+
+	/// Sets the URI identifying this occurrence, usually the request path.
+	pub fn instance(mut self, instance: impl Into<String>) -> Self {
+		self.instance = Some(instance.into());
+		self
+	}
+
+	/// Adds an extension member. Values that do not serialize are skipped.
+	pub fn extension(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
+		if let Ok(value) = serde_json::to_value(value) {
+			self.extensions.insert(key.into(), value);
+		}
+		self
+	}
+
+	/// Sets the `errors` extension to the given field-level errors.
+	pub fn errors(self, errors: impl IntoIterator<Item = MurFieldError>) -> Self {
+		let errors: Vec<MurFieldError> = errors.into_iter().collect();
+		self.extension("errors", errors)
+	}
+
+	/// The problem as a JSON object. Standard members take precedence over
+	/// extensions with the same name.
+	pub fn to_json(&self) -> Value {
+		let mut body = self.extensions.clone();
+		body.insert("type".to_string(), self.problem_type.clone().into());
+		body.insert("title".to_string(), self.title.clone().into());
+		body.insert("status".to_string(), self.status.as_u16().into());
+		if let Some(detail) = &self.detail {
+			body.insert("detail".to_string(), detail.clone().into());
+		}
+		if let Some(instance) = &self.instance {
+			body.insert("instance".to_string(), instance.clone().into());
+		}
+		Value::Object(body)
+	}
+
+	pub fn into_response(self) -> MurResponse {
+		Response::builder()
+			.status(self.status)
+			.header("Content-Type", Self::CONTENT_TYPE)
+			.body(Full::new(Bytes::from(self.to_json().to_string())))
+			.unwrap()
+	}
+}
+
+impl Serialize for MurProblem {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.to_json().serialize(serializer)
+	}
+}
+
+impl std::fmt::Display for MurProblem {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.detail {
+			Some(detail) => write!(f, "{}: {}", self.title, detail),
+			None => write!(f, "{}", self.title),
+		}
+	}
+}
+
+impl From<MurProblem> for MurError {
+	fn from(problem: MurProblem) -> Self {
+		MurError::Problem(Box::new(problem))
+	}
+}
+
+impl From<MurProblem> for MurRes {
+	fn from(problem: MurProblem) -> Self {
+		MurRes::from(MurError::from(problem))
+	}
+}
+
+/// A validation failure on a single field, reported in the `errors`
+/// extension of a problem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MurFieldError {
+	pub field: String,
+	pub detail: String,
+}
+
+impl MurFieldError {
+	pub fn new(field: impl Into<String>, detail: impl Into<String>) -> Self {
+		Self {
+			field: field.into(),
+			detail: detail.into(),
+		}
+	}
+}
@@ -6,6 +6,7 @@ use crate::server::http::MurRequestContext;
 use std::any::Any;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 
 /// Pinned boxed future returned by [`MurGuard::check_can_activate`].
 pub type MurGuardFuture<'a> = Pin<Box<dyn Future<Output = bool> + Send + 'a>>;
@@ -17,7 +18,7 @@ pub type MurGuardFuture<'a> = Pin<Box<dyn Future<Output = bool> + Send + 'a>>;
 /// the request is rejected with [`rejection_response`](MurGuard::rejection_response).
 ///
 /// Guards can be registered globally on the server (applying to every route) or
-/// per-route via the `#[guard]` attribute. DI-enabled guards should implement
+/// on controllers and handlers with `#[use_guards]`. DI-enabled guards should implement
 /// [`MurGuardFactory`] as well.
 ///
 /// # Example
@@ -73,6 +74,18 @@ pub trait MurGuardFactory: MurGuard {
 	fn __create_factory(injects: &MurInjects, _container: &MurServiceContainer) -> Self;
 }
 
+/// Builds a route guard from the DI scope of the module that owns the route.
+///
+/// `#[use_guards(..)]` stores one provider per listed guard on each route
+/// definition; they are resolved when the module's controllers are registered.
+pub type MurGuardProvider =
+	fn(&MurInjects, &MurServiceContainer) -> Arc<dyn MurGuard + Send + Sync>;
+
+/// Returns the [`MurGuardProvider`] that builds `T` through its factory.
+pub fn mur_guard_provider<T: MurGuardFactory>() -> MurGuardProvider {
+	|injects, container| Arc::new(T::__create_factory(injects, container))
+}
+
 /// Synchronous variant of [`MurGuard`] for guards that do not need async I/O.
 pub trait MurGuardSync: Send + Sync + 'static {
 	/// Synchronously determines whether the request is allowed to proceed.
@@ -3,4 +3,6 @@ mod contract;
 pub use contract::MurGuard;
 pub use contract::MurGuardFactory;
 pub use contract::MurGuardFuture;
+pub use contract::MurGuardProvider;
 pub use contract::MurGuardSync;
+pub use contract::mur_guard_provider;
@@ -0,0 +1,196 @@
+use std::fs::{File, TryLockError};
+use std::future::Future;
+use std::io::Write;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+/// Zero-downtime hand-off between two releases of the same server.
+///
+/// Both processes listen on the same sockets (`SO_REUSEPORT` or sockets
+/// inherited from systemd). Once the new process accepts connections it
+/// records its pid in the pid file and sends `SIGUSR2` to the pid recorded
+/// before; the old process takes that as its cue to stop accepting and
+/// drain in-flight requests.
+///
+/// Each process keeps its pid file locked while it runs, so a pid left by a
+/// process that died, and possibly reused since, is never signalled.
+pub(crate) struct MurHandoff {
+	pid_file: PathBuf,
+	lock: Mutex<Option<File>>,
+}
+
+impl MurHandoff {
+	pub(crate) fn new(pid_file: impl Into<PathBuf>) -> Self {
+		Self {
+			pid_file: pid_file.into(),
+			lock: Mutex::new(None),
+		}
+	}
+
+	/// Resolves when a successor announces it is ready. The signal handler
+	/// is installed before this returns, so a successor cannot race it.
+	pub(crate) fn successor_ready(
+		&self,
+	) -> Result<Pin<Box<dyn Future<Output = ()> + Send>>, std::io::Error> {
+		#[cfg(unix)]
+		{
+			use tokio::signal::unix::{SignalKind, signal};
+
+			let mut ready = signal(SignalKind::user_defined2())?;
+			Ok(Box::pin(async move {
+				ready.recv().await;
+			}))
+		}
+		#[cfg(not(unix))]
+		Ok(Box::pin(std::future::pending()))
+	}
+
+	/// Records this process in the pid file and tells the previous process,
+	/// if any is still running, to shut down. Returns the pid signalled.
+	pub(crate) fn announce(&self) -> Result<Option<u32>, std::io::Error> {
+		let own = std::process::id();
+		let previous = self
+			.recorded_pid()
+			.filter(|&pid| pid > 1 && pid != own && self.held_by_predecessor());
+
+		let mut tmp = self.pid_file.clone().into_os_string();
+		tmp.push(format!(".{}.tmp", own));
+		let mut file = File::create(&tmp)?;
+		file.try_lock().map_err(std::io::Error::from)?;
+		writeln!(file, "{}", own)?;
+		std::fs::rename(&tmp, &self.pid_file)?;
+		*self.lock.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+
+		match previous {
+			Some(pid) => notify_predecessor(pid),
+			None => Ok(None),
+		}
+	}
+
+	/// Removes the pid file unless a successor already took it over.
+	pub(crate) fn release(&self) {
+		if self.recorded_pid() == Some(std::process::id()) {
+			let _ = std::fs::remove_file(&self.pid_file);
+		}
+	}
+
+	fn recorded_pid(&self) -> Option<u32> {
+		std::fs::read_to_string(&self.pid_file)
+			.ok()
+			.and_then(|pid| pid.trim().parse().ok())
+	}
+
+	/// Whether a running process still holds the lock on the pid file.
+	fn held_by_predecessor(&self) -> bool {
+		let Ok(file) = File::open(&self.pid_file) else {
+			return false;
+		};
+		matches!(file.try_lock(), Err(TryLockError::WouldBlock))
+	}
+}
+
+#[cfg(unix)]
+fn notify_predecessor(pid: u32) -> Result<Option<u32>, std::io::Error> {
+	let Ok(target) = libc::pid_t::try_from(pid) else {
+		return Ok(None);
+	};
+	// SAFETY: kill only sends a signal; it has no memory-safety preconditions.
+	if unsafe { libc::kill(target, libc::SIGUSR2) } == 0 {
+		return Ok(Some(pid));
+	}
+	match std::io::Error::last_os_error() {
+		// A stale pid file left by a process that already exited.
+		e if e.raw_os_error() == Some(libc::ESRCH) => Ok(None),
+		e => Err(e),
+	}
+}
+
+#[cfg(not(unix))]
+fn notify_predecessor(_pid: u32) -> Result<Option<u32>, std::io::Error> {
+	Err(std::io::Error::new(
+		std::io::ErrorKind::Unsupported,
+		"Process hand-off is not supported on this platform",
+	))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::*;
+	use std::os::unix::process::ExitStatusExt;
+
+	fn pid_file() -> PathBuf {
+		std::env::temp_dir().join(format!("mur-handoff-{}.pid", uuid::Uuid::new_v4()))
+	}
+
+	/// Replaces the pid file the way another process would, with a new file.
+	fn record(path: &std::path::Path, pid: &str) {
+		let _ = std::fs::remove_file(path);
+		std::fs::write(path, pid).unwrap();
+	}
+
+	#[test]
+	fn test_announce_signals_previous_process() {
+		let path = pid_file();
+		let mut previous = std::process::Command::new("sleep")
+			.arg("30")
+			.spawn()
+			.unwrap();
+		std::fs::write(&path, previous.id().to_string()).unwrap();
+		let predecessor_lock = File::open(&path).unwrap();
+		predecessor_lock.try_lock().unwrap();
+
+		let handoff = MurHandoff::new(&path);
+		assert_eq!(handoff.announce().unwrap(), Some(previous.id()));
+		assert_eq!(handoff.recorded_pid(), Some(std::process::id()));
+		let status = previous.wait().unwrap();
+		assert_eq!(status.signal(), Some(libc::SIGUSR2));
+
+		handoff.release();
+		assert!(!path.exists());
+	}
+
+	#[test]
+	fn test_announce_ignores_stale_or_missing_pid() {
+		let path = pid_file();
+		let handoff = MurHandoff::new(&path);
+		assert_eq!(handoff.announce().unwrap(), None);
+
+		let mut exited = std::process::Command::new("true").spawn().unwrap();
+		exited.wait().unwrap();
+		std::fs::write(&path, exited.id().to_string()).unwrap();
+		assert_eq!(handoff.announce().unwrap(), None);
+		assert_eq!(handoff.recorded_pid(), Some(std::process::id()));
+
+		std::fs::write(&path, "1\n").unwrap();
+		handoff.release();
+		assert!(path.exists());
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn test_announce_only_signals_a_locked_pid_above_one() {
+		let path = pid_file();
+		let handoff = MurHandoff::new(&path);
+
+		for pid in ["0", "1"] {
+			record(&path, pid);
+			let lock = File::open(&path).unwrap();
+			lock.try_lock().unwrap();
+			assert_eq!(handoff.announce().unwrap(), None);
+		}
+
+		let mut unrelated = std::process::Command::new("sleep")
+			.arg("30")
+			.spawn()
+			.unwrap();
+		record(&path, &unrelated.id().to_string());
+		assert_eq!(handoff.announce().unwrap(), None);
+		assert_eq!(unrelated.try_wait().unwrap(), None);
+		unrelated.kill().unwrap();
+		unrelated.wait().unwrap();
+
+		handoff.release();
+		assert!(!path.exists());
+	}
+}
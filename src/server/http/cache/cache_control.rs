@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+/// Typed builder for the `Cache-Control` response header.
+///
+/// ```rust,ignore
+/// MurHttpResponse::ok()
+///     .cache_control(MurCacheControl::new().public().max_age(Duration::from_secs(60)))
+///     .json(&users)
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MurCacheControl {
+	pub public: bool,
+	pub private: bool,
+	pub no_cache: bool,
+	pub no_store: bool,
+	pub no_transform: bool,
+	pub must_revalidate: bool,
+	pub immutable: bool,
+	pub max_age: Option<u64>,
+	pub s_maxage: Option<u64>,
+	pub stale_while_revalidate: Option<u64>,
+	pub stale_if_error: Option<u64>,
+}
+
+impl MurCacheControl {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// `no-store` — the response must not be stored by any cache.
+	pub fn never() -> Self {
+		Self::new().no_store()
+	}
+
+	/// `no-cache` — caches must revalidate before reusing the response.
+	pub fn revalidate() -> Self {
+		Self::new().no_cache()
+	}
+
+	pub fn public(mut self) -> Self {
+		self.public = true;
+		self.private = false;
+		self
+	}
+
+	pub fn private(mut self) -> Self {
+		self.private = true;
+		self.public = false;
+		self
+	}
+
+	pub fn no_cache(mut self) -> Self {
+		self.no_cache = true;
+		self
+	}
+
+	pub fn no_store(mut self) -> Self {
+		self.no_store = true;
+		self
+	}
+
+	pub fn no_transform(mut self) -> Self {
+		self.no_transform = true;
+		self
+	}
+
+	pub fn must_revalidate(mut self) -> Self {
+		self.must_revalidate = true;
+		self
+	}
+
+	pub fn immutable(mut self) -> Self {
+		self.immutable = true;
+		self
+	}
+
+	pub fn max_age(mut self, age: Duration) -> Self {
+		self.max_age = Some(age.as_secs());
+		self
+	}
+
+	pub fn s_maxage(mut self, age: Duration) -> Self {
+		self.s_maxage = Some(age.as_secs());
+		self
+	}
+
+	pub fn stale_while_revalidate(mut self, window: Duration) -> Self {
+		self.stale_while_revalidate = Some(window.as_secs());
+		self
+	}
+
+	pub fn stale_if_error(mut self, window: Duration) -> Self {
+		self.stale_if_error = Some(window.as_secs());
+		self
+	}
+
+	/// Returns `true` when a shared cache is allowed to store the response.
+	pub fn is_storable(&self) -> bool {
+		!self.no_store && !self.private
+	}
+
+	pub fn to_header_value(&self) -> String {
+		let mut parts: Vec<String> = Vec::new();
+		if self.public {
+			parts.push("public".to_string());
+		}
+		if self.private {
+			parts.push("private".to_string());
+		}
+		if self.no_cache {
+			parts.push("no-cache".to_string());
+		}
+		if self.no_store {
+			parts.push("no-store".to_string());
+		}
+		if self.no_transform {
+			parts.push("no-transform".to_string());
+		}
+		if self.must_revalidate {
+			parts.push("must-revalidate".to_string());
+		}
+		if self.immutable {
+			parts.push("immutable".to_string());
+		}
+		if let Some(age) = self.max_age {
+			parts.push(format!("max-age={}", age));
+		}
+		if let Some(age) = self.s_maxage {
+			parts.push(format!("s-maxage={}", age));
+		}
+		if let Some(window) = self.stale_while_revalidate {
+			parts.push(format!("stale-while-revalidate={}", window));
+		}
+		if let Some(window) = self.stale_if_error {
+			parts.push(format!("stale-if-error={}", window));
+		}
+		parts.join(", ")
+	}
+
+	/// Parses a `Cache-Control` header value. Unknown directives are ignored.
+	pub fn parse(value: &str) -> Self {
+		let mut control = Self::new();
+		for directive in value.split(',') {
+			let directive = directive.trim();
+			let (name, arg) = match directive.split_once('=') {
+				Some((n, a)) => (n.trim(), Some(a.trim().trim_matches('"'))),
+				None => (directive, None),
+			};
+			let seconds = arg.and_then(|a| a.parse::<u64>().ok());
+
+			match name.to_ascii_lowercase().as_str() {
+				"public" => control.public = true,
+				"private" => control.private = true,
+				"no-cache" => control.no_cache = true,
+				"no-store" => control.no_store = true,
+				"no-transform" => control.no_transform = true,
+				"must-revalidate" => control.must_revalidate = true,
+				"immutable" => control.immutable = true,
+				"max-age" => control.max_age = seconds,
+				"s-maxage" => control.s_maxage = seconds,
+				"stale-while-revalidate" => control.stale_while_revalidate = seconds,
+				"stale-if-error" => control.stale_if_error = seconds,
+				_ => {}
+			}
+		}
+		control
+	}
+}
+
+impl std::fmt::Display for MurCacheControl {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.to_header_value())
+	}
+}
@@ -0,0 +1,83 @@
+use super::MurEntityTag;
+use chrono::{DateTime, TimeZone, Utc};
+use http::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use http::{HeaderMap, Method, StatusCode};
+use http_body_util::Full;
+use hyper::Response;
+use hyper::body::Bytes;
+
+/// Headers a `304 Not Modified` must repeat from the full response (RFC 9110 §15.4.5).
+const NOT_MODIFIED_HEADERS: [&str; 7] = [
+	"cache-control",
+	"content-location",
+	"date",
+	"etag",
+	"expires",
+	"last-modified",
+	"vary",
+];
+
+/// Formats a timestamp as an IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub fn mur_format_http_date(date: DateTime<Utc>) -> String {
+	date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Parses an IMF-fixdate (or any RFC 2822 date) into UTC.
+pub fn mur_parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+	DateTime::parse_from_rfc2822(value.trim())
+		.ok()
+		.map(|d| d.with_timezone(&Utc))
+		.or_else(|| {
+			chrono::NaiveDateTime::parse_from_str(value.trim(), "%a, %d %b %Y %H:%M:%S GMT")
+				.ok()
+				.map(|d| Utc.from_utc_datetime(&d))
+		})
+}
+
+/// Evaluates `If-None-Match` / `If-Modified-Since` against a response's validators.
+///
+/// Only `GET` and `HEAD` are considered. When the request carries
+/// `If-None-Match`, `If-Modified-Since` is ignored as required by RFC 9110 §13.2.2.
+pub fn mur_is_not_modified(method: &Method, request: &HeaderMap, response: &HeaderMap) -> bool {
+	if method != Method::GET && method != Method::HEAD {
+		return false;
+	}
+
+	if let Some(if_none_match) = request.get(IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+		return response
+			.get(ETAG)
+			.and_then(|v| v.to_str().ok())
+			.and_then(MurEntityTag::parse)
+			.map(|etag| etag.matches_if_none_match(if_none_match))
+			.unwrap_or(false);
+	}
+
+	let since = request
+		.get(IF_MODIFIED_SINCE)
+		.and_then(|v| v.to_str().ok())
+		.and_then(mur_parse_http_date);
+	let last_modified = response
+		.get(LAST_MODIFIED)
+		.and_then(|v| v.to_str().ok())
+		.and_then(mur_parse_http_date);
+
+	match (since, last_modified) {
+		(Some(since), Some(last_modified)) => last_modified <= since,
+		_ => false,
+	}
+}
+
+/// Converts a full response into a bodiless `304 Not Modified`, keeping only
+/// the validator and caching headers.
+pub fn mur_not_modified(response: Response<Full<Bytes>>) -> Response<Full<Bytes>> {
+	let (parts, _) = response.into_parts();
+	let mut not_modified = Response::new(Full::new(Bytes::new()));
+	*not_modified.status_mut() = StatusCode::NOT_MODIFIED;
+
+	for name in NOT_MODIFIED_HEADERS {
+		for value in parts.headers.get_all(name) {
+			not_modified.headers_mut().append(name, value.clone());
+		}
+	}
+	not_modified
+}
@@ -0,0 +1,87 @@
+/// An HTTP entity tag as carried by the `ETag` and `If-None-Match` headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MurEntityTag {
+	pub tag: String,
+	pub weak: bool,
+}
+
+impl MurEntityTag {
+	pub fn strong(tag: impl Into<String>) -> Self {
+		Self {
+			tag: tag.into(),
+			weak: false,
+		}
+	}
+
+	pub fn weak(tag: impl Into<String>) -> Self {
+		Self {
+			tag: tag.into(),
+			weak: true,
+		}
+	}
+
+	/// Builds a weak validator from a buffered body.
+	///
+	/// The tag is the body length plus a 64-bit FNV-1a digest, which is stable
+	/// across processes so replicas behind a load balancer agree on it.
+	pub fn from_body(body: &[u8]) -> Self {
+		let mut hash: u64 = 0xcbf29ce484222325;
+		for byte in body {
+			hash ^= *byte as u64;
+			hash = hash.wrapping_mul(0x100000001b3);
+		}
+		Self::weak(format!("{:x}-{:016x}", body.len(), hash))
+	}
+
+	/// Parses a single entity tag such as `"abc"` or `W/"abc"`.
+	pub fn parse(value: &str) -> Option<Self> {
+		let value = value.trim();
+		let (weak, rest) = match value.strip_prefix("W/") {
+			Some(rest) => (true, rest),
+			None => (false, value),
+		};
+		let tag = rest.strip_prefix('"')?.strip_suffix('"')?;
+		if tag.contains('"') {
+			return None;
+		}
+		Some(Self {
+			tag: tag.to_string(),
+			weak,
+		})
+	}
+
+	/// Weak comparison (RFC 9110 §8.8.3.2), used for `If-None-Match`.
+	pub fn weak_eq(&self, other: &Self) -> bool {
+		self.tag == other.tag
+	}
+
+	/// Strong comparison: both tags must be strong and identical.
+	pub fn strong_eq(&self, other: &Self) -> bool {
+		!self.weak && !other.weak && self.tag == other.tag
+	}
+
+	/// Returns `true` if an `If-None-Match` header value matches this tag.
+	pub fn matches_if_none_match(&self, header: &str) -> bool {
+		if header.trim() == "*" {
+			return true;
+		}
+		header
+			.split(',')
+			.filter_map(Self::parse)
+			.any(|candidate| candidate.weak_eq(self))
+	}
+
+	pub fn to_header_value(&self) -> String {
+		if self.weak {
+			format!("W/\"{}\"", self.tag)
+		} else {
+			format!("\"{}\"", self.tag)
+		}
+	}
+}
+
+impl std::fmt::Display for MurEntityTag {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.to_header_value())
+	}
+}
@@ -0,0 +1,13 @@
+pub mod cache_control;
+pub mod conditional;
+pub mod entity_tag;
+
+pub use cache_control::MurCacheControl;
+pub use conditional::mur_format_http_date;
+pub use conditional::mur_is_not_modified;
+pub use conditional::mur_not_modified;
+pub use conditional::mur_parse_http_date;
+pub use entity_tag::MurEntityTag;
+
+#[cfg(test)]
+mod test;
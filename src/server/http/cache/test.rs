@@ -0,0 +1,152 @@
+use super::*;
+use crate::server::http::MurResponseBuilder;
+use chrono::{TimeZone, Utc};
+use http::{HeaderMap, HeaderValue, Method};
+use std::time::Duration;
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+	let mut map = HeaderMap::new();
+	for (name, value) in pairs {
+		map.insert(*name, HeaderValue::from_str(value).unwrap());
+	}
+	map
+}
+
+#[test]
+fn test_cache_control_header_value() {
+	let control = MurCacheControl::new()
+		.public()
+		.max_age(Duration::from_secs(60))
+		.stale_while_revalidate(Duration::from_secs(30));
+	assert_eq!(
+		control.to_header_value(),
+		"public, max-age=60, stale-while-revalidate=30"
+	);
+	assert_eq!(MurCacheControl::never().to_header_value(), "no-store");
+}
+
+#[test]
+fn test_cache_control_parse() {
+	let control = MurCacheControl::parse("private, max-age=0, must-revalidate");
+	assert!(control.private);
+	assert!(control.must_revalidate);
+	assert_eq!(control.max_age, Some(0));
+	assert!(!control.is_storable());
+
+	let control = MurCacheControl::parse("public, s-maxage=120");
+	assert!(control.is_storable());
+	assert_eq!(control.s_maxage, Some(120));
+}
+
+#[test]
+fn test_entity_tag_parse_and_format() {
+	let strong = MurEntityTag::parse("\"abc\"").unwrap();
+	assert!(!strong.weak);
+	assert_eq!(strong.to_header_value(), "\"abc\"");
+
+	let weak = MurEntityTag::parse("W/\"abc\"").unwrap();
+	assert!(weak.weak);
+	assert!(weak.weak_eq(&strong));
+	assert!(!weak.strong_eq(&strong));
+
+	assert!(MurEntityTag::parse("abc").is_none());
+}
+
+#[test]
+fn test_entity_tag_from_body_is_stable() {
+	let a = MurEntityTag::from_body(b"hello");
+	let b = MurEntityTag::from_body(b"hello");
+	let c = MurEntityTag::from_body(b"hello!");
+	assert!(a.weak);
+	assert_eq!(a, b);
+	assert_ne!(a, c);
+}
+
+#[test]
+fn test_if_none_match_lists_and_wildcard() {
+	let etag = MurEntityTag::weak("v2");
+	assert!(etag.matches_if_none_match("\"v1\", W/\"v2\""));
+	assert!(etag.matches_if_none_match("*"));
+	assert!(!etag.matches_if_none_match("\"v1\""));
+}
+
+#[test]
+fn test_http_date_round_trip() {
+	let date = Utc.with_ymd_and_hms(1994, 11, 6, 8, 49, 37).unwrap();
+	let formatted = mur_format_http_date(date);
+	assert_eq!(formatted, "Sun, 06 Nov 1994 08:49:37 GMT");
+	assert_eq!(mur_parse_http_date(&formatted), Some(date));
+}
+
+#[test]
+fn test_not_modified_by_etag() {
+	let response = headers(&[("etag", "W/\"v1\"")]);
+	let request = headers(&[("if-none-match", "W/\"v1\"")]);
+	assert!(mur_is_not_modified(&Method::GET, &request, &response));
+	assert!(!mur_is_not_modified(&Method::POST, &request, &response));
+
+	let request = headers(&[("if-none-match", "\"v0\"")]);
+	assert!(!mur_is_not_modified(&Method::GET, &request, &response));
+}
+
+#[test]
+fn test_not_modified_by_date() {
+	let response = headers(&[("last-modified", "Sun, 06 Nov 1994 08:49:37 GMT")]);
+	let request = headers(&[("if-modified-since", "Sun, 06 Nov 1994 08:49:37 GMT")]);
+	assert!(mur_is_not_modified(&Method::GET, &request, &response));
+
+	let request = headers(&[("if-modified-since", "Sat, 05 Nov 1994 08:49:37 GMT")]);
+	assert!(!mur_is_not_modified(&Method::GET, &request, &response));
+}
+
+#[test]
+fn test_if_none_match_takes_precedence_over_date() {
+	let response = headers(&[
+		("etag", "\"v2\""),
+		("last-modified", "Sun, 06 Nov 1994 08:49:37 GMT"),
+	]);
+	let request = headers(&[
+		("if-none-match", "\"v1\""),
+		("if-modified-since", "Sun, 06 Nov 1994 08:49:37 GMT"),
+	]);
+	assert!(!mur_is_not_modified(&Method::GET, &request, &response));
+}
+
+#[test]
+fn test_not_modified_response_keeps_validators_only() {
+	let response = MurResponseBuilder::new()
+		.etag(MurEntityTag::strong("v1"))
+		.max_age(Duration::from_secs(10))
+		.json(serde_json::json!({ "a": 1 }))
+		.unwrap();
+
+	let not_modified = mur_not_modified(response);
+	assert_eq!(not_modified.status(), http::StatusCode::NOT_MODIFIED);
+	assert_eq!(not_modified.headers().get("etag").unwrap(), "\"v1\"");
+	assert_eq!(
+		not_modified.headers().get("cache-control").unwrap(),
+		"public, max-age=10"
+	);
+	assert!(not_modified.headers().get("content-type").is_none());
+}
+
+#[test]
+fn test_builder_cache_helpers() {
+	let response = MurResponseBuilder::new()
+		.no_store()
+		.vary("Accept")
+		.vary("Accept-Language")
+		.last_modified(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap())
+		.empty()
+		.unwrap();
+
+	assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+	assert_eq!(
+		response.headers().get("vary").unwrap(),
+		"Accept, Accept-Language"
+	);
+	assert_eq!(
+		response.headers().get("last-modified").unwrap(),
+		"Tue, 02 Jan 2024 03:04:05 GMT"
+	);
+}
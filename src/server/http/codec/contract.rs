@@ -0,0 +1,42 @@
+use crate::server::error::MurError;
+use serde_json::Value;
+
+/// A body format beyond the built-in ones, such as YAML or a vendor format.
+///
+/// Codecs work on [`serde_json::Value`]: request bodies are decoded to a
+/// value before being deserialized into the handler's type, and negotiated
+/// responses are serialized to a value before being encoded.
+///
+/// ```rust,ignore
+/// struct YamlCodec;
+///
+/// impl MurBodyCodec for YamlCodec {
+///     fn media_type(&self) -> &str {
+///         "application/yaml"
+///     }
+///
+///     fn decode(&self, body: &[u8]) -> Result<Value, MurError> { /* … */ }
+///
+///     fn encode(&self, value: &Value) -> Result<Vec<u8>, MurError> { /* … */ }
+/// }
+///
+/// MurServer::new().body_codec(YamlCodec)
+/// ```
+pub trait MurBodyCodec: Send + Sync + 'static {
+	/// The media type written as `Content-Type` on encoded responses.
+	fn media_type(&self) -> &str;
+
+	/// Whether the codec reads and writes `media_type`, given in lowercase
+	/// and without parameters. Defaults to [`media_type`](Self::media_type)
+	/// only.
+	fn handles(&self, media_type: &str) -> bool {
+		media_type.eq_ignore_ascii_case(self.media_type())
+	}
+
+	/// Decodes a request body. Malformed bodies should fail with
+	/// [`MurError::BadRequest`].
+	fn decode(&self, body: &[u8]) -> Result<Value, MurError>;
+
+	/// Encodes a response body.
+	fn encode(&self, value: &Value) -> Result<Vec<u8>, MurError>;
+}
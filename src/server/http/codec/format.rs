@@ -0,0 +1,139 @@
+use super::MurBodyCodec;
+use crate::server::error::MurError;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// A body format a [`MurBodyCodecs`](super::MurBodyCodecs) registry reads
+/// and writes.
+///
+/// Built-in formats deserialize straight into the target type; custom
+/// codecs go through [`serde_json::Value`].
+#[derive(Clone)]
+pub enum MurBodyFormat {
+	/// `application/json` and `+json` media types.
+	Json,
+	/// `application/msgpack`, `application/x-msgpack` and `+msgpack` media
+	/// types. Structs are encoded as maps.
+	#[cfg(feature = "msgpack")]
+	MessagePack,
+	/// `application/cbor` and `+cbor` media types.
+	#[cfg(feature = "cbor")]
+	Cbor,
+	/// `application/xml`, `text/xml` and `+xml` media types. Values without
+	/// a name of their own, such as maps, are wrapped in `<response>`.
+	#[cfg(feature = "xml")]
+	Xml,
+	Custom(Arc<dyn MurBodyCodec>),
+}
+
+impl std::fmt::Debug for MurBodyFormat {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Custom(codec) => f.debug_tuple("Custom").field(&codec.media_type()).finish(),
+			format => f.write_str(format.name()),
+		}
+	}
+}
+
+impl MurBodyFormat {
+	/// The media type written as `Content-Type` on encoded responses.
+	pub fn media_type(&self) -> &str {
+		match self {
+			Self::Json => "application/json",
+			#[cfg(feature = "msgpack")]
+			Self::MessagePack => "application/msgpack",
+			#[cfg(feature = "cbor")]
+			Self::Cbor => "application/cbor",
+			#[cfg(feature = "xml")]
+			Self::Xml => "application/xml",
+			Self::Custom(codec) => codec.media_type(),
+		}
+	}
+
+	/// Whether the format reads and writes `media_type`, given in lowercase
+	/// and without parameters.
+	pub fn handles(&self, media_type: &str) -> bool {
+		match self {
+			Self::Json => media_type == "application/json" || media_type.ends_with("+json"),
+			#[cfg(feature = "msgpack")]
+			Self::MessagePack => {
+				matches!(
+					media_type,
+					"application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack"
+				) || media_type.ends_with("+msgpack")
+			}
+			#[cfg(feature = "cbor")]
+			Self::Cbor => media_type == "application/cbor" || media_type.ends_with("+cbor"),
+			#[cfg(feature = "xml")]
+			Self::Xml => {
+				matches!(media_type, "application/xml" | "text/xml") || media_type.ends_with("+xml")
+			}
+			Self::Custom(codec) => codec.handles(media_type),
+		}
+	}
+
+	/// Deserializes a body in this format.
+	///
+	/// Returns [`MurError::BadRequest`] when the body is malformed or does
+	/// not fit `T`.
+	pub fn decode<T: DeserializeOwned>(&self, body: &[u8]) -> Result<T, MurError> {
+		let invalid =
+			|e: &dyn Display| MurError::BadRequest(format!("Invalid {}: {}", self.name(), e));
+
+		match self {
+			Self::Json => serde_json::from_slice(body).map_err(|e| invalid(&e)),
+			#[cfg(feature = "msgpack")]
+			Self::MessagePack => rmp_serde::from_slice(body).map_err(|e| invalid(&e)),
+			#[cfg(feature = "cbor")]
+			Self::Cbor => ciborium::from_reader(body).map_err(|e| invalid(&e)),
+			#[cfg(feature = "xml")]
+			Self::Xml => quick_xml::de::from_reader(body).map_err(|e| invalid(&e)),
+			Self::Custom(codec) => {
+				serde_json::from_value(codec.decode(body)?).map_err(|e| invalid(&e))
+			}
+		}
+	}
+
+	/// Serializes `value` in this format.
+	pub fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, MurError> {
+		let failed = |e: &dyn Display| {
+			MurError::Internal(format!("Failed to encode {}: {}", self.name(), e))
+		};
+
+		match self {
+			Self::Json => serde_json::to_vec(value).map_err(|e| failed(&e)),
+			#[cfg(feature = "msgpack")]
+			Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| failed(&e)),
+			#[cfg(feature = "cbor")]
+			Self::Cbor => {
+				let mut encoded = Vec::new();
+				ciborium::into_writer(value, &mut encoded).map_err(|e| failed(&e))?;
+				Ok(encoded)
+			}
+			#[cfg(feature = "xml")]
+			Self::Xml => quick_xml::se::to_string(value)
+				.or_else(|_| quick_xml::se::to_string_with_root("response", value))
+				.map(String::into_bytes)
+				.map_err(|e| failed(&e)),
+			Self::Custom(codec) => {
+				let value = serde_json::to_value(value).map_err(|e| failed(&e))?;
+				codec.encode(&value)
+			}
+		}
+	}
+
+	fn name(&self) -> &str {
+		match self {
+			Self::Json => "JSON",
+			#[cfg(feature = "msgpack")]
+			Self::MessagePack => "MessagePack",
+			#[cfg(feature = "cbor")]
+			Self::Cbor => "CBOR",
+			#[cfg(feature = "xml")]
+			Self::Xml => "XML",
+			Self::Custom(codec) => codec.media_type(),
+		}
+	}
+}
@@ -0,0 +1,70 @@
+use super::MurBodyFormat;
+
+/// One media range of an `Accept` header, such as `application/json;q=0.8`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MurMediaRange {
+	/// The media type in lowercase, without parameters. May be `*/*` or
+	/// `type/*`.
+	pub media_type: String,
+	/// The `q` parameter, `1.0` when absent.
+	pub quality: f32,
+}
+
+impl MurMediaRange {
+	/// Parses every range of an `Accept` header, skipping those that are not
+	/// `type/subtype`.
+	pub fn parse_all(accept: &str) -> Vec<Self> {
+		accept
+			.split(',')
+			.filter_map(|range| {
+				let mut params = range.split(';');
+				let media_type = mur_media_essence(params.next()?);
+				if !media_type.contains('/') {
+					return None;
+				}
+				let quality = params
+					.filter_map(|param| param.split_once('='))
+					.find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+					.and_then(|(_, value)| value.trim().parse::<f32>().ok())
+					.map_or(1.0, |q| q.clamp(0.0, 1.0));
+				Some(Self {
+					media_type,
+					quality,
+				})
+			})
+			.collect()
+	}
+
+	/// How specifically the range names `format`: `2` for a media type the
+	/// format handles, `1` for its `type/*` and `0` for `*/*`.
+	pub fn specificity(&self, format: &MurBodyFormat) -> Option<u8> {
+		if self.media_type == "*/*" {
+			return Some(0);
+		}
+		if let Some(kind) = self.media_type.strip_suffix("/*") {
+			let format_kind = format.media_type().split('/').next().unwrap_or_default();
+			return format_kind.eq_ignore_ascii_case(kind).then_some(1);
+		}
+		format.handles(&self.media_type).then_some(2)
+	}
+
+	/// The quality `ranges` give `format`: that of the most specific range
+	/// naming it, or `None` when no range does.
+	pub fn quality_of(ranges: &[Self], format: &MurBodyFormat) -> Option<f32> {
+		ranges
+			.iter()
+			.filter_map(|range| Some((range.specificity(format)?, range.quality)))
+			.max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+			.map(|(_, quality)| quality)
+	}
+}
+
+/// A `Content-Type` or media range without its parameters, in lowercase.
+pub(crate) fn mur_media_essence(value: &str) -> String {
+	value
+		.split(';')
+		.next()
+		.unwrap_or_default()
+		.trim()
+		.to_ascii_lowercase()
+}
@@ -0,0 +1,16 @@
+mod contract;
+mod format;
+mod media_range;
+mod negotiated;
+mod registry;
+
+pub use contract::MurBodyCodec;
+pub use format::MurBodyFormat;
+pub use media_range::MurMediaRange;
+pub(crate) use negotiated::MurNegotiated;
+pub(crate) use negotiated::mur_encode_negotiated;
+pub use registry::MurBodyCodecs;
+pub(crate) use registry::mur_default_body_codecs;
+
+#[cfg(test)]
+mod test;
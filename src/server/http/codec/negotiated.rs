@@ -0,0 +1,52 @@
+use super::MurBodyFormat;
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::http::response::mur_res::RawRes;
+use http::HeaderValue;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE, VARY};
+use http_body_util::Full;
+use hyper::Response;
+use hyper::body::Bytes;
+use serde::Serialize;
+use std::sync::Arc;
+
+type MurEncoder = Arc<dyn Fn(&MurBodyFormat) -> Result<Vec<u8>, MurError> + Send + Sync>;
+
+/// Response extension left by [`MurRes::negotiated`](crate::MurRes::negotiated):
+/// the value to encode once the router knows the request's `Accept` header.
+#[derive(Clone)]
+pub(crate) struct MurNegotiated(MurEncoder);
+
+impl MurNegotiated {
+	pub fn new<T: Serialize + Send + Sync + 'static>(value: T) -> Self {
+		Self(Arc::new(move |format| format.encode(&value)))
+	}
+}
+
+/// Writes a negotiated response in the format the request accepts. Other
+/// responses pass through unchanged.
+pub(crate) fn mur_encode_negotiated(
+	mut response: Response<Full<Bytes>>,
+	ctx: &MurRequestContext,
+) -> RawRes {
+	let Some(negotiated) = response.extensions_mut().remove::<MurNegotiated>() else {
+		return Ok(response);
+	};
+	response
+		.headers_mut()
+		.append(VARY, HeaderValue::from_static("Accept"));
+
+	let format = ctx.response_format()?;
+	if matches!(format, MurBodyFormat::Json) {
+		return Ok(response);
+	}
+
+	let encoded = (negotiated.0)(format)?;
+	let content_type = HeaderValue::from_str(format.media_type())
+		.map_err(|e| MurError::Internal(format!("Invalid media type: {}", e)))?;
+	let headers = response.headers_mut();
+	headers.insert(CONTENT_TYPE, content_type);
+	headers.remove(CONTENT_LENGTH);
+	*response.body_mut() = Full::new(Bytes::from(encoded));
+	Ok(response)
+}
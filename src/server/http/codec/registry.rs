@@ -0,0 +1,154 @@
+use super::media_range::mur_media_essence;
+use super::{MurBodyCodec, MurBodyFormat, MurMediaRange};
+use crate::server::error::MurError;
+use serde::de::DeserializeOwned;
+use std::sync::{Arc, LazyLock};
+
+static DEFAULT_BODY_CODECS: LazyLock<MurBodyCodecs> = LazyLock::new(MurBodyCodecs::default);
+
+/// The body formats a server reads and writes, in order of preference.
+///
+/// JSON is always registered first, followed by MessagePack, CBOR and XML
+/// when the `msgpack`, `cbor` and `xml` features are enabled. Request bodies
+/// are read in the format named by their `Content-Type`, and negotiated
+/// responses are written in the format the `Accept` header prefers.
+#[derive(Debug, Clone)]
+pub struct MurBodyCodecs {
+	formats: Vec<MurBodyFormat>,
+}
+
+impl Default for MurBodyCodecs {
+	fn default() -> Self {
+		Self {
+			formats: vec![
+				MurBodyFormat::Json,
+				#[cfg(feature = "msgpack")]
+				MurBodyFormat::MessagePack,
+				#[cfg(feature = "cbor")]
+				MurBodyFormat::Cbor,
+				#[cfg(feature = "xml")]
+				MurBodyFormat::Xml,
+			],
+		}
+	}
+}
+
+impl MurBodyCodecs {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers `codec`. It takes the place of a format with the same media
+	/// type, and comes last otherwise.
+	pub fn with(mut self, codec: impl MurBodyCodec) -> Self {
+		self.register(Arc::new(codec));
+		self
+	}
+
+	pub fn register(&mut self, codec: Arc<dyn MurBodyCodec>) {
+		let existing = self
+			.formats
+			.iter()
+			.position(|format| format.media_type().eq_ignore_ascii_case(codec.media_type()));
+		match existing {
+			Some(index) => self.formats[index] = MurBodyFormat::Custom(codec),
+			None => self.formats.push(MurBodyFormat::Custom(codec)),
+		}
+	}
+
+	pub fn formats(&self) -> &[MurBodyFormat] {
+		&self.formats
+	}
+
+	/// The format of a body sent with `content_type`. Bodies without one are
+	/// read in the first format.
+	///
+	/// Returns a `415 Unsupported Media Type` error when no format handles
+	/// the content type.
+	pub fn for_content_type(&self, content_type: Option<&str>) -> Result<&MurBodyFormat, MurError> {
+		let unsupported = |media_type: &str| {
+			MurError::unsupported_media_type(format!("Unsupported content type: {}", media_type))
+		};
+
+		let Some(content_type) = content_type.filter(|ct| !ct.trim().is_empty()) else {
+			return self.formats.first().ok_or_else(|| unsupported("none"));
+		};
+		let media_type = mur_media_essence(content_type);
+		self.formats
+			.iter()
+			.find(|format| format.handles(&media_type))
+			.ok_or_else(|| unsupported(&media_type))
+	}
+
+	/// The format to answer a request sending `accept` with: the one given
+	/// the highest quality, earlier formats winning ties. Requests without
+	/// an `Accept` header get the first format.
+	///
+	/// Returns a `406 Not Acceptable` error when the header rules out every
+	/// format.
+	pub fn negotiate(&self, accept: Option<&str>) -> Result<&MurBodyFormat, MurError> {
+		let not_acceptable = || {
+			let available: Vec<&str> = self.formats.iter().map(MurBodyFormat::media_type).collect();
+			MurError::not_acceptable(format!(
+				"None of the accepted media types is available. Available: {}",
+				available.join(", ")
+			))
+		};
+
+		let ranges = match accept.filter(|accept| !accept.trim().is_empty()) {
+			Some(accept) => MurMediaRange::parse_all(accept),
+			None => return self.formats.first().ok_or_else(not_acceptable),
+		};
+
+		let mut best: Option<(&MurBodyFormat, f32)> = None;
+		for format in &self.formats {
+			let Some(quality) = MurMediaRange::quality_of(&ranges, format) else {
+				continue;
+			};
+			if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+				best = Some((format, quality));
+			}
+		}
+		best.map(|(format, _)| format).ok_or_else(not_acceptable)
+	}
+
+	/// Whether a request sending `accept` takes `media_type`.
+	pub fn accepts(accept: Option<&str>, media_type: &str) -> bool {
+		let Some(accept) = accept.filter(|accept| !accept.trim().is_empty()) else {
+			return true;
+		};
+		let media_type = mur_media_essence(media_type);
+		let kind = media_type.split('/').next().unwrap_or_default();
+
+		MurMediaRange::parse_all(accept)
+			.iter()
+			.filter_map(|range| {
+				let specificity = if range.media_type == media_type {
+					2
+				} else if range.media_type.strip_suffix("/*") == Some(kind) {
+					1
+				} else if range.media_type == "*/*" {
+					0
+				} else {
+					return None;
+				};
+				Some((specificity, range.quality))
+			})
+			.max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+			.is_some_and(|(_, quality)| quality > 0.0)
+	}
+
+	/// Decodes a body sent with `content_type`.
+	pub fn decode<T: DeserializeOwned>(
+		&self,
+		content_type: Option<&str>,
+		body: &[u8],
+	) -> Result<T, MurError> {
+		self.for_content_type(content_type)?.decode(body)
+	}
+}
+
+/// The registry used when a request does not carry the server's own.
+pub(crate) fn mur_default_body_codecs() -> &'static MurBodyCodecs {
+	&DEFAULT_BODY_CODECS
+}
@@ -0,0 +1,167 @@
+use super::*;
+use crate::server::error::MurError;
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+use http::StatusCode;
+use serde_json::{Value, json};
+
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct User {
+	id: u32,
+	name: String,
+}
+
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+fn user() -> User {
+	User {
+		id: 7,
+		name: "Ada".to_string(),
+	}
+}
+
+struct TextCodec;
+
+impl MurBodyCodec for TextCodec {
+	fn media_type(&self) -> &str {
+		"text/plain"
+	}
+
+	fn decode(&self, body: &[u8]) -> Result<Value, MurError> {
+		Ok(Value::String(String::from_utf8_lossy(body).into_owned()))
+	}
+
+	fn encode(&self, value: &Value) -> Result<Vec<u8>, MurError> {
+		Ok(value.to_string().into_bytes())
+	}
+}
+
+#[test]
+fn test_media_range_parse() {
+	let ranges = MurMediaRange::parse_all("Application/JSON; charset=utf-8, text/*;q=0.5, bogus");
+
+	assert_eq!(ranges.len(), 2);
+	assert_eq!(ranges[0].media_type, "application/json");
+	assert_eq!(ranges[0].quality, 1.0);
+	assert_eq!(ranges[1].media_type, "text/*");
+	assert_eq!(ranges[1].quality, 0.5);
+}
+
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+#[test]
+fn test_negotiate_by_quality() {
+	let codecs = MurBodyCodecs::default();
+	let pick = |accept| {
+		codecs
+			.negotiate(Some(accept))
+			.map(|f| f.media_type().to_string())
+	};
+
+	assert_eq!(
+		codecs.negotiate(None).unwrap().media_type(),
+		"application/json"
+	);
+	assert_eq!(pick("*/*").unwrap(), "application/json");
+	assert_eq!(
+		pick("application/vnd.api+json").unwrap(),
+		"application/json"
+	);
+	assert_eq!(
+		pick("application/json;q=0.5, application/cbor").unwrap(),
+		"application/cbor"
+	);
+	assert_eq!(
+		pick("application/json;q=0, */*").unwrap(),
+		"application/msgpack"
+	);
+	assert_eq!(pick("text/xml").unwrap(), "application/xml");
+
+	let err = pick("text/html").unwrap_err();
+	assert_eq!(err.status_code(), StatusCode::NOT_ACCEPTABLE);
+}
+
+#[test]
+fn test_accepts_honours_quality() {
+	assert!(MurBodyCodecs::accepts(None, "application/json"));
+	assert!(MurBodyCodecs::accepts(
+		Some("application/*"),
+		"application/json"
+	));
+	assert!(!MurBodyCodecs::accepts(
+		Some("application/json;q=0, */*"),
+		"application/json"
+	));
+	assert!(!MurBodyCodecs::accepts(
+		Some("text/html"),
+		"application/json"
+	));
+}
+
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+#[test]
+fn test_for_content_type() {
+	let codecs = MurBodyCodecs::default();
+
+	assert!(matches!(
+		codecs.for_content_type(None),
+		Ok(MurBodyFormat::Json)
+	));
+	assert!(matches!(
+		codecs.for_content_type(Some("application/x-msgpack")),
+		Ok(MurBodyFormat::MessagePack)
+	));
+	let err = codecs.for_content_type(Some("text/csv")).unwrap_err();
+	assert_eq!(err.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+#[test]
+fn test_built_in_formats_round_trip() {
+	for format in MurBodyCodecs::default().formats() {
+		let encoded = format.encode(&user()).unwrap();
+		let decoded: User = format.decode(&encoded).unwrap();
+		assert_eq!(decoded, user(), "{:?}", format);
+	}
+
+	let xml = MurBodyFormat::Xml.encode(&user()).unwrap();
+	assert_eq!(
+		String::from_utf8(xml).unwrap(),
+		"<User><id>7</id><name>Ada</name></User>"
+	);
+	let err = MurBodyFormat::Cbor.decode::<User>(b"\xff").unwrap_err();
+	assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_custom_codecs() {
+	let codecs = MurBodyCodecs::default().with(TextCodec);
+	let format = codecs.for_content_type(Some("text/plain")).unwrap();
+
+	assert_eq!(format.encode(&json!("hi")).unwrap(), b"\"hi\"");
+	assert_eq!(format.decode::<String>(b"hi").unwrap(), "hi");
+	assert_eq!(
+		codecs.formats().len(),
+		MurBodyCodecs::default().formats().len() + 1
+	);
+
+	struct JsonLines;
+	impl MurBodyCodec for JsonLines {
+		fn media_type(&self) -> &str {
+			"application/json"
+		}
+		fn decode(&self, _: &[u8]) -> Result<Value, MurError> {
+			Ok(Value::Null)
+		}
+		fn encode(&self, _: &Value) -> Result<Vec<u8>, MurError> {
+			Ok(Vec::new())
+		}
+	}
+	let codecs = MurBodyCodecs::default().with(JsonLines);
+	assert!(matches!(
+		codecs.negotiate(None),
+		Ok(MurBodyFormat::Custom(_))
+	));
+	assert_eq!(
+		codecs.formats().len(),
+		MurBodyCodecs::default().formats().len()
+	);
+}
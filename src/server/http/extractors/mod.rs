@@ -4,6 +4,7 @@ mod mur_header;
 mod mur_json;
 mod mur_param;
 mod mur_path;
+mod mur_payload;
 mod mur_query;
 mod mur_query_param;
 mod mur_text;
@@ -18,6 +19,7 @@ pub use mur_header::MurHeader;
 pub use mur_json::MurJson;
 pub use mur_param::Param;
 pub use mur_path::MurPath;
+pub use mur_payload::MurPayload;
 pub use mur_query::MurQuery;
 pub use mur_query_param::MurQueryParam;
 pub use mur_text::MurText;
@@ -0,0 +1,58 @@
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use serde::de::DeserializeOwned;
+use std::ops::Deref;
+
+/// A typed wrapper for request bodies in any registered format.
+///
+/// `MurPayload<T>` deserializes the request body into `T` in the format
+/// named by its `Content-Type`: JSON, MessagePack, CBOR, XML or a codec
+/// registered with `MurServer::body_codec`. Bodies without a content type
+/// are read as JSON; content types no format handles are answered with
+/// `415 Unsupported Media Type`.
+///
+/// # Usage in route handlers
+///
+/// ```rust,ignore
+/// #[post("/users")]
+/// async fn create_user(&self, body: MurPayload<CreateUserDto>) -> MurRes {
+///     MurRes::negotiated(self.users.create(body.into_inner()))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MurPayload<T>(pub T);
+
+impl<T> MurPayload<T> {
+	/// Wraps `value` in a `MurPayload` extractor.
+	pub fn new(value: T) -> Self {
+		Self(value)
+	}
+
+	/// Consumes the wrapper and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T> Deref for MurPayload<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T: DeserializeOwned> MurPayload<T> {
+	/// Extracts and deserializes the body from the request context.
+	///
+	/// See [`MurRequestContext::body_as`] for the errors returned.
+	pub fn extract(ctx: &MurRequestContext) -> Result<Self, MurError> {
+		ctx.body_as().map(MurPayload)
+	}
+}
+
+impl<T> AsRef<T> for MurPayload<T> {
+	fn as_ref(&self) -> &T {
+		&self.0
+	}
+}
@@ -1,3 +1,5 @@
+pub mod cache;
+pub mod codec;
 pub mod extractors;
 mod methods;
 pub mod multipart;
@@ -11,6 +13,7 @@ pub use extractors::MurExtractorSync;
 pub use extractors::MurHeader;
 pub use extractors::MurJson;
 pub use extractors::MurPath;
+pub use extractors::MurPayload;
 pub use extractors::MurQuery;
 pub use extractors::MurQueryParam;
 pub use extractors::MurText;
@@ -1,8 +1,21 @@
 use crate::core::utils::MurCodec;
 use crate::server::error::MurError;
+use crate::server::http::codec::{MurBodyCodecs, MurBodyFormat, mur_default_body_codecs};
+use crate::server::listener::MurListener;
+use crate::server::middleware::csrf::MurCsrfToken;
+use crate::server::middleware::request_id::mur_request_id::MurRequestIdExtension;
+use crate::server::middleware::timeout::MurDeadline;
+use crate::server::router::MurApiVersion;
+use crate::server::router::MurHostParams;
 use crate::server::router::MurRouteAccessControl;
+use crate::server::router::MurRouteMetadata;
+use crate::server::security::api_key::MurApiKeyIdentity;
+use crate::server::security::cookie::{MurCookieJar, MurCookieKeys};
+use crate::server::security::tls::MurClientCert;
 use crate::server::service::MurService;
 use crate::server::service::MurServiceContainer;
+use crate::server::session::MurSession;
+use crate::server::shutdown::MurShutdownToken;
 use http::request::Parts;
 use hyper::body::Bytes;
 use serde::de::DeserializeOwned;
@@ -157,6 +170,63 @@ impl MurRequestContext {
 		self.cookie(name).is_some()
 	}
 
+	/// The server's cookie keys, when set with `MurServer::cookie_keys`.
+	pub fn cookie_keys(&self) -> Option<&MurCookieKeys> {
+		self.parts.extensions.get::<MurCookieKeys>()
+	}
+
+	/// The value of a signed cookie whose signature checks out.
+	pub fn signed_cookie(&self, name: &str) -> Option<String> {
+		self.cookie_keys()?.verify(name, self.cookie(name)?)
+	}
+
+	/// The decrypted value of an encrypted cookie.
+	pub fn encrypted_cookie(&self, name: &str) -> Option<String> {
+		self.cookie_keys()?.decrypt(name, self.cookie(name)?)
+	}
+
+	/// The request's cookies, to read and to collect response cookies in.
+	pub fn cookie_jar(&self) -> MurCookieJar {
+		MurCookieJar::from_ctx(self)
+	}
+
+	/// The CSRF token set by the `MurCsrf` middleware, if installed.
+	pub fn csrf_token(&self) -> Option<&MurCsrfToken> {
+		self.parts.extensions.get::<MurCsrfToken>()
+	}
+
+	/// The API key the request was authenticated with by `MurApiKeyAuth`.
+	pub fn api_key(&self) -> Option<&MurApiKeyIdentity> {
+		self.parts.extensions.get::<MurApiKeyIdentity>()
+	}
+
+	/// The verified certificate the client presented over mutual TLS.
+	pub fn client_cert(&self) -> Option<&MurClientCert> {
+		self.parts
+			.extensions
+			.get::<Arc<MurClientCert>>()
+			.map(Arc::as_ref)
+	}
+
+	/// The listener the request arrived on.
+	pub fn listener(&self) -> Option<&MurListener> {
+		self.parts
+			.extensions
+			.get::<Arc<MurListener>>()
+			.map(Arc::as_ref)
+	}
+
+	/// The token cancelled when the server shuts down.
+	pub fn shutdown_token(&self) -> Option<MurShutdownToken> {
+		self.service::<MurShutdownToken>()
+			.map(|token| token.as_ref().clone())
+	}
+
+	/// The session installed by `MurSessionModule`, if registered.
+	pub fn session(&self) -> Option<MurSession> {
+		self.parts.extensions.get::<MurSession>().cloned()
+	}
+
 	pub fn content_type(&self) -> Option<&str> {
 		self.header("Content-Type")
 	}
@@ -209,10 +279,31 @@ impl MurRequestContext {
 		self.header("Accept")
 	}
 
+	/// Whether the `Accept` header takes `media_type`, honouring q-values:
+	/// `application/json;q=0` rules JSON out. Requests without the header
+	/// accept anything.
+	pub fn accepts(&self, media_type: &str) -> bool {
+		MurBodyCodecs::accepts(self.accept(), media_type)
+	}
+
 	pub fn accepts_json(&self) -> bool {
-		self.accept()
-			.map(|a| a.contains("application/json") || a.contains("*/*"))
-			.unwrap_or(true)
+		self.accepts("application/json")
+	}
+
+	/// The server's body formats, or the built-in ones outside a server.
+	pub fn body_codecs(&self) -> &MurBodyCodecs {
+		self.parts
+			.extensions
+			.get::<Arc<MurBodyCodecs>>()
+			.map_or_else(|| mur_default_body_codecs(), |codecs| codecs.as_ref())
+	}
+
+	/// The format negotiated responses are written in, chosen by the
+	/// `Accept` header.
+	///
+	/// Returns a `406 Not Acceptable` error when no format is accepted.
+	pub fn response_format(&self) -> Result<&MurBodyFormat, MurError> {
+		self.body_codecs().negotiate(self.accept())
 	}
 
 	pub fn method(&self) -> &http::Method {
@@ -253,6 +344,22 @@ impl MurRequestContext {
 			.map_err(|e| MurError::BadRequest(format!("Invalid JSON: {}", e)))
 	}
 
+	/// Deserializes the body in the format named by `Content-Type`, JSON
+	/// when the header is absent.
+	///
+	/// Returns a `415 Unsupported Media Type` error for content types no
+	/// format handles, and [`MurError::BadRequest`] when the body is missing
+	/// or malformed.
+	pub fn body_as<T: DeserializeOwned>(&self) -> Result<T, MurError> {
+		let format = self.body_codecs().for_content_type(self.content_type())?;
+		let body = self
+			.body
+			.as_ref()
+			.ok_or_else(|| MurError::BadRequest("Missing request body".to_string()))?;
+
+		format.decode(body)
+	}
+
 	pub fn body_bytes(&self) -> Option<&Bytes> {
 		self.body.as_ref()
 	}
@@ -292,6 +399,29 @@ impl MurRequestContext {
 		self.header("Host")
 	}
 
+	/// A parameter captured from the `Host` header by the matched
+	/// controller's host pattern, e.g. `tenant` in `:tenant.example.com`.
+	pub fn host_param(&self, name: &str) -> Option<&str> {
+		self.host_params()?.get(name).map(|s| s.as_str())
+	}
+
+	/// All parameters captured by the matched controller's host pattern.
+	pub fn host_params(&self) -> Option<&HashMap<String, String>> {
+		self.parts
+			.extensions
+			.get::<MurHostParams>()
+			.map(|params| &params.0)
+	}
+
+	/// The API version served to the request, when versioning is enabled
+	/// and the request asked for one or a default version is set.
+	pub fn api_version(&self) -> Option<&str> {
+		self.parts
+			.extensions
+			.get::<MurApiVersion>()
+			.map(|version| version.0.as_str())
+	}
+
 	pub fn origin(&self) -> Option<&str> {
 		self.header("Origin")
 	}
@@ -315,6 +445,53 @@ impl MurRequestContext {
 			.map_err(|e| MurError::BadRequest(format!("Failed to parse query params: {}", e)))
 	}
 
+	/// The ID assigned by the `MurRequestId` middleware, if installed.
+	pub fn request_id(&self) -> Option<&str> {
+		self.parts
+			.extensions
+			.get::<MurRequestIdExtension>()
+			.map(|id| id.0.as_str())
+	}
+
+	/// The deadline set by `MurTimeout` or the route's `#[timeout]`, if any.
+	pub fn deadline(&self) -> Option<MurDeadline> {
+		self.parts.extensions.get::<MurDeadline>().copied()
+	}
+
+	/// Time left before the deadline, or `None` when the request has none.
+	pub fn remaining_time(&self) -> Option<std::time::Duration> {
+		self.deadline().map(|deadline| deadline.remaining())
+	}
+
+	/// Metadata of the matched route, set once routing has picked a handler.
+	pub fn matched_route(&self) -> Option<&MurRouteMetadata> {
+		self.parts
+			.extensions
+			.get::<Arc<MurRouteMetadata>>()
+			.map(|metadata| metadata.as_ref())
+	}
+
+	/// The typed metadata value of type `T` set on the matched route with
+	/// `#[set_metadata(..)]`, `#[decorate(..)]` or the route builder.
+	pub fn route_metadata<T: 'static>(&self) -> Option<&T> {
+		self.matched_route()?.get_typed::<T>()
+	}
+
+	/// The string metadata value for `key` on the matched route.
+	pub fn route_metadata_value(&self, key: &str) -> Option<&str> {
+		self.matched_route()?.get(key)
+	}
+
+	/// The pattern of the matched route, e.g. `/users/:id`.
+	pub fn route_pattern(&self) -> Option<&str> {
+		self.matched_route().map(|route| route.pattern())
+	}
+
+	/// The name of the controller owning the matched route.
+	pub fn controller_name(&self) -> Option<&str> {
+		self.matched_route().map(|route| route.controller())
+	}
+
 	pub fn with_access_control(mut self, access_control: MurRouteAccessControl) -> Self {
 		self.access_control = access_control;
 		self
@@ -1,4 +1,7 @@
+use crate::server::http::cache::{MurCacheControl, MurEntityTag, mur_format_http_date};
+use crate::server::http::codec::MurNegotiated;
 use crate::server::http::response::mur_res::MurRes;
+use chrono::{DateTime, Utc};
 use http::StatusCode;
 use http_body_util::Full;
 use hyper::Response;
@@ -39,6 +42,42 @@ impl MurResponseBuilder {
 		self.header("Content-Type", content_type)
 	}
 
+	pub fn cache_control(self, control: MurCacheControl) -> Self {
+		self.header("Cache-Control", control.to_header_value())
+	}
+
+	/// `Cache-Control: public, max-age=<secs>`.
+	pub fn max_age(self, age: std::time::Duration) -> Self {
+		self.cache_control(MurCacheControl::new().public().max_age(age))
+	}
+
+	/// `Cache-Control: no-cache` — clients must revalidate before reuse.
+	pub fn no_cache(self) -> Self {
+		self.cache_control(MurCacheControl::revalidate())
+	}
+
+	/// `Cache-Control: no-store` — the response must never be cached.
+	pub fn no_store(self) -> Self {
+		self.cache_control(MurCacheControl::never())
+	}
+
+	pub fn etag(self, etag: MurEntityTag) -> Self {
+		self.header("ETag", etag.to_header_value())
+	}
+
+	pub fn last_modified(self, date: DateTime<Utc>) -> Self {
+		self.header("Last-Modified", mur_format_http_date(date))
+	}
+
+	pub fn vary(self, header: impl Into<String>) -> Self {
+		let header = header.into();
+		let value = match self.headers.get("Vary") {
+			Some(existing) => format!("{}, {}", existing, header),
+			None => header,
+		};
+		self.header("Vary", value)
+	}
+
 	pub fn json<T: Serialize>(self, body: T) -> MurRes {
 		let encoded = serde_json::to_string(&body)
 			.unwrap_or_else(|e| format!(r#"{{"error":"Serialization failed: {}"}}"#, e));
@@ -52,6 +91,15 @@ impl MurResponseBuilder {
 		MurRes::from(builder.body(Full::new(Bytes::from(encoded))).unwrap())
 	}
 
+	/// Like [`json`](Self::json), but written in the format the request's
+	/// `Accept` header prefers. See [`MurRes::negotiated`].
+	pub fn negotiated<T: Serialize + Send + Sync + 'static>(self, body: T) -> MurRes {
+		self.json(&body).map_response(|mut r| {
+			r.extensions_mut().insert(MurNegotiated::new(body));
+			r
+		})
+	}
+
 	pub fn text(self, body: impl Into<String>) -> MurRes {
 		let text_body = body.into();
 		let mut builder = Response::builder().status(self.status);
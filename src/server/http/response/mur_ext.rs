@@ -29,19 +29,15 @@ impl MurResExt for MurRes {
 	}
 
 	fn created() -> MurRes {
-		MurRes::from(
-			crate::server::http::response::builder::MurResponseBuilder::new()
-				.status(StatusCode::CREATED)
-				.empty(),
-		)
+		crate::server::http::response::builder::MurResponseBuilder::new()
+			.status(StatusCode::CREATED)
+			.empty()
 	}
 
 	fn accepted() -> MurRes {
-		MurRes::from(
-			crate::server::http::response::builder::MurResponseBuilder::new()
-				.status(StatusCode::ACCEPTED)
-				.empty(),
-		)
+		crate::server::http::response::builder::MurResponseBuilder::new()
+			.status(StatusCode::ACCEPTED)
+			.empty()
 	}
 
 	fn no_content() -> MurRes {
@@ -69,11 +65,9 @@ impl MurResExt for MurRes {
 	}
 
 	fn status(status: StatusCode) -> MurRes {
-		MurRes::from(
-			crate::server::http::response::builder::MurResponseBuilder::new()
-				.status(status)
-				.empty(),
-		)
+		crate::server::http::response::builder::MurResponseBuilder::new()
+			.status(status)
+			.empty()
 	}
 
 	fn json<T: Serialize>(body: T) -> MurRes {
@@ -1,4 +1,6 @@
 use crate::server::error::MurError;
+use crate::server::http::codec::MurNegotiated;
+use crate::server::security::cookie::MurCookieJar;
 use http::StatusCode;
 use http_body_util::Full;
 use hyper::Response;
@@ -52,6 +54,11 @@ impl MurCookie {
 		}
 	}
 
+	/// A cookie telling the client to delete `name`, set at path `/`.
+	pub fn removal(name: impl Into<String>) -> Self {
+		Self::new(name, "").path("/").max_age(0)
+	}
+
 	pub fn http_only(mut self) -> Self {
 		self.http_only = true;
 		self
@@ -161,6 +168,18 @@ impl MurRes {
 		Self::ok(body)
 	}
 
+	/// `200 OK` with `body` in the format the request's `Accept` header
+	/// prefers among the server's body formats, JSON by default.
+	///
+	/// Requests accepting none of them are answered with
+	/// `406 Not Acceptable`.
+	pub fn negotiated<T: Serialize + Send + Sync + 'static>(body: T) -> Self {
+		Self(json_body(StatusCode::OK, &body)).map_response(|mut r| {
+			r.extensions_mut().insert(MurNegotiated::new(body));
+			r
+		})
+	}
+
 	/// `200 OK` with plain-text body.
 	pub fn text(body: impl Into<String>) -> Self {
 		let b = body.into();
@@ -331,6 +350,12 @@ impl MurRes {
 		})
 	}
 
+	/// Appends a `Set-Cookie` header per cookie the jar changed. No-op on
+	/// error responses.
+	pub fn with_cookie_jar(self, jar: &MurCookieJar) -> Self {
+		jar.apply(self)
+	}
+
 	/// Overrides the HTTP status code. No-op on error responses.
 	pub fn with_status(self, code: StatusCode) -> Self {
 		self.map_response(move |mut r| {
@@ -349,6 +374,14 @@ impl MurRes {
 		self.0.is_err()
 	}
 
+	/// The response status, or the status the error will be answered with.
+	pub fn status_code(&self) -> StatusCode {
+		match &self.0 {
+			Ok(response) => response.status(),
+			Err(e) => e.status_code(),
+		}
+	}
+
 	pub fn unwrap(self) -> Response<Full<Bytes>> {
 		self.0.unwrap()
 	}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/// Response header a handler can set to attach extra invalidation tags to a
+/// cached response, e.g. `X-Cache-Tags: users, user:42`. It is stripped
+/// before the response leaves the server.
+pub const CACHE_TAGS_HEADER: &str = "x-cache-tags";
+
+#[derive(Debug, Clone)]
+pub struct MurCacheConfig {
+	pub ttl: Duration,
+	pub vary_headers: Vec<String>,
+	pub tags: Vec<String>,
+	pub key_prefix: String,
+	pub cacheable_statuses: Vec<u16>,
+	pub include_cache_header: bool,
+	pub respect_cache_control: bool,
+}
+
+impl Default for MurCacheConfig {
+	fn default() -> Self {
+		Self {
+			ttl: Duration::from_secs(60),
+			vary_headers: Vec::new(),
+			tags: Vec::new(),
+			key_prefix: String::new(),
+			cacheable_statuses: vec![200, 203, 300, 301, 308],
+			include_cache_header: true,
+			respect_cache_control: true,
+		}
+	}
+}
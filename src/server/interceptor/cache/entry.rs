@@ -0,0 +1,53 @@
+use http::{HeaderMap, HeaderValue, StatusCode};
+use http_body_util::Full;
+use hyper::Response;
+use hyper::body::Bytes;
+use std::time::{Duration, Instant};
+
+/// A buffered response held by a [`MurCacheStore`](super::MurCacheStore).
+#[derive(Debug, Clone)]
+pub struct MurCachedResponse {
+	pub status: StatusCode,
+	pub headers: HeaderMap,
+	pub body: Bytes,
+	pub tags: Vec<String>,
+	pub stored_at: Instant,
+	pub ttl: Duration,
+}
+
+impl MurCachedResponse {
+	pub fn new(status: StatusCode, headers: HeaderMap, body: Bytes, ttl: Duration) -> Self {
+		Self {
+			status,
+			headers,
+			body,
+			tags: Vec::new(),
+			stored_at: Instant::now(),
+			ttl,
+		}
+	}
+
+	pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+		self.tags = tags;
+		self
+	}
+
+	pub fn is_expired(&self) -> bool {
+		self.stored_at.elapsed() >= self.ttl
+	}
+
+	pub fn age(&self) -> Duration {
+		self.stored_at.elapsed()
+	}
+
+	/// Rebuilds the HTTP response, adding an `Age` header.
+	pub fn to_response(&self) -> Response<Full<Bytes>> {
+		let mut response = Response::new(Full::new(self.body.clone()));
+		*response.status_mut() = self.status;
+		*response.headers_mut() = self.headers.clone();
+		if let Ok(age) = HeaderValue::from_str(&self.age().as_secs().to_string()) {
+			response.headers_mut().insert("age", age);
+		}
+		response
+	}
+}
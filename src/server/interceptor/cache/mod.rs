@@ -0,0 +1,15 @@
+pub mod config;
+pub mod entry;
+pub mod mur_cache;
+pub mod mur_cache_interceptor;
+pub mod store;
+
+pub use config::MurCacheConfig;
+pub use entry::MurCachedResponse;
+pub use mur_cache::MurCache;
+pub use mur_cache_interceptor::MurCacheInterceptor;
+pub use store::InMemoryCacheStore;
+pub use store::MurCacheStore;
+
+#[cfg(test)]
+mod test;
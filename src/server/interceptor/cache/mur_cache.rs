@@ -0,0 +1,102 @@
+use super::{InMemoryCacheStore, MurCacheStore, MurCachedResponse};
+use crate::server::service::{MurInjectable, MurService};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Shared handle to a response cache store.
+///
+/// Register it once on the server and inject it wherever invalidation is
+/// needed; every [`MurCacheInterceptor`](super::MurCacheInterceptor) built from
+/// the same handle reads and writes the same store.
+///
+/// ```rust,ignore
+/// let cache = MurCache::in_memory();
+///
+/// MurServer::new()
+///     .service(cache.clone())
+///     .interceptor::<MurCacheInterceptor>()
+///
+/// #[injectable]
+/// struct UserService { cache: MurCache }
+///
+/// impl UserService {
+///     fn rename(&self, id: u64, name: &str) {
+///         // ...
+///         self.cache.invalidate_tag("users");
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MurCache {
+	store: Arc<dyn MurCacheStore>,
+}
+
+impl MurCache {
+	pub fn in_memory() -> Self {
+		Self::with_store(InMemoryCacheStore::new())
+	}
+
+	pub fn with_store<S: MurCacheStore>(store: S) -> Self {
+		Self {
+			store: Arc::new(store),
+		}
+	}
+
+	pub fn from_arc(store: Arc<dyn MurCacheStore>) -> Self {
+		Self { store }
+	}
+
+	pub fn store(&self) -> &Arc<dyn MurCacheStore> {
+		&self.store
+	}
+
+	pub fn get(&self, key: &str) -> Option<MurCachedResponse> {
+		self.store.get(key)
+	}
+
+	pub fn set(&self, key: &str, entry: MurCachedResponse) {
+		self.store.set(key, entry);
+	}
+
+	pub fn invalidate(&self, key: &str) -> bool {
+		self.store.remove(key)
+	}
+
+	pub fn invalidate_tag(&self, tag: &str) -> usize {
+		self.store.invalidate_tag(tag)
+	}
+
+	pub fn invalidate_tags<'a>(&self, tags: impl IntoIterator<Item = &'a str>) -> usize {
+		tags.into_iter()
+			.map(|tag| self.store.invalidate_tag(tag))
+			.sum()
+	}
+
+	pub fn clear(&self) {
+		self.store.clear();
+	}
+}
+
+impl Default for MurCache {
+	fn default() -> Self {
+		Self::in_memory()
+	}
+}
+
+impl std::fmt::Debug for MurCache {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurCache").finish_non_exhaustive()
+	}
+}
+
+impl MurService for MurCache {
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl MurInjectable for MurCache {
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
@@ -0,0 +1,346 @@
+use super::config::CACHE_TAGS_HEADER;
+use super::{MurCache, MurCacheConfig, MurCachedResponse};
+use crate::server::aliases::MurRes;
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::http::cache::{
+	MurCacheControl, MurEntityTag, mur_is_not_modified, mur_not_modified,
+};
+use crate::server::http::codec::{MurBodyFormat, mur_encode_negotiated};
+use crate::server::interceptor::{
+	MurCallHandler, MurInterceptFuture, MurInterceptor, MurInterceptorFactory,
+};
+use crate::server::router::request_host;
+use crate::server::service::{MurInjects, MurServiceContainer};
+use http::header::{AUTHORIZATION, CACHE_CONTROL, COOKIE, ETAG, SET_COOKIE};
+use http::{HeaderValue, Method};
+use http_body_util::{BodyExt, Full};
+use hyper::Response;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Caches `GET` responses in a [`MurCache`] and serves later hits without
+/// running the handler.
+///
+/// Entries are keyed by host, path, query string, the API version served,
+/// the response format negotiated from `Accept` when the server writes more
+/// than one, and the configured `vary_by` headers. Requests carrying
+/// `Authorization` or `Cookie` are neither served from nor stored in the
+/// cache unless that header is part of the key.
+///
+/// ```rust,ignore
+/// let cache = MurCache::in_memory();
+///
+/// MurRouteBuilder::new(&mut router, "GET", "/users")
+///     .interceptor(MurCacheInterceptor::new(cache.clone()).ttl_secs(30).tag("users"))
+///     .handler(list_users);
+/// ```
+#[derive(Clone)]
+pub struct MurCacheInterceptor {
+	cache: MurCache,
+	config: Arc<MurCacheConfig>,
+}
+
+impl MurCacheInterceptor {
+	pub fn new(cache: MurCache) -> Self {
+		Self::from_config(cache, MurCacheConfig::default())
+	}
+
+	pub fn from_config(cache: MurCache, config: MurCacheConfig) -> Self {
+		Self {
+			cache,
+			config: Arc::new(config),
+		}
+	}
+
+	pub fn ttl(mut self, ttl: Duration) -> Self {
+		let mut config = (*self.config).clone();
+		config.ttl = ttl;
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn ttl_secs(self, secs: u64) -> Self {
+		self.ttl(Duration::from_secs(secs))
+	}
+
+	/// Adds a request header whose value becomes part of the cache key.
+	pub fn vary_by(mut self, header: impl Into<String>) -> Self {
+		let mut config = (*self.config).clone();
+		config.vary_headers.push(header.into().to_ascii_lowercase());
+		self.config = Arc::new(config);
+		self
+	}
+
+	/// Tags every entry stored by this interceptor for later invalidation.
+	pub fn tag(mut self, tag: impl Into<String>) -> Self {
+		let mut config = (*self.config).clone();
+		config.tags.push(tag.into());
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn key_prefix(mut self, prefix: impl Into<String>) -> Self {
+		let mut config = (*self.config).clone();
+		config.key_prefix = prefix.into();
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn no_cache_header(mut self) -> Self {
+		let mut config = (*self.config).clone();
+		config.include_cache_header = false;
+		self.config = Arc::new(config);
+		self
+	}
+
+	/// Ignores `Cache-Control` on requests and responses.
+	pub fn ignore_cache_control(mut self) -> Self {
+		let mut config = (*self.config).clone();
+		config.respect_cache_control = false;
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn cache(&self) -> &MurCache {
+		&self.cache
+	}
+
+	pub fn config(&self) -> &MurCacheConfig {
+		&self.config
+	}
+
+	pub fn cache_key(&self, ctx: &MurRequestContext) -> String {
+		let host = request_host(&ctx.parts).unwrap_or_default();
+		let mut key = format!(
+			"{}{}{}",
+			self.config.key_prefix,
+			host.to_ascii_lowercase(),
+			ctx.path()
+		);
+		if let Some(query) = ctx.query_string() {
+			key.push('?');
+			key.push_str(query);
+		}
+		if let Some(version) = ctx.api_version() {
+			key.push_str("|version=");
+			key.push_str(version);
+		}
+		if ctx.body_codecs().formats().len() > 1 {
+			key.push_str("|format=");
+			key.push_str(ctx.response_format().map_or("", MurBodyFormat::media_type));
+		}
+		for header in &self.config.vary_headers {
+			key.push('|');
+			key.push_str(header);
+			key.push('=');
+			key.push_str(ctx.header(header).unwrap_or(""));
+		}
+		key
+	}
+
+	fn is_cacheable_request(&self, ctx: &MurRequestContext) -> bool {
+		if ctx.method() != Method::GET {
+			return false;
+		}
+		let keyed_by = |header: &str| self.config.vary_headers.iter().any(|h| h == header);
+		for credential in [AUTHORIZATION, COOKIE] {
+			if ctx.has_header(credential.as_str()) && !keyed_by(credential.as_str()) {
+				return false;
+			}
+		}
+		if self.config.respect_cache_control
+			&& let Some(value) = ctx.header(CACHE_CONTROL.as_str())
+		{
+			let control = MurCacheControl::parse(value);
+			return !control.no_cache && !control.no_store;
+		}
+		true
+	}
+
+	/// Returns the TTL to store a response with, or `None` if it must not be stored.
+	fn storable_ttl(&self, response: &Response<Full<hyper::body::Bytes>>) -> Option<Duration> {
+		if !self
+			.config
+			.cacheable_statuses
+			.contains(&response.status().as_u16())
+			|| response.headers().contains_key(SET_COOKIE)
+		{
+			return None;
+		}
+
+		if !self.config.respect_cache_control {
+			return Some(self.config.ttl);
+		}
+
+		match response
+			.headers()
+			.get(CACHE_CONTROL)
+			.and_then(|v| v.to_str().ok())
+		{
+			Some(value) => {
+				let control = MurCacheControl::parse(value);
+				if !control.is_storable() || control.no_cache {
+					return None;
+				}
+				let ttl = control
+					.s_maxage
+					.or(control.max_age)
+					.map(|secs| Duration::from_secs(secs).min(self.config.ttl))
+					.unwrap_or(self.config.ttl);
+				(!ttl.is_zero()).then_some(ttl)
+			}
+			None => Some(self.config.ttl),
+		}
+	}
+
+	fn mark(&self, response: &mut Response<Full<hyper::body::Bytes>>, status: &'static str) {
+		if self.config.include_cache_header {
+			response
+				.headers_mut()
+				.insert("x-cache", HeaderValue::from_static(status));
+		}
+	}
+}
+
+impl std::fmt::Debug for MurCacheInterceptor {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurCacheInterceptor")
+			.field("config", &self.config)
+			.finish()
+	}
+}
+
+impl MurCacheInterceptor {
+	/// The cached response for `ctx`, if the request is cacheable and an
+	/// entry exists.
+	pub fn lookup(&self, ctx: &MurRequestContext) -> Option<MurRes> {
+		if !self.is_cacheable_request(ctx) {
+			return None;
+		}
+
+		self.cache.get(&self.cache_key(ctx)).map(|entry| {
+			let mut response = entry.to_response();
+			self.mark(&mut response, "HIT");
+			if mur_is_not_modified(ctx.method(), &ctx.parts.headers, response.headers()) {
+				response = mur_not_modified(response);
+			}
+			MurRes::from(response)
+		})
+	}
+
+	/// Stores a handler response for `ctx` when both are cacheable, and
+	/// returns the response to send.
+	pub fn store(
+		&self,
+		ctx: &MurRequestContext,
+		response: MurRes,
+	) -> Pin<Box<dyn Future<Output = MurRes> + Send>> {
+		if !self.is_cacheable_request(ctx) {
+			let response = response.map_response(|mut r| {
+				r.headers_mut().remove(CACHE_TAGS_HEADER);
+				r
+			});
+			return Box::pin(async move { response });
+		}
+
+		let interceptor = self.clone();
+		let key = self.cache_key(ctx);
+		let method = ctx.method().clone();
+		let request_headers = ctx.parts.headers.clone();
+		// Negotiated bodies are encoded now so the entry holds the format
+		// its key names.
+		let response = response
+			.into_result()
+			.and_then(|response| mur_encode_negotiated(response, ctx));
+
+		Box::pin(async move {
+			let response = match response {
+				Ok(response) => response,
+				Err(e) => return MurRes::from(e),
+			};
+
+			let Some(ttl) = interceptor.storable_ttl(&response) else {
+				let mut response = response;
+				response.headers_mut().remove(CACHE_TAGS_HEADER);
+				return MurRes::from(response);
+			};
+
+			let (mut parts, body) = response.into_parts();
+			let body = match body.collect().await {
+				Ok(c) => c.to_bytes(),
+				Err(_) => {
+					return MurRes::from(MurError::Internal(
+						"Failed to read response body".into(),
+					));
+				}
+			};
+
+			let mut tags = interceptor.config.tags.clone();
+			if let Some(value) = parts.headers.remove(CACHE_TAGS_HEADER)
+				&& let Ok(value) = value.to_str()
+			{
+				tags.extend(
+					value
+						.split(',')
+						.map(|t| t.trim().to_string())
+						.filter(|t| !t.is_empty()),
+				);
+			}
+
+			if !parts.headers.contains_key(ETAG)
+				&& let Ok(value) =
+					HeaderValue::from_str(&MurEntityTag::from_body(&body).to_header_value())
+			{
+				parts.headers.insert(ETAG, value);
+			}
+
+			interceptor.cache.set(
+				&key,
+				MurCachedResponse::new(parts.status, parts.headers.clone(), body.clone(), ttl)
+					.with_tags(tags),
+			);
+
+			let mut response = Response::from_parts(parts, Full::new(body));
+			interceptor.mark(&mut response, "MISS");
+			if mur_is_not_modified(&method, &request_headers, response.headers()) {
+				response = mur_not_modified(response);
+			}
+			MurRes::from(response)
+		})
+	}
+}
+
+impl MurInterceptor for MurCacheInterceptor {
+	fn intercept<'a>(
+		&'a self,
+		ctx: MurRequestContext,
+		next: MurCallHandler,
+	) -> MurInterceptFuture<'a> {
+		Box::pin(async move {
+			if let Some(hit) = self.lookup(&ctx) {
+				return hit;
+			}
+			let response = next.run(ctx.clone()).await;
+			self.store(&ctx, response).await
+		})
+	}
+
+	fn name(&self) -> &str {
+		"MurCacheInterceptor"
+	}
+}
+
+impl MurInterceptorFactory for MurCacheInterceptor {
+	/// Uses the [`MurCache`] registered on the server, falling back to a
+	/// private in-memory store when none is registered.
+	fn __create_factory(_injects: &MurInjects, container: &MurServiceContainer) -> Self {
+		let cache = container
+			.get::<MurCache>()
+			.map(|cache| (*cache).clone())
+			.unwrap_or_default();
+		Self::new(cache)
+	}
+}
@@ -0,0 +1,179 @@
+use super::MurCachedResponse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, RwLock};
+
+pub trait MurCacheStore: Send + Sync + 'static {
+	fn get(&self, key: &str) -> Option<MurCachedResponse>;
+	fn set(&self, key: &str, entry: MurCachedResponse);
+	fn remove(&self, key: &str) -> bool;
+	/// Removes every entry stored with `tag`, returning how many were dropped.
+	fn invalidate_tag(&self, tag: &str) -> usize;
+	fn clear(&self);
+}
+
+#[derive(Debug)]
+pub struct InMemoryCacheStore {
+	pub entries: RwLock<HashMap<String, MurCachedResponse>>,
+	pub tags: RwLock<HashMap<String, HashSet<String>>>,
+	pub max_entries: usize,
+	recency: Mutex<Recency>,
+}
+
+/// Use order of the stored keys, least recently used first.
+#[derive(Debug, Default)]
+struct Recency {
+	tick: u64,
+	by_tick: BTreeMap<u64, String>,
+	by_key: HashMap<String, u64>,
+}
+
+impl Recency {
+	fn touch(&mut self, key: &str) {
+		self.forget(key);
+		self.tick += 1;
+		self.by_tick.insert(self.tick, key.to_string());
+		self.by_key.insert(key.to_string(), self.tick);
+	}
+
+	fn forget(&mut self, key: &str) {
+		if let Some(tick) = self.by_key.remove(key) {
+			self.by_tick.remove(&tick);
+		}
+	}
+
+	fn pop_least_recent(&mut self) -> Option<String> {
+		let (_, key) = self.by_tick.pop_first()?;
+		self.by_key.remove(&key);
+		Some(key)
+	}
+}
+
+impl InMemoryCacheStore {
+	pub fn new() -> Self {
+		Self::with_capacity(10_000)
+	}
+
+	pub fn with_capacity(max_entries: usize) -> Self {
+		Self {
+			entries: RwLock::new(HashMap::new()),
+			tags: RwLock::new(HashMap::new()),
+			max_entries,
+			recency: Mutex::new(Recency::default()),
+		}
+	}
+
+	fn recency(&self) -> std::sync::MutexGuard<'_, Recency> {
+		self.recency.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries
+			.read()
+			.unwrap_or_else(|e| e.into_inner())
+			.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	fn untag(&self, key: &str, tags: &[String]) {
+		let mut index = self.tags.write().unwrap_or_else(|e| e.into_inner());
+		for tag in tags {
+			if let Some(keys) = index.get_mut(tag) {
+				keys.remove(key);
+				if keys.is_empty() {
+					index.remove(tag);
+				}
+			}
+		}
+	}
+
+	/// Makes room for one more entry by dropping the least recently used.
+	fn evict(&self, entries: &mut HashMap<String, MurCachedResponse>) {
+		let mut recency = self.recency();
+		while entries.len() >= self.max_entries {
+			let Some(key) = recency.pop_least_recent() else {
+				break;
+			};
+			if let Some(entry) = entries.remove(&key) {
+				self.untag(&key, &entry.tags);
+			}
+		}
+	}
+}
+
+impl Default for InMemoryCacheStore {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MurCacheStore for InMemoryCacheStore {
+	fn get(&self, key: &str) -> Option<MurCachedResponse> {
+		{
+			let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+			match entries.get(key) {
+				Some(entry) if !entry.is_expired() => {
+					self.recency().touch(key);
+					return Some(entry.clone());
+				}
+				Some(_) => {}
+				None => return None,
+			}
+		}
+		self.remove(key);
+		None
+	}
+
+	fn set(&self, key: &str, entry: MurCachedResponse) {
+		let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+		if let Some(previous) = entries.remove(key) {
+			self.untag(key, &previous.tags);
+		}
+		self.evict(&mut entries);
+
+		{
+			let mut index = self.tags.write().unwrap_or_else(|e| e.into_inner());
+			for tag in &entry.tags {
+				index
+					.entry(tag.clone())
+					.or_default()
+					.insert(key.to_string());
+			}
+		}
+		self.recency().touch(key);
+		entries.insert(key.to_string(), entry);
+	}
+
+	fn remove(&self, key: &str) -> bool {
+		let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+		self.recency().forget(key);
+		match entries.remove(key) {
+			Some(entry) => {
+				self.untag(key, &entry.tags);
+				true
+			}
+			None => false,
+		}
+	}
+
+	fn invalidate_tag(&self, tag: &str) -> usize {
+		let keys = self
+			.tags
+			.write()
+			.unwrap_or_else(|e| e.into_inner())
+			.remove(tag)
+			.unwrap_or_default();
+		keys.iter().filter(|key| self.remove(key)).count()
+	}
+
+	fn clear(&self) {
+		self.entries
+			.write()
+			.unwrap_or_else(|e| e.into_inner())
+			.clear();
+		self.tags.write().unwrap_or_else(|e| e.into_inner()).clear();
+		*self.recency() = Recency::default();
+	}
+}
@@ -0,0 +1,324 @@
+use super::*;
+use crate::server::aliases::MurRes;
+use crate::server::http::MurRequestContext;
+use crate::server::http::codec::MurBodyCodecs;
+use crate::server::router::MurApiVersion;
+use crate::server::service::MurServiceContainer;
+use http::{HeaderMap, StatusCode};
+use http_body_util::{BodyExt, Full};
+use hyper::Response;
+use hyper::body::Bytes;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn ctx(method: &str, uri: &str, headers: &[(&str, &str)]) -> MurRequestContext {
+	let mut builder = http::Request::builder().method(method).uri(uri);
+	for (name, value) in headers {
+		builder = builder.header(*name, *value);
+	}
+	let (parts, _) = builder.body(()).unwrap().into_parts();
+	MurRequestContext::new(
+		parts,
+		None,
+		HashMap::new(),
+		Arc::new(MurServiceContainer::new()),
+	)
+}
+
+fn ok(body: &'static str, headers: &[(&'static str, &'static str)]) -> MurRes {
+	let mut builder = Response::builder().status(StatusCode::OK);
+	for (name, value) in headers {
+		builder = builder.header(*name, *value);
+	}
+	MurRes::from(builder.body(Full::new(Bytes::from(body))).unwrap())
+}
+
+/// The key part naming the negotiated format, present when the default
+/// codecs write more than JSON.
+fn json_format() -> &'static str {
+	if MurBodyCodecs::default().formats().len() > 1 {
+		"|format=application/json"
+	} else {
+		""
+	}
+}
+
+fn entry(body: &'static str, ttl: Duration) -> MurCachedResponse {
+	MurCachedResponse::new(StatusCode::OK, HeaderMap::new(), Bytes::from(body), ttl)
+}
+
+#[test]
+fn test_store_get_set_remove() {
+	let store = InMemoryCacheStore::new();
+	store.set("a", entry("one", Duration::from_secs(60)));
+	assert_eq!(store.len(), 1);
+	assert_eq!(store.get("a").unwrap().body, Bytes::from("one"));
+	assert!(store.remove("a"));
+	assert!(!store.remove("a"));
+	assert!(store.is_empty());
+}
+
+#[test]
+fn test_store_expired_entries_are_misses() {
+	let store = InMemoryCacheStore::new();
+	store.set("a", entry("one", Duration::ZERO));
+	assert!(store.get("a").is_none());
+}
+
+#[test]
+fn test_store_invalidate_tag() {
+	let store = InMemoryCacheStore::new();
+	let ttl = Duration::from_secs(60);
+	store.set("a", entry("a", ttl).with_tags(vec!["users".into()]));
+	store.set(
+		"b",
+		entry("b", ttl).with_tags(vec!["users".into(), "admin".into()]),
+	);
+	store.set("c", entry("c", ttl).with_tags(vec!["posts".into()]));
+
+	assert_eq!(store.invalidate_tag("users"), 2);
+	assert!(store.get("a").is_none());
+	assert!(store.get("b").is_none());
+	assert!(store.get("c").is_some());
+	assert_eq!(store.invalidate_tag("admin"), 0);
+}
+
+#[test]
+fn test_store_evicts_when_full() {
+	let store = InMemoryCacheStore::with_capacity(2);
+	let ttl = Duration::from_secs(60);
+	store.set("a", entry("a", ttl));
+	store.set("b", entry("b", ttl));
+	store.set("c", entry("c", ttl));
+	assert_eq!(store.len(), 2);
+	assert!(store.get("c").is_some());
+}
+
+#[test]
+fn test_store_evicts_least_recently_used() {
+	let store = InMemoryCacheStore::with_capacity(2);
+	let ttl = Duration::from_secs(60);
+	store.set("a", entry("a", ttl));
+	store.set("b", entry("b", ttl));
+	assert!(store.get("a").is_some());
+
+	store.set("c", entry("c", ttl));
+	assert!(store.get("a").is_some());
+	assert!(store.get("b").is_none());
+	assert!(store.get("c").is_some());
+
+	store.set("a", entry("a2", ttl));
+	store.set("d", entry("d", ttl));
+	assert_eq!(store.get("a").unwrap().body, Bytes::from("a2"));
+	assert!(store.get("c").is_none());
+}
+
+#[test]
+fn test_cached_response_sets_age() {
+	let response = entry("body", Duration::from_secs(60)).to_response();
+	assert_eq!(response.status(), StatusCode::OK);
+	assert!(response.headers().contains_key("age"));
+}
+
+#[test]
+fn test_cache_key_includes_query_and_vary_headers() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory())
+		.key_prefix("v1:")
+		.vary_by("Accept-Language");
+	let key = interceptor.cache_key(&ctx(
+		"GET",
+		"/users?page=2",
+		&[("accept-language", "pt-BR")],
+	));
+	assert_eq!(
+		key,
+		format!("v1:/users?page=2{}|accept-language=pt-BR", json_format())
+	);
+}
+
+#[test]
+fn test_cache_key_separates_hosts_versions_and_formats() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory());
+	let api = interceptor.cache_key(&ctx("GET", "/users", &[("host", "API.example.com")]));
+	let admin = interceptor.cache_key(&ctx("GET", "/users", &[("host", "admin.example.com")]));
+	assert_eq!(api, format!("api.example.com/users{}", json_format()));
+	assert_ne!(api, admin);
+
+	let mut versioned = ctx("GET", "/users", &[]);
+	versioned
+		.parts
+		.extensions
+		.insert(MurApiVersion("2".to_string()));
+	let key = interceptor.cache_key(&versioned);
+	assert_eq!(key, format!("/users|version=2{}", json_format()));
+
+	#[cfg(feature = "msgpack")]
+	{
+		let msgpack = ctx("GET", "/users", &[("accept", "application/msgpack")]);
+		let key = interceptor.cache_key(&msgpack);
+		assert!(key.contains("|format=application/msgpack"));
+	}
+}
+
+#[tokio::test]
+async fn test_miss_then_hit() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory());
+	let request = ctx("GET", "/users", &[]);
+
+	assert!(interceptor.lookup(&request).is_none());
+	let response = interceptor
+		.store(&request, ok("users", &[]))
+		.await
+		.into_result()
+		.unwrap();
+	assert_eq!(response.headers().get("x-cache").unwrap(), "MISS");
+	assert!(response.headers().contains_key("etag"));
+
+	let hit = interceptor
+		.lookup(&request)
+		.expect("cached response")
+		.into_result()
+		.unwrap();
+	assert_eq!(hit.headers().get("x-cache").unwrap(), "HIT");
+	let body = hit.into_body().collect().await.unwrap().to_bytes();
+	assert_eq!(&body[..], b"users");
+}
+
+#[tokio::test]
+async fn test_hit_honours_if_none_match() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory());
+	let request = ctx("GET", "/users", &[]);
+	let response = interceptor
+		.store(&request, ok("users", &[]))
+		.await
+		.into_result()
+		.unwrap();
+	let etag = response.headers().get("etag").unwrap().to_str().unwrap();
+
+	let conditional = ctx("GET", "/users", &[("if-none-match", etag)]);
+	let hit = interceptor
+		.lookup(&conditional)
+		.unwrap()
+		.into_result()
+		.unwrap();
+	assert_eq!(hit.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn test_uncacheable_requests_and_responses() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory());
+
+	let cached = |request: &MurRequestContext| {
+		interceptor
+			.cache()
+			.get(&interceptor.cache_key(request))
+			.is_some()
+	};
+
+	let post = ctx("POST", "/users", &[]);
+	interceptor.store(&post, ok("users", &[])).await;
+	assert!(!cached(&post));
+
+	let authorized = ctx("GET", "/me", &[("authorization", "Bearer x")]);
+	interceptor.store(&authorized, ok("me", &[])).await;
+	assert!(!cached(&authorized));
+
+	let with_session = ctx("GET", "/me", &[("cookie", "mur.sid=abc")]);
+	interceptor.store(&with_session, ok("me", &[])).await;
+	assert!(!cached(&with_session));
+
+	let request = ctx("GET", "/private", &[]);
+	interceptor
+		.store(&request, ok("secret", &[("cache-control", "private")]))
+		.await;
+	assert!(!cached(&request));
+
+	let request = ctx("GET", "/cookie", &[]);
+	interceptor
+		.store(&request, ok("c", &[("set-cookie", "a=b")]))
+		.await;
+	assert!(!cached(&request));
+}
+
+#[tokio::test]
+async fn test_cookie_requests_are_cached_when_keyed_by_cookie() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory()).vary_by("Cookie");
+	let alice = ctx("GET", "/me", &[("cookie", "user=alice")]);
+	interceptor.store(&alice, ok("alice", &[])).await;
+
+	assert!(interceptor.lookup(&alice).is_some());
+	let bob = ctx("GET", "/me", &[("cookie", "user=bob")]);
+	assert!(interceptor.lookup(&bob).is_none());
+}
+
+#[cfg(feature = "msgpack")]
+#[tokio::test]
+async fn test_negotiated_responses_are_cached_per_format() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory());
+	let msgpack = ctx("GET", "/users", &[("accept", "application/msgpack")]);
+	let body = serde_json::json!({ "users": ["ana"] });
+	let response = interceptor
+		.store(&msgpack, MurRes::negotiated(body))
+		.await
+		.into_result()
+		.unwrap();
+	assert_eq!(
+		response.headers().get("content-type").unwrap(),
+		"application/msgpack"
+	);
+
+	assert!(interceptor.lookup(&ctx("GET", "/users", &[])).is_none());
+	let hit = interceptor
+		.lookup(&msgpack)
+		.expect("cached response")
+		.into_result()
+		.unwrap();
+	assert_eq!(
+		hit.headers().get("content-type").unwrap(),
+		"application/msgpack"
+	);
+	assert_eq!(hit.headers().get("vary").unwrap(), "Accept");
+}
+
+#[tokio::test]
+async fn test_response_max_age_caps_ttl() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory()).ttl_secs(300);
+	let request = ctx("GET", "/short", &[]);
+	interceptor
+		.store(&request, ok("s", &[("cache-control", "public, max-age=5")]))
+		.await;
+	let cached = interceptor
+		.cache()
+		.get(&interceptor.cache_key(&request))
+		.unwrap();
+	assert_eq!(cached.ttl, Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_tags_header_is_merged_and_stripped() {
+	let cache = MurCache::in_memory();
+	let interceptor = MurCacheInterceptor::new(cache.clone()).tag("users");
+	let request = ctx("GET", "/users/1", &[]);
+	let response = interceptor
+		.store(&request, ok("u", &[("x-cache-tags", "user:1, profile")]))
+		.await
+		.into_result()
+		.unwrap();
+	assert!(!response.headers().contains_key("x-cache-tags"));
+
+	assert_eq!(cache.invalidate_tag("user:1"), 1);
+	assert!(cache.get(&interceptor.cache_key(&request)).is_none());
+}
+
+#[tokio::test]
+async fn test_no_cache_header_option() {
+	let interceptor = MurCacheInterceptor::new(MurCache::in_memory()).no_cache_header();
+	let request = ctx("GET", "/users", &[]);
+	let response = interceptor
+		.store(&request, ok("users", &[]))
+		.await
+		.into_result()
+		.unwrap();
+	assert!(response.headers().get("x-cache").is_none());
+}
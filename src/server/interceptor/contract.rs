@@ -1,9 +1,10 @@
-use crate::server::aliases::MurRes;
+use crate::server::aliases::{MurFuture, MurRes};
 use crate::server::error::MurError;
 use crate::server::http::MurRequestContext;
 use crate::{MurInjects, MurServiceContainer};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 
 /// Dependency-injection factory for [`MurInterceptor`] implementations.
 ///
@@ -16,19 +17,40 @@ pub trait MurInterceptorFactory: MurInterceptor {
 		Self: Sized;
 }
 
+/// Builds a route interceptor from the DI scope of the module that owns the
+/// route.
+///
+/// `#[use_interceptors(..)]` stores one provider per listed interceptor on
+/// each route definition; they are resolved when the module's controllers are
+/// registered.
+pub type MurInterceptorProvider =
+	fn(&MurInjects, &MurServiceContainer) -> Arc<dyn MurInterceptor + Send + Sync>;
+
+/// Returns the [`MurInterceptorProvider`] that builds `T` through its factory.
+pub fn mur_interceptor_provider<T: MurInterceptorFactory>() -> MurInterceptorProvider {
+	|injects, container| Arc::new(T::__create_factory(injects, container))
+}
+
 /// Middleware-like hook that wraps individual route handler invocations.
 ///
-/// `MurInterceptor` is called immediately before and after every matched
-/// route handler. Use it to implement cross-cutting concerns such as logging,
-/// metrics, response transformation, or rate-limiting at the handler level.
+/// `MurInterceptor` wraps every matched route handler, after the guards have
+/// passed. Use it to implement cross-cutting concerns such as logging,
+/// metrics, caching, retries or response transformation at the handler level.
 ///
 /// # Lifecycle
 ///
-/// 1. [`before`](MurInterceptor::before) — runs before the handler. Returning
-///    `Err` short-circuits execution and propagates the error.
-/// 2. The route handler executes.
-/// 3. [`after`](MurInterceptor::after) — receives the handler's response and
-///    can transform it before it is sent to the client.
+/// Interceptors nest: global interceptors wrap route interceptors, which wrap
+/// the handler. Each one's [`intercept`](MurInterceptor::intercept) receives
+/// the rest of the chain as a [`MurCallHandler`] and decides whether, when and
+/// how often to run it.
+///
+/// The default `intercept` adapts the simpler hooks:
+///
+/// 1. [`before`](MurInterceptor::before) — runs before the rest of the chain.
+///    Returning `Err` skips it and propagates the error.
+/// 2. The rest of the chain runs.
+/// 3. [`after`](MurInterceptor::after) — receives the response and can
+///    transform it before it is returned outwards.
 ///
 /// # Example
 ///
@@ -45,9 +67,50 @@ pub trait MurInterceptorFactory: MurInterceptor {
 ///         })
 ///     }
 /// }
+///
+/// #[interceptor]
+/// struct TimingInterceptor;
+///
+/// impl MurInterceptor for TimingInterceptor {
+///     fn intercept<'a>(
+///         &'a self,
+///         ctx: MurRequestContext,
+///         next: MurCallHandler,
+///     ) -> MurInterceptFuture<'a> {
+///         Box::pin(async move {
+///             let started = std::time::Instant::now();
+///             let response = next.run(ctx).await;
+///             println!("handled in {:?}", started.elapsed());
+///             response
+///         })
+///     }
+/// }
 /// ```
 pub trait MurInterceptor: Send + Sync + 'static {
-	/// Called before the route handler.
+	/// Wraps the rest of the interceptor chain and the handler.
+	///
+	/// Call `next.run(ctx)` to continue; skip it to answer the request
+	/// directly, or clone `next` to run it more than once. Errors returned as
+	/// `MurRes` go through the exception filters once the chain completes.
+	///
+	/// The default implementation runs [`before`](MurInterceptor::before),
+	/// then `next`, then [`after`](MurInterceptor::after).
+	fn intercept<'a>(
+		&'a self,
+		ctx: MurRequestContext,
+		next: MurCallHandler,
+	) -> MurInterceptFuture<'a> {
+		Box::pin(async move {
+			if let Err(e) = self.before(&ctx).await {
+				return MurRes::from(e);
+			}
+			let response = next.run(ctx.clone()).await;
+			self.after(&ctx, response).await
+		})
+	}
+
+	/// Called before the route handler by the default
+	/// [`intercept`](MurInterceptor::intercept).
 	///
 	/// Returning `Err` aborts execution and sends the error as the response.
 	/// The default implementation is a no-op.
@@ -55,7 +118,8 @@ pub trait MurInterceptor: Send + Sync + 'static {
 		Box::pin(async { Ok(()) })
 	}
 
-	/// Called after the route handler with its response.
+	/// Called after the route handler with its response by the default
+	/// [`intercept`](MurInterceptor::intercept).
 	///
 	/// The interceptor may transform the response before it is returned to
 	/// the client. The default implementation passes the response unchanged.
@@ -75,3 +139,25 @@ pub trait MurInterceptor: Send + Sync + 'static {
 
 /// Pinned boxed future returned by [`MurInterceptor::before`].
 pub type MurInterceptorFuture = Pin<Box<dyn Future<Output = Result<(), MurError>> + Send>>;
+
+/// Pinned boxed future returned by [`MurInterceptor::intercept`].
+pub type MurInterceptFuture<'a> = Pin<Box<dyn Future<Output = MurRes> + Send + 'a>>;
+
+/// The rest of the interceptor chain, ending with the route handler.
+///
+/// Cloning is cheap; each clone can be run independently, which lets an
+/// interceptor retry the handler.
+#[derive(Clone)]
+pub struct MurCallHandler {
+	pub(crate) handler: Arc<dyn Fn(MurRequestContext) -> MurFuture + Send + Sync>,
+}
+
+impl MurCallHandler {
+	pub fn new(handler: Arc<dyn Fn(MurRequestContext) -> MurFuture + Send + Sync>) -> Self {
+		Self { handler }
+	}
+
+	pub fn run(self, ctx: MurRequestContext) -> MurFuture {
+		(self.handler)(ctx)
+	}
+}
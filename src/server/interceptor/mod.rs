@@ -1,5 +1,10 @@
+pub mod cache;
 mod contract;
 
+pub use contract::MurCallHandler;
+pub use contract::MurInterceptFuture;
 pub use contract::MurInterceptor;
 pub use contract::MurInterceptorFactory;
 pub use contract::MurInterceptorFuture;
+pub use contract::MurInterceptorProvider;
+pub use contract::mur_interceptor_provider;
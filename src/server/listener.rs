@@ -0,0 +1,299 @@
+use super::builder::resolve_addr;
+use super::security::tls::MurTlsConfig;
+use std::fmt;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
+
+/// Where a [`MurListener`] accepts connections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MurListenAddr {
+	Tcp(SocketAddr),
+	/// A Unix domain socket, e.g. for a local sidecar. A stale socket file
+	/// left at the path is replaced on bind.
+	Unix(PathBuf),
+	/// A socket that is already bound and listening, inherited as a raw file
+	/// descriptor, e.g. through systemd socket activation. The listener
+	/// takes ownership of the descriptor when the server starts.
+	Inherited(i32),
+}
+
+impl fmt::Display for MurListenAddr {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			MurListenAddr::Tcp(addr) => write!(f, "{}", addr),
+			MurListenAddr::Unix(path) => write!(f, "unix:{}", path.display()),
+			MurListenAddr::Inherited(fd) => write!(f, "fd:{}", fd),
+		}
+	}
+}
+
+/// One socket a [`MurServerRunner`](crate::MurServerRunner) serves the
+/// router on, with its own TLS config, body limit and route scope.
+///
+/// ```rust,ignore
+/// MurServer::new()
+///     .module(AppModule::new())
+///     .listener(MurListener::https_redirect(("0.0.0.0", 80), 443)?)
+///     .listener(MurListener::tcp(("127.0.0.1", 9000))?.allow_routes(["/admin"]))
+///     .listener(MurListener::unix("/run/app.sock"))
+///     .bind_tls(("0.0.0.0", 443), tls)?
+///     .run()
+///     .await
+/// ```
+#[derive(Debug, Clone)]
+pub struct MurListener {
+	pub addr: MurListenAddr,
+	pub tls: Option<MurTlsConfig>,
+	/// Overrides the server-wide body limit for this listener.
+	pub body_limit: Option<usize>,
+	/// Path prefixes served here; empty serves every route.
+	pub allow_routes: Vec<String>,
+	/// Path prefixes answered with `404` here, checked after `allow_routes`.
+	pub deny_routes: Vec<String>,
+	/// When set, every request is answered with a `308` to HTTPS on this
+	/// port instead of being routed.
+	pub redirect_https: Option<u16>,
+	/// Binds with `SO_REUSEPORT`, so another process can listen on the same
+	/// address at the same time.
+	pub reuse_port: bool,
+}
+
+impl MurListener {
+	pub fn new(addr: MurListenAddr) -> Self {
+		Self {
+			addr,
+			tls: None,
+			body_limit: None,
+			allow_routes: Vec::new(),
+			deny_routes: Vec::new(),
+			redirect_https: None,
+			reuse_port: false,
+		}
+	}
+
+	/// A plain HTTP listener on `addr`.
+	pub fn tcp(addr: impl ToSocketAddrs) -> Result<Self, std::io::Error> {
+		Ok(Self::new(MurListenAddr::Tcp(resolve_addr(addr)?)))
+	}
+
+	/// An HTTPS listener on `addr`.
+	pub fn tls(addr: impl ToSocketAddrs, config: MurTlsConfig) -> Result<Self, std::io::Error> {
+		Ok(Self::tcp(addr)?.with_tls(config))
+	}
+
+	/// A plain HTTP listener on a Unix domain socket.
+	pub fn unix(path: impl Into<PathBuf>) -> Self {
+		Self::new(MurListenAddr::Unix(path.into()))
+	}
+
+	/// Serves a socket inherited as a raw file descriptor.
+	pub fn inherited(fd: i32) -> Self {
+		Self::new(MurListenAddr::Inherited(fd))
+	}
+
+	/// The sockets passed by systemd socket activation (`LISTEN_FDS`),
+	/// empty when the process was not socket activated.
+	///
+	/// ```rust,ignore
+	/// let inherited = MurListener::from_listen_fds()?;
+	/// let runner = if inherited.is_empty() {
+	///     server.bind(("0.0.0.0", 8080))?
+	/// } else {
+	///     server.bind_listeners(inherited)?
+	/// };
+	/// ```
+	pub fn from_listen_fds() -> Result<Vec<Self>, std::io::Error> {
+		listen_fds(
+			std::env::var("LISTEN_PID").ok().as_deref(),
+			std::env::var("LISTEN_FDS").ok().as_deref(),
+			std::process::id(),
+		)
+	}
+
+	/// A listener that answers every request with a `308 Permanent Redirect`
+	/// to the same host and path over HTTPS on `https_port`.
+	pub fn https_redirect(
+		addr: impl ToSocketAddrs,
+		https_port: u16,
+	) -> Result<Self, std::io::Error> {
+		let mut listener = Self::tcp(addr)?;
+		listener.redirect_https = Some(https_port);
+		Ok(listener)
+	}
+
+	pub fn with_tls(mut self, config: MurTlsConfig) -> Self {
+		self.tls = Some(config);
+		self
+	}
+
+	pub fn reuse_port(mut self) -> Self {
+		self.reuse_port = true;
+		self
+	}
+
+	pub fn body_limit(mut self, limit: usize) -> Self {
+		self.body_limit = Some(limit);
+		self
+	}
+
+	/// Only serves paths under these prefixes, e.g. an internal admin port.
+	pub fn allow_routes<I, S>(mut self, prefixes: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.allow_routes
+			.extend(prefixes.into_iter().map(Into::into));
+		self
+	}
+
+	/// Hides paths under these prefixes, e.g. admin routes on the public port.
+	pub fn deny_routes<I, S>(mut self, prefixes: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.deny_routes
+			.extend(prefixes.into_iter().map(Into::into));
+		self
+	}
+
+	/// Whether requests for `path` are routed on this listener. Empty
+	/// segments are ignored, as the router does, so `//admin` is `/admin`.
+	pub fn serves(&self, path: &str) -> bool {
+		let path = canonical(path);
+		let covers = |prefix: &String| under(&path, &canonical(prefix));
+		(self.allow_routes.is_empty() || self.allow_routes.iter().any(covers))
+			&& !self.deny_routes.iter().any(covers)
+	}
+
+	/// The `Location` a redirect listener sends for `host` and
+	/// `path_and_query`, dropping any port from `host`.
+	pub fn redirect_location(&self, host: &str, path_and_query: &str) -> Option<String> {
+		let port = self.redirect_https?;
+		let hostname = match host.rsplit_once(':') {
+			Some((name, port)) if port.bytes().all(|b| b.is_ascii_digit()) => name,
+			_ => host,
+		};
+		Some(match port {
+			443 => format!("https://{}{}", hostname, path_and_query),
+			port => format!("https://{}:{}{}", hostname, port, path_and_query),
+		})
+	}
+}
+
+/// The first descriptor systemd passes, after stdin, stdout and stderr.
+const LISTEN_FDS_START: i32 = 3;
+
+fn listen_fds(
+	pid: Option<&str>,
+	count: Option<&str>,
+	own_pid: u32,
+) -> Result<Vec<MurListener>, std::io::Error> {
+	// The variables are meant for the process systemd started, not for
+	// children that inherited the environment.
+	if pid.is_some_and(|pid| pid.trim().parse() != Ok(own_pid)) {
+		return Ok(Vec::new());
+	}
+	let Some(count) = count else {
+		return Ok(Vec::new());
+	};
+	let count: i32 = count.trim().parse().map_err(|_| {
+		std::io::Error::new(
+			std::io::ErrorKind::InvalidInput,
+			format!("Invalid LISTEN_FDS value: {}", count),
+		)
+	})?;
+	Ok((LISTEN_FDS_START..LISTEN_FDS_START + count)
+		.map(MurListener::inherited)
+		.collect())
+}
+
+fn canonical(path: &str) -> String {
+	let mut canonical = String::with_capacity(path.len());
+	for segment in path.split('/').filter(|s| !s.is_empty()) {
+		canonical.push('/');
+		canonical.push_str(segment);
+	}
+	canonical
+}
+
+fn under(path: &str, prefix: &str) -> bool {
+	let prefix = prefix.trim_end_matches('/');
+	prefix.is_empty()
+		|| path
+			.strip_prefix(prefix)
+			.is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_serves_route_prefixes() {
+		let admin = MurListener::unix("/tmp/admin.sock").allow_routes(["/admin/"]);
+		assert!(admin.serves("/admin"));
+		assert!(admin.serves("/admin/users"));
+		assert!(!admin.serves("/administrator"));
+		assert!(!admin.serves("/api"));
+
+		let public = MurListener::unix("/tmp/public.sock").deny_routes(["/admin"]);
+		assert!(public.serves("/api/orders"));
+		assert!(!public.serves("/admin/users"));
+		assert!(!public.serves("//admin/users/42"));
+		assert!(!public.serves("/admin//users"));
+
+		let doubled = MurListener::unix("/tmp/doubled.sock").deny_routes(["//admin/"]);
+		assert!(!doubled.serves("/admin"));
+
+		let everything = MurListener::unix("/tmp/app.sock");
+		assert!(everything.serves("/"));
+		assert!(everything.serves("/anything"));
+	}
+
+	#[test]
+	fn test_redirect_location() {
+		let listener = MurListener::https_redirect("127.0.0.1:0", 443).unwrap();
+		assert_eq!(
+			listener
+				.redirect_location("example.com:80", "/a?b=1")
+				.as_deref(),
+			Some("https://example.com/a?b=1")
+		);
+
+		let listener = MurListener::https_redirect("127.0.0.1:0", 8443).unwrap();
+		assert_eq!(
+			listener.redirect_location("example.com", "/").as_deref(),
+			Some("https://example.com:8443/")
+		);
+		assert_eq!(
+			MurListener::unix("/tmp/app.sock").redirect_location("example.com", "/"),
+			None
+		);
+	}
+
+	#[test]
+	fn test_listen_fds() {
+		let fds = |pid, count| {
+			listen_fds(pid, count, 42)
+				.unwrap()
+				.into_iter()
+				.map(|l| l.addr)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(
+			fds(Some("42"), Some("2")),
+			[MurListenAddr::Inherited(3), MurListenAddr::Inherited(4)]
+		);
+		assert_eq!(fds(None, Some("1")), [MurListenAddr::Inherited(3)]);
+		assert!(fds(Some("7"), Some("2")).is_empty());
+		assert!(fds(Some("42"), None).is_empty());
+		assert!(listen_fds(Some("42"), Some("two"), 42).is_err());
+	}
+
+	#[test]
+	fn test_bind_listeners_requires_one() {
+		assert!(crate::MurServer::new().bind_listeners([]).is_err());
+	}
+}
@@ -1 +1,32 @@
-// TODO: create log module
+//! Request-scoped values for log output.
+//!
+//! [`MurRequestId`](crate::server::middleware::request_id::MurRequestId)
+//! runs the rest of the chain inside [`mur_with_request_id`], so anything
+//! executed on the request's task can tag its output with
+//! [`mur_current_request_id`]. Tasks spawned with `tokio::spawn` do not
+//! inherit the scope; wrap them in [`mur_with_request_id`] to carry it over.
+
+use std::future::Future;
+
+tokio::task_local! {
+	static MUR_REQUEST_ID: String;
+}
+
+/// Runs `future` with `id` as the current request ID.
+pub async fn mur_with_request_id<F: Future>(id: String, future: F) -> F::Output {
+	MUR_REQUEST_ID.scope(id, future).await
+}
+
+/// The ID of the request being handled on this task, if any.
+pub fn mur_current_request_id() -> Option<String> {
+	MUR_REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Formats `message` with the current request ID, as used by the framework's
+/// own log lines: `[request_id=…] message`.
+pub fn mur_log_line(message: impl std::fmt::Display) -> String {
+	match mur_current_request_id() {
+		Some(id) => format!("[request_id={}] {}", id, message),
+		None => message.to_string(),
+	}
+}
@@ -0,0 +1,86 @@
+use super::registry::metric_name;
+use super::{MurCounter, MurGauge, MurMetrics};
+use crate::server::aliases::MurRouteHandler;
+use crate::server::controller::MurController;
+use crate::server::http::{MurHttpResponse, MurRequestContext};
+use crate::server::router::{MurRouteDefinition, MurRouteMetadata};
+use crate::server::runner::MurConnectionStats;
+use crate::server::service::MurServiceContainer;
+use std::sync::Arc;
+
+/// The content type of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Serves the scrape endpoint of [`MurMetricsModule`](super::MurMetricsModule).
+#[derive(Debug, Clone)]
+pub struct MurMetricsController {
+	metrics: MurMetrics,
+	path: String,
+	connections_active: MurGauge,
+	connections_accepted: MurCounter,
+}
+
+impl MurMetricsController {
+	pub fn new(metrics: MurMetrics, path: impl Into<String>, namespace: &str) -> Self {
+		let name = |name: &str| metric_name(namespace, name);
+		Self {
+			connections_active: metrics.gauge(
+				&name("connections_active"),
+				"Connections currently open.",
+				&[],
+			),
+			connections_accepted: metrics.counter(
+				&name("connections_accepted_total"),
+				"Connections accepted since the server started.",
+				&[],
+			),
+			metrics,
+			path: path.into(),
+		}
+	}
+
+	/// `GET path` renders every metric, refreshing the connection gauges
+	/// from the runner first.
+	fn scrape(&self) -> MurRouteHandler {
+		let this = self.clone();
+		Arc::new(move |ctx: MurRequestContext| {
+			let this = this.clone();
+			Box::pin(async move {
+				if let Some(stats) = ctx.service::<MurConnectionStats>() {
+					this.connections_active.set(&[], stats.active() as f64);
+					this.connections_accepted
+						.set_total(&[], stats.total() as f64);
+				}
+				MurHttpResponse::ok()
+					.content_type(CONTENT_TYPE)
+					.text(this.metrics.render())
+			})
+		})
+	}
+}
+
+impl MurController for MurMetricsController {
+	fn routes(self: Arc<Self>, _container: &MurServiceContainer) -> Vec<MurRouteDefinition> {
+		vec![MurRouteDefinition {
+			method: "GET".to_string(),
+			path: self.path.clone(),
+			handler: self.scrape(),
+			is_public: true,
+			allowed_roles: vec![],
+			throttle: None,
+			timeout: None,
+			metadata: MurRouteMetadata::new(),
+			guards: vec![],
+			interceptors: vec![],
+			versions: vec![],
+		}]
+	}
+
+	fn base_path(&self) -> &str {
+		""
+	}
+
+	fn name(&self) -> &str {
+		"MurMetricsController"
+	}
+}
@@ -0,0 +1,107 @@
+use super::registry::metric_name;
+use super::{MurCounter, MurHistogram, MurMetrics};
+use crate::server::aliases::MurFuture;
+use crate::server::http::MurRequestContext;
+use crate::server::middleware::rate_limit::headers::MurRateLimitRejection;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use std::time::Instant;
+
+/// The `route` label of requests that matched no route.
+pub const MUR_UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Records the built-in HTTP metrics of
+/// [`MurMetricsModule`](super::MurMetricsModule).
+///
+/// Requests are labeled with the method, the matched route pattern (not the
+/// raw path, which would make one series per id) and the status class.
+/// Methods outside the standard set are labeled `OTHER` for the same reason.
+#[derive(Clone)]
+pub struct MurMetricsMiddleware {
+	requests: MurCounter,
+	duration: MurHistogram,
+	rate_limited: MurCounter,
+}
+
+impl MurMetricsMiddleware {
+	pub fn new(metrics: &MurMetrics, namespace: &str, buckets: &[f64]) -> Self {
+		let name = |name: &str| metric_name(namespace, name);
+		let labels = ["method", "route", "status"];
+		Self {
+			requests: metrics.counter(
+				&name("http_requests_total"),
+				"HTTP requests handled.",
+				&labels,
+			),
+			duration: metrics.histogram(
+				&name("http_request_duration_seconds"),
+				"Time spent handling HTTP requests.",
+				&labels,
+				buckets,
+			),
+			rate_limited: metrics.counter(
+				&name("http_rate_limited_total"),
+				"HTTP requests rejected by a rate limit.",
+				&["route"],
+			),
+		}
+	}
+}
+
+impl MurMiddleware for MurMetricsMiddleware {
+	fn handle(&self, ctx: MurRequestContext, next: MurNext) -> MurFuture {
+		let this = self.clone();
+		Box::pin(async move {
+			let method = method_label(ctx.method().as_str());
+			let route = ctx
+				.route_pattern()
+				.unwrap_or(MUR_UNMATCHED_ROUTE)
+				.to_string();
+			let start = Instant::now();
+
+			let res = next.run(ctx).await;
+
+			let status = format!("{}xx", res.status_code().as_u16() / 100);
+			let labels = [method, route.as_str(), status.as_str()];
+			this.requests.inc(&labels);
+			this.duration
+				.observe(&labels, start.elapsed().as_secs_f64());
+			let rate_limited = res
+				.0
+				.as_ref()
+				.is_ok_and(|r| r.extensions().get::<MurRateLimitRejection>().is_some());
+			if rate_limited {
+				this.rate_limited.inc(&[route.as_str()]);
+			}
+			res
+		})
+	}
+
+	fn name(&self) -> &str {
+		"MurMetricsMiddleware"
+	}
+
+	fn needs_matched_route(&self) -> bool {
+		true
+	}
+}
+
+fn method_label(method: &str) -> &'static str {
+	match method {
+		"GET" => "GET",
+		"HEAD" => "HEAD",
+		"POST" => "POST",
+		"PUT" => "PUT",
+		"DELETE" => "DELETE",
+		"PATCH" => "PATCH",
+		"OPTIONS" => "OPTIONS",
+		"CONNECT" => "CONNECT",
+		"TRACE" => "TRACE",
+		_ => "OTHER",
+	}
+}
+
+impl std::fmt::Debug for MurMetricsMiddleware {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurMetricsMiddleware").finish()
+	}
+}
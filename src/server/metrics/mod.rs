@@ -0,0 +1,12 @@
+pub mod controller;
+pub mod middleware;
+pub mod module;
+pub mod registry;
+
+pub use controller::MurMetricsController;
+pub use middleware::{MUR_UNMATCHED_ROUTE, MurMetricsMiddleware};
+pub use module::{MurMetricsConfig, MurMetricsModule};
+pub use registry::{MUR_DEFAULT_BUCKETS, MurCounter, MurGauge, MurHistogram, MurMetrics};
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,137 @@
+use super::{MUR_DEFAULT_BUCKETS, MurMetrics, MurMetricsController, MurMetricsMiddleware};
+use crate::server::controller::MurController;
+use crate::server::middleware::MurMiddleware;
+use crate::server::module::{MurModule, MurModuleConfig};
+use crate::server::service::MurService;
+use std::any::TypeId;
+use std::sync::Arc;
+
+/// Options of [`MurMetricsModule`].
+#[derive(Debug, Clone)]
+pub struct MurMetricsConfig {
+	/// The scrape endpoint, `/metrics` by default.
+	pub path: String,
+	/// Prefix of the built-in metric names, `murgamu` by default.
+	pub namespace: String,
+	/// Buckets of the request latency histogram, in seconds.
+	pub buckets: Vec<f64>,
+}
+
+impl Default for MurMetricsConfig {
+	fn default() -> Self {
+		Self {
+			path: "/metrics".to_string(),
+			namespace: "murgamu".to_string(),
+			buckets: MUR_DEFAULT_BUCKETS.to_vec(),
+		}
+	}
+}
+
+/// Prometheus metrics, scraped from `GET /metrics`.
+///
+/// Records request counts and latencies by method, route pattern and status
+/// class, rate-limit rejections, and open and accepted connections. The
+/// module is global: its [`MurMetrics`] registry can be injected into the
+/// services of any module for application metrics. Register it with
+/// [`module_in_place`](crate::MurServer::module_in_place) ahead of any
+/// rate-limiting middleware so rejected requests are counted too.
+///
+/// ```rust,ignore
+/// MurServer::new()
+///     .module_in_place(MurMetricsModule::new().namespace("shop"))
+///     .middleware(MurThrottler::new().requests(100).per_minutes(1))
+///     .module(AppModule::new())
+/// ```
+#[derive(Debug, Clone)]
+pub struct MurMetricsModule {
+	config: MurMetricsConfig,
+	metrics: MurMetrics,
+}
+
+impl MurMetricsModule {
+	pub fn new() -> Self {
+		Self::with_options(MurMetricsConfig::default())
+	}
+
+	pub fn path(mut self, path: impl Into<String>) -> Self {
+		self.config.path = path.into();
+		self
+	}
+
+	/// Prefixes the built-in metric names; empty for none.
+	pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+		self.config.namespace = namespace.into();
+		self
+	}
+
+	pub fn buckets(mut self, buckets: impl Into<Vec<f64>>) -> Self {
+		self.config.buckets = buckets.into();
+		self
+	}
+
+	/// Records into `metrics` instead of a fresh registry.
+	pub fn registry(mut self, metrics: MurMetrics) -> Self {
+		self.metrics = metrics;
+		self
+	}
+
+	/// The registry the module records into and serves.
+	pub fn metrics(&self) -> MurMetrics {
+		self.metrics.clone()
+	}
+}
+
+impl Default for MurMetricsModule {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MurModuleConfig for MurMetricsModule {
+	type Options = MurMetricsConfig;
+
+	fn with_options(options: MurMetricsConfig) -> Self {
+		Self {
+			config: options,
+			metrics: MurMetrics::new(),
+		}
+	}
+}
+
+impl MurModule for MurMetricsModule {
+	fn controllers(&self) -> Vec<Arc<dyn MurController>> {
+		vec![Arc::new(MurMetricsController::new(
+			self.metrics(),
+			&self.config.path,
+			&self.config.namespace,
+		))]
+	}
+
+	fn services(&self) -> Vec<(TypeId, Arc<dyn MurService>)> {
+		vec![(TypeId::of::<MurMetrics>(), Arc::new(self.metrics()))]
+	}
+
+	fn middleware(&self) -> Vec<Box<dyn MurMiddleware + Send + Sync>> {
+		vec![Box::new(MurMetricsMiddleware::new(
+			&self.metrics,
+			&self.config.namespace,
+			&self.config.buckets,
+		))]
+	}
+
+	fn is_global(&self) -> bool {
+		true
+	}
+
+	fn name(&self) -> &str {
+		"MurMetricsModule"
+	}
+
+	fn exports(&self) -> Vec<TypeId> {
+		vec![TypeId::of::<MurMetrics>()]
+	}
+
+	fn imports(&self) -> Vec<Arc<dyn MurModule>> {
+		Vec::new()
+	}
+}
@@ -0,0 +1,375 @@
+use crate::server::service::MurService;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Latency buckets, in seconds, used when a histogram is given none.
+pub const MUR_DEFAULT_BUCKETS: &[f64] = &[
+	0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// A registry of counters, gauges and histograms rendered in the
+/// Prometheus text exposition format.
+///
+/// Clones share the same metrics. [`MurMetricsModule`](super::MurMetricsModule)
+/// registers one as a service, so user services can add their own:
+///
+/// ```rust,ignore
+/// let metrics = ctx.service_required::<MurMetrics>();
+/// let sent = metrics.counter("emails_sent_total", "Emails sent", &["template"]);
+/// sent.inc(&["welcome"]);
+/// ```
+///
+/// Registering a name twice returns the metric registered first.
+///
+/// # Panics
+///
+/// Registering panics when the name is not a valid metric name, or is taken
+/// by a metric of another type or with other labels. Recording panics when
+/// the number of label values does not match the label names.
+#[derive(Clone, Default)]
+pub struct MurMetrics {
+	families: Arc<RwLock<Vec<Arc<Family>>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+	Counter,
+	Gauge,
+	Histogram,
+}
+
+impl Kind {
+	fn as_str(self) -> &'static str {
+		match self {
+			Kind::Counter => "counter",
+			Kind::Gauge => "gauge",
+			Kind::Histogram => "histogram",
+		}
+	}
+}
+
+struct Family {
+	name: String,
+	help: String,
+	kind: Kind,
+	labels: Vec<String>,
+	buckets: Vec<f64>,
+	series: Mutex<BTreeMap<Vec<String>, Series>>,
+}
+
+#[derive(Default)]
+struct Series {
+	value: f64,
+	bucket_counts: Vec<u64>,
+	count: u64,
+}
+
+impl Family {
+	fn update(&self, values: &[&str], f: impl FnOnce(&mut Series)) {
+		assert_eq!(
+			values.len(),
+			self.labels.len(),
+			"metric {} expects labels {:?}",
+			self.name,
+			self.labels
+		);
+		let key = values.iter().map(|v| v.to_string()).collect();
+		let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+		f(series.entry(key).or_default());
+	}
+
+	fn value(&self, values: &[&str]) -> f64 {
+		let key: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+		let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+		series.get(&key).map_or(0.0, |s| s.value)
+	}
+
+	fn render(&self, out: &mut String) {
+		let _ = writeln!(out, "# HELP {} {}", self.name, escape_help(&self.help));
+		let _ = writeln!(out, "# TYPE {} {}", self.name, self.kind.as_str());
+
+		let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+		for (values, series) in series.iter() {
+			let labels = self.label_pairs(values);
+			if self.kind != Kind::Histogram {
+				let value = number(series.value);
+				let _ = writeln!(out, "{}{} {}", self.name, braces(&labels), value);
+				continue;
+			}
+
+			let mut cumulative = 0;
+			for (bound, count) in self.buckets.iter().zip(&series.bucket_counts) {
+				cumulative += count;
+				let le = join(&labels, &format!("le=\"{}\"", number(*bound)));
+				let _ = writeln!(out, "{}_bucket{{{}}} {}", self.name, le, cumulative);
+			}
+			let le = join(&labels, "le=\"+Inf\"");
+			let _ = writeln!(out, "{}_bucket{{{}}} {}", self.name, le, series.count);
+			let sum = number(series.value);
+			let _ = writeln!(out, "{}_sum{} {}", self.name, braces(&labels), sum);
+			let _ = writeln!(
+				out,
+				"{}_count{} {}",
+				self.name,
+				braces(&labels),
+				series.count
+			);
+		}
+	}
+
+	fn label_pairs(&self, values: &[String]) -> String {
+		self.labels
+			.iter()
+			.zip(values)
+			.map(|(name, value)| format!("{}=\"{}\"", name, escape_label(value)))
+			.collect::<Vec<_>>()
+			.join(",")
+	}
+}
+
+impl std::fmt::Debug for Family {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Family")
+			.field("name", &self.name)
+			.field("kind", &self.kind)
+			.field("labels", &self.labels)
+			.finish()
+	}
+}
+
+impl MurMetrics {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// A monotonically increasing counter, e.g. `jobs_processed_total`.
+	pub fn counter(&self, name: &str, help: &str, labels: &[&str]) -> MurCounter {
+		MurCounter(self.register(name, help, Kind::Counter, labels, &[]))
+	}
+
+	/// A value that goes up and down, e.g. `queue_depth`.
+	pub fn gauge(&self, name: &str, help: &str, labels: &[&str]) -> MurGauge {
+		MurGauge(self.register(name, help, Kind::Gauge, labels, &[]))
+	}
+
+	/// Observations counted into `buckets` (upper bounds, ascending), or
+	/// [`MUR_DEFAULT_BUCKETS`] when empty.
+	pub fn histogram(
+		&self,
+		name: &str,
+		help: &str,
+		labels: &[&str],
+		buckets: &[f64],
+	) -> MurHistogram {
+		let buckets = if buckets.is_empty() {
+			MUR_DEFAULT_BUCKETS
+		} else {
+			buckets
+		};
+		MurHistogram(self.register(name, help, Kind::Histogram, labels, buckets))
+	}
+
+	/// Every metric in the Prometheus text exposition format, in
+	/// registration order.
+	pub fn render(&self) -> String {
+		let mut out = String::new();
+		let families = self.families.read().unwrap_or_else(|e| e.into_inner());
+		for family in families.iter() {
+			family.render(&mut out);
+		}
+		out
+	}
+
+	fn register(
+		&self,
+		name: &str,
+		help: &str,
+		kind: Kind,
+		labels: &[&str],
+		buckets: &[f64],
+	) -> Arc<Family> {
+		assert!(valid_name(name), "invalid metric name {:?}", name);
+		for label in labels {
+			assert!(
+				valid_name(label) && !label.contains(':') && *label != "le",
+				"invalid label name {:?} for metric {}",
+				label,
+				name
+			);
+		}
+
+		let mut families = self.families.write().unwrap_or_else(|e| e.into_inner());
+		if let Some(family) = families.iter().find(|f| f.name == name) {
+			assert!(
+				family.kind == kind && family.labels == labels,
+				"metric {} is already registered as a {} with labels {:?}",
+				name,
+				family.kind.as_str(),
+				family.labels
+			);
+			return Arc::clone(family);
+		}
+
+		let mut buckets = buckets.to_vec();
+		buckets.sort_by(f64::total_cmp);
+		buckets.dedup();
+		let family = Arc::new(Family {
+			name: name.to_string(),
+			help: help.to_string(),
+			kind,
+			labels: labels.iter().map(|l| l.to_string()).collect(),
+			buckets,
+			series: Mutex::new(BTreeMap::new()),
+		});
+		families.push(Arc::clone(&family));
+		family
+	}
+}
+
+impl MurService for MurMetrics {
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl std::fmt::Debug for MurMetrics {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let families = self.families.read().unwrap_or_else(|e| e.into_inner());
+		f.debug_struct("MurMetrics")
+			.field(
+				"metrics",
+				&families.iter().map(|f| &f.name).collect::<Vec<_>>(),
+			)
+			.finish()
+	}
+}
+
+/// A counter registered with [`MurMetrics::counter`].
+#[derive(Debug, Clone)]
+pub struct MurCounter(Arc<Family>);
+
+impl MurCounter {
+	/// Overwrites the total with a count kept elsewhere.
+	pub(crate) fn set_total(&self, labels: &[&str], total: f64) {
+		self.0.update(labels, |s| s.value = total);
+	}
+
+	pub fn inc(&self, labels: &[&str]) {
+		self.inc_by(labels, 1.0);
+	}
+
+	/// Adds `amount`; negative amounts are ignored, counters never go down.
+	pub fn inc_by(&self, labels: &[&str], amount: f64) {
+		if amount >= 0.0 {
+			self.0.update(labels, |s| s.value += amount);
+		}
+	}
+
+	pub fn get(&self, labels: &[&str]) -> f64 {
+		self.0.value(labels)
+	}
+}
+
+/// A gauge registered with [`MurMetrics::gauge`].
+#[derive(Debug, Clone)]
+pub struct MurGauge(Arc<Family>);
+
+impl MurGauge {
+	pub fn set(&self, labels: &[&str], value: f64) {
+		self.0.update(labels, |s| s.value = value);
+	}
+
+	pub fn add(&self, labels: &[&str], amount: f64) {
+		self.0.update(labels, |s| s.value += amount);
+	}
+
+	pub fn inc(&self, labels: &[&str]) {
+		self.add(labels, 1.0);
+	}
+
+	pub fn dec(&self, labels: &[&str]) {
+		self.add(labels, -1.0);
+	}
+
+	pub fn get(&self, labels: &[&str]) -> f64 {
+		self.0.value(labels)
+	}
+}
+
+/// A histogram registered with [`MurMetrics::histogram`].
+#[derive(Debug, Clone)]
+pub struct MurHistogram(Arc<Family>);
+
+impl MurHistogram {
+	pub fn observe(&self, labels: &[&str], value: f64) {
+		let buckets = &self.0.buckets;
+		self.0.update(labels, |s| {
+			if s.bucket_counts.is_empty() {
+				s.bucket_counts = vec![0; buckets.len()];
+			}
+			if let Some(i) = buckets.iter().position(|bound| value <= *bound) {
+				s.bucket_counts[i] += 1;
+			}
+			s.value += value;
+			s.count += 1;
+		});
+	}
+
+	/// The number of observations and their sum.
+	pub fn get(&self, labels: &[&str]) -> (u64, f64) {
+		let key: Vec<String> = labels.iter().map(|v| v.to_string()).collect();
+		let series = self.0.series.lock().unwrap_or_else(|e| e.into_inner());
+		series.get(&key).map_or((0, 0.0), |s| (s.count, s.value))
+	}
+}
+
+fn valid_name(name: &str) -> bool {
+	let mut chars = name.chars();
+	chars
+		.next()
+		.is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+		&& chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+/// `name` prefixed with `namespace`, e.g. `murgamu_http_requests_total`.
+pub(crate) fn metric_name(namespace: &str, name: &str) -> String {
+	match namespace {
+		"" => name.to_string(),
+		namespace => format!("{}_{}", namespace, name),
+	}
+}
+
+/// Formats a sample value; Prometheus spells infinities `+Inf` and `-Inf`.
+fn number(value: f64) -> String {
+	match value {
+		f64::INFINITY => "+Inf".to_string(),
+		f64::NEG_INFINITY => "-Inf".to_string(),
+		value => value.to_string(),
+	}
+}
+
+fn escape_help(help: &str) -> String {
+	help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label(value: &str) -> String {
+	escape_help(value).replace('"', "\\\"")
+}
+
+fn braces(labels: &str) -> String {
+	if labels.is_empty() {
+		String::new()
+	} else {
+		format!("{{{}}}", labels)
+	}
+}
+
+fn join(labels: &str, extra: &str) -> String {
+	if labels.is_empty() {
+		extra.to_string()
+	} else {
+		format!("{},{}", labels, extra)
+	}
+}
@@ -0,0 +1,151 @@
+use super::*;
+use crate::server::aliases::MurFuture;
+use crate::server::http::{MurHttpResponse, MurRequestContext};
+use crate::server::middleware::rate_limit::headers::mur_rate_limited;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use crate::server::service::MurServiceContainer;
+use http::StatusCode;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn ctx(method: &str) -> MurRequestContext {
+	let (parts, _) = http::Request::builder()
+		.method(method)
+		.uri("/missing")
+		.body(())
+		.unwrap()
+		.into_parts();
+	MurRequestContext::new(
+		parts,
+		None,
+		HashMap::new(),
+		Arc::new(MurServiceContainer::new()),
+	)
+}
+
+fn respond(status: StatusCode) -> MurNext {
+	MurNext::new(Arc::new(move |_ctx| -> MurFuture {
+		Box::pin(async move { MurHttpResponse::status(status).text("") })
+	}))
+}
+
+fn throttled() -> MurNext {
+	MurNext::new(Arc::new(|_ctx| -> MurFuture {
+		Box::pin(async { mur_rate_limited(StatusCode::TOO_MANY_REQUESTS, "slow down", 1, None) })
+	}))
+}
+
+#[test]
+fn test_render_counter_and_gauge() {
+	let metrics = MurMetrics::new();
+	let jobs = metrics.counter("jobs_total", "Jobs done.", &["queue"]);
+	jobs.inc(&["mail"]);
+	jobs.inc_by(&["mail"], 2.0);
+	jobs.inc_by(&["mail"], -5.0);
+	jobs.inc(&["say \"hi\"\n"]);
+
+	let depth = metrics.gauge("queue_depth", "Jobs\nwaiting.", &[]);
+	depth.set(&[], 4.0);
+	depth.dec(&[]);
+
+	assert_eq!(jobs.get(&["mail"]), 3.0);
+	assert_eq!(depth.get(&[]), 3.0);
+	assert_eq!(
+		metrics.render(),
+		"# HELP jobs_total Jobs done.\n\
+		 # TYPE jobs_total counter\n\
+		 jobs_total{queue=\"mail\"} 3\n\
+		 jobs_total{queue=\"say \\\"hi\\\"\\n\"} 1\n\
+		 # HELP queue_depth Jobs\\nwaiting.\n\
+		 # TYPE queue_depth gauge\n\
+		 queue_depth 3\n"
+	);
+}
+
+#[test]
+fn test_render_histogram_buckets_are_cumulative() {
+	let metrics = MurMetrics::new();
+	let latency = metrics.histogram("latency_seconds", "Latency.", &["op"], &[1.0, 0.5]);
+	latency.observe(&["read"], 0.2);
+	latency.observe(&["read"], 0.7);
+	latency.observe(&["read"], 3.0);
+
+	assert_eq!(latency.get(&["read"]), (3, 3.9));
+	assert_eq!(
+		metrics.render(),
+		"# HELP latency_seconds Latency.\n\
+		 # TYPE latency_seconds histogram\n\
+		 latency_seconds_bucket{op=\"read\",le=\"0.5\"} 1\n\
+		 latency_seconds_bucket{op=\"read\",le=\"1\"} 2\n\
+		 latency_seconds_bucket{op=\"read\",le=\"+Inf\"} 3\n\
+		 latency_seconds_sum{op=\"read\"} 3.9\n\
+		 latency_seconds_count{op=\"read\"} 3\n"
+	);
+}
+
+#[test]
+fn test_registering_twice_shares_the_metric() {
+	let metrics = MurMetrics::new();
+	metrics
+		.counter("hits_total", "Hits.", &["page"])
+		.inc(&["home"]);
+	metrics
+		.clone()
+		.counter("hits_total", "Hits.", &["page"])
+		.inc(&["home"]);
+
+	let hits = metrics.counter("hits_total", "Hits.", &["page"]);
+	assert_eq!(hits.get(&["home"]), 2.0);
+	assert_eq!(metrics.render().matches("# TYPE").count(), 1);
+}
+
+#[test]
+#[should_panic(expected = "already registered as a counter")]
+fn test_conflicting_registration_panics() {
+	let metrics = MurMetrics::new();
+	metrics.counter("hits_total", "Hits.", &[]);
+	metrics.gauge("hits_total", "Hits.", &[]);
+}
+
+#[test]
+#[should_panic(expected = "invalid metric name")]
+fn test_invalid_name_panics() {
+	MurMetrics::new().counter("2xx-hits", "Hits.", &[]);
+}
+
+#[tokio::test]
+async fn test_middleware_records_requests_and_rejections() {
+	let metrics = MurMetrics::new();
+	let middleware = MurMetricsMiddleware::new(&metrics, "app", &[0.1]);
+	assert!(middleware.needs_matched_route());
+
+	middleware.handle(ctx("GET"), respond(StatusCode::OK)).await;
+	middleware
+		.handle(ctx("GET"), respond(StatusCode::NOT_FOUND))
+		.await;
+	middleware.handle(ctx("POST"), throttled()).await;
+	middleware
+		.handle(ctx("BREW"), respond(StatusCode::OK))
+		.await;
+
+	let requests = metrics.counter(
+		"app_http_requests_total",
+		"",
+		&["method", "route", "status"],
+	);
+	assert_eq!(requests.get(&["GET", MUR_UNMATCHED_ROUTE, "2xx"]), 1.0);
+	assert_eq!(requests.get(&["GET", MUR_UNMATCHED_ROUTE, "4xx"]), 1.0);
+	assert_eq!(requests.get(&["POST", MUR_UNMATCHED_ROUTE, "4xx"]), 1.0);
+	assert_eq!(requests.get(&["OTHER", MUR_UNMATCHED_ROUTE, "2xx"]), 1.0);
+
+	let rejected = metrics.counter("app_http_rate_limited_total", "", &["route"]);
+	assert_eq!(rejected.get(&[MUR_UNMATCHED_ROUTE]), 1.0);
+
+	let duration = metrics.histogram(
+		"app_http_request_duration_seconds",
+		"",
+		&["method", "route", "status"],
+		&[],
+	);
+	assert_eq!(duration.get(&["GET", MUR_UNMATCHED_ROUTE, "2xx"]).0, 1);
+}
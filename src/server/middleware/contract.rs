@@ -8,6 +8,19 @@ pub trait MurMiddleware: Send + Sync + 'static {
 	fn name(&self) -> &str {
 		std::any::type_name::<Self>()
 	}
+
+	/// Whether the middleware reads the matched route, e.g. through
+	/// [`MurRequestContext::route_pattern`]. When any does, requests are
+	/// routed before the middleware chain runs.
+	fn needs_matched_route(&self) -> bool {
+		false
+	}
+
+	/// Names of middleware that must run ahead of this one. Binding fails when
+	/// one is missing or registered after it.
+	fn requires(&self) -> &[&str] {
+		&[]
+	}
 }
 
 pub struct MurNext {
@@ -0,0 +1,50 @@
+/// Where [`MurCsrf`](super::MurCsrf) keeps the token requests must echo.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MurCsrfMode {
+	/// The token lives in a cookie, and unsafe requests must repeat it in a
+	/// header or form field. Stateless; the cookie is signed when the server
+	/// has cookie keys.
+	#[default]
+	DoubleSubmit,
+	/// The token lives in the session. Requires `MurSessionModule`,
+	/// registered before `MurCsrf`.
+	Synchronizer,
+}
+
+#[derive(Debug, Clone)]
+pub struct MurCsrfConfig {
+	pub mode: MurCsrfMode,
+	/// The double-submit cookie.
+	pub cookie_name: String,
+	/// The header scripts send the token in.
+	pub header_name: String,
+	/// The form field HTML forms send the token in.
+	pub field_name: String,
+	/// The session key of synchronizer tokens.
+	pub session_key: String,
+	/// Origins other than the server's own allowed to send unsafe requests,
+	/// such as `https://admin.example.com`.
+	pub trusted_origins: Vec<String>,
+	/// Accept requests from another origin of the same site, as reported by
+	/// `Sec-Fetch-Site` or, without it, judged from `Origin` or `Referer`.
+	pub allow_same_site: bool,
+	/// Reject unsafe requests carrying neither `Origin` nor `Referer`.
+	pub require_origin: bool,
+	pub secure_cookie: bool,
+}
+
+impl Default for MurCsrfConfig {
+	fn default() -> Self {
+		Self {
+			mode: MurCsrfMode::default(),
+			cookie_name: "mur.csrf".to_string(),
+			header_name: "X-CSRF-Token".to_string(),
+			field_name: "_csrf".to_string(),
+			session_key: "_csrf".to_string(),
+			trusted_origins: Vec::new(),
+			allow_same_site: false,
+			require_origin: false,
+			secure_cookie: false,
+		}
+	}
+}
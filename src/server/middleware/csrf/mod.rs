@@ -0,0 +1,12 @@
+pub mod config;
+pub mod mur_csrf;
+pub mod token;
+
+pub use config::MurCsrfConfig;
+pub use config::MurCsrfMode;
+pub use mur_csrf::MurCsrf;
+pub use mur_csrf::MurCsrfExempt;
+pub use token::MurCsrfToken;
+
+#[cfg(test)]
+mod test;
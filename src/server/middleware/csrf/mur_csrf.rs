@@ -0,0 +1,308 @@
+use super::{MurCsrfConfig, MurCsrfMode, MurCsrfToken};
+use crate::core::utils::MurRand;
+use crate::server::aliases::MurFuture;
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::http::response::MurCookie;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use crate::server::router::strip_port;
+use aws_lc_rs::constant_time::verify_slices_are_equal;
+use http::{Method, Uri};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// Route metadata exempting a route from CSRF verification, set with
+/// `#[skip_csrf]` or `MurRouteBuilder::skip_csrf`.
+#[derive(Debug, Clone, Copy)]
+pub struct MurCsrfExempt;
+
+/// Rejects cross-site requests that change state.
+///
+/// Unsafe requests (anything but `GET`, `HEAD`, `OPTIONS` and `TRACE`) must
+/// come from the server's own origin or a trusted one, judged by
+/// `Sec-Fetch-Site`, `Origin` and `Referer`, and must echo the request's
+/// [`MurCsrfToken`] in the `X-CSRF-Token` header or the `_csrf` form field.
+/// Failures are answered with `403 Forbidden`. Synchronizer tokens live in the
+/// session, so add the session module with
+/// [`module_in_place`](crate::MurServer::module_in_place) ahead of this;
+/// binding fails otherwise.
+///
+/// ```rust,ignore
+/// MurServer::new()
+///     .module_in_place(MurSessionModule::new())
+///     .middleware(MurCsrf::synchronizer().trust_origin("https://admin.example.com"))
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MurCsrf {
+	config: Arc<MurCsrfConfig>,
+}
+
+impl MurCsrf {
+	/// Double-submit cookie tokens.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn from_config(config: MurCsrfConfig) -> Self {
+		Self {
+			config: Arc::new(config),
+		}
+	}
+
+	/// Session-stored tokens; see [`MurCsrfMode::Synchronizer`].
+	pub fn synchronizer() -> Self {
+		Self::new().mode(MurCsrfMode::Synchronizer)
+	}
+
+	pub fn mode(mut self, mode: MurCsrfMode) -> Self {
+		Arc::make_mut(&mut self.config).mode = mode;
+		self
+	}
+
+	pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+		Arc::make_mut(&mut self.config).cookie_name = name.into();
+		self
+	}
+
+	pub fn header_name(mut self, name: impl Into<String>) -> Self {
+		Arc::make_mut(&mut self.config).header_name = name.into();
+		self
+	}
+
+	pub fn field_name(mut self, name: impl Into<String>) -> Self {
+		Arc::make_mut(&mut self.config).field_name = name.into();
+		self
+	}
+
+	/// Allows unsafe requests from `origin`, such as
+	/// `https://admin.example.com`.
+	pub fn trust_origin(mut self, origin: impl Into<String>) -> Self {
+		let origin = origin.into().trim_end_matches('/').to_string();
+		Arc::make_mut(&mut self.config).trusted_origins.push(origin);
+		self
+	}
+
+	/// Accepts requests from other origins of the same site, such as a
+	/// sibling subdomain.
+	pub fn allow_same_site(mut self) -> Self {
+		Arc::make_mut(&mut self.config).allow_same_site = true;
+		self
+	}
+
+	/// Rejects unsafe requests that carry neither `Origin` nor `Referer`.
+	pub fn require_origin(mut self) -> Self {
+		Arc::make_mut(&mut self.config).require_origin = true;
+		self
+	}
+
+	/// Sends the double-submit cookie over HTTPS only.
+	pub fn secure_cookie(mut self) -> Self {
+		Arc::make_mut(&mut self.config).secure_cookie = true;
+		self
+	}
+
+	pub fn is_safe_method(method: &Method) -> bool {
+		matches!(
+			*method,
+			Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+		)
+	}
+
+	/// The request's token, and whether it is new and must be sent as a
+	/// cookie.
+	fn token(&self, ctx: &MurRequestContext) -> Result<(String, bool), MurError> {
+		match self.config.mode {
+			MurCsrfMode::DoubleSubmit => {
+				if let Some(token) = self.cookie_token(ctx) {
+					return Ok((token, false));
+				}
+				let token = MurRand::secure_token(32);
+				let token = match ctx.cookie_keys() {
+					Some(keys) => keys.sign(&self.config.cookie_name, &token),
+					None => token,
+				};
+				Ok((token, true))
+			}
+			MurCsrfMode::Synchronizer => {
+				let session = ctx.session().ok_or_else(|| {
+					MurError::internal("MurCsrf synchronizer tokens need MurSessionModule")
+				})?;
+				if let Some(token) = session.get::<String>(&self.config.session_key) {
+					return Ok((token, false));
+				}
+				let token = MurRand::secure_token(32);
+				session.insert(self.config.session_key.clone(), &token)?;
+				Ok((token, false))
+			}
+		}
+	}
+
+	/// The double-submit cookie, when present and, with cookie keys, signed
+	/// by the server.
+	fn cookie_token(&self, ctx: &MurRequestContext) -> Option<String> {
+		let value = ctx.cookie(&self.config.cookie_name)?;
+		if value.is_empty() {
+			return None;
+		}
+		if let Some(keys) = ctx.cookie_keys() {
+			keys.verify(&self.config.cookie_name, value)?;
+		}
+		Some(value.to_string())
+	}
+
+	/// Checks that the request comes from the server's own origin or a
+	/// trusted one, or with [`allow_same_site`](Self::allow_same_site) from
+	/// another origin of the same site.
+	pub fn verify_origin(&self, ctx: &MurRequestContext) -> Result<(), MurError> {
+		let origin = match ctx.header("origin") {
+			Some("null") => return Err(MurError::forbidden("Cross-origin request rejected")),
+			Some(origin) => Some(origin.trim_end_matches('/').to_string()),
+			None => ctx.header("referer").and_then(referer_origin),
+		};
+		let trusted = origin
+			.as_deref()
+			.is_some_and(|origin| self.config.trusted_origins.iter().any(|o| o == origin));
+
+		let fetch_site = ctx.header("sec-fetch-site");
+		let fetch_site_ok = match fetch_site {
+			None | Some("same-origin") | Some("none") => true,
+			Some("same-site") => self.config.allow_same_site,
+			Some(_) => false,
+		};
+		if !fetch_site_ok && !trusted {
+			return Err(MurError::forbidden("Cross-site request rejected"));
+		}
+
+		let host = ctx.header("host");
+		let allowed = |origin: &str| {
+			is_same_host(origin, host)
+				|| (self.config.allow_same_site
+					&& fetch_site.map_or_else(|| is_same_site(origin, host), |s| s == "same-site"))
+		};
+		match origin {
+			Some(origin) if !trusted && !allowed(&origin) => {
+				Err(MurError::forbidden("Cross-origin request rejected"))
+			}
+			None if self.config.require_origin => {
+				Err(MurError::forbidden("Request origin could not be verified"))
+			}
+			_ => Ok(()),
+		}
+	}
+
+	/// Checks that the request echoes `expected` in the header or form field.
+	pub fn verify_token(&self, ctx: &MurRequestContext, expected: &str) -> Result<(), MurError> {
+		let submitted = match ctx.header(&self.config.header_name) {
+			Some(token) => Some(token.to_string()),
+			None if ctx.is_form() => ctx.body.as_ref().and_then(|body| {
+				serde_urlencoded::from_bytes::<HashMap<String, String>>(body)
+					.ok()?
+					.remove(&self.config.field_name)
+			}),
+			None => None,
+		};
+
+		match submitted {
+			Some(token)
+				if verify_slices_are_equal(token.as_bytes(), expected.as_bytes()).is_ok() =>
+			{
+				Ok(())
+			}
+			Some(_) => Err(MurError::forbidden("Invalid CSRF token")),
+			None => Err(MurError::forbidden("Missing CSRF token")),
+		}
+	}
+
+	fn cookie(&self, token: &str) -> MurCookie {
+		let cookie = MurCookie::new(&self.config.cookie_name, token).path("/");
+		if self.config.secure_cookie {
+			cookie.secure()
+		} else {
+			cookie
+		}
+	}
+}
+
+impl MurMiddleware for MurCsrf {
+	fn handle(&self, mut ctx: MurRequestContext, next: MurNext) -> MurFuture {
+		let this = self.clone();
+		Box::pin(async move {
+			let (token, is_new) = match this.token(&ctx) {
+				Ok(token) => token,
+				Err(e) => return e.into(),
+			};
+
+			let exempt = ctx.parts.extensions.get::<MurCsrfExempt>().is_some();
+			if !exempt && !Self::is_safe_method(ctx.method()) {
+				let verified = this.verify_origin(&ctx).and_then(|_| {
+					// A freshly minted token was never given to the client.
+					if is_new {
+						return Err(MurError::forbidden("Missing CSRF token"));
+					}
+					this.verify_token(&ctx, &token)
+				});
+				if let Err(e) = verified {
+					return e.into();
+				}
+			}
+
+			let config = &this.config;
+			ctx.parts.extensions.insert(MurCsrfToken::new(
+				token.clone(),
+				&config.field_name,
+				&config.header_name,
+			));
+
+			let res = next.run(ctx).await;
+			if is_new {
+				res.with_cookie(this.cookie(&token))
+			} else {
+				res
+			}
+		})
+	}
+
+	fn name(&self) -> &str {
+		"MurCsrf"
+	}
+
+	fn requires(&self) -> &[&str] {
+		match self.config.mode {
+			MurCsrfMode::DoubleSubmit => &[],
+			MurCsrfMode::Synchronizer => &["MurSessionMiddleware"],
+		}
+	}
+}
+
+/// The `scheme://authority` of a `Referer`.
+fn referer_origin(referer: &str) -> Option<String> {
+	let uri: Uri = referer.parse().ok()?;
+	Some(format!("{}://{}", uri.scheme_str()?, uri.authority()?))
+}
+
+fn is_same_host(origin: &str, host: Option<&str>) -> bool {
+	let authority = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+	host.is_some_and(|host| authority.eq_ignore_ascii_case(host))
+}
+
+/// Whether `origin` and `host` share a registrable domain. Without a public
+/// suffix list this is approximated by the last two labels, so it is only
+/// consulted when the browser sent no `Sec-Fetch-Site` to decide it.
+fn is_same_site(origin: &str, host: Option<&str>) -> bool {
+	let authority = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+	host.is_some_and(|host| site(authority).eq_ignore_ascii_case(site(host)))
+}
+
+/// The registrable part of an authority's host; IP addresses stand alone.
+fn site(authority: &str) -> &str {
+	let host = strip_port(authority).trim_end_matches('.');
+	let literal = host.trim_start_matches('[').trim_end_matches(']');
+	if literal.parse::<IpAddr>().is_ok() {
+		return host;
+	}
+	match host.rmatch_indices('.').nth(1) {
+		Some((index, _)) => &host[index + 1..],
+		None => host,
+	}
+}
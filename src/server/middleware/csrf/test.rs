@@ -0,0 +1,262 @@
+use super::*;
+use crate::server::aliases::{MurFuture, MurRes};
+use crate::server::http::MurRequestContext;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use crate::server::security::cookie::MurCookieKeys;
+use crate::server::service::MurServiceContainer;
+use http::StatusCode;
+use http_body_util::{BodyExt, Full};
+use hyper::Response;
+use hyper::body::Bytes;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn ctx(method: &str, headers: &[(&str, &str)], body: Option<&str>) -> MurRequestContext {
+	let mut builder = http::Request::builder()
+		.method(method)
+		.uri("/settings")
+		.header("host", "app.example.com");
+	for (name, value) in headers {
+		builder = builder.header(*name, *value);
+	}
+	let (parts, _) = builder.body(()).unwrap().into_parts();
+	MurRequestContext::new(
+		parts,
+		body.map(|body| Bytes::from(body.to_string())),
+		HashMap::new(),
+		Arc::new(MurServiceContainer::new()),
+	)
+}
+
+/// Echoes the token the middleware put on the context.
+fn echo() -> MurNext {
+	MurNext::new(Arc::new(|ctx| -> MurFuture {
+		Box::pin(async move {
+			let token = ctx.csrf_token().map(|t| t.value().to_string());
+			MurRes::from(
+				Response::builder()
+					.status(StatusCode::OK)
+					.body(Full::new(Bytes::from(token.unwrap_or_default())))
+					.unwrap(),
+			)
+		})
+	}))
+}
+
+async fn run(csrf: &MurCsrf, ctx: MurRequestContext) -> Response<Full<Bytes>> {
+	match csrf.handle(ctx, echo()).await.into_result() {
+		Ok(response) => response,
+		Err(e) => e.into_response(),
+	}
+}
+
+/// The token and cookie handed out on a first `GET`.
+async fn issue(csrf: &MurCsrf) -> (String, String) {
+	let response = run(csrf, ctx("GET", &[], None)).await;
+	assert_eq!(response.status(), StatusCode::OK);
+	let cookie = response.headers()["set-cookie"].to_str().unwrap();
+	let cookie = cookie.split(';').next().unwrap().to_string();
+	let body = response.into_body().collect().await.unwrap().to_bytes();
+	(String::from_utf8(body.to_vec()).unwrap(), cookie)
+}
+
+#[tokio::test]
+async fn test_double_submit_round_trip() {
+	let csrf = MurCsrf::new();
+	let (token, cookie) = issue(&csrf).await;
+	assert_eq!(cookie, format!("mur.csrf={}", token));
+
+	let ok = run(
+		&csrf,
+		ctx(
+			"POST",
+			&[("cookie", &cookie), ("x-csrf-token", &token)],
+			None,
+		),
+	)
+	.await;
+	assert_eq!(ok.status(), StatusCode::OK);
+	assert!(ok.headers().get("set-cookie").is_none());
+
+	let form = format!("name=ada&_csrf={}", token);
+	let headers = [
+		("cookie", cookie.as_str()),
+		("content-type", "application/x-www-form-urlencoded"),
+	];
+	let ok = run(&csrf, ctx("POST", &headers, Some(&form))).await;
+	assert_eq!(ok.status(), StatusCode::OK);
+
+	let wrong = run(
+		&csrf,
+		ctx(
+			"DELETE",
+			&[("cookie", &cookie), ("x-csrf-token", "guess")],
+			None,
+		),
+	)
+	.await;
+	assert_eq!(wrong.status(), StatusCode::FORBIDDEN);
+
+	let missing = run(&csrf, ctx("POST", &[("cookie", &cookie)], None)).await;
+	assert_eq!(missing.status(), StatusCode::FORBIDDEN);
+
+	let no_cookie = run(&csrf, ctx("POST", &[("x-csrf-token", &token)], None)).await;
+	assert_eq!(no_cookie.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_signed_double_submit_rejects_planted_cookies() {
+	let keys = MurCookieKeys::new("0123456789abcdef0123456789abcdef").unwrap();
+	let csrf = MurCsrf::new();
+
+	let mut planted = ctx(
+		"POST",
+		&[("cookie", "mur.csrf=planted"), ("x-csrf-token", "planted")],
+		None,
+	);
+	planted.parts.extensions.insert(keys.clone());
+	assert_eq!(run(&csrf, planted).await.status(), StatusCode::FORBIDDEN);
+
+	let token = keys.sign("mur.csrf", "minted");
+	let cookie = format!("mur.csrf={}", token);
+	let mut signed = ctx(
+		"POST",
+		&[("cookie", &cookie), ("x-csrf-token", &token)],
+		None,
+	);
+	signed.parts.extensions.insert(keys);
+	assert_eq!(run(&csrf, signed).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_origin_verification() {
+	let csrf = MurCsrf::new().trust_origin("https://admin.example.com/");
+	let (token, cookie) = issue(&csrf).await;
+	let post = |extra: &[(&'static str, &'static str)]| {
+		let mut headers = vec![
+			("cookie", cookie.as_str()),
+			("x-csrf-token", token.as_str()),
+		];
+		headers.extend_from_slice(extra);
+		ctx("POST", &headers, None)
+	};
+
+	let cases: [(&[(&str, &str)], StatusCode); 8] = [
+		(&[("origin", "https://app.example.com")], StatusCode::OK),
+		(&[("origin", "https://admin.example.com")], StatusCode::OK),
+		(&[("origin", "https://evil.example")], StatusCode::FORBIDDEN),
+		(&[("origin", "null")], StatusCode::FORBIDDEN),
+		(
+			&[("referer", "https://app.example.com/form?x=1")],
+			StatusCode::OK,
+		),
+		(
+			&[("referer", "https://evil.example/form")],
+			StatusCode::FORBIDDEN,
+		),
+		(&[("sec-fetch-site", "cross-site")], StatusCode::FORBIDDEN),
+		(&[("sec-fetch-site", "same-site")], StatusCode::FORBIDDEN),
+	];
+	for (headers, expected) in cases {
+		assert_eq!(
+			run(&csrf, post(headers)).await.status(),
+			expected,
+			"{headers:?}"
+		);
+	}
+
+	let trusted = post(&[
+		("sec-fetch-site", "cross-site"),
+		("origin", "https://admin.example.com"),
+	]);
+	assert_eq!(run(&csrf, trusted).await.status(), StatusCode::OK);
+
+	let strict = MurCsrf::new().require_origin();
+	let headers = [
+		("cookie", cookie.as_str()),
+		("x-csrf-token", token.as_str()),
+	];
+	let anonymous = run(&strict, ctx("POST", &headers, None)).await;
+	assert_eq!(anonymous.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_allow_same_site_accepts_sibling_origins() {
+	let csrf = MurCsrf::new().allow_same_site();
+	let (token, cookie) = issue(&csrf).await;
+	let post = |extra: &[(&'static str, &'static str)]| {
+		let mut headers = vec![
+			("cookie", cookie.as_str()),
+			("x-csrf-token", token.as_str()),
+		];
+		headers.extend_from_slice(extra);
+		ctx("POST", &headers, None)
+	};
+
+	let cases: [(&[(&str, &str)], StatusCode); 6] = [
+		(
+			&[
+				("sec-fetch-site", "same-site"),
+				("origin", "https://admin.example.com"),
+			],
+			StatusCode::OK,
+		),
+		(&[("origin", "https://admin.example.com")], StatusCode::OK),
+		(
+			&[("referer", "https://example.com:8443/form")],
+			StatusCode::OK,
+		),
+		(
+			&[
+				("sec-fetch-site", "cross-site"),
+				("origin", "https://admin.example.com"),
+			],
+			StatusCode::FORBIDDEN,
+		),
+		(&[("origin", "https://example.org")], StatusCode::FORBIDDEN),
+		(
+			&[("origin", "https://app.example.com.evil.io")],
+			StatusCode::FORBIDDEN,
+		),
+	];
+	for (headers, expected) in cases {
+		assert_eq!(
+			run(&csrf, post(headers)).await.status(),
+			expected,
+			"{headers:?}"
+		);
+	}
+
+	let strict = MurCsrf::new();
+	let sibling = post(&[("origin", "https://admin.example.com")]);
+	assert_eq!(run(&strict, sibling).await.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_exempt_routes_skip_verification() {
+	let csrf = MurCsrf::new();
+	let mut exempt = ctx("POST", &[("origin", "https://evil.example")], None);
+	exempt.parts.extensions.insert(MurCsrfExempt);
+
+	assert_eq!(run(&csrf, exempt).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_synchronizer_needs_sessions() {
+	let response = run(&MurCsrf::synchronizer(), ctx("GET", &[], None)).await;
+	assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[test]
+fn test_token_markup_is_escaped() {
+	let token = MurCsrfToken::new("a\"<b>".to_string(), "_csrf", "X-CSRF-Token");
+
+	assert_eq!(
+		token.hidden_input(),
+		"<input type=\"hidden\" name=\"_csrf\" value=\"a&quot;&lt;b&gt;\">"
+	);
+	assert_eq!(
+		token.meta_tag(),
+		"<meta name=\"csrf-token\" content=\"a&quot;&lt;b&gt;\">"
+	);
+}
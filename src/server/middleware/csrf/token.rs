@@ -0,0 +1,85 @@
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+
+/// The CSRF token of the current request, set by
+/// [`MurCsrf`](super::MurCsrf), to embed in rendered forms and pages.
+///
+/// ```rust,ignore
+/// #[get("/settings")]
+/// async fn settings(&self, csrf: MurCsrfToken) -> MurRes {
+///     MurHttpResponse::ok().html(format!(
+///         "<form method=\"post\">{}<button>Save</button></form>",
+///         csrf.hidden_input()
+///     ))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MurCsrfToken {
+	value: String,
+	field_name: String,
+	header_name: String,
+}
+
+impl MurCsrfToken {
+	pub(crate) fn new(value: String, field_name: &str, header_name: &str) -> Self {
+		Self {
+			value,
+			field_name: field_name.to_string(),
+			header_name: header_name.to_string(),
+		}
+	}
+
+	/// The token of the request.
+	///
+	/// Fails when `MurCsrf` is not registered.
+	pub fn from_ctx(ctx: &MurRequestContext) -> Result<Self, MurError> {
+		ctx.csrf_token()
+			.cloned()
+			.ok_or_else(|| MurError::internal("No CSRF token available; register MurCsrf"))
+	}
+
+	pub fn value(&self) -> &str {
+		&self.value
+	}
+
+	/// The form field the token is read from.
+	pub fn field_name(&self) -> &str {
+		&self.field_name
+	}
+
+	/// The header the token is read from.
+	pub fn header_name(&self) -> &str {
+		&self.header_name
+	}
+
+	/// A hidden `<input>` carrying the token, for HTML forms.
+	pub fn hidden_input(&self) -> String {
+		format!(
+			"<input type=\"hidden\" name=\"{}\" value=\"{}\">",
+			escape_attr(&self.field_name),
+			escape_attr(&self.value)
+		)
+	}
+
+	/// A `<meta name="csrf-token">` tag, for scripts to read the token from.
+	pub fn meta_tag(&self) -> String {
+		format!(
+			"<meta name=\"csrf-token\" content=\"{}\">",
+			escape_attr(&self.value)
+		)
+	}
+}
+
+impl std::fmt::Display for MurCsrfToken {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.value)
+	}
+}
+
+fn escape_attr(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('"', "&quot;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+}
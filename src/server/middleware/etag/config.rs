@@ -0,0 +1,16 @@
+#[derive(Debug, Clone)]
+pub struct MurETagConfig {
+	pub max_body_size: usize,
+	pub skip_paths: Vec<String>,
+	pub conditional_only: bool,
+}
+
+impl Default for MurETagConfig {
+	fn default() -> Self {
+		Self {
+			max_body_size: 4 * 1024 * 1024,
+			skip_paths: Vec::new(),
+			conditional_only: false,
+		}
+	}
+}
@@ -0,0 +1,8 @@
+pub mod config;
+pub mod mur_etag;
+
+pub use config::MurETagConfig;
+pub use mur_etag::MurETag;
+
+#[cfg(test)]
+mod test;
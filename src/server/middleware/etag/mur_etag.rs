@@ -0,0 +1,135 @@
+use super::MurETagConfig;
+use crate::server::aliases::{MurFuture, MurRes};
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::http::cache::{MurEntityTag, mur_is_not_modified, mur_not_modified};
+use crate::server::middleware::{MurMiddleware, MurNext};
+use http::header::ETAG;
+use http::{HeaderMap, HeaderValue, Method};
+use http_body_util::{BodyExt, Full};
+use hyper::Response;
+use std::sync::Arc;
+
+/// Adds weak `ETag`s to buffered `GET`/`HEAD` responses and answers
+/// conditional requests (`If-None-Match`, `If-Modified-Since`) with
+/// `304 Not Modified`.
+///
+/// Handlers that set their own `ETag` or `Last-Modified` keep them; the
+/// middleware only fills in a body digest when no validator is present.
+#[derive(Clone)]
+pub struct MurETag {
+	config: Arc<MurETagConfig>,
+}
+
+impl MurETag {
+	pub fn new() -> Self {
+		Self {
+			config: Arc::new(MurETagConfig::default()),
+		}
+	}
+
+	pub fn from_config(config: MurETagConfig) -> Self {
+		Self {
+			config: Arc::new(config),
+		}
+	}
+
+	/// Skips digesting bodies larger than `size` bytes.
+	pub fn max_body_size(mut self, size: usize) -> Self {
+		let mut config = (*self.config).clone();
+		config.max_body_size = size;
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn skip_path(mut self, path: impl Into<String>) -> Self {
+		let mut config = (*self.config).clone();
+		config.skip_paths.push(path.into());
+		self.config = Arc::new(config);
+		self
+	}
+
+	/// Only evaluates validators set by handlers; never generates `ETag`s.
+	pub fn conditional_only(mut self) -> Self {
+		let mut config = (*self.config).clone();
+		config.conditional_only = true;
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn should_skip(&self, path: &str) -> bool {
+		self.config.skip_paths.iter().any(|p| {
+			if let Some(prefix) = p.strip_suffix('*') {
+				path.starts_with(prefix)
+			} else {
+				path == p
+			}
+		})
+	}
+}
+
+impl Default for MurETag {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl std::fmt::Debug for MurETag {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurETag")
+			.field("config", &self.config)
+			.finish()
+	}
+}
+
+impl MurMiddleware for MurETag {
+	fn handle(&self, ctx: MurRequestContext, next: MurNext) -> MurFuture {
+		let method = ctx.method().clone();
+		if (method != Method::GET && method != Method::HEAD) || self.should_skip(ctx.path()) {
+			return next.run(ctx);
+		}
+
+		let config = Arc::clone(&self.config);
+		let request_headers: HeaderMap = ctx.parts.headers.clone();
+
+		Box::pin(async move {
+			let response = match next.run(ctx).await.into_result() {
+				Ok(response) => response,
+				Err(e) => return MurRes::from(e),
+			};
+
+			if !response.status().is_success() {
+				return MurRes::from(response);
+			}
+
+			let (mut parts, body) = response.into_parts();
+			let collected = match body.collect().await {
+				Ok(c) => c.to_bytes(),
+				Err(_) => {
+					return MurRes::from(MurError::Internal(
+						"Failed to read response body".into(),
+					));
+				}
+			};
+
+			if !config.conditional_only
+				&& !parts.headers.contains_key(ETAG)
+				&& collected.len() <= config.max_body_size
+				&& let Ok(value) =
+					HeaderValue::from_str(&MurEntityTag::from_body(&collected).to_header_value())
+			{
+				parts.headers.insert(ETAG, value);
+			}
+
+			let response = Response::from_parts(parts, Full::new(collected));
+			if mur_is_not_modified(&method, &request_headers, response.headers()) {
+				return MurRes::from(mur_not_modified(response));
+			}
+			MurRes::from(response)
+		})
+	}
+
+	fn name(&self) -> &str {
+		"MurETag"
+	}
+}
@@ -0,0 +1,118 @@
+use super::*;
+use crate::server::aliases::{MurFuture, MurRes};
+use crate::server::http::MurRequestContext;
+use crate::server::http::cache::MurEntityTag;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use crate::server::service::MurServiceContainer;
+use http::StatusCode;
+use http_body_util::{BodyExt, Full};
+use hyper::Response;
+use hyper::body::Bytes;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn ctx(method: &str, path: &str, headers: &[(&str, &str)]) -> MurRequestContext {
+	let mut builder = http::Request::builder().method(method).uri(path);
+	for (name, value) in headers {
+		builder = builder.header(*name, *value);
+	}
+	let (parts, _) = builder.body(()).unwrap().into_parts();
+	MurRequestContext::new(
+		parts,
+		None,
+		HashMap::new(),
+		Arc::new(MurServiceContainer::new()),
+	)
+}
+
+fn next_with(body: &'static str) -> MurNext {
+	MurNext::new(Arc::new(move |_ctx| -> MurFuture {
+		Box::pin(async move {
+			MurRes::from(
+				Response::builder()
+					.status(StatusCode::OK)
+					.body(Full::new(Bytes::from(body)))
+					.unwrap(),
+			)
+		})
+	}))
+}
+
+#[test]
+fn test_skip_paths() {
+	let etag = MurETag::new().skip_path("/stream/*").skip_path("/live");
+	assert!(etag.should_skip("/stream/abc"));
+	assert!(etag.should_skip("/live"));
+	assert!(!etag.should_skip("/live/other"));
+	assert!(!etag.should_skip("/users"));
+}
+
+#[tokio::test]
+async fn test_adds_weak_etag() {
+	let etag = MurETag::new();
+	let response = etag
+		.handle(ctx("GET", "/users", &[]), next_with("hello"))
+		.await
+		.into_result()
+		.unwrap();
+
+	let expected = MurEntityTag::from_body(b"hello").to_header_value();
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_eq!(response.headers().get("etag").unwrap(), expected.as_str());
+	let body = response.into_body().collect().await.unwrap().to_bytes();
+	assert_eq!(&body[..], b"hello");
+}
+
+#[tokio::test]
+async fn test_matching_if_none_match_returns_304() {
+	let tag = MurEntityTag::from_body(b"hello").to_header_value();
+	let etag = MurETag::new();
+	let response = etag
+		.handle(
+			ctx("GET", "/users", &[("if-none-match", tag.as_str())]),
+			next_with("hello"),
+		)
+		.await
+		.into_result()
+		.unwrap();
+
+	assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+	assert_eq!(response.headers().get("etag").unwrap(), tag.as_str());
+	let body = response.into_body().collect().await.unwrap().to_bytes();
+	assert!(body.is_empty());
+}
+
+#[tokio::test]
+async fn test_non_get_requests_are_untouched() {
+	let etag = MurETag::new();
+	let response = etag
+		.handle(ctx("POST", "/users", &[]), next_with("hello"))
+		.await
+		.into_result()
+		.unwrap();
+	assert!(response.headers().get("etag").is_none());
+}
+
+#[tokio::test]
+async fn test_conditional_only_does_not_generate() {
+	let etag = MurETag::new().conditional_only();
+	let response = etag
+		.handle(ctx("GET", "/users", &[]), next_with("hello"))
+		.await
+		.into_result()
+		.unwrap();
+	assert!(response.headers().get("etag").is_none());
+}
+
+#[tokio::test]
+async fn test_large_bodies_are_skipped() {
+	let etag = MurETag::new().max_body_size(2);
+	let response = etag
+		.handle(ctx("GET", "/users", &[]), next_with("hello"))
+		.await
+		.into_result()
+		.unwrap();
+	assert!(response.headers().get("etag").is_none());
+	let body = response.into_body().collect().await.unwrap().to_bytes();
+	assert_eq!(&body[..], b"hello");
+}
@@ -3,6 +3,7 @@ use super::MurHealthCheck;
 use super::MurHealthConfig;
 use super::MurHealthIndicator;
 use super::MurHealthIndicatorResult;
+use crate::server::shutdown::MurShutdownToken;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -12,6 +13,7 @@ pub struct MurHealthBuilder {
 	config: MurHealthConfig,
 	indicators: Vec<(String, Arc<dyn MurHealthIndicator + Send + Sync>)>,
 	readiness_indicators: Vec<String>,
+	shutdown: Option<MurShutdownToken>,
 }
 
 impl MurHealthBuilder {
@@ -20,6 +22,7 @@ impl MurHealthBuilder {
 			config: MurHealthConfig::default(),
 			indicators: Vec::new(),
 			readiness_indicators: Vec::new(),
+			shutdown: None,
 		}
 	}
 
@@ -99,6 +102,15 @@ impl MurHealthBuilder {
 		self
 	}
 
+	/// Reports readiness `DOWN` while the server drains, e.g. with the
+	/// token from [`MurServer::shutdown_token`](crate::MurServer::shutdown_token).
+	/// Not needed with [`MurHealthCheck::handle_ctx`], which falls back to
+	/// the server's token.
+	pub fn shutdown_token(mut self, token: MurShutdownToken) -> Self {
+		self.shutdown = Some(token);
+		self
+	}
+
 	pub fn check<F>(self, name: impl Into<String>, can_activate_fn: F) -> Self
 	where
 		F: Fn() -> Pin<Box<dyn Future<Output = MurHealthIndicatorResult> + Send>>
@@ -116,6 +128,7 @@ impl MurHealthBuilder {
 			config: Arc::new(self.config),
 			indicators: Arc::new(self.indicators),
 			readiness_indicators: Arc::new(self.readiness_indicators),
+			shutdown: self.shutdown,
 		}
 	}
 }
@@ -1,10 +1,12 @@
 use super::MurHealthBuilder;
 use super::MurHealthConfig;
 use super::MurHealthIndicator;
+use super::MurHealthIndicatorResult;
 use super::MurHealthResponse;
 use super::MurHealthStatus;
 use crate::server::aliases::MurRes;
-use crate::server::http::MurHttpResponse;
+use crate::server::http::{MurHttpResponse, MurRequestContext};
+use crate::server::shutdown::MurShutdownToken;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
@@ -15,6 +17,8 @@ pub struct MurHealthCheck {
 	pub config: Arc<MurHealthConfig>,
 	pub indicators: Arc<Vec<(String, Arc<dyn MurHealthIndicator + Send + Sync>)>>,
 	pub readiness_indicators: Arc<Vec<String>>,
+	/// Reports readiness `DOWN` once the server starts draining.
+	pub shutdown: Option<MurShutdownToken>,
 }
 
 impl MurHealthCheck {
@@ -47,6 +51,24 @@ impl MurHealthCheck {
 	}
 
 	pub async fn check_readiness(&self) -> MurHealthResponse {
+		self.readiness(self.shutdown.as_ref()).await
+	}
+
+	async fn readiness(&self, shutdown: Option<&MurShutdownToken>) -> MurHealthResponse {
+		if shutdown.is_some_and(MurShutdownToken::is_draining) {
+			let mut response = MurHealthResponse::unhealthy();
+			response.version = self.config.version.clone();
+			if !self.config.include_timestamp {
+				response.timestamp = None;
+			}
+			if self.config.include_details {
+				let result =
+					MurHealthIndicatorResult::unhealthy().detail("reason", "shutting down");
+				response.indicators.insert("shutdown".to_string(), result);
+			}
+			return response;
+		}
+
 		if self.readiness_indicators.is_empty() {
 			return self.check().await;
 		}
@@ -132,12 +154,25 @@ impl MurHealthCheck {
 	}
 
 	pub async fn handle_request(&self, path: &str) -> MurRes {
+		self.respond(path, self.shutdown.as_ref()).await
+	}
+
+	/// Answers the request like [`handle_request`](Self::handle_request),
+	/// falling back to the server's shutdown token from `ctx` when none was
+	/// configured, so readiness reports `DOWN` while the server drains.
+	pub async fn handle_ctx(&self, ctx: &MurRequestContext) -> MurRes {
+		let fallback = ctx.shutdown_token();
+		let shutdown = self.shutdown.as_ref().or(fallback.as_ref());
+		self.respond(ctx.path(), shutdown).await
+	}
+
+	async fn respond(&self, path: &str, shutdown: Option<&MurShutdownToken>) -> MurRes {
 		use hyper::StatusCode;
 
 		let response = if Some(path) == self.liveness_path() {
 			self.check_liveness().await
 		} else if Some(path) == self.readiness_path() {
-			self.check_readiness().await
+			self.readiness(shutdown).await
 		} else {
 			self.check().await
 		};
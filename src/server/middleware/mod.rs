@@ -1,8 +1,11 @@
 pub mod compression;
 pub mod contract;
 pub mod cors;
+pub mod csrf;
+pub mod etag;
 pub mod health;
 pub mod rate_limit;
+pub mod request_id;
 pub mod timeout;
 
 pub use contract::MurMiddleware;
@@ -1,4 +1,5 @@
 use super::MurThrottlerKey;
+use std::collections::HashMap;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,6 +20,12 @@ pub struct MurThrottlerConfig {
 	pub skip_paths: Vec<String>,
 	pub skip_on_missing_key: bool,
 	pub status_code: u16,
+	/// Name reported in the `RateLimit-Policy` and `RateLimit` headers.
+	pub policy_name: String,
+	/// Let requests through when the store is unreachable.
+	pub fail_open: bool,
+	/// Quotas replacing `max_requests` for API keys of a rate-limit tier.
+	pub tiers: HashMap<String, u64>,
 }
 
 impl Default for MurThrottlerConfig {
@@ -33,6 +40,9 @@ impl Default for MurThrottlerConfig {
 			skip_paths: Vec::new(),
 			skip_on_missing_key: false,
 			status_code: 429,
+			policy_name: "default".to_string(),
+			fail_open: true,
+			tiers: HashMap::new(),
 		}
 	}
 }
@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub struct MurThrottlerEntry {
@@ -7,6 +7,9 @@ pub struct MurThrottlerEntry {
 	pub prev_count: u64,
 	pub tokens: f64,
 	pub last_refill: Instant,
+	/// The window the entry is counted over, which decides how long
+	/// cleanup keeps it.
+	pub window: Duration,
 }
 
 impl MurThrottlerEntry {
@@ -18,6 +21,7 @@ impl MurThrottlerEntry {
 			prev_count: 0,
 			tokens: 0.0,
 			last_refill: now,
+			window: Duration::ZERO,
 		}
 	}
 }
@@ -0,0 +1,86 @@
+use crate::core::utils::MurTime;
+use crate::server::aliases::MurRes;
+use crate::server::http::MurHttpResponse;
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use std::time::Duration;
+
+/// Quota information written to responses by [`mur_rate_limit_headers`].
+#[derive(Debug, Clone, Copy)]
+pub struct MurRateLimitInfo<'a> {
+	/// Policy name used in the IETF `RateLimit-Policy`/`RateLimit` headers.
+	pub policy: &'a str,
+	pub limit: u64,
+	pub remaining: u64,
+	/// Unix timestamp (seconds) at which the quota is restored.
+	pub reset_at: u64,
+	pub window: Duration,
+}
+
+/// Writes the legacy `X-RateLimit-*` headers together with the IETF
+/// `RateLimit-Policy` and `RateLimit` fields
+/// (`"<policy>";q=<limit>;w=<window>` and `"<policy>";r=<remaining>;t=<reset>`).
+pub fn mur_rate_limit_headers(headers: &mut HeaderMap, info: &MurRateLimitInfo<'_>) {
+	let reset_after = info.reset_at.saturating_sub(MurTime::timestamp_secs());
+	let policy = info.policy.replace(['"', '\\'], "");
+
+	let values = [
+		("x-ratelimit-limit", info.limit.to_string()),
+		("x-ratelimit-remaining", info.remaining.to_string()),
+		("x-ratelimit-reset", info.reset_at.to_string()),
+		(
+			"ratelimit-policy",
+			format!(
+				"\"{}\";q={};w={}",
+				policy,
+				info.limit,
+				info.window.as_secs().max(1)
+			),
+		),
+		(
+			"ratelimit",
+			format!("\"{}\";r={};t={}", policy, info.remaining, reset_after),
+		),
+	];
+
+	for (name, value) in values {
+		if let Ok(value) = HeaderValue::from_str(&value) {
+			headers.insert(HeaderName::from_static(name), value);
+		}
+	}
+}
+
+/// Marks a response as a rate-limit rejection, so metrics can count them.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MurRateLimitRejection;
+
+/// Builds the rejection returned once a quota is exhausted.
+pub fn mur_rate_limited(
+	status: StatusCode,
+	message: &str,
+	retry_after: u64,
+	info: Option<&MurRateLimitInfo<'_>>,
+) -> MurRes {
+	let response = MurHttpResponse::status(status)
+		.json(serde_json::json!({
+			"error": "Too Many Requests",
+			"message": message,
+			"retry_after": retry_after
+		}))
+		.map_response(|mut resp| {
+			resp.extensions_mut().insert(MurRateLimitRejection);
+			resp
+		});
+
+	let Some(info) = info.copied() else {
+		return response;
+	};
+
+	response.map_response(move |mut resp| {
+		let headers = resp.headers_mut();
+		mur_rate_limit_headers(headers, &info);
+		if let Ok(v) = HeaderValue::from_str(&retry_after.to_string()) {
+			headers.insert(http::header::RETRY_AFTER, v);
+		}
+		resp
+	})
+}
@@ -9,6 +9,8 @@ pub enum MurThrottlerKey {
 	Header(String),
 	BearerToken,
 	IpAndHeader(String),
+	/// The id of the key attached by `MurApiKeyAuth`.
+	ApiKey,
 	Custom(MurThrottlerCustomType),
 	Global,
 }
@@ -20,6 +22,7 @@ impl std::fmt::Debug for MurThrottlerKey {
 			MurThrottlerKey::Header(name) => write!(f, "Header({:?})", name),
 			MurThrottlerKey::BearerToken => write!(f, "BearerToken"),
 			MurThrottlerKey::IpAndHeader(name) => write!(f, "IpAndHeader({:?})", name),
+			MurThrottlerKey::ApiKey => write!(f, "ApiKey"),
 			MurThrottlerKey::Custom(_) => write!(f, "Custom(<function>)"),
 			MurThrottlerKey::Global => write!(f, "Global"),
 		}
@@ -37,6 +40,7 @@ impl MurThrottlerKey {
 				let header = ctx.header(name).map(|s| s.to_string()).unwrap_or_default();
 				Some(format!("{}:{}", ip, header))
 			}
+			MurThrottlerKey::ApiKey => ctx.api_key().map(|key| format!("api_key:{}", key.id)),
 			MurThrottlerKey::Custom(f) => f(ctx),
 			MurThrottlerKey::Global => Some("__global__".to_string()),
 		}
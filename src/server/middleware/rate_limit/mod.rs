@@ -1,7 +1,12 @@
 pub mod config;
 pub mod entry;
+pub mod headers;
 pub mod key;
 pub mod mur_rate_limit;
+pub mod policy;
+pub mod redis_store;
+pub mod resp;
+pub mod result;
 pub mod sliding_window_store;
 pub mod store;
 pub mod token_bucket;
@@ -9,10 +14,20 @@ pub mod token_bucket;
 pub use config::MurThrottlerAlgorithm;
 pub use config::MurThrottlerConfig;
 pub use entry::MurThrottlerEntry;
+pub use headers::MurRateLimitInfo;
+pub use headers::mur_rate_limit_headers;
 pub use key::MurThrottlerKey;
 pub use mur_rate_limit::MurThrottler;
+pub use policy::MurRouteThrottle;
+pub use policy::MurThrottleOverride;
+pub use policy::MurThrottlePolicy;
+pub use redis_store::RedisThrottlerStore;
+pub use result::MurThrottlerError;
+pub use result::MurThrottlerResult;
+pub use result::MurThrottlerStatus;
 pub use sliding_window_store::SlidingWindowStore;
 pub use store::InMemoryStore;
+pub use store::MurThrottlerFuture;
 pub use store::MurThrottlerStore;
 pub use token_bucket::TokenBucketStore;
 
@@ -2,15 +2,19 @@ use super::InMemoryStore;
 use super::MurThrottlerAlgorithm;
 use super::MurThrottlerConfig;
 use super::MurThrottlerKey;
+use super::MurThrottlerResult;
 use super::MurThrottlerStore;
 use super::SlidingWindowStore;
 use super::TokenBucketStore;
+use super::headers::{MurRateLimitInfo, mur_rate_limit_headers, mur_rate_limited};
+use super::policy::MurThrottleOverride;
 use crate::server::aliases::{MurFuture, MurRes};
-use crate::server::http::MurHttpResponse;
+use crate::server::error::MurError;
 use crate::server::http::MurRequestContext;
+use crate::server::logging::mur_log_line;
 use crate::server::middleware::{MurMiddleware, MurNext};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 
 pub struct MurThrottler {
 	pub config: MurThrottlerConfig,
@@ -80,6 +84,12 @@ impl MurThrottler {
 		self
 	}
 
+	/// Counts requests per API key, as authenticated by `MurApiKeyAuth`.
+	pub fn by_api_key(mut self) -> Self {
+		self.config.key_extractor = MurThrottlerKey::ApiKey;
+		self
+	}
+
 	pub fn global(mut self) -> Self {
 		self.config.key_extractor = MurThrottlerKey::Global;
 		self
@@ -148,6 +158,13 @@ impl MurThrottler {
 		self
 	}
 
+	/// Rejects requests with `503 Service Unavailable` when the store fails,
+	/// instead of letting them through.
+	pub fn fail_closed(mut self) -> Self {
+		self.config.fail_open = false;
+		self
+	}
+
 	pub fn should_skip(&self, path: &str) -> bool {
 		self.config.skip_paths.iter().any(|p| {
 			if p.ends_with('*') {
@@ -158,43 +175,54 @@ impl MurThrottler {
 		})
 	}
 
-	fn rate_limit_response(&self, _remaining: u64, reset_at: u64, retry_after: u64) -> MurRes {
+	/// Names the policy in the `RateLimit-Policy` and `RateLimit` headers.
+	pub fn policy_name(mut self, name: impl Into<String>) -> Self {
+		self.config.policy_name = name.into();
+		self
+	}
+
+	/// Allows `max_requests` per window to API keys of rate-limit `tier`.
+	pub fn tier(mut self, tier: impl Into<String>, max_requests: u64) -> Self {
+		self.config.tiers.insert(tier.into(), max_requests);
+		self
+	}
+
+	/// The quota of the request's API key tier, or `max_requests`.
+	fn limit_for(&self, ctx: &MurRequestContext) -> u64 {
+		ctx.api_key()
+			.and_then(|key| key.tier.as_ref())
+			.and_then(|tier| self.config.tiers.get(tier))
+			.copied()
+			.unwrap_or(self.config.max_requests)
+	}
+
+	fn info(&self, limit: u64, remaining: u64, reset_at: u64) -> MurRateLimitInfo<'_> {
+		MurRateLimitInfo {
+			policy: &self.config.policy_name,
+			limit,
+			remaining,
+			reset_at,
+			window: self.config.window,
+		}
+	}
+
+	fn rate_limit_response(&self, limit: u64, result: &MurThrottlerResult) -> MurRes {
 		let message = self
 			.config
 			.message
-			.clone()
-			.unwrap_or_else(|| "Too Many Requests. Please try again later.".to_string());
+			.as_deref()
+			.unwrap_or("Too Many Requests. Please try again later.");
 
 		let status = http::StatusCode::from_u16(self.config.status_code)
 			.unwrap_or(http::StatusCode::TOO_MANY_REQUESTS);
 
-		let base = MurHttpResponse::status(status).json(serde_json::json!({
-			"error": "Too Many Requests",
-			"message": message,
-			"retry_after": retry_after
-		}));
-
-		if self.config.include_headers {
-			let max = self.config.max_requests;
-			return base.map_response(move |mut resp| {
-				let headers = resp.headers_mut();
-				if let Ok(v) = max.to_string().parse() {
-					headers.insert("X-RateLimit-Limit", v);
-				}
-				if let Ok(v) = "0".parse() {
-					headers.insert("X-RateLimit-Remaining", v);
-				}
-				if let Ok(v) = reset_at.to_string().parse() {
-					headers.insert("X-RateLimit-Reset", v);
-				}
-				if let Ok(v) = retry_after.to_string().parse() {
-					headers.insert("Retry-After", v);
-				}
-				resp
-			});
-		}
-
-		base
+		let info = self.info(limit, 0, result.reset_at);
+		mur_rate_limited(
+			status,
+			message,
+			result.retry_after,
+			self.config.include_headers.then_some(&info),
+		)
 	}
 
 	pub fn headers(
@@ -202,18 +230,22 @@ impl MurThrottler {
 		response: &mut http::Response<http_body_util::Full<hyper::body::Bytes>>,
 		remaining: u64,
 		reset_at: u64,
+	) {
+		self.limit_headers(response, self.config.max_requests, remaining, reset_at);
+	}
+
+	fn limit_headers(
+		&self,
+		response: &mut http::Response<http_body_util::Full<hyper::body::Bytes>>,
+		limit: u64,
+		remaining: u64,
+		reset_at: u64,
 	) {
 		if self.config.include_headers {
-			let headers = response.headers_mut();
-			if let Ok(v) = self.config.max_requests.to_string().parse() {
-				headers.insert("X-RateLimit-Limit", v);
-			}
-			if let Ok(v) = remaining.to_string().parse() {
-				headers.insert("X-RateLimit-Remaining", v);
-			}
-			if let Ok(v) = reset_at.to_string().parse() {
-				headers.insert("X-RateLimit-Reset", v);
-			}
+			mur_rate_limit_headers(
+				response.headers_mut(),
+				&self.info(limit, remaining, reset_at),
+			);
 		}
 	}
 }
@@ -247,7 +279,7 @@ impl MurMiddleware for MurThrottler {
 	fn handle(&self, ctx: MurRequestContext, next: MurNext) -> MurFuture {
 		let path = ctx.path().to_string();
 
-		if self.should_skip(&path) {
+		if self.should_skip(&path) || ctx.parts.extensions.get::<MurThrottleOverride>().is_some() {
 			return next.run(ctx);
 		}
 
@@ -262,40 +294,47 @@ impl MurMiddleware for MurThrottler {
 			}
 		};
 
-		let (allowed, remaining, reset_at) =
-			self.store
-				.check_and_update(&key, self.config.max_requests, self.config.window);
-
-		if !allowed {
-			let now = SystemTime::now()
-				.duration_since(UNIX_EPOCH)
-				.unwrap_or_default()
-				.as_secs();
-			let retry_after = reset_at.saturating_sub(now);
-			let response = self.rate_limit_response(remaining, reset_at, retry_after);
-
-			return Box::pin(async move { response });
-		}
-
-		let include_headers = self.config.include_headers;
-		let max_requests = self.config.max_requests;
+		let limit = self.limit_for(&ctx);
+		let throttler = self.clone();
 
 		Box::pin(async move {
-			let result = next.run(ctx).await;
-
-			result.map_response(|mut response| {
-				if include_headers {
-					let headers = response.headers_mut();
-					if let Ok(v) = max_requests.to_string().parse() {
-						headers.insert("X-RateLimit-Limit", v);
-					}
-					if let Ok(v) = remaining.to_string().parse() {
-						headers.insert("X-RateLimit-Remaining", v);
-					}
-					if let Ok(v) = reset_at.to_string().parse() {
-						headers.insert("X-RateLimit-Reset", v);
-					}
+			let result = match throttler
+				.store
+				.check_and_update(&key, limit, throttler.config.window)
+				.await
+			{
+				Ok(result) => result,
+				Err(e) if throttler.config.fail_open => {
+					eprintln!(
+						"{}",
+						mur_log_line(format_args!(
+							"Throttler store error, allowing request: {}",
+							e
+						))
+					);
+					return next.run(ctx).await;
+				}
+				Err(e) => {
+					eprintln!(
+						"{}",
+						mur_log_line(format_args!(
+							"Throttler store error, rejecting request: {}",
+							e
+						))
+					);
+					return MurRes::from(MurError::Custom(
+						http::StatusCode::SERVICE_UNAVAILABLE,
+						"Rate limiter unavailable".to_string(),
+					));
 				}
+			};
+
+			if !result.allowed {
+				return throttler.rate_limit_response(limit, &result);
+			}
+
+			next.run(ctx).await.map_response(|mut response| {
+				throttler.limit_headers(&mut response, limit, result.remaining, result.reset_at);
 				response
 			})
 		})
@@ -0,0 +1,97 @@
+use super::MurThrottlerKey;
+use std::time::Duration;
+
+/// A rate limit that can be attached to individual routes or controllers,
+/// either inline via `#[throttle(limit = .., per = ..)]` or by name through
+/// [`MurServer::throttle_policy`](crate::MurServer::throttle_policy).
+///
+/// Counters are kept per route: two handlers sharing a named policy each get
+/// their own quota.
+#[derive(Debug, Clone)]
+pub struct MurThrottlePolicy {
+	pub name: Option<String>,
+	pub limit: u64,
+	pub window: Duration,
+	pub key: MurThrottlerKey,
+	/// Let requests through when the store is unreachable.
+	pub fail_open: bool,
+}
+
+impl MurThrottlePolicy {
+	pub fn new(limit: u64, window: Duration) -> Self {
+		Self {
+			name: None,
+			limit,
+			window,
+			key: MurThrottlerKey::Ip,
+			fail_open: true,
+		}
+	}
+
+	pub fn per_second(limit: u64) -> Self {
+		Self::new(limit, Duration::from_secs(1))
+	}
+
+	pub fn per_minute(limit: u64) -> Self {
+		Self::new(limit, Duration::from_secs(60))
+	}
+
+	pub fn per_hour(limit: u64) -> Self {
+		Self::new(limit, Duration::from_secs(3600))
+	}
+
+	pub fn named(mut self, name: impl Into<String>) -> Self {
+		self.name = Some(name.into());
+		self
+	}
+
+	pub fn key(mut self, key: MurThrottlerKey) -> Self {
+		self.key = key;
+		self
+	}
+
+	pub fn by_ip(self) -> Self {
+		self.key(MurThrottlerKey::Ip)
+	}
+
+	pub fn by_header(self, name: impl Into<String>) -> Self {
+		self.key(MurThrottlerKey::Header(name.into()))
+	}
+
+	pub fn by_bearer_token(self) -> Self {
+		self.key(MurThrottlerKey::BearerToken)
+	}
+
+	/// Counts requests per API key, as authenticated by `MurApiKeyAuth`.
+	pub fn by_api_key(self) -> Self {
+		self.key(MurThrottlerKey::ApiKey)
+	}
+
+	pub fn global(self) -> Self {
+		self.key(MurThrottlerKey::Global)
+	}
+
+	/// Rejects requests with `503 Service Unavailable` when the store fails,
+	/// instead of letting them through.
+	pub fn fail_closed(mut self) -> Self {
+		self.fail_open = false;
+		self
+	}
+
+}
+
+/// Throttling declared on a route definition.
+#[derive(Debug, Clone)]
+pub enum MurRouteThrottle {
+	/// An inline policy.
+	Policy(MurThrottlePolicy),
+	/// A policy registered on the server under this name.
+	Named(String),
+	/// Exempts the route from route policies and from [`MurThrottler`](super::MurThrottler).
+	Skip,
+}
+
+/// Request extension set by the router when the matched route declares its own
+/// throttling, telling the global [`MurThrottler`](super::MurThrottler) to step aside.
+#[derive(Debug, Clone, Copy)]
+pub struct MurThrottleOverride;
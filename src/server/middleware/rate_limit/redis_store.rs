@@ -0,0 +1,255 @@
+use super::MurThrottlerError;
+use super::resp::{MurRespPool, MurRespValue};
+use super::store::MurThrottlerFuture;
+use super::{MurThrottlerResult, MurThrottlerStatus, MurThrottlerStore};
+use crate::core::utils::MurTime;
+use std::time::Duration;
+
+/// Fixed-window counter executed atomically on the server.
+///
+/// Returns `{count, pttl}`; the expiry is (re)applied whenever the key has
+/// none, so a crash between `INCR` and `PEXPIRE` cannot leave a counter that
+/// never resets.
+const FIXED_WINDOW_SCRIPT: &str = "\
+local count = redis.call('INCR', KEYS[1])
+local ttl = redis.call('PTTL', KEYS[1])
+if ttl < 0 then
+	redis.call('PEXPIRE', KEYS[1], ARGV[1])
+	ttl = tonumber(ARGV[1])
+end
+return {count, ttl}";
+
+/// A [`MurThrottlerStore`] that keeps fixed-window counters in Redis (or any
+/// server speaking RESP2), so limits hold across every replica.
+///
+/// Connections are opened lazily and kept in a small pool; a connection that
+/// fails is dropped and re-established on the next request.
+///
+/// ```rust,ignore
+/// let throttler = MurThrottler::new()
+///     .requests(100)
+///     .per_minutes(1)
+///     .with_store(RedisThrottlerStore::new("redis://:secret@10.0.0.5:6379/2"));
+/// ```
+#[derive(Debug)]
+pub struct RedisThrottlerStore {
+	client: MurRespPool,
+	key_prefix: String,
+	use_script: bool,
+}
+
+impl RedisThrottlerStore {
+	/// Accepts `host:port` or `redis://[[user]:password@]host[:port][/db]`.
+	pub fn new(url: impl AsRef<str>) -> Self {
+		Self {
+			client: MurRespPool::new(url),
+			key_prefix: "murgamu:throttle:".to_string(),
+			use_script: true,
+		}
+	}
+
+	pub fn password(mut self, password: impl Into<String>) -> Self {
+		self.client.set_password(password.into());
+		self
+	}
+
+	pub fn username(mut self, username: impl Into<String>) -> Self {
+		self.client.set_username(username.into());
+		self
+	}
+
+	pub fn database(mut self, database: u32) -> Self {
+		self.client.set_database(database);
+		self
+	}
+
+	pub fn key_prefix(mut self, prefix: impl Into<String>) -> Self {
+		self.key_prefix = prefix.into();
+		self
+	}
+
+	/// Upper bound for connecting and for each round-trip.
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.client.set_timeout(timeout);
+		self
+	}
+
+	pub fn pool_size(mut self, size: usize) -> Self {
+		self.client.set_pool_size(size);
+		self
+	}
+
+	/// Uses pipelined `INCR`/`PTTL`/`PEXPIRE` instead of a Lua script, for
+	/// servers where `EVAL` is disabled.
+	pub fn incr_expire(mut self) -> Self {
+		self.use_script = false;
+		self
+	}
+
+	pub fn addr(&self) -> &str {
+		self.client.addr()
+	}
+
+	fn key(&self, key: &str) -> Vec<u8> {
+		format!("{}{}", self.key_prefix, key).into_bytes()
+	}
+
+	async fn run(&self, commands: &[Vec<Vec<u8>>]) -> Result<Vec<MurRespValue>, MurThrottlerError> {
+		self.client.run(commands).await
+	}
+
+	fn decide(count: i64, pttl: i64, max_requests: u64, window: Duration) -> MurThrottlerResult {
+		let count = count.max(0) as u64;
+		let reset_ms = if pttl > 0 {
+			pttl as u64
+		} else {
+			window.as_millis() as u64
+		};
+		let reset_secs = reset_ms.div_ceil(1000);
+		let reset_at = MurTime::timestamp_secs() + reset_secs;
+
+		if count <= max_requests {
+			MurThrottlerResult::allowed(max_requests, max_requests - count, reset_at)
+		} else {
+			MurThrottlerResult::denied(max_requests, reset_at, reset_secs)
+		}
+	}
+
+	async fn hit(
+		&self,
+		key: &str,
+		max_requests: u64,
+		window: Duration,
+	) -> Result<MurThrottlerResult, MurThrottlerError> {
+		let key = self.key(key);
+		let window_ms = window.as_millis().max(1).to_string().into_bytes();
+
+		if self.use_script {
+			let reply = self
+				.run(&[vec![
+					b"EVAL".to_vec(),
+					FIXED_WINDOW_SCRIPT.as_bytes().to_vec(),
+					b"1".to_vec(),
+					key,
+					window_ms,
+				]])
+				.await?
+				.remove(0)
+				.into_result()?;
+
+			return match reply {
+				MurRespValue::Array(Some(items)) if items.len() == 2 => {
+					let count = items[0].as_integer();
+					let pttl = items[1].as_integer();
+					match (count, pttl) {
+						(Some(count), Some(pttl)) => Ok(Self::decide(count, pttl, max_requests, window)),
+						_ => Err(MurThrottlerError::Protocol(
+							"unexpected script reply".into(),
+						)),
+					}
+				}
+				_ => Err(MurThrottlerError::Protocol(
+					"unexpected script reply".into(),
+				)),
+			};
+		}
+
+		let replies = self
+			.run(&[
+				vec![b"INCR".to_vec(), key.clone()],
+				vec![b"PTTL".to_vec(), key.clone()],
+			])
+			.await?;
+		let mut replies = replies.into_iter().map(MurRespValue::into_result);
+		let count = integer_reply(replies.next())?;
+		let mut pttl = integer_reply(replies.next())?;
+
+		if pttl < 0 {
+			self
+				.run(&[vec![b"PEXPIRE".to_vec(), key, window_ms]])
+				.await?
+				.remove(0)
+				.into_result()?;
+			pttl = window.as_millis() as i64;
+		}
+
+		Ok(Self::decide(count, pttl, max_requests, window))
+	}
+
+	async fn status(
+		&self,
+		key: &str,
+		max_requests: u64,
+		window: Duration,
+	) -> Result<MurThrottlerStatus, MurThrottlerError> {
+		let key = self.key(key);
+		let replies = self
+			.run(&[
+				vec![b"GET".to_vec(), key.clone()],
+				vec![b"PTTL".to_vec(), key],
+			])
+			.await?;
+		let mut replies = replies.into_iter().map(MurRespValue::into_result);
+		let count = match replies.next() {
+			Some(Ok(value)) if value.is_nil() => 0,
+			other => integer_reply(other)?.max(0) as u64,
+		};
+		let pttl = integer_reply(replies.next())?;
+
+		let reset_ms = if pttl > 0 {
+			pttl as u64
+		} else {
+			window.as_millis() as u64
+		};
+
+		Ok(MurThrottlerStatus {
+			count,
+			limit: max_requests,
+			remaining: max_requests.saturating_sub(count),
+			reset_at: MurTime::timestamp_secs() + reset_ms.div_ceil(1000),
+		})
+	}
+}
+
+fn integer_reply(
+	reply: Option<Result<MurRespValue, MurThrottlerError>>,
+) -> Result<i64, MurThrottlerError> {
+	match reply {
+		Some(Ok(value)) => value
+			.as_integer()
+			.ok_or_else(|| MurThrottlerError::Protocol(format!("expected integer, got {:?}", value))),
+		Some(Err(e)) => Err(e),
+		None => Err(MurThrottlerError::Protocol("missing reply".into())),
+	}
+}
+
+impl MurThrottlerStore for RedisThrottlerStore {
+	fn check_and_update<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerResult> {
+		Box::pin(self.hit(key, max_requests, window))
+	}
+
+	fn reset<'a>(&'a self, key: &'a str) -> MurThrottlerFuture<'a, ()> {
+		Box::pin(async move {
+			self
+				.run(&[vec![b"DEL".to_vec(), self.key(key)]])
+				.await?
+				.remove(0)
+				.into_result()?;
+			Ok(())
+		})
+	}
+
+	fn get_status<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerStatus> {
+		Box::pin(self.status(key, max_requests, window))
+	}
+}
@@ -0,0 +1,322 @@
+//! Minimal RESP2 client used by [`RedisThrottlerStore`](super::RedisThrottlerStore)
+//! and the Redis session store.
+//!
+//! Only what those stores need is implemented: encoding commands as arrays
+//! of bulk strings, pipelining, and decoding the five RESP2 reply types.
+
+use super::MurThrottlerError;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+/// Largest bulk string accepted, the same as Redis' `proto-max-bulk-len`.
+const MAX_BULK_LEN: i64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MurRespValue {
+	Simple(String),
+	Error(String),
+	Integer(i64),
+	Bulk(Option<Vec<u8>>),
+	Array(Option<Vec<MurRespValue>>),
+}
+
+impl MurRespValue {
+	pub fn as_integer(&self) -> Option<i64> {
+		match self {
+			MurRespValue::Integer(n) => Some(*n),
+			MurRespValue::Bulk(Some(bytes)) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+			MurRespValue::Simple(s) => s.parse().ok(),
+			_ => None,
+		}
+	}
+
+	pub fn is_nil(&self) -> bool {
+		matches!(self, MurRespValue::Bulk(None) | MurRespValue::Array(None))
+	}
+
+	/// Turns `-ERR ...` replies into [`MurThrottlerError::Backend`].
+	pub fn into_result(self) -> Result<MurRespValue, MurThrottlerError> {
+		match self {
+			MurRespValue::Error(e) => Err(MurThrottlerError::Backend(e)),
+			value => Ok(value),
+		}
+	}
+}
+
+pub fn mur_resp_encode(args: &[&[u8]], out: &mut Vec<u8>) {
+	out.extend_from_slice(format!("*{}\r\n", args.len()).as_bytes());
+	for arg in args {
+		out.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+		out.extend_from_slice(arg);
+		out.extend_from_slice(b"\r\n");
+	}
+}
+
+pub fn mur_resp_read<'a, R>(
+	reader: &'a mut R,
+) -> Pin<Box<dyn Future<Output = Result<MurRespValue, MurThrottlerError>> + Send + 'a>>
+where
+	R: AsyncBufRead + Unpin + Send,
+{
+	Box::pin(async move {
+		let line = read_line(reader).await?;
+		let kind = line.as_bytes()[0];
+		if !kind.is_ascii() {
+			return Err(MurThrottlerError::Protocol(format!(
+				"unexpected reply '{}'",
+				line
+			)));
+		}
+		// The type byte is ASCII, so the rest starts on a char boundary.
+		let rest = &line[1..];
+		match kind {
+			b'+' => Ok(MurRespValue::Simple(rest.to_string())),
+			b'-' => Ok(MurRespValue::Error(rest.to_string())),
+			b':' => Ok(MurRespValue::Integer(parse_len(rest)?)),
+			b'$' => {
+				let len = parse_len(rest)?;
+				if len < 0 {
+					return Ok(MurRespValue::Bulk(None));
+				}
+				if len > MAX_BULK_LEN {
+					return Err(MurThrottlerError::Protocol(format!(
+						"bulk string of {} bytes exceeds the limit",
+						len
+					)));
+				}
+				// Grows with the bytes received rather than the announced length.
+				let mut buf = Vec::new();
+				let expected = len as u64 + 2;
+				let read = reader.take(expected).read_to_end(&mut buf).await?;
+				if read as u64 != expected {
+					return Err(MurThrottlerError::Io(std::io::Error::new(
+						std::io::ErrorKind::UnexpectedEof,
+						"connection closed",
+					)));
+				}
+				if !buf.ends_with(b"\r\n") {
+					return Err(MurThrottlerError::Protocol(
+						"bulk string not terminated by CRLF".into(),
+					));
+				}
+				buf.truncate(len as usize);
+				Ok(MurRespValue::Bulk(Some(buf)))
+			}
+			b'*' => {
+				let len = parse_len(rest)?;
+				if len < 0 {
+					return Ok(MurRespValue::Array(None));
+				}
+				let mut items = Vec::with_capacity(len.min(64) as usize);
+				for _ in 0..len {
+					items.push(mur_resp_read(reader).await?);
+				}
+				Ok(MurRespValue::Array(Some(items)))
+			}
+			other => Err(MurThrottlerError::Protocol(format!(
+				"unexpected reply type '{}'",
+				other as char
+			))),
+		}
+	})
+}
+
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<String, MurThrottlerError> {
+	let mut line = String::new();
+	let read = reader.read_line(&mut line).await?;
+	if read == 0 {
+		return Err(MurThrottlerError::Io(std::io::Error::new(
+			std::io::ErrorKind::UnexpectedEof,
+			"connection closed",
+		)));
+	}
+	let line = line
+		.strip_suffix("\r\n")
+		.ok_or_else(|| MurThrottlerError::Protocol("line not terminated by CRLF".into()))?;
+	if line.is_empty() {
+		return Err(MurThrottlerError::Protocol("empty reply".into()));
+	}
+	Ok(line.to_string())
+}
+
+fn parse_len(value: &str) -> Result<i64, MurThrottlerError> {
+	value
+		.parse()
+		.map_err(|_| MurThrottlerError::Protocol(format!("invalid integer '{}'", value)))
+}
+
+/// A single connection speaking RESP2.
+#[derive(Debug)]
+pub struct MurRespConnection {
+	stream: BufReader<TcpStream>,
+}
+
+impl MurRespConnection {
+	pub async fn connect(addr: &str) -> Result<Self, MurThrottlerError> {
+		let stream = TcpStream::connect(addr).await?;
+		stream.set_nodelay(true)?;
+		Ok(Self {
+			stream: BufReader::new(stream),
+		})
+	}
+
+	/// Sends every command in one write and reads the replies in order.
+	pub async fn pipeline(
+		&mut self,
+		commands: &[Vec<Vec<u8>>],
+	) -> Result<Vec<MurRespValue>, MurThrottlerError> {
+		let mut out = Vec::new();
+		for command in commands {
+			let args: Vec<&[u8]> = command.iter().map(Vec::as_slice).collect();
+			mur_resp_encode(&args, &mut out);
+		}
+		self.stream.get_mut().write_all(&out).await?;
+
+		let mut replies = Vec::with_capacity(commands.len());
+		for _ in commands {
+			replies.push(mur_resp_read(&mut self.stream).await?);
+		}
+		Ok(replies)
+	}
+
+	pub async fn command(&mut self, args: Vec<Vec<u8>>) -> Result<MurRespValue, MurThrottlerError> {
+		let mut replies = self.pipeline(&[args]).await?;
+		replies
+			.pop()
+			.ok_or_else(|| MurThrottlerError::Protocol("missing reply".into()))
+	}
+}
+
+/// A small pool of lazily opened connections to one server.
+///
+/// A connection that fails is dropped and re-established on the next use.
+#[derive(Debug)]
+pub struct MurRespPool {
+	addr: String,
+	username: Option<String>,
+	password: Option<String>,
+	database: Option<u32>,
+	timeout: Duration,
+	pool: Vec<Mutex<Option<MurRespConnection>>>,
+	next: AtomicUsize,
+}
+
+impl MurRespPool {
+	/// Accepts `host:port` or `redis://[[user]:password@]host[:port][/db]`.
+	pub fn new(url: impl AsRef<str>) -> Self {
+		let url = url.as_ref();
+		let rest = url.strip_prefix("redis://").unwrap_or(url);
+
+		let (auth, rest) = match rest.rsplit_once('@') {
+			Some((auth, rest)) => (Some(auth), rest),
+			None => (None, rest),
+		};
+		let (username, password) = match auth.map(|a| a.split_once(':')) {
+			Some(Some((user, pass))) => (
+				(!user.is_empty()).then(|| user.to_string()),
+				Some(pass.to_string()),
+			),
+			Some(None) => (None, auth.map(str::to_string)),
+			None => (None, None),
+		};
+
+		let (host, database) = match rest.split_once('/') {
+			Some((host, db)) => (host, db.parse().ok()),
+			None => (rest, None),
+		};
+		let addr = if host.contains(':') {
+			host.to_string()
+		} else {
+			format!("{}:6379", host)
+		};
+
+		Self {
+			addr,
+			username,
+			password,
+			database,
+			timeout: Duration::from_secs(1),
+			pool: Self::make_pool(4),
+			next: AtomicUsize::new(0),
+		}
+	}
+
+	fn make_pool(size: usize) -> Vec<Mutex<Option<MurRespConnection>>> {
+		(0..size.max(1)).map(|_| Mutex::new(None)).collect()
+	}
+
+	pub fn set_password(&mut self, password: String) {
+		self.password = Some(password);
+	}
+
+	pub fn set_username(&mut self, username: String) {
+		self.username = Some(username);
+	}
+
+	pub fn set_database(&mut self, database: u32) {
+		self.database = Some(database);
+	}
+
+	/// Upper bound for connecting and for each round-trip.
+	pub fn set_timeout(&mut self, timeout: Duration) {
+		self.timeout = timeout;
+	}
+
+	pub fn set_pool_size(&mut self, size: usize) {
+		self.pool = Self::make_pool(size);
+	}
+
+	pub fn addr(&self) -> &str {
+		&self.addr
+	}
+
+	async fn open(&self) -> Result<MurRespConnection, MurThrottlerError> {
+		let mut conn = MurRespConnection::connect(&self.addr).await?;
+		if let Some(password) = &self.password {
+			let mut args = vec![b"AUTH".to_vec()];
+			if let Some(username) = &self.username {
+				args.push(username.as_bytes().to_vec());
+			}
+			args.push(password.as_bytes().to_vec());
+			conn.command(args).await?.into_result()?;
+		}
+		if let Some(db) = self.database {
+			conn.command(vec![b"SELECT".to_vec(), db.to_string().into_bytes()])
+				.await?
+				.into_result()?;
+		}
+		Ok(conn)
+	}
+
+	/// Runs `commands` as one pipeline on a pooled connection.
+	pub async fn run(
+		&self,
+		commands: &[Vec<Vec<u8>>],
+	) -> Result<Vec<MurRespValue>, MurThrottlerError> {
+		let slot = &self.pool[self.next.fetch_add(1, Ordering::Relaxed) % self.pool.len()];
+		let mut guard = slot.lock().await;
+
+		let result = tokio::time::timeout(self.timeout, async {
+			if guard.is_none() {
+				*guard = Some(self.open().await?);
+			}
+			match guard.as_mut() {
+				Some(conn) => conn.pipeline(commands).await,
+				None => Err(MurThrottlerError::Protocol("no connection".into())),
+			}
+		})
+		.await
+		.unwrap_or(Err(MurThrottlerError::Timeout));
+
+		if result.is_err() {
+			// The stream may hold a partial reply; never reuse it.
+			*guard = None;
+		}
+		result
+	}
+}
@@ -0,0 +1,82 @@
+use crate::core::utils::MurTime;
+
+/// Outcome of counting a request against a throttling key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MurThrottlerResult {
+	/// Whether the request fits within the limit.
+	pub allowed: bool,
+	/// The configured maximum for the window.
+	pub limit: u64,
+	/// Requests still available in the current window.
+	pub remaining: u64,
+	/// Unix timestamp (seconds) at which the quota is fully restored.
+	pub reset_at: u64,
+	/// Seconds the client should wait before retrying; `0` when allowed.
+	pub retry_after: u64,
+}
+
+impl MurThrottlerResult {
+	pub fn allowed(limit: u64, remaining: u64, reset_at: u64) -> Self {
+		Self {
+			allowed: true,
+			limit,
+			remaining,
+			reset_at,
+			retry_after: 0,
+		}
+	}
+
+	pub fn denied(limit: u64, reset_at: u64, retry_after: u64) -> Self {
+		Self {
+			allowed: false,
+			limit,
+			remaining: 0,
+			reset_at,
+			retry_after,
+		}
+	}
+
+	/// Seconds until the quota is restored, relative to now.
+	pub fn reset_after(&self) -> u64 {
+		self.reset_at.saturating_sub(MurTime::timestamp_secs())
+	}
+}
+
+/// Read-only view of a throttling key, as returned by
+/// [`MurThrottlerStore::get_status`](super::MurThrottlerStore::get_status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MurThrottlerStatus {
+	/// Requests counted in the current window.
+	pub count: u64,
+	pub limit: u64,
+	pub remaining: u64,
+	pub reset_at: u64,
+}
+
+/// Errors raised by throttler stores backed by an external service.
+#[derive(Debug)]
+pub enum MurThrottlerError {
+	Io(std::io::Error),
+	Timeout,
+	Protocol(String),
+	Backend(String),
+}
+
+impl std::fmt::Display for MurThrottlerError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			MurThrottlerError::Io(e) => write!(f, "Throttler store I/O error: {}", e),
+			MurThrottlerError::Timeout => write!(f, "Throttler store timed out"),
+			MurThrottlerError::Protocol(e) => write!(f, "Throttler store protocol error: {}", e),
+			MurThrottlerError::Backend(e) => write!(f, "Throttler store error: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for MurThrottlerError {}
+
+impl From<std::io::Error> for MurThrottlerError {
+	fn from(err: std::io::Error) -> Self {
+		MurThrottlerError::Io(err)
+	}
+}
@@ -1,6 +1,8 @@
 use super::InMemoryStore;
-use super::MurThrottlerStore;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use super::store::MurThrottlerFuture;
+use super::{MurThrottlerResult, MurThrottlerStatus, MurThrottlerStore};
+use crate::core::utils::MurTime;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct SlidingWindowStore {
@@ -21,13 +23,14 @@ impl Default for SlidingWindowStore {
 	}
 }
 
-impl MurThrottlerStore for SlidingWindowStore {
-	fn check_and_update(&self, key: &str, max_requests: u64, window: Duration) -> (bool, u64, u64) {
+impl SlidingWindowStore {
+	fn hit(&self, key: &str, max_requests: u64, window: Duration) -> MurThrottlerResult {
 		self.inner.cleanup(window);
 
 		let now = Instant::now();
 		let mut data = self.inner.data.write().unwrap();
 		let entry = data.entry(key.to_string()).or_default();
+		entry.window = window;
 		let elapsed = now.duration_since(entry.window_start);
 
 		if elapsed >= window {
@@ -40,52 +43,67 @@ impl MurThrottlerStore for SlidingWindowStore {
 		let weighted_count = entry.count as f64 + (entry.prev_count as f64 * weight.max(0.0));
 		entry.count += 1;
 
-		let allowed = weighted_count < max_requests as f64;
-		let remaining = if allowed {
-			(max_requests as f64 - weighted_count - 1.0).max(0.0) as u64
-		} else {
-			0
-		};
-
-		let reset_at = SystemTime::now()
-			.duration_since(UNIX_EPOCH)
-			.unwrap()
-			.as_secs()
-			+ (window.as_secs() - elapsed.as_secs().min(window.as_secs()));
+		let remaining_secs = window.as_secs() - elapsed.as_secs().min(window.as_secs());
+		let reset_at = MurTime::timestamp_secs() + remaining_secs;
 
-		(allowed, remaining, reset_at)
-	}
-
-	fn reset(&self, key: &str) {
-		self.inner.reset(key);
+		if weighted_count < max_requests as f64 {
+			let remaining = (max_requests as f64 - weighted_count - 1.0).max(0.0) as u64;
+			MurThrottlerResult::allowed(max_requests, remaining, reset_at)
+		} else {
+			MurThrottlerResult::denied(max_requests, reset_at, remaining_secs)
+		}
 	}
 
-	fn get_status(&self, key: &str, max_requests: u64, window: Duration) -> (u64, u64, u64) {
+	fn status(&self, key: &str, max_requests: u64, window: Duration) -> MurThrottlerStatus {
 		let data = self.inner.data.read().unwrap();
 
-		match data.get(key) {
+		let (count, remaining, reset_at) = match data.get(key) {
 			Some(entry) => {
 				let now = Instant::now();
 				let elapsed = now.duration_since(entry.window_start);
 				let weight = 1.0 - (elapsed.as_secs_f64() / window.as_secs_f64());
-				let weighted_count =
-					entry.count as f64 + (entry.prev_count as f64 * weight.max(0.0));
+				let weighted_count = entry.count as f64 + (entry.prev_count as f64 * weight.max(0.0));
 				let remaining = (max_requests as f64 - weighted_count).max(0.0) as u64;
-				let reset_at = SystemTime::now()
-					.duration_since(UNIX_EPOCH)
-					.unwrap()
-					.as_secs() + (window.as_secs()
-					- elapsed.as_secs().min(window.as_secs()));
+				let reset_at =
+					MurTime::timestamp_secs() + (window.as_secs() - elapsed.as_secs().min(window.as_secs()));
 
 				(weighted_count as u64, remaining, reset_at)
 			}
-			None => {
-				let reset_at = SystemTime::now()
-					.duration_since(UNIX_EPOCH)
-					.unwrap()
-					.as_secs() + window.as_secs();
-				(0, max_requests, reset_at)
-			}
+			None => (0, max_requests, MurTime::timestamp_secs() + window.as_secs()),
+		};
+
+		MurThrottlerStatus {
+			count,
+			limit: max_requests,
+			remaining,
+			reset_at,
 		}
 	}
 }
+
+impl MurThrottlerStore for SlidingWindowStore {
+	fn check_and_update<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerResult> {
+		let result = self.hit(key, max_requests, window);
+		Box::pin(async move { Ok(result) })
+	}
+
+	fn reset<'a>(&'a self, key: &'a str) -> MurThrottlerFuture<'a, ()> {
+		self.inner.remove(key);
+		Box::pin(async { Ok(()) })
+	}
+
+	fn get_status<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerStatus> {
+		let status = self.status(key, max_requests, window);
+		Box::pin(async move { Ok(status) })
+	}
+}
@@ -1,12 +1,38 @@
 use super::MurThrottlerEntry;
+use super::{MurThrottlerError, MurThrottlerResult, MurThrottlerStatus};
+use crate::core::utils::MurTime;
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::RwLock;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 
+pub type MurThrottlerFuture<'a, T> =
+	Pin<Box<dyn Future<Output = Result<T, MurThrottlerError>> + Send + 'a>>;
+
+/// Backing storage for [`MurThrottler`](super::MurThrottler) counters.
+///
+/// Stores are asynchronous so that counters can live in a service shared by
+/// every replica (see [`RedisThrottlerStore`](super::RedisThrottlerStore)).
+/// In-process stores simply return ready futures.
 pub trait MurThrottlerStore: Send + Sync + 'static {
-	fn check_and_update(&self, key: &str, max_requests: u64, window: Duration) -> (bool, u64, u64);
-	fn reset(&self, key: &str);
-	fn get_status(&self, key: &str, max_requests: u64, window: Duration) -> (u64, u64, u64);
+	/// Counts one request against `key` and reports whether it is allowed.
+	fn check_and_update<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerResult>;
+
+	fn reset<'a>(&'a self, key: &'a str) -> MurThrottlerFuture<'a, ()>;
+
+	/// Reports the state of `key` without counting a request.
+	fn get_status<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerStatus>;
 }
 
 #[derive(Debug)]
@@ -33,6 +59,9 @@ impl InMemoryStore {
 		}
 	}
 
+	/// Drops entries idle for twice their own window, so policies with
+	/// different windows can share the store. Entries that recorded no
+	/// window use `max_age`.
 	pub fn cleanup(&self, max_age: Duration) {
 		// Fast path: read lock to avoid write contention on every request.
 		{
@@ -51,7 +80,13 @@ impl InMemoryStore {
 		drop(last_cleanup); // Release before acquiring data lock to avoid deadlock.
 
 		let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
-		data.retain(|_, entry| now.duration_since(entry.window_start) < max_age * 2);
+		data.retain(|_, entry| {
+			let window = match entry.window {
+				Duration::ZERO => max_age,
+				window => window,
+			};
+			now.duration_since(entry.window_start) < window * 2
+		});
 	}
 }
 
@@ -61,13 +96,14 @@ impl Default for InMemoryStore {
 	}
 }
 
-impl MurThrottlerStore for InMemoryStore {
-	fn check_and_update(&self, key: &str, max_requests: u64, window: Duration) -> (bool, u64, u64) {
+impl InMemoryStore {
+	fn hit(&self, key: &str, max_requests: u64, window: Duration) -> MurThrottlerResult {
 		self.cleanup(window);
 
 		let now = Instant::now();
 		let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
 		let entry = data.entry(key.to_string()).or_default();
+		entry.window = window;
 		let elapsed = now.duration_since(entry.window_start);
 
 		let remaining_secs = if elapsed >= window {
@@ -81,34 +117,23 @@ impl MurThrottlerStore for InMemoryStore {
 
 		entry.count += 1;
 
-		let allowed = entry.count <= max_requests;
-		let remaining = if allowed {
-			max_requests - entry.count
+		let reset_at = MurTime::timestamp_secs() + remaining_secs;
+		if entry.count <= max_requests {
+			MurThrottlerResult::allowed(max_requests, max_requests - entry.count, reset_at)
 		} else {
-			0
-		};
-		let reset_at = SystemTime::now()
-			.duration_since(UNIX_EPOCH)
-			.unwrap_or_default()
-			.as_secs()
-			+ remaining_secs;
-
-		(allowed, remaining, reset_at)
+			MurThrottlerResult::denied(max_requests, reset_at, remaining_secs)
+		}
 	}
 
-	fn reset(&self, key: &str) {
+	pub(crate) fn remove(&self, key: &str) {
 		let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
 		data.remove(key);
 	}
 
-	fn get_status(&self, key: &str, max_requests: u64, window: Duration) -> (u64, u64, u64) {
+	fn status(&self, key: &str, max_requests: u64, window: Duration) -> MurThrottlerStatus {
 		let data = self.data.read().unwrap_or_else(|e| e.into_inner());
-		let now_unix = SystemTime::now()
-			.duration_since(UNIX_EPOCH)
-			.unwrap_or_default()
-			.as_secs();
-
-		match data.get(key) {
+		let now_unix = MurTime::timestamp_secs();
+		let (count, remaining, reset_at) = match data.get(key) {
 			Some(entry) => {
 				let now = Instant::now();
 				let elapsed = now.duration_since(entry.window_start);
@@ -122,6 +147,40 @@ impl MurThrottlerStore for InMemoryStore {
 				}
 			}
 			None => (0, max_requests, now_unix + window.as_secs()),
+		};
+
+		MurThrottlerStatus {
+			count,
+			limit: max_requests,
+			remaining,
+			reset_at,
 		}
 	}
 }
+
+impl MurThrottlerStore for InMemoryStore {
+	fn check_and_update<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerResult> {
+		let result = self.hit(key, max_requests, window);
+		Box::pin(async move { Ok(result) })
+	}
+
+	fn reset<'a>(&'a self, key: &'a str) -> MurThrottlerFuture<'a, ()> {
+		self.remove(key);
+		Box::pin(async { Ok(()) })
+	}
+
+	fn get_status<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerStatus> {
+		let status = self.status(key, max_requests, window);
+		Box::pin(async move { Ok(status) })
+	}
+}
@@ -1,5 +1,11 @@
+use super::resp::{MurRespValue, mur_resp_encode, mur_resp_read};
 use super::*;
-use std::time::Duration;
+use crate::core::utils::MurTime;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
 
 pub fn mur_rate_limit_per_minute(requests: u64) -> MurThrottler {
 	MurThrottler::new().requests(requests).per_minutes(1)
@@ -34,59 +40,68 @@ fn test_rate_limit_builder() {
 	assert_eq!(limiter.config.window, Duration::from_secs(300));
 }
 
-#[test]
-fn test_in_memory_store_basic() {
+#[tokio::test]
+async fn test_in_memory_store_basic() {
 	let store = InMemoryStore::new();
 	let window = Duration::from_secs(60);
 
-	let (allowed, remaining, _) = store.check_and_update("test_key", 10, window);
-	assert!(allowed);
-	assert_eq!(remaining, 9);
+	let result = store
+		.check_and_update("test_key", 10, window)
+		.await
+		.unwrap();
+	assert!(result.allowed);
+	assert_eq!(result.remaining, 9);
 
 	for i in 0..9 {
-		let (allowed, remaining, _) = store.check_and_update("test_key", 10, window);
-		assert!(allowed, "Request {} should be allowed", i + 2);
-		assert_eq!(remaining, 8 - i);
+		let result = store
+			.check_and_update("test_key", 10, window)
+			.await
+			.unwrap();
+		assert!(result.allowed, "Request {} should be allowed", i + 2);
+		assert_eq!(result.remaining, 8 - i);
 	}
 
-	let (allowed, remaining, _) = store.check_and_update("test_key", 10, window);
-	assert!(!allowed);
-	assert_eq!(remaining, 0);
+	let result = store
+		.check_and_update("test_key", 10, window)
+		.await
+		.unwrap();
+	assert!(!result.allowed);
+	assert_eq!(result.remaining, 0);
 }
 
-#[test]
-fn test_in_memory_store_different_keys() {
+#[tokio::test]
+async fn test_in_memory_store_different_keys() {
 	let store = InMemoryStore::new();
 	let window = Duration::from_secs(60);
 
-	let (allowed, _, _) = store.check_and_update("key_a", 5, window);
-	assert!(allowed);
+	let result = store.check_and_update("key_a", 5, window).await.unwrap();
+	assert!(result.allowed);
 
-	let (allowed, remaining, _) = store.check_and_update("key_b", 5, window);
-	assert!(allowed);
-	assert_eq!(remaining, 4);
+	let result = store.check_and_update("key_b", 5, window).await.unwrap();
+	assert!(result.allowed);
+	assert_eq!(result.remaining, 4);
 }
 
-#[test]
-fn test_token_bucket_store() {
+#[tokio::test]
+async fn test_token_bucket_store() {
 	let store = TokenBucketStore::new();
 	let window = Duration::from_secs(60);
 
-	let (allowed, remaining, _) = store.check_and_update("test", 10, window);
-	assert!(allowed);
-	assert_eq!(remaining, 9);
+	let result = store.check_and_update("test", 10, window).await.unwrap();
+	assert!(result.allowed);
+	assert_eq!(result.remaining, 9);
 
 	for _ in 0..8 {
-		let (allowed, _, _) = store.check_and_update("test", 10, window);
-		assert!(allowed);
+		let result = store.check_and_update("test", 10, window).await.unwrap();
+		assert!(result.allowed);
 	}
 
-	let (allowed, remaining, _) = store.check_and_update("test", 10, window);
-	assert!(allowed);
-	assert_eq!(remaining, 0);
+	let result = store.check_and_update("test", 10, window).await.unwrap();
+	assert!(result.allowed);
+	assert_eq!(result.remaining, 0);
 
-	let (allowed, _, _) = store.check_and_update("test", 10, window);
-	assert!(!allowed);
+	let result = store.check_and_update("test", 10, window).await.unwrap();
+	assert!(!result.allowed);
 }
 
 #[test]
@@ -111,12 +126,12 @@ fn test_rate_limit_key_extraction() {
 	let _key = MurThrottlerKey::Global;
 }
 
-#[test]
-fn test_sliding_window_store() {
+#[tokio::test]
+async fn test_sliding_window_store() {
 	let store = SlidingWindowStore::new();
 	let window = Duration::from_secs(60);
-	let (allowed, _, _) = store.check_and_update("test", 10, window);
-	assert!(allowed);
+	let result = store.check_and_update("test", 10, window).await.unwrap();
+	assert!(result.allowed);
 }
 
 #[test]
@@ -158,19 +173,420 @@ fn test_rate_limit_clone() {
 	assert_eq!(cloned.config.max_requests, 100);
 }
 
-#[test]
-fn test_store_reset() {
+#[tokio::test]
+async fn test_cleanup_keeps_entries_of_longer_windows() {
+	let store = InMemoryStore::with_cleanup_interval(Duration::ZERO);
+	let short = Duration::from_millis(20);
+	let long = Duration::from_secs(3600);
+
+	store.check_and_update("login", 1, long).await.unwrap();
+	store.check_and_update("search", 5, short).await.unwrap();
+	tokio::time::sleep(short * 3).await;
+
+	// Hitting the short policy runs a cleanup with its own window.
+	store.check_and_update("other", 5, short).await.unwrap();
+	assert!(!store.data.read().unwrap().contains_key("search"));
+
+	let result = store.check_and_update("login", 1, long).await.unwrap();
+	assert!(!result.allowed);
+}
+
+#[tokio::test]
+async fn test_store_reset() {
 	let store = InMemoryStore::new();
 	let window = Duration::from_secs(60);
 
-	store.check_and_update("test", 10, window);
-	store.check_and_update("test", 10, window);
+	store.check_and_update("test", 10, window).await.unwrap();
+	store.check_and_update("test", 10, window).await.unwrap();
+
+	let status = store.get_status("test", 10, window).await.unwrap();
+	assert_eq!(status.count, 2);
+	store.reset("test").await.unwrap();
+
+	let status = store.get_status("test", 10, window).await.unwrap();
+	assert_eq!(status.count, 0);
+	assert_eq!(status.remaining, 10);
+}
+
+#[tokio::test]
+async fn test_token_bucket_retry_after() {
+	let store = TokenBucketStore::new();
+	let window = Duration::from_secs(10);
+	for _ in 0..2 {
+		store.check_and_update("k", 2, window).await.unwrap();
+	}
+	let result = store.check_and_update("k", 2, window).await.unwrap();
+	assert!(!result.allowed);
+	assert_eq!(result.limit, 2);
+	assert_eq!(result.retry_after, 5);
+}
+
+#[tokio::test]
+async fn test_resp_decode_replies() {
+	let data: &[u8] = b"+OK\r\n-ERR boom\r\n:42\r\n$3\r\nabc\r\n$-1\r\n*2\r\n:1\r\n$1\r\nx\r\n";
+	let mut reader = BufReader::new(data);
+
+	assert_eq!(
+		mur_resp_read(&mut reader).await.unwrap(),
+		MurRespValue::Simple("OK".into())
+	);
+	assert!(
+		mur_resp_read(&mut reader)
+			.await
+			.unwrap()
+			.into_result()
+			.is_err()
+	);
+	assert_eq!(
+		mur_resp_read(&mut reader).await.unwrap().as_integer(),
+		Some(42)
+	);
+	assert_eq!(
+		mur_resp_read(&mut reader).await.unwrap(),
+		MurRespValue::Bulk(Some(b"abc".to_vec()))
+	);
+	assert!(mur_resp_read(&mut reader).await.unwrap().is_nil());
+	assert_eq!(
+		mur_resp_read(&mut reader).await.unwrap(),
+		MurRespValue::Array(Some(vec![
+			MurRespValue::Integer(1),
+			MurRespValue::Bulk(Some(b"x".to_vec())),
+		]))
+	);
+}
+
+#[tokio::test]
+async fn test_resp_rejects_malformed_replies() {
+	for data in [
+		"\u{e9}t\u{e9}\r\n".as_bytes(),
+		b"$1073741824\r\nabc\r\n",
+		b"$10\r\nabc\r\n",
+	] {
+		let mut reader = BufReader::new(data);
+		assert!(mur_resp_read(&mut reader).await.is_err());
+	}
+}
+
+#[test]
+fn test_resp_encode_command() {
+	let mut out = Vec::new();
+	mur_resp_encode(&[b"INCR", b"key"], &mut out);
+	assert_eq!(out, b"*2\r\n$4\r\nINCR\r\n$3\r\nkey\r\n");
+}
+
+/// In-repo RESP stand-in implementing the handful of commands the Redis
+/// store issues. `EVAL` emulates the bundled fixed-window script.
+type FakeEntry = (i64, Option<Instant>);
+
+#[derive(Default)]
+struct FakeRedis {
+	data: Mutex<HashMap<Vec<u8>, FakeEntry>>,
+	commands: Mutex<Vec<String>>,
+}
+
+impl FakeRedis {
+	fn pttl(entry: Option<&FakeEntry>) -> i64 {
+		match entry {
+			None => -2,
+			Some((_, None)) => -1,
+			Some((_, Some(at))) => at.saturating_duration_since(Instant::now()).as_millis() as i64,
+		}
+	}
+
+	fn execute(&self, args: Vec<Vec<u8>>) -> MurRespValue {
+		let name = String::from_utf8_lossy(&args[0]).to_uppercase();
+		self.commands.lock().unwrap().push(name.clone());
+
+		let mut data = self.data.lock().unwrap();
+		data.retain(|_, (_, expires)| expires.is_none_or(|at| at > Instant::now()));
+
+		match name.as_str() {
+			"AUTH" | "SELECT" => MurRespValue::Simple("OK".into()),
+			"INCR" => {
+				let entry = data.entry(args[1].clone()).or_insert((0, None));
+				entry.0 += 1;
+				MurRespValue::Integer(entry.0)
+			}
+			"PTTL" => MurRespValue::Integer(Self::pttl(data.get(&args[1]))),
+			"PEXPIRE" => {
+				let ms: u64 = String::from_utf8_lossy(&args[2]).parse().unwrap();
+				match data.get_mut(&args[1]) {
+					Some(entry) => {
+						entry.1 = Some(Instant::now() + Duration::from_millis(ms));
+						MurRespValue::Integer(1)
+					}
+					None => MurRespValue::Integer(0),
+				}
+			}
+			"GET" => MurRespValue::Bulk(
+				data
+					.get(&args[1])
+					.map(|(count, _)| count.to_string().into_bytes()),
+			),
+			"DEL" => MurRespValue::Integer(data.remove(&args[1]).is_some() as i64),
+			"EVAL" => {
+				let key = args[3].clone();
+				let ms: u64 = String::from_utf8_lossy(&args[4]).parse().unwrap();
+				let entry = data.entry(key).or_insert((0, None));
+				entry.0 += 1;
+				if entry.1.is_none() {
+					entry.1 = Some(Instant::now() + Duration::from_millis(ms));
+				}
+				let ttl = Self::pttl(Some(entry));
+				MurRespValue::Array(Some(vec![
+					MurRespValue::Integer(entry.0),
+					MurRespValue::Integer(ttl),
+				]))
+			}
+			other => MurRespValue::Error(format!("ERR unknown command '{}'", other)),
+		}
+	}
+}
+
+fn encode_reply(value: &MurRespValue, out: &mut Vec<u8>) {
+	match value {
+		MurRespValue::Simple(s) => out.extend_from_slice(format!("+{}\r\n", s).as_bytes()),
+		MurRespValue::Error(e) => out.extend_from_slice(format!("-{}\r\n", e).as_bytes()),
+		MurRespValue::Integer(n) => out.extend_from_slice(format!(":{}\r\n", n).as_bytes()),
+		MurRespValue::Bulk(None) => out.extend_from_slice(b"$-1\r\n"),
+		MurRespValue::Bulk(Some(bytes)) => {
+			out.extend_from_slice(format!("${}\r\n", bytes.len()).as_bytes());
+			out.extend_from_slice(bytes);
+			out.extend_from_slice(b"\r\n");
+		}
+		MurRespValue::Array(None) => out.extend_from_slice(b"*-1\r\n"),
+		MurRespValue::Array(Some(items)) => {
+			out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+			for item in items {
+				encode_reply(item, out);
+			}
+		}
+	}
+}
+
+async fn spawn_fake_redis() -> (String, Arc<FakeRedis>) {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap().to_string();
+	let server = Arc::new(FakeRedis::default());
+	let shared = Arc::clone(&server);
+
+	tokio::spawn(async move {
+		while let Ok((stream, _)) = listener.accept().await {
+			let server = Arc::clone(&shared);
+			tokio::spawn(async move {
+				let mut stream = BufReader::new(stream);
+				while let Ok(MurRespValue::Array(Some(items))) = mur_resp_read(&mut stream).await {
+					let args = items
+						.into_iter()
+						.filter_map(|item| match item {
+							MurRespValue::Bulk(Some(bytes)) => Some(bytes),
+							_ => None,
+						})
+						.collect();
+					let mut out = Vec::new();
+					encode_reply(&server.execute(args), &mut out);
+					if stream.get_mut().write_all(&out).await.is_err() {
+						break;
+					}
+				}
+			});
+		}
+	});
+
+	(addr, server)
+}
+
+#[test]
+fn test_redis_store_url_parsing() {
+	assert_eq!(
+		RedisThrottlerStore::new("localhost").addr(),
+		"localhost:6379"
+	);
+	assert_eq!(
+		RedisThrottlerStore::new("redis://:secret@10.0.0.5:6380/2").addr(),
+		"10.0.0.5:6380"
+	);
+	assert_eq!(
+		RedisThrottlerStore::new("127.0.0.1:7000").addr(),
+		"127.0.0.1:7000"
+	);
+}
+
+#[tokio::test]
+async fn test_redis_store_script_fixed_window() {
+	let (addr, server) = spawn_fake_redis().await;
+	let store = RedisThrottlerStore::new(format!("redis://:pw@{}/3", addr));
+	let window = Duration::from_secs(60);
+
+	for i in 0..3 {
+		let result = store.check_and_update("ip", 3, window).await.unwrap();
+		assert!(result.allowed);
+		assert_eq!(result.remaining, 2 - i);
+	}
+	let result = store.check_and_update("ip", 3, window).await.unwrap();
+	assert!(!result.allowed);
+	assert!(result.retry_after > 0 && result.retry_after <= 60);
+
+	let status = store.get_status("ip", 3, window).await.unwrap();
+	assert_eq!(status.count, 4);
+	assert_eq!(status.remaining, 0);
+
+	store.reset("ip").await.unwrap();
+	let status = store.get_status("ip", 3, window).await.unwrap();
+	assert_eq!(status.count, 0);
+
+	let commands = server.commands.lock().unwrap().clone();
+	assert!(commands.contains(&"AUTH".to_string()));
+	assert!(commands.contains(&"SELECT".to_string()));
+	assert!(commands.contains(&"EVAL".to_string()));
+	assert!(!commands.contains(&"INCR".to_string()));
+}
 
-	let (count, _, _) = store.get_status("test", 10, window);
-	assert_eq!(count, 2);
-	store.reset("test");
+#[tokio::test]
+async fn test_redis_store_incr_expire_sets_ttl() {
+	let (addr, server) = spawn_fake_redis().await;
+	let store = RedisThrottlerStore::new(&addr)
+		.incr_expire()
+		.key_prefix("t:");
+	let window = Duration::from_millis(200);
+
+	assert!(
+		store
+			.check_and_update("k", 1, window)
+			.await
+			.unwrap()
+			.allowed
+	);
+	assert!(
+		!store
+			.check_and_update("k", 1, window)
+			.await
+			.unwrap()
+			.allowed
+	);
+	assert!(server.data.lock().unwrap().contains_key(&b"t:k".to_vec()));
+
+	tokio::time::sleep(Duration::from_millis(300)).await;
+	assert!(
+		store
+			.check_and_update("k", 1, window)
+			.await
+			.unwrap()
+			.allowed
+	);
+}
+
+#[tokio::test]
+async fn test_redis_store_shared_between_instances() {
+	let (addr, _server) = spawn_fake_redis().await;
+	let replica_a = RedisThrottlerStore::new(&addr);
+	let replica_b = RedisThrottlerStore::new(&addr);
+	let window = Duration::from_secs(60);
 
-	let (count, remaining, _) = store.get_status("test", 10, window);
-	assert_eq!(count, 0);
-	assert_eq!(remaining, 10);
+	assert!(
+		replica_a
+			.check_and_update("k", 2, window)
+			.await
+			.unwrap()
+			.allowed
+	);
+	assert!(
+		replica_b
+			.check_and_update("k", 2, window)
+			.await
+			.unwrap()
+			.allowed
+	);
+	assert!(
+		!replica_a
+			.check_and_update("k", 2, window)
+			.await
+			.unwrap()
+			.allowed
+	);
+}
+
+#[tokio::test]
+async fn test_redis_store_unreachable_errors() {
+	let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+	let addr = listener.local_addr().unwrap().to_string();
+	drop(listener);
+
+	let store = RedisThrottlerStore::new(&addr).timeout(Duration::from_millis(200));
+	assert!(
+		store
+			.check_and_update("k", 1, Duration::from_secs(1))
+			.await
+			.is_err()
+	);
+}
+
+/// Runs against a real server when `MUR_TEST_REDIS_URL` is set.
+#[tokio::test]
+async fn test_redis_store_real_server() {
+	let Ok(url) = std::env::var("MUR_TEST_REDIS_URL") else {
+		return;
+	};
+	let store = RedisThrottlerStore::new(url).key_prefix("murgamu:test:");
+	let window = Duration::from_secs(5);
+	store.reset("k").await.unwrap();
+
+	assert!(
+		store
+			.check_and_update("k", 1, window)
+			.await
+			.unwrap()
+			.allowed
+	);
+	assert!(
+		!store
+			.check_and_update("k", 1, window)
+			.await
+			.unwrap()
+			.allowed
+	);
+	store.reset("k").await.unwrap();
+}
+
+#[test]
+fn test_fail_closed_builder() {
+	assert!(MurThrottlerConfig::default().fail_open);
+	let limiter = MurThrottler::new().fail_closed();
+	assert!(!limiter.config.fail_open);
+}
+
+#[test]
+fn test_rate_limit_headers() {
+	let mut headers = http::HeaderMap::new();
+	let reset_at = MurTime::timestamp_secs() + 30;
+	mur_rate_limit_headers(
+		&mut headers,
+		&MurRateLimitInfo {
+			policy: "login",
+			limit: 5,
+			remaining: 4,
+			reset_at,
+			window: Duration::from_secs(60),
+		},
+	);
+
+	assert_eq!(headers["x-ratelimit-limit"], "5");
+	assert_eq!(headers["x-ratelimit-remaining"], "4");
+	assert_eq!(headers["x-ratelimit-reset"], reset_at.to_string().as_str());
+	assert_eq!(headers["ratelimit-policy"], "\"login\";q=5;w=60");
+	let rate_limit = headers["ratelimit"].to_str().unwrap();
+	assert!(rate_limit.starts_with("\"login\";r=4;t="), "{rate_limit}");
+}
+
+#[test]
+fn test_throttler_headers_include_policy_name() {
+	let limiter = MurThrottler::new()
+		.requests(10)
+		.per_minutes(1)
+		.policy_name("api");
+	let mut response = http::Response::new(http_body_util::Full::new(hyper::body::Bytes::new()));
+	limiter.headers(&mut response, 9, MurTime::timestamp_secs() + 60);
+
+	assert_eq!(response.headers()["ratelimit-policy"], "\"api\";q=10;w=60");
+	assert_eq!(response.headers()["x-ratelimit-remaining"], "9");
 }
@@ -1,7 +1,9 @@
-use super::MurThrottlerStore;
+use super::store::MurThrottlerFuture;
+use super::{MurThrottlerResult, MurThrottlerStatus, MurThrottlerStore};
+use crate::core::utils::MurTime;
 use std::collections::HashMap;
 use std::sync::RwLock;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct TokenBucketStore {
@@ -28,8 +30,8 @@ impl Default for TokenBucketStore {
 	}
 }
 
-impl MurThrottlerStore for TokenBucketStore {
-	fn check_and_update(&self, key: &str, max_requests: u64, window: Duration) -> (bool, u64, u64) {
+impl TokenBucketStore {
+	fn hit(&self, key: &str, max_requests: u64, window: Duration) -> MurThrottlerResult {
 		let now = Instant::now();
 		let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
 		let refill_rate = max_requests as f64 / window.as_secs_f64();
@@ -50,35 +52,29 @@ impl MurThrottlerStore for TokenBucketStore {
 			entry.tokens -= 1.0;
 		}
 
-		let remaining = entry.tokens.max(0.0) as u64;
-		let seconds_until_full = if refill_rate > 0.0 {
-			((max_requests as f64 - entry.tokens) / refill_rate).ceil() as u64
-		} else {
-			0
+		let seconds_until = |tokens: f64| {
+			if refill_rate > 0.0 {
+				(tokens / refill_rate).ceil() as u64
+			} else {
+				0
+			}
 		};
-		let reset_at = SystemTime::now()
-			.duration_since(UNIX_EPOCH)
-			.unwrap_or_default()
-			.as_secs()
-			+ seconds_until_full;
-
-		(allowed, remaining, reset_at)
-	}
+		let reset_at =
+			MurTime::timestamp_secs() + seconds_until(max_requests as f64 - entry.tokens);
 
-	fn reset(&self, key: &str) {
-		let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
-		data.remove(key);
+		if allowed {
+			MurThrottlerResult::allowed(max_requests, entry.tokens.max(0.0) as u64, reset_at)
+		} else {
+			MurThrottlerResult::denied(max_requests, reset_at, seconds_until(1.0 - entry.tokens))
+		}
 	}
 
-	fn get_status(&self, key: &str, max_requests: u64, window: Duration) -> (u64, u64, u64) {
+	fn status(&self, key: &str, max_requests: u64, window: Duration) -> MurThrottlerStatus {
 		let data = self.data.read().unwrap_or_else(|e| e.into_inner());
 		let refill_rate = max_requests as f64 / window.as_secs_f64();
-		let now_unix = SystemTime::now()
-			.duration_since(UNIX_EPOCH)
-			.unwrap_or_default()
-			.as_secs();
+		let now_unix = MurTime::timestamp_secs();
 
-		match data.get(key) {
+		let (count, remaining, reset_at) = match data.get(key) {
 			Some(entry) => {
 				let now = Instant::now();
 				let elapsed = now.duration_since(entry.last_refill);
@@ -94,6 +90,41 @@ impl MurThrottlerStore for TokenBucketStore {
 				(used, remaining, now_unix + seconds_until_full)
 			}
 			None => (0, max_requests, now_unix),
+		};
+
+		MurThrottlerStatus {
+			count,
+			limit: max_requests,
+			remaining,
+			reset_at,
 		}
 	}
 }
+
+impl MurThrottlerStore for TokenBucketStore {
+	fn check_and_update<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerResult> {
+		let result = self.hit(key, max_requests, window);
+		Box::pin(async move { Ok(result) })
+	}
+
+	fn reset<'a>(&'a self, key: &'a str) -> MurThrottlerFuture<'a, ()> {
+		let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
+		data.remove(key);
+		Box::pin(async { Ok(()) })
+	}
+
+	fn get_status<'a>(
+		&'a self,
+		key: &'a str,
+		max_requests: u64,
+		window: Duration,
+	) -> MurThrottlerFuture<'a, MurThrottlerStatus> {
+		let status = self.status(key, max_requests, window);
+		Box::pin(async move { Ok(status) })
+	}
+}
@@ -0,0 +1,59 @@
+use crate::core::utils::MurRand;
+use std::sync::Arc;
+
+/// How new request IDs are generated.
+#[derive(Clone, Default)]
+pub enum MurRequestIdGenerator {
+	/// Time-ordered UUID (RFC 9562 version 7).
+	#[default]
+	UuidV7,
+	/// Random UUID (version 4).
+	UuidV4,
+	/// Compact hex ID from [`MurRand::mur_gen_id`].
+	MurGenId,
+	Custom(Arc<dyn Fn() -> String + Send + Sync>),
+}
+
+impl MurRequestIdGenerator {
+	pub fn generate(&self) -> String {
+		match self {
+			Self::UuidV7 => uuid::Uuid::now_v7().to_string(),
+			Self::UuidV4 => uuid::Uuid::new_v4().to_string(),
+			Self::MurGenId => MurRand::mur_gen_id(),
+			Self::Custom(generator) => generator(),
+		}
+	}
+}
+
+impl std::fmt::Debug for MurRequestIdGenerator {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::UuidV7 => f.write_str("UuidV7"),
+			Self::UuidV4 => f.write_str("UuidV4"),
+			Self::MurGenId => f.write_str("MurGenId"),
+			Self::Custom(_) => f.write_str("Custom"),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct MurRequestIdConfig {
+	pub header_name: String,
+	/// Reuse a valid ID sent by the client or an upstream proxy instead of
+	/// generating a new one.
+	pub trust_incoming: bool,
+	/// Incoming IDs longer than this are replaced.
+	pub max_length: usize,
+	pub generator: MurRequestIdGenerator,
+}
+
+impl Default for MurRequestIdConfig {
+	fn default() -> Self {
+		Self {
+			header_name: "X-Request-Id".to_string(),
+			trust_incoming: true,
+			max_length: 128,
+			generator: MurRequestIdGenerator::default(),
+		}
+	}
+}
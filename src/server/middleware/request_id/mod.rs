@@ -0,0 +1,9 @@
+pub mod config;
+pub mod mur_request_id;
+
+pub use config::MurRequestIdConfig;
+pub use config::MurRequestIdGenerator;
+pub use mur_request_id::MurRequestId;
+
+#[cfg(test)]
+mod test;
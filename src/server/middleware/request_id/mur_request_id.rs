@@ -0,0 +1,129 @@
+use super::{MurRequestIdConfig, MurRequestIdGenerator};
+use crate::server::aliases::{MurFuture, MurRes};
+use crate::server::http::MurRequestContext;
+use crate::server::logging::mur_with_request_id;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use http::{HeaderName, HeaderValue};
+use std::sync::Arc;
+
+/// Request extension holding the ID assigned by [`MurRequestId`]. Read it
+/// with [`MurRequestContext::request_id`].
+#[derive(Debug, Clone)]
+pub(crate) struct MurRequestIdExtension(pub String);
+
+/// Assigns every request an ID, taken from the incoming `X-Request-Id` when
+/// it is well-formed or generated otherwise.
+///
+/// The ID is available as `ctx.request_id()`, echoed in the response header,
+/// included in error bodies built by `MurError::into_response`, and exposed
+/// to log output through
+/// [`mur_current_request_id`](crate::server::logging::mur_current_request_id).
+/// Register it first so the rest of the chain runs inside its scope.
+#[derive(Clone)]
+pub struct MurRequestId {
+	config: Arc<MurRequestIdConfig>,
+}
+
+impl MurRequestId {
+	pub fn new() -> Self {
+		Self {
+			config: Arc::new(MurRequestIdConfig::default()),
+		}
+	}
+
+	pub fn from_config(config: MurRequestIdConfig) -> Self {
+		Self {
+			config: Arc::new(config),
+		}
+	}
+
+	pub fn header_name(mut self, name: impl Into<String>) -> Self {
+		let mut config = (*self.config).clone();
+		config.header_name = name.into();
+		self.config = Arc::new(config);
+		self
+	}
+
+	/// Always generates a fresh ID, ignoring the incoming header.
+	pub fn ignore_incoming(mut self) -> Self {
+		let mut config = (*self.config).clone();
+		config.trust_incoming = false;
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn generator(mut self, generator: MurRequestIdGenerator) -> Self {
+		let mut config = (*self.config).clone();
+		config.generator = generator;
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn custom_generator(self, generator: impl Fn() -> String + Send + Sync + 'static) -> Self {
+		self.generator(MurRequestIdGenerator::Custom(Arc::new(generator)))
+	}
+
+	/// Returns the incoming ID if it may be reused, or a new one.
+	pub fn resolve(&self, ctx: &MurRequestContext) -> String {
+		if self.config.trust_incoming
+			&& let Some(id) = ctx.header(&self.config.header_name)
+			&& self.is_valid(id)
+		{
+			return id.to_string();
+		}
+		self.config.generator.generate()
+	}
+
+	/// Accepts IDs of at most `max_length` characters made of letters,
+	/// digits and `-_.:`, so they are safe to echo and log.
+	pub fn is_valid(&self, id: &str) -> bool {
+		!id.is_empty()
+			&& id.len() <= self.config.max_length
+			&& id
+				.bytes()
+				.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+	}
+}
+
+impl Default for MurRequestId {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl std::fmt::Debug for MurRequestId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurRequestId")
+			.field("config", &self.config)
+			.finish()
+	}
+}
+
+impl MurMiddleware for MurRequestId {
+	fn handle(&self, mut ctx: MurRequestContext, next: MurNext) -> MurFuture {
+		let id = self.resolve(&ctx);
+		let header_name = HeaderName::from_bytes(self.config.header_name.as_bytes()).ok();
+		ctx
+			.parts
+			.extensions
+			.insert(MurRequestIdExtension(id.clone()));
+
+		Box::pin(mur_with_request_id(id.clone(), async move {
+			// Errors are rendered here, inside the scope, so their bodies
+			// carry the ID.
+			let mut response = match next.run(ctx).await.into_result() {
+				Ok(response) => response,
+				Err(e) => e.into_response(),
+			};
+
+			if let (Some(name), Ok(value)) = (header_name, HeaderValue::from_str(&id)) {
+				response.headers_mut().entry(name).or_insert(value);
+			}
+			MurRes::from(response)
+		}))
+	}
+
+	fn name(&self) -> &str {
+		"MurRequestId"
+	}
+}
@@ -0,0 +1,124 @@
+use super::*;
+use crate::server::aliases::{MurFuture, MurRes};
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::logging::mur_current_request_id;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use crate::server::service::MurServiceContainer;
+use http::StatusCode;
+use http_body_util::{BodyExt, Full};
+use hyper::Response;
+use hyper::body::Bytes;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn ctx(headers: &[(&str, &str)]) -> MurRequestContext {
+	let mut builder = http::Request::builder().method("GET").uri("/");
+	for (name, value) in headers {
+		builder = builder.header(*name, *value);
+	}
+	let (parts, _) = builder.body(()).unwrap().into_parts();
+	MurRequestContext::new(
+		parts,
+		None,
+		HashMap::new(),
+		Arc::new(MurServiceContainer::new()),
+	)
+}
+
+/// Echoes the ID seen on the context and in the logging scope.
+fn echo() -> MurNext {
+	MurNext::new(Arc::new(|ctx| -> MurFuture {
+		Box::pin(async move {
+			let body = format!(
+				"{}|{}",
+				ctx.request_id().unwrap_or_default(),
+				mur_current_request_id().unwrap_or_default()
+			);
+			MurRes::from(
+				Response::builder()
+					.status(StatusCode::OK)
+					.body(Full::new(Bytes::from(body)))
+					.unwrap(),
+			)
+		})
+	}))
+}
+
+fn failing() -> MurNext {
+	MurNext::new(Arc::new(|_ctx| -> MurFuture {
+		Box::pin(async { MurRes::from(MurError::not_found("missing")) })
+	}))
+}
+
+async fn body_text(response: hyper::Response<Full<Bytes>>) -> String {
+	let bytes = response.into_body().collect().await.unwrap().to_bytes();
+	String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[test]
+fn test_generators() {
+	let v7 = MurRequestIdGenerator::UuidV7.generate();
+	assert_eq!(uuid::Uuid::parse_str(&v7).unwrap().get_version_num(), 7);
+
+	let v4 = MurRequestIdGenerator::UuidV4.generate();
+	assert_eq!(uuid::Uuid::parse_str(&v4).unwrap().get_version_num(), 4);
+
+	let compact = MurRequestIdGenerator::MurGenId.generate();
+	assert!(compact.bytes().all(|b| b.is_ascii_hexdigit()));
+}
+
+#[test]
+fn test_incoming_validation() {
+	let middleware = MurRequestId::new();
+	assert!(middleware.is_valid("abc-123_x.y:z"));
+	assert!(!middleware.is_valid(""));
+	assert!(!middleware.is_valid("has space"));
+	assert!(!middleware.is_valid("line\nbreak"));
+	assert!(!middleware.is_valid(&"a".repeat(129)));
+}
+
+#[test]
+fn test_resolve_reuses_or_generates() {
+	let middleware = MurRequestId::new().custom_generator(|| "generated".to_string());
+	assert_eq!(middleware.resolve(&ctx(&[("x-request-id", "abc")])), "abc");
+	assert_eq!(
+		middleware.resolve(&ctx(&[("x-request-id", "bad id")])),
+		"generated"
+	);
+	assert_eq!(middleware.resolve(&ctx(&[])), "generated");
+
+	let strict = middleware.ignore_incoming();
+	assert_eq!(
+		strict.resolve(&ctx(&[("x-request-id", "abc")])),
+		"generated"
+	);
+}
+
+#[tokio::test]
+async fn test_id_on_context_scope_and_header() {
+	let response = MurRequestId::new()
+		.handle(ctx(&[("X-Request-Id", "req-1")]), echo())
+		.await
+		.into_result()
+		.unwrap();
+
+	assert_eq!(response.headers()["x-request-id"], "req-1");
+	assert_eq!(body_text(response).await, "req-1|req-1");
+}
+
+#[tokio::test]
+async fn test_error_body_carries_id() {
+	let response = MurRequestId::new()
+		.header_name("X-Correlation-Id")
+		.handle(ctx(&[("X-Correlation-Id", "corr-9")]), failing())
+		.await
+		.into_result()
+		.unwrap();
+
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	assert_eq!(response.headers()["x-correlation-id"], "corr-9");
+	let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+	assert_eq!(body["request_id"], "corr-9");
+	assert_eq!(body["error"], "missing");
+}
@@ -11,6 +11,10 @@ pub struct TimeoutConfig {
 	pub include_timeout_header: bool,
 	pub timeout_header_name: String,
 	pub log_timeouts: bool,
+	/// Shorten the deadline when the client sends a smaller timeout in one of
+	/// `inbound_timeout_headers`. The configured timeout always caps it.
+	pub honor_inbound_timeout: bool,
+	pub inbound_timeout_headers: Vec<String>,
 }
 
 impl TimeoutConfig {
@@ -24,6 +28,8 @@ impl TimeoutConfig {
 			include_timeout_header: false,
 			timeout_header_name: "X-Timeout-Duration".to_string(),
 			log_timeouts: true,
+			honor_inbound_timeout: true,
+			inbound_timeout_headers: vec!["grpc-timeout".to_string(), "x-request-timeout".to_string()],
 		}
 	}
 
@@ -84,6 +90,16 @@ impl TimeoutConfig {
 		self.log_timeouts = log;
 		self
 	}
+
+	pub fn honor_inbound_timeout(mut self, honor: bool) -> Self {
+		self.honor_inbound_timeout = honor;
+		self
+	}
+
+	pub fn inbound_timeout_header(mut self, name: impl Into<String>) -> Self {
+		self.inbound_timeout_headers.push(name.into());
+		self
+	}
 }
 
 impl Default for TimeoutConfig {
@@ -0,0 +1,111 @@
+use crate::server::error::MurError;
+use hyper::StatusCode;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// The point in time by which the current request must be answered.
+///
+/// [`MurTimeout`](super::MurTimeout) stores it in the request extensions; read
+/// it with [`MurRequestContext::deadline`](crate::MurRequestContext::deadline)
+/// to size downstream calls or to stop long-running work early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MurDeadline {
+	at: Instant,
+	budget: Duration,
+}
+
+impl MurDeadline {
+	pub fn after(budget: Duration) -> Self {
+		Self {
+			at: Instant::now() + budget,
+			budget,
+		}
+	}
+
+	pub fn at(&self) -> Instant {
+		self.at
+	}
+
+	/// The total time the request was given.
+	pub fn budget(&self) -> Duration {
+		self.budget
+	}
+
+	pub fn remaining(&self) -> Duration {
+		self.at.saturating_duration_since(Instant::now())
+	}
+
+	pub fn is_expired(&self) -> bool {
+		Instant::now() >= self.at
+	}
+
+	/// Returns an error once the deadline has passed, for cooperative checks
+	/// inside loops or blocking work.
+	pub fn check(&self) -> Result<(), MurError> {
+		if self.is_expired() {
+			Err(Self::exceeded())
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Runs `future` until it completes or the deadline passes, whichever
+	/// comes first. The future is dropped when the deadline is hit.
+	pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, MurError> {
+		tokio::time::timeout_at(self.at.into(), future)
+			.await
+			.map_err(|_| Self::exceeded())
+	}
+
+	fn exceeded() -> MurError {
+		MurError::Custom(StatusCode::GATEWAY_TIMEOUT, "Deadline exceeded".to_string())
+	}
+}
+
+/// Request extension carrying the timeout set with `#[timeout]` on the
+/// matched route. [`MurTimeout`](super::MurTimeout) uses it in place of its
+/// configured timeout.
+#[derive(Debug, Clone, Copy)]
+pub struct MurRouteTimeout(pub Duration);
+
+/// Parses an inbound timeout header value.
+///
+/// Accepts the gRPC format (`"100m"`, `"5S"`: up to 8 digits followed by one of
+/// `H`, `M`, `S`, `m`, `u`, `n`) as well as plain seconds (`"2.5"`, `"2.5s"`)
+/// and milliseconds (`"500ms"`).
+pub fn mur_parse_timeout(value: &str) -> Option<Duration> {
+	let value = value.trim();
+	if value.is_empty() {
+		return None;
+	}
+
+	if let Some(ms) = value.strip_suffix("ms") {
+		return ms.parse::<u64>().ok().map(Duration::from_millis);
+	}
+
+	let (amount, unit) = match value.char_indices().last() {
+		Some((i, _)) => value.split_at(i),
+		None => return None,
+	};
+	if !amount.is_empty() && amount.len() <= 8 && amount.bytes().all(|b| b.is_ascii_digit()) {
+		let amount: u64 = amount.parse().ok()?;
+		let duration = match unit {
+			"H" => Duration::from_secs(amount * 3_600),
+			"M" => Duration::from_secs(amount * 60),
+			"S" | "s" => Duration::from_secs(amount),
+			"m" => Duration::from_millis(amount),
+			"u" => Duration::from_micros(amount),
+			"n" => Duration::from_nanos(amount),
+			_ => return None,
+		};
+		return Some(duration);
+	}
+
+	value
+		.strip_suffix('s')
+		.unwrap_or(value)
+		.parse::<f64>()
+		.ok()
+		.filter(|secs| secs.is_finite() && *secs >= 0.0)
+		.and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+}
@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters kept by [`MurTimeout`](super::MurTimeout). Clones of the same
+/// middleware share one set of counters.
+#[derive(Debug, Default)]
+pub struct MurTimeoutMetrics {
+	requests: AtomicU64,
+	timed_out: AtomicU64,
+	skipped: AtomicU64,
+	inbound: AtomicU64,
+}
+
+impl MurTimeoutMetrics {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Requests that ran under a deadline.
+	pub fn requests(&self) -> u64 {
+		self.requests.load(Ordering::Relaxed)
+	}
+
+	/// Requests that hit their deadline and were cancelled.
+	pub fn timed_out(&self) -> u64 {
+		self.timed_out.load(Ordering::Relaxed)
+	}
+
+	/// Requests on skipped paths.
+	pub fn skipped(&self) -> u64 {
+		self.skipped.load(Ordering::Relaxed)
+	}
+
+	/// Requests whose deadline was shortened by an inbound timeout header.
+	pub fn inbound(&self) -> u64 {
+		self.inbound.load(Ordering::Relaxed)
+	}
+
+	pub(crate) fn record_request(&self) {
+		self.requests.fetch_add(1, Ordering::Relaxed);
+	}
+
+	pub(crate) fn record_timeout(&self) {
+		self.timed_out.fetch_add(1, Ordering::Relaxed);
+	}
+
+	pub(crate) fn record_skip(&self) {
+		self.skipped.fetch_add(1, Ordering::Relaxed);
+	}
+
+	pub(crate) fn record_inbound(&self) {
+		self.inbound.fetch_add(1, Ordering::Relaxed);
+	}
+}
@@ -1,7 +1,13 @@
 pub mod config;
+pub mod deadline;
+pub mod metrics;
 pub mod mur_timeout;
 
 pub use config::TimeoutConfig;
+pub use deadline::MurDeadline;
+pub use deadline::MurRouteTimeout;
+pub use deadline::mur_parse_timeout;
+pub use metrics::MurTimeoutMetrics;
 pub use mur_timeout::MurTimeout;
 
 #[cfg(test)]
@@ -1,8 +1,11 @@
 use super::TimeoutConfig;
+use super::deadline::{MurDeadline, MurRouteTimeout, mur_parse_timeout};
+use super::metrics::MurTimeoutMetrics;
 use crate::server::aliases::MurFuture;
 use crate::server::aliases::MurRes;
 use crate::server::http::MurHttpResponse;
 use crate::server::http::MurRequestContext;
+use crate::server::logging::mur_log_line;
 use crate::server::middleware::{MurMiddleware, MurNext};
 use hyper::StatusCode;
 use std::sync::Arc;
@@ -11,13 +14,12 @@ use std::time::Duration;
 #[derive(Clone)]
 pub struct MurTimeout {
 	pub config: Arc<TimeoutConfig>,
+	metrics: Arc<MurTimeoutMetrics>,
 }
 
 impl MurTimeout {
 	pub fn new(timeout: Duration) -> Self {
-		Self {
-			config: Arc::new(TimeoutConfig::new(timeout)),
-		}
+		Self::from_config(TimeoutConfig::new(timeout))
 	}
 
 	pub fn from_secs(secs: u64) -> Self {
@@ -31,6 +33,7 @@ impl MurTimeout {
 	pub fn from_config(config: TimeoutConfig) -> Self {
 		Self {
 			config: Arc::new(config),
+			metrics: Arc::new(MurTimeoutMetrics::new()),
 		}
 	}
 
@@ -102,6 +105,45 @@ impl MurTimeout {
 		self
 	}
 
+	pub fn honor_inbound(mut self, honor: bool) -> Self {
+		let mut config = (*self.config).clone();
+		config.honor_inbound_timeout = honor;
+		self.config = Arc::new(config);
+		self
+	}
+
+	pub fn metrics(&self) -> &Arc<MurTimeoutMetrics> {
+		&self.metrics
+	}
+
+	/// Computes the deadline for a request: the route's `#[timeout]` or the
+	/// configured timeout, shortened by an inbound timeout header when one is
+	/// present and smaller. Returns whether the inbound header was used.
+	pub fn deadline_for(&self, ctx: &MurRequestContext) -> (MurDeadline, bool) {
+		let budget = ctx
+			.parts
+			.extensions
+			.get::<MurRouteTimeout>()
+			.map(|route| route.0)
+			.unwrap_or(self.config.timeout);
+
+		let inbound = if self.config.honor_inbound_timeout {
+			self
+				.config
+				.inbound_timeout_headers
+				.iter()
+				.find_map(|name| ctx.header(name).and_then(mur_parse_timeout))
+				.filter(|inbound| *inbound < budget)
+		} else {
+			None
+		};
+
+		match inbound {
+			Some(inbound) => (MurDeadline::after(inbound), true),
+			None => (MurDeadline::after(budget), false),
+		}
+	}
+
 	pub fn should_skip(&self, path: &str) -> bool {
 		if self.config.skip_paths.iter().any(|p| p == path) {
 			return true;
@@ -120,20 +162,17 @@ impl MurTimeout {
 	}
 
 	pub fn build_timeout_response(&self) -> MurRes {
+		self.timeout_response(self.config.timeout)
+	}
+
+	fn timeout_response(&self, budget: Duration) -> MurRes {
 		let message = self
 			.config
 			.message
-			.clone()
-			.unwrap_or_else(|| "Request timed out".to_string());
-
-		let timeout_secs = self.config.timeout.as_secs_f64();
+			.as_deref()
+			.unwrap_or("Request timed out");
 
-		MurHttpResponse::status(self.config.status_code).json(serde_json::json!({
-			"error": "Timeout",
-			"message": message,
-			"timeout_seconds": timeout_secs,
-			"status": self.config.status_code.as_u16()
-		}))
+		mur_timed_out(self.config.status_code, message, budget)
 	}
 }
 
@@ -161,22 +200,32 @@ impl MurMiddleware for MurTimeout {
 			let method = ctx.method().to_string();
 
 			if timeout_middleware.should_skip(&path) {
+				timeout_middleware.metrics.record_skip();
 				return next.run(ctx).await;
 			}
 
+			let (deadline, inbound) = timeout_middleware.deadline_for(&ctx);
+			timeout_middleware.metrics.record_request();
+			if inbound {
+				timeout_middleware.metrics.record_inbound();
+			}
+
+			let mut ctx = ctx;
+			ctx.parts.extensions.insert(deadline);
+
 			let start = std::time::Instant::now();
-			let result = tokio::time::timeout(config.timeout, next.run(ctx)).await;
+			let result = tokio::time::timeout_at(deadline.at().into(), next.run(ctx)).await;
 
 			match result {
 				Ok(response) => {
 					if config.include_timeout_header {
 						let elapsed = start.elapsed();
 						response.map_response(|mut resp| {
-							if let Ok(value) = format!("{}ms", elapsed.as_millis())
-								.parse::<hyper::header::HeaderValue>()
-								&& let Ok(header_name) = hyper::header::HeaderName::from_bytes(
-									config.timeout_header_name.as_bytes(),
-								) {
+							if let Ok(value) =
+								format!("{}ms", elapsed.as_millis()).parse::<hyper::header::HeaderValue>()
+								&& let Ok(header_name) =
+									hyper::header::HeaderName::from_bytes(config.timeout_header_name.as_bytes())
+							{
 								resp.headers_mut().insert(header_name, value);
 							}
 							resp
@@ -186,15 +235,19 @@ impl MurMiddleware for MurTimeout {
 					}
 				}
 				Err(_elapsed) => {
+					timeout_middleware.metrics.record_timeout();
 					if config.log_timeouts {
 						eprintln!(
-							"[TIMEOUT] {} {} exceeded {}ms timeout",
-							method,
-							path,
-							config.timeout.as_millis()
+							"{}",
+							mur_log_line(format_args!(
+								"[TIMEOUT] {} {} exceeded {}ms timeout",
+								method,
+								path,
+								deadline.budget().as_millis()
+							))
 						);
 					}
-					timeout_middleware.build_timeout_response()
+					timeout_middleware.timeout_response(deadline.budget())
 				}
 			}
 		})
@@ -204,3 +257,13 @@ impl MurMiddleware for MurTimeout {
 		"MurTimeout"
 	}
 }
+
+/// Builds the JSON body returned when a request runs out of time.
+pub(crate) fn mur_timed_out(status: StatusCode, message: &str, budget: Duration) -> MurRes {
+	MurHttpResponse::status(status).json(serde_json::json!({
+		"error": "Timeout",
+		"message": message,
+		"timeout_seconds": budget.as_secs_f64(),
+		"status": status.as_u16()
+	}))
+}
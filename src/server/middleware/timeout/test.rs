@@ -1,7 +1,46 @@
 use super::*;
-use hyper::StatusCode;
+use crate::server::aliases::{MurFuture, MurRes};
+use crate::server::http::MurRequestContext;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use crate::server::service::MurServiceContainer;
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::{Response, StatusCode};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+fn ctx(path: &str, headers: &[(&str, &str)]) -> MurRequestContext {
+	let mut builder = http::Request::builder().method("GET").uri(path);
+	for (name, value) in headers {
+		builder = builder.header(*name, *value);
+	}
+	let (parts, _) = builder.body(()).unwrap().into_parts();
+	MurRequestContext::new(
+		parts,
+		None,
+		HashMap::new(),
+		Arc::new(MurServiceContainer::new()),
+	)
+}
+
+/// A handler that sleeps for `delay` and records the deadline it saw.
+fn sleeping(delay: Duration, seen: Arc<Mutex<Option<MurDeadline>>>) -> MurNext {
+	MurNext::new(Arc::new(move |ctx| -> MurFuture {
+		let seen = Arc::clone(&seen);
+		Box::pin(async move {
+			*seen.lock().unwrap() = ctx.deadline();
+			tokio::time::sleep(delay).await;
+			MurRes::from(
+				Response::builder()
+					.status(StatusCode::OK)
+					.body(Full::new(Bytes::from("done")))
+					.unwrap(),
+			)
+		})
+	}))
+}
+
 #[test]
 fn test_timeout_config_default() {
 	let config = TimeoutConfig::default();
@@ -71,3 +110,113 @@ fn test_convenience_functions() {
 	let gateway = MurTimeout::from_secs(60).gateway_timeout();
 	assert_eq!(gateway.config.status_code, StatusCode::GATEWAY_TIMEOUT);
 }
+
+#[test]
+fn test_parse_grpc_timeout() {
+	assert_eq!(mur_parse_timeout("100m"), Some(Duration::from_millis(100)));
+	assert_eq!(mur_parse_timeout("5S"), Some(Duration::from_secs(5)));
+	assert_eq!(mur_parse_timeout("2M"), Some(Duration::from_secs(120)));
+	assert_eq!(mur_parse_timeout("1H"), Some(Duration::from_secs(3600)));
+	assert_eq!(mur_parse_timeout("250u"), Some(Duration::from_micros(250)));
+	assert_eq!(mur_parse_timeout("10n"), Some(Duration::from_nanos(10)));
+	assert_eq!(mur_parse_timeout("123456789S"), None);
+}
+
+#[test]
+fn test_parse_plain_timeout() {
+	assert_eq!(mur_parse_timeout("2"), Some(Duration::from_secs(2)));
+	assert_eq!(mur_parse_timeout("1.5"), Some(Duration::from_millis(1500)));
+	assert_eq!(mur_parse_timeout("1.5s"), Some(Duration::from_millis(1500)));
+	assert_eq!(mur_parse_timeout("500ms"), Some(Duration::from_millis(500)));
+	assert_eq!(mur_parse_timeout(""), None);
+	assert_eq!(mur_parse_timeout("-1"), None);
+	assert_eq!(mur_parse_timeout("soon"), None);
+	assert_eq!(mur_parse_timeout("5é"), None);
+}
+
+#[tokio::test]
+async fn test_deadline_run_cancels_future() {
+	let deadline = MurDeadline::after(Duration::from_millis(20));
+	let result = deadline
+		.run(tokio::time::sleep(Duration::from_secs(5)))
+		.await;
+	let err = result.unwrap_err();
+	assert_eq!(err.status_code(), StatusCode::GATEWAY_TIMEOUT);
+	assert!(deadline.is_expired());
+	assert!(deadline.check().is_err());
+	assert_eq!(deadline.remaining(), Duration::ZERO);
+}
+
+#[test]
+fn test_deadline_for_uses_smaller_inbound_header() {
+	let timeout = MurTimeout::from_secs(10);
+
+	let (deadline, inbound) = timeout.deadline_for(&ctx("/", &[("grpc-timeout", "200m")]));
+	assert!(inbound);
+	assert_eq!(deadline.budget(), Duration::from_millis(200));
+
+	let (deadline, inbound) = timeout.deadline_for(&ctx("/", &[("X-Request-Timeout", "60")]));
+	assert!(
+		!inbound,
+		"inbound timeouts are capped by the configured one"
+	);
+	assert_eq!(deadline.budget(), Duration::from_secs(10));
+
+	let ignoring = MurTimeout::from_secs(10).honor_inbound(false);
+	let (deadline, _) = ignoring.deadline_for(&ctx("/", &[("grpc-timeout", "200m")]));
+	assert_eq!(deadline.budget(), Duration::from_secs(10));
+}
+
+#[test]
+fn test_deadline_for_uses_route_override() {
+	let timeout = MurTimeout::from_secs(10);
+	let mut request = ctx("/", &[]);
+	request
+		.parts
+		.extensions
+		.insert(MurRouteTimeout(Duration::from_secs(60)));
+
+	let (deadline, _) = timeout.deadline_for(&request);
+	assert_eq!(deadline.budget(), Duration::from_secs(60));
+}
+
+#[tokio::test]
+async fn test_handler_sees_deadline() {
+	let timeout = MurTimeout::from_secs(10);
+	let seen = Arc::new(Mutex::new(None));
+	let response = timeout
+		.handle(ctx("/", &[]), sleeping(Duration::ZERO, Arc::clone(&seen)))
+		.await
+		.into_result()
+		.unwrap();
+
+	assert_eq!(response.status(), StatusCode::OK);
+	let deadline = seen.lock().unwrap().expect("deadline set");
+	assert_eq!(deadline.budget(), Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn test_timeout_cancels_handler_and_counts() {
+	let timeout = MurTimeout::from_secs(10).log(false).skip_path("/health");
+	let seen = Arc::new(Mutex::new(None));
+
+	let response = timeout
+		.handle(
+			ctx("/slow", &[("grpc-timeout", "20m")]),
+			sleeping(Duration::from_secs(5), Arc::clone(&seen)),
+		)
+		.await
+		.into_result()
+		.unwrap();
+	assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+
+	timeout
+		.handle(ctx("/health", &[]), sleeping(Duration::ZERO, seen))
+		.await;
+
+	let metrics = timeout.metrics();
+	assert_eq!(metrics.requests(), 1);
+	assert_eq!(metrics.timed_out(), 1);
+	assert_eq!(metrics.inbound(), 1);
+	assert_eq!(metrics.skipped(), 1);
+}
@@ -5,17 +5,26 @@ pub mod controller;
 pub mod decorator;
 pub mod error;
 pub mod guard;
+mod handoff;
 pub mod http;
 pub mod interceptor;
+pub mod listener;
+pub mod logging;
+pub mod metrics;
 pub mod middleware;
 pub mod module;
+pub mod oidc;
 pub mod pipe;
 pub mod provider;
 pub mod router;
 pub mod runner;
 pub mod security;
 pub mod service;
+pub mod session;
+pub mod shutdown;
 pub mod specs;
 
 pub use builder::MurServer;
-pub use runner::MurServerRunner;
+pub use listener::{MurListenAddr, MurListener};
+pub use runner::{MurConnectionStats, MurServerRunner};
+pub use shutdown::MurShutdownToken;
@@ -1,5 +1,6 @@
 use crate::MurServiceContainer;
 use crate::server::controller::MurController;
+use crate::server::middleware::MurMiddleware;
 use crate::server::service::MurInjects;
 use crate::server::service::MurService;
 use std::any::TypeId;
@@ -35,6 +36,24 @@ pub trait MurModule: Send + Sync + 'static {
 		self.services()
 	}
 
+	/// The path prefix applied to every controller declared in this module.
+	fn prefix(&self) -> &str {
+		"/"
+	}
+
+	/// Middleware applied to every request, after the server's global
+	/// middleware.
+	fn middleware(&self) -> Vec<Box<dyn MurMiddleware + Send + Sync>> {
+		Vec::new()
+	}
+
+	/// Whether the exports of this module are visible to every module, as if
+	/// each one imported it. Framework modules whose services should be
+	/// injectable anywhere, such as a metrics registry, return `true`.
+	fn is_global(&self) -> bool {
+		false
+	}
+
 	fn name(&self) -> &str;
 	fn exports(&self) -> Vec<TypeId>;
 	fn imports(&self) -> Vec<Arc<dyn MurModule>>;
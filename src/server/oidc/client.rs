@@ -0,0 +1,507 @@
+use super::fetch::{self, MurOidcResponse};
+use super::id_token::MurJwt;
+use super::identity::{IDENTITY_KEY, MurOidcPending, MurTokenResponse, PENDING_KEY};
+use super::{
+	MurIdTokenClaims, MurJwk, MurJwkSet, MurOidcConfig, MurOidcDiscovery, MurOidcIdentity,
+	MurOidcTokens,
+};
+use crate::core::utils::{MurCodec, MurRand, MurTime};
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::service::MurService;
+use crate::server::session::MurSession;
+use aws_lc_rs::constant_time::verify_slices_are_equal;
+use aws_lc_rs::digest::{SHA256, digest};
+use http::{Method, Request};
+use http_body_util::Full;
+use hyper::body::Bytes;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::any::Any;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Talks to an OpenID Connect provider: discovery, the authorization code
+/// flow with PKCE, ID token validation and token refresh.
+///
+/// The discovery document and the JWKS are cached. A token signed with a
+/// key missing from the cached JWKS causes a refetch, so rotated keys are
+/// picked up without a restart.
+///
+/// Registered as a service by [`MurOidcModule`](super::MurOidcModule):
+///
+/// ```rust,ignore
+/// #[get("/profile")]
+/// async fn profile(&self, ctx: MurRequestContext) -> MurRes {
+///     let oidc = ctx.service_required::<MurOidcClient>();
+///     match oidc.identity(&ctx).await {
+///         Ok(Some(identity)) => MurHttpResponse::ok().json(identity.claims),
+///         Ok(None) => MurHttpResponse::redirect("/auth/login?return_to=/profile"),
+///         Err(e) => e.into(),
+///     }
+/// }
+/// ```
+pub struct MurOidcClient {
+	config: Arc<MurOidcConfig>,
+	discovery: RwLock<Option<MurCached<MurOidcDiscovery>>>,
+	jwks: RwLock<Option<MurCached<MurJwkSet>>>,
+}
+
+struct MurCached<T> {
+	value: Arc<T>,
+	fetched_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct MurTokenError {
+	error: String,
+	#[serde(default)]
+	error_description: Option<String>,
+}
+
+impl std::fmt::Debug for MurOidcClient {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurOidcClient")
+			.field("issuer", &self.config.issuer)
+			.field("client_id", &self.config.client_id)
+			.finish()
+	}
+}
+
+impl MurOidcClient {
+	pub fn new(config: MurOidcConfig) -> Self {
+		Self {
+			config: Arc::new(config),
+			discovery: RwLock::new(None),
+			jwks: RwLock::new(None),
+		}
+	}
+
+	pub fn config(&self) -> &MurOidcConfig {
+		&self.config
+	}
+
+	/// The provider's discovery document, fetched once per `discovery_ttl`.
+	pub async fn discovery(&self) -> Result<Arc<MurOidcDiscovery>, MurError> {
+		if let Some(cached) = fresh(&self.discovery, self.config.discovery_ttl) {
+			return Ok(cached);
+		}
+
+		let document: MurOidcDiscovery = self.get_json(&self.config.discovery_url()).await?;
+		if document.issuer.trim_end_matches('/') != self.config.issuer.trim_end_matches('/') {
+			return Err(MurError::service_unavailable(format!(
+				"Discovery document names issuer '{}', expected '{}'",
+				document.issuer, self.config.issuer
+			)));
+		}
+		Ok(store(&self.discovery, document))
+	}
+
+	/// The provider's signing keys, fetched once per `jwks_ttl`.
+	pub async fn jwks(&self) -> Result<Arc<MurJwkSet>, MurError> {
+		self.load_jwks(self.config.jwks_ttl).await
+	}
+
+	async fn load_jwks(&self, max_age: Duration) -> Result<Arc<MurJwkSet>, MurError> {
+		if let Some(cached) = fresh(&self.jwks, max_age) {
+			return Ok(cached);
+		}
+		let discovery = self.discovery().await?;
+		let keys: MurJwkSet = self.get_json(&discovery.jwks_uri).await?;
+		Ok(store(&self.jwks, keys))
+	}
+
+	/// The key for `kid`, refetching the JWKS when the cached one lacks it.
+	async fn signing_key(&self, kid: Option<&str>, alg: &str) -> Result<MurJwk, MurError> {
+		if let Some(key) = self.jwks().await?.find(kid, alg) {
+			return Ok(key.clone());
+		}
+		let interval = self.config.jwks_refresh_interval.min(self.config.jwks_ttl);
+		self.load_jwks(interval)
+			.await?
+			.find(kid, alg)
+			.cloned()
+			.ok_or_else(|| MurError::unauthorized("ID token signed with an unknown key"))
+	}
+
+	/// The URL sending the user to the provider to log in.
+	pub async fn authorization_url(
+		&self,
+		state: &str,
+		nonce: &str,
+		code_challenge: &str,
+	) -> Result<String, MurError> {
+		let discovery = self.discovery().await?;
+		let scope = self.config.scopes.join(" ");
+		let mut params = vec![
+			("response_type", "code"),
+			("client_id", self.config.client_id.as_str()),
+			("redirect_uri", self.config.redirect_uri.as_str()),
+			("scope", scope.as_str()),
+			("state", state),
+			("nonce", nonce),
+			("code_challenge", code_challenge),
+			("code_challenge_method", "S256"),
+		];
+		params.extend(
+			self.config
+				.auth_params
+				.iter()
+				.map(|(name, value)| (name.as_str(), value.as_str())),
+		);
+		Ok(with_query(&discovery.authorization_endpoint, &params))
+	}
+
+	/// Redeems an authorization code for tokens.
+	pub async fn exchange_code(
+		&self,
+		code: &str,
+		verifier: &str,
+	) -> Result<MurOidcTokens, MurError> {
+		self.token_request(&[
+			("grant_type", "authorization_code"),
+			("code", code),
+			("redirect_uri", self.config.redirect_uri.as_str()),
+			("code_verifier", verifier),
+		])
+		.await
+	}
+
+	/// Trades a refresh token for fresh tokens. A rejected refresh token
+	/// fails with `401 Unauthorized`.
+	pub async fn refresh(&self, refresh_token: &str) -> Result<MurOidcTokens, MurError> {
+		self.token_request(&[
+			("grant_type", "refresh_token"),
+			("refresh_token", refresh_token),
+		])
+		.await
+	}
+
+	/// Verifies an ID token's signature against the provider's keys and
+	/// checks its issuer, audience, times and, when given, nonce.
+	pub async fn validate_id_token(
+		&self,
+		id_token: &str,
+		nonce: Option<&str>,
+	) -> Result<MurIdTokenClaims, MurError> {
+		let jwt = MurJwt::parse(id_token)?;
+		let alg = jwt.header.alg.as_str();
+		let discovery = self.discovery().await?;
+		if !discovery.id_token_signing_alg_values_supported.is_empty()
+			&& !discovery
+				.id_token_signing_alg_values_supported
+				.iter()
+				.any(|supported| supported == alg)
+		{
+			return Err(MurError::unauthorized(format!(
+				"ID token signed with unexpected algorithm '{}'",
+				alg
+			)));
+		}
+
+		let key = self.signing_key(jwt.header.kid.as_deref(), alg).await?;
+		if !key.verify(alg, jwt.signing_input.as_bytes(), &jwt.signature) {
+			return Err(MurError::unauthorized("Invalid ID token signature"));
+		}
+
+		let claims = jwt.claims()?;
+		self.check_claims(&claims, &discovery.issuer, nonce, MurTime::timestamp_secs())?;
+		Ok(claims)
+	}
+
+	pub(crate) fn check_claims(
+		&self,
+		claims: &MurIdTokenClaims,
+		issuer: &str,
+		nonce: Option<&str>,
+		now: u64,
+	) -> Result<(), MurError> {
+		let client_id = &self.config.client_id;
+		let leeway = self.config.leeway.as_secs();
+
+		if claims.iss != issuer {
+			return Err(MurError::unauthorized("ID token issued by another issuer"));
+		}
+		if !claims.aud.contains(client_id)
+			|| (claims.aud.len() > 1 && claims.azp.as_ref() != Some(client_id))
+		{
+			return Err(MurError::unauthorized("ID token issued for another client"));
+		}
+		if claims.exp.saturating_add(leeway) <= now {
+			return Err(MurError::unauthorized("ID token expired"));
+		}
+		if claims.iat > now.saturating_add(leeway) {
+			return Err(MurError::unauthorized("ID token issued in the future"));
+		}
+		if let Some(expected) = nonce {
+			let matches = claims.nonce.as_deref().is_some_and(|nonce| {
+				verify_slices_are_equal(nonce.as_bytes(), expected.as_bytes()).is_ok()
+			});
+			if !matches {
+				return Err(MurError::unauthorized("ID token nonce mismatch"));
+			}
+		}
+		Ok(())
+	}
+
+	/// Starts a login: remembers its state in the session and returns the
+	/// provider URL to redirect to. `return_to` must be a local path.
+	pub async fn start_login(
+		&self,
+		session: &MurSession,
+		return_to: Option<&str>,
+	) -> Result<String, MurError> {
+		let pending = MurOidcPending {
+			state: MurRand::secure_token(24),
+			nonce: MurRand::secure_token(24),
+			verifier: MurRand::secure_token(32),
+			return_to: return_to
+				.filter(|path| is_local_path(path))
+				.unwrap_or(&self.config.default_return_to)
+				.to_string(),
+			started_at: MurTime::timestamp_secs(),
+		};
+		let url = self
+			.authorization_url(
+				&pending.state,
+				&pending.nonce,
+				&pkce_challenge(&pending.verifier),
+			)
+			.await?;
+		session.insert(PENDING_KEY, &pending)?;
+		Ok(url)
+	}
+
+	/// Completes a login from the provider's callback request: checks the
+	/// state, redeems the code and validates the ID token. The session id is
+	/// regenerated and the identity stored in it. Returns the local path to
+	/// send the user to.
+	pub async fn finish_login(&self, ctx: &MurRequestContext) -> Result<String, MurError> {
+		let session = MurSession::from_ctx(ctx)?;
+		let pending = session
+			.remove(PENDING_KEY)
+			.and_then(|value| serde_json::from_value::<MurOidcPending>(value).ok())
+			.ok_or_else(|| MurError::bad_request("No login in progress"))?;
+
+		if let Some(error) = ctx.query_param("error") {
+			let description = ctx.query_param("error_description").unwrap_or(error);
+			return Err(MurError::unauthorized(format!(
+				"Login failed: {}",
+				description
+			)));
+		}
+		let state = ctx.query_param("state").unwrap_or_default();
+		if verify_slices_are_equal(state.as_bytes(), pending.state.as_bytes()).is_err() {
+			return Err(MurError::bad_request("Login state mismatch"));
+		}
+		if pending.started_at + self.config.login_timeout.as_secs() < MurTime::timestamp_secs() {
+			return Err(MurError::bad_request("Login expired"));
+		}
+		let code = ctx
+			.query_param("code")
+			.ok_or_else(|| MurError::bad_request("Missing authorization code"))?;
+
+		let tokens = self.exchange_code(code, &pending.verifier).await?;
+		let id_token = tokens
+			.id_token
+			.as_deref()
+			.ok_or_else(|| MurError::unauthorized("Token response lacks an ID token"))?;
+		let claims = self
+			.validate_id_token(id_token, Some(&pending.nonce))
+			.await?;
+
+		session.regenerate();
+		session.insert(IDENTITY_KEY, MurOidcIdentity { claims, tokens })?;
+		Ok(pending.return_to)
+	}
+
+	/// The logged-in user, with the access token refreshed first when it
+	/// expires within `refresh_margin`. A rejected refresh token logs the
+	/// user out.
+	pub async fn identity(
+		&self,
+		ctx: &MurRequestContext,
+	) -> Result<Option<MurOidcIdentity>, MurError> {
+		let session = MurSession::from_ctx(ctx)?;
+		let Some(mut identity) = session.get::<MurOidcIdentity>(IDENTITY_KEY) else {
+			return Ok(None);
+		};
+		let margin = self.config.refresh_margin.as_secs();
+		if !identity
+			.tokens
+			.expires_within(MurTime::timestamp_secs(), margin)
+		{
+			return Ok(Some(identity));
+		}
+		let Some(refresh_token) = identity.tokens.refresh_token.clone() else {
+			return Ok(Some(identity));
+		};
+
+		let mut tokens = match self.refresh(&refresh_token).await {
+			Ok(tokens) => tokens,
+			Err(MurError::Unauthorized(_)) => {
+				session.remove(IDENTITY_KEY);
+				return Ok(None);
+			}
+			Err(e) => return Err(e),
+		};
+		match tokens.id_token.as_deref() {
+			Some(id_token) => {
+				let claims = self.validate_id_token(id_token, None).await?;
+				if claims.sub != identity.claims.sub {
+					session.remove(IDENTITY_KEY);
+					return Err(MurError::unauthorized(
+						"Refreshed ID token names another subject",
+					));
+				}
+				identity.claims = claims;
+			}
+			None => tokens.id_token = identity.tokens.id_token.take(),
+		}
+		tokens.refresh_token.get_or_insert(refresh_token);
+		identity.tokens = tokens;
+
+		session.insert(IDENTITY_KEY, &identity)?;
+		Ok(Some(identity))
+	}
+
+	/// Ends the local session and returns where to send the user: the
+	/// provider's logout endpoint when it has one, or
+	/// `post_logout_redirect_uri`.
+	pub async fn logout(&self, ctx: &MurRequestContext) -> Result<String, MurError> {
+		let session = MurSession::from_ctx(ctx)?;
+		let id_token = session
+			.get::<MurOidcIdentity>(IDENTITY_KEY)
+			.and_then(|identity| identity.tokens.id_token);
+		session.destroy();
+
+		let local = self
+			.config
+			.post_logout_redirect_uri
+			.clone()
+			.unwrap_or_else(|| self.config.default_return_to.clone());
+		let discovery = self.discovery().await?;
+		let Some(endpoint) = &discovery.end_session_endpoint else {
+			return Ok(local);
+		};
+
+		let mut params = vec![("client_id", self.config.client_id.as_str())];
+		if let Some(id_token) = &id_token {
+			params.push(("id_token_hint", id_token.as_str()));
+		}
+		if let Some(uri) = &self.config.post_logout_redirect_uri {
+			params.push(("post_logout_redirect_uri", uri.as_str()));
+		}
+		Ok(with_query(endpoint, &params))
+	}
+
+	async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, MurError> {
+		let request = Request::builder()
+			.method(Method::GET)
+			.uri(url)
+			.header("Accept", "application/json")
+			.body(Full::new(Bytes::new()))
+			.map_err(|e| MurError::internal(format!("Invalid identity provider URL: {}", e)))?;
+		let response = fetch::send(request, self.config.http_timeout).await?;
+		if !response.status.is_success() {
+			return Err(MurError::service_unavailable(format!(
+				"Identity provider answered {} for {}",
+				response.status, url
+			)));
+		}
+		parse(&response)
+	}
+
+	async fn token_request(&self, params: &[(&str, &str)]) -> Result<MurOidcTokens, MurError> {
+		let discovery = self.discovery().await?;
+		let mut request = Request::builder()
+			.method(Method::POST)
+			.uri(&discovery.token_endpoint)
+			.header("Accept", "application/json")
+			.header("Content-Type", "application/x-www-form-urlencoded");
+
+		let mut params = params.to_vec();
+		match &self.config.client_secret {
+			Some(secret) => {
+				let credentials = format!(
+					"{}:{}",
+					urlencoding::encode(&self.config.client_id),
+					urlencoding::encode(secret)
+				);
+				let basic = format!("Basic {}", MurCodec::base64_encode(credentials.as_bytes()));
+				request = request.header("Authorization", basic);
+			}
+			None => params.push(("client_id", self.config.client_id.as_str())),
+		}
+		let body =
+			serde_urlencoded::to_string(&params).map_err(|e| MurError::internal(e.to_string()))?;
+		let request = request
+			.body(Full::new(Bytes::from(body)))
+			.map_err(|e| MurError::internal(format!("Invalid token endpoint: {}", e)))?;
+
+		let response = fetch::send(request, self.config.http_timeout).await?;
+		if response.status.is_success() {
+			let tokens: MurTokenResponse = parse(&response)?;
+			return Ok(tokens.into_tokens(MurTime::timestamp_secs()));
+		}
+		match serde_json::from_slice::<MurTokenError>(&response.body) {
+			Ok(error) if response.status.is_client_error() => Err(MurError::unauthorized(format!(
+				"Token request rejected: {}",
+				error.error_description.unwrap_or(error.error)
+			))),
+			_ => Err(MurError::service_unavailable(format!(
+				"Token endpoint answered {}",
+				response.status
+			))),
+		}
+	}
+}
+
+impl MurService for MurOidcClient {
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+/// The S256 PKCE challenge of `verifier` (RFC 7636 §4.2).
+pub(crate) fn pkce_challenge(verifier: &str) -> String {
+	MurCodec::base64_url_encode(digest(&SHA256, verifier.as_bytes()).as_ref())
+}
+
+/// Whether `path` stays on this site, so that `return_to` cannot be used
+/// as an open redirect.
+pub(crate) fn is_local_path(path: &str) -> bool {
+	path.starts_with('/')
+		&& !path.starts_with("//")
+		&& !path.contains('\\')
+		&& !path.chars().any(char::is_control)
+}
+
+fn with_query(url: &str, params: &[(&str, &str)]) -> String {
+	let query = serde_urlencoded::to_string(params).unwrap_or_default();
+	let separator = if url.contains('?') { '&' } else { '?' };
+	format!("{}{}{}", url, separator, query)
+}
+
+fn parse<T: DeserializeOwned>(response: &MurOidcResponse) -> Result<T, MurError> {
+	serde_json::from_slice(&response.body).map_err(|e| {
+		MurError::service_unavailable(format!("Invalid response from identity provider: {}", e))
+	})
+}
+
+fn fresh<T>(cache: &RwLock<Option<MurCached<T>>>, max_age: Duration) -> Option<Arc<T>> {
+	let cache = cache.read().unwrap_or_else(|e| e.into_inner());
+	cache
+		.as_ref()
+		.filter(|cached| cached.fetched_at.elapsed() < max_age)
+		.map(|cached| Arc::clone(&cached.value))
+}
+
+fn store<T>(cache: &RwLock<Option<MurCached<T>>>, value: T) -> Arc<T> {
+	let value = Arc::new(value);
+	*cache.write().unwrap_or_else(|e| e.into_inner()) = Some(MurCached {
+		value: Arc::clone(&value),
+		fetched_at: Instant::now(),
+	});
+	value
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+/// Provider, route and cache settings of [`MurOidcModule`](super::MurOidcModule).
+#[derive(Debug, Clone)]
+pub struct MurOidcConfig {
+	/// The provider's issuer URL. Its discovery document is read from
+	/// `{issuer}/.well-known/openid-configuration`.
+	pub issuer: String,
+	pub client_id: String,
+	/// Sent with HTTP Basic authentication to the token endpoint. Public
+	/// clients leave it unset and rely on PKCE alone.
+	pub client_secret: Option<String>,
+	/// The absolute URL of the callback route, as registered with the
+	/// provider.
+	pub redirect_uri: String,
+	pub scopes: Vec<String>,
+	/// Extra parameters of the authorization request, such as `prompt` or
+	/// `audience`.
+	pub auth_params: Vec<(String, String)>,
+	pub login_path: String,
+	pub callback_path: String,
+	pub logout_path: String,
+	/// Where users land after logging in when the login request named no
+	/// `return_to` path.
+	pub default_return_to: String,
+	/// Where users land after logging out. Sent to the provider as
+	/// `post_logout_redirect_uri` when it supports RP-initiated logout.
+	pub post_logout_redirect_uri: Option<String>,
+	/// The clock skew tolerated when checking token times.
+	pub leeway: Duration,
+	/// Access tokens expiring within this margin are refreshed by
+	/// [`MurOidcClient::identity`](super::MurOidcClient::identity).
+	pub refresh_margin: Duration,
+	/// How long a login may take between the redirect to the provider and
+	/// the callback.
+	pub login_timeout: Duration,
+	pub discovery_ttl: Duration,
+	pub jwks_ttl: Duration,
+	/// The least time between two JWKS fetches caused by a token signed with
+	/// an unknown key.
+	pub jwks_refresh_interval: Duration,
+	/// Upper bound for each request to the provider.
+	pub http_timeout: Duration,
+}
+
+impl MurOidcConfig {
+	pub fn new(
+		issuer: impl Into<String>,
+		client_id: impl Into<String>,
+		redirect_uri: impl Into<String>,
+	) -> Self {
+		Self {
+			issuer: issuer.into(),
+			client_id: client_id.into(),
+			client_secret: None,
+			redirect_uri: redirect_uri.into(),
+			scopes: vec![
+				"openid".to_string(),
+				"profile".to_string(),
+				"email".to_string(),
+			],
+			auth_params: Vec::new(),
+			login_path: "/auth/login".to_string(),
+			callback_path: "/auth/callback".to_string(),
+			logout_path: "/auth/logout".to_string(),
+			default_return_to: "/".to_string(),
+			post_logout_redirect_uri: None,
+			leeway: Duration::from_secs(60),
+			refresh_margin: Duration::from_secs(30),
+			login_timeout: Duration::from_secs(10 * 60),
+			discovery_ttl: Duration::from_secs(24 * 60 * 60),
+			jwks_ttl: Duration::from_secs(60 * 60),
+			jwks_refresh_interval: Duration::from_secs(30),
+			http_timeout: Duration::from_secs(10),
+		}
+	}
+
+	/// The URL of the provider's discovery document.
+	pub fn discovery_url(&self) -> String {
+		format!(
+			"{}/.well-known/openid-configuration",
+			self.issuer.trim_end_matches('/')
+		)
+	}
+}
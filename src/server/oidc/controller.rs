@@ -0,0 +1,108 @@
+use super::MurOidcClient;
+use crate::server::aliases::MurRouteHandler;
+use crate::server::controller::MurController;
+use crate::server::http::{MurHttpResponse, MurRequestContext};
+use crate::server::router::{MurRouteDefinition, MurRouteMetadata};
+use crate::server::service::MurServiceContainer;
+use crate::server::session::MurSession;
+use std::sync::Arc;
+
+/// The login, callback and logout routes of
+/// [`MurOidcModule`](super::MurOidcModule).
+#[derive(Debug, Clone)]
+pub struct MurOidcController {
+	client: Arc<MurOidcClient>,
+}
+
+impl MurOidcController {
+	pub fn new(client: Arc<MurOidcClient>) -> Self {
+		Self { client }
+	}
+
+	/// `GET login_path[?return_to=/path]` redirects to the provider.
+	fn login(&self) -> MurRouteHandler {
+		let client = Arc::clone(&self.client);
+		Arc::new(move |ctx: MurRequestContext| {
+			let client = Arc::clone(&client);
+			Box::pin(async move {
+				let session = match MurSession::from_ctx(&ctx) {
+					Ok(session) => session,
+					Err(e) => return e.into(),
+				};
+				match client
+					.start_login(&session, ctx.query_param("return_to"))
+					.await
+				{
+					Ok(url) => MurHttpResponse::redirect(url),
+					Err(e) => e.into(),
+				}
+			})
+		})
+	}
+
+	/// `GET callback_path?code=…&state=…` completes the login.
+	fn callback(&self) -> MurRouteHandler {
+		let client = Arc::clone(&self.client);
+		Arc::new(move |ctx: MurRequestContext| {
+			let client = Arc::clone(&client);
+			Box::pin(async move {
+				match client.finish_login(&ctx).await {
+					Ok(return_to) => MurHttpResponse::redirect(return_to),
+					Err(e) => e.into(),
+				}
+			})
+		})
+	}
+
+	/// `POST logout_path` ends the session, at the provider too when it
+	/// supports RP-initiated logout. It is not a `GET` so that a cross-site
+	/// link or image cannot log the user out; the `Lax` session cookie is not
+	/// sent on cross-site posts, and [`MurCsrf`](crate::MurCsrf) checks them
+	/// when registered.
+	fn logout(&self) -> MurRouteHandler {
+		let client = Arc::clone(&self.client);
+		Arc::new(move |ctx: MurRequestContext| {
+			let client = Arc::clone(&client);
+			Box::pin(async move {
+				match client.logout(&ctx).await {
+					Ok(location) => MurHttpResponse::see_other(location),
+					Err(e) => e.into(),
+				}
+			})
+		})
+	}
+}
+
+impl MurController for MurOidcController {
+	fn routes(self: Arc<Self>, _container: &MurServiceContainer) -> Vec<MurRouteDefinition> {
+		let config = self.client.config();
+		[
+			("GET", &config.login_path, self.login()),
+			("GET", &config.callback_path, self.callback()),
+			("POST", &config.logout_path, self.logout()),
+		]
+		.into_iter()
+		.map(|(method, path, handler)| MurRouteDefinition {
+			method: method.to_string(),
+			path: path.clone(),
+			handler,
+			is_public: true,
+			allowed_roles: vec![],
+			throttle: None,
+			timeout: None,
+			metadata: MurRouteMetadata::new(),
+			guards: vec![],
+			interceptors: vec![],
+			versions: vec![],
+		})
+		.collect()
+	}
+
+	fn base_path(&self) -> &str {
+		""
+	}
+
+	fn name(&self) -> &str {
+		"MurOidcController"
+	}
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// The parts of a provider's discovery document the login flow uses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MurOidcDiscovery {
+	pub issuer: String,
+	pub authorization_endpoint: String,
+	pub token_endpoint: String,
+	pub jwks_uri: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub userinfo_endpoint: Option<String>,
+	/// The RP-initiated logout endpoint, when supported.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub end_session_endpoint: Option<String>,
+	#[serde(default)]
+	pub scopes_supported: Vec<String>,
+	#[serde(default)]
+	pub id_token_signing_alg_values_supported: Vec<String>,
+	#[serde(default)]
+	pub code_challenge_methods_supported: Vec<String>,
+}
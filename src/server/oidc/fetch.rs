@@ -0,0 +1,124 @@
+use crate::server::error::MurError;
+use http::{Request, StatusCode, Uri};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Bytes;
+use hyper::rt::{Read, Write};
+use hyper_util::rt::TokioIo;
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, RootCertStore};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+
+/// Responses from the provider larger than this are refused.
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// A response from the identity provider.
+#[derive(Debug)]
+pub(crate) struct MurOidcResponse {
+	pub status: StatusCode,
+	pub body: Bytes,
+}
+
+/// Sends `request` to the provider over a fresh HTTP/1.1 connection, with
+/// TLS verified against the bundled web PKI roots for `https` URLs.
+pub(crate) async fn send(
+	mut request: Request<Full<Bytes>>,
+	timeout: Duration,
+) -> Result<MurOidcResponse, MurError> {
+	let uri = request.uri().clone();
+	let host = uri
+		.host()
+		.ok_or_else(|| MurError::internal(format!("Invalid identity provider URL '{}'", uri)))?
+		.trim_start_matches('[')
+		.trim_end_matches(']')
+		.to_string();
+	let https = match uri.scheme_str() {
+		Some("https") => true,
+		Some("http") => false,
+		_ => {
+			return Err(MurError::internal(format!(
+				"Unsupported identity provider URL '{}'",
+				uri
+			)));
+		}
+	};
+	let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+
+	if let Some(authority) = uri.authority()
+		&& let Ok(value) = authority.as_str().parse()
+	{
+		request.headers_mut().insert(http::header::HOST, value);
+	}
+	*request.uri_mut() = uri
+		.path_and_query()
+		.map(|path| path.as_str())
+		.unwrap_or("/")
+		.parse::<Uri>()
+		.map_err(|e| MurError::internal(e.to_string()))?;
+
+	let exchange = async {
+		let stream = TcpStream::connect((host.as_str(), port)).await?;
+		if https {
+			let name = ServerName::try_from(host.clone())
+				.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+			let stream = TlsConnector::from(tls_config())
+				.connect(name, stream)
+				.await?;
+			exchange(TokioIo::new(stream), request).await
+		} else {
+			exchange(TokioIo::new(stream), request).await
+		}
+	};
+
+	match tokio::time::timeout(timeout, exchange).await {
+		Ok(Ok(response)) => Ok(response),
+		Ok(Err(e)) => Err(MurError::service_unavailable(format!(
+			"Identity provider unavailable: {}",
+			e
+		))),
+		Err(_) => Err(MurError::service_unavailable(
+			"Identity provider unavailable: request timed out",
+		)),
+	}
+}
+
+async fn exchange<T>(io: T, request: Request<Full<Bytes>>) -> std::io::Result<MurOidcResponse>
+where
+	T: Read + Write + Unpin + Send + 'static,
+{
+	let (mut sender, connection) = hyper::client::conn::http1::handshake(io)
+		.await
+		.map_err(std::io::Error::other)?;
+	tokio::spawn(async move {
+		let _ = connection.await;
+	});
+
+	let response = sender
+		.send_request(request)
+		.await
+		.map_err(std::io::Error::other)?;
+	let status = response.status();
+	let body = Limited::new(response.into_body(), MAX_RESPONSE_BYTES)
+		.collect()
+		.await
+		.map_err(std::io::Error::other)?
+		.to_bytes();
+	Ok(MurOidcResponse { status, body })
+}
+
+fn tls_config() -> Arc<ClientConfig> {
+	static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+	CONFIG
+		.get_or_init(|| {
+			let roots = RootCertStore {
+				roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+			};
+			let config = ClientConfig::builder()
+				.with_root_certificates(roots)
+				.with_no_client_auth();
+			Arc::new(config)
+		})
+		.clone()
+}
@@ -0,0 +1,92 @@
+use crate::core::utils::MurCodec;
+use crate::server::error::MurError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// The claims of a validated ID token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MurIdTokenClaims {
+	pub iss: String,
+	pub sub: String,
+	/// The audiences, sent as a single string or an array.
+	#[serde(deserialize_with = "one_or_many")]
+	pub aud: Vec<String>,
+	pub exp: u64,
+	#[serde(default)]
+	pub iat: u64,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub azp: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub email: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub email_verified: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub preferred_username: Option<String>,
+	/// Every other claim.
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+impl MurIdTokenClaims {
+	/// A claim outside the standard fields, such as `groups`.
+	pub fn get<T: DeserializeOwned>(&self, claim: &str) -> Option<T> {
+		serde_json::from_value(self.extra.get(claim)?.clone()).ok()
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MurJwtHeader {
+	pub alg: String,
+	#[serde(default)]
+	pub kid: Option<String>,
+}
+
+/// A compact JWS split into its parts, not yet verified.
+#[derive(Debug)]
+pub(crate) struct MurJwt<'a> {
+	pub header: MurJwtHeader,
+	pub payload: Vec<u8>,
+	pub signing_input: &'a str,
+	pub signature: Vec<u8>,
+}
+
+impl<'a> MurJwt<'a> {
+	pub fn parse(token: &'a str) -> Result<Self, MurError> {
+		let invalid = || MurError::unauthorized("Malformed ID token");
+		let (signing_input, signature) = token.rsplit_once('.').ok_or_else(invalid)?;
+		let (header, payload) = signing_input.split_once('.').ok_or_else(invalid)?;
+
+		let header = MurCodec::base64_url_decode(header).map_err(|_| invalid())?;
+		let header = serde_json::from_slice(&header).map_err(|_| invalid())?;
+		Ok(Self {
+			header,
+			payload: MurCodec::base64_url_decode(payload).map_err(|_| invalid())?,
+			signing_input,
+			signature: MurCodec::base64_url_decode(signature).map_err(|_| invalid())?,
+		})
+	}
+
+	pub fn claims(&self) -> Result<MurIdTokenClaims, MurError> {
+		serde_json::from_slice(&self.payload)
+			.map_err(|e| MurError::unauthorized(format!("Invalid ID token claims: {}", e)))
+	}
+}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum OneOrMany {
+		One(String),
+		Many(Vec<String>),
+	}
+
+	Ok(match OneOrMany::deserialize(deserializer)? {
+		OneOrMany::One(value) => vec![value],
+		OneOrMany::Many(values) => values,
+	})
+}
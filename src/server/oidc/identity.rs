@@ -0,0 +1,93 @@
+use super::MurIdTokenClaims;
+use crate::server::http::MurRequestContext;
+use serde::{Deserialize, Serialize};
+
+/// The session key of the logged-in user's identity.
+pub(crate) const IDENTITY_KEY: &str = "mur.oidc";
+
+/// The tokens issued by the provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MurOidcTokens {
+	pub access_token: String,
+	pub token_type: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub refresh_token: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id_token: Option<String>,
+	/// When the access token expires, in seconds since the Unix epoch.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub expires_at: Option<u64>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub scope: Option<String>,
+}
+
+impl MurOidcTokens {
+	/// Whether the access token expires within `margin` seconds of `now`.
+	pub fn expires_within(&self, now: u64, margin: u64) -> bool {
+		self.expires_at
+			.is_some_and(|expires_at| expires_at <= now.saturating_add(margin))
+	}
+}
+
+/// A token endpoint response (RFC 6749 §5.1).
+#[derive(Debug, Deserialize)]
+pub(crate) struct MurTokenResponse {
+	pub access_token: String,
+	#[serde(default)]
+	pub token_type: Option<String>,
+	#[serde(default)]
+	pub refresh_token: Option<String>,
+	#[serde(default)]
+	pub id_token: Option<String>,
+	#[serde(default)]
+	pub expires_in: Option<u64>,
+	#[serde(default)]
+	pub scope: Option<String>,
+}
+
+impl MurTokenResponse {
+	pub fn into_tokens(self, now: u64) -> MurOidcTokens {
+		MurOidcTokens {
+			access_token: self.access_token,
+			token_type: self.token_type.unwrap_or_else(|| "Bearer".to_string()),
+			refresh_token: self.refresh_token,
+			id_token: self.id_token,
+			expires_at: self.expires_in.map(|secs| now.saturating_add(secs)),
+			scope: self.scope,
+		}
+	}
+}
+
+/// The logged-in user, kept in the session by
+/// [`MurOidcModule`](super::MurOidcModule).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MurOidcIdentity {
+	pub claims: MurIdTokenClaims,
+	pub tokens: MurOidcTokens,
+}
+
+impl MurOidcIdentity {
+	/// The identity stored in the request's session, as is. Use
+	/// [`MurOidcClient::identity`](super::MurOidcClient::identity) to have
+	/// expiring tokens refreshed.
+	pub fn from_ctx(ctx: &MurRequestContext) -> Option<Self> {
+		ctx.session()?.get(IDENTITY_KEY)
+	}
+
+	pub fn subject(&self) -> &str {
+		&self.claims.sub
+	}
+}
+
+/// A login between the redirect to the provider and its callback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MurOidcPending {
+	pub state: String,
+	pub nonce: String,
+	pub verifier: String,
+	pub return_to: String,
+	pub started_at: u64,
+}
+
+/// The session key of the login in progress.
+pub(crate) const PENDING_KEY: &str = "mur.oidc.pending";
@@ -0,0 +1,126 @@
+use crate::core::utils::MurCodec;
+use aws_lc_rs::signature::{self, RsaParameters, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::{Deserialize, Serialize};
+
+/// A public key from a provider's JWKS (RFC 7517).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MurJwk {
+	pub kty: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub kid: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub alg: Option<String>,
+	#[serde(rename = "use", default, skip_serializing_if = "Option::is_none")]
+	pub key_use: Option<String>,
+	/// The RSA modulus.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub n: Option<String>,
+	/// The RSA public exponent.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub e: Option<String>,
+	/// The elliptic curve, `P-256` or `P-384`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub crv: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub x: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub y: Option<String>,
+}
+
+/// A provider's signing keys.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MurJwkSet {
+	pub keys: Vec<MurJwk>,
+}
+
+impl MurJwk {
+	/// Whether the key may verify `alg` signatures.
+	pub fn supports(&self, alg: &str) -> bool {
+		if self
+			.key_use
+			.as_deref()
+			.is_some_and(|key_use| key_use != "sig")
+			|| self.alg.as_deref().is_some_and(|key_alg| key_alg != alg)
+		{
+			return false;
+		}
+		match alg {
+			"RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => self.kty == "RSA",
+			"ES256" => self.kty == "EC" && self.crv.as_deref() == Some("P-256"),
+			"ES384" => self.kty == "EC" && self.crv.as_deref() == Some("P-384"),
+			_ => false,
+		}
+	}
+
+	/// Checks an `alg` signature over `message`. Only the asymmetric
+	/// algorithms are accepted.
+	pub fn verify(&self, alg: &str, message: &[u8], signature: &[u8]) -> bool {
+		if !self.supports(alg) {
+			return false;
+		}
+		match alg {
+			"RS256" => self.verify_rsa(&signature::RSA_PKCS1_2048_8192_SHA256, message, signature),
+			"RS384" => self.verify_rsa(&signature::RSA_PKCS1_2048_8192_SHA384, message, signature),
+			"RS512" => self.verify_rsa(&signature::RSA_PKCS1_2048_8192_SHA512, message, signature),
+			"PS256" => self.verify_rsa(&signature::RSA_PSS_2048_8192_SHA256, message, signature),
+			"PS384" => self.verify_rsa(&signature::RSA_PSS_2048_8192_SHA384, message, signature),
+			"PS512" => self.verify_rsa(&signature::RSA_PSS_2048_8192_SHA512, message, signature),
+			"ES256" => self.verify_ec(&signature::ECDSA_P256_SHA256_FIXED, message, signature),
+			"ES384" => self.verify_ec(&signature::ECDSA_P384_SHA384_FIXED, message, signature),
+			_ => false,
+		}
+	}
+
+	fn verify_rsa(&self, params: &RsaParameters, message: &[u8], signature: &[u8]) -> bool {
+		let (Some(n), Some(e)) = (decode(&self.n), decode(&self.e)) else {
+			return false;
+		};
+		let key = RsaPublicKeyComponents {
+			n: strip_leading_zeros(&n),
+			e: strip_leading_zeros(&e),
+		};
+		key.verify(params, message, signature).is_ok()
+	}
+
+	fn verify_ec(
+		&self,
+		algorithm: &'static signature::EcdsaVerificationAlgorithm,
+		message: &[u8],
+		signature: &[u8],
+	) -> bool {
+		let (Some(x), Some(y)) = (decode(&self.x), decode(&self.y)) else {
+			return false;
+		};
+		let mut point = Vec::with_capacity(1 + x.len() + y.len());
+		point.push(0x04);
+		point.extend_from_slice(&x);
+		point.extend_from_slice(&y);
+		UnparsedPublicKey::new(algorithm, point)
+			.verify(message, signature)
+			.is_ok()
+	}
+}
+
+impl MurJwkSet {
+	/// The key for a token signed with `alg` by key `kid`. Without a `kid`,
+	/// only a set holding a single suitable key yields one.
+	pub fn find(&self, kid: Option<&str>, alg: &str) -> Option<&MurJwk> {
+		let mut candidates = self.keys.iter().filter(|key| key.supports(alg));
+		match kid {
+			Some(kid) => candidates.find(|key| key.kid.as_deref() == Some(kid)),
+			None => {
+				let key = candidates.next()?;
+				candidates.next().is_none().then_some(key)
+			}
+		}
+	}
+}
+
+fn decode(value: &Option<String>) -> Option<Vec<u8>> {
+	MurCodec::base64_url_decode(value.as_deref()?).ok()
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+	let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+	&bytes[start..]
+}
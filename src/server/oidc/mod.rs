@@ -0,0 +1,23 @@
+pub mod client;
+pub mod config;
+pub mod controller;
+pub mod discovery;
+mod fetch;
+pub mod id_token;
+pub mod identity;
+pub mod jwks;
+pub mod module;
+
+pub use client::MurOidcClient;
+pub use config::MurOidcConfig;
+pub use controller::MurOidcController;
+pub use discovery::MurOidcDiscovery;
+pub use id_token::MurIdTokenClaims;
+pub use identity::MurOidcIdentity;
+pub use identity::MurOidcTokens;
+pub use jwks::MurJwk;
+pub use jwks::MurJwkSet;
+pub use module::MurOidcModule;
+
+#[cfg(test)]
+mod test;
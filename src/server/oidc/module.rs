@@ -0,0 +1,173 @@
+use super::{MurOidcClient, MurOidcConfig, MurOidcController};
+use crate::server::controller::MurController;
+use crate::server::module::{MurModule, MurModuleConfig};
+use crate::server::service::MurService;
+use std::any::TypeId;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// OpenID Connect login: the authorization code flow with PKCE against a
+/// provider found through discovery.
+///
+/// Adds `GET /auth/login`, `GET /auth/callback` and `POST /auth/logout`,
+/// and registers [`MurOidcClient`] as a service. The logged-in user is kept
+/// in the session, so [`MurSessionModule`](crate::MurSessionModule) must be
+/// registered first.
+///
+/// ```rust,ignore
+/// MurServer::new()
+///     .cookie_keys(MurCookieKeys::from_config(&config)?)
+///     .module(MurSessionModule::new().secure())
+///     .module(
+///         MurOidcModule::new(
+///             "https://accounts.example.com",
+///             "my-app",
+///             "https://app.example.com/auth/callback",
+///         )
+///         .client_secret(config.get("OIDC_CLIENT_SECRET")?)
+///         .scope("groups"),
+///     )
+/// ```
+#[derive(Debug)]
+pub struct MurOidcModule {
+	config: MurOidcConfig,
+	client: OnceLock<Arc<MurOidcClient>>,
+}
+
+impl MurOidcModule {
+	pub fn new(
+		issuer: impl Into<String>,
+		client_id: impl Into<String>,
+		redirect_uri: impl Into<String>,
+	) -> Self {
+		Self::with_options(MurOidcConfig::new(issuer, client_id, redirect_uri))
+	}
+
+	pub fn client_secret(mut self, secret: impl Into<String>) -> Self {
+		self.config.client_secret = Some(secret.into());
+		self
+	}
+
+	/// Replaces the requested scopes; `openid` is always requested.
+	pub fn scopes<I, S>(mut self, scopes: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.config.scopes = vec!["openid".to_string()];
+		for scope in scopes {
+			self = self.scope(scope);
+		}
+		self
+	}
+
+	/// Requests one more scope.
+	pub fn scope(mut self, scope: impl Into<String>) -> Self {
+		let scope = scope.into();
+		if !self.config.scopes.contains(&scope) {
+			self.config.scopes.push(scope);
+		}
+		self
+	}
+
+	/// Adds a parameter to the authorization request.
+	pub fn auth_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+		self.config.auth_params.push((name.into(), value.into()));
+		self
+	}
+
+	pub fn login_path(mut self, path: impl Into<String>) -> Self {
+		self.config.login_path = path.into();
+		self
+	}
+
+	pub fn callback_path(mut self, path: impl Into<String>) -> Self {
+		self.config.callback_path = path.into();
+		self
+	}
+
+	pub fn logout_path(mut self, path: impl Into<String>) -> Self {
+		self.config.logout_path = path.into();
+		self
+	}
+
+	pub fn default_return_to(mut self, path: impl Into<String>) -> Self {
+		self.config.default_return_to = path.into();
+		self
+	}
+
+	pub fn post_logout_redirect_uri(mut self, uri: impl Into<String>) -> Self {
+		self.config.post_logout_redirect_uri = Some(uri.into());
+		self
+	}
+
+	pub fn leeway(mut self, leeway: Duration) -> Self {
+		self.config.leeway = leeway;
+		self
+	}
+
+	pub fn refresh_margin(mut self, margin: Duration) -> Self {
+		self.config.refresh_margin = margin;
+		self
+	}
+
+	pub fn jwks_ttl(mut self, ttl: Duration) -> Self {
+		self.config.jwks_ttl = ttl;
+		self
+	}
+
+	pub fn jwks_refresh_interval(mut self, interval: Duration) -> Self {
+		self.config.jwks_refresh_interval = interval;
+		self
+	}
+
+	pub fn http_timeout(mut self, timeout: Duration) -> Self {
+		self.config.http_timeout = timeout;
+		self
+	}
+
+	pub fn oidc_config(&self) -> &MurOidcConfig {
+		&self.config
+	}
+
+	/// The module's client, shared with its routes and the container.
+	pub fn client(&self) -> Arc<MurOidcClient> {
+		Arc::clone(
+			self.client
+				.get_or_init(|| Arc::new(MurOidcClient::new(self.config.clone()))),
+		)
+	}
+}
+
+impl MurModuleConfig for MurOidcModule {
+	type Options = MurOidcConfig;
+
+	fn with_options(options: MurOidcConfig) -> Self {
+		Self {
+			config: options,
+			client: OnceLock::new(),
+		}
+	}
+}
+
+impl MurModule for MurOidcModule {
+	fn controllers(&self) -> Vec<Arc<dyn MurController>> {
+		vec![Arc::new(MurOidcController::new(self.client()))]
+	}
+
+	fn services(&self) -> Vec<(TypeId, Arc<dyn MurService>)> {
+		vec![(TypeId::of::<MurOidcClient>(), self.client())]
+	}
+
+	fn name(&self) -> &str {
+		"MurOidcModule"
+	}
+
+	fn exports(&self) -> Vec<TypeId> {
+		vec![TypeId::of::<MurOidcClient>()]
+	}
+
+	fn imports(&self) -> Vec<Arc<dyn MurModule>> {
+		Vec::new()
+	}
+}
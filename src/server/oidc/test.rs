@@ -0,0 +1,164 @@
+use super::client::{is_local_path, pkce_challenge};
+use super::id_token::MurJwt;
+use super::{MurIdTokenClaims, MurJwk, MurJwkSet, MurOidcClient, MurOidcConfig};
+use crate::core::utils::MurCodec;
+use aws_lc_rs::rand::SystemRandom;
+use aws_lc_rs::rsa::KeySize;
+use aws_lc_rs::signature::{self, EcdsaKeyPair, KeyPair, RsaKeyPair};
+use serde_json::json;
+
+fn rsa_jwk(key: &RsaKeyPair, kid: &str) -> MurJwk {
+	let public = key.public_key();
+	MurJwk {
+		kty: "RSA".to_string(),
+		kid: Some(kid.to_string()),
+		alg: Some("RS256".to_string()),
+		n: Some(MurCodec::base64_url_encode(
+			public.modulus().big_endian_without_leading_zero(),
+		)),
+		e: Some(MurCodec::base64_url_encode(
+			public.exponent().big_endian_without_leading_zero(),
+		)),
+		..MurJwk::default()
+	}
+}
+
+fn rsa_sign(key: &RsaKeyPair, kid: &str, claims: serde_json::Value) -> String {
+	let header = json!({ "alg": "RS256", "kid": kid, "typ": "JWT" });
+	let input = format!(
+		"{}.{}",
+		MurCodec::base64_url_encode(header.to_string().as_bytes()),
+		MurCodec::base64_url_encode(claims.to_string().as_bytes())
+	);
+	let mut signature = vec![0; key.public_modulus_len()];
+	key.sign(
+		&signature::RSA_PKCS1_SHA256,
+		&SystemRandom::new(),
+		input.as_bytes(),
+		&mut signature,
+	)
+	.unwrap();
+	format!("{}.{}", input, MurCodec::base64_url_encode(&signature))
+}
+
+fn claims(value: serde_json::Value) -> MurIdTokenClaims {
+	serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_pkce_challenge_matches_rfc_7636() {
+	assert_eq!(
+		pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+		"E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+	);
+}
+
+#[test]
+fn test_rsa_signatures_verify_against_jwk() {
+	let key = RsaKeyPair::generate(KeySize::Rsa2048).unwrap();
+	let jwk = rsa_jwk(&key, "k1");
+	let token = rsa_sign(&key, "k1", json!({ "sub": "alice" }));
+	let jwt = MurJwt::parse(&token).unwrap();
+
+	assert_eq!(jwt.header.alg, "RS256");
+	assert_eq!(jwt.header.kid.as_deref(), Some("k1"));
+	assert!(jwk.verify("RS256", jwt.signing_input.as_bytes(), &jwt.signature));
+	assert!(!jwk.verify("RS256", b"tampered", &jwt.signature));
+	assert!(!jwk.verify("RS384", jwt.signing_input.as_bytes(), &jwt.signature));
+	assert!(!jwk.verify("HS256", jwt.signing_input.as_bytes(), &jwt.signature));
+	assert!(!jwk.verify("none", jwt.signing_input.as_bytes(), &[]));
+}
+
+#[test]
+fn test_ec_signatures_verify_against_jwk() {
+	let rng = SystemRandom::new();
+	let key = EcdsaKeyPair::generate(&signature::ECDSA_P256_SHA256_FIXED_SIGNING).unwrap();
+	let point = key.public_key().as_ref();
+	let jwk = MurJwk {
+		kty: "EC".to_string(),
+		crv: Some("P-256".to_string()),
+		x: Some(MurCodec::base64_url_encode(&point[1..33])),
+		y: Some(MurCodec::base64_url_encode(&point[33..])),
+		..MurJwk::default()
+	};
+	let signature = key.sign(&rng, b"payload").unwrap();
+
+	assert!(jwk.verify("ES256", b"payload", signature.as_ref()));
+	assert!(!jwk.verify("ES256", b"other", signature.as_ref()));
+	assert!(!jwk.verify("ES384", b"payload", signature.as_ref()));
+}
+
+#[test]
+fn test_jwk_set_finds_keys_by_kid_and_algorithm() {
+	let key = RsaKeyPair::generate(KeySize::Rsa2048).unwrap();
+	let mut encryption = rsa_jwk(&key, "enc");
+	encryption.key_use = Some("enc".to_string());
+	let set = MurJwkSet {
+		keys: vec![rsa_jwk(&key, "a"), encryption],
+	};
+
+	assert!(set.find(Some("a"), "RS256").is_some());
+	assert!(set.find(Some("enc"), "RS256").is_none());
+	assert!(set.find(Some("b"), "RS256").is_none());
+	assert!(set.find(None, "RS256").is_some());
+	assert!(set.find(None, "ES256").is_none());
+
+	let ambiguous = MurJwkSet {
+		keys: vec![rsa_jwk(&key, "a"), rsa_jwk(&key, "b")],
+	};
+	assert!(ambiguous.find(None, "RS256").is_none());
+	assert!(MurJwt::parse("not-a-token").is_err());
+	assert!(MurJwt::parse("a.b.c").is_err());
+}
+
+#[test]
+fn test_id_token_claims_are_checked() {
+	let client = MurOidcClient::new(MurOidcConfig::new(
+		"https://idp.test",
+		"app",
+		"https://app.test/auth/callback",
+	));
+	let check =
+		|value, nonce| client.check_claims(&claims(value), "https://idp.test", nonce, 1_000);
+	let valid = json!({
+		"iss": "https://idp.test", "sub": "alice", "aud": "app",
+		"exp": 1_100, "iat": 990, "nonce": "n-1",
+	});
+
+	assert!(check(valid.clone(), Some("n-1")).is_ok());
+	assert!(check(valid.clone(), None).is_ok());
+	assert!(check(valid.clone(), Some("n-2")).is_err());
+
+	let mut other = valid.clone();
+	other["iss"] = json!("https://evil.test");
+	assert!(check(other, None).is_err());
+
+	let mut other = valid.clone();
+	other["aud"] = json!(["app", "api"]);
+	assert!(check(other.clone(), None).is_err());
+	other["azp"] = json!("app");
+	assert!(check(other, None).is_ok());
+
+	let mut other = valid.clone();
+	other["aud"] = json!("another-app");
+	assert!(check(other, None).is_err());
+
+	let mut other = valid.clone();
+	other["exp"] = json!(900);
+	assert!(check(other.clone(), None).is_err());
+	other["exp"] = json!(960);
+	assert!(check(other, None).is_ok(), "within the leeway");
+
+	let mut other = valid;
+	other["iat"] = json!(2_000);
+	assert!(check(other, None).is_err());
+}
+
+#[test]
+fn test_return_to_must_be_local() {
+	assert!(is_local_path("/dashboard?tab=1"));
+	assert!(!is_local_path("https://evil.test"));
+	assert!(!is_local_path("//evil.test"));
+	assert!(!is_local_path("/\\evil.test"));
+	assert!(!is_local_path("dashboard"));
+}
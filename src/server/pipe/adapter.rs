@@ -0,0 +1,199 @@
+use super::contract::MurPipeFuture;
+use super::{MurArgument, MurPipe, MurPipeAsync, MurPipeDyn};
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::any::Any;
+use std::marker::PhantomData;
+
+/// Decodes an argument value as a pipe's input type.
+pub fn mur_pipe_input<I: DeserializeOwned>(
+	value: Value,
+	argument: &MurArgument,
+) -> Result<I, MurError> {
+	serde_json::from_value(value)
+		.map_err(|e| MurError::bad_request(format!("Invalid value for '{}': {}", argument.name, e)))
+}
+
+/// Encodes a pipe's output back into an argument value.
+pub fn mur_pipe_output<O: Serialize>(output: O) -> Result<Value, MurError> {
+	serde_json::to_value(output).map_err(|e| MurError::Serde(e.to_string()))
+}
+
+/// Runs a [`MurPipe`] as a global, controller or parameter pipe. The argument
+/// is decoded as `I` and the output encoded back to JSON.
+///
+/// ```rust,ignore
+/// MurServer::new().global_pipe(MurSyncPipe::new(LowercaseEmailPipe))
+/// ```
+pub struct MurSyncPipe<P, I> {
+	pipe: P,
+	_input: PhantomData<fn(I)>,
+}
+
+impl<P, I> MurSyncPipe<P, I> {
+	pub fn new(pipe: P) -> Self {
+		Self {
+			pipe,
+			_input: PhantomData,
+		}
+	}
+}
+
+impl<P, I> MurPipeDyn for MurSyncPipe<P, I>
+where
+	P: MurPipe<I>,
+	P::Output: Serialize,
+	I: DeserializeOwned + 'static,
+{
+	fn name(&self) -> &str {
+		self.pipe.name()
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self.pipe.as_any()
+	}
+
+	fn transform_argument<'a>(
+		&'a self,
+		ctx: &'a MurRequestContext,
+		value: Value,
+		argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move {
+			let input = mur_pipe_input::<I>(value, argument)?;
+			let output = self
+				.pipe
+				.apply_transform(ctx.clone(), input)
+				.map_err(Into::into)?;
+			mur_pipe_output(output)
+		})
+	}
+}
+
+/// Runs a [`MurPipeAsync`] as a global, controller or parameter pipe. See
+/// [`MurSyncPipe`].
+pub struct MurAsyncPipe<P, I> {
+	pipe: P,
+	_input: PhantomData<fn(I)>,
+}
+
+impl<P, I> MurAsyncPipe<P, I> {
+	pub fn new(pipe: P) -> Self {
+		Self {
+			pipe,
+			_input: PhantomData,
+		}
+	}
+}
+
+impl<P, I> MurPipeDyn for MurAsyncPipe<P, I>
+where
+	P: MurPipeAsync<I>,
+	P::Output: Serialize,
+	I: DeserializeOwned + 'static,
+{
+	fn name(&self) -> &str {
+		self.pipe.name()
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		&self.pipe
+	}
+
+	fn transform_argument<'a>(
+		&'a self,
+		ctx: &'a MurRequestContext,
+		value: Value,
+		argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move {
+			let input = mur_pipe_input::<I>(value, argument)?;
+			let output = self
+				.pipe
+				.apply_transform(ctx.clone(), input)
+				.await
+				.map_err(Into::into)?;
+			mur_pipe_output(output)
+		})
+	}
+}
+
+/// Lets `#[pipe]` bridge a pipe's `MurPipe<I>` implementation into
+/// [`MurPipeDyn::transform_argument`] when `I` and the output are serde
+/// types, and leave arguments unchanged otherwise:
+///
+/// ```rust,ignore
+/// use murgamu::server::pipe::{MurBridgeFallback as _, MurBridgeSerde as _};
+/// (&&MurPipeBridge::<Self, I>::new()).transform(self, ctx, value, argument)
+/// ```
+pub struct MurPipeBridge<P, I>(PhantomData<fn(P, I)>);
+
+impl<P, I> MurPipeBridge<P, I> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<P, I> Default for MurPipeBridge<P, I> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+pub trait MurBridgeSerde<P> {
+	fn transform<'a>(
+		&self,
+		pipe: &'a P,
+		ctx: &'a MurRequestContext,
+		value: Value,
+		argument: &'a MurArgument,
+	) -> MurPipeFuture<'a>;
+}
+
+impl<P, I> MurBridgeSerde<P> for &MurPipeBridge<P, I>
+where
+	P: MurPipe<I>,
+	P::Output: Serialize,
+	I: DeserializeOwned + 'static,
+{
+	fn transform<'a>(
+		&self,
+		pipe: &'a P,
+		ctx: &'a MurRequestContext,
+		value: Value,
+		argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move {
+			let input = mur_pipe_input::<I>(value, argument)?;
+			let output = pipe
+				.apply_transform(ctx.clone(), input)
+				.map_err(Into::into)?;
+			mur_pipe_output(output)
+		})
+	}
+}
+
+pub trait MurBridgeFallback<P> {
+	fn transform<'a>(
+		&self,
+		pipe: &'a P,
+		ctx: &'a MurRequestContext,
+		value: Value,
+		argument: &'a MurArgument,
+	) -> MurPipeFuture<'a>;
+}
+
+impl<P, I> MurBridgeFallback<P> for MurPipeBridge<P, I> {
+	fn transform<'a>(
+		&self,
+		_pipe: &'a P,
+		_ctx: &'a MurRequestContext,
+		value: Value,
+		_argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move { Ok(value) })
+	}
+}
@@ -0,0 +1,63 @@
+use crate::server::error::MurError;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Where a handler argument was extracted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MurArgumentKind {
+	/// The JSON request body (`MurJson<T>`, `#[body]`).
+	Body,
+	/// The whole query string (`MurQuery<T>`, `#[query]`).
+	Query,
+	/// All path parameters (`MurPath<T>`).
+	Path,
+	/// A single path parameter (`Param<T>`, `#[param]`).
+	Param,
+	/// A single query parameter (`#[queryparam]`).
+	QueryParam,
+}
+
+/// Validates an extracted value, decoding it as the parameter's type first.
+pub type MurValidator = fn(&Value, MurArgumentKind) -> Result<(), MurError>;
+
+/// Describes the handler argument a pipe is transforming.
+#[derive(Debug, Clone, Copy)]
+pub struct MurArgument {
+	pub kind: MurArgumentKind,
+	/// The parameter name, which is also the key for path and query parameters.
+	pub name: &'static str,
+	pub type_name: &'static str,
+	pub validator: Option<MurValidator>,
+}
+
+impl MurArgument {
+	pub fn new(kind: MurArgumentKind, name: &'static str, type_name: &'static str) -> Self {
+		Self {
+			kind,
+			name,
+			type_name,
+			validator: None,
+		}
+	}
+
+	pub fn validator(mut self, validator: Option<MurValidator>) -> Self {
+		self.validator = validator;
+		self
+	}
+}
+
+/// Converts a (possibly transformed) argument value into `T`. Query strings
+/// are re-encoded so that numbers and booleans parse as they would from the
+/// raw query.
+pub fn mur_decode_argument<T: DeserializeOwned>(
+	kind: MurArgumentKind,
+	value: Value,
+) -> Result<T, String> {
+	match kind {
+		MurArgumentKind::Query => {
+			let encoded = serde_urlencoded::to_string(&value).map_err(|e| e.to_string())?;
+			serde_urlencoded::from_str(&encoded).map_err(|e| e.to_string())
+		}
+		_ => serde_json::from_value(value).map_err(|e| e.to_string()),
+	}
+}
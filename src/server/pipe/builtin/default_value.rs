@@ -0,0 +1,50 @@
+use crate::server::http::MurRequestContext;
+use crate::server::pipe::contract::MurPipeFuture;
+use crate::server::pipe::{MurArgument, MurPipeDyn};
+use serde_json::Value;
+use std::any::Any;
+
+/// Substitutes a default for missing arguments.
+///
+/// ```rust,ignore
+/// async fn list(&self, #[queryparam] #[use_pipe(DefaultValuePipe::new(20))] limit: u32) -> MurRes
+/// ```
+#[derive(Debug, Clone)]
+pub struct DefaultValuePipe {
+	default: Value,
+}
+
+impl DefaultValuePipe {
+	pub fn new(default: impl Into<Value>) -> Self {
+		Self {
+			default: default.into(),
+		}
+	}
+
+	pub fn apply(&self, value: Value) -> Value {
+		if value.is_null() {
+			self.default.clone()
+		} else {
+			value
+		}
+	}
+}
+
+impl MurPipeDyn for DefaultValuePipe {
+	fn name(&self) -> &str {
+		"DefaultValuePipe"
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn transform_argument<'a>(
+		&'a self,
+		_ctx: &'a MurRequestContext,
+		value: Value,
+		_argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move { Ok(self.apply(value)) })
+	}
+}
@@ -0,0 +1,11 @@
+mod default_value;
+mod parse_int;
+mod parse_uuid;
+mod trim;
+mod validation_pipe;
+
+pub use default_value::DefaultValuePipe;
+pub use parse_int::ParseIntPipe;
+pub use parse_uuid::ParseUuidPipe;
+pub use trim::TrimPipe;
+pub use validation_pipe::ValidationPipe;
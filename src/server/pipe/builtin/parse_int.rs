@@ -0,0 +1,67 @@
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::pipe::contract::MurPipeFuture;
+use crate::server::pipe::{MurArgument, MurPipeDyn, MurPipeFactory};
+use crate::{MurInjects, MurServiceContainer};
+use serde_json::Value;
+use std::any::Any;
+
+/// Parses a string argument into an integer, answering `400 Bad Request`
+/// when it is not one. Missing values pass through untouched.
+///
+/// ```rust,ignore
+/// #[get("/users/:id")]
+/// async fn find(&self, #[param] #[use_pipe(ParseIntPipe)] id: i64) -> MurRes { /* … */ }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseIntPipe;
+
+impl ParseIntPipe {
+	pub fn new() -> Self {
+		Self
+	}
+
+	pub fn parse(&self, value: Value, argument: &MurArgument) -> Result<Value, MurError> {
+		match value {
+			Value::Null => Ok(Value::Null),
+			Value::Number(n) if n.is_i64() || n.is_u64() => Ok(Value::Number(n)),
+			Value::String(text) => text
+				.parse::<i64>()
+				.map(Value::from)
+				.map_err(|_| invalid(argument)),
+			_ => Err(invalid(argument)),
+		}
+	}
+}
+
+fn invalid(argument: &MurArgument) -> MurError {
+	MurError::validation(format!(
+		"Validation failed for '{}': numeric string is expected",
+		argument.name
+	))
+}
+
+impl MurPipeDyn for ParseIntPipe {
+	fn name(&self) -> &str {
+		"ParseIntPipe"
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn transform_argument<'a>(
+		&'a self,
+		_ctx: &'a MurRequestContext,
+		value: Value,
+		argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move { self.parse(value, argument) })
+	}
+}
+
+impl MurPipeFactory for ParseIntPipe {
+	fn __create_factory(_injects: &MurInjects, _container: &MurServiceContainer) -> Self {
+		Self
+	}
+}
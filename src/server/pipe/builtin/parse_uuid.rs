@@ -0,0 +1,81 @@
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::pipe::contract::MurPipeFuture;
+use crate::server::pipe::{MurArgument, MurPipeDyn, MurPipeFactory};
+use crate::{MurInjects, MurServiceContainer};
+use serde_json::Value;
+use std::any::Any;
+
+/// Checks that a string argument is a UUID, optionally of a given version,
+/// and normalises it to the lowercase hyphenated form. Missing values pass
+/// through untouched.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseUuidPipe {
+	version: Option<usize>,
+}
+
+impl ParseUuidPipe {
+	pub fn new() -> Self {
+		Self { version: None }
+	}
+
+	/// Only accepts UUIDs of `version` (for example `4` or `7`).
+	pub fn version(mut self, version: usize) -> Self {
+		self.version = Some(version);
+		self
+	}
+
+	pub fn parse(&self, value: Value, argument: &MurArgument) -> Result<Value, MurError> {
+		let text = match value {
+			Value::Null => return Ok(Value::Null),
+			Value::String(text) => text,
+			_ => return Err(self.invalid(argument)),
+		};
+
+		let uuid = uuid::Uuid::parse_str(&text).map_err(|_| self.invalid(argument))?;
+		if self
+			.version
+			.is_some_and(|version| uuid.get_version_num() != version)
+		{
+			return Err(self.invalid(argument));
+		}
+
+		Ok(Value::String(uuid.hyphenated().to_string()))
+	}
+
+	fn invalid(&self, argument: &MurArgument) -> MurError {
+		let expected = match self.version {
+			Some(version) => format!("uuid v{} is expected", version),
+			None => "uuid is expected".to_string(),
+		};
+		MurError::validation(format!(
+			"Validation failed for '{}': {}",
+			argument.name, expected
+		))
+	}
+}
+
+impl MurPipeDyn for ParseUuidPipe {
+	fn name(&self) -> &str {
+		"ParseUuidPipe"
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn transform_argument<'a>(
+		&'a self,
+		_ctx: &'a MurRequestContext,
+		value: Value,
+		argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move { self.parse(value, argument) })
+	}
+}
+
+impl MurPipeFactory for ParseUuidPipe {
+	fn __create_factory(_injects: &MurInjects, _container: &MurServiceContainer) -> Self {
+		Self::new()
+	}
+}
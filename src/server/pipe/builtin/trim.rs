@@ -0,0 +1,56 @@
+use crate::server::http::MurRequestContext;
+use crate::server::pipe::contract::MurPipeFuture;
+use crate::server::pipe::{MurArgument, MurPipeDyn, MurPipeFactory};
+use crate::{MurInjects, MurServiceContainer};
+use serde_json::Value;
+use std::any::Any;
+
+/// Trims leading and trailing whitespace from string arguments, including
+/// every string nested in a body or query object.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrimPipe;
+
+impl TrimPipe {
+	pub fn new() -> Self {
+		Self
+	}
+
+	pub fn trim(&self, value: Value) -> Value {
+		match value {
+			Value::String(text) => Value::String(text.trim().to_string()),
+			Value::Array(items) => Value::Array(items.into_iter().map(|v| self.trim(v)).collect()),
+			Value::Object(map) => Value::Object(
+				map
+					.into_iter()
+					.map(|(key, value)| (key, self.trim(value)))
+					.collect(),
+			),
+			other => other,
+		}
+	}
+}
+
+impl MurPipeDyn for TrimPipe {
+	fn name(&self) -> &str {
+		"TrimPipe"
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn transform_argument<'a>(
+		&'a self,
+		_ctx: &'a MurRequestContext,
+		value: Value,
+		_argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move { Ok(self.trim(value)) })
+	}
+}
+
+impl MurPipeFactory for TrimPipe {
+	fn __create_factory(_injects: &MurInjects, _container: &MurServiceContainer) -> Self {
+		Self
+	}
+}
@@ -0,0 +1,57 @@
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::pipe::contract::MurPipeFuture;
+use crate::server::pipe::{MurArgument, MurPipeDyn, MurPipeFactory};
+use crate::{MurInjects, MurServiceContainer};
+use serde_json::Value;
+use std::any::Any;
+
+/// Runs [`MurValidate`](crate::server::pipe::MurValidate) on arguments whose
+/// type implements it, answering `400 Bad Request` with the validation
+/// message or the failing fields. Other arguments pass through.
+///
+/// Register it globally to validate every DTO, or use `#[validate]` on a
+/// single parameter.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidationPipe;
+
+impl ValidationPipe {
+	pub fn new() -> Self {
+		Self
+	}
+
+	pub fn check(&self, value: &Value, argument: &MurArgument) -> Result<(), MurError> {
+		match argument.validator {
+			Some(validator) => validator(value, argument.kind),
+			None => Ok(()),
+		}
+	}
+}
+
+impl MurPipeDyn for ValidationPipe {
+	fn name(&self) -> &str {
+		"ValidationPipe"
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn transform_argument<'a>(
+		&'a self,
+		_ctx: &'a MurRequestContext,
+		value: Value,
+		argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move {
+			self.check(&value, argument)?;
+			Ok(value)
+		})
+	}
+}
+
+impl MurPipeFactory for ValidationPipe {
+	fn __create_factory(_injects: &MurInjects, _container: &MurServiceContainer) -> Self {
+		Self
+	}
+}
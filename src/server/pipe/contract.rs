@@ -1,11 +1,16 @@
+use super::MurArgument;
 use crate::server::error::MurError;
 use crate::{MurInjects, MurRequestContext, MurServiceContainer};
+use serde_json::Value;
 use std::any::Any;
 use std::future::Future;
 use std::pin::Pin;
 
 type MurFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send>>;
 
+/// Future returned by [`MurPipeDyn::transform_argument`].
+pub type MurPipeFuture<'a> = Pin<Box<dyn Future<Output = Result<Value, MurError>> + Send + 'a>>;
+
 pub trait MurPipe<Input>: Send + Sync + 'static {
 	type Output;
 	type Error: Into<MurError>;
@@ -29,9 +34,28 @@ pub trait MurPipeFactory: Send + Sync + 'static {
 		Self: Sized;
 }
 
+/// Object-safe form of a pipe, as stored by the router and handlers.
+///
+/// Pipes run against handler arguments extracted from the request (bodies,
+/// query strings and path or query parameters) while they are still
+/// JSON values, before they are converted to the declared parameter type.
+/// Global pipes run first, then controller, method and parameter pipes.
 pub trait MurPipeDyn: Send + Sync + 'static {
 	fn name(&self) -> &str;
 	fn as_any(&self) -> &dyn std::any::Any;
+
+	/// Transforms one extracted argument. Returning an error stops the
+	/// request; the error goes through the registered exception filters.
+	///
+	/// The default implementation leaves the value unchanged.
+	fn transform_argument<'a>(
+		&'a self,
+		_ctx: &'a MurRequestContext,
+		value: Value,
+		_argument: &'a MurArgument,
+	) -> MurPipeFuture<'a> {
+		Box::pin(async move { Ok(value) })
+	}
 }
 
 pub trait MurPipeAsync<Input>: Send + Sync + 'static {
@@ -1,6 +1,37 @@
-mod contract;
+mod adapter;
+mod argument;
+mod builtin;
+pub(crate) mod contract;
+mod pipeline;
+mod validation;
 
+pub use adapter::MurAsyncPipe;
+pub use adapter::MurBridgeFallback;
+pub use adapter::MurBridgeSerde;
+pub use adapter::MurPipeBridge;
+pub use adapter::MurSyncPipe;
+pub use adapter::mur_pipe_input;
+pub use adapter::mur_pipe_output;
+pub use argument::MurArgument;
+pub use argument::MurArgumentKind;
+pub use argument::MurValidator;
+pub use argument::mur_decode_argument;
+pub use builtin::DefaultValuePipe;
+pub use builtin::ParseIntPipe;
+pub use builtin::ParseUuidPipe;
+pub use builtin::TrimPipe;
+pub use builtin::ValidationPipe;
 pub use contract::MurPipe;
 pub use contract::MurPipeAsync;
 pub use contract::MurPipeDyn;
 pub use contract::MurPipeFactory;
+pub use contract::MurPipeFuture;
+pub use pipeline::MurArgumentPipeline;
+pub(crate) use pipeline::MurGlobalPipes;
+pub use validation::MurProbeFallback;
+pub use validation::MurProbeValidate;
+pub use validation::MurValidate;
+pub use validation::MurValidatorProbe;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,239 @@
+use super::argument::mur_decode_argument;
+use super::{MurArgument, MurArgumentKind, MurPipeDyn};
+use crate::core::utils::MurResponder;
+use crate::server::aliases::MurRes;
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Request extension carrying the router's global pipes to the handler.
+#[derive(Clone)]
+pub(crate) struct MurGlobalPipes(pub Arc<Vec<Arc<dyn MurPipeDyn>>>);
+
+/// The pipes applied to one handler argument, built once per route by the
+/// `#[controller]` macro.
+///
+/// Extraction runs the global pipes first, then the controller, method and
+/// parameter pipes given to [`new`](Self::new), in that order. Extraction
+/// failures answer `400 Bad Request`; pipe errors are returned as-is so
+/// exception filters can handle them.
+#[derive(Clone)]
+pub struct MurArgumentPipeline {
+	argument: MurArgument,
+	pipes: Arc<[Arc<dyn MurPipeDyn>]>,
+}
+
+impl MurArgumentPipeline {
+	pub fn new(argument: MurArgument, pipes: Vec<Arc<dyn MurPipeDyn>>) -> Self {
+		Self {
+			argument,
+			pipes: pipes.into(),
+		}
+	}
+
+	pub fn argument(&self) -> &MurArgument {
+		&self.argument
+	}
+
+	fn is_active(&self, ctx: &MurRequestContext) -> bool {
+		!self.pipes.is_empty() || ctx.parts.extensions.get::<MurGlobalPipes>().is_some()
+	}
+
+	/// Runs every applicable pipe over `value`.
+	pub async fn run(&self, ctx: &MurRequestContext, mut value: Value) -> Result<Value, MurError> {
+		if let Some(global) = ctx.parts.extensions.get::<MurGlobalPipes>() {
+			for pipe in global.0.iter() {
+				value = pipe.transform_argument(ctx, value, &self.argument).await?;
+			}
+		}
+		for pipe in self.pipes.iter() {
+			value = pipe.transform_argument(ctx, value, &self.argument).await?;
+		}
+		Ok(value)
+	}
+
+	/// Extracts the JSON body as `T`.
+	pub async fn body<T: DeserializeOwned>(&self, ctx: &MurRequestContext) -> Result<T, MurRes> {
+		let invalid = |e: &dyn Display| MurResponder::error(&format!("Failed to parse body: {}", e));
+
+		if !self.is_active(ctx) {
+			return ctx.json().map_err(|e| invalid(&e));
+		}
+
+		let value: Value = ctx.json().map_err(|e| invalid(&e))?;
+		let value = self.run(ctx, value).await.map_err(MurRes::from)?;
+		mur_decode_argument(MurArgumentKind::Body, value).map_err(|e| invalid(&e))
+	}
+
+	/// Extracts the body as `T` in the format named by its `Content-Type`.
+	/// Unsupported content types answer `415 Unsupported Media Type`.
+	pub async fn payload<T: DeserializeOwned>(&self, ctx: &MurRequestContext) -> Result<T, MurRes> {
+		let invalid = |e: MurError| match e.status_code() {
+			StatusCode::UNSUPPORTED_MEDIA_TYPE => MurRes::from(e),
+			_ => MurResponder::error(&format!("Failed to parse body: {}", e)),
+		};
+
+		if !self.is_active(ctx) {
+			return ctx.body_as().map_err(invalid);
+		}
+
+		let value: Value = ctx.body_as().map_err(invalid)?;
+		let value = self.run(ctx, value).await.map_err(MurRes::from)?;
+		mur_decode_argument(MurArgumentKind::Body, value)
+			.map_err(|e| MurResponder::error(&format!("Failed to parse body: {}", e)))
+	}
+
+	/// Extracts the query string as `T`.
+	pub async fn query<T: DeserializeOwned>(&self, ctx: &MurRequestContext) -> Result<T, MurRes> {
+		let invalid = |e: &dyn Display| MurResponder::error(&format!("Failed to parse query: {}", e));
+		let query = ctx.query_string().unwrap_or("");
+
+		if !self.is_active(ctx) {
+			return serde_urlencoded::from_str(query).map_err(|e| invalid(&e));
+		}
+
+		let map: serde_json::Map<String, Value> =
+			serde_urlencoded::from_str(query).map_err(|e| invalid(&e))?;
+		let value = self
+			.run(ctx, Value::Object(map))
+			.await
+			.map_err(MurRes::from)?;
+		mur_decode_argument(MurArgumentKind::Query, value).map_err(|e| invalid(&e))
+	}
+
+	/// Extracts all path parameters as `T`.
+	pub async fn path<T: DeserializeOwned>(&self, ctx: &MurRequestContext) -> Result<T, MurRes> {
+		let value =
+			serde_json::to_value(&ctx.path_params).unwrap_or(Value::Object(serde_json::Map::new()));
+		let value = self.run(ctx, value).await.map_err(MurRes::from)?;
+		mur_decode_argument(MurArgumentKind::Path, value)
+			.map_err(|e| MurResponder::error(&format!("Failed to parse path: {}", e)))
+	}
+
+	/// Extracts the path parameter named after the argument.
+	pub async fn param<T>(&self, ctx: &MurRequestContext) -> Result<T, MurRes>
+	where
+		T: FromStr,
+		T::Err: Display,
+	{
+		let name = self.argument.name;
+		match self.path_scalar::<T>(ctx, false).await? {
+			Some(value) => Ok(value),
+			None => Err(MurResponder::error(&format!(
+				"Missing path parameter: {}",
+				name
+			))),
+		}
+	}
+
+	/// Like [`param`](Self::param), but yields `None` when the parameter is
+	/// missing or does not parse.
+	pub async fn param_opt<T>(&self, ctx: &MurRequestContext) -> Result<Option<T>, MurRes>
+	where
+		T: FromStr,
+		T::Err: Display,
+	{
+		self.path_scalar(ctx, true).await
+	}
+
+	/// Extracts the query parameter named after the argument.
+	pub async fn query_param<T>(&self, ctx: &MurRequestContext) -> Result<T, MurRes>
+	where
+		T: FromStr,
+		T::Err: Display,
+	{
+		let name = self.argument.name;
+		match self.query_scalar::<T>(ctx, false).await? {
+			Some(value) => Ok(value),
+			None => Err(MurResponder::error(&format!(
+				"Missing query parameter: {}",
+				name
+			))),
+		}
+	}
+
+	/// Like [`query_param`](Self::query_param), but yields `None` when the
+	/// parameter is missing or does not parse.
+	pub async fn query_param_opt<T>(&self, ctx: &MurRequestContext) -> Result<Option<T>, MurRes>
+	where
+		T: FromStr,
+		T::Err: Display,
+	{
+		self.query_scalar(ctx, true).await
+	}
+
+	async fn path_scalar<T>(
+		&self,
+		ctx: &MurRequestContext,
+		lenient: bool,
+	) -> Result<Option<T>, MurRes>
+	where
+		T: FromStr,
+		T::Err: Display,
+	{
+		let raw = ctx.path_param(self.argument.name);
+		self.scalar(ctx, raw, lenient, "path parameter").await
+	}
+
+	async fn query_scalar<T>(
+		&self,
+		ctx: &MurRequestContext,
+		lenient: bool,
+	) -> Result<Option<T>, MurRes>
+	where
+		T: FromStr,
+		T::Err: Display,
+	{
+		let raw = ctx.query_param(self.argument.name);
+		self.scalar(ctx, raw, lenient, "query parameter").await
+	}
+
+	async fn scalar<T>(
+		&self,
+		ctx: &MurRequestContext,
+		raw: Option<&str>,
+		lenient: bool,
+		label: &str,
+	) -> Result<Option<T>, MurRes>
+	where
+		T: FromStr,
+		T::Err: Display,
+	{
+		let value = raw.map_or(Value::Null, |raw| Value::String(raw.to_string()));
+		let value = self.run(ctx, value).await.map_err(MurRes::from)?;
+
+		let text = match value {
+			Value::Null => return Ok(None),
+			Value::String(text) => text,
+			Value::Number(n) => n.to_string(),
+			Value::Bool(b) => b.to_string(),
+			other => other.to_string(),
+		};
+
+		match text.parse::<T>() {
+			Ok(parsed) => Ok(Some(parsed)),
+			Err(_) if lenient => Ok(None),
+			Err(e) => Err(MurResponder::error(&format!(
+				"Invalid value for {} '{}': {}",
+				label, self.argument.name, e
+			))),
+		}
+	}
+}
+
+impl std::fmt::Debug for MurArgumentPipeline {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurArgumentPipeline")
+			.field("argument", &self.argument)
+			.field(
+				"pipes",
+				&self.pipes.iter().map(|p| p.name()).collect::<Vec<_>>(),
+			)
+			.finish()
+	}
+}
@@ -0,0 +1,216 @@
+use super::*;
+use crate::server::http::MurRequestContext;
+use crate::server::service::MurServiceContainer;
+use hyper::StatusCode;
+use hyper::body::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+fn ctx(uri: &str, params: &[(&str, &str)], body: Option<&str>) -> MurRequestContext {
+	let (parts, _) = http::Request::builder()
+		.method("POST")
+		.uri(uri)
+		.body(())
+		.unwrap()
+		.into_parts();
+	MurRequestContext::new(
+		parts,
+		body.map(|b| Bytes::from(b.to_string())),
+		params
+			.iter()
+			.map(|(k, v)| (k.to_string(), v.to_string()))
+			.collect(),
+		Arc::new(MurServiceContainer::new()),
+	)
+}
+
+fn argument(kind: MurArgumentKind, name: &'static str) -> MurArgument {
+	MurArgument::new(kind, name, "T")
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Signup {
+	name: String,
+	age: u32,
+}
+
+impl MurValidate for Signup {
+	fn validate(&self) -> Result<(), String> {
+		if self.age < 18 {
+			return Err("age must be at least 18".to_string());
+		}
+		Ok(())
+	}
+}
+
+#[derive(Debug, Deserialize)]
+struct Plain {
+	#[allow(dead_code)]
+	name: String,
+}
+
+#[test]
+fn test_parse_int_pipe() {
+	let pipe = ParseIntPipe::new();
+	let arg = argument(MurArgumentKind::Param, "id");
+
+	assert_eq!(pipe.parse(json!("42"), &arg).unwrap(), json!(42));
+	assert_eq!(pipe.parse(json!(-7), &arg).unwrap(), json!(-7));
+	assert_eq!(pipe.parse(Value::Null, &arg).unwrap(), Value::Null);
+
+	let err = pipe.parse(json!("4x"), &arg).unwrap_err();
+	assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+	assert!(err.to_string().contains("'id'"));
+	assert!(pipe.parse(json!(1.5), &arg).is_err());
+}
+
+#[test]
+fn test_parse_uuid_pipe() {
+	let arg = argument(MurArgumentKind::Param, "id");
+	let v4 = "6F9619FF-8B86-4011-B42D-00C04FC964FF";
+
+	assert_eq!(
+		ParseUuidPipe::new().parse(json!(v4), &arg).unwrap(),
+		json!(v4.to_lowercase())
+	);
+	assert!(ParseUuidPipe::new().parse(json!("nope"), &arg).is_err());
+	assert!(
+		ParseUuidPipe::new()
+			.version(4)
+			.parse(json!(v4), &arg)
+			.is_ok()
+	);
+
+	let err = ParseUuidPipe::new()
+		.version(7)
+		.parse(json!(v4), &arg)
+		.unwrap_err();
+	assert!(err.to_string().contains("uuid v7"));
+}
+
+#[test]
+fn test_default_value_pipe() {
+	let pipe = DefaultValuePipe::new(10);
+
+	assert_eq!(pipe.apply(Value::Null), json!(10));
+	assert_eq!(pipe.apply(json!("3")), json!("3"));
+}
+
+#[test]
+fn test_trim_pipe_is_recursive() {
+	let trimmed = TrimPipe::new().trim(json!({
+		"name": "  Ada ",
+		"tags": [" a", "b "],
+		"age": 3
+	}));
+
+	assert_eq!(
+		trimmed,
+		json!({"name": "Ada", "tags": ["a", "b"], "age": 3})
+	);
+}
+
+#[test]
+fn test_validator_probe_selects_impl() {
+	use super::{MurProbeFallback as _, MurProbeValidate as _};
+
+	let validator = (&&MurValidatorProbe::<Signup>::new()).validator().unwrap();
+	assert!(validator(&json!({"name": "a", "age": 30}), MurArgumentKind::Body).is_ok());
+	let err = validator(&json!({"name": "a", "age": 3}), MurArgumentKind::Body).unwrap_err();
+	assert_eq!(err.message(), "age must be at least 18");
+	// Values that do not decode are left for extraction to reject.
+	assert!(validator(&json!({"name": "a"}), MurArgumentKind::Body).is_ok());
+
+	// Generated code always probes through `&&`, whatever the type.
+	#[allow(clippy::needless_borrow)]
+	let fallback = (&&MurValidatorProbe::<Plain>::new()).validator();
+	assert!(fallback.is_none());
+}
+
+#[test]
+fn test_validation_pipe() {
+	use super::MurProbeValidate as _;
+
+	let pipe = ValidationPipe::new();
+	let arg = argument(MurArgumentKind::Body, "dto")
+		.validator((&&MurValidatorProbe::<Signup>::new()).validator());
+
+	assert!(pipe.check(&json!({"name": "a", "age": 20}), &arg).is_ok());
+	let err = pipe
+		.check(&json!({"name": "a", "age": 2}), &arg)
+		.unwrap_err();
+	assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+
+	let unchecked = argument(MurArgumentKind::Body, "dto");
+	assert!(pipe.check(&json!({"age": 2}), &unchecked).is_ok());
+}
+
+#[test]
+fn test_decode_query_argument_parses_scalars() {
+	let value = json!({"name": "x", "age": "21"});
+
+	let decoded: Signup = mur_decode_argument(MurArgumentKind::Query, value.clone()).unwrap();
+	assert_eq!(decoded.age, 21);
+	assert!(mur_decode_argument::<Signup>(MurArgumentKind::Body, value).is_err());
+}
+
+#[tokio::test]
+async fn test_pipeline_runs_global_then_scoped_pipes() {
+	let mut ctx = ctx("/users?limit=", &[("id", " 12 ")], None);
+	ctx.parts.extensions.insert(MurGlobalPipes(Arc::new(vec![
+		Arc::new(TrimPipe) as Arc<dyn MurPipeDyn>
+	])));
+
+	let id = MurArgumentPipeline::new(
+		argument(MurArgumentKind::Param, "id"),
+		vec![Arc::new(ParseIntPipe)],
+	);
+	assert_eq!(id.param::<i64>(&ctx).await.ok(), Some(12));
+
+	let limit = MurArgumentPipeline::new(
+		argument(MurArgumentKind::QueryParam, "page"),
+		vec![Arc::new(DefaultValuePipe::new(1))],
+	);
+	assert_eq!(limit.query_param::<u32>(&ctx).await.ok(), Some(1));
+}
+
+#[tokio::test]
+async fn test_pipeline_errors_are_returned_as_errors() {
+	let ctx = ctx("/users/abc", &[("id", "abc")], None);
+	let pipeline = MurArgumentPipeline::new(
+		argument(MurArgumentKind::Param, "id"),
+		vec![Arc::new(ParseIntPipe)],
+	);
+
+	let res = pipeline.param::<i64>(&ctx).await.unwrap_err();
+	let err = res.into_result().unwrap_err();
+	assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_pipeline_body_and_missing_values() {
+	use super::MurProbeValidate as _;
+
+	let ctx = ctx("/", &[], Some(r#"{"name":" Ada ","age":17}"#));
+	let body = MurArgumentPipeline::new(
+		argument(MurArgumentKind::Body, "dto")
+			.validator((&&MurValidatorProbe::<Signup>::new()).validator()),
+		vec![Arc::new(TrimPipe), Arc::new(ValidationPipe)],
+	);
+	let res = body.body::<Signup>(&ctx).await.unwrap_err();
+	assert!(res.into_result().is_err());
+
+	let trim_only = MurArgumentPipeline::new(
+		argument(MurArgumentKind::Body, "dto"),
+		vec![Arc::new(TrimPipe)],
+	);
+	assert_eq!(
+		trim_only.body::<Signup>(&ctx).await.ok().unwrap().name,
+		"Ada"
+	);
+
+	let missing = MurArgumentPipeline::new(argument(MurArgumentKind::Param, "id"), vec![]);
+	assert!(missing.param::<i64>(&ctx).await.is_err());
+	assert_eq!(missing.param_opt::<i64>(&ctx).await.ok(), Some(None));
+}
@@ -0,0 +1,88 @@
+use super::argument::{MurArgumentKind, MurValidator, mur_decode_argument};
+use crate::server::error::{MurError, MurFieldError};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// Validation rules for a DTO, run by [`ValidationPipe`](super::ValidationPipe)
+/// and by `#[validate]` parameters.
+///
+/// `#[derive(MurDto)]` implements it with no rules; implement it by hand
+/// instead of deriving when the DTO has constraints. Failures reported by
+/// [`field_errors`](MurValidate::field_errors) are answered with the list
+/// of fields, in the `errors` member of the error body.
+///
+/// ```rust,ignore
+/// impl MurValidate for CreateUserDto {
+///     fn field_errors(&self) -> Vec<MurFieldError> {
+///         let mut errors = Vec::new();
+///         if self.name.is_empty() {
+///             errors.push(MurFieldError::new("name", "cannot be empty"));
+///         }
+///         errors
+///     }
+/// }
+/// ```
+pub trait MurValidate {
+	fn validate(&self) -> Result<(), String> {
+		Ok(())
+	}
+
+	/// Field-level failures, checked before [`validate`](MurValidate::validate).
+	fn field_errors(&self) -> Vec<MurFieldError> {
+		Vec::new()
+	}
+}
+
+/// Lets generated handler code obtain a [`MurValidator`] for types that
+/// implement [`MurValidate`] and `None` for every other type, without
+/// requiring the bound:
+///
+/// ```rust,ignore
+/// use murgamu::server::pipe::{MurProbeFallback as _, MurProbeValidate as _};
+/// let validator = (&&MurValidatorProbe::<T>::new()).validator();
+/// ```
+pub struct MurValidatorProbe<T>(PhantomData<T>);
+
+impl<T> MurValidatorProbe<T> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T> Default for MurValidatorProbe<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+pub trait MurProbeValidate {
+	fn validator(&self) -> Option<MurValidator>;
+}
+
+impl<T: MurValidate + DeserializeOwned> MurProbeValidate for &MurValidatorProbe<T> {
+	fn validator(&self) -> Option<MurValidator> {
+		// Values that do not decode are left for the extractor to reject.
+		Some(
+			|value, kind: MurArgumentKind| match mur_decode_argument::<T>(kind, value.clone()) {
+				Ok(decoded) => {
+					let errors = decoded.field_errors();
+					if !errors.is_empty() {
+						return Err(MurError::invalid(errors));
+					}
+					decoded.validate().map_err(MurError::validation)
+				}
+				Err(_) => Ok(()),
+			},
+		)
+	}
+}
+
+pub trait MurProbeFallback {
+	fn validator(&self) -> Option<MurValidator>;
+}
+
+impl<T> MurProbeFallback for MurValidatorProbe<T> {
+	fn validator(&self) -> Option<MurValidator> {
+		None
+	}
+}
@@ -1,12 +1,16 @@
 use super::MurRouteInfo;
 use super::core::MurRouter;
 use super::entry::MurRouteEntry;
+use super::metadata::MurRouteMetadata;
 use super::pattern::MurRoutePattern;
 use crate::server::aliases::MurRouteHandler;
+use crate::server::decorator::MurDecorator;
 use crate::server::guard::MurGuard;
 use crate::server::interceptor::MurInterceptor;
-use std::collections::HashMap;
+use crate::server::middleware::csrf::MurCsrfExempt;
+use crate::server::middleware::rate_limit::{MurRouteThrottle, MurThrottlePolicy};
 use std::sync::Arc;
+use std::time::Duration;
 
 pub struct MurRouteBuilder<'a> {
 	router: &'a mut MurRouter,
@@ -14,7 +18,9 @@ pub struct MurRouteBuilder<'a> {
 	path: String,
 	guards: Vec<Arc<dyn MurGuard + Sync + Send>>,
 	interceptors: Vec<Arc<dyn MurInterceptor + Sync + Send>>,
-	metadata: HashMap<String, String>,
+	metadata: MurRouteMetadata,
+	throttle: Option<MurRouteThrottle>,
+	timeout: Option<Duration>,
 }
 
 impl<'a> MurRouteBuilder<'a> {
@@ -25,7 +31,9 @@ impl<'a> MurRouteBuilder<'a> {
 			path: path.to_string(),
 			guards: Vec::new(),
 			interceptors: Vec::new(),
-			metadata: HashMap::new(),
+			metadata: MurRouteMetadata::new(),
+			throttle: None,
+			timeout: None,
 		}
 	}
 
@@ -40,7 +48,43 @@ impl<'a> MurRouteBuilder<'a> {
 	}
 
 	pub fn metadata(mut self, key: &str, value: &str) -> Self {
-		self.metadata.insert(key.to_string(), value.to_string());
+		self.metadata.insert(key, value);
+		self
+	}
+
+	pub fn typed_metadata<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+		self.metadata.insert_typed(value);
+		self
+	}
+
+	pub fn decorate(mut self, decorator: impl MurDecorator) -> Self {
+		self.metadata = self.metadata.decorate(decorator);
+		self
+	}
+
+	pub fn throttle(mut self, policy: MurThrottlePolicy) -> Self {
+		self.throttle = Some(MurRouteThrottle::Policy(policy));
+		self
+	}
+
+	pub fn throttle_policy(mut self, name: impl Into<String>) -> Self {
+		self.throttle = Some(MurRouteThrottle::Named(name.into()));
+		self
+	}
+
+	pub fn skip_throttle(mut self) -> Self {
+		self.throttle = Some(MurRouteThrottle::Skip);
+		self
+	}
+
+	/// Exempts the route from `MurCsrf` verification.
+	pub fn skip_csrf(mut self) -> Self {
+		self.metadata.insert_typed(MurCsrfExempt);
+		self
+	}
+
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
 		self
 	}
 
@@ -49,20 +93,29 @@ impl<'a> MurRouteBuilder<'a> {
 		let mut entry = MurRouteEntry::new(pattern, handler);
 		entry.guards = self.guards;
 		entry.interceptors = self.interceptors;
-		entry.metadata = self.metadata;
+		entry.metadata = self
+			.router
+			.resolve_metadata(self.metadata.bind(&self.method, &self.path, "manual"));
+		entry.throttle = self
+			.router
+			.resolve_throttle(&self.method, &self.path, self.throttle);
+		entry.timeout = self.router.resolve_timeout(self.timeout);
 
 		self.router.route_info.push(MurRouteInfo {
 			method: self.method.clone(),
 			path: self.path,
 			controller: String::from("manual"),
 			handler: String::new(),
+			versions: Vec::new(),
+			deprecated: false,
 		});
 
 		if !self.router.registered_methods.contains(&self.method) {
 			self.router.registered_methods.push(self.method.clone());
 		}
 
-		self.router
+		self
+			.router
 			.routes_by_method
 			.entry(self.method)
 			.or_default()
@@ -2,37 +2,69 @@ use super::MurRouteInfo;
 use super::entry::MurRouteEntry;
 use super::pattern::MurRoutePattern;
 use crate::MurPipeDyn;
-use crate::server::aliases::{MurPathParams, MurRes, MurRouteHandler};
+use crate::server::aliases::{MurFuture, MurPathParams, MurRes, MurRouteHandler};
 use crate::server::controller::MurController;
 use crate::server::error::MurError;
 use crate::server::error::MurExceptionFilter;
+use crate::server::error::MurErrorFormat;
 use crate::server::guard::MurGuard;
 use crate::server::http::MurHttpResponse;
+use crate::server::http::codec::{MurBodyCodec, MurBodyCodecs, mur_encode_negotiated};
 use crate::server::http::MurRequestContext;
-use crate::server::interceptor::MurInterceptor;
+use crate::server::interceptor::{MurCallHandler, MurInterceptor};
+use crate::server::logging::mur_log_line;
 use crate::server::middleware::MurMiddleware;
+use crate::server::middleware::csrf::MurCsrfExempt;
+use crate::server::middleware::rate_limit::headers::{
+	MurRateLimitInfo, mur_rate_limit_headers, mur_rate_limited,
+};
+use crate::server::middleware::rate_limit::{
+	InMemoryStore, MurRouteThrottle, MurThrottleOverride, MurThrottlePolicy, MurThrottlerResult,
+	MurThrottlerStore,
+};
+use crate::server::middleware::timeout::mur_timeout::mur_timed_out;
+use crate::server::middleware::timeout::{MurDeadline, MurRouteTimeout};
+use crate::server::pipe::MurGlobalPipes;
+use super::fallback::{MurFallback, MurTrailingSlash};
+use super::host::{MurHostParams, MurHostPattern, request_host};
+use super::versioning::{MurApiVersion, MurVersioning};
+use super::pattern::{is_under, normalize_path};
 use crate::server::router::MurRouteAccessControl;
+use crate::server::router::MurRouteMetadata;
 use crate::server::security::PreprocessedBody;
-use crate::server::service::MurServiceContainer;
+use crate::server::security::cookie::MurCookieKeys;
+use crate::server::service::{MurInjects, MurServiceContainer};
 use http_body_util::Full;
 use hyper::body::Bytes;
 use hyper::{Response, StatusCode};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub struct MurRouter {
 	pub(crate) routes_by_method: HashMap<String, Vec<MurRouteEntry>>,
 	pub(crate) global_guards: Vec<Arc<dyn MurGuard + Send + Sync>>,
-	pub(crate) pipes: Vec<Arc<dyn MurPipeDyn>>,
+	pub(crate) pipes: Arc<Vec<Arc<dyn MurPipeDyn>>>,
+	pub(crate) body_codecs: Arc<MurBodyCodecs>,
+	pub(crate) cookie_keys: Option<MurCookieKeys>,
 	pub(crate) global_interceptors: Vec<Arc<dyn MurInterceptor + Send + Sync>>,
 	pub(crate) global_middleware: Vec<Arc<dyn MurMiddleware + Send + Sync>>,
 	pub(crate) exception_filters: Vec<Arc<dyn MurExceptionFilter + Send + Sync>>,
 	pub(crate) container: Arc<MurServiceContainer>,
 	pub(crate) route_info: Vec<MurRouteInfo>,
-	pub(crate) not_found_handler: Option<MurRouteHandler>,
+	pub(crate) fallbacks: Vec<MurFallback>,
+	pub(crate) trailing_slash: MurTrailingSlash,
+	pub(crate) global_prefix: String,
+	pub(crate) global_prefix_exclusions: Vec<String>,
+	pub(crate) versioning: Option<MurVersioning>,
 	pub(crate) error_handler: Option<Arc<dyn Fn(MurError) -> MurRes + Send + Sync>>,
+	pub(crate) error_format: MurErrorFormat,
 	pub(crate) registered_methods: Vec<String>,
 	pub(crate) default_public: bool,
+	pub(crate) throttle_policies: HashMap<String, MurThrottlePolicy>,
+	pub(crate) throttle_store: Arc<dyn MurThrottlerStore>,
+	has_route_overrides: bool,
+	route_aware_middleware: bool,
 }
 
 impl MurRouter {
@@ -45,43 +77,98 @@ impl MurRouter {
 		Self {
 			routes_by_method,
 			global_guards: Vec::new(),
-			pipes: Vec::new(),
+			pipes: Arc::new(Vec::new()),
+			body_codecs: Arc::new(MurBodyCodecs::default()),
+			cookie_keys: None,
 			global_interceptors: Vec::new(),
 			global_middleware: Vec::new(),
 			exception_filters: Vec::new(),
 			container,
 			route_info: Vec::new(),
-			not_found_handler: None,
+			fallbacks: Vec::new(),
+			trailing_slash: MurTrailingSlash::default(),
+			global_prefix: String::new(),
+			global_prefix_exclusions: Vec::new(),
+			versioning: None,
 			error_handler: None,
+			error_format: MurErrorFormat::default(),
 			registered_methods: Vec::new(),
 			default_public: false,
+			throttle_policies: HashMap::new(),
+			throttle_store: Arc::new(InMemoryStore::new()),
+			has_route_overrides: false,
+			route_aware_middleware: false,
 		}
 	}
 
 	pub fn register_controller(&mut self, controller: Arc<dyn MurController>) {
+		let container = Arc::clone(&self.container);
+		self.register_controller_in(controller, "/", &MurInjects::new(), &container);
+	}
+
+	/// Registers a controller under the path prefix of the module that
+	/// declares it, resolving its route guards and interceptors from the
+	/// module's DI scope.
+	pub fn register_controller_in(
+		&mut self,
+		controller: Arc<dyn MurController>,
+		module_prefix: &str,
+		injects: &MurInjects,
+		container: &MurServiceContainer,
+	) {
 		let controller_name = controller.name().to_string();
-		let routes = controller.routes(&self.container);
+		let host = controller.host().map(MurHostPattern::new);
+		let routes = controller.routes(container);
 
 		for route_def in routes {
-			let pattern = MurRoutePattern::new(&route_def.path);
-			let mut entry = MurRouteEntry::new(pattern, route_def.handler);
-
-			entry.access_control = MurRouteAccessControl {
+			let guards: Vec<_> = route_def
+				.guards
+				.iter()
+				.map(|provider| provider(injects, container))
+				.collect();
+			let interceptors: Vec<_> = route_def
+				.interceptors
+				.iter()
+				.map(|provider| provider(injects, container))
+				.collect();
+			let access_control = MurRouteAccessControl {
 				is_public: route_def.is_public || self.default_public,
-				allowed_roles: route_def.allowed_roles.into_iter().collect(),
+				allowed_roles: route_def.allowed_roles.iter().cloned().collect(),
 			};
 
-			self.route_info.push(MurRouteInfo {
-				method: route_def.method.clone(),
-				path: route_def.path.clone(),
-				controller: controller_name.clone(),
-				handler: String::new(),
-			});
-
-			self.routes_by_method
-				.entry(route_def.method.clone())
-				.or_default()
-				.push(entry);
+			for (path, versions) in
+				self.versioned_paths(module_prefix, &route_def.path, &route_def.versions)
+			{
+				let pattern = MurRoutePattern::new(&path);
+				let mut entry = MurRouteEntry::new(pattern, Arc::clone(&route_def.handler));
+				entry.host = host.clone();
+				entry.access_control = access_control.clone();
+				entry.throttle =
+					self.resolve_throttle(&route_def.method, &path, route_def.throttle.clone());
+				entry.timeout = self.resolve_timeout(route_def.timeout);
+				entry.guards = guards.clone();
+				entry.interceptors = interceptors.clone();
+				entry.metadata = self.resolve_metadata(route_def.metadata.clone().bind(
+					&route_def.method,
+					&path,
+					&controller_name,
+				));
+
+				self.route_info.push(MurRouteInfo {
+					method: route_def.method.clone(),
+					path,
+					controller: controller_name.clone(),
+					handler: entry.metadata.handler_name().to_string(),
+					deprecated: self.is_deprecated(&versions),
+					versions: versions.clone(),
+				});
+				entry.versions = versions;
+
+				self.routes_by_method
+					.entry(route_def.method.clone())
+					.or_default()
+					.push(entry);
+			}
 
 			if !self.registered_methods.contains(&route_def.method) {
 				self.registered_methods.push(route_def.method);
@@ -91,16 +178,82 @@ impl MurRouter {
 		self.sort_all_routes();
 	}
 
+	/// The paths a controller route is registered under, with the versions
+	/// each serves. URI versioning registers one path per version, plus the
+	/// unversioned path for the default version.
+	fn versioned_paths(
+		&self,
+		module_prefix: &str,
+		path: &str,
+		versions: &[String],
+	) -> Vec<(String, Vec<String>)> {
+		let versioning = self.versioning.as_ref().filter(|v| v.is_uri());
+		let Some(versioning) = versioning.filter(|_| !versions.is_empty()) else {
+			return vec![(self.scoped_path(module_prefix, path, None), versions.to_vec())];
+		};
+
+		let mut paths: Vec<(String, Vec<String>)> = versions
+			.iter()
+			.map(|version| {
+				let segment = versioning.uri_segment(version);
+				let path = self.scoped_path(module_prefix, path, segment.as_deref());
+				(path, vec![version.clone()])
+			})
+			.collect();
+		if let Some(default) = versioning
+			.default_version_str()
+			.filter(|default| versions.iter().any(|v| v == default))
+		{
+			let path = self.scoped_path(module_prefix, path, None);
+			paths.push((path, vec![default.to_string()]));
+		}
+		paths
+	}
+
+	/// Prefixes a controller route with the module prefix and the version
+	/// segment, then with the global prefix unless the route is excluded
+	/// from it.
+	fn scoped_path(&self, module_prefix: &str, path: &str, version: Option<&str>) -> String {
+		let path = join_paths(module_prefix, path);
+		let excluded = self
+			.global_prefix_exclusions
+			.iter()
+			.any(|excluded| is_under(&path, excluded));
+		let path = match version {
+			Some(segment) => join_paths(segment, &path),
+			None => path,
+		};
+		if excluded {
+			path
+		} else {
+			join_paths(&self.global_prefix, &path)
+		}
+	}
+
+	/// Whether every version in `versions` is deprecated.
+	fn is_deprecated(&self, versions: &[String]) -> bool {
+		let Some(versioning) = &self.versioning else {
+			return false;
+		};
+		!versions.is_empty()
+			&& versions
+				.iter()
+				.all(|version| versioning.deprecation(version).is_some())
+	}
+
 	pub fn route(&mut self, method: &str, path: &str, handler: MurRouteHandler) {
 		let method = method.to_uppercase();
 		let pattern = MurRoutePattern::new(path);
-		let entry = MurRouteEntry::new(pattern, handler);
+		let mut entry = MurRouteEntry::new(pattern, handler);
+		entry.metadata = Arc::new(MurRouteMetadata::new().bind(&method, path, "manual"));
 
 		self.route_info.push(MurRouteInfo {
 			method: method.clone(),
 			path: path.to_string(),
 			controller: String::from("manual"),
 			handler: String::new(),
+			versions: Vec::new(),
+			deprecated: false,
 		});
 
 		self.routes_by_method
@@ -113,11 +266,7 @@ impl MurRouter {
 		}
 
 		if let Some(routes) = self.routes_by_method.get_mut(&method) {
-			routes.sort_by(|a, b| {
-				b.pattern
-					.specificity_score()
-					.cmp(&a.pattern.specificity_score())
-			});
+			routes.sort_by_key(|route| std::cmp::Reverse(route.rank()));
 		}
 	}
 
@@ -163,10 +312,14 @@ impl MurRouter {
 	}
 
 	pub fn middleware(&mut self, middleware: impl MurMiddleware + 'static) {
-		self.global_middleware.push(Arc::new(middleware));
+		self.middleware_boxed(Box::new(middleware));
 	}
 
 	pub fn middleware_boxed(&mut self, middleware: Box<dyn MurMiddleware + Send + Sync>) {
+		if middleware.needs_matched_route() {
+			self.has_route_overrides = true;
+			self.route_aware_middleware = true;
+		}
 		self.global_middleware.push(Arc::from(middleware));
 	}
 
@@ -175,7 +328,144 @@ impl MurRouter {
 	}
 
 	pub fn pipe_boxed(&mut self, pipe: Box<dyn MurPipeDyn>) {
-		self.pipes.push(Arc::from(pipe));
+		Arc::make_mut(&mut self.pipes).push(Arc::from(pipe));
+	}
+
+	/// Adds a body format read by `MurPayload` and written by negotiated
+	/// responses.
+	pub fn body_codec(&mut self, codec: Arc<dyn MurBodyCodec>) {
+		Arc::make_mut(&mut self.body_codecs).register(codec);
+	}
+
+	/// Sets the keys signed and encrypted cookies are written with.
+	pub fn set_cookie_keys(&mut self, keys: MurCookieKeys) {
+		self.cookie_keys = Some(keys);
+	}
+
+	/// Registers a named policy usable as `#[throttle("name")]`.
+	///
+	/// Policies must be registered before the controllers that use them.
+	pub fn throttle_policy(&mut self, name: impl Into<String>, policy: MurThrottlePolicy) {
+		let name = name.into();
+		self
+			.throttle_policies
+			.insert(name.clone(), policy.named(name));
+	}
+
+	/// Replaces the store holding route policy counters (in-memory by default).
+	pub fn throttle_store(&mut self, store: Arc<dyn MurThrottlerStore>) {
+		self.throttle_store = store;
+	}
+
+	/// Resolves named policies and gives inline ones a name for the
+	/// `RateLimit-Policy` header. Unknown names are kept as
+	/// [`MurRouteThrottle::Named`] and reported by [`Self::validate`].
+	pub(crate) fn resolve_throttle(
+		&mut self,
+		method: &str,
+		path: &str,
+		throttle: Option<MurRouteThrottle>,
+	) -> Option<MurRouteThrottle> {
+		let throttle = match throttle? {
+			MurRouteThrottle::Named(name) => match self.throttle_policies.get(&name) {
+				Some(policy) => MurRouteThrottle::Policy(policy.clone()),
+				None => MurRouteThrottle::Named(name),
+			},
+			MurRouteThrottle::Policy(policy) if policy.name.is_none() => {
+				MurRouteThrottle::Policy(policy.named(format!("{} {}", method, path)))
+			}
+			other => other,
+		};
+		self.has_route_overrides = true;
+		Some(throttle)
+	}
+
+	/// Records that a route carries its own timeout, so requests are looked up
+	/// before the middleware chain runs.
+	pub(crate) fn resolve_timeout(&mut self, timeout: Option<Duration>) -> Option<Duration> {
+		if timeout.is_some() {
+			self.has_route_overrides = true;
+		}
+		timeout
+	}
+
+	/// Records that a route is exempt from CSRF verification, so requests are
+	/// looked up before the middleware chain runs.
+	pub(crate) fn resolve_metadata(&mut self, metadata: MurRouteMetadata) -> Arc<MurRouteMetadata> {
+		if metadata.contains_typed::<MurCsrfExempt>() {
+			self.has_route_overrides = true;
+		}
+		Arc::new(metadata)
+	}
+
+	/// Checks the registered routes: path and host patterns must be well
+	/// formed, named throttle policies must exist, versioned routes need a
+	/// versioning strategy, and no two routes of a method may match the same
+	/// paths on the same hosts and versions.
+	pub fn validate(&self) -> Result<(), String> {
+		let versions_in_path = self.versioning.as_ref().is_some_and(|v| v.is_uri());
+
+		for (method, routes) in &self.routes_by_method {
+			let mut shapes: HashMap<String, String> = HashMap::new();
+
+			for route in routes {
+				if let Some(error) = route.pattern.error() {
+					return Err(error.to_string());
+				}
+				if let Some(error) = route.host.as_ref().and_then(|host| host.error()) {
+					return Err(error.to_string());
+				}
+				if let Some(MurRouteThrottle::Named(name)) = &route.throttle {
+					return Err(format!(
+						"Unknown throttle policy '{}' on {} {}",
+						name, method, route.pattern.pattern
+					));
+				}
+				if !route.versions.is_empty() && self.versioning.is_none() {
+					return Err(format!(
+						"Route {} {} declares API versions but no versioning strategy is set",
+						method, route.pattern.pattern
+					));
+				}
+				let (host_shape, name) = match &route.host {
+					Some(host) => (
+						host.shape(),
+						format!("{} (host {})", route.pattern.pattern, host.pattern),
+					),
+					None => (String::new(), route.pattern.pattern.clone()),
+				};
+				let versions = if versions_in_path || route.versions.is_empty() {
+					vec![""]
+				} else {
+					route.versions.iter().map(String::as_str).collect()
+				};
+				for shape in route.pattern.shapes() {
+					for version in &versions {
+						let key = format!("{}|{}{}", version, host_shape, shape);
+						if let Some(other) = shapes.insert(key, name.clone()) {
+							return Err(format!(
+								"Conflicting routes: {} {} and {} {} match the same paths",
+								method, other, method, name
+							));
+						}
+					}
+				}
+			}
+		}
+
+		for (index, middleware) in self.global_middleware.iter().enumerate() {
+			let ahead = &self.global_middleware[..index];
+			for required in middleware.requires() {
+				if !ahead.iter().any(|m| m.name() == *required) {
+					return Err(format!(
+						"{} needs {} registered ahead of it",
+						middleware.name(),
+						required
+					));
+				}
+			}
+		}
+		Ok(())
 	}
 
 	pub async fn execute_matched_route(
@@ -211,8 +501,44 @@ impl MurRouter {
 		self.exception_filters.push(Arc::new(filter));
 	}
 
+	/// Sets the handler for requests that match no route. It runs inside
+	/// the middleware chain with the request's context.
 	pub fn set_not_found_handler(&mut self, handler: MurRouteHandler) {
-		self.not_found_handler = Some(handler);
+		self.set_not_found_handler_for("/", handler);
+	}
+
+	/// Sets the not-found handler for the paths under `prefix`. The longest
+	/// matching prefix wins.
+	pub fn set_not_found_handler_for(&mut self, prefix: &str, handler: MurRouteHandler) {
+		let fallback = MurFallback::new(prefix, handler);
+		self.fallbacks.retain(|f| f.prefix != fallback.prefix);
+		self.fallbacks.push(fallback);
+		self
+			.fallbacks
+			.sort_by_key(|f| std::cmp::Reverse(f.prefix.len()));
+	}
+
+	/// Prefixes every controller route with `prefix`, e.g. `/api`.
+	pub fn set_global_prefix(&mut self, prefix: &str) {
+		self.global_prefix = normalize_path(prefix).into_owned();
+	}
+
+	/// Keeps the controller routes at or below `path` out of the global
+	/// prefix.
+	pub fn exclude_from_global_prefix(&mut self, path: &str) {
+		self
+			.global_prefix_exclusions
+			.push(normalize_path(path).into_owned());
+	}
+
+	/// Selects controller routes by API version. Set it before registering
+	/// controllers.
+	pub fn set_versioning(&mut self, versioning: MurVersioning) {
+		self.versioning = Some(versioning);
+	}
+
+	pub fn set_trailing_slash(&mut self, policy: MurTrailingSlash) {
+		self.trailing_slash = policy;
 	}
 
 	pub fn set_error_handler(
@@ -222,6 +548,12 @@ impl MurRouter {
 		self.error_handler = Some(Arc::new(handler));
 	}
 
+	/// Sets how errors are written when no filter or error handler takes
+	/// them. Routes override it with a `MurErrorFormat` metadata value.
+	pub fn set_error_format(&mut self, format: MurErrorFormat) {
+		self.error_format = format;
+	}
+
 	#[inline]
 	pub fn route_info(&self) -> &[MurRouteInfo] {
 		&self.route_info
@@ -246,13 +578,18 @@ impl MurRouter {
 		let mut idx = 0;
 
 		for info in self.route_info() {
-			let size = info.controller.len() + info.method.len() + info.path.len();
+			let path = if info.versions.is_empty() {
+				info.path.clone()
+			} else {
+				format!("{} [v{}]", info.path, info.versions.join(", v"))
+			};
+			let size = info.controller.len() + info.method.len() + path.len();
 			if size > max_width {
 				max_width = size;
 			}
 			routes.push((
 				info.method.clone(),
-				info.path.clone(),
+				path,
 				info.controller.clone(),
 				size,
 			));
@@ -334,12 +671,45 @@ impl MurRouter {
 			Err(e) => return e.into(),
 		};
 
-		let ctx = MurRequestContext::new(
+		let mut ctx = MurRequestContext::new(
 			preprocess.parts,
 			preprocess.body_bytes,
 			MurPathParams::new(),
 			Arc::clone(&self.container),
 		);
+		if let Some(keys) = &self.cookie_keys {
+			ctx.parts.extensions.insert(keys.clone());
+		}
+
+		// Checked before any middleware so scoped paths stay hidden from
+		// endpoints that middleware serves too.
+		if ctx.listener().is_some_and(|listener| !listener.serves(&preprocess.path)) {
+			return self.handle_not_found(ctx).await;
+		}
+
+		let scope = self.has_route_overrides.then(|| self.request_scope(&ctx));
+		if let Some(scope) = &scope
+			&& let Some((route, _)) = self
+				.find_route(&preprocess.method, scope, &preprocess.path)
+				// HEAD falls back to the GET route, as in `route_ctx`.
+				.or_else(|| match preprocess.method.as_str() {
+					"HEAD" => self.find_route("GET", scope, &preprocess.path),
+					_ => None,
+				})
+		{
+			if route.throttle.is_some() {
+				ctx.parts.extensions.insert(MurThrottleOverride);
+			}
+			if let Some(timeout) = route.timeout {
+				ctx.parts.extensions.insert(MurRouteTimeout(timeout));
+			}
+			if route.metadata.contains_typed::<MurCsrfExempt>() {
+				ctx.parts.extensions.insert(MurCsrfExempt);
+			}
+			if self.route_aware_middleware {
+				ctx.parts.extensions.insert(Arc::clone(&route.metadata));
+			}
+		}
 
 		if self.global_middleware.is_empty() {
 			return self
@@ -392,31 +762,242 @@ impl MurRouter {
 	}
 
 	async fn route_ctx(&self, method: String, path: String, mut ctx: MurRequestContext) -> MurRes {
-		let route_match = self.find_route(&method, &path);
+		let scope = self.request_scope(&ctx);
+		if path.len() > 1 && path.ends_with('/') {
+			match self.trailing_slash {
+				MurTrailingSlash::Lenient => {}
+				MurTrailingSlash::Strict => return self.handle_not_found(ctx).await,
+				MurTrailingSlash::Redirect => return self.redirect_trailing_slash(&path, ctx).await,
+			}
+		}
+
+		let route_match = self.find_route(&method, &scope, &path);
 
 		if route_match.is_none() {
 			if method == "OPTIONS" {
-				return self.handle_options(path);
+				return self.handle_options(&scope, &path);
 			}
 
 			if method == "HEAD"
-				&& let Some((route, params)) = self.find_route("GET", &path)
+				&& let Some((route, params)) = self.find_route("GET", &scope, &path)
 			{
 				ctx.path_params = params;
-				return self.execute_handler(route, ctx).await;
+				return self.execute_in_scope(route, &scope, ctx).await;
+			}
+
+			let allowed = self.allowed_methods(&scope, &path);
+			if !allowed.is_empty() {
+				return Self::method_not_allowed(&method, &allowed);
 			}
-			return self.handle_not_found(&path);
+			return self.handle_not_found(ctx).await;
 		}
 
 		let (route, path_params) = route_match.unwrap();
 		ctx.path_params = path_params;
-		self.execute_handler(route, ctx).await
+		self.execute_in_scope(route, &scope, ctx).await
+	}
+
+	/// The host and API version a request is routed by, besides its method
+	/// and path.
+	fn request_scope(&self, ctx: &MurRequestContext) -> MurRequestScope {
+		MurRequestScope {
+			host: request_host(&ctx.parts).map(str::to_string),
+			version: self
+				.versioning
+				.as_ref()
+				.and_then(|versioning| versioning.requested_version(ctx)),
+		}
+	}
+
+	fn serves(&self, route: &MurRouteEntry, scope: &MurRequestScope) -> bool {
+		if !route.serves_host(scope.host.as_deref()) {
+			return false;
+		}
+		route.versions.is_empty()
+			|| self.versioning.as_ref().is_some_and(|v| v.is_uri())
+			|| scope
+				.version
+				.as_ref()
+				.is_some_and(|version| route.versions.contains(version))
+	}
+
+	/// Runs the route with the API version it serves on the context, and
+	/// announces the version's deprecation on the response.
+	async fn execute_in_scope(
+		&self,
+		route: &MurRouteEntry,
+		scope: &MurRequestScope,
+		mut ctx: MurRequestContext,
+	) -> MurRes {
+		let version = match (&self.versioning, route.versions.as_slice()) {
+			(Some(versioning), [version]) if versioning.is_uri() => Some(version.clone()),
+			_ => scope.version.clone(),
+		};
+		let Some(version) = version else {
+			return self.execute_handler(route, ctx).await;
+		};
+
+		ctx.parts.extensions.insert(MurApiVersion(version.clone()));
+		let response = self.execute_handler(route, ctx).await;
+
+		let deprecation = self
+			.versioning
+			.as_ref()
+			.filter(|_| !route.versions.is_empty())
+			.and_then(|versioning| versioning.deprecation(&version));
+		match deprecation {
+			Some(deprecation) => response.map_response(|mut res| {
+				deprecation.apply(res.headers_mut());
+				res
+			}),
+			None => response,
+		}
 	}
 
 	async fn execute_handler(&self, route: &MurRouteEntry, ctx: MurRequestContext) -> MurRes {
-		let ctx = ctx.with_access_control(route.access_control.clone());
+		let throttle = match &route.throttle {
+			Some(MurRouteThrottle::Policy(policy)) => {
+				match self.check_route_throttle(route, policy, &ctx).await {
+					Ok(Some(result)) if !result.allowed => {
+						return Self::route_rate_limited(policy, &result);
+					}
+					Ok(Some(result)) => Some((policy, result)),
+					Ok(None) => None,
+					Err(rejected) => return rejected,
+				}
+			}
+			_ => None,
+		};
+
+		let response = match route.timeout {
+			Some(timeout) if ctx.deadline().is_none() => {
+				self.run_handler_with_timeout(route, ctx, timeout).await
+			}
+			_ => self.run_handler(route, ctx).await,
+		};
+
+		match throttle {
+			Some((policy, result)) => response.map_response(|mut res| {
+				mur_rate_limit_headers(res.headers_mut(), &Self::rate_limit_info(policy, &result));
+				res
+			}),
+			None => response,
+		}
+	}
+
+	/// Enforces a route's `#[timeout]` when no `MurTimeout` middleware has set
+	/// a deadline for the request.
+	async fn run_handler_with_timeout(
+		&self,
+		route: &MurRouteEntry,
+		mut ctx: MurRequestContext,
+		timeout: Duration,
+	) -> MurRes {
+		let deadline = MurDeadline::after(timeout);
+		ctx.parts.extensions.insert(deadline);
+
+		match tokio::time::timeout_at(deadline.at().into(), self.run_handler(route, ctx)).await {
+			Ok(response) => response,
+			Err(_elapsed) => mur_timed_out(StatusCode::REQUEST_TIMEOUT, "Request timed out", timeout),
+		}
+	}
+
+	/// Counts the request against the route's policy. Store failures let the
+	/// request through unless the policy fails closed.
+	async fn check_route_throttle(
+		&self,
+		route: &MurRouteEntry,
+		policy: &MurThrottlePolicy,
+		ctx: &MurRequestContext,
+	) -> Result<Option<MurThrottlerResult>, MurRes> {
+		let client = policy
+			.key
+			.extract(ctx)
+			.unwrap_or_else(|| "unknown".to_string());
+		let key = format!(
+			"{}|{} {}|{}",
+			policy.name.as_deref().unwrap_or_default(),
+			ctx.method(),
+			route.pattern.pattern,
+			client
+		);
+
+		match self
+			.throttle_store
+			.check_and_update(&key, policy.limit, policy.window)
+			.await
+		{
+			Ok(result) => Ok(Some(result)),
+			Err(e) if policy.fail_open => {
+				eprintln!(
+					"{}",
+					mur_log_line(format_args!(
+						"Throttler store error, allowing request: {}",
+						e
+					))
+				);
+				Ok(None)
+			}
+			Err(e) => {
+				eprintln!(
+					"{}",
+					mur_log_line(format_args!(
+						"Throttler store error, rejecting request: {}",
+						e
+					))
+				);
+				Err(MurRes::from(MurError::Custom(
+					StatusCode::SERVICE_UNAVAILABLE,
+					"Rate limiter unavailable".to_string(),
+				)))
+			}
+		}
+	}
+
+	fn rate_limit_info<'a>(
+		policy: &'a MurThrottlePolicy,
+		result: &MurThrottlerResult,
+	) -> MurRateLimitInfo<'a> {
+		MurRateLimitInfo {
+			policy: policy.name.as_deref().unwrap_or_default(),
+			limit: result.limit,
+			remaining: result.remaining,
+			reset_at: result.reset_at,
+			window: policy.window,
+		}
+	}
+
+	fn route_rate_limited(policy: &MurThrottlePolicy, result: &MurThrottlerResult) -> MurRes {
+		mur_rate_limited(
+			StatusCode::TOO_MANY_REQUESTS,
+			"Too Many Requests. Please try again later.",
+			result.retry_after,
+			Some(&Self::rate_limit_info(policy, result)),
+		)
+	}
+
+	async fn run_handler(&self, route: &MurRouteEntry, ctx: MurRequestContext) -> MurRes {
+		let mut ctx = ctx.with_access_control(route.access_control.clone());
+		ctx.parts.extensions.insert(Arc::clone(&route.metadata));
+		let host_params = route
+			.host
+			.as_ref()
+			.zip(request_host(&ctx.parts))
+			.and_then(|(pattern, host)| pattern.match_host(host));
+		if let Some(params) = host_params {
+			ctx.parts.extensions.insert(MurHostParams(params));
+		}
+		if !self.pipes.is_empty() {
+			ctx.parts
+				.extensions
+				.insert(MurGlobalPipes(Arc::clone(&self.pipes)));
+		}
+		ctx.parts.extensions.insert(Arc::clone(&self.body_codecs));
 
-		if !ctx.access_control.is_public && self.global_guards.is_empty() {
+		if !ctx.access_control.is_public
+			&& self.global_guards.is_empty()
+			&& route.guards.is_empty()
+		{
 			return MurHttpResponse::unauthorized().json(serde_json::json!({
 				"error": "Unauthorized",
 				"message": "Authentication required",
@@ -436,40 +1017,55 @@ impl MurRouter {
 			}
 		}
 
-		for interceptor in &self.global_interceptors {
-			if let Err(e) = interceptor.before(&ctx).await {
-				return self.handle_error(e);
-			}
-		}
+		let error_ctx = ctx.clone();
+		let response = if self.global_interceptors.is_empty() && route.interceptors.is_empty() {
+			(route.handler)(ctx).await
+		} else {
+			self.intercept_chain(route).run(ctx).await
+		};
 
-		for interceptor in &route.interceptors {
-			if let Err(e) = interceptor.before(&ctx).await {
-				return self.handle_error(e);
-			}
+		match response
+			.into_result()
+			.and_then(|res| mur_encode_negotiated(res, &error_ctx))
+		{
+			Ok(res) => MurRes::from(res),
+			Err(e) => self.handle_error(e, &error_ctx),
 		}
+	}
 
-		let result = (route.handler)(ctx.clone()).await;
-		let mut response = result;
-
-		for interceptor in route.interceptors.iter().rev() {
-			response = interceptor.after(&ctx, response).await;
-		}
+	/// Nests the route handler inside the route interceptors, and those inside
+	/// the global interceptors.
+	fn intercept_chain(&self, route: &MurRouteEntry) -> MurCallHandler {
+		let handler: Arc<dyn Fn(MurRequestContext) -> MurFuture + Send + Sync> =
+			route.handler.clone();
 
-		for interceptor in self.global_interceptors.iter().rev() {
-			response = interceptor.after(&ctx, response).await;
-		}
+		let chain = self
+			.global_interceptors
+			.iter()
+			.chain(&route.interceptors)
+			.rev()
+			.fold(handler, |next_handler, interceptor| {
+				let interceptor = Arc::clone(interceptor);
+				Arc::new(move |ctx: MurRequestContext| -> MurFuture {
+					let interceptor = Arc::clone(&interceptor);
+					let next = MurCallHandler::new(Arc::clone(&next_handler));
+					Box::pin(async move { interceptor.intercept(ctx, next).await })
+				})
+			});
 
-		match response.into_result() {
-			Ok(res) => MurRes::from(res),
-			Err(e) => self.handle_error(e),
-		}
+		MurCallHandler::new(chain)
 	}
 
 	#[inline]
-	fn find_route(&self, method: &str, path: &str) -> Option<(&MurRouteEntry, MurPathParams)> {
+	fn find_route(
+		&self,
+		method: &str,
+		scope: &MurRequestScope,
+		path: &str,
+	) -> Option<(&MurRouteEntry, MurPathParams)> {
 		let routes = self.routes_by_method.get(method)?;
 
-		for route in routes {
+		for route in routes.iter().filter(|route| self.serves(route, scope)) {
 			if let Some(params) = route.pattern.match_path(path) {
 				return Some((route, params));
 			}
@@ -479,49 +1075,108 @@ impl MurRouter {
 
 	pub(crate) fn sort_all_routes(&mut self) {
 		for routes in self.routes_by_method.values_mut() {
-			routes.sort_by(|a, b| {
-				b.pattern
-					.specificity_score()
-					.cmp(&a.pattern.specificity_score())
-			});
+			routes.sort_by_key(|route| std::cmp::Reverse(route.rank()));
 		}
 	}
 
-	fn handle_not_found(&self, path: &String) -> MurRes {
-		if let Some(_handler) = &self.not_found_handler {
-			let parts = http::Request::builder()
-				.uri(path)
-				.method("GET")
-				.body(())
-				.unwrap()
-				.into_parts()
-				.0;
-			let _ctx = MurRequestContext::new(
-				parts,
-				None,
-				MurPathParams::new(),
-				Arc::clone(&self.container),
-			);
+	/// Runs the not-found handler with the longest prefix covering the path,
+	/// or answers the default `404` body.
+	async fn handle_not_found(&self, ctx: MurRequestContext) -> MurRes {
+		let path = ctx.path().to_string();
+		let Some(fallback) = self.fallbacks.iter().find(|f| f.covers(&path)) else {
+			return MurHttpResponse::not_found().json(serde_json::json!({
+				"error": "Not Found",
+				"message": format!("No route found for path: {}", path),
+				"status": 404
+			}));
+		};
+
+		let error_ctx = ctx.clone();
+		match (fallback.handler)(ctx).await.into_result() {
+			Ok(res) => MurRes::from(res),
+			Err(e) => self.handle_error(e, &error_ctx),
+		}
+	}
+
+	/// Redirects `/users/` to `/users`, keeping the query, when the trimmed
+	/// path has a route.
+	async fn redirect_trailing_slash(&self, path: &str, ctx: MurRequestContext) -> MurRes {
+		let trimmed = normalize_path(path);
+		if self
+			.allowed_methods(&self.request_scope(&ctx), &trimmed)
+			.is_empty()
+		{
+			return self.handle_not_found(ctx).await;
 		}
 
-		MurHttpResponse::not_found().json(serde_json::json!({
-			"error": "Not Found",
-			"message": format!("No route found for path: {}", path),
-			"status": 404
-		}))
+		let location = match ctx.parts.uri.query() {
+			Some(query) => format!("{}?{}", trimmed, query),
+			None => trimmed.into_owned(),
+		};
+		MurRes::from(
+			Response::builder()
+				.status(StatusCode::PERMANENT_REDIRECT)
+				.header("Location", location)
+				.body(Full::new(Bytes::new()))
+				.unwrap(),
+		)
 	}
 
-	fn handle_options(&self, path: String) -> MurRes {
-		let mut methods = Vec::with_capacity(8);
+	/// The methods that have a route matching `path` in `scope`, plus the
+	/// `HEAD` and `OPTIONS` the router answers on their behalf.
+	fn allowed_methods(&self, scope: &MurRequestScope, path: &str) -> Vec<&str> {
+		const ORDER: [&str; 7] = ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
 
-		for (method, routes) in &self.routes_by_method {
-			for route in routes {
-				if route.pattern.match_path(path.as_str()).is_some() {
-					methods.push(method.as_str());
-					break;
-				}
-			}
+		let mut methods: Vec<&str> = self
+			.routes_by_method
+			.iter()
+			.filter(|(_, routes)| {
+				routes.iter().any(|route| {
+					self.serves(route, scope) && route.pattern.match_path(path).is_some()
+				})
+			})
+			.map(|(method, _)| method.as_str())
+			.collect();
+		if methods.is_empty() {
+			return methods;
+		}
+
+		if methods.contains(&"GET") && !methods.contains(&"HEAD") {
+			methods.push("HEAD");
+		}
+		if !methods.contains(&"OPTIONS") {
+			methods.push("OPTIONS");
 		}
+		methods.sort_by_key(|method| {
+			ORDER
+				.iter()
+				.position(|known| known == method)
+				.unwrap_or(ORDER.len())
+		});
+		methods
+	}
+
+	fn method_not_allowed(method: &str, allowed: &[&str]) -> MurRes {
+		let allow = allowed.join(", ");
+		MurRes::from(
+			Response::builder()
+				.status(StatusCode::METHOD_NOT_ALLOWED)
+				.header("Allow", &allow)
+				.header("Content-Type", "application/json")
+				.body(Full::new(Bytes::from(
+					serde_json::json!({
+						"error": "Method Not Allowed",
+						"message": format!("Method {} is not allowed; allowed: {}", method, allow),
+						"status": 405
+					})
+					.to_string(),
+				)))
+				.unwrap(),
+		)
+	}
+
+	fn handle_options(&self, scope: &MurRequestScope, path: &str) -> MurRes {
+		let methods = self.allowed_methods(scope, path);
 
 		let allow = if methods.is_empty() {
 			"GET, POST, PUT, DELETE, PATCH, OPTIONS, HEAD".to_string()
@@ -544,23 +1199,10 @@ impl MurRouter {
 		)
 	}
 
-	fn handle_error(&self, error: MurError) -> MurRes {
+	fn handle_error(&self, error: MurError, ctx: &MurRequestContext) -> MurRes {
 		for filter in &self.exception_filters {
 			if filter.can_handle(&error) {
-				let parts = http::Request::builder()
-					.uri("/")
-					.method("GET")
-					.body(())
-					.unwrap()
-					.into_parts()
-					.0;
-				let ctx = MurRequestContext::new(
-					parts,
-					None,
-					MurPathParams::new(),
-					Arc::clone(&self.container),
-				);
-				return filter.catch(error, &ctx);
+				return filter.catch(error, ctx);
 			}
 		}
 
@@ -568,6 +1210,33 @@ impl MurRouter {
 			return handler(error);
 		}
 
-		MurRes::from(error)
+		let format = ctx
+			.route_metadata::<MurErrorFormat>()
+			.copied()
+			.unwrap_or(self.error_format);
+		match format.resolve(ctx) {
+			MurErrorFormat::Problem => {
+				let mut problem = error.to_problem();
+				if problem.instance.is_none() {
+					problem.instance = Some(ctx.path().to_string());
+				}
+				MurRes::from(problem.into_response())
+			}
+			_ => MurRes::from(error),
+		}
+	}
+}
+
+/// What a request is routed by besides its method and path.
+struct MurRequestScope {
+	host: Option<String>,
+	version: Option<String>,
+}
+
+fn join_paths(prefix: &str, path: &str) -> String {
+	match (normalize_path(prefix).as_ref(), normalize_path(path).as_ref()) {
+		("/", path) => path.to_string(),
+		(prefix, "/") => prefix.to_string(),
+		(prefix, path) => format!("{}{}", prefix, path),
 	}
 }
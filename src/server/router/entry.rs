@@ -1,10 +1,13 @@
+use super::host::MurHostPattern;
+use super::metadata::MurRouteMetadata;
 use super::pattern::MurRoutePattern;
 use crate::server::aliases::MurRouteHandler;
 use crate::server::guard::MurGuard;
 use crate::server::interceptor::MurInterceptor;
-use std::collections::HashMap;
+use crate::server::middleware::rate_limit::MurRouteThrottle;
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Default, Clone)]
 pub struct MurRouteAccessControl {
@@ -14,22 +17,50 @@ pub struct MurRouteAccessControl {
 
 pub(crate) struct MurRouteEntry {
 	pub pattern: MurRoutePattern,
+	pub host: Option<MurHostPattern>,
 	pub handler: MurRouteHandler,
 	pub guards: Vec<Arc<dyn MurGuard + Send + Sync>>,
 	pub interceptors: Vec<Arc<dyn MurInterceptor + Send + Sync>>,
-	pub metadata: HashMap<String, String>,
+	pub metadata: Arc<MurRouteMetadata>,
 	pub access_control: MurRouteAccessControl,
+	pub throttle: Option<MurRouteThrottle>,
+	pub timeout: Option<Duration>,
+	pub versions: Vec<String>,
 }
 
 impl MurRouteEntry {
 	pub fn new(pattern: MurRoutePattern, handler: MurRouteHandler) -> Self {
 		Self {
 			pattern,
+			host: None,
 			handler,
 			guards: Vec::new(),
 			interceptors: Vec::new(),
-			metadata: HashMap::new(),
+			metadata: Arc::new(MurRouteMetadata::new()),
 			access_control: MurRouteAccessControl::default(),
+			throttle: None,
+			timeout: None,
+			versions: Vec::new(),
 		}
 	}
+
+	/// Whether the route serves requests sent to `host`. Routes without a
+	/// host pattern serve every host.
+	pub fn serves_host(&self, host: Option<&str>) -> bool {
+		match (&self.host, host) {
+			(None, _) => true,
+			(Some(pattern), Some(host)) => pattern.match_host(host).is_some(),
+			(Some(_), None) => false,
+		}
+	}
+
+	/// Host-bound routes are tried first, then versioned routes, then more
+	/// specific paths.
+	pub fn rank(&self) -> (bool, bool, i32) {
+		(
+			self.host.is_some(),
+			!self.versions.is_empty(),
+			self.pattern.specificity_score(),
+		)
+	}
 }
@@ -0,0 +1,64 @@
+use super::pattern::{is_under, normalize_path};
+use crate::server::aliases::MurRouteHandler;
+
+/// How the router treats a request path with a trailing slash, such as
+/// `/users/`, when routes are registered without one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MurTrailingSlash {
+	/// `/users/` is routed as `/users`.
+	#[default]
+	Lenient,
+	/// `/users/` is answered `404 Not Found`. Route patterns are registered
+	/// without their trailing slash, so no route matches such a path.
+	Strict,
+	/// `/users/` is answered `308 Permanent Redirect` to `/users` when that
+	/// path has a route, `404 Not Found` otherwise.
+	Redirect,
+}
+
+/// A not-found handler applied to the paths under `prefix`.
+#[derive(Clone)]
+pub(crate) struct MurFallback {
+	pub prefix: String,
+	pub handler: MurRouteHandler,
+}
+
+impl MurFallback {
+	pub fn new(prefix: &str, handler: MurRouteHandler) -> Self {
+		Self {
+			prefix: normalize_path(prefix).into_owned(),
+			handler,
+		}
+	}
+
+	/// Whether `path` is the prefix itself or lies below it.
+	pub fn covers(&self, path: &str) -> bool {
+		is_under(path, &self.prefix)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::server::http::MurHttpResponse;
+	use std::sync::Arc;
+
+	fn fallback(prefix: &str) -> MurFallback {
+		MurFallback::new(
+			prefix,
+			Arc::new(|_ctx| Box::pin(async { MurHttpResponse::ok().text("fallback") })),
+		)
+	}
+
+	#[test]
+	fn test_fallback_covers_prefix_segments() {
+		let api = fallback("/api/");
+
+		assert_eq!(api.prefix, "/api");
+		assert!(api.covers("/api"));
+		assert!(api.covers("/api/users/7"));
+		assert!(!api.covers("/apiary"));
+		assert!(!api.covers("/"));
+		assert!(fallback("/").covers("/anything"));
+	}
+}
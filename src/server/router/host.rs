@@ -0,0 +1,155 @@
+use crate::server::aliases::MurPathParams;
+use http::request::Parts;
+
+/// A `Host` pattern such as `:tenant.example.com` that restricts a
+/// controller to matching hosts.
+///
+/// Labels are literals, compared case-insensitively, parameters (`:tenant`)
+/// capturing one label, or wildcards (`*`) matching any one label. The port
+/// of the request's host is ignored.
+#[derive(Debug, Clone)]
+pub(crate) struct MurHostPattern {
+	pub pattern: String,
+	labels: Vec<MurHostLabel>,
+	error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum MurHostLabel {
+	Literal(String),
+	Param(String),
+	Wildcard,
+}
+
+/// The host parameters captured for the matched route, stored in the
+/// request extensions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MurHostParams(pub MurPathParams);
+
+impl MurHostPattern {
+	pub fn new(pattern: &str) -> Self {
+		let pattern = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+		let mut labels = Vec::new();
+		let mut error = None;
+
+		for label in pattern.split('.') {
+			let parsed = if label == "*" {
+				MurHostLabel::Wildcard
+			} else if let Some(name) = label.strip_prefix(':') {
+				if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+					error.get_or_insert_with(|| {
+						format!("Invalid parameter '{}' in host '{}'", label, pattern)
+					});
+				}
+				MurHostLabel::Param(name.to_string())
+			} else {
+				if label.is_empty() {
+					error.get_or_insert_with(|| format!("Empty label in host '{}'", pattern));
+				}
+				MurHostLabel::Literal(label.to_string())
+			};
+			labels.push(parsed);
+		}
+
+		Self {
+			pattern,
+			labels,
+			error,
+		}
+	}
+
+	/// The captured parameters when `host` matches, `None` otherwise.
+	pub fn match_host(&self, host: &str) -> Option<MurPathParams> {
+		let host = strip_port(host).trim_end_matches('.');
+		let mut params = MurPathParams::new();
+		let mut labels = host.split('.');
+
+		for expected in &self.labels {
+			let label = labels.next().filter(|label| !label.is_empty())?;
+			match expected {
+				MurHostLabel::Literal(literal) if !literal.eq_ignore_ascii_case(label) => {
+					return None;
+				}
+				MurHostLabel::Param(name) => {
+					params.insert(name.clone(), label.to_ascii_lowercase());
+				}
+				_ => {}
+			}
+		}
+
+		labels.next().is_none().then_some(params)
+	}
+
+	pub fn error(&self) -> Option<&str> {
+		self.error.as_deref()
+	}
+
+	/// The pattern with parameter names erased, so that `:a.example.com` and
+	/// `:b.example.com` compare equal.
+	pub fn shape(&self) -> String {
+		self.labels
+			.iter()
+			.map(|label| match label {
+				MurHostLabel::Literal(literal) => literal.as_str(),
+				MurHostLabel::Param(_) | MurHostLabel::Wildcard => "*",
+			})
+			.collect::<Vec<_>>()
+			.join(".")
+	}
+}
+
+/// The host a request was sent to: the `Host` header, or the URI authority
+/// for HTTP/2 requests.
+pub(crate) fn request_host(parts: &Parts) -> Option<&str> {
+	parts
+		.headers
+		.get(http::header::HOST)
+		.and_then(|host| host.to_str().ok())
+		.or_else(|| parts.uri.host())
+}
+
+/// `host` without its `:port`, keeping IPv6 literals bracketed.
+pub(crate) fn strip_port(host: &str) -> &str {
+	if host.starts_with('[') {
+		return host
+			.split_once(']')
+			.map_or(host, |(ip, _)| &host[..=ip.len()]);
+	}
+	host.rsplit_once(':').map_or(host, |(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_host_pattern_captures_labels() {
+		let pattern = MurHostPattern::new(":tenant.Example.com");
+
+		let params = pattern.match_host("Acme.example.com:8080").unwrap();
+		assert_eq!(params.get("tenant").map(String::as_str), Some("acme"));
+		assert!(pattern.match_host("example.com").is_none());
+		assert!(pattern.match_host("a.b.example.com").is_none());
+		assert!(pattern.match_host("acme.example.org").is_none());
+		assert!(
+			MurHostPattern::new("*.example.com")
+				.match_host("www.example.com")
+				.is_some()
+		);
+		assert!(
+			MurHostPattern::new("[::1]")
+				.match_host("[::1]:3000")
+				.is_some()
+		);
+	}
+
+	#[test]
+	fn test_host_pattern_errors_and_shape() {
+		assert!(MurHostPattern::new("api..example.com").error().is_some());
+		assert!(MurHostPattern::new(":.example.com").error().is_some());
+		assert_eq!(
+			MurHostPattern::new(":tenant.example.com").shape(),
+			MurHostPattern::new("*.example.com").shape()
+		);
+	}
+}
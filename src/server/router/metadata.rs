@@ -0,0 +1,191 @@
+use crate::server::decorator::MurDecorator;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Metadata attached to a route, readable from guards, interceptors and
+/// handlers through [`MurRequestContext`](crate::MurRequestContext).
+///
+/// Holds string values (`#[set_metadata(key = "value")]`), at most one typed
+/// value per type (`#[set_metadata(RequiredScope("admin"))]`), and the
+/// identity of the matched route: its pattern, method, controller and
+/// handler name.
+///
+/// ```rust,ignore
+/// impl MurGuard for ScopeGuard {
+///     fn check_can_activate<'a>(&'a self, ctx: &'a MurRequestContext) -> MurGuardFuture<'a> {
+///         let required = ctx.route_metadata::<RequiredScope>().map(|s| s.0);
+///         Box::pin(async move { required.is_none_or(|scope| has_scope(ctx, scope)) })
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct MurRouteMetadata {
+	values: HashMap<String, String>,
+	typed: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+	method: String,
+	pattern: String,
+	controller: String,
+	handler: String,
+}
+
+impl MurRouteMetadata {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets a string value, replacing any previous value for `key`.
+	pub fn set(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+		self.insert(key, value);
+		self
+	}
+
+	/// Sets a typed value, replacing any previous value of the same type.
+	pub fn typed<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+		self.insert_typed(value);
+		self
+	}
+
+	/// Applies a custom decorator: its string metadata is merged in and the
+	/// decorator itself is stored as a typed value.
+	pub fn decorate<D: MurDecorator>(mut self, decorator: D) -> Self {
+		for (key, value) in decorator.metadata() {
+			self.values.insert(key, value);
+		}
+		self.insert_typed(decorator);
+		self
+	}
+
+	/// Sets the name of the handler method. Filled in by `#[controller]`.
+	pub fn handler(mut self, name: impl Into<String>) -> Self {
+		self.handler = name.into();
+		self
+	}
+
+	pub fn insert(&mut self, key: impl Into<String>, value: impl ToString) {
+		self.values.insert(key.into(), value.to_string());
+	}
+
+	pub fn insert_typed<T: Send + Sync + 'static>(&mut self, value: T) {
+		self.typed.insert(TypeId::of::<T>(), Arc::new(value));
+	}
+
+	pub fn get(&self, key: &str) -> Option<&str> {
+		self.values.get(key).map(|value| value.as_str())
+	}
+
+	pub fn get_typed<T: 'static>(&self) -> Option<&T> {
+		self
+			.typed
+			.get(&TypeId::of::<T>())
+			.and_then(|value| value.downcast_ref::<T>())
+	}
+
+	pub fn contains(&self, key: &str) -> bool {
+		self.values.contains_key(key)
+	}
+
+	pub fn contains_typed<T: 'static>(&self) -> bool {
+		self.typed.contains_key(&TypeId::of::<T>())
+	}
+
+	pub fn values(&self) -> &HashMap<String, String> {
+		&self.values
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty() && self.typed.is_empty()
+	}
+
+	/// The HTTP method of the route.
+	pub fn method(&self) -> &str {
+		&self.method
+	}
+
+	/// The route pattern as registered, e.g. `/users/:id`.
+	pub fn pattern(&self) -> &str {
+		&self.pattern
+	}
+
+	/// The controller's name, or `"manual"` for routes added on the router.
+	pub fn controller(&self) -> &str {
+		&self.controller
+	}
+
+	/// The handler method's name; empty for manual routes.
+	pub fn handler_name(&self) -> &str {
+		&self.handler
+	}
+
+	pub(crate) fn bind(mut self, method: &str, pattern: &str, controller: &str) -> Self {
+		self.method = method.to_string();
+		self.pattern = pattern.to_string();
+		self.controller = controller.to_string();
+		self
+	}
+}
+
+impl std::fmt::Debug for MurRouteMetadata {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurRouteMetadata")
+			.field("method", &self.method)
+			.field("pattern", &self.pattern)
+			.field("controller", &self.controller)
+			.field("handler", &self.handler)
+			.field("values", &self.values)
+			.field("typed", &self.typed.len())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Scope(&'static str);
+
+	struct Flag;
+
+	impl MurDecorator for Flag {
+		fn metadata(&self) -> HashMap<String, String> {
+			HashMap::from([("feature".to_string(), "beta".to_string())])
+		}
+	}
+
+	#[test]
+	fn test_string_and_typed_values() {
+		let metadata = MurRouteMetadata::new()
+			.set("area", "reports")
+			.set("area", "labs")
+			.set("limit", 5)
+			.typed(Scope("read"));
+
+		assert_eq!(metadata.get("area"), Some("labs"));
+		assert_eq!(metadata.get("limit"), Some("5"));
+		assert_eq!(metadata.get_typed::<Scope>().map(|s| s.0), Some("read"));
+		assert!(metadata.get_typed::<Flag>().is_none());
+		assert!(!metadata.is_empty());
+	}
+
+	#[test]
+	fn test_decorate_merges_metadata() {
+		let metadata = MurRouteMetadata::new().decorate(Flag);
+
+		assert_eq!(metadata.get("feature"), Some("beta"));
+		assert!(metadata.contains_typed::<Flag>());
+	}
+
+	#[test]
+	fn test_bind_sets_route_identity() {
+		let metadata =
+			MurRouteMetadata::new()
+				.handler("find")
+				.bind("GET", "/users/:id", "UserController");
+
+		assert_eq!(metadata.method(), "GET");
+		assert_eq!(metadata.pattern(), "/users/:id");
+		assert_eq!(metadata.controller(), "UserController");
+		assert_eq!(metadata.handler_name(), "find");
+		assert!(metadata.is_empty());
+	}
+}
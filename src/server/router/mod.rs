@@ -1,13 +1,24 @@
 pub mod builder;
 pub mod core;
 pub mod entry;
+mod fallback;
+mod host;
+mod metadata;
 pub mod open_api;
 pub mod pattern;
 mod types;
+mod versioning;
 
 pub use builder::MurRouteBuilder;
 pub use core::MurRouter;
 pub use entry::MurRouteAccessControl;
+pub use fallback::MurTrailingSlash;
+pub(crate) use host::{MurHostParams, request_host, strip_port};
+pub use metadata::MurRouteMetadata;
 pub use pattern::MurRoutePattern;
 pub use types::MurRouteDefinition;
 pub use types::MurRouteInfo;
+pub(crate) use versioning::MurApiVersion;
+pub use versioning::{
+	MurVersionDeprecation, MurVersionExtractor, MurVersioning, MurVersioningStrategy,
+};
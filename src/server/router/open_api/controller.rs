@@ -5,6 +5,7 @@ use crate::server::controller::MurController;
 use crate::server::http::MurHttpResponse;
 use crate::server::http::MurRequestContext;
 use crate::server::router::MurRouteDefinition;
+use crate::server::router::MurRouteMetadata;
 use crate::server::service::MurServiceContainer;
 use std::sync::Arc;
 
@@ -58,6 +59,12 @@ impl MurController for MurOpenApiController {
 				},
 				is_public: true,
 				allowed_roles: vec![],
+				throttle: None,
+				timeout: None,
+				metadata: MurRouteMetadata::new(),
+				guards: vec![],
+				interceptors: vec![],
+				versions: vec![],
 			},
 			MurRouteDefinition {
 				method: "GET".to_string(),
@@ -77,6 +84,12 @@ impl MurController for MurOpenApiController {
 				},
 				is_public: true,
 				allowed_roles: vec![],
+				throttle: None,
+				timeout: None,
+				metadata: MurRouteMetadata::new(),
+				guards: vec![],
+				interceptors: vec![],
+				versions: vec![],
 			},
 		]
 	}
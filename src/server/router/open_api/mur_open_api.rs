@@ -1,9 +1,11 @@
 use super::{
 	components::MurApiComponents, contact::MurApiContact, external_doc::MurApiExternalDocs,
-	info::MurApiInfo, license::MurApiLicense, path_item::MurApiPathItem, schema::MurApiSchema,
-	security_scheme::MurApiSecurityScheme, server::MurApiServer, spec::MurOpenApiSpec,
-	tag::MurApiTag,
+	info::MurApiInfo, license::MurApiLicense, operation::MurApiOperation,
+	path_item::MurApiPathItem, schema::MurApiSchema, security_scheme::MurApiSecurityScheme,
+	server::MurApiServer, spec::MurOpenApiSpec, tag::MurApiTag,
 };
+use crate::server::router::{MurRouteInfo, MurRoutePattern};
+use indexmap::IndexMap;
 use std::collections::HashMap;
 
 pub struct MurOpenApi {
@@ -157,6 +159,40 @@ impl MurOpenApi {
 		self
 	}
 
+	/// Describes the routes of a route table that the spec does not cover
+	/// yet. A route served in several API versions lists them in
+	/// `x-api-versions`, and is deprecated when all of them are.
+	pub fn routes(mut self, routes: &[MurRouteInfo]) -> Self {
+		let mut generated: IndexMap<(String, String), MurApiOperation> = IndexMap::new();
+
+		for route in routes {
+			let key = (open_api_path(&route.path), route.method.clone());
+			match generated.get_mut(&key) {
+				Some(operation) => {
+					for version in &route.versions {
+						if !operation.api_versions.contains(version) {
+							operation.api_versions.push(version.clone());
+						}
+					}
+					if !route.deprecated {
+						operation.deprecated = None;
+					}
+				}
+				None => {
+					generated.insert(key, route_operation(route));
+				}
+			}
+		}
+
+		for ((path, method), operation) in generated {
+			let item = self.spec.paths.entry(path).or_default();
+			if let Some(slot @ None) = item.operation_mut(&method) {
+				*slot = Some(operation);
+			}
+		}
+		self
+	}
+
 	pub fn build(self) -> MurOpenApiSpec {
 		self.spec
 	}
@@ -169,3 +205,72 @@ impl MurOpenApi {
 		self.spec.to_json_compact()
 	}
 }
+
+fn route_operation(route: &MurRouteInfo) -> MurApiOperation {
+	let summary = if route.handler.is_empty() {
+		format!("{} {}", route.method, route.path)
+	} else {
+		route.handler.clone()
+	};
+	let mut operation = MurApiOperation::new(summary);
+
+	let controller = route.controller.rsplit("::").next().unwrap_or_default();
+	if !controller.is_empty() && controller != "manual" {
+		operation = operation.tag(controller);
+	}
+	for name in MurRoutePattern::new(&route.path).param_names() {
+		operation = operation.path_param(name, "");
+	}
+	for version in &route.versions {
+		operation = operation.api_version(version);
+	}
+	if route.deprecated {
+		operation = operation.deprecated();
+	}
+	operation
+}
+
+/// Rewrites route parameters as OpenAPI templates, dropping constraints:
+/// `/users/:id(\d+)` becomes `/users/{id}`.
+fn open_api_path(path: &str) -> String {
+	let mut out = String::with_capacity(path.len());
+	let mut chars = path.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		if c != ':' && !(c == '*' && chars.peek().is_some_and(|n| n.is_alphanumeric())) {
+			out.push(c);
+			continue;
+		}
+
+		let mut name = String::new();
+		while let Some(&n) = chars.peek().filter(|n| n.is_alphanumeric() || **n == '_') {
+			name.push(n);
+			chars.next();
+		}
+		if chars.peek() == Some(&'(') {
+			let mut depth = 0;
+			while let Some(n) = chars.next() {
+				match n {
+					'\\' => {
+						chars.next();
+					}
+					'(' => depth += 1,
+					')' => {
+						depth -= 1;
+						if depth == 0 {
+							break;
+						}
+					}
+					_ => {}
+				}
+			}
+		}
+		if chars.peek() == Some(&'?') {
+			chars.next();
+		}
+		out.push('{');
+		out.push_str(&name);
+		out.push('}');
+	}
+	out
+}
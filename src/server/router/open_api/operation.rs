@@ -28,6 +28,13 @@ pub struct MurApiOperation {
 	pub deprecated: Option<bool>,
 	#[serde(rename = "externalDocs", skip_serializing_if = "Option::is_none")]
 	pub external_docs: Option<MurApiExternalDocs>,
+	/// The API versions serving the operation.
+	#[serde(
+		rename = "x-api-versions",
+		skip_serializing_if = "Vec::is_empty",
+		default
+	)]
+	pub api_versions: Vec<String>,
 }
 
 impl MurApiOperation {
@@ -136,4 +143,12 @@ impl MurApiOperation {
 		self.deprecated = Some(true);
 		self
 	}
+
+	pub fn api_version(mut self, version: impl Into<String>) -> Self {
+		let version = version.into();
+		if !self.api_versions.contains(&version) {
+			self.api_versions.push(version);
+		}
+		self
+	}
 }
@@ -55,6 +55,20 @@ impl MurApiPathItem {
 		self
 	}
 
+	/// The operation slot for an HTTP method, such as `get` or `POST`.
+	pub fn operation_mut(&mut self, method: &str) -> Option<&mut Option<MurApiOperation>> {
+		match method.to_ascii_lowercase().as_str() {
+			"get" => Some(&mut self.get),
+			"post" => Some(&mut self.post),
+			"put" => Some(&mut self.put),
+			"delete" => Some(&mut self.delete),
+			"patch" => Some(&mut self.patch),
+			"head" => Some(&mut self.head),
+			"options" => Some(&mut self.options),
+			_ => None,
+		}
+	}
+
 	pub fn parameter(mut self, param: MurApiParameter) -> Self {
 		self.parameters.push(param);
 		self
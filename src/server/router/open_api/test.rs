@@ -1,4 +1,5 @@
 use super::{mur_open_api::MurOpenApi, operation::MurApiOperation, schema::MurApiSchema};
+use crate::server::router::MurRouteInfo;
 
 #[test]
 fn test_schema_string() {
@@ -64,3 +65,40 @@ fn test_to_json() {
 	assert!(json.is_ok());
 	assert!(json.unwrap().contains("\"openapi\": \"3.0.3\""));
 }
+
+fn route(method: &str, path: &str, versions: &[&str], deprecated: bool) -> MurRouteInfo {
+	MurRouteInfo {
+		method: method.to_string(),
+		path: path.to_string(),
+		controller: "app::UserController".to_string(),
+		handler: "find".to_string(),
+		versions: versions.iter().map(|v| v.to_string()).collect(),
+		deprecated,
+	}
+}
+
+#[test]
+fn test_routes_describe_the_route_table() {
+	let spec = MurOpenApi::new("Test", "1.0.0")
+		.path("/users", |p| p.get(MurApiOperation::new("Hand written")))
+		.routes(&[
+			route("GET", "/users", &[], false),
+			route("GET", r"/users/:id(\d+)", &["1"], true),
+			route("GET", r"/users/:id(\d+)", &["2"], false),
+			route("DELETE", "/v1/users/:id", &["1"], true),
+		])
+		.build();
+
+	let listed = spec.paths["/users"].get.as_ref().unwrap();
+	assert_eq!(listed.summary.as_deref(), Some("Hand written"));
+
+	let find = spec.paths["/users/{id}"].get.as_ref().unwrap();
+	assert_eq!(find.api_versions, vec!["1", "2"]);
+	assert_eq!(find.deprecated, None);
+	assert_eq!(find.tags, vec!["UserController"]);
+	assert_eq!(find.parameters.len(), 1);
+
+	let delete = spec.paths["/v1/users/{id}"].delete.as_ref().unwrap();
+	assert_eq!(delete.deprecated, Some(true));
+	assert!(spec.to_json().unwrap().contains("x-api-versions"));
+}
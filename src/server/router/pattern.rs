@@ -1,6 +1,18 @@
+use regex::Regex;
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+/// A route path such as `/users/:id(\d+)/files/:name.:ext`.
+///
+/// Segments are literals, parameters (`:id`), parameters constrained by a
+/// regex or a named type (`:id(\d+)`, `:id(uuid)`), optional trailing
+/// parameters (`:page?`), segments mixing literals and parameters
+/// (`:name.:ext`, `v:version(uint)`), wildcards (`*`) and catch-alls
+/// (`**`, `*rest`).
+///
+/// A path whose segment fails a constraint does not match, so the router
+/// tries the next candidate route. Malformed patterns are reported by
+/// [`error`](Self::error) and rejected when the server binds.
 #[derive(Debug, Clone)]
 pub struct MurRoutePattern {
 	pub pattern: String,
@@ -9,16 +21,45 @@ pub struct MurRoutePattern {
 	segment_count: usize,
 	is_static: bool,
 	specificity: i32,
+	error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum MurPatternSegment {
 	Literal(Box<str>),
 	Param(Box<str>),
+	/// A single parameter whose value must match `regex`.
+	Constrained(Box<str>, Regex),
+	/// Literals and parameters sharing a segment; `regex` captures the
+	/// parameters as `p0`, `p1`, … in the order of `names`.
+	Composite(Regex, Vec<Box<str>>),
+	/// A trailing parameter that may be absent.
+	Optional(Box<str>, Option<Regex>),
 	Wildcard,
 	CatchAll(Option<Box<str>>),
 }
 
+/// Regex sources for the named constraints, e.g. `:id(uuid)`.
+const NAMED_CONSTRAINTS: &[(&str, &str)] = &[
+	("int", r"-?\d+"),
+	("uint", r"\d+"),
+	(
+		"uuid",
+		r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
+	),
+	("alpha", r"[A-Za-z]+"),
+	("alnum", r"[A-Za-z0-9]+"),
+	("slug", r"[a-z0-9]+(?:-[a-z0-9]+)*"),
+];
+
+const ANY_SEGMENT: &str = "[^/]+";
+
+/// One `:name(constraint)` or literal run inside a segment.
+enum SegmentPart {
+	Literal(String),
+	Param(String, Option<String>),
+}
+
 impl MurRoutePattern {
 	pub fn new(pattern: &str) -> Self {
 		let pattern = normalize_path(pattern).into_owned();
@@ -26,32 +67,40 @@ impl MurRoutePattern {
 		let mut param_names = Vec::with_capacity(4);
 		let mut is_static = true;
 		let mut specificity = 0i32;
+		let mut error = None;
 
 		for segment in pattern.split('/').filter(|s| !s.is_empty()) {
-			if let Some(param_name) = segment.strip_prefix(':') {
-				param_names.push(param_name.to_string());
-				segments.push(MurPatternSegment::Param(param_name.into()));
-				is_static = false;
-				specificity += 10;
-			} else if segment == "*" {
-				segments.push(MurPatternSegment::Wildcard);
-				is_static = false;
-				specificity += 1;
+			let parsed = if segment == "*" {
+				Ok((MurPatternSegment::Wildcard, 1))
 			} else if segment == "**" {
-				segments.push(MurPatternSegment::CatchAll(None));
-				is_static = false;
-				specificity -= 100;
-			} else if let Some(param_name) = segment.strip_prefix('*') {
+				Ok((MurPatternSegment::CatchAll(None), -100))
+			} else if let Some(param_name) =
+				segment.strip_prefix('*').filter(|name| is_param_name(name))
+			{
 				param_names.push(param_name.to_string());
-				segments.push(MurPatternSegment::CatchAll(Some(param_name.into())));
-				is_static = false;
-				specificity -= 100;
+				Ok((MurPatternSegment::CatchAll(Some(param_name.into())), -100))
 			} else {
-				segments.push(MurPatternSegment::Literal(segment.into()));
-				specificity += 100;
+				parse_segment(segment, &mut param_names)
+			};
+
+			match parsed {
+				Ok((segment, score)) => {
+					is_static &= matches!(segment, MurPatternSegment::Literal(_));
+					specificity += score;
+					segments.push(segment);
+				}
+				Err(e) => {
+					error.get_or_insert(format!("Invalid route '{}': {}", pattern, e));
+					is_static = false;
+				}
 			}
 		}
 
+		if error.is_none() {
+			error = check_layout(&segments, &param_names)
+				.map(|e| format!("Invalid route '{}': {}", pattern, e));
+		}
+
 		let segment_count = segments.len();
 
 		Self {
@@ -61,6 +110,7 @@ impl MurRoutePattern {
 			segment_count,
 			is_static,
 			specificity,
+			error,
 		}
 	}
 
@@ -78,6 +128,10 @@ impl MurRoutePattern {
 
 	#[inline(never)]
 	fn match_path_dynamic(&self, path: &str) -> Option<HashMap<String, String>> {
+		if self.error.is_some() {
+			return None;
+		}
+
 		let normalized = normalize_path(path);
 		let mut params = HashMap::with_capacity(self.param_names.len());
 		let mut path_iter = normalized.split('/').filter(|s| !s.is_empty());
@@ -95,6 +149,27 @@ impl MurRoutePattern {
 					}
 					None => return None,
 				},
+				MurPatternSegment::Constrained(name, regex) => match path_iter.next() {
+					Some(segment) if regex.is_match(segment) => {
+						params.insert(name.to_string(), segment.to_string());
+					}
+					_ => return None,
+				},
+				MurPatternSegment::Composite(regex, names) => {
+					let captures = regex.captures(path_iter.next()?)?;
+					for (i, name) in names.iter().enumerate() {
+						let value = captures.name(&format!("p{}", i))?;
+						params.insert(name.to_string(), value.as_str().to_string());
+					}
+				}
+				MurPatternSegment::Optional(name, regex) => match path_iter.next() {
+					Some(segment) if regex.as_ref().is_none_or(|r| r.is_match(segment)) => {
+						params.insert(name.to_string(), segment.to_string());
+					}
+					Some(_) => return None,
+					// Only optional segments follow.
+					None => return Some(params),
+				},
 				MurPatternSegment::Wildcard => {
 					path_iter.next()?;
 				}
@@ -144,6 +219,237 @@ impl MurRoutePattern {
 	pub fn is_static(&self) -> bool {
 		self.is_static
 	}
+
+	/// Why the pattern is malformed, if it is. Malformed patterns match nothing.
+	pub fn error(&self) -> Option<&str> {
+		self.error.as_deref()
+	}
+
+	/// The shapes of the paths this pattern matches, ignoring parameter
+	/// names: one per number of optional segments present. Two routes of
+	/// the same method sharing a shape match the same paths.
+	pub(crate) fn shapes(&self) -> Vec<String> {
+		let mut shapes = Vec::new();
+		let mut shape = String::new();
+
+		for segment in &self.segments {
+			let part = match segment {
+				MurPatternSegment::Literal(literal) => regex::escape(literal),
+				MurPatternSegment::Param(_) | MurPatternSegment::Wildcard => any_segment_shape(),
+				MurPatternSegment::Constrained(_, regex) => regex.as_str().to_string(),
+				MurPatternSegment::Composite(regex, _) => strip_group_names(regex.as_str()),
+				MurPatternSegment::Optional(_, regex) => {
+					shapes.push(shape.clone());
+					regex
+						.as_ref()
+						.map_or_else(any_segment_shape, |r| r.as_str().to_string())
+				}
+				MurPatternSegment::CatchAll(_) => "**".to_string(),
+			};
+			shape.push('/');
+			shape.push_str(&part);
+		}
+
+		shapes.push(shape);
+		shapes
+	}
+}
+
+fn is_param_name(name: &str) -> bool {
+	!name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parses a segment that is not a wildcard or catch-all, returning it with
+/// its specificity.
+fn parse_segment(
+	segment: &str,
+	param_names: &mut Vec<String>,
+) -> Result<(MurPatternSegment, i32), String> {
+	let (body, optional) = match segment.strip_suffix('?') {
+		Some(body) if body.starts_with(':') => (body, true),
+		_ => (segment, false),
+	};
+	let parts = split_segment(body)?;
+
+	for part in &parts {
+		if let SegmentPart::Param(name, _) = part {
+			param_names.push(name.clone());
+		}
+	}
+
+	match parts.as_slice() {
+		[SegmentPart::Literal(literal)] => {
+			Ok((MurPatternSegment::Literal(literal.as_str().into()), 100))
+		}
+		[SegmentPart::Param(name, constraint)] if optional => {
+			let regex = constraint.as_deref().map(anchored).transpose()?;
+			Ok((MurPatternSegment::Optional(name.as_str().into(), regex), 5))
+		}
+		[SegmentPart::Param(name, None)] => {
+			Ok((MurPatternSegment::Param(name.as_str().into()), 10))
+		}
+		[SegmentPart::Param(name, Some(constraint))] => Ok((
+			MurPatternSegment::Constrained(name.as_str().into(), anchored(constraint)?),
+			50,
+		)),
+		_ if optional => Err(format!(
+			"optional parameters must be a whole segment, found '{}'",
+			segment
+		)),
+		_ => {
+			let mut source = String::from("^");
+			let mut names = Vec::new();
+			for part in &parts {
+				match part {
+					SegmentPart::Literal(literal) => source.push_str(&regex::escape(literal)),
+					SegmentPart::Param(name, constraint) => {
+						let constraint = constraint.as_deref().unwrap_or(ANY_SEGMENT);
+						source.push_str(&format!("(?P<p{}>{})", names.len(), constraint));
+						names.push(Box::from(name.as_str()));
+					}
+				}
+			}
+			source.push('$');
+			let regex = Regex::new(&source).map_err(|e| e.to_string())?;
+			Ok((MurPatternSegment::Composite(regex, names), 50))
+		}
+	}
+}
+
+/// Splits `v:version(\d+).:ext` into literal runs and `:name(constraint)`
+/// parameters, resolving named constraints.
+fn split_segment(segment: &str) -> Result<Vec<SegmentPart>, String> {
+	let mut parts = Vec::new();
+	let mut literal = String::new();
+	let mut chars = segment.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		if c != ':' {
+			literal.push(c);
+			continue;
+		}
+
+		let mut name = String::new();
+		while let Some(&c) = chars.peek() {
+			if !(c.is_ascii_alphanumeric() || c == '_') {
+				break;
+			}
+			name.push(c);
+			chars.next();
+		}
+		if name.is_empty() {
+			return Err(format!("missing parameter name in '{}'", segment));
+		}
+
+		let mut constraint = None;
+		if chars.peek() == Some(&'(') {
+			chars.next();
+			let mut depth = 1;
+			let mut source = String::new();
+			while depth > 0 {
+				let Some(c) = chars.next() else {
+					return Err(format!("unclosed constraint for ':{}'", name));
+				};
+				match c {
+					'\\' => {
+						source.push(c);
+						if let Some(escaped) = chars.next() {
+							source.push(escaped);
+						}
+						continue;
+					}
+					'(' => depth += 1,
+					')' => depth -= 1,
+					_ => {}
+				}
+				if depth > 0 {
+					source.push(c);
+				}
+			}
+			constraint = Some(resolve_constraint(source));
+		}
+
+		if !literal.is_empty() {
+			parts.push(SegmentPart::Literal(std::mem::take(&mut literal)));
+		}
+		if matches!(parts.last(), Some(SegmentPart::Param(..))) {
+			return Err(format!(
+				"parameters ':{}' and its predecessor need a literal between them",
+				name
+			));
+		}
+		parts.push(SegmentPart::Param(name, constraint));
+	}
+
+	if !literal.is_empty() {
+		parts.push(SegmentPart::Literal(literal));
+	}
+	Ok(parts)
+}
+
+fn resolve_constraint(source: String) -> String {
+	NAMED_CONSTRAINTS
+		.iter()
+		.find(|(name, _)| *name == source)
+		.map_or(source, |(_, regex)| regex.to_string())
+}
+
+fn any_segment_shape() -> String {
+	format!("^(?:{})$", ANY_SEGMENT)
+}
+
+fn anchored(constraint: &str) -> Result<Regex, String> {
+	Regex::new(&format!("^(?:{})$", constraint)).map_err(|e| e.to_string())
+}
+
+/// `^(?P<p0>[^/]+)\.(?P<p1>[^/]+)$` → `^([^/]+)\.([^/]+)$`.
+fn strip_group_names(source: &str) -> String {
+	let mut out = String::with_capacity(source.len());
+	let mut rest = source;
+	while let Some(at) = rest.find("(?P<p") {
+		out.push_str(&rest[..at + 1]);
+		rest = &rest[at..];
+		let close = rest.find('>').map_or(rest.len(), |i| i + 1);
+		rest = &rest[close..];
+	}
+	out.push_str(rest);
+	out
+}
+
+/// Checks what a single segment cannot: unique names, catch-alls last and
+/// optional parameters trailing.
+fn check_layout(segments: &[MurPatternSegment], param_names: &[String]) -> Option<String> {
+	for (i, name) in param_names.iter().enumerate() {
+		if param_names[..i].contains(name) {
+			return Some(format!("parameter ':{}' is declared twice", name));
+		}
+	}
+
+	let last = segments.len().saturating_sub(1);
+	let mut optional_seen = false;
+	for (i, segment) in segments.iter().enumerate() {
+		match segment {
+			MurPatternSegment::CatchAll(_) if i != last => {
+				return Some("catch-all segments must come last".to_string());
+			}
+			MurPatternSegment::Optional(..) => optional_seen = true,
+			_ if optional_seen => {
+				return Some("optional parameters must come last".to_string());
+			}
+			_ => {}
+		}
+	}
+	None
+}
+
+/// Whether `path` is `base` or lies below it, segment-wise: `/api` covers
+/// `/api/users` but not `/apiary`.
+pub(crate) fn is_under(path: &str, base: &str) -> bool {
+	let base = normalize_path(base);
+	base == "/"
+		|| path
+			.strip_prefix(base.as_ref())
+			.is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
 }
 
 pub(crate) fn normalize_path(path: &str) -> Cow<'_, str> {
@@ -233,6 +539,87 @@ mod tests {
 		assert!(wildcard.specificity_score() > catch_all.specificity_score());
 	}
 
+	#[test]
+	fn test_pattern_constraints() {
+		let numeric = MurRoutePattern::new(r"/users/:id(\d+)");
+		assert_eq!(numeric.match_path("/users/42").unwrap()["id"], "42");
+		assert!(numeric.match_path("/users/me").is_none());
+		assert!(numeric.match_path("/users/42x").is_none());
+
+		let uuid = MurRoutePattern::new("/orders/:order(uuid)");
+		assert!(
+			uuid.match_path("/orders/6f9619ff-8b86-4011-b42d-00c04fc964ff")
+				.is_some()
+		);
+		assert!(uuid.match_path("/orders/42").is_none());
+
+		let nested = MurRoutePattern::new("/tags/:tag([a-z]+(-[a-z]+)*)");
+		assert!(nested.match_path("/tags/rust-web").is_some());
+		assert!(nested.match_path("/tags/Rust").is_none());
+
+		assert!(
+			numeric.specificity_score() > MurRoutePattern::new("/users/:id").specificity_score()
+		);
+	}
+
+	#[test]
+	fn test_pattern_optional_segment() {
+		let pattern = MurRoutePattern::new(r"/posts/:page(\d+)?");
+		assert_eq!(pattern.match_path("/posts/3").unwrap()["page"], "3");
+		assert!(!pattern.match_path("/posts").unwrap().contains_key("page"));
+		assert!(pattern.match_path("/posts/last").is_none());
+		assert!(pattern.match_path("/posts/3/4").is_none());
+	}
+
+	#[test]
+	fn test_pattern_composite_segment() {
+		let file = MurRoutePattern::new("/files/:name.:ext");
+		let params = file.match_path("/files/archive.tar.gz").unwrap();
+		assert_eq!(params["name"], "archive.tar");
+		assert_eq!(params["ext"], "gz");
+		assert!(file.match_path("/files/README").is_none());
+
+		let version = MurRoutePattern::new("/api/v:version(uint)/health");
+		assert_eq!(
+			version.match_path("/api/v2/health").unwrap()["version"],
+			"2"
+		);
+		assert!(version.match_path("/api/vx/health").is_none());
+		assert_eq!(version.param_names(), ["version"]);
+	}
+
+	#[test]
+	fn test_pattern_errors() {
+		assert!(MurRoutePattern::new("/users/:id").error().is_none());
+		assert!(MurRoutePattern::new("/users/:id(").error().is_some());
+		assert!(MurRoutePattern::new(r"/users/:id([)").error().is_some());
+		assert!(MurRoutePattern::new("/users/:id/:id").error().is_some());
+		assert!(
+			MurRoutePattern::new("/users/:page?/posts")
+				.error()
+				.is_some()
+		);
+		assert!(MurRoutePattern::new("/files/:a:b").error().is_some());
+		assert!(MurRoutePattern::new("/files/**/x").error().is_some());
+		assert!(
+			MurRoutePattern::new("/users/:id(")
+				.match_path("/users/(")
+				.is_none()
+		);
+	}
+
+	#[test]
+	fn test_pattern_shapes() {
+		let shapes = |p: &str| MurRoutePattern::new(p).shapes();
+
+		assert_eq!(shapes("/users/:id"), shapes("/users/:name"));
+		assert_eq!(shapes("/users/:id"), shapes("/users/*"));
+		assert_eq!(shapes(r"/users/:id(\d+)"), shapes(r"/users/:n(\d+)"));
+		assert_ne!(shapes(r"/users/:id(\d+)"), shapes("/users/:id"));
+		assert_eq!(shapes("/f/:a.:b"), shapes("/f/:x.:y"));
+		assert!(shapes("/posts/:page?").contains(&shapes("/posts")[0]));
+	}
+
 	#[test]
 	fn test_static_route_optimization() {
 		let pattern = MurRoutePattern::new("/static/route");
@@ -1,4 +1,9 @@
+use super::metadata::MurRouteMetadata;
 use crate::server::aliases::MurRouteHandler;
+use crate::server::guard::MurGuardProvider;
+use crate::server::interceptor::MurInterceptorProvider;
+use crate::server::middleware::rate_limit::MurRouteThrottle;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct MurRouteInfo {
@@ -6,6 +11,10 @@ pub struct MurRouteInfo {
 	pub path: String,
 	pub controller: String,
 	pub handler: String,
+	/// The API versions the route serves; empty when version-neutral.
+	pub versions: Vec<String>,
+	/// Whether every version the route serves is deprecated.
+	pub deprecated: bool,
 }
 
 #[derive(Clone)]
@@ -15,4 +24,13 @@ pub struct MurRouteDefinition {
 	pub handler: MurRouteHandler,
 	pub is_public: bool,
 	pub allowed_roles: Vec<String>,
+	pub throttle: Option<MurRouteThrottle>,
+	pub timeout: Option<Duration>,
+	pub metadata: MurRouteMetadata,
+	/// Route guards, run after the global guards in order.
+	pub guards: Vec<MurGuardProvider>,
+	/// Route interceptors, nested inside the global interceptors in order.
+	pub interceptors: Vec<MurInterceptorProvider>,
+	/// The API versions the route serves; empty when version-neutral.
+	pub versions: Vec<String>,
 }
@@ -0,0 +1,269 @@
+use crate::server::http::MurRequestContext;
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+use http::HeaderValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Reads the requested API version from a request.
+pub type MurVersionExtractor = Arc<dyn Fn(&MurRequestContext) -> Option<String> + Send + Sync>;
+
+/// Where a request carries the API version it asks for.
+#[derive(Clone)]
+pub enum MurVersioningStrategy {
+	/// A leading path segment such as `/v2/users`, `prefix` being `v`.
+	Uri { prefix: String },
+	/// A request header such as `X-API-Version: 2`.
+	Header(String),
+	/// A media type parameter of the `Accept` header, such as
+	/// `Accept: application/json;v=2`.
+	MediaType { key: String },
+	/// A query parameter such as `?version=2`.
+	Query(String),
+	/// A custom extractor.
+	Custom(MurVersionExtractor),
+}
+
+impl std::fmt::Debug for MurVersioningStrategy {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Uri { prefix } => f.debug_struct("Uri").field("prefix", prefix).finish(),
+			Self::Header(name) => f.debug_tuple("Header").field(name).finish(),
+			Self::MediaType { key } => f.debug_struct("MediaType").field("key", key).finish(),
+			Self::Query(name) => f.debug_tuple("Query").field(name).finish(),
+			Self::Custom(_) => f.write_str("Custom"),
+		}
+	}
+}
+
+/// How routes declared with `#[controller(version = "2")]` or
+/// `#[get("/users", version = ["1", "2"])]` are selected.
+///
+/// Routes without a version are version-neutral: they serve every version,
+/// and requests that carry none.
+///
+/// ```rust,ignore
+/// MurServer::new().versioning(
+///     MurVersioning::header("X-API-Version")
+///         .default_version("2")
+///         .deprecate("1", MurVersionDeprecation::new().sunset(sunset)),
+/// )
+/// ```
+#[derive(Debug, Clone)]
+pub struct MurVersioning {
+	strategy: MurVersioningStrategy,
+	default_version: Option<String>,
+	deprecations: HashMap<String, MurVersionDeprecation>,
+}
+
+impl MurVersioning {
+	pub fn new(strategy: MurVersioningStrategy) -> Self {
+		Self {
+			strategy,
+			default_version: None,
+			deprecations: HashMap::new(),
+		}
+	}
+
+	/// Versions routes by a `/v{version}` path segment, placed after the
+	/// global prefix and before module prefixes.
+	pub fn uri() -> Self {
+		Self::uri_with_prefix("v")
+	}
+
+	pub fn uri_with_prefix(prefix: impl Into<String>) -> Self {
+		Self::new(MurVersioningStrategy::Uri {
+			prefix: prefix.into(),
+		})
+	}
+
+	pub fn header(name: impl Into<String>) -> Self {
+		Self::new(MurVersioningStrategy::Header(name.into()))
+	}
+
+	/// Versions routes by the `v` parameter of the `Accept` header.
+	pub fn media_type() -> Self {
+		Self::media_type_key("v")
+	}
+
+	pub fn media_type_key(key: impl Into<String>) -> Self {
+		Self::new(MurVersioningStrategy::MediaType { key: key.into() })
+	}
+
+	pub fn query(name: impl Into<String>) -> Self {
+		Self::new(MurVersioningStrategy::Query(name.into()))
+	}
+
+	pub fn custom<F>(extract: F) -> Self
+	where
+		F: Fn(&MurRequestContext) -> Option<String> + Send + Sync + 'static,
+	{
+		Self::new(MurVersioningStrategy::Custom(Arc::new(extract)))
+	}
+
+	/// The version served to requests that do not ask for one. With URI
+	/// versioning, its routes are also registered without the version
+	/// segment.
+	pub fn default_version(mut self, version: impl Into<String>) -> Self {
+		self.default_version = Some(version.into());
+		self
+	}
+
+	/// Marks `version` as deprecated: its responses carry `Deprecation`,
+	/// and `Sunset` and `Link` when set.
+	pub fn deprecate(
+		mut self,
+		version: impl Into<String>,
+		deprecation: MurVersionDeprecation,
+	) -> Self {
+		self.deprecations.insert(version.into(), deprecation);
+		self
+	}
+
+	pub fn strategy(&self) -> &MurVersioningStrategy {
+		&self.strategy
+	}
+
+	pub fn is_uri(&self) -> bool {
+		matches!(self.strategy, MurVersioningStrategy::Uri { .. })
+	}
+
+	pub fn default_version_str(&self) -> Option<&str> {
+		self.default_version.as_deref()
+	}
+
+	pub fn deprecation(&self, version: &str) -> Option<&MurVersionDeprecation> {
+		self.deprecations.get(version)
+	}
+
+	/// The version the request asks for, or the default version. Always
+	/// `None` with URI versioning, where the path selects the route.
+	pub fn requested_version(&self, ctx: &MurRequestContext) -> Option<String> {
+		let requested = match &self.strategy {
+			MurVersioningStrategy::Uri { .. } => return None,
+			MurVersioningStrategy::Header(name) => ctx.header(name).map(str::to_string),
+			MurVersioningStrategy::MediaType { key } => ctx
+				.header("accept")
+				.and_then(|accept| media_type_version(accept, key)),
+			MurVersioningStrategy::Query(name) => ctx.query_param(name).map(str::to_string),
+			MurVersioningStrategy::Custom(extract) => extract(ctx),
+		};
+		requested
+			.map(|version| version.trim().to_string())
+			.filter(|version| !version.is_empty())
+			.or_else(|| self.default_version.clone())
+	}
+
+	/// The path segment for `version` under URI versioning.
+	pub(crate) fn uri_segment(&self, version: &str) -> Option<String> {
+		match &self.strategy {
+			MurVersioningStrategy::Uri { prefix } => Some(format!("/{}{}", prefix, version)),
+			_ => None,
+		}
+	}
+}
+
+/// The deprecation announced for an API version.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MurVersionDeprecation {
+	pub since: Option<DateTime<Utc>>,
+	pub sunset: Option<DateTime<Utc>>,
+	pub link: Option<String>,
+}
+
+impl MurVersionDeprecation {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// When the version was deprecated, sent as `Deprecation: @<epoch>`.
+	/// Without it the header is `Deprecation: true`.
+	pub fn since(mut self, at: DateTime<Utc>) -> Self {
+		self.since = Some(at);
+		self
+	}
+
+	/// When the version stops being served, sent as the `Sunset` header.
+	pub fn sunset(mut self, at: DateTime<Utc>) -> Self {
+		self.sunset = Some(at);
+		self
+	}
+
+	/// A page describing the deprecation, sent as a `Link` with
+	/// `rel="deprecation"`.
+	pub fn link(mut self, url: impl Into<String>) -> Self {
+		self.link = Some(url.into());
+		self
+	}
+
+	pub(crate) fn apply(&self, headers: &mut HeaderMap) {
+		let deprecation = match self.since {
+			Some(since) => format!("@{}", since.timestamp()),
+			None => "true".to_string(),
+		};
+		if let Ok(value) = HeaderValue::from_str(&deprecation) {
+			headers.insert("Deprecation", value);
+		}
+		if let Some(sunset) = self.sunset {
+			let value = sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+			if let Ok(value) = HeaderValue::from_str(&value) {
+				headers.insert("Sunset", value);
+			}
+		}
+		if let Some(link) = &self.link
+			&& let Ok(value) = HeaderValue::from_str(&format!("<{}>; rel=\"deprecation\"", link))
+		{
+			headers.append("Link", value);
+		}
+	}
+}
+
+/// The version served to the matched request, stored in the request
+/// extensions.
+#[derive(Debug, Clone)]
+pub(crate) struct MurApiVersion(pub String);
+
+fn media_type_version(accept: &str, key: &str) -> Option<String> {
+	accept.split(',').find_map(|range| {
+		range.split(';').skip(1).find_map(|param| {
+			let (name, value) = param.split_once('=')?;
+			name.trim()
+				.eq_ignore_ascii_case(key)
+				.then(|| value.trim().trim_matches('"').to_string())
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::TimeZone;
+
+	#[test]
+	fn test_media_type_version() {
+		let accept = "text/html, application/json; charset=utf-8; v=\"2\"";
+
+		assert_eq!(media_type_version(accept, "v"), Some("2".to_string()));
+		assert_eq!(media_type_version(accept, "version"), None);
+		assert_eq!(media_type_version("application/json", "v"), None);
+	}
+
+	#[test]
+	fn test_deprecation_headers() {
+		let at = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap();
+		let mut headers = HeaderMap::new();
+
+		MurVersionDeprecation::new()
+			.since(at)
+			.sunset(at)
+			.link("https://example.com/v1")
+			.apply(&mut headers);
+
+		assert_eq!(headers["Deprecation"], "@1798761600");
+		assert_eq!(headers["Sunset"], "Fri, 01 Jan 2027 00:00:00 GMT");
+		assert_eq!(
+			headers["Link"],
+			"<https://example.com/v1>; rel=\"deprecation\""
+		);
+	}
+}
@@ -1,11 +1,14 @@
 use super::config::MurServerConfig;
+use super::handoff::MurHandoff;
+use super::listener::{MurListenAddr, MurListener};
 use super::module::MurModule;
 use super::router::MurRouter;
-use super::security::tls::MurTlsAcceptor;
+use super::security::tls::{MurClientCert, MurTlsAcceptor};
+use super::shutdown::MurShutdownToken;
 use crate::MurError;
 use crate::server::security::limited_body_extraction;
-use crate::server::service::MurInjects;
-use http::{Request, Response};
+use crate::server::service::{MurInjects, MurService};
+use http::{Request, Response, StatusCode, header};
 use http_body_util::Full;
 use hyper::body::{Bytes, Incoming};
 use hyper::rt::{Read, Write};
@@ -15,10 +18,12 @@ use hyper_util::rt::TokioIo;
 use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::{Duration, Instant};
-use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::watch;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpSocket, TcpStream};
+use tokio::sync::{Notify, watch};
+use tokio::task::JoinSet;
 
 // ---------------------------------------------------------------------------
 // Tipos internos
@@ -32,6 +37,76 @@ enum ShutdownMode {
 	Graceful { timeout: Duration },
 }
 
+/// Socket já aberto de um listener.
+enum BoundSocket {
+	Tcp(TcpListener),
+	#[cfg(unix)]
+	Unix(tokio::net::UnixListener),
+}
+
+/// Conexão aceita por um [`BoundSocket`].
+enum Accepted {
+	Tcp(TcpStream),
+	#[cfg(unix)]
+	Unix(tokio::net::UnixStream),
+}
+
+/// Connection counters of a running server, registered as a service.
+#[derive(Debug, Default)]
+pub struct MurConnectionStats {
+	active: AtomicUsize,
+	total: AtomicU64,
+	idle: Notify,
+}
+
+impl MurConnectionStats {
+	/// Connections currently open, across every listener.
+	pub fn active(&self) -> usize {
+		self.active.load(Ordering::Acquire)
+	}
+
+	/// Connections accepted since the server started.
+	pub fn total(&self) -> u64 {
+		self.total.load(Ordering::Acquire)
+	}
+
+	fn open(&self) {
+		self.total.fetch_add(1, Ordering::AcqRel);
+		self.active.fetch_add(1, Ordering::AcqRel);
+	}
+
+	fn close(&self) {
+		if self.active.fetch_sub(1, Ordering::AcqRel) == 1 {
+			self.idle.notify_waiters();
+		}
+	}
+
+	async fn idle(&self) {
+		loop {
+			// Registra o interesse antes de conferir, para não perder o aviso.
+			let notified = self.idle.notified();
+			if self.active() == 0 {
+				return;
+			}
+			notified.await;
+		}
+	}
+}
+
+impl MurService for MurConnectionStats {
+	fn as_any(&self) -> &dyn std::any::Any {
+		self
+	}
+}
+
+/// Estado compartilhado pelas conexões de um listener.
+struct ListenerState {
+	listener: Arc<MurListener>,
+	tls: Option<MurTlsAcceptor>,
+	router: Arc<MurRouter>,
+	body_limit: usize,
+}
+
 // ---------------------------------------------------------------------------
 // MurServerRunner
 // ---------------------------------------------------------------------------
@@ -43,7 +118,9 @@ pub struct MurServerRunner {
 	pub(crate) injects: MurInjects,
 	pub(crate) on_startup: Vec<Box<dyn Fn() + Send + Sync>>,
 	pub(crate) on_shutdown: Vec<Box<dyn Fn() + Send + Sync>>,
-	pub(crate) tls_acceptor: Option<MurTlsAcceptor>,
+	pub(crate) listeners: Vec<(MurListener, Option<MurTlsAcceptor>)>,
+	pub(crate) shutdown: MurShutdownToken,
+	pub(crate) connections: Arc<MurConnectionStats>,
 }
 
 impl MurServerRunner {
@@ -77,6 +154,21 @@ impl MurServerRunner {
 		&self.config
 	}
 
+	/// Open and accepted connection counts.
+	pub fn connection_stats(&self) -> Arc<MurConnectionStats> {
+		Arc::clone(&self.connections)
+	}
+
+	/// The token cancelled when the server shuts down.
+	pub fn shutdown_token(&self) -> MurShutdownToken {
+		self.shutdown.clone()
+	}
+
+	/// Every listener the server accepts connections on, primary first.
+	pub fn listeners(&self) -> impl Iterator<Item = &MurListener> {
+		self.listeners.iter().map(|(listener, _)| listener)
+	}
+
 	// -----------------------------------------------------------------------
 	// Núcleo — um único caminho de execução
 	// -----------------------------------------------------------------------
@@ -89,25 +181,35 @@ impl MurServerRunner {
 		F: Future<Output = ()> + Send + 'static,
 	{
 		for hook in &self.on_startup {
-			if let Err(panic) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| hook())) {
+			if let Err(panic) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(hook)) {
 				eprintln!("Startup hook panicked");
 				std::panic::resume_unwind(panic);
 			}
 		}
 
-		let listener = TcpListener::bind(self.config.addr).await?;
-		let protocol = if self.tls_acceptor.is_some() {
-			"https"
-		} else {
-			"http"
-		};
+		let mut sockets = Vec::with_capacity(self.listeners.len());
+		for (listener, _) in &self.listeners {
+			sockets.push(bind_socket(listener).await?);
+		}
 
-		println!(
-			"{} server listening on {}://{}",
-			self.config.server_name, protocol, self.config.addr
-		);
+		for (listener, _) in &self.listeners {
+			let url = match &listener.addr {
+				MurListenAddr::Tcp(addr) if listener.tls.is_some() => format!("https://{}", addr),
+				MurListenAddr::Tcp(addr) => format!("http://{}", addr),
+				MurListenAddr::Unix(_) | MurListenAddr::Inherited(_) => listener.addr.to_string(),
+			};
+			match listener.redirect_https {
+				Some(port) => println!(
+					"{} redirecting {} to https on port {}",
+					self.config.server_name, url, port
+				),
+				None => println!("{} server listening on {}", self.config.server_name, url),
+			}
+		}
 
-		let shutdown_mode = if self.config.graceful_shutdown {
+		// O hand-off sempre drena as conexões, senão o deploy derruba requisições
+		let drain = self.config.graceful_shutdown || self.config.handoff_pid_file.is_some();
+		let shutdown_mode = if drain {
 			ShutdownMode::Graceful {
 				timeout: self.config.shutdown_timeout,
 			}
@@ -115,14 +217,15 @@ impl MurServerRunner {
 			ShutdownMode::Forever
 		};
 
-		self.accept_loop(listener, shutdown_signal, shutdown_mode)
+		self.accept_loop(sockets, shutdown_signal, shutdown_mode)
 			.await
 	}
 
-	/// Loop central de accept — lida com plain TCP e TLS de forma unificada.
+	/// Inicia um loop de accept por listener — plain TCP, TLS e Unix — e
+	/// aguarda todos terminarem no shutdown.
 	async fn accept_loop<F>(
 		self,
-		listener: TcpListener,
+		sockets: Vec<BoundSocket>,
 		shutdown_signal: F,
 		mode: ShutdownMode,
 	) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
@@ -130,39 +233,79 @@ impl MurServerRunner {
 		F: Future<Output = ()> + Send + 'static,
 	{
 		let (shutdown_tx, shutdown_rx) = watch::channel(false);
-		let active = Arc::new(AtomicUsize::new(0));
-
-		// Tarefa que aguarda o sinal de shutdown (Ctrl+C ou sinal customizado)
+		let connections = Arc::clone(&self.connections);
+		let handoff = self.config.handoff_pid_file.as_ref().map(MurHandoff::new);
+		let successor_ready = handoff
+			.as_ref()
+			.map(MurHandoff::successor_ready)
+			.transpose()?;
+
+		// Tarefa que aguarda o sinal de shutdown (SIGINT/SIGTERM/SIGQUIT, sinal
+		// customizado ou um novo processo assumindo os sockets)
+		let shutdown = self.shutdown.clone();
+		let pre_stop_delay = self.config.pre_stop_delay;
 		tokio::spawn(async move {
-			shutdown_signal.await;
-			println!("\nShutdown signal received, starting graceful shutdown...");
+			let successor_ready = async move {
+				match successor_ready {
+					Some(ready) => ready.await,
+					None => std::future::pending().await,
+				}
+			};
+			let reason = tokio::select! {
+				_ = shutdown_signal => "Shutdown signal received",
+				_ = successor_ready => "Successor process is ready",
+			};
+			println!("\n{}, starting graceful shutdown...", reason);
+
+			// Pre-stop: readiness passa a DOWN, mas ainda aceitamos conexões
+			shutdown.drain();
+			if !pre_stop_delay.is_zero() {
+				println!(
+					"Draining for {:?} before closing listeners...",
+					pre_stop_delay
+				);
+				tokio::time::sleep(pre_stop_delay).await;
+			}
+			shutdown.cancel();
 			let _ = shutdown_tx.send(true);
 		});
 
-		loop {
-			// Materializa o future de shutdown antes do select! para evitar
-			// "temporary value dropped while borrowed" (E0716).
-			let mut changed = shutdown_rx.clone();
-			tokio::select! {
-				result = listener.accept() => {
-					match result {
-						Ok((stream, _)) => {
-							self.spawn_connection(
-								stream,
-								Arc::clone(&active),
-								shutdown_rx.clone(),
-							);
-						}
-						Err(e) => eprintln!("Accept error: {}", e),
-					}
-				}
-				_ = changed.changed() => break,
+		let mut loops = JoinSet::new();
+		for ((listener, tls), socket) in self.listeners.iter().zip(sockets) {
+			// Recarga de certificados TLS (arquivos observados e/ou SIGHUP)
+			if let Some(acceptor) = tls {
+				acceptor.spawn_reloader(shutdown_rx.clone());
 			}
+			let state = Arc::new(ListenerState {
+				listener: Arc::new(listener.clone()),
+				tls: tls.clone(),
+				router: Arc::clone(&self.router),
+				body_limit: listener.body_limit.unwrap_or(self.config.body_limit),
+			});
+			loops.spawn(accept_connections(
+				socket,
+				state,
+				Arc::clone(&connections),
+				shutdown_rx.clone(),
+			));
 		}
 
+		// Hand-off: avisa o processo anterior que já estamos aceitando conexões
+		if let Some(handoff) = &handoff {
+			match handoff.announce() {
+				Ok(Some(pid)) => println!("Took over from process {}", pid),
+				Ok(None) => {}
+				Err(e) => eprintln!("Failed to hand off from the previous process: {}", e),
+			}
+		}
+		loops.join_all().await;
+
 		// Graceful: aguarda conexões ativas
 		if let ShutdownMode::Graceful { timeout } = mode {
-			wait_for_connections(&active, timeout).await;
+			wait_for_connections(&connections, timeout).await;
+		}
+		if let Some(handoff) = &handoff {
+			handoff.release();
 		}
 
 		self.run_shutdown_hooks();
@@ -170,39 +313,9 @@ impl MurServerRunner {
 		Ok(())
 	}
 
-	/// Cria uma task para uma conexão nova, com ou sem TLS.
-	fn spawn_connection(
-		&self,
-		stream: TcpStream,
-		active: Arc<AtomicUsize>,
-		mut shutdown_rx: watch::Receiver<bool>,
-	) {
-		let router = Arc::clone(&self.router);
-		let limit = self.config.body_limit;
-		let tls = self.tls_acceptor.clone();
-
-		active.fetch_add(1, Ordering::Relaxed);
-
-		tokio::spawn(async move {
-			match tls {
-				Some(acceptor) => match acceptor.accept(stream).await {
-					Ok(tls_stream) => {
-						serve(TokioIo::new(tls_stream), router, limit, &mut shutdown_rx).await;
-					}
-					Err(e) => eprintln!("TLS handshake error: {}", e),
-				},
-				None => {
-					serve(TokioIo::new(stream), router, limit, &mut shutdown_rx).await;
-				}
-			}
-
-			active.fetch_sub(1, Ordering::Relaxed);
-		});
-	}
-
 	fn run_shutdown_hooks(&self) {
 		for hook in &self.on_shutdown {
-			let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| hook()));
+			let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(hook));
 		}
 		for module in &self.modules {
 			let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| module.on_shutdown()));
@@ -216,17 +329,176 @@ impl MurServerRunner {
 // Funções auxiliares livres
 // ---------------------------------------------------------------------------
 
+/// Abre o socket de um listener. Um arquivo de socket Unix antigo no mesmo
+/// caminho é removido antes do bind.
+async fn bind_socket(listener: &MurListener) -> Result<BoundSocket, std::io::Error> {
+	match &listener.addr {
+		#[cfg(unix)]
+		MurListenAddr::Tcp(addr) if listener.reuse_port => bind_reuse_port(*addr).map(BoundSocket::Tcp),
+		#[cfg(not(unix))]
+		MurListenAddr::Tcp(_) if listener.reuse_port => Err(std::io::Error::new(
+			std::io::ErrorKind::Unsupported,
+			"SO_REUSEPORT is not supported on this platform",
+		)),
+		MurListenAddr::Tcp(addr) => Ok(BoundSocket::Tcp(TcpListener::bind(addr).await?)),
+		#[cfg(unix)]
+		MurListenAddr::Unix(path) => {
+			use std::os::unix::fs::FileTypeExt;
+
+			if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+				std::fs::remove_file(path)?;
+			}
+			Ok(BoundSocket::Unix(tokio::net::UnixListener::bind(path)?))
+		}
+		#[cfg(unix)]
+		MurListenAddr::Inherited(fd) => inherited_socket(*fd),
+		#[cfg(not(unix))]
+		MurListenAddr::Unix(_) | MurListenAddr::Inherited(_) => Err(std::io::Error::new(
+			std::io::ErrorKind::Unsupported,
+			"Unix domain sockets and inherited sockets are not supported on this platform",
+		)),
+	}
+}
+
+/// Bind TCP com `SO_REUSEPORT`: outro processo pode escutar no mesmo
+/// endereço enquanto este ainda drena as conexões.
+#[cfg(unix)]
+fn bind_reuse_port(addr: SocketAddr) -> Result<TcpListener, std::io::Error> {
+	let socket = if addr.is_ipv4() {
+		TcpSocket::new_v4()?
+	} else {
+		TcpSocket::new_v6()?
+	};
+	socket.set_reuseaddr(true)?;
+	socket.set_reuseport(true)?;
+	socket.bind(addr)?;
+	socket.listen(1024)
+}
+
+/// Adota um socket herdado (`LISTEN_FDS` ou fd bruto), TCP ou Unix.
+#[cfg(unix)]
+fn inherited_socket(fd: i32) -> Result<BoundSocket, std::io::Error> {
+	use std::os::fd::{FromRawFd, OwnedFd};
+
+	// SAFETY: F_GETFD só consulta as flags do fd; um fd fechado retorna -1.
+	if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+		return Err(std::io::Error::last_os_error());
+	}
+	// SAFETY: o fd está aberto e foi entregue a este processo para ser
+	// servido; a partir daqui o listener é o seu único dono.
+	let owned = unsafe { OwnedFd::from_raw_fd(fd) };
+
+	let tcp = std::net::TcpListener::from(owned);
+	if tcp.local_addr().is_ok() {
+		tcp.set_nonblocking(true)?;
+		return Ok(BoundSocket::Tcp(TcpListener::from_std(tcp)?));
+	}
+	let unix = std::os::unix::net::UnixListener::from(OwnedFd::from(tcp));
+	if unix.local_addr().is_err() {
+		return Err(std::io::Error::new(
+			std::io::ErrorKind::InvalidInput,
+			format!("Inherited fd {} is not a listening socket", fd),
+		));
+	}
+	unix.set_nonblocking(true)?;
+	Ok(BoundSocket::Unix(tokio::net::UnixListener::from_std(unix)?))
+}
+
+impl BoundSocket {
+	async fn accept(&self) -> Result<Accepted, std::io::Error> {
+		match self {
+			BoundSocket::Tcp(listener) => Ok(Accepted::Tcp(listener.accept().await?.0)),
+			#[cfg(unix)]
+			BoundSocket::Unix(listener) => Ok(Accepted::Unix(listener.accept().await?.0)),
+		}
+	}
+}
+
+/// Aceita conexões de um listener até o sinal de shutdown.
+async fn accept_connections(
+	socket: BoundSocket,
+	state: Arc<ListenerState>,
+	connections: Arc<MurConnectionStats>,
+	shutdown_rx: watch::Receiver<bool>,
+) {
+	loop {
+		// Materializa o future de shutdown antes do select! para evitar
+		// "temporary value dropped while borrowed" (E0716).
+		let mut changed = shutdown_rx.clone();
+		tokio::select! {
+			result = socket.accept() => {
+				let state = Arc::clone(&state);
+				let connections = Arc::clone(&connections);
+				let shutdown_rx = shutdown_rx.clone();
+				match result {
+					Ok(Accepted::Tcp(stream)) => {
+						spawn_connection(stream, state, connections, shutdown_rx);
+					}
+					#[cfg(unix)]
+					Ok(Accepted::Unix(stream)) => {
+						spawn_connection(stream, state, connections, shutdown_rx);
+					}
+					Err(e) => eprintln!("Accept error: {}", e),
+				}
+			}
+			_ = changed.changed() => break,
+		}
+	}
+
+	#[cfg(unix)]
+	if let MurListenAddr::Unix(path) = &state.listener.addr {
+		let _ = std::fs::remove_file(path);
+	}
+}
+
+/// Cria uma task para uma conexão nova, com ou sem TLS.
+fn spawn_connection<S>(
+	stream: S,
+	state: Arc<ListenerState>,
+	connections: Arc<MurConnectionStats>,
+	mut shutdown_rx: watch::Receiver<bool>,
+) where
+	S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+	connections.open();
+
+	tokio::spawn(async move {
+		match &state.tls {
+			Some(acceptor) => match acceptor.accept(stream).await {
+				Ok(tls_stream) => {
+					let client_cert = tls_stream
+						.get_ref()
+						.1
+						.peer_certificates()
+						.and_then(MurClientCert::from_chain)
+						.map(Arc::new);
+					let io = TokioIo::new(tls_stream);
+					serve(io, Arc::clone(&state), client_cert, &mut shutdown_rx).await;
+				}
+				Err(e) => eprintln!("TLS handshake error: {}", e),
+			},
+			None => {
+				let io = TokioIo::new(stream);
+				serve(io, Arc::clone(&state), None, &mut shutdown_rx).await;
+			}
+		}
+
+		connections.close();
+	});
+}
+
 /// Serve uma única conexão com suporte a graceful shutdown.
 /// Funciona com qualquer stream que implemente os bounds do hyper.
+/// O certificado do cliente (mTLS) é anexado a cada requisição da conexão.
 async fn serve<I>(
 	io: I,
-	router: Arc<MurRouter>,
-	limit: usize,
+	state: Arc<ListenerState>,
+	client_cert: Option<Arc<MurClientCert>>,
 	shutdown_rx: &mut watch::Receiver<bool>,
 ) where
 	I: Read + Write + Unpin + Send + 'static,
 {
-	let service = make_service(router, limit);
+	let service = make_service(state, client_cert);
 	let conn = http1::Builder::new()
 		.serve_connection(io, service)
 		.with_upgrades();
@@ -236,10 +508,10 @@ async fn serve<I>(
 	loop {
 		tokio::select! {
 			result = conn.as_mut() => {
-				if let Err(err) = result {
-					if !err.is_closed() {
-						eprintln!("Connection error: {}", err);
-					}
+				if let Err(err) = result
+					&& !err.is_closed()
+				{
+					eprintln!("Connection error: {}", err);
 				}
 				break;
 			}
@@ -250,21 +522,33 @@ async fn serve<I>(
 	}
 }
 
-/// Constrói o service hyper que despacha para o router.
+/// Constrói o service hyper que despacha para o router, ou redireciona para
+/// HTTPS quando o listener é de redirecionamento.
 fn make_service(
-	router: Arc<MurRouter>,
-	rate_limit: usize,
+	state: Arc<ListenerState>,
+	client_cert: Option<Arc<MurClientCert>>,
 ) -> impl Service<
 	Request<Incoming>,
 	Response = Response<Full<Bytes>>,
 	Error = MurError,
 	Future = impl Future<Output = Result<Response<Full<Bytes>>, MurError>>,
 > {
-	service_fn(move |req| {
-		let router = Arc::clone(&router);
+	service_fn(move |mut req: Request<Incoming>| {
+		let state = Arc::clone(&state);
+		if let Some(cert) = &client_cert {
+			req.extensions_mut().insert(Arc::clone(cert));
+		}
+		req.extensions_mut().insert(Arc::clone(&state.listener));
 		async move {
-			let req = limited_body_extraction(req, rate_limit).await;
-			match router.handle_direct(req).await.into_result() {
+			if state.listener.redirect_https.is_some() {
+				return Ok(https_redirect(&state.listener, &req));
+			}
+			let req = limited_body_extraction(req, state.body_limit).await;
+			match Arc::clone(&state.router)
+				.handle_direct(req)
+				.await
+				.into_result()
+			{
 				Ok(res) => Ok(res),
 				Err(err) => Ok(err.into_response()),
 			}
@@ -272,28 +556,54 @@ fn make_service(
 	})
 }
 
+/// Responde `308` apontando para o mesmo host e caminho em HTTPS.
+fn https_redirect(listener: &MurListener, req: &Request<Incoming>) -> Response<Full<Bytes>> {
+	let host = req
+		.headers()
+		.get(header::HOST)
+		.and_then(|h| h.to_str().ok())
+		.or_else(|| req.uri().authority().map(|a| a.as_str()));
+	let path = req.uri().path_and_query().map_or("/", |p| p.as_str());
+
+	match host.and_then(|host| listener.redirect_location(host, path)) {
+		Some(location) => Response::builder()
+			.status(StatusCode::PERMANENT_REDIRECT)
+			.header(header::LOCATION, location)
+			.body(Full::new(Bytes::new()))
+			.unwrap_or_else(|_| MurError::internal("Invalid redirect").into_response()),
+		None => MurError::bad_request("Missing Host header").into_response(),
+	}
+}
+
 /// Aguarda até todas as conexões ativas fecharem ou o timeout estourar.
-async fn wait_for_connections(active: &AtomicUsize, timeout: Duration) {
+async fn wait_for_connections(connections: &MurConnectionStats, timeout: Duration) {
 	println!(
 		"Waiting for {} active connection(s) to finish...",
-		active.load(Ordering::Relaxed)
+		connections.active()
 	);
 
-	let start = Instant::now();
-	while active.load(Ordering::Relaxed) > 0 {
-		if start.elapsed() > timeout {
-			println!("Shutdown timeout exceeded, forcing shutdown...");
-			break;
-		}
-		tokio::time::sleep(Duration::from_millis(100)).await;
+	let drained = tokio::time::timeout(timeout, connections.idle()).await;
+	if drained.is_err() {
+		println!("Shutdown timeout exceeded, forcing shutdown...");
 	}
 }
 
-/// Sinal de shutdown padrão: Ctrl+C.
-fn default_shutdown_signal() -> impl Future<Output = ()> + Send + 'static {
-	async {
-		tokio::signal::ctrl_c()
-			.await
-			.expect("Failed to listen for Ctrl+C");
+/// Sinal de shutdown padrão: Ctrl+C (SIGINT), SIGTERM ou SIGQUIT.
+async fn default_shutdown_signal() {
+	#[cfg(unix)]
+	{
+		use tokio::signal::unix::{SignalKind, signal};
+
+		let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+		let mut quit = signal(SignalKind::quit()).expect("Failed to listen for SIGQUIT");
+		tokio::select! {
+			result = tokio::signal::ctrl_c() => result.expect("Failed to listen for Ctrl+C"),
+			_ = terminate.recv() => {}
+			_ = quit.recv() => {}
+		}
 	}
+	#[cfg(not(unix))]
+	tokio::signal::ctrl_c()
+		.await
+		.expect("Failed to listen for Ctrl+C");
 }
@@ -0,0 +1,130 @@
+use super::{MurApiKey, MurApiKeyIdentity, MurApiKeyStore};
+use crate::server::aliases::MurFuture;
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use chrono::Utc;
+use std::sync::Arc;
+
+/// Where a request carries its API key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MurApiKeySource {
+	Header(String),
+	Query(String),
+	Cookie(String),
+}
+
+impl MurApiKeySource {
+	fn extract<'a>(&self, ctx: &'a MurRequestContext) -> Option<&'a str> {
+		let value = match self {
+			Self::Header(name) => ctx.header(name),
+			Self::Query(name) => ctx.query_param(name),
+			Self::Cookie(name) => ctx.cookie(name),
+		};
+		value.map(str::trim).filter(|value| !value.is_empty())
+	}
+}
+
+/// Authenticates requests by API key.
+///
+/// The key is read from the first configured source that has one, the
+/// `X-API-Key` header by default, and looked up in the store by its hash.
+/// A known, unexpired key attaches its [`MurApiKeyIdentity`] to the
+/// request; other requests pass through without one, to be turned away by
+/// [`MurApiKeyGuard`](super::MurApiKeyGuard) where a key is required.
+///
+/// Register it before `MurThrottler` so that rate limits can be keyed by
+/// API key.
+///
+/// ```rust,ignore
+/// MurServer::new()
+///     .middleware(MurApiKeyAuth::new(MurFileApiKeyStore::new("keys.json")).query("api_key"))
+///     .middleware(MurThrottler::new().by_api_key().tier("partner", 10_000))
+///     .guard::<MurApiKeyGuard>()
+/// ```
+#[derive(Clone)]
+pub struct MurApiKeyAuth {
+	store: Arc<dyn MurApiKeyStore>,
+	sources: Vec<MurApiKeySource>,
+}
+
+impl std::fmt::Debug for MurApiKeyAuth {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurApiKeyAuth")
+			.field("sources", &self.sources)
+			.finish()
+	}
+}
+
+impl MurApiKeyAuth {
+	pub fn new(store: impl MurApiKeyStore) -> Self {
+		Self::with_store(Arc::new(store))
+	}
+
+	pub fn with_store(store: Arc<dyn MurApiKeyStore>) -> Self {
+		Self {
+			store,
+			sources: vec![MurApiKeySource::Header("X-API-Key".to_string())],
+		}
+	}
+
+	/// Replaces the sources the key is read from.
+	pub fn sources(mut self, sources: impl IntoIterator<Item = MurApiKeySource>) -> Self {
+		self.sources = sources.into_iter().collect();
+		self
+	}
+
+	/// Also reads the key from the `name` header.
+	pub fn header(mut self, name: impl Into<String>) -> Self {
+		self.sources.push(MurApiKeySource::Header(name.into()));
+		self
+	}
+
+	/// Also reads the key from the `name` query parameter. Query strings end
+	/// up in logs, so prefer headers where clients allow it.
+	pub fn query(mut self, name: impl Into<String>) -> Self {
+		self.sources.push(MurApiKeySource::Query(name.into()));
+		self
+	}
+
+	/// Also reads the key from the `name` cookie.
+	pub fn cookie(mut self, name: impl Into<String>) -> Self {
+		self.sources.push(MurApiKeySource::Cookie(name.into()));
+		self
+	}
+
+	/// The identity of the key the request presents, if it is known and
+	/// unexpired.
+	pub async fn authenticate(
+		&self,
+		ctx: &MurRequestContext,
+	) -> Result<Option<MurApiKeyIdentity>, MurError> {
+		let Some(secret) = self.sources.iter().find_map(|source| source.extract(ctx)) else {
+			return Ok(None);
+		};
+		let key = self.store.find(&MurApiKey::hash(secret)).await?;
+		Ok(key
+			.filter(|key| !key.is_expired(Utc::now()))
+			.map(|key| key.identity()))
+	}
+}
+
+impl MurMiddleware for MurApiKeyAuth {
+	fn handle(&self, mut ctx: MurRequestContext, next: MurNext) -> MurFuture {
+		let auth = self.clone();
+		Box::pin(async move {
+			match auth.authenticate(&ctx).await {
+				Ok(Some(identity)) => {
+					ctx.parts.extensions.insert(identity);
+				}
+				Ok(None) => {}
+				Err(e) => return e.into(),
+			}
+			next.run(ctx).await
+		})
+	}
+
+	fn name(&self) -> &str {
+		"MurApiKeyAuth"
+	}
+}
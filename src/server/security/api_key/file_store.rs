@@ -0,0 +1,82 @@
+use super::MurApiKey;
+use super::store::{MurApiKeyFuture, MurApiKeyStore};
+use crate::server::error::MurError;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+/// Reads keys from a JSON file holding an array of [`MurApiKey`] records.
+///
+/// The file is read again whenever its modification time changes, so keys
+/// can be added or revoked without a restart.
+///
+/// ```json
+/// [
+///   { "id": "billing-sync", "key_hash": "9f86d0…", "scopes": ["invoices:read"],
+///     "expires_at": "2027-01-01T00:00:00Z", "tier": "partner" }
+/// ]
+/// ```
+#[derive(Debug)]
+pub struct MurFileApiKeyStore {
+	path: PathBuf,
+	loaded: RwLock<Option<MurLoadedKeys>>,
+}
+
+#[derive(Debug)]
+struct MurLoadedKeys {
+	modified: SystemTime,
+	keys: Arc<HashMap<String, MurApiKey>>,
+}
+
+impl MurFileApiKeyStore {
+	/// The file is first read on the first lookup.
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self {
+			path: path.into(),
+			loaded: RwLock::new(None),
+		}
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	async fn keys(&self) -> Result<Arc<HashMap<String, MurApiKey>>, MurError> {
+		let modified = tokio::fs::metadata(&self.path)
+			.await
+			.and_then(|metadata| metadata.modified())
+			.map_err(io_error)?;
+		{
+			let loaded = self.loaded.read().unwrap_or_else(|e| e.into_inner());
+			if let Some(loaded) = loaded.as_ref().filter(|loaded| loaded.modified == modified) {
+				return Ok(Arc::clone(&loaded.keys));
+			}
+		}
+
+		let bytes = tokio::fs::read(&self.path).await.map_err(io_error)?;
+		let keys: Vec<MurApiKey> = serde_json::from_slice(&bytes)
+			.map_err(|e| MurError::service_unavailable(format!("Invalid API key file: {}", e)))?;
+		let keys: Arc<HashMap<_, _>> = Arc::new(
+			keys.into_iter()
+				.map(|key| (key.key_hash.to_ascii_lowercase(), key))
+				.collect(),
+		);
+
+		*self.loaded.write().unwrap_or_else(|e| e.into_inner()) = Some(MurLoadedKeys {
+			modified,
+			keys: Arc::clone(&keys),
+		});
+		Ok(keys)
+	}
+}
+
+fn io_error(e: std::io::Error) -> MurError {
+	MurError::service_unavailable(format!("API key store I/O error: {}", e))
+}
+
+impl MurApiKeyStore for MurFileApiKeyStore {
+	fn find<'a>(&'a self, key_hash: &'a str) -> MurApiKeyFuture<'a, Option<MurApiKey>> {
+		Box::pin(async move { Ok(self.keys().await?.get(key_hash).cloned()) })
+	}
+}
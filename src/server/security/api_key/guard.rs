@@ -0,0 +1,98 @@
+use crate::MurInjects;
+use crate::server::aliases::MurRes;
+use crate::server::guard::{MurGuard, MurGuardFactory, MurGuardFuture};
+use crate::server::http::{MurHttpResponse, MurRequestContext};
+use crate::server::service::MurServiceContainer;
+use std::any::Any;
+
+/// Route metadata naming the scopes an API key must hold, checked by
+/// [`MurApiKeyScopeGuard`].
+///
+/// ```rust,ignore
+/// #[post("/orders")]
+/// #[use_guards(MurApiKeyGuard, MurApiKeyScopeGuard)]
+/// #[set_metadata(MurApiKeyScopes::new(["orders:write"]))]
+/// async fn create(&self, key: MurApiKeyIdentity) -> MurRes { .. }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MurApiKeyScopes(pub Vec<String>);
+
+impl MurApiKeyScopes {
+	pub fn new<I, S>(scopes: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		Self(scopes.into_iter().map(Into::into).collect())
+	}
+}
+
+/// Admits requests that [`MurApiKeyAuth`](super::MurApiKeyAuth)
+/// authenticated, answering others with `401 Unauthorized`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MurApiKeyGuard;
+
+impl MurGuard for MurApiKeyGuard {
+	fn check_can_activate<'a>(&'a self, ctx: &'a MurRequestContext) -> MurGuardFuture<'a> {
+		let allowed = ctx.api_key().is_some();
+		Box::pin(async move { allowed })
+	}
+
+	fn rejection_response(&self) -> MurRes {
+		MurHttpResponse::unauthorized().json(serde_json::json!({
+			"error": "Unauthorized",
+			"message": "A valid API key is required"
+		}))
+	}
+
+	fn name(&self) -> &str {
+		"MurApiKeyGuard"
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl MurGuardFactory for MurApiKeyGuard {
+	fn __create_factory(_injects: &MurInjects, _container: &MurServiceContainer) -> Self {
+		Self
+	}
+}
+
+/// Admits requests whose API key holds every scope in the route's
+/// [`MurApiKeyScopes`], answering others with `403 Forbidden`. Routes
+/// without scopes admit any authenticated key.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MurApiKeyScopeGuard;
+
+impl MurGuard for MurApiKeyScopeGuard {
+	fn check_can_activate<'a>(&'a self, ctx: &'a MurRequestContext) -> MurGuardFuture<'a> {
+		let allowed = ctx.api_key().is_some_and(|key| {
+			ctx.route_metadata::<MurApiKeyScopes>()
+				.is_none_or(|required| required.0.iter().all(|scope| key.has_scope(scope)))
+		});
+		Box::pin(async move { allowed })
+	}
+
+	fn rejection_response(&self) -> MurRes {
+		MurHttpResponse::forbidden().json(serde_json::json!({
+			"error": "Forbidden",
+			"message": "The API key lacks a required scope"
+		}))
+	}
+
+	fn name(&self) -> &str {
+		"MurApiKeyScopeGuard"
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl MurGuardFactory for MurApiKeyScopeGuard {
+	fn __create_factory(_injects: &MurInjects, _container: &MurServiceContainer) -> Self {
+		Self
+	}
+}
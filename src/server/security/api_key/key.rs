@@ -0,0 +1,140 @@
+use crate::core::utils::MurRand;
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use aws_lc_rs::digest::{SHA256, digest};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// A stored API key. Only the SHA-256 hash of the secret is kept, so a
+/// leaked store does not leak usable keys.
+///
+/// ```rust,ignore
+/// let (secret, key) = MurApiKey::generate("billing-sync");
+/// store.insert(key.scope("invoices:read").tier("partner"));
+/// // hand `secret` to the client once; it cannot be recovered later
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MurApiKey {
+	pub id: String,
+	/// The hex SHA-256 hash of the secret, as made by [`MurApiKey::hash`].
+	pub key_hash: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(default)]
+	pub scopes: Vec<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub expires_at: Option<DateTime<Utc>>,
+	/// The rate-limit tier, matched against `MurThrottler::tier`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub tier: Option<String>,
+}
+
+impl MurApiKey {
+	/// A key accepting `secret`.
+	pub fn new(id: impl Into<String>, secret: &str) -> Self {
+		Self::from_hash(id, Self::hash(secret))
+	}
+
+	/// A key whose secret hash was computed elsewhere.
+	pub fn from_hash(id: impl Into<String>, key_hash: impl Into<String>) -> Self {
+		Self {
+			id: id.into(),
+			key_hash: key_hash.into().to_ascii_lowercase(),
+			name: None,
+			scopes: Vec::new(),
+			expires_at: None,
+			tier: None,
+		}
+	}
+
+	/// A fresh random secret and the key accepting it.
+	pub fn generate(id: impl Into<String>) -> (String, Self) {
+		let secret = MurRand::secure_token(32);
+		let key = Self::new(id, &secret);
+		(secret, key)
+	}
+
+	/// The hex SHA-256 hash stored for `secret`. API key secrets are long
+	/// and random, so a fast hash is enough.
+	pub fn hash(secret: &str) -> String {
+		digest(&SHA256, secret.as_bytes()).as_ref().iter().fold(
+			String::with_capacity(64),
+			|mut hex, byte| {
+				let _ = write!(hex, "{:02x}", byte);
+				hex
+			},
+		)
+	}
+
+	pub fn name(mut self, name: impl Into<String>) -> Self {
+		self.name = Some(name.into());
+		self
+	}
+
+	pub fn scope(mut self, scope: impl Into<String>) -> Self {
+		self.scopes.push(scope.into());
+		self
+	}
+
+	pub fn scopes<I, S>(mut self, scopes: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.scopes.extend(scopes.into_iter().map(Into::into));
+		self
+	}
+
+	pub fn expires_at(mut self, at: DateTime<Utc>) -> Self {
+		self.expires_at = Some(at);
+		self
+	}
+
+	pub fn tier(mut self, tier: impl Into<String>) -> Self {
+		self.tier = Some(tier.into());
+		self
+	}
+
+	pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+		self.expires_at.is_some_and(|expires_at| expires_at <= now)
+	}
+
+	/// The identity attached to requests made with this key.
+	pub fn identity(&self) -> MurApiKeyIdentity {
+		MurApiKeyIdentity {
+			id: self.id.clone(),
+			name: self.name.clone(),
+			scopes: self.scopes.clone(),
+			expires_at: self.expires_at,
+			tier: self.tier.clone(),
+		}
+	}
+}
+
+/// The API key a request was authenticated with, read with
+/// `ctx.api_key()` or a `MurApiKeyIdentity` handler parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MurApiKeyIdentity {
+	pub id: String,
+	pub name: Option<String>,
+	pub scopes: Vec<String>,
+	pub expires_at: Option<DateTime<Utc>>,
+	pub tier: Option<String>,
+}
+
+impl MurApiKeyIdentity {
+	/// The identity attached by [`MurApiKeyAuth`](super::MurApiKeyAuth).
+	///
+	/// Fails with `401 Unauthorized` when the request carried no valid key.
+	pub fn from_ctx(ctx: &MurRequestContext) -> Result<Self, MurError> {
+		ctx.api_key()
+			.cloned()
+			.ok_or_else(|| MurError::unauthorized("A valid API key is required"))
+	}
+
+	/// Whether the key holds `scope`, or the `*` scope.
+	pub fn has_scope(&self, scope: &str) -> bool {
+		self.scopes.iter().any(|held| held == scope || held == "*")
+	}
+}
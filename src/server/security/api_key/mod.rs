@@ -0,0 +1,20 @@
+pub mod auth;
+pub mod file_store;
+pub mod guard;
+pub mod key;
+pub mod store;
+
+pub use auth::MurApiKeyAuth;
+pub use auth::MurApiKeySource;
+pub use file_store::MurFileApiKeyStore;
+pub use guard::MurApiKeyGuard;
+pub use guard::MurApiKeyScopeGuard;
+pub use guard::MurApiKeyScopes;
+pub use key::MurApiKey;
+pub use key::MurApiKeyIdentity;
+pub use store::MurApiKeyFuture;
+pub use store::MurApiKeyStore;
+pub use store::MurMemoryApiKeyStore;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,76 @@
+use super::MurApiKey;
+use crate::server::error::MurError;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::RwLock;
+
+pub type MurApiKeyFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, MurError>> + Send + 'a>>;
+
+/// Where [`MurApiKeyAuth`](super::MurApiKeyAuth) looks keys up, by the hash
+/// of the secret a request presents.
+///
+/// Implement it to keep keys in a database:
+///
+/// ```rust,ignore
+/// impl MurApiKeyStore for PgApiKeys {
+///     fn find<'a>(&'a self, key_hash: &'a str) -> MurApiKeyFuture<'a, Option<MurApiKey>> {
+///         Box::pin(async move { self.repo.by_hash(key_hash).await.map_err(MurError::from) })
+///     }
+/// }
+/// ```
+pub trait MurApiKeyStore: Send + Sync + 'static {
+	fn find<'a>(&'a self, key_hash: &'a str) -> MurApiKeyFuture<'a, Option<MurApiKey>>;
+}
+
+/// Keeps keys in process memory.
+#[derive(Debug, Default)]
+pub struct MurMemoryApiKeyStore {
+	keys: RwLock<HashMap<String, MurApiKey>>,
+}
+
+impl MurMemoryApiKeyStore {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds `key`, for chaining at startup.
+	pub fn with_key(self, key: MurApiKey) -> Self {
+		self.insert(key);
+		self
+	}
+
+	/// Adds `key`, replacing any key with the same id.
+	pub fn insert(&self, key: MurApiKey) {
+		let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+		keys.retain(|_, stored| stored.id != key.id);
+		keys.insert(key.key_hash.clone(), key);
+	}
+
+	/// Revokes the key with `id`.
+	pub fn remove(&self, id: &str) -> Option<MurApiKey> {
+		let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+		let hash = keys.values().find(|key| key.id == id)?.key_hash.clone();
+		keys.remove(&hash)
+	}
+
+	pub fn len(&self) -> usize {
+		self.keys.read().unwrap_or_else(|e| e.into_inner()).len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl MurApiKeyStore for MurMemoryApiKeyStore {
+	fn find<'a>(&'a self, key_hash: &'a str) -> MurApiKeyFuture<'a, Option<MurApiKey>> {
+		let key = self
+			.keys
+			.read()
+			.unwrap_or_else(|e| e.into_inner())
+			.get(key_hash)
+			.cloned();
+		Box::pin(async move { Ok(key) })
+	}
+}
@@ -0,0 +1,200 @@
+use super::*;
+use crate::server::aliases::{MurFuture, MurRes};
+use crate::server::guard::MurGuard;
+use crate::server::http::MurRequestContext;
+use crate::server::middleware::rate_limit::{MurThrottler, MurThrottlerKey};
+use crate::server::middleware::{MurMiddleware, MurNext};
+use crate::server::router::MurRouteMetadata;
+use crate::server::service::MurServiceContainer;
+use chrono::{Duration, Utc};
+use http::StatusCode;
+use http_body_util::{BodyExt, Full};
+use hyper::Response;
+use hyper::body::Bytes;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn ctx(uri: &str, headers: &[(&str, &str)]) -> MurRequestContext {
+	let mut builder = http::Request::builder().uri(uri);
+	for (name, value) in headers {
+		builder = builder.header(*name, *value);
+	}
+	let (parts, _) = builder.body(()).unwrap().into_parts();
+	MurRequestContext::new(
+		parts,
+		None,
+		HashMap::new(),
+		Arc::new(MurServiceContainer::new()),
+	)
+}
+
+/// Echoes the id of the key the middleware attached.
+fn echo() -> MurNext {
+	MurNext::new(Arc::new(|ctx| -> MurFuture {
+		Box::pin(async move {
+			let id = ctx.api_key().map(|key| key.id.clone());
+			MurRes::from(
+				Response::builder()
+					.status(StatusCode::OK)
+					.body(Full::new(Bytes::from(id.unwrap_or_default())))
+					.unwrap(),
+			)
+		})
+	}))
+}
+
+async fn run(middleware: &dyn MurMiddleware, ctx: MurRequestContext) -> (StatusCode, String) {
+	let response = match middleware.handle(ctx, echo()).await.into_result() {
+		Ok(response) => response,
+		Err(e) => e.into_response(),
+	};
+	let status = response.status();
+	let body = response.into_body().collect().await.unwrap().to_bytes();
+	(status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn identity(scopes: &[&str], tier: Option<&str>) -> MurApiKeyIdentity {
+	let mut key = MurApiKey::new("svc", "secret").scopes(scopes.iter().copied());
+	key.tier = tier.map(str::to_string);
+	key.identity()
+}
+
+#[test]
+fn test_keys_store_only_the_secret_hash() {
+	assert_eq!(
+		MurApiKey::hash("abc"),
+		"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+	);
+
+	let (secret, key) = MurApiKey::generate("svc");
+	assert_eq!(key.key_hash, MurApiKey::hash(&secret));
+	assert!(!serde_json::to_string(&key).unwrap().contains(&secret));
+
+	let key = MurApiKey::from_hash("svc", "BA7816BF").expires_at(Utc::now());
+	assert_eq!(key.key_hash, "ba7816bf");
+	assert!(key.is_expired(Utc::now()));
+}
+
+#[tokio::test]
+async fn test_memory_store_replaces_and_revokes_keys() {
+	let store = MurMemoryApiKeyStore::new()
+		.with_key(MurApiKey::new("a", "one"))
+		.with_key(MurApiKey::new("b", "two"));
+	store.insert(MurApiKey::new("a", "rotated"));
+
+	assert_eq!(store.len(), 2);
+	assert!(store.find(&MurApiKey::hash("one")).await.unwrap().is_none());
+	let found = store.find(&MurApiKey::hash("rotated")).await.unwrap();
+	assert_eq!(found.unwrap().id, "a");
+
+	assert_eq!(store.remove("b").unwrap().id, "b");
+	assert!(store.find(&MurApiKey::hash("two")).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_file_store_reloads_when_the_file_changes() {
+	let path = std::env::temp_dir().join(format!("mur-api-keys-{}.json", uuid::Uuid::new_v4()));
+	let write = |keys: Vec<MurApiKey>, age: u64| {
+		std::fs::write(&path, serde_json::to_vec(&keys).unwrap()).unwrap();
+		let modified = std::time::SystemTime::now() - std::time::Duration::from_secs(age);
+		std::fs::File::options()
+			.write(true)
+			.open(&path)
+			.unwrap()
+			.set_modified(modified)
+			.unwrap();
+	};
+	let store = MurFileApiKeyStore::new(&path);
+
+	write(vec![MurApiKey::new("a", "one").scope("read")], 60);
+	let found = store.find(&MurApiKey::hash("one")).await.unwrap().unwrap();
+	assert_eq!(found.scopes, vec!["read"]);
+
+	write(vec![MurApiKey::new("b", "two")], 0);
+	assert!(store.find(&MurApiKey::hash("one")).await.unwrap().is_none());
+	assert!(store.find(&MurApiKey::hash("two")).await.unwrap().is_some());
+
+	std::fs::remove_file(&path).unwrap();
+	let missing = store.find(&MurApiKey::hash("two")).await.unwrap_err();
+	assert_eq!(missing.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_auth_attaches_known_unexpired_keys() {
+	let store = MurMemoryApiKeyStore::new()
+		.with_key(MurApiKey::new("live", "live-secret"))
+		.with_key(MurApiKey::new("old", "old-secret").expires_at(Utc::now() - Duration::hours(1)));
+	let auth = MurApiKeyAuth::new(store).query("api_key").cookie("api_key");
+
+	let (_, id) = run(&auth, ctx("/", &[("x-api-key", "live-secret")])).await;
+	assert_eq!(id, "live");
+	let (_, id) = run(&auth, ctx("/?api_key=live-secret", &[])).await;
+	assert_eq!(id, "live");
+	let (_, id) = run(&auth, ctx("/", &[("cookie", "api_key=live-secret")])).await;
+	assert_eq!(id, "live");
+
+	let (status, id) = run(&auth, ctx("/", &[("x-api-key", "old-secret")])).await;
+	assert_eq!((status, id.as_str()), (StatusCode::OK, ""));
+	let (_, id) = run(&auth, ctx("/", &[("x-api-key", "guess")])).await;
+	assert_eq!(id, "");
+
+	let header_only = MurApiKeyAuth::new(MurMemoryApiKeyStore::new())
+		.sources([MurApiKeySource::Header("Authorization".to_string())]);
+	let (_, id) = run(&header_only, ctx("/?api_key=live-secret", &[])).await;
+	assert_eq!(id, "");
+}
+
+#[tokio::test]
+async fn test_guards_check_key_and_route_scopes() {
+	let mut with_key = ctx("/", &[]);
+	with_key
+		.parts
+		.extensions
+		.insert(identity(&["orders:read"], None));
+	let mut admin = ctx("/", &[]);
+	admin.parts.extensions.insert(identity(&["*"], None));
+	let anonymous = ctx("/", &[]);
+
+	assert!(MurApiKeyGuard.check_can_activate(&with_key).await);
+	assert!(!MurApiKeyGuard.check_can_activate(&anonymous).await);
+	assert!(MurApiKeyScopeGuard.check_can_activate(&with_key).await);
+
+	let scopes = Arc::new(MurRouteMetadata::new().typed(MurApiKeyScopes::new(["orders:write"])));
+	for ctx in [&mut with_key, &mut admin] {
+		ctx.parts.extensions.insert(Arc::clone(&scopes));
+	}
+	assert!(!MurApiKeyScopeGuard.check_can_activate(&with_key).await);
+	assert!(MurApiKeyScopeGuard.check_can_activate(&admin).await);
+	assert!(!MurApiKeyScopeGuard.check_can_activate(&anonymous).await);
+
+	let rejection = MurApiKeyScopeGuard
+		.rejection_response()
+		.into_result()
+		.unwrap();
+	assert_eq!(rejection.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_throttler_counts_per_key_with_tier_quotas() {
+	let mut gold = ctx("/", &[]);
+	gold.parts.extensions.insert(identity(&[], Some("gold")));
+	assert_eq!(
+		MurThrottlerKey::ApiKey.extract(&gold).as_deref(),
+		Some("api_key:svc")
+	);
+	assert!(MurThrottlerKey::ApiKey.extract(&ctx("/", &[])).is_none());
+
+	let throttler = MurThrottler::new().requests(1).by_api_key().tier("gold", 2);
+	let mut statuses = Vec::new();
+	for _ in 0..3 {
+		statuses.push(run(&throttler, gold.clone()).await.0);
+	}
+	assert_eq!(
+		statuses,
+		[
+			StatusCode::OK,
+			StatusCode::OK,
+			StatusCode::TOO_MANY_REQUESTS
+		]
+	);
+}
@@ -0,0 +1,106 @@
+use super::MurCookieKeys;
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::http::response::{MurCookie, MurRes};
+use std::collections::HashMap;
+
+/// The cookies of a request, and those to set on its response.
+///
+/// Plain cookies are read as sent. Signed cookies can be read but not
+/// forged; encrypted cookies can be neither read nor forged by the client.
+/// Both need the server's [`MurCookieKeys`].
+///
+/// ```rust,ignore
+/// #[get("/prefs")]
+/// async fn prefs(&self, ctx: MurRequestContext) -> MurRes {
+///     let mut jar = ctx.cookie_jar();
+///     let theme = jar.get_signed("theme").unwrap_or_else(|| "light".into());
+///     if let Err(e) = jar.add_signed(MurCookie::new("theme", &theme).path("/")) {
+///         return e.into();
+///     }
+///     MurRes::ok(json!({ "theme": theme })).with_cookie_jar(&jar)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MurCookieJar {
+	incoming: HashMap<String, String>,
+	outgoing: Vec<MurCookie>,
+	keys: Option<MurCookieKeys>,
+}
+
+impl MurCookieJar {
+	pub fn new(cookies: HashMap<String, String>, keys: Option<MurCookieKeys>) -> Self {
+		Self {
+			incoming: cookies,
+			outgoing: Vec::new(),
+			keys,
+		}
+	}
+
+	/// The request's cookies, with the server's cookie keys.
+	pub fn from_ctx(ctx: &MurRequestContext) -> Self {
+		Self::new(ctx.cookies(), ctx.cookie_keys().cloned())
+	}
+
+	/// A cookie as sent by the client.
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.incoming.get(name).map(String::as_str)
+	}
+
+	/// The value of a signed cookie whose signature checks out.
+	pub fn get_signed(&self, name: &str) -> Option<String> {
+		self.keys.as_ref()?.verify(name, self.get(name)?)
+	}
+
+	/// The decrypted value of an encrypted cookie.
+	pub fn get_encrypted(&self, name: &str) -> Option<String> {
+		self.keys.as_ref()?.decrypt(name, self.get(name)?)
+	}
+
+	pub fn add(&mut self, cookie: MurCookie) {
+		self.outgoing.retain(|c| c.name != cookie.name);
+		self.outgoing.push(cookie);
+	}
+
+	/// Sets a cookie whose value the client can read but not change.
+	///
+	/// Fails when the server has no cookie keys.
+	pub fn add_signed(&mut self, mut cookie: MurCookie) -> Result<(), MurError> {
+		cookie.value = self.keys()?.sign(&cookie.name, &cookie.value);
+		self.add(cookie);
+		Ok(())
+	}
+
+	/// Sets a cookie whose value the client can neither read nor change.
+	///
+	/// Fails when the server has no cookie keys.
+	pub fn add_encrypted(&mut self, mut cookie: MurCookie) -> Result<(), MurError> {
+		cookie.value = self.keys()?.encrypt(&cookie.name, &cookie.value);
+		self.add(cookie);
+		Ok(())
+	}
+
+	/// Tells the client to delete the cookie set at path `/`.
+	pub fn remove(&mut self, name: &str) {
+		self.incoming.remove(name);
+		self.add(MurCookie::removal(name));
+	}
+
+	/// The cookies to set on the response.
+	pub fn delta(&self) -> &[MurCookie] {
+		&self.outgoing
+	}
+
+	/// Adds a `Set-Cookie` header per changed cookie to `res`.
+	pub fn apply(&self, res: MurRes) -> MurRes {
+		self.outgoing
+			.iter()
+			.fold(res, |res, cookie| res.with_cookie(cookie.clone()))
+	}
+
+	fn keys(&self) -> Result<&MurCookieKeys, MurError> {
+		self.keys.as_ref().ok_or_else(|| {
+			MurError::Internal("No cookie keys configured; see MurServer::cookie_keys".to_string())
+		})
+	}
+}
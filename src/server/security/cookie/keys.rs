@@ -0,0 +1,160 @@
+use crate::core::utils::{MurCodec, MurRand};
+use crate::server::config::{MurConfig, MurConfigError, MurConfigResult};
+use aws_lc_rs::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use aws_lc_rs::hmac;
+use std::sync::Arc;
+
+/// Keys signing and encrypting cookies.
+///
+/// The first key signs and encrypts; every key verifies and decrypts, so
+/// cookies written before a key rotation stay readable until they expire.
+/// Keys are derived from secrets of at least
+/// [`MIN_SECRET_LEN`](Self::MIN_SECRET_LEN) bytes.
+///
+/// ```rust,ignore
+/// let keys = MurCookieKeys::new(&current)?.with_previous(&retired)?;
+/// MurServer::new().cookie_keys(keys)
+/// ```
+#[derive(Clone)]
+pub struct MurCookieKeys {
+	keys: Vec<Arc<MurCookieKey>>,
+}
+
+struct MurCookieKey {
+	signing: hmac::Key,
+	encryption: LessSafeKey,
+}
+
+impl std::fmt::Debug for MurCookieKeys {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurCookieKeys")
+			.field("keys", &self.keys.len())
+			.finish()
+	}
+}
+
+impl MurCookieKeys {
+	pub const MIN_SECRET_LEN: usize = 32;
+
+	/// The configuration key holding the current secret.
+	pub const SECRET_KEY: &'static str = "COOKIE_SECRET";
+
+	/// The configuration key holding comma-separated retired secrets.
+	pub const PREVIOUS_SECRETS_KEY: &'static str = "COOKIE_SECRET_PREVIOUS";
+
+	pub fn new(secret: impl AsRef<[u8]>) -> MurConfigResult<Self> {
+		Ok(Self {
+			keys: vec![Arc::new(MurCookieKey::derive(secret.as_ref())?)],
+		})
+	}
+
+	/// Adds a retired secret, still accepted when reading cookies.
+	pub fn with_previous(mut self, secret: impl AsRef<[u8]>) -> MurConfigResult<Self> {
+		let key = MurCookieKey::derive(secret.as_ref())?;
+		self.keys.push(Arc::new(key));
+		Ok(self)
+	}
+
+	/// Reads the current secret from `COOKIE_SECRET` and retired ones from
+	/// `COOKIE_SECRET_PREVIOUS`.
+	pub fn from_config(config: &MurConfig) -> MurConfigResult<Self> {
+		let mut keys = Self::new(config.get_required(Self::SECRET_KEY)?)?;
+		for secret in config.get_list_or(Self::PREVIOUS_SECRETS_KEY, Vec::new()) {
+			keys = keys.with_previous(secret)?;
+		}
+		Ok(keys)
+	}
+
+	/// A fresh random secret suitable for `COOKIE_SECRET`.
+	pub fn generate_secret() -> String {
+		MurRand::secure_token(Self::MIN_SECRET_LEN)
+	}
+
+	/// `value` followed by a signature binding it to the cookie `name`.
+	pub fn sign(&self, name: &str, value: &str) -> String {
+		let tag = hmac::sign(&self.primary().signing, &signed_input(name, value));
+		format!("{}.{}", value, MurCodec::base64_url_encode(tag.as_ref()))
+	}
+
+	/// The value of a cookie written by [`sign`](Self::sign) with any of the
+	/// keys, or `None` when the signature does not match.
+	pub fn verify(&self, name: &str, signed: &str) -> Option<String> {
+		let (value, tag) = signed.rsplit_once('.')?;
+		let tag = MurCodec::base64_url_decode(tag).ok()?;
+		let input = signed_input(name, value);
+		self.keys
+			.iter()
+			.any(|key| hmac::verify(&key.signing, &input, &tag).is_ok())
+			.then(|| value.to_string())
+	}
+
+	/// `value` encrypted and authenticated with AES-256-GCM, bound to the
+	/// cookie `name`.
+	pub fn encrypt(&self, name: &str, value: &str) -> String {
+		let nonce: [u8; NONCE_LEN] = MurRand::secure_bytes(NONCE_LEN)
+			.try_into()
+			.expect("nonce length");
+		let mut sealed = value.as_bytes().to_vec();
+		self.primary()
+			.encryption
+			.seal_in_place_append_tag(
+				Nonce::assume_unique_for_key(nonce),
+				Aad::from(name.as_bytes()),
+				&mut sealed,
+			)
+			.expect("AES-GCM sealing cannot fail for cookie-sized values");
+
+		let mut out = nonce.to_vec();
+		out.extend_from_slice(&sealed);
+		MurCodec::base64_url_encode(&out)
+	}
+
+	/// The value of a cookie written by [`encrypt`](Self::encrypt) with any
+	/// of the keys, or `None` when it was tampered with.
+	pub fn decrypt(&self, name: &str, sealed: &str) -> Option<String> {
+		let sealed = MurCodec::base64_url_decode(sealed).ok()?;
+		if sealed.len() < NONCE_LEN {
+			return None;
+		}
+		let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+		self.keys.iter().find_map(|key| {
+			let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+			let mut in_out = ciphertext.to_vec();
+			let plain = key
+				.encryption
+				.open_in_place(nonce, Aad::from(name.as_bytes()), &mut in_out)
+				.ok()?;
+			String::from_utf8(plain.to_vec()).ok()
+		})
+	}
+
+	fn primary(&self) -> &MurCookieKey {
+		&self.keys[0]
+	}
+}
+
+impl MurCookieKey {
+	fn derive(secret: &[u8]) -> MurConfigResult<Self> {
+		if secret.len() < MurCookieKeys::MIN_SECRET_LEN {
+			return Err(MurConfigError::ValidationError(format!(
+				"cookie secrets must be at least {} bytes",
+				MurCookieKeys::MIN_SECRET_LEN
+			)));
+		}
+		let master = hmac::Key::new(hmac::HMAC_SHA256, secret);
+		let signing = hmac::sign(&master, b"murgamu.cookie.signing");
+		let encryption = hmac::sign(&master, b"murgamu.cookie.encryption");
+
+		let encryption = UnboundKey::new(&AES_256_GCM, encryption.as_ref())
+			.map_err(|_| MurConfigError::ValidationError("invalid cookie key".to_string()))?;
+		Ok(Self {
+			signing: hmac::Key::new(hmac::HMAC_SHA256, signing.as_ref()),
+			encryption: LessSafeKey::new(encryption),
+		})
+	}
+}
+
+fn signed_input(name: &str, value: &str) -> Vec<u8> {
+	format!("{}={}", name, value).into_bytes()
+}
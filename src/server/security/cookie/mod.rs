@@ -0,0 +1,8 @@
+mod jar;
+mod keys;
+
+pub use jar::MurCookieJar;
+pub use keys::MurCookieKeys;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,106 @@
+use super::*;
+use crate::server::http::response::MurCookie;
+use std::collections::HashMap;
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+const RETIRED: &str = "fedcba9876543210fedcba9876543210";
+
+#[test]
+fn test_signed_cookie_round_trip() {
+	let keys = MurCookieKeys::new(SECRET).unwrap();
+	let signed = keys.sign("theme", "dark");
+
+	assert!(signed.starts_with("dark."));
+	assert_eq!(keys.verify("theme", &signed), Some("dark".to_string()));
+	assert_eq!(keys.verify("mode", &signed), None);
+	assert_eq!(
+		keys.verify("theme", &signed.replacen("dark", "lite", 1)),
+		None
+	);
+	assert_eq!(keys.verify("theme", "dark"), None);
+}
+
+#[test]
+fn test_encrypted_cookie_round_trip() {
+	let keys = MurCookieKeys::new(SECRET).unwrap();
+	let sealed = keys.encrypt("cart", "42 items");
+
+	assert!(!sealed.contains("42 items"));
+	assert_ne!(sealed, keys.encrypt("cart", "42 items"));
+	assert_eq!(keys.decrypt("cart", &sealed), Some("42 items".to_string()));
+	assert_eq!(keys.decrypt("basket", &sealed), None);
+
+	let mut tampered = sealed.into_bytes();
+	let last = tampered.len() - 2;
+	tampered[last] = if tampered[last] == b'A' { b'B' } else { b'A' };
+	assert_eq!(
+		keys.decrypt("cart", &String::from_utf8(tampered).unwrap()),
+		None
+	);
+}
+
+#[test]
+fn test_rotated_keys_still_read_old_cookies() {
+	let old = MurCookieKeys::new(RETIRED).unwrap();
+	let signed = old.sign("theme", "dark");
+	let sealed = old.encrypt("cart", "3");
+
+	let rotated = MurCookieKeys::new(SECRET)
+		.unwrap()
+		.with_previous(RETIRED)
+		.unwrap();
+	assert_eq!(rotated.verify("theme", &signed), Some("dark".to_string()));
+	assert_eq!(rotated.decrypt("cart", &sealed), Some("3".to_string()));
+
+	let fresh = MurCookieKeys::new(SECRET).unwrap();
+	assert_eq!(fresh.verify("theme", &signed), None);
+	assert_eq!(
+		fresh.verify("theme", &rotated.sign("theme", "dark")),
+		Some("dark".to_string())
+	);
+}
+
+#[test]
+fn test_short_secret_is_rejected() {
+	assert!(MurCookieKeys::new("too short").is_err());
+	assert!(
+		MurCookieKeys::new(SECRET)
+			.unwrap()
+			.with_previous("short")
+			.is_err()
+	);
+	assert!(MurCookieKeys::generate_secret().len() >= MurCookieKeys::MIN_SECRET_LEN);
+}
+
+#[test]
+fn test_jar_reads_and_writes_cookies() {
+	let keys = MurCookieKeys::new(SECRET).unwrap();
+	let mut incoming = HashMap::new();
+	incoming.insert("plain".to_string(), "a".to_string());
+	incoming.insert("theme".to_string(), keys.sign("theme", "dark"));
+	incoming.insert("forged".to_string(), "dark.AAAA".to_string());
+
+	let mut jar = MurCookieJar::new(incoming, Some(keys));
+	assert_eq!(jar.get("plain"), Some("a"));
+	assert_eq!(jar.get_signed("theme"), Some("dark".to_string()));
+	assert_eq!(jar.get_signed("forged"), None);
+
+	jar.add(MurCookie::new("plain", "b"));
+	jar.add(MurCookie::new("plain", "c"));
+	jar.add_encrypted(MurCookie::new("cart", "3")).unwrap();
+	jar.remove("theme");
+
+	let delta = jar.delta();
+	assert_eq!(delta.len(), 3);
+	assert_eq!(delta[0].value, "c");
+	assert_ne!(delta[1].value, "3");
+	assert_eq!(delta[2].max_age, Some(0));
+	assert_eq!(jar.get("theme"), None);
+}
+
+#[test]
+fn test_jar_without_keys_refuses_signed_cookies() {
+	let mut jar = MurCookieJar::default();
+	assert!(jar.add_signed(MurCookie::new("theme", "dark")).is_err());
+	assert!(jar.delta().is_empty());
+}
@@ -1,4 +1,6 @@
+pub mod api_key;
 mod body;
+pub mod cookie;
 pub mod headers;
 pub mod tls;
 
@@ -12,7 +14,7 @@ pub use body::limited_body_extraction;
 // TODO: Implement authentication utilities?
 // mod auth;
 // pub use auth::{
-//     MurApiKeyAuth, MurBasicAuth, MurBearerAuth,
+//     MurBasicAuth, MurBearerAuth,
 //     MurAuthGuard, MurRoleGuard, MurPermissionGuard,
 // };
 
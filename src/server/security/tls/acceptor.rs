@@ -1,29 +1,57 @@
 use super::config::MurTlsConfig;
 use super::error::MurTlsError;
+use crate::server::logging::mur_log_line;
 use rustls::ServerConfig;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
 use tokio_rustls::TlsAcceptor;
 
+/// Accepts TLS connections with a rustls config that can be swapped while
+/// the server runs. Handshakes in flight and established connections keep
+/// the config they started with; new connections pick up the latest one.
+///
+/// Because the config can change under it, the acceptor is no longer a
+/// public `inner` field: [`inner`](Self::inner) returns a clone of the
+/// current one instead of a reference. See `CHANGELOG.md` for migrating.
 #[derive(Clone)]
 pub struct MurTlsAcceptor {
-	pub inner: TlsAcceptor,
+	shared: Arc<Shared>,
+}
+
+struct Shared {
+	current: RwLock<TlsAcceptor>,
+	config: Mutex<Option<MurTlsConfig>>,
 }
 
 impl MurTlsAcceptor {
 	pub fn new(config: &MurTlsConfig) -> Result<Self, MurTlsError> {
 		Ok(Self {
-			inner: config.build_acceptor()?,
+			shared: Arc::new(Shared {
+				current: RwLock::new(config.build_acceptor()?),
+				config: Mutex::new(Some(config.clone())),
+			}),
 		})
 	}
 
 	pub fn from_config(config: ServerConfig) -> Self {
 		Self {
-			inner: TlsAcceptor::from(Arc::new(config)),
+			shared: Arc::new(Shared {
+				current: RwLock::new(TlsAcceptor::from(Arc::new(config))),
+				config: Mutex::new(None),
+			}),
 		}
 	}
 
-	pub fn inner(&self) -> &TlsAcceptor {
-		&self.inner
+	/// The acceptor new connections are currently handed to. Later reloads
+	/// do not affect the returned clone.
+	pub fn inner(&self) -> TlsAcceptor {
+		self.shared
+			.current
+			.read()
+			.unwrap_or_else(|e| e.into_inner())
+			.clone()
 	}
 
 	pub async fn accept<S>(
@@ -33,7 +61,73 @@ impl MurTlsAcceptor {
 	where
 		S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
 	{
-		self.inner.accept(stream).await
+		self.inner().accept(stream).await
+	}
+
+	/// Swaps in a new rustls config for subsequent connections.
+	pub fn replace(&self, config: ServerConfig) {
+		*self
+			.shared
+			.current
+			.write()
+			.unwrap_or_else(|e| e.into_inner()) = TlsAcceptor::from(Arc::new(config));
+	}
+
+	/// Re-reads the certificate files of the [`MurTlsConfig`] this acceptor
+	/// was built from and swaps in the result. On error the running config
+	/// is kept.
+	pub fn reload(&self) -> Result<(), MurTlsError> {
+		let mut config = self.shared.config.lock().unwrap_or_else(|e| e.into_inner());
+		let Some(current) = config.as_ref() else {
+			return Err(MurTlsError::General(
+				"acceptor was not built from a MurTlsConfig".to_string(),
+			));
+		};
+
+		let fresh = current.reloaded()?;
+		self.replace(fresh.build_server_config()?);
+		*config = Some(fresh);
+		Ok(())
+	}
+
+	/// Starts the reload triggers the config asked for: polling the watched
+	/// files every `reload_interval`, and `SIGHUP`. The tasks stop on
+	/// shutdown or once every clone of the acceptor is gone.
+	pub(crate) fn spawn_reloader(&self, shutdown_rx: watch::Receiver<bool>) {
+		let (interval, sighup, paths) = {
+			let config = self.shared.config.lock().unwrap_or_else(|e| e.into_inner());
+			match config.as_ref() {
+				Some(c) => (c.reload_interval, c.reload_on_sighup, c.watched_paths()),
+				None => return,
+			}
+		};
+
+		if let Some(interval) = interval
+			&& !paths.is_empty()
+		{
+			let acceptor = Arc::downgrade(&self.shared);
+			let seen = modified_times(&paths);
+			let watch = watch_files(acceptor, paths, seen, interval, shutdown_rx.clone());
+			tokio::spawn(watch);
+		}
+
+		#[cfg(unix)]
+		if sighup {
+			use tokio::signal::unix::{SignalKind, signal};
+
+			match signal(SignalKind::hangup()) {
+				Ok(hangup) => {
+					let acceptor = Arc::downgrade(&self.shared);
+					tokio::spawn(reload_on_sighup(acceptor, hangup, shutdown_rx));
+				}
+				Err(e) => eprintln!(
+					"{}",
+					mur_log_line(format_args!("Failed to listen for SIGHUP: {}", e))
+				),
+			}
+		}
+		#[cfg(not(unix))]
+		let _ = sighup;
 	}
 }
 
@@ -42,3 +136,72 @@ impl std::fmt::Debug for MurTlsAcceptor {
 		f.debug_struct("MurTlsAcceptor").finish()
 	}
 }
+
+fn reload_weak(shared: &Weak<Shared>, reason: &str) -> bool {
+	let Some(shared) = shared.upgrade() else {
+		return false;
+	};
+	match (MurTlsAcceptor { shared }).reload() {
+		Ok(()) => println!(
+			"{}",
+			mur_log_line(format_args!("TLS certificates reloaded ({})", reason))
+		),
+		Err(e) => eprintln!(
+			"{}",
+			mur_log_line(format_args!(
+				"TLS reload failed, keeping current certificates: {}",
+				e
+			))
+		),
+	}
+	true
+}
+
+fn modified_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+	paths
+		.iter()
+		.map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+		.collect()
+}
+
+async fn watch_files(
+	shared: Weak<Shared>,
+	paths: Vec<PathBuf>,
+	mut seen: Vec<Option<SystemTime>>,
+	interval: Duration,
+	mut shutdown_rx: watch::Receiver<bool>,
+) {
+	loop {
+		tokio::select! {
+			_ = tokio::time::sleep(interval) => {}
+			_ = shutdown_rx.changed() => return,
+		}
+
+		let modified = modified_times(&paths);
+		if modified == seen {
+			continue;
+		}
+		seen = modified;
+		if !reload_weak(&shared, "files changed") {
+			return;
+		}
+	}
+}
+
+#[cfg(unix)]
+async fn reload_on_sighup(
+	shared: Weak<Shared>,
+	mut hangup: tokio::signal::unix::Signal,
+	mut shutdown_rx: watch::Receiver<bool>,
+) {
+	loop {
+		tokio::select! {
+			received = hangup.recv() => {
+				if received.is_none() || !reload_weak(&shared, "SIGHUP") {
+					return;
+				}
+			}
+			_ = shutdown_rx.changed() => return,
+		}
+	}
+}
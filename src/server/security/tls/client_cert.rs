@@ -0,0 +1,153 @@
+use crate::MurError;
+use crate::server::http::MurRequestContext;
+use aws_lc_rs::digest::{SHA256, digest};
+use chrono::{DateTime, Utc};
+use rustls::pki_types::CertificateDer;
+use std::net::IpAddr;
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::FromDer;
+
+/// The certificate a client presented during a mutual TLS handshake.
+///
+/// The server only attaches certificates that chained to one of the
+/// configured client CAs, so every field here has been verified.
+#[derive(Debug, Clone)]
+pub struct MurClientCert {
+	/// The presented chain in DER form, leaf first.
+	pub chain: Vec<CertificateDer<'static>>,
+	/// The subject distinguished name, e.g. `CN=billing, O=Acme`.
+	pub subject: String,
+	pub common_name: Option<String>,
+	pub issuer: String,
+	/// The serial number as colon-separated hex.
+	pub serial: String,
+	pub dns_names: Vec<String>,
+	pub uris: Vec<String>,
+	pub emails: Vec<String>,
+	pub ip_addresses: Vec<IpAddr>,
+	pub not_before: DateTime<Utc>,
+	pub not_after: DateTime<Utc>,
+	/// Lowercase hex SHA-256 of the leaf certificate.
+	pub fingerprint: String,
+}
+
+impl MurClientCert {
+	/// Parses the leaf of `chain`, returning `None` for an empty chain or
+	/// a leaf that is not valid X.509.
+	pub fn from_chain(chain: &[CertificateDer<'_>]) -> Option<Self> {
+		let leaf = chain.first()?;
+		let (_, cert) = X509Certificate::from_der(leaf).ok()?;
+
+		let mut dns_names = Vec::new();
+		let mut uris = Vec::new();
+		let mut emails = Vec::new();
+		let mut ip_addresses = Vec::new();
+		if let Ok(Some(san)) = cert.subject_alternative_name() {
+			for name in &san.value.general_names {
+				match name {
+					GeneralName::DNSName(name) => dns_names.push(name.to_string()),
+					GeneralName::URI(uri) => uris.push(uri.to_string()),
+					GeneralName::RFC822Name(email) => emails.push(email.to_string()),
+					GeneralName::IPAddress(bytes) => {
+						if let Some(ip) = ip_from_bytes(bytes) {
+							ip_addresses.push(ip);
+						}
+					}
+					_ => {}
+				}
+			}
+		}
+
+		let validity = cert.validity();
+		Some(Self {
+			chain: chain.iter().map(|c| c.clone().into_owned()).collect(),
+			subject: cert.subject().to_string(),
+			common_name: cert
+				.subject()
+				.iter_common_name()
+				.next()
+				.and_then(|cn| cn.as_str().ok())
+				.map(str::to_string),
+			issuer: cert.issuer().to_string(),
+			serial: cert.raw_serial_as_string(),
+			dns_names,
+			uris,
+			emails,
+			ip_addresses,
+			not_before: DateTime::from_timestamp(validity.not_before.timestamp(), 0)?,
+			not_after: DateTime::from_timestamp(validity.not_after.timestamp(), 0)?,
+			fingerprint: digest(&SHA256, leaf)
+				.as_ref()
+				.iter()
+				.map(|b| format!("{:02x}", b))
+				.collect(),
+		})
+	}
+
+	/// The certificate attached to a mutual TLS connection.
+	///
+	/// Fails with `401 Unauthorized` when the client presented none.
+	pub fn from_ctx(ctx: &MurRequestContext) -> Result<&Self, MurError> {
+		ctx.client_cert()
+			.ok_or_else(|| MurError::unauthorized("A client certificate is required"))
+	}
+
+	/// The leaf certificate in DER form.
+	pub fn leaf(&self) -> &CertificateDer<'static> {
+		&self.chain[0]
+	}
+
+	/// Every subject alternative name, rendered as a string.
+	pub fn sans(&self) -> Vec<String> {
+		self.dns_names
+			.iter()
+			.chain(&self.uris)
+			.chain(&self.emails)
+			.cloned()
+			.chain(self.ip_addresses.iter().map(IpAddr::to_string))
+			.collect()
+	}
+
+	/// Whether any subject alternative name matches `pattern`, where `*`
+	/// stands for any run of characters.
+	pub fn matches_san(&self, pattern: &str) -> bool {
+		self.sans().iter().any(|san| glob_match(pattern, san))
+	}
+
+	/// Whether the common name or the full subject matches `pattern`, where
+	/// `*` stands for any run of characters.
+	pub fn matches_subject(&self, pattern: &str) -> bool {
+		self.common_name
+			.as_deref()
+			.is_some_and(|cn| glob_match(pattern, cn))
+			|| glob_match(pattern, &self.subject)
+	}
+}
+
+fn ip_from_bytes(bytes: &[u8]) -> Option<IpAddr> {
+	match bytes.len() {
+		4 => <[u8; 4]>::try_from(bytes).ok().map(IpAddr::from),
+		16 => <[u8; 16]>::try_from(bytes).ok().map(IpAddr::from),
+		_ => None,
+	}
+}
+
+fn glob_match(pattern: &str, value: &str) -> bool {
+	let Some((head, rest)) = pattern.split_once('*') else {
+		return pattern == value;
+	};
+	let Some(mut value) = value.strip_prefix(head) else {
+		return false;
+	};
+
+	let mut parts: Vec<&str> = rest.split('*').collect();
+	let tail = parts.pop().unwrap_or_default();
+	for part in parts {
+		match value.find(part) {
+			Some(at) => value = &value[at + part.len()..],
+			None => return false,
+		}
+	}
+	value.ends_with(tail)
+}
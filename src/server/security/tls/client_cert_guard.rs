@@ -0,0 +1,80 @@
+use crate::MurInjects;
+use crate::server::aliases::MurRes;
+use crate::server::guard::{MurGuard, MurGuardFactory, MurGuardFuture};
+use crate::server::http::{MurHttpResponse, MurRequestContext};
+use crate::server::service::MurServiceContainer;
+use std::any::Any;
+
+/// Route metadata listing the client certificates [`MurClientCertGuard`]
+/// admits. A certificate is admitted when any SAN pattern matches one of
+/// its subject alternative names or any subject pattern matches its common
+/// name or distinguished name; `*` stands for any run of characters.
+///
+/// ```rust,ignore
+/// #[post("/ledger")]
+/// #[use_guards(MurClientCertGuard)]
+/// #[set_metadata(MurClientCertPatterns::new().san("spiffe://prod/billing/*"))]
+/// async fn append(&self, ctx: MurRequestContext) -> MurRes { .. }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MurClientCertPatterns {
+	pub sans: Vec<String>,
+	pub subjects: Vec<String>,
+}
+
+impl MurClientCertPatterns {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn san(mut self, pattern: impl Into<String>) -> Self {
+		self.sans.push(pattern.into());
+		self
+	}
+
+	pub fn subject(mut self, pattern: impl Into<String>) -> Self {
+		self.subjects.push(pattern.into());
+		self
+	}
+}
+
+/// Admits requests whose connection presented a verified client
+/// certificate matching the route's [`MurClientCertPatterns`]. Routes
+/// without patterns admit any verified certificate; requests without one
+/// are answered with `403 Forbidden`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MurClientCertGuard;
+
+impl MurGuard for MurClientCertGuard {
+	fn check_can_activate<'a>(&'a self, ctx: &'a MurRequestContext) -> MurGuardFuture<'a> {
+		let allowed = ctx.client_cert().is_some_and(|cert| {
+			ctx.route_metadata::<MurClientCertPatterns>()
+				.is_none_or(|patterns| {
+					patterns.sans.iter().any(|p| cert.matches_san(p))
+						|| patterns.subjects.iter().any(|p| cert.matches_subject(p))
+				})
+		});
+		Box::pin(async move { allowed })
+	}
+
+	fn rejection_response(&self) -> MurRes {
+		MurHttpResponse::forbidden().json(serde_json::json!({
+			"error": "Forbidden",
+			"message": "The client certificate is not allowed"
+		}))
+	}
+
+	fn name(&self) -> &str {
+		"MurClientCertGuard"
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl MurGuardFactory for MurClientCertGuard {
+	fn __create_factory(_injects: &MurInjects, _container: &MurServiceContainer) -> Self {
+		Self
+	}
+}
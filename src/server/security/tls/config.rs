@@ -1,23 +1,37 @@
 use super::MurTlsLoader;
 use super::config_builder::MurTlsConfigBuilder;
 use super::error::MurTlsError;
+use super::sni::{MurSniCert, MurSniResolver};
 use super::version::MurTlsVersion;
-use rustls::ServerConfig;
+use rustls::crypto::CryptoProvider;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
-use std::path::Path;
+use rustls::server::WebPkiClientVerifier;
+use rustls::server::danger::ClientCertVerifier;
+use rustls::sign::CertifiedKey;
+use rustls::{RootCertStore, ServerConfig};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio_rustls::TlsAcceptor;
 
 pub struct MurTlsConfig {
 	pub(crate) certs: Vec<CertificateDer<'static>>,
 	pub(crate) key_der: Vec<u8>,
+	pub(crate) files: Option<(PathBuf, PathBuf)>,
+	pub(crate) sni: BTreeMap<String, MurSniCert>,
 	pub min_version: MurTlsVersion,
 	pub max_version: MurTlsVersion,
 	pub alpn_protocols: Vec<Vec<u8>>,
 	pub client_auth: bool,
+	pub client_auth_optional: bool,
 	pub(crate) client_ca_certs: Option<Vec<CertificateDer<'static>>>,
+	pub(crate) client_ca_path: Option<PathBuf>,
 	pub session_resumption: bool,
 	pub ocsp_response: Option<Vec<u8>>,
+	pub ocsp_response_path: Option<PathBuf>,
+	pub reload_interval: Option<Duration>,
+	pub reload_on_sighup: bool,
 }
 
 impl Clone for MurTlsConfig {
@@ -25,13 +39,20 @@ impl Clone for MurTlsConfig {
 		Self {
 			certs: self.certs.clone(),
 			key_der: self.key_der.clone(),
+			files: self.files.clone(),
+			sni: self.sni.clone(),
 			min_version: self.min_version,
 			max_version: self.max_version,
 			alpn_protocols: self.alpn_protocols.clone(),
 			client_auth: self.client_auth,
+			client_auth_optional: self.client_auth_optional,
 			client_ca_certs: self.client_ca_certs.clone(),
+			client_ca_path: self.client_ca_path.clone(),
 			session_resumption: self.session_resumption,
 			ocsp_response: self.ocsp_response.clone(),
+			ocsp_response_path: self.ocsp_response_path.clone(),
+			reload_interval: self.reload_interval,
+			reload_on_sighup: self.reload_on_sighup,
 		}
 	}
 }
@@ -40,11 +61,15 @@ impl std::fmt::Debug for MurTlsConfig {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		f.debug_struct("MurTlsConfig")
 			.field("certs_count", &self.certs.len())
+			.field("sni_hosts", &self.sni.keys().collect::<Vec<_>>())
 			.field("min_version", &self.min_version)
 			.field("max_version", &self.max_version)
 			.field("alpn_protocols", &self.alpn_protocols.len())
 			.field("client_auth", &self.client_auth)
+			.field("client_auth_optional", &self.client_auth_optional)
 			.field("session_resumption", &self.session_resumption)
+			.field("reload_interval", &self.reload_interval)
+			.field("reload_on_sighup", &self.reload_on_sighup)
 			.finish()
 	}
 }
@@ -54,20 +79,30 @@ impl MurTlsConfig {
 		cert_path: impl AsRef<Path>,
 		key_path: impl AsRef<Path>,
 	) -> Result<Self, MurTlsError> {
-		let certs = MurTlsLoader::certs(cert_path)?;
-		let key = MurTlsLoader::private_key(key_path)?;
+		let certs = MurTlsLoader::certs(&cert_path)?;
+		let key = MurTlsLoader::private_key(&key_path)?;
 		let key_der = key.secret_der().to_vec();
 
 		Ok(Self {
 			certs,
 			key_der,
+			files: Some((
+				cert_path.as_ref().to_path_buf(),
+				key_path.as_ref().to_path_buf(),
+			)),
+			sni: BTreeMap::new(),
 			min_version: MurTlsVersion::Tls12,
 			max_version: MurTlsVersion::Tls13,
 			alpn_protocols: vec![b"h2".to_vec(), b"http/1.1".to_vec()],
 			client_auth: false,
+			client_auth_optional: false,
 			client_ca_certs: None,
+			client_ca_path: None,
 			session_resumption: true,
 			ocsp_response: None,
+			ocsp_response_path: None,
+			reload_interval: None,
+			reload_on_sighup: false,
 		})
 	}
 
@@ -79,13 +114,20 @@ impl MurTlsConfig {
 		Ok(Self {
 			certs,
 			key_der,
+			files: None,
+			sni: BTreeMap::new(),
 			min_version: MurTlsVersion::Tls12,
 			max_version: MurTlsVersion::Tls13,
 			alpn_protocols: vec![b"h2".to_vec(), b"http/1.1".to_vec()],
 			client_auth: false,
+			client_auth_optional: false,
 			client_ca_certs: None,
+			client_ca_path: None,
 			session_resumption: true,
 			ocsp_response: None,
+			ocsp_response_path: None,
+			reload_interval: None,
+			reload_on_sighup: false,
 		})
 	}
 
@@ -96,13 +138,20 @@ impl MurTlsConfig {
 		Ok(Self {
 			certs,
 			key_der: key_der_bytes,
+			files: None,
+			sni: BTreeMap::new(),
 			min_version: MurTlsVersion::Tls12,
 			max_version: MurTlsVersion::Tls13,
 			alpn_protocols: vec![b"h2".to_vec(), b"http/1.1".to_vec()],
 			client_auth: false,
+			client_auth_optional: false,
 			client_ca_certs: None,
+			client_ca_path: None,
 			session_resumption: true,
 			ocsp_response: None,
+			ocsp_response_path: None,
+			reload_interval: None,
+			reload_on_sighup: false,
 		})
 	}
 
@@ -135,32 +184,216 @@ impl MurTlsConfig {
 	}
 
 	pub fn client_ca_certs_path(mut self, path: impl AsRef<Path>) -> Result<Self, MurTlsError> {
-		self.client_ca_certs = Some(MurTlsLoader::certs(path)?);
+		self.client_ca_certs = Some(MurTlsLoader::certs(&path)?);
+		self.client_ca_path = Some(path.as_ref().to_path_buf());
 		self.client_auth = true;
 		Ok(self)
 	}
 
+	pub fn client_ca_certs_pem(mut self, pem: &str) -> Result<Self, MurTlsError> {
+		self.client_ca_certs = Some(MurTlsLoader::certs_from_pem(pem)?);
+		self.client_auth = true;
+		Ok(self)
+	}
+
+	/// Requests a client certificate without requiring one. Connections
+	/// that present none are accepted, and guards such as
+	/// `MurClientCertGuard` decide per route.
+	pub fn client_auth_optional(mut self, enabled: bool) -> Self {
+		self.client_auth_optional = enabled;
+		self
+	}
+
 	pub fn session_resumption(mut self, enabled: bool) -> Self {
 		self.session_resumption = enabled;
 		self
 	}
 
+	/// Staples a DER OCSP response on the default certificate. SNI
+	/// certificates take their own with
+	/// [`sni_ocsp_response`](Self::sni_ocsp_response).
 	pub fn ocsp_response(mut self, response: Vec<u8>) -> Self {
 		self.ocsp_response = Some(response);
 		self
 	}
 
+	/// Staples the DER OCSP response stored at `path` on the default
+	/// certificate, re-reading it on every reload so a cron job can refresh
+	/// it in place.
+	pub fn ocsp_response_path(mut self, path: impl AsRef<Path>) -> Result<Self, MurTlsError> {
+		self.ocsp_response = Some(std::fs::read(&path).map_err(MurTlsError::OcspRead)?);
+		self.ocsp_response_path = Some(path.as_ref().to_path_buf());
+		Ok(self)
+	}
+
+	/// Serves `hostname` with its own certificate, selected by SNI. A
+	/// `*.example.com` hostname matches any single label; clients asking
+	/// for other names get the default certificate.
+	pub fn sni_cert_files(
+		mut self,
+		hostname: &str,
+		cert_path: impl AsRef<Path>,
+		key_path: impl AsRef<Path>,
+	) -> Result<Self, MurTlsError> {
+		let cert = MurSniCert::from_files(cert_path, key_path)?;
+		self.sni.insert(hostname.to_ascii_lowercase(), cert);
+		Ok(self)
+	}
+
+	pub fn sni_cert_pem(
+		mut self,
+		hostname: &str,
+		cert_pem: &str,
+		key_pem: &str,
+	) -> Result<Self, MurTlsError> {
+		let cert = MurSniCert::from_pem(cert_pem, key_pem)?;
+		self.sni.insert(hostname.to_ascii_lowercase(), cert);
+		Ok(self)
+	}
+
+	/// Staples a DER OCSP response on the certificate added for `hostname`
+	/// with [`sni_cert_files`](Self::sni_cert_files) or
+	/// [`sni_cert_pem`](Self::sni_cert_pem).
+	pub fn sni_ocsp_response(
+		mut self,
+		hostname: &str,
+		response: Vec<u8>,
+	) -> Result<Self, MurTlsError> {
+		self.sni_cert_mut(hostname)?.ocsp = Some(response);
+		Ok(self)
+	}
+
+	/// Like [`sni_ocsp_response`](Self::sni_ocsp_response), reading the
+	/// response from `path` and again on every reload.
+	pub fn sni_ocsp_response_path(
+		mut self,
+		hostname: &str,
+		path: impl AsRef<Path>,
+	) -> Result<Self, MurTlsError> {
+		let cert = self.sni_cert_mut(hostname)?;
+		cert.ocsp = Some(std::fs::read(&path).map_err(MurTlsError::OcspRead)?);
+		cert.ocsp_path = Some(path.as_ref().to_path_buf());
+		Ok(self)
+	}
+
+	fn sni_cert_mut(&mut self, hostname: &str) -> Result<&mut MurSniCert, MurTlsError> {
+		self.sni
+			.get_mut(&hostname.to_ascii_lowercase())
+			.ok_or_else(|| MurTlsError::ConfigBuild(format!("no SNI certificate for {}", hostname)))
+	}
+
+	/// Polls the certificate, key, client CA and OCSP files every
+	/// `interval` and reloads when any of them changes.
+	pub fn reload_interval(mut self, interval: Duration) -> Self {
+		self.reload_interval = Some(interval);
+		self
+	}
+
+	/// Reloads the certificate files when the process receives `SIGHUP`.
+	/// Ignored on platforms without Unix signals.
+	pub fn reload_on_sighup(mut self, enabled: bool) -> Self {
+		self.reload_on_sighup = enabled;
+		self
+	}
+
+	/// A copy of this config with every file-backed certificate, key, client
+	/// CA and OCSP response read again from disk.
+	pub fn reloaded(&self) -> Result<Self, MurTlsError> {
+		let mut fresh = self.clone();
+		if let Some((cert_path, key_path)) = &self.files {
+			fresh.certs = MurTlsLoader::certs(cert_path)?;
+			fresh.key_der = MurTlsLoader::private_key(key_path)?.secret_der().to_vec();
+		}
+		if let Some(path) = &self.client_ca_path {
+			fresh.client_ca_certs = Some(MurTlsLoader::certs(path)?);
+		}
+		if let Some(path) = &self.ocsp_response_path {
+			fresh.ocsp_response = Some(std::fs::read(path).map_err(MurTlsError::OcspRead)?);
+		}
+		for (hostname, cert) in &self.sni {
+			fresh.sni.insert(hostname.clone(), cert.reloaded()?);
+		}
+		Ok(fresh)
+	}
+
+	/// The files [`reloaded`](Self::reloaded) reads.
+	pub fn watched_paths(&self) -> Vec<PathBuf> {
+		let mut paths = Vec::new();
+		if let Some((cert_path, key_path)) = &self.files {
+			paths.extend([cert_path.clone(), key_path.clone()]);
+		}
+		paths.extend(self.client_ca_path.clone());
+		paths.extend(self.ocsp_response_path.clone());
+		for cert in self.sni.values() {
+			if let Some((cert_path, key_path)) = &cert.files {
+				paths.extend([cert_path.clone(), key_path.clone()]);
+			}
+			paths.extend(cert.ocsp_path.clone());
+		}
+		paths
+	}
+
 	pub fn build_server_config(&self) -> Result<ServerConfig, MurTlsError> {
-		let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(self.key_der.clone()));
-		let mut config = ServerConfig::builder()
-			.with_no_client_auth()
-			.with_single_cert(self.certs.clone(), key)
-			.map_err(|e| MurTlsError::ConfigBuild(e.to_string()))?;
+		let builder = ServerConfig::builder();
+		let builder = match self.client_verifier()? {
+			Some(verifier) => builder.with_client_cert_verifier(verifier),
+			None => builder.with_no_client_auth(),
+		};
+		let resolver = self.cert_resolver(builder.crypto_provider())?;
+		let mut config = builder.with_cert_resolver(Arc::new(resolver));
 
 		config.alpn_protocols = self.alpn_protocols.clone();
 		Ok(config)
 	}
 
+	pub(crate) fn cert_resolver(
+		&self,
+		provider: &CryptoProvider,
+	) -> Result<MurSniResolver, MurTlsError> {
+		let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(self.key_der.clone()));
+		let mut default = CertifiedKey::from_der(self.certs.clone(), key, provider)
+			.map_err(|e| MurTlsError::ConfigBuild(e.to_string()))?;
+		default.ocsp = self.ocsp_response.clone();
+
+		let mut hosts = HashMap::new();
+		for (hostname, cert) in &self.sni {
+			hosts.insert(hostname.clone(), Arc::new(cert.certified_key(provider)?));
+		}
+		Ok(MurSniResolver {
+			default: Arc::new(default),
+			hosts,
+		})
+	}
+
+	pub(crate) fn client_verifier(
+		&self,
+	) -> Result<Option<Arc<dyn ClientCertVerifier>>, MurTlsError> {
+		if !self.client_auth {
+			return Ok(None);
+		}
+		let Some(ca_certs) = &self.client_ca_certs else {
+			return Err(MurTlsError::ConfigBuild(
+				"client authentication requires client CA certificates".to_string(),
+			));
+		};
+
+		let mut roots = RootCertStore::empty();
+		for cert in ca_certs {
+			roots
+				.add(cert.clone())
+				.map_err(|e| MurTlsError::CertificateParse(e.to_string()))?;
+		}
+
+		let mut builder = WebPkiClientVerifier::builder(Arc::new(roots));
+		if self.client_auth_optional {
+			builder = builder.allow_unauthenticated();
+		}
+		builder
+			.build()
+			.map(Some)
+			.map_err(|e| MurTlsError::ConfigBuild(e.to_string()))
+	}
+
 	pub fn build_acceptor(&self) -> Result<TlsAcceptor, MurTlsError> {
 		let config = self.build_server_config()?;
 		Ok(TlsAcceptor::from(Arc::new(config)))
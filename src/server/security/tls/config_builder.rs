@@ -3,6 +3,9 @@ use super::error::MurTlsError;
 use super::loader::MurTlsLoader;
 use super::version::MurTlsVersion;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Default)]
 pub struct MurTlsConfigBuilder {
@@ -14,9 +17,16 @@ pub struct MurTlsConfigBuilder {
 	pub max_version: MurTlsVersion,
 	pub alpn_protocols: Vec<Vec<u8>>,
 	pub client_auth: bool,
+	pub client_auth_optional: bool,
 	pub client_ca_path: Option<String>,
+	pub client_ca_pem: Option<String>,
 	pub session_resumption: bool,
 	pub ocsp_response: Option<Vec<u8>>,
+	pub ocsp_response_path: Option<String>,
+	pub sni_cert_paths: Vec<(String, String, String)>,
+	pub sni_ocsp_response_paths: Vec<(String, String)>,
+	pub reload_interval: Option<Duration>,
+	pub reload_on_sighup: bool,
 }
 
 impl MurTlsConfigBuilder {
@@ -30,9 +40,16 @@ impl MurTlsConfigBuilder {
 			max_version: MurTlsVersion::Tls13,
 			alpn_protocols: vec![b"h2".to_vec(), b"http/1.1".to_vec()],
 			client_auth: false,
+			client_auth_optional: false,
 			client_ca_path: None,
+			client_ca_pem: None,
 			session_resumption: true,
 			ocsp_response: None,
+			ocsp_response_path: None,
+			sni_cert_paths: Vec::new(),
+			sni_ocsp_response_paths: Vec::new(),
+			reload_interval: None,
+			reload_on_sighup: false,
 		}
 	}
 
@@ -96,6 +113,17 @@ impl MurTlsConfigBuilder {
 		self
 	}
 
+	pub fn client_ca_pem(mut self, pem: impl Into<String>) -> Self {
+		self.client_ca_pem = Some(pem.into());
+		self.client_auth = true;
+		self
+	}
+
+	pub fn client_auth_optional(mut self, enabled: bool) -> Self {
+		self.client_auth_optional = enabled;
+		self
+	}
+
 	pub fn session_resumption(mut self, enabled: bool) -> Self {
 		self.session_resumption = enabled;
 		self
@@ -106,6 +134,42 @@ impl MurTlsConfigBuilder {
 		self
 	}
 
+	pub fn ocsp_response_path(mut self, path: impl Into<String>) -> Self {
+		self.ocsp_response_path = Some(path.into());
+		self
+	}
+
+	pub fn sni_cert_path(
+		mut self,
+		hostname: impl Into<String>,
+		cert_path: impl Into<String>,
+		key_path: impl Into<String>,
+	) -> Self {
+		self.sni_cert_paths
+			.push((hostname.into(), cert_path.into(), key_path.into()));
+		self
+	}
+
+	pub fn sni_ocsp_response_path(
+		mut self,
+		hostname: impl Into<String>,
+		path: impl Into<String>,
+	) -> Self {
+		self.sni_ocsp_response_paths
+			.push((hostname.into(), path.into()));
+		self
+	}
+
+	pub fn reload_interval(mut self, interval: Duration) -> Self {
+		self.reload_interval = Some(interval);
+		self
+	}
+
+	pub fn reload_on_sighup(mut self, enabled: bool) -> Self {
+		self.reload_on_sighup = enabled;
+		self
+	}
+
 	pub fn build(self) -> Result<MurTlsConfig, MurTlsError> {
 		let (certs, key): (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>) =
 			if let (Some(cert_path), Some(key_path)) = (&self.cert_path, &self.key_path) {
@@ -127,20 +191,45 @@ impl MurTlsConfigBuilder {
 		let key_der: Vec<u8> = key.secret_der().to_vec();
 		let client_ca_certs = if let Some(ca_path) = &self.client_ca_path {
 			Some(MurTlsLoader::certs(ca_path)?)
+		} else if let Some(ca_pem) = &self.client_ca_pem {
+			Some(MurTlsLoader::certs_from_pem(ca_pem)?)
 		} else {
 			None
 		};
 
-		Ok(MurTlsConfig {
+		let files = self
+			.cert_path
+			.as_ref()
+			.zip(self.key_path.as_ref())
+			.map(|(cert_path, key_path)| (PathBuf::from(cert_path), PathBuf::from(key_path)));
+
+		let mut config = MurTlsConfig {
 			certs,
 			key_der,
+			files,
+			sni: BTreeMap::new(),
 			min_version: self.min_version,
 			max_version: self.max_version,
 			alpn_protocols: self.alpn_protocols,
 			client_auth: self.client_auth,
+			client_auth_optional: self.client_auth_optional,
 			client_ca_certs,
+			client_ca_path: self.client_ca_path.map(PathBuf::from),
 			session_resumption: self.session_resumption,
 			ocsp_response: self.ocsp_response,
-		})
+			ocsp_response_path: None,
+			reload_interval: self.reload_interval,
+			reload_on_sighup: self.reload_on_sighup,
+		};
+		if let Some(path) = &self.ocsp_response_path {
+			config = config.ocsp_response_path(path)?;
+		}
+		for (hostname, cert_path, key_path) in &self.sni_cert_paths {
+			config = config.sni_cert_files(hostname, cert_path, key_path)?;
+		}
+		for (hostname, path) in &self.sni_ocsp_response_paths {
+			config = config.sni_ocsp_response_path(hostname, path)?;
+		}
+		Ok(config)
 	}
 }
@@ -9,6 +9,7 @@ pub enum MurTlsError {
 	NoPrivateKey,
 	NoCertificates,
 	ConfigBuild(String),
+	OcspRead(std::io::Error),
 	InvalidVersion(String),
 	General(String),
 }
@@ -23,6 +24,7 @@ impl std::fmt::Display for MurTlsError {
 			MurTlsError::NoPrivateKey => write!(f, "No private key found in file"),
 			MurTlsError::NoCertificates => write!(f, "No certificates found in file"),
 			MurTlsError::ConfigBuild(e) => write!(f, "Failed to build TLS config: {}", e),
+			MurTlsError::OcspRead(e) => write!(f, "Failed to read OCSP response: {}", e),
 			MurTlsError::InvalidVersion(e) => write!(f, "Invalid TLS version: {}", e),
 			MurTlsError::General(e) => write!(f, "TLS error: {}", e),
 		}
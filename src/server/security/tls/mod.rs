@@ -1,11 +1,16 @@
 mod acceptor;
+mod client_cert;
+mod client_cert_guard;
 mod config;
 mod config_builder;
 mod error;
 mod loader;
+mod sni;
 mod version;
 
 pub use acceptor::MurTlsAcceptor;
+pub use client_cert::MurClientCert;
+pub use client_cert_guard::{MurClientCertGuard, MurClientCertPatterns};
 pub use config::MurTlsConfig;
 pub use config_builder::MurTlsConfigBuilder;
 pub use error::MurTlsError;
@@ -0,0 +1,119 @@
+use super::MurTlsLoader;
+use super::error::MurTlsError;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A certificate chain, key and optional OCSP staple served for one SNI
+/// hostname, remembering the files they came from so reloads can pick up
+/// rotated material.
+pub(crate) struct MurSniCert {
+	pub(crate) certs: Vec<CertificateDer<'static>>,
+	pub(crate) key: PrivateKeyDer<'static>,
+	pub(crate) files: Option<(PathBuf, PathBuf)>,
+	pub(crate) ocsp: Option<Vec<u8>>,
+	pub(crate) ocsp_path: Option<PathBuf>,
+}
+
+impl Clone for MurSniCert {
+	fn clone(&self) -> Self {
+		Self {
+			certs: self.certs.clone(),
+			key: self.key.clone_key(),
+			files: self.files.clone(),
+			ocsp: self.ocsp.clone(),
+			ocsp_path: self.ocsp_path.clone(),
+		}
+	}
+}
+
+impl MurSniCert {
+	pub(crate) fn from_files(
+		cert_path: impl AsRef<Path>,
+		key_path: impl AsRef<Path>,
+	) -> Result<Self, MurTlsError> {
+		Ok(Self {
+			certs: MurTlsLoader::certs(&cert_path)?,
+			key: MurTlsLoader::private_key(&key_path)?,
+			files: Some((
+				cert_path.as_ref().to_path_buf(),
+				key_path.as_ref().to_path_buf(),
+			)),
+			ocsp: None,
+			ocsp_path: None,
+		})
+	}
+
+	pub(crate) fn from_pem(cert_pem: &str, key_pem: &str) -> Result<Self, MurTlsError> {
+		Ok(Self {
+			certs: MurTlsLoader::certs_from_pem(cert_pem)?,
+			key: MurTlsLoader::private_key_from_pem(key_pem)?,
+			files: None,
+			ocsp: None,
+			ocsp_path: None,
+		})
+	}
+
+	/// Re-reads the backing files; in-memory certificates and staples are
+	/// kept as is.
+	pub(crate) fn reloaded(&self) -> Result<Self, MurTlsError> {
+		let mut fresh = match &self.files {
+			Some((cert_path, key_path)) => Self::from_files(cert_path, key_path)?,
+			None => self.clone(),
+		};
+		fresh.ocsp = match &self.ocsp_path {
+			Some(path) => Some(std::fs::read(path).map_err(MurTlsError::OcspRead)?),
+			None => self.ocsp.clone(),
+		};
+		fresh.ocsp_path = self.ocsp_path.clone();
+		Ok(fresh)
+	}
+
+	pub(crate) fn certified_key(
+		&self,
+		provider: &CryptoProvider,
+	) -> Result<CertifiedKey, MurTlsError> {
+		let mut key = CertifiedKey::from_der(self.certs.clone(), self.key.clone_key(), provider)
+			.map_err(|e| MurTlsError::ConfigBuild(e.to_string()))?;
+		key.ocsp = self.ocsp.clone();
+		Ok(key)
+	}
+}
+
+/// Picks the certificate for a handshake from the client's SNI hostname.
+///
+/// Exact hostnames win over `*.` wildcards, which match a single label.
+/// Clients that send no SNI, or an unknown name, get the default
+/// certificate.
+#[derive(Debug)]
+pub(crate) struct MurSniResolver {
+	pub(crate) default: Arc<CertifiedKey>,
+	pub(crate) hosts: HashMap<String, Arc<CertifiedKey>>,
+}
+
+impl MurSniResolver {
+	pub(crate) fn select(&self, server_name: Option<&str>) -> Arc<CertifiedKey> {
+		let Some(name) = server_name.map(str::to_ascii_lowercase) else {
+			return Arc::clone(&self.default);
+		};
+
+		let wildcard = name
+			.split_once('.')
+			.map(|(_, parent)| format!("*.{}", parent));
+		self.hosts
+			.get(&name)
+			.or_else(|| wildcard.and_then(|w| self.hosts.get(&w)))
+			.unwrap_or(&self.default)
+			.clone()
+	}
+}
+
+impl ResolvesServerCert for MurSniResolver {
+	fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+		Some(self.select(client_hello.server_name()))
+	}
+}
@@ -1,12 +1,18 @@
 #[cfg(feature = "tls")]
 use super::acceptor;
 #[cfg(feature = "tls")]
+use super::client_cert;
+#[cfg(feature = "tls")]
+use super::client_cert_guard;
+#[cfg(feature = "tls")]
 use super::config;
 #[cfg(feature = "tls")]
 use super::config_builder;
 #[cfg(feature = "tls")]
 use super::error;
 #[cfg(feature = "tls")]
+use super::loader;
+#[cfg(feature = "tls")]
 use super::version;
 #[cfg(feature = "tls")]
 use rcgen::{CertifiedKey, generate_simple_self_signed};
@@ -24,12 +30,102 @@ pub fn generate_self_signed(
 #[cfg(all(test, feature = "tls"))]
 mod tests {
 	use super::*;
+	use crate::server::guard::MurGuard;
+	use crate::server::http::MurRequestContext;
+	use crate::server::router::MurRouteMetadata;
+	use crate::server::service::MurServiceContainer;
 	use acceptor::MurTlsAcceptor;
+	use client_cert::MurClientCert;
+	use client_cert_guard::{MurClientCertGuard, MurClientCertPatterns};
 	use config::MurTlsConfig;
 	use config_builder::MurTlsConfigBuilder;
 	use error::MurTlsError;
+	use loader::MurTlsLoader;
+	use rcgen::{
+		BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, Issuer,
+		KeyPair, SanType,
+	};
+	use rustls::pki_types::{CertificateDer, ServerName};
+	use std::collections::HashMap;
+	use std::path::{Path, PathBuf};
+	use std::sync::Arc;
+	use std::time::{Duration, SystemTime};
 	use version::MurTlsVersion;
 
+	fn temp_dir() -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("mur-tls-{}", uuid::Uuid::new_v4()));
+		std::fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	/// Writes a fresh certificate pair into `dir`, backdating the files by
+	/// `age` so a later write is seen as a change. Returns the cert PEM.
+	fn write_cert(dir: &Path, age: u64) -> String {
+		let (cert_pem, key_pem) = generate_self_signed("localhost").unwrap();
+		let modified = SystemTime::now() - Duration::from_secs(age);
+		for (name, pem) in [("cert.pem", &cert_pem), ("key.pem", &key_pem)] {
+			std::fs::write(dir.join(name), pem).unwrap();
+			std::fs::File::options()
+				.write(true)
+				.open(dir.join(name))
+				.unwrap()
+				.set_modified(modified)
+				.unwrap();
+		}
+		cert_pem
+	}
+
+	/// Whether a client trusting only `trusted_pem` completes a handshake
+	/// for `server_name`.
+	async fn handshake(acceptor: &MurTlsAcceptor, server_name: &str, trusted_pem: &str) -> bool {
+		let mut roots = rustls::RootCertStore::empty();
+		for cert in MurTlsLoader::certs_from_pem(trusted_pem).unwrap() {
+			roots.add(cert).unwrap();
+		}
+		let config = rustls::ClientConfig::builder()
+			.with_root_certificates(roots)
+			.with_no_client_auth();
+		let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+		let name = ServerName::try_from(server_name.to_string()).unwrap();
+
+		let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+		let (server, client) = tokio::join!(
+			acceptor.accept(server_io),
+			connector.connect(name, client_io)
+		);
+		server.is_ok() && client.is_ok()
+	}
+
+	/// A client CA and a leaf it signed, as `(ca_pem, leaf_der)`.
+	fn client_ca_and_leaf() -> (String, CertificateDer<'static>) {
+		let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+		ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+		ca_params
+			.distinguished_name
+			.push(DnType::CommonName, "Test CA");
+		let ca_key = KeyPair::generate().unwrap();
+		let ca_pem = ca_params.self_signed(&ca_key).unwrap().pem();
+		let issuer = Issuer::new(ca_params, ca_key);
+
+		let mut params = CertificateParams::new(vec!["billing.svc.internal".to_string()]).unwrap();
+		params.subject_alt_names.extend([
+			SanType::URI("spiffe://prod/billing/api".try_into().unwrap()),
+			SanType::Rfc822Name("ops@example.com".try_into().unwrap()),
+			SanType::IpAddress("10.0.0.7".parse().unwrap()),
+		]);
+		params
+			.distinguished_name
+			.push(DnType::CommonName, "billing");
+		params
+			.distinguished_name
+			.push(DnType::OrganizationName, "Acme");
+		params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+		let key = KeyPair::generate().unwrap();
+		let leaf = params.signed_by(&key, &issuer).unwrap();
+
+		(ca_pem, leaf.der().clone())
+	}
+
 	#[test]
 	fn test_tls_version_default() {
 		assert_eq!(MurTlsVersion::default(), MurTlsVersion::Tls13);
@@ -86,4 +182,279 @@ mod tests {
 
 		assert!(acceptor.is_ok());
 	}
+
+	#[test]
+	fn test_client_cert_exposes_verified_identity() {
+		let (_, leaf) = client_ca_and_leaf();
+		let cert = MurClientCert::from_chain(std::slice::from_ref(&leaf)).unwrap();
+
+		assert_eq!(cert.common_name.as_deref(), Some("billing"));
+		assert_eq!(cert.subject, "CN=billing, O=Acme");
+		assert_eq!(cert.issuer, "CN=Test CA");
+		assert_eq!(cert.dns_names, vec!["billing.svc.internal"]);
+		assert_eq!(cert.uris, vec!["spiffe://prod/billing/api"]);
+		assert_eq!(cert.emails, vec!["ops@example.com"]);
+		assert_eq!(
+			cert.ip_addresses,
+			vec!["10.0.0.7".parse::<std::net::IpAddr>().unwrap()]
+		);
+		assert_eq!(cert.fingerprint.len(), 64);
+		assert_eq!(cert.leaf(), &leaf);
+		assert!(cert.not_before < cert.not_after);
+
+		assert!(MurClientCert::from_chain(&[]).is_none());
+		assert!(MurClientCert::from_chain(&[CertificateDer::from(vec![0u8; 8])]).is_none());
+	}
+
+	#[test]
+	fn test_client_cert_patterns_match_sans_and_subject() {
+		let (_, leaf) = client_ca_and_leaf();
+		let cert = MurClientCert::from_chain(&[leaf]).unwrap();
+
+		assert!(cert.matches_san("*.svc.internal"));
+		assert!(cert.matches_san("spiffe://prod/*/api"));
+		assert!(cert.matches_san("10.0.0.*"));
+		assert!(!cert.matches_san("spiffe://staging/*"));
+		assert!(!cert.matches_san("svc.internal"));
+
+		assert!(cert.matches_subject("billing"));
+		assert!(cert.matches_subject("*O=Acme"));
+		assert!(!cert.matches_subject("bill"));
+	}
+
+	#[test]
+	fn test_client_auth_builds_required_and_optional_verifiers() {
+		let (cert_pem, key_pem) = generate_self_signed("localhost").unwrap();
+		let (ca_pem, _) = client_ca_and_leaf();
+
+		let required = MurTlsConfig::from_pem(&cert_pem, &key_pem)
+			.unwrap()
+			.client_ca_certs_pem(&ca_pem)
+			.unwrap();
+		assert!(required.client_auth);
+		let verifier = required.client_verifier().unwrap().unwrap();
+		assert!(verifier.offer_client_auth());
+		assert!(verifier.client_auth_mandatory());
+
+		let optional = required.client_auth_optional(true);
+		assert!(optional.build_server_config().is_ok());
+		let verifier = optional.client_verifier().unwrap().unwrap();
+		assert!(!verifier.client_auth_mandatory());
+
+		let built = MurTlsConfigBuilder::new()
+			.cert_pem(&cert_pem)
+			.key_pem(&key_pem)
+			.client_ca_pem(&ca_pem)
+			.client_auth_optional(true)
+			.build()
+			.unwrap();
+		assert!(built.client_auth && built.client_auth_optional);
+
+		let missing_ca = MurTlsConfig::from_pem(&cert_pem, &key_pem)
+			.unwrap()
+			.client_auth(true);
+		assert!(matches!(
+			missing_ca.build_server_config(),
+			Err(MurTlsError::ConfigBuild(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_client_cert_guard_checks_route_patterns() {
+		let (_, leaf) = client_ca_and_leaf();
+		let cert = Arc::new(MurClientCert::from_chain(&[leaf]).unwrap());
+		let ctx = |cert: Option<&Arc<MurClientCert>>, patterns: Option<MurClientCertPatterns>| {
+			let (mut parts, _) = http::Request::builder().body(()).unwrap().into_parts();
+			if let Some(cert) = cert {
+				parts.extensions.insert(Arc::clone(cert));
+			}
+			if let Some(patterns) = patterns {
+				parts
+					.extensions
+					.insert(Arc::new(MurRouteMetadata::new().typed(patterns)));
+			}
+			MurRequestContext::new(
+				parts,
+				None,
+				HashMap::new(),
+				Arc::new(MurServiceContainer::new()),
+			)
+		};
+		let guard = MurClientCertGuard;
+
+		assert!(guard.check_can_activate(&ctx(Some(&cert), None)).await);
+		assert!(!guard.check_can_activate(&ctx(None, None)).await);
+
+		let billing = MurClientCertPatterns::new().san("spiffe://prod/billing/*");
+		assert!(
+			guard
+				.check_can_activate(&ctx(Some(&cert), Some(billing)))
+				.await
+		);
+		let admins = MurClientCertPatterns::new()
+			.san("*.admin.internal")
+			.subject("root");
+		assert!(
+			!guard
+				.check_can_activate(&ctx(Some(&cert), Some(admins)))
+				.await
+		);
+		let by_subject = MurClientCertPatterns::new().subject("billing");
+		assert!(
+			guard
+				.check_can_activate(&ctx(Some(&cert), Some(by_subject)))
+				.await
+		);
+	}
+
+	#[test]
+	fn test_sni_resolver_selects_certificate_by_hostname() {
+		let (default_pem, default_key) = generate_self_signed("localhost").unwrap();
+		let (api_pem, api_key) = generate_self_signed("api.example.com").unwrap();
+		let (apps_pem, apps_key) = generate_self_signed("apps.example.com").unwrap();
+		let config = MurTlsConfig::from_pem(&default_pem, &default_key)
+			.unwrap()
+			.sni_cert_pem("API.example.com", &api_pem, &api_key)
+			.unwrap()
+			.sni_cert_pem("*.apps.example.com", &apps_pem, &apps_key)
+			.unwrap()
+			.sni_ocsp_response("api.example.com", b"api staple".to_vec())
+			.unwrap()
+			.ocsp_response(b"staple".to_vec());
+
+		let provider = rustls::crypto::aws_lc_rs::default_provider();
+		let resolver = config.cert_resolver(&provider).unwrap();
+		let leaf = |name: Option<&str>| resolver.select(name).cert[0].clone();
+		let pem_leaf = |pem: &str| MurTlsLoader::certs_from_pem(pem).unwrap()[0].clone();
+
+		assert_eq!(leaf(Some("api.example.com")), pem_leaf(&api_pem));
+		assert_eq!(leaf(Some("Api.Example.com")), pem_leaf(&api_pem));
+		assert_eq!(leaf(Some("billing.apps.example.com")), pem_leaf(&apps_pem));
+		assert_eq!(leaf(Some("a.b.apps.example.com")), pem_leaf(&default_pem));
+		assert_eq!(leaf(Some("other.example.com")), pem_leaf(&default_pem));
+		assert_eq!(leaf(None), pem_leaf(&default_pem));
+		assert_eq!(resolver.select(None).ocsp.as_deref(), Some(&b"staple"[..]));
+		let api_ocsp = resolver.select(Some("api.example.com")).ocsp.clone();
+		assert_eq!(api_ocsp.as_deref(), Some(&b"api staple"[..]));
+		assert_eq!(resolver.select(Some("x.apps.example.com")).ocsp, None);
+
+		let unknown = config.sni_ocsp_response("www.example.com", b"staple".to_vec());
+		assert!(matches!(unknown, Err(MurTlsError::ConfigBuild(_))));
+	}
+
+	#[test]
+	fn test_reloaded_config_rereads_certificate_and_ocsp_files() {
+		let dir = temp_dir();
+		write_cert(&dir, 60);
+		std::fs::write(dir.join("ocsp.der"), b"first").unwrap();
+		std::fs::write(dir.join("api-ocsp.der"), b"api first").unwrap();
+		let config = MurTlsConfig::from_pem_files(dir.join("cert.pem"), dir.join("key.pem"))
+			.unwrap()
+			.ocsp_response_path(dir.join("ocsp.der"))
+			.unwrap()
+			.sni_cert_files("api.example.com", dir.join("cert.pem"), dir.join("key.pem"))
+			.unwrap()
+			.sni_ocsp_response_path("api.example.com", dir.join("api-ocsp.der"))
+			.unwrap();
+		assert_eq!(config.watched_paths().len(), 6);
+
+		let rotated = write_cert(&dir, 0);
+		std::fs::write(dir.join("ocsp.der"), b"second").unwrap();
+		std::fs::write(dir.join("api-ocsp.der"), b"api second").unwrap();
+		let fresh = config.reloaded().unwrap();
+		assert_eq!(fresh.certs, MurTlsLoader::certs_from_pem(&rotated).unwrap());
+		assert_eq!(fresh.ocsp_response.as_deref(), Some(&b"second"[..]));
+		let api = &fresh.sni["api.example.com"];
+		assert_eq!(api.certs, fresh.certs);
+		assert_eq!(api.ocsp.as_deref(), Some(&b"api second"[..]));
+
+		std::fs::remove_file(dir.join("ocsp.der")).unwrap();
+		assert!(matches!(config.reloaded(), Err(MurTlsError::OcspRead(_))));
+		std::fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_acceptor_reload_swaps_certificates_and_keeps_them_on_error() {
+		let dir = temp_dir();
+		let original = write_cert(&dir, 60);
+		let config =
+			MurTlsConfig::from_pem_files(dir.join("cert.pem"), dir.join("key.pem")).unwrap();
+		let acceptor = MurTlsAcceptor::new(&config).unwrap();
+		assert!(handshake(&acceptor, "localhost", &original).await);
+
+		let rotated = write_cert(&dir, 0);
+		assert!(handshake(&acceptor, "localhost", &original).await);
+		acceptor.reload().unwrap();
+		assert!(handshake(&acceptor, "localhost", &rotated).await);
+		assert!(!handshake(&acceptor, "localhost", &original).await);
+
+		std::fs::write(dir.join("cert.pem"), "not a certificate").unwrap();
+		assert!(acceptor.reload().is_err());
+		assert!(handshake(&acceptor, "localhost", &rotated).await);
+
+		let server_config = config.build_server_config().unwrap();
+		assert!(MurTlsAcceptor::from_config(server_config).reload().is_err());
+		std::fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_acceptor_reloads_when_watched_files_change() {
+		let dir = temp_dir();
+		write_cert(&dir, 60);
+		let config = MurTlsConfig::from_pem_files(dir.join("cert.pem"), dir.join("key.pem"))
+			.unwrap()
+			.reload_interval(Duration::from_millis(10));
+		let acceptor = MurTlsAcceptor::new(&config).unwrap();
+		let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+		acceptor.spawn_reloader(shutdown_rx);
+
+		let rotated = write_cert(&dir, 0);
+		let mut reloaded = false;
+		for _ in 0..200 {
+			if handshake(&acceptor, "localhost", &rotated).await {
+				reloaded = true;
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+		assert!(reloaded);
+
+		shutdown_tx.send(true).unwrap();
+		std::fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_acceptor_reloads_on_sighup() {
+		let dir = temp_dir();
+		write_cert(&dir, 60);
+		let config = MurTlsConfig::from_pem_files(dir.join("cert.pem"), dir.join("key.pem"))
+			.unwrap()
+			.reload_on_sighup(true);
+		let acceptor = MurTlsAcceptor::new(&config).unwrap();
+		let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+		acceptor.spawn_reloader(shutdown_rx);
+
+		let rotated = write_cert(&dir, 0);
+		assert!(!handshake(&acceptor, "localhost", &rotated).await);
+		let pid = std::process::id().to_string();
+		let status = std::process::Command::new("kill")
+			.args(["-HUP", &pid])
+			.status()
+			.unwrap();
+		assert!(status.success());
+
+		let mut reloaded = false;
+		for _ in 0..200 {
+			if handshake(&acceptor, "localhost", &rotated).await {
+				reloaded = true;
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+		assert!(reloaded);
+
+		shutdown_tx.send(true).unwrap();
+		std::fs::remove_dir_all(dir).unwrap();
+	}
 }
@@ -0,0 +1,50 @@
+use crate::server::http::response::{MurCookie, SameSite};
+use std::time::Duration;
+
+/// Cookie and expiry settings of [`MurSessionModule`](super::MurSessionModule).
+#[derive(Debug, Clone)]
+pub struct MurSessionConfig {
+	pub cookie_name: String,
+	/// A session unused for this long expires.
+	pub idle_timeout: Duration,
+	/// A session expires this long after it was created, however active.
+	pub absolute_timeout: Duration,
+	pub path: String,
+	pub domain: Option<String>,
+	pub secure: bool,
+	pub http_only: bool,
+	pub same_site: SameSite,
+}
+
+impl Default for MurSessionConfig {
+	fn default() -> Self {
+		Self {
+			cookie_name: "mur.sid".to_string(),
+			idle_timeout: Duration::from_secs(30 * 60),
+			absolute_timeout: Duration::from_secs(24 * 60 * 60),
+			path: "/".to_string(),
+			domain: None,
+			secure: false,
+			http_only: true,
+			same_site: SameSite::Lax,
+		}
+	}
+}
+
+impl MurSessionConfig {
+	/// The session cookie carrying `value`.
+	pub(crate) fn cookie(&self, value: impl Into<String>) -> MurCookie {
+		let mut cookie = MurCookie::new(&self.cookie_name, value)
+			.path(&self.path)
+			.same_site(self.same_site.clone());
+		cookie.domain = self.domain.clone();
+		cookie.secure = self.secure;
+		cookie.http_only = self.http_only;
+		cookie
+	}
+
+	/// A cookie telling the client to forget its session.
+	pub(crate) fn removal_cookie(&self) -> MurCookie {
+		self.cookie("").max_age(0)
+	}
+}
@@ -0,0 +1,113 @@
+use super::MurSessionRecord;
+use super::store::{MurSessionFuture, MurSessionStore, is_valid_session_id};
+use crate::core::utils::{MurRand, MurTime};
+use crate::server::error::MurError;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Keeps each session as a JSON file in a directory, so sessions survive
+/// restarts of a single-instance deployment.
+///
+/// Expired files are removed when read; nothing sweeps files of sessions
+/// that are never read again.
+#[derive(Debug, Clone)]
+pub struct MurFileSessionStore {
+	dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MurSessionFile {
+	expires_at: u64,
+	record: MurSessionRecord,
+}
+
+impl MurFileSessionStore {
+	/// The directory is created on the first save.
+	pub fn new(dir: impl Into<PathBuf>) -> Self {
+		Self { dir: dir.into() }
+	}
+
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+
+	fn path(&self, id: &str) -> Result<PathBuf, MurError> {
+		if !is_valid_session_id(id) {
+			return Err(MurError::bad_request("Invalid session id"));
+		}
+		Ok(self.dir.join(format!("{}.json", id)))
+	}
+}
+
+fn io_error(e: std::io::Error) -> MurError {
+	MurError::service_unavailable(format!("Session store I/O error: {}", e))
+}
+
+impl MurSessionStore for MurFileSessionStore {
+	fn load<'a>(&'a self, id: &'a str) -> MurSessionFuture<'a, Option<MurSessionRecord>> {
+		Box::pin(async move {
+			// Not an id this store could have written, so there is no such session.
+			let Ok(path) = self.path(id) else {
+				return Ok(None);
+			};
+			let bytes = match tokio::fs::read(&path).await {
+				Ok(bytes) => bytes,
+				Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+				Err(e) => return Err(io_error(e)),
+			};
+
+			match serde_json::from_slice::<MurSessionFile>(&bytes) {
+				Ok(file) if file.expires_at > MurTime::timestamp_secs() => Ok(Some(file.record)),
+				// Expired or unreadable: treat it as gone.
+				_ => {
+					let _ = tokio::fs::remove_file(&path).await;
+					Ok(None)
+				}
+			}
+		})
+	}
+
+	fn save<'a>(
+		&'a self,
+		id: &'a str,
+		record: &'a MurSessionRecord,
+		ttl: Duration,
+	) -> MurSessionFuture<'a, ()> {
+		Box::pin(async move {
+			let path = self.path(id)?;
+			let file = MurSessionFile {
+				expires_at: MurTime::timestamp_secs() + ttl.as_secs(),
+				record: record.clone(),
+			};
+			let bytes = serde_json::to_vec(&file).map_err(|e| MurError::internal(e.to_string()))?;
+
+			tokio::fs::create_dir_all(&self.dir)
+				.await
+				.map_err(io_error)?;
+			// Write then rename, so readers never see a partial file.
+			let tmp = self
+				.dir
+				.join(format!(".{}.{}.tmp", id, MurRand::secure_token(8)));
+			tokio::fs::write(&tmp, bytes).await.map_err(io_error)?;
+			if let Err(e) = tokio::fs::rename(&tmp, &path).await {
+				let _ = tokio::fs::remove_file(&tmp).await;
+				return Err(io_error(e));
+			}
+			Ok(())
+		})
+	}
+
+	fn delete<'a>(&'a self, id: &'a str) -> MurSessionFuture<'a, ()> {
+		Box::pin(async move {
+			let Ok(path) = self.path(id) else {
+				return Ok(());
+			};
+			match tokio::fs::remove_file(path).await {
+				Err(e) if e.kind() != ErrorKind::NotFound => Err(io_error(e)),
+				_ => Ok(()),
+			}
+		})
+	}
+}
@@ -0,0 +1,155 @@
+use super::mur_session::MurSessionState;
+use super::store::is_valid_session_id;
+use super::{MurSession, MurSessionConfig, MurSessionRecord, MurSessionStore};
+use crate::core::utils::{MurRand, MurTime};
+use crate::server::aliases::{MurFuture, MurRes};
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use crate::server::middleware::{MurMiddleware, MurNext};
+use crate::server::security::cookie::MurCookieKeys;
+use std::sync::Arc;
+
+/// Loads the request's session before the handler runs and saves it after.
+///
+/// Installed by [`MurSessionModule`](super::MurSessionModule). The session
+/// cookie is signed when the server has cookie keys.
+#[derive(Clone)]
+pub struct MurSessionMiddleware {
+	store: Arc<dyn MurSessionStore>,
+	config: Arc<MurSessionConfig>,
+}
+
+impl std::fmt::Debug for MurSessionMiddleware {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurSessionMiddleware")
+			.field("config", &self.config)
+			.finish()
+	}
+}
+
+impl MurSessionMiddleware {
+	pub fn new(store: Arc<dyn MurSessionStore>, config: MurSessionConfig) -> Self {
+		Self {
+			store,
+			config: Arc::new(config),
+		}
+	}
+
+	/// The session id sent by the client, if its signature checks out and it
+	/// looks like an id the module issued. Anything else starts a new session.
+	fn incoming_id(&self, ctx: &MurRequestContext) -> Option<String> {
+		let name = &self.config.cookie_name;
+		let id = match ctx.cookie_keys() {
+			Some(keys) => keys.verify(name, ctx.cookie(name)?)?,
+			None => ctx.cookie(name)?.to_string(),
+		};
+		is_valid_session_id(&id).then_some(id)
+	}
+
+	async fn open(&self, ctx: &MurRequestContext, now: u64) -> Result<MurSessionState, MurError> {
+		if let Some(id) = self.incoming_id(ctx)
+			&& let Some(mut record) = self.store.load(&id).await?
+		{
+			if !record.is_expired(&self.config, now) {
+				let flashes = std::mem::take(&mut record.flash);
+				record.last_seen = now;
+				return Ok(MurSessionState {
+					id,
+					record,
+					flashes,
+					loaded: true,
+					id_changed: false,
+					stale_ids: Vec::new(),
+				});
+			}
+			self.store.delete(&id).await?;
+		}
+
+		Ok(MurSessionState {
+			id: MurRand::secure_token(32),
+			record: MurSessionRecord::new(now),
+			flashes: Default::default(),
+			loaded: false,
+			id_changed: true,
+			stale_ids: Vec::new(),
+		})
+	}
+
+	async fn commit(
+		&self,
+		session: &MurSession,
+		keys: Option<MurCookieKeys>,
+		had_cookie: bool,
+		res: MurRes,
+	) -> MurRes {
+		let (id, record, loaded, id_changed, stale_ids) = {
+			let mut state = session.state();
+			(
+				state.id.clone(),
+				state.record.clone(),
+				state.loaded,
+				state.id_changed,
+				std::mem::take(&mut state.stale_ids),
+			)
+		};
+
+		for stale in &stale_ids {
+			if let Err(e) = self.store.delete(stale).await {
+				return e.into();
+			}
+		}
+
+		if record.is_empty() {
+			if loaded && let Err(e) = self.store.delete(&id).await {
+				return e.into();
+			}
+			return if had_cookie {
+				res.with_cookie(self.config.removal_cookie())
+			} else {
+				res
+			};
+		}
+
+		let ttl = record.time_to_live(&self.config, MurTime::timestamp_secs());
+		if let Err(e) = self.store.save(&id, &record, ttl).await {
+			return e.into();
+		}
+		if !id_changed {
+			return res;
+		}
+
+		let name = &self.config.cookie_name;
+		let value = match keys {
+			Some(keys) => keys.sign(name, &id),
+			None => id,
+		};
+		res.with_cookie(self.config.cookie(value))
+	}
+}
+
+impl MurMiddleware for MurSessionMiddleware {
+	fn handle(&self, mut ctx: MurRequestContext, next: MurNext) -> MurFuture {
+		let this = self.clone();
+		Box::pin(async move {
+			let keys = ctx.cookie_keys().cloned();
+			let had_cookie = ctx.has_cookie(&this.config.cookie_name);
+			let state = match this.open(&ctx, MurTime::timestamp_secs()).await {
+				Ok(state) => state,
+				Err(e) => return e.into(),
+			};
+
+			let session = MurSession::new(state);
+			ctx.parts.extensions.insert(session.clone());
+
+			let res = next.run(ctx).await;
+			if res.0.is_err() {
+				return res;
+			}
+			this.commit(&session, keys, had_cookie, res).await
+		})
+	}
+
+	fn name(&self) -> &str {
+		"MurSessionMiddleware"
+	}
+}
@@ -0,0 +1,22 @@
+pub mod config;
+pub mod file_store;
+pub mod middleware;
+pub mod module;
+pub mod mur_session;
+pub mod record;
+pub mod redis_store;
+pub mod store;
+
+pub use config::MurSessionConfig;
+pub use file_store::MurFileSessionStore;
+pub use middleware::MurSessionMiddleware;
+pub use module::MurSessionModule;
+pub use mur_session::MurSession;
+pub use record::MurSessionRecord;
+pub use redis_store::MurRedisSessionStore;
+pub use store::MurMemorySessionStore;
+pub use store::MurSessionFuture;
+pub use store::MurSessionStore;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,129 @@
+use super::{MurMemorySessionStore, MurSessionConfig, MurSessionMiddleware, MurSessionStore};
+use crate::server::http::response::SameSite;
+use crate::server::middleware::MurMiddleware;
+use crate::server::module::{MurModule, MurModuleConfig};
+use std::any::TypeId;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Server-side sessions, referenced by a cookie.
+///
+/// Handlers read the session with a `#[session] session: MurSession`
+/// parameter or [`MurSession::from_ctx`](super::MurSession::from_ctx).
+/// Sessions live in memory unless another [`MurSessionStore`] is given.
+///
+/// ```rust,ignore
+/// MurServer::new()
+///     .cookie_keys(MurCookieKeys::from_config(&config)?)
+///     .module(
+///         MurSessionModule::new()
+///             .store(MurRedisSessionStore::new("redis://127.0.0.1"))
+///             .idle_timeout(Duration::from_secs(15 * 60))
+///             .secure(),
+///     )
+/// ```
+#[derive(Clone)]
+pub struct MurSessionModule {
+	store: Arc<dyn MurSessionStore>,
+	config: MurSessionConfig,
+}
+
+impl std::fmt::Debug for MurSessionModule {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurSessionModule")
+			.field("config", &self.config)
+			.finish()
+	}
+}
+
+impl MurSessionModule {
+	pub fn new() -> Self {
+		Self::with_options(MurSessionConfig::default())
+	}
+
+	pub fn store(mut self, store: impl MurSessionStore) -> Self {
+		self.store = Arc::new(store);
+		self
+	}
+
+	pub fn config(mut self, config: MurSessionConfig) -> Self {
+		self.config = config;
+		self
+	}
+
+	pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+		self.config.cookie_name = name.into();
+		self
+	}
+
+	pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+		self.config.idle_timeout = timeout;
+		self
+	}
+
+	pub fn absolute_timeout(mut self, timeout: Duration) -> Self {
+		self.config.absolute_timeout = timeout;
+		self
+	}
+
+	pub fn path(mut self, path: impl Into<String>) -> Self {
+		self.config.path = path.into();
+		self
+	}
+
+	pub fn domain(mut self, domain: impl Into<String>) -> Self {
+		self.config.domain = Some(domain.into());
+		self
+	}
+
+	/// Sends the session cookie over HTTPS only.
+	pub fn secure(mut self) -> Self {
+		self.config.secure = true;
+		self
+	}
+
+	pub fn same_site(mut self, same_site: SameSite) -> Self {
+		self.config.same_site = same_site;
+		self
+	}
+
+	pub fn session_config(&self) -> &MurSessionConfig {
+		&self.config
+	}
+}
+
+impl Default for MurSessionModule {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MurModuleConfig for MurSessionModule {
+	type Options = MurSessionConfig;
+
+	fn with_options(options: MurSessionConfig) -> Self {
+		Self {
+			store: Arc::new(MurMemorySessionStore::new()),
+			config: options,
+		}
+	}
+}
+
+impl MurModule for MurSessionModule {
+	fn name(&self) -> &str {
+		"MurSessionModule"
+	}
+
+	fn exports(&self) -> Vec<TypeId> {
+		Vec::new()
+	}
+
+	fn imports(&self) -> Vec<Arc<dyn MurModule>> {
+		Vec::new()
+	}
+
+	fn middleware(&self) -> Vec<Box<dyn MurMiddleware + Send + Sync>> {
+		let middleware = MurSessionMiddleware::new(Arc::clone(&self.store), self.config.clone());
+		vec![Box::new(middleware)]
+	}
+}
@@ -0,0 +1,139 @@
+use super::MurSessionRecord;
+use crate::core::utils::MurRand;
+use crate::server::error::MurError;
+use crate::server::http::MurRequestContext;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The session of the current request, installed by
+/// [`MurSessionModule`](super::MurSessionModule).
+///
+/// Clones share the same state. Changes are saved once the handler returns,
+/// and discarded when it returns an error.
+///
+/// ```rust,ignore
+/// #[post("/login")]
+/// async fn login(&self, #[session] session: MurSession, #[body] form: Login) -> MurRes {
+///     let Some(user) = self.users.authenticate(&form).await else {
+///         return MurError::unauthorized("Invalid credentials").into();
+///     };
+///     session.regenerate();
+///     if let Err(e) = session.insert("user_id", user.id) {
+///         return e.into();
+///     }
+///     session.flash("notice", "Welcome back");
+///     MurRes::ok(json!({ "ok": true }))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MurSession {
+	state: Arc<Mutex<MurSessionState>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct MurSessionState {
+	pub id: String,
+	pub record: MurSessionRecord,
+	/// Flash messages set by the previous request.
+	pub flashes: HashMap<String, String>,
+	/// Whether the record was loaded from the store.
+	pub loaded: bool,
+	/// Whether the client must be sent a new session cookie.
+	pub id_changed: bool,
+	/// Ids whose stored records must be deleted.
+	pub stale_ids: Vec<String>,
+}
+
+impl MurSession {
+	pub(crate) fn new(state: MurSessionState) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(state)),
+		}
+	}
+
+	/// The session installed on the request.
+	///
+	/// Fails when `MurSessionModule` is not registered.
+	pub fn from_ctx(ctx: &MurRequestContext) -> Result<Self, MurError> {
+		ctx.session()
+			.ok_or_else(|| MurError::internal("No session available; register MurSessionModule"))
+	}
+
+	pub(crate) fn state(&self) -> MutexGuard<'_, MurSessionState> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	pub fn id(&self) -> String {
+		self.state().id.clone()
+	}
+
+	/// Whether the client had no live session before this request.
+	pub fn is_new(&self) -> bool {
+		!self.state().loaded
+	}
+
+	pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+		let value = self.state().record.data.get(key).cloned()?;
+		serde_json::from_value(value).ok()
+	}
+
+	pub fn insert<T: Serialize>(&self, key: impl Into<String>, value: T) -> Result<(), MurError> {
+		let value = serde_json::to_value(value).map_err(|e| MurError::internal(e.to_string()))?;
+		self.state().record.data.insert(key.into(), value);
+		Ok(())
+	}
+
+	pub fn remove(&self, key: &str) -> Option<Value> {
+		self.state().record.data.remove(key)
+	}
+
+	pub fn contains(&self, key: &str) -> bool {
+		self.state().record.data.contains_key(key)
+	}
+
+	/// Removes every value, keeping the session id.
+	pub fn clear(&self) {
+		self.state().record.data.clear();
+	}
+
+	/// Moves the session to a new id, keeping its data. Call it whenever the
+	/// user's privileges change, such as on login, to defeat session
+	/// fixation.
+	pub fn regenerate(&self) {
+		let mut state = self.state();
+		let old = std::mem::replace(&mut state.id, MurRand::secure_token(32));
+		// Only the id the client came with has a stored record.
+		if state.loaded && !state.id_changed {
+			state.stale_ids.push(old);
+		}
+		state.id_changed = true;
+	}
+
+	/// Deletes the session and its data. Values inserted afterwards start a
+	/// new session.
+	pub fn destroy(&self) {
+		self.regenerate();
+		let mut state = self.state();
+		state.record = MurSessionRecord::new(state.record.last_seen);
+		state.flashes.clear();
+		state.loaded = false;
+	}
+
+	/// Stores a message for the next request only.
+	pub fn flash(&self, key: impl Into<String>, message: impl Into<String>) {
+		self.state().record.flash.insert(key.into(), message.into());
+	}
+
+	/// A flash message set by the previous request.
+	pub fn get_flash(&self, key: &str) -> Option<String> {
+		self.state().flashes.get(key).cloned()
+	}
+
+	/// Every flash message set by the previous request.
+	pub fn flashes(&self) -> HashMap<String, String> {
+		self.state().flashes.clone()
+	}
+}
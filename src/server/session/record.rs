@@ -0,0 +1,48 @@
+use super::MurSessionConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// What a [`MurSessionStore`](super::MurSessionStore) persists for one
+/// session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MurSessionRecord {
+	pub data: HashMap<String, Value>,
+	/// Flash messages for the next request.
+	#[serde(default)]
+	pub flash: HashMap<String, String>,
+	/// Unix time, in seconds.
+	pub created_at: u64,
+	/// Unix time, in seconds.
+	pub last_seen: u64,
+}
+
+impl MurSessionRecord {
+	pub fn new(now: u64) -> Self {
+		Self {
+			created_at: now,
+			last_seen: now,
+			..Self::default()
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty() && self.flash.is_empty()
+	}
+
+	/// Whether the idle or the absolute timeout has elapsed at `now`.
+	pub fn is_expired(&self, config: &MurSessionConfig, now: u64) -> bool {
+		now.saturating_sub(self.last_seen) >= config.idle_timeout.as_secs()
+			|| now.saturating_sub(self.created_at) >= config.absolute_timeout.as_secs()
+	}
+
+	/// How long the store should keep the record when saved at `now`.
+	pub fn time_to_live(&self, config: &MurSessionConfig, now: u64) -> Duration {
+		let remaining = config
+			.absolute_timeout
+			.as_secs()
+			.saturating_sub(now.saturating_sub(self.created_at));
+		config.idle_timeout.min(Duration::from_secs(remaining))
+	}
+}
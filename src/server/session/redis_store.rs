@@ -0,0 +1,131 @@
+use super::MurSessionRecord;
+use super::store::{MurSessionFuture, MurSessionStore};
+use crate::server::error::MurError;
+use crate::server::middleware::rate_limit::MurThrottlerError;
+use crate::server::middleware::rate_limit::resp::{MurRespPool, MurRespValue};
+use std::time::Duration;
+
+/// Keeps sessions in Redis (or any server speaking RESP2), so every replica
+/// sees the same sessions. Records expire through the key's TTL.
+///
+/// ```rust,ignore
+/// MurSessionModule::new().store(MurRedisSessionStore::new("redis://:secret@10.0.0.5/1"))
+/// ```
+#[derive(Debug)]
+pub struct MurRedisSessionStore {
+	client: MurRespPool,
+	key_prefix: String,
+}
+
+impl MurRedisSessionStore {
+	/// Accepts `host:port` or `redis://[[user]:password@]host[:port][/db]`.
+	pub fn new(url: impl AsRef<str>) -> Self {
+		Self {
+			client: MurRespPool::new(url),
+			key_prefix: "murgamu:session:".to_string(),
+		}
+	}
+
+	pub fn password(mut self, password: impl Into<String>) -> Self {
+		self.client.set_password(password.into());
+		self
+	}
+
+	pub fn username(mut self, username: impl Into<String>) -> Self {
+		self.client.set_username(username.into());
+		self
+	}
+
+	pub fn database(mut self, database: u32) -> Self {
+		self.client.set_database(database);
+		self
+	}
+
+	pub fn key_prefix(mut self, prefix: impl Into<String>) -> Self {
+		self.key_prefix = prefix.into();
+		self
+	}
+
+	/// Upper bound for connecting and for each round-trip.
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.client.set_timeout(timeout);
+		self
+	}
+
+	pub fn pool_size(mut self, size: usize) -> Self {
+		self.client.set_pool_size(size);
+		self
+	}
+
+	pub fn addr(&self) -> &str {
+		self.client.addr()
+	}
+
+	fn key(&self, id: &str) -> Vec<u8> {
+		format!("{}{}", self.key_prefix, id).into_bytes()
+	}
+
+	async fn run(&self, command: Vec<Vec<u8>>) -> Result<MurRespValue, MurError> {
+		let reply = self
+			.client
+			.run(&[command])
+			.await
+			.and_then(|mut replies| {
+				replies
+					.pop()
+					.ok_or_else(|| MurThrottlerError::Protocol("missing reply".into()))
+			})
+			.and_then(MurRespValue::into_result);
+		reply.map_err(session_store_error)
+	}
+}
+
+fn session_store_error(err: MurThrottlerError) -> MurError {
+	let message = match err {
+		MurThrottlerError::Io(e) => format!("Session store I/O error: {}", e),
+		MurThrottlerError::Timeout => "Session store timed out".to_string(),
+		MurThrottlerError::Protocol(e) => format!("Session store protocol error: {}", e),
+		MurThrottlerError::Backend(e) => format!("Session store error: {}", e),
+	};
+	MurError::service_unavailable(message)
+}
+
+impl MurSessionStore for MurRedisSessionStore {
+	fn load<'a>(&'a self, id: &'a str) -> MurSessionFuture<'a, Option<MurSessionRecord>> {
+		Box::pin(async move {
+			match self.run(vec![b"GET".to_vec(), self.key(id)]).await? {
+				MurRespValue::Bulk(Some(bytes)) => Ok(serde_json::from_slice(&bytes).ok()),
+				_ => Ok(None),
+			}
+		})
+	}
+
+	fn save<'a>(
+		&'a self,
+		id: &'a str,
+		record: &'a MurSessionRecord,
+		ttl: Duration,
+	) -> MurSessionFuture<'a, ()> {
+		Box::pin(async move {
+			let json = serde_json::to_vec(record).map_err(|e| MurError::internal(e.to_string()))?;
+			let ttl = ttl.as_millis().max(1).to_string();
+			self.run(vec![
+				b"SET".to_vec(),
+				self.key(id),
+				json,
+				b"PX".to_vec(),
+				ttl.into_bytes(),
+			])
+			.await
+			.map(|_| ())
+		})
+	}
+
+	fn delete<'a>(&'a self, id: &'a str) -> MurSessionFuture<'a, ()> {
+		Box::pin(async move {
+			self.run(vec![b"DEL".to_vec(), self.key(id)])
+				.await
+				.map(|_| ())
+		})
+	}
+}
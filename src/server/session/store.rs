@@ -0,0 +1,118 @@
+use super::MurSessionRecord;
+use crate::server::error::MurError;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+pub type MurSessionFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, MurError>> + Send + 'a>>;
+
+/// Whether `id` has the shape of an id the module issues: URL-safe base64
+/// of bounded length. Anything else came from a client, never from a store.
+pub(crate) fn is_valid_session_id(id: &str) -> bool {
+	!id.is_empty()
+		&& id.len() <= 128
+		&& id
+			.bytes()
+			.all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Where [`MurSessionModule`](super::MurSessionModule) keeps session records.
+///
+/// Stores drop records once their time-to-live has passed; the module also
+/// checks the idle and absolute timeouts itself when loading.
+pub trait MurSessionStore: Send + Sync + 'static {
+	fn load<'a>(&'a self, id: &'a str) -> MurSessionFuture<'a, Option<MurSessionRecord>>;
+
+	fn save<'a>(
+		&'a self,
+		id: &'a str,
+		record: &'a MurSessionRecord,
+		ttl: Duration,
+	) -> MurSessionFuture<'a, ()>;
+
+	fn delete<'a>(&'a self, id: &'a str) -> MurSessionFuture<'a, ()>;
+}
+
+/// Keeps sessions in process memory. Sessions are lost on restart and not
+/// shared between replicas.
+#[derive(Debug)]
+pub struct MurMemorySessionStore {
+	data: RwLock<HashMap<String, (MurSessionRecord, Instant)>>,
+	cleanup_interval: Duration,
+	last_cleanup: RwLock<Instant>,
+}
+
+impl MurMemorySessionStore {
+	pub fn new() -> Self {
+		Self {
+			data: RwLock::new(HashMap::new()),
+			cleanup_interval: Duration::from_secs(300),
+			last_cleanup: RwLock::new(Instant::now()),
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.data.read().unwrap_or_else(|e| e.into_inner()).len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	fn maybe_cleanup(&self) {
+		{
+			let last = self.last_cleanup.read().unwrap_or_else(|e| e.into_inner());
+			if last.elapsed() < self.cleanup_interval {
+				return;
+			}
+		}
+		*self.last_cleanup.write().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+
+		let now = Instant::now();
+		self.data
+			.write()
+			.unwrap_or_else(|e| e.into_inner())
+			.retain(|_, (_, expires_at)| *expires_at > now);
+	}
+}
+
+impl Default for MurMemorySessionStore {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MurSessionStore for MurMemorySessionStore {
+	fn load<'a>(&'a self, id: &'a str) -> MurSessionFuture<'a, Option<MurSessionRecord>> {
+		let data = self.data.read().unwrap_or_else(|e| e.into_inner());
+		let record = data
+			.get(id)
+			.filter(|(_, expires_at)| *expires_at > Instant::now())
+			.map(|(record, _)| record.clone());
+		Box::pin(std::future::ready(Ok(record)))
+	}
+
+	fn save<'a>(
+		&'a self,
+		id: &'a str,
+		record: &'a MurSessionRecord,
+		ttl: Duration,
+	) -> MurSessionFuture<'a, ()> {
+		self.maybe_cleanup();
+		self.data
+			.write()
+			.unwrap_or_else(|e| e.into_inner())
+			.insert(id.to_string(), (record.clone(), Instant::now() + ttl));
+		Box::pin(std::future::ready(Ok(())))
+	}
+
+	fn delete<'a>(&'a self, id: &'a str) -> MurSessionFuture<'a, ()> {
+		self.data
+			.write()
+			.unwrap_or_else(|e| e.into_inner())
+			.remove(id);
+		Box::pin(std::future::ready(Ok(())))
+	}
+}
@@ -0,0 +1,195 @@
+use super::*;
+use crate::server::middleware::rate_limit::resp::{MurRespValue, mur_resp_read};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+fn record(user: &str) -> MurSessionRecord {
+	let mut record = MurSessionRecord::new(1_000);
+	record.data.insert("user".to_string(), user.into());
+	record
+}
+
+#[test]
+fn test_record_expiry() {
+	let config = MurSessionConfig {
+		idle_timeout: Duration::from_secs(60),
+		absolute_timeout: Duration::from_secs(300),
+		..MurSessionConfig::default()
+	};
+	let mut record = MurSessionRecord::new(1_000);
+
+	assert!(!record.is_expired(&config, 1_059));
+	assert!(record.is_expired(&config, 1_060));
+
+	record.last_seen = 1_280;
+	assert!(!record.is_expired(&config, 1_290));
+	assert!(record.is_expired(&config, 1_300));
+
+	assert_eq!(record.time_to_live(&config, 1_000), Duration::from_secs(60));
+	assert_eq!(record.time_to_live(&config, 1_280), Duration::from_secs(20));
+}
+
+#[test]
+fn test_session_regenerate_and_destroy() {
+	let session = MurSession::new(mur_session::MurSessionState {
+		id: "old".to_string(),
+		record: record("ana"),
+		flashes: HashMap::from([("notice".to_string(), "hi".to_string())]),
+		loaded: true,
+		id_changed: false,
+		stale_ids: Vec::new(),
+	});
+
+	assert_eq!(session.get::<String>("user").as_deref(), Some("ana"));
+	assert_eq!(session.get_flash("notice").as_deref(), Some("hi"));
+
+	session.regenerate();
+	assert_ne!(session.id(), "old");
+	assert_eq!(session.get::<String>("user").as_deref(), Some("ana"));
+	assert!(!session.is_new());
+
+	session.destroy();
+	assert!(session.is_new());
+	assert!(!session.contains("user"));
+	assert!(session.flashes().is_empty());
+	assert_eq!(session.state().stale_ids, vec!["old".to_string()]);
+}
+
+#[tokio::test]
+async fn test_memory_store() {
+	let store = MurMemorySessionStore::new();
+	store
+		.save("a", &record("ana"), Duration::from_secs(60))
+		.await
+		.unwrap();
+	store
+		.save("b", &record("bob"), Duration::ZERO)
+		.await
+		.unwrap();
+
+	assert_eq!(store.load("a").await.unwrap(), Some(record("ana")));
+	assert_eq!(store.load("b").await.unwrap(), None);
+
+	store.delete("a").await.unwrap();
+	assert_eq!(store.load("a").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_file_store() {
+	let dir = std::env::temp_dir().join(format!("murgamu-sessions-{}", uuid::Uuid::new_v4()));
+	let store = MurFileSessionStore::new(&dir);
+
+	assert_eq!(store.load("missing").await.unwrap(), None);
+	store
+		.save("a", &record("ana"), Duration::from_secs(60))
+		.await
+		.unwrap();
+	store
+		.save("b", &record("bob"), Duration::ZERO)
+		.await
+		.unwrap();
+
+	assert_eq!(store.load("a").await.unwrap(), Some(record("ana")));
+	assert_eq!(store.load("b").await.unwrap(), None);
+	assert!(!dir.join("b.json").exists());
+	assert_eq!(store.load("../etc/passwd").await.unwrap(), None);
+	store.delete("../etc/passwd").await.unwrap();
+	assert!(
+		store
+			.save("../x", &record("eve"), Duration::from_secs(60))
+			.await
+			.is_err()
+	);
+
+	store.delete("a").await.unwrap();
+	store.delete("a").await.unwrap();
+	assert_eq!(store.load("a").await.unwrap(), None);
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+type FakeData = Mutex<HashMap<Vec<u8>, (Vec<u8>, Instant)>>;
+
+fn execute(data: &FakeData, args: Vec<Vec<u8>>) -> Vec<u8> {
+	let mut data = data.lock().unwrap();
+	data.retain(|_, (_, expires)| *expires > Instant::now());
+
+	match args[0].as_slice() {
+		b"SET" => {
+			assert_eq!(args[3], b"PX");
+			let ms: u64 = String::from_utf8_lossy(&args[4]).parse().unwrap();
+			let expires = Instant::now() + Duration::from_millis(ms);
+			data.insert(args[1].clone(), (args[2].clone(), expires));
+			b"+OK\r\n".to_vec()
+		}
+		b"GET" => match data.get(&args[1]) {
+			Some((value, _)) => {
+				let mut out = format!("${}\r\n", value.len()).into_bytes();
+				out.extend_from_slice(value);
+				out.extend_from_slice(b"\r\n");
+				out
+			}
+			None => b"$-1\r\n".to_vec(),
+		},
+		b"DEL" => format!(":{}\r\n", data.remove(&args[1]).is_some() as i64).into_bytes(),
+		_ => b"-ERR unknown command\r\n".to_vec(),
+	}
+}
+
+/// Stand-in for the `SET`/`GET`/`DEL` subset the Redis session store issues.
+async fn spawn_fake_redis() -> String {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap().to_string();
+	let data: Arc<FakeData> = Default::default();
+
+	tokio::spawn(async move {
+		while let Ok((stream, _)) = listener.accept().await {
+			let data = Arc::clone(&data);
+			tokio::spawn(async move {
+				let mut stream = BufReader::new(stream);
+				while let Ok(MurRespValue::Array(Some(items))) = mur_resp_read(&mut stream).await {
+					let args: Vec<Vec<u8>> = items
+						.into_iter()
+						.filter_map(|item| match item {
+							MurRespValue::Bulk(Some(bytes)) => Some(bytes),
+							_ => None,
+						})
+						.collect();
+					let reply = execute(&data, args);
+					if stream.get_mut().write_all(&reply).await.is_err() {
+						break;
+					}
+				}
+			});
+		}
+	});
+
+	addr
+}
+
+#[tokio::test]
+async fn test_redis_store() {
+	let store = MurRedisSessionStore::new(spawn_fake_redis().await);
+
+	store
+		.save("a", &record("ana"), Duration::from_secs(60))
+		.await
+		.unwrap();
+	assert_eq!(store.load("a").await.unwrap(), Some(record("ana")));
+	assert_eq!(store.load("b").await.unwrap(), None);
+
+	store.delete("a").await.unwrap();
+	assert_eq!(store.load("a").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_redis_store_unreachable() {
+	let store = MurRedisSessionStore::new("127.0.0.1:1").timeout(Duration::from_millis(200));
+	let err = store.load("a").await.unwrap_err();
+
+	assert_eq!(err.status_code(), http::StatusCode::SERVICE_UNAVAILABLE);
+	assert!(err.message().starts_with("Session store"));
+}
@@ -0,0 +1,136 @@
+use crate::server::service::MurService;
+use std::any::Any;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
+
+/// Tells handlers and services that the server is going away.
+///
+/// Shutdown happens in two steps. First the server starts draining: it keeps
+/// accepting connections for the configured pre-stop delay, but readiness
+/// checks report `DOWN` so load balancers stop routing to it. Then the
+/// token is cancelled: the server stops accepting, and long-lived work such
+/// as streams or upgraded connections should wrap up.
+///
+/// The server registers its token as a service, so it can be injected or
+/// read with [`MurRequestContext::shutdown_token`](crate::MurRequestContext::shutdown_token).
+///
+/// ```rust,ignore
+/// #[get("/events")]
+/// async fn events(&self, ctx: MurRequestContext) -> MurRes {
+///     let shutdown = ctx.shutdown_token().expect("served by MurServer");
+///     tokio::select! {
+///         event = self.bus.next() => mur_json!({ "event": event }),
+///         _ = shutdown.cancelled() => MurHttpResponse::no_content(),
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct MurShutdownToken {
+	inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+	draining: AtomicBool,
+	cancelled: AtomicBool,
+	notify: Notify,
+}
+
+impl MurShutdownToken {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Whether shutdown has started. Readiness reports `DOWN` from here on.
+	pub fn is_draining(&self) -> bool {
+		self.inner.draining.load(Ordering::Acquire)
+	}
+
+	/// Whether the server has stopped accepting connections.
+	pub fn is_cancelled(&self) -> bool {
+		self.inner.cancelled.load(Ordering::Acquire)
+	}
+
+	/// Resolves once the token is cancelled; immediately if it already is.
+	pub async fn cancelled(&self) {
+		loop {
+			let notified = self.inner.notify.notified();
+			if self.is_cancelled() {
+				return;
+			}
+			notified.await;
+		}
+	}
+
+	/// Starts the drain phase without cancelling yet.
+	pub fn drain(&self) {
+		self.inner.draining.store(true, Ordering::Release);
+	}
+
+	/// Cancels the token, waking every task waiting on
+	/// [`cancelled`](Self::cancelled).
+	pub fn cancel(&self) {
+		self.drain();
+		self.inner.cancelled.store(true, Ordering::Release);
+		self.inner.notify.notify_waiters();
+	}
+}
+
+impl MurService for MurShutdownToken {
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl std::fmt::Debug for MurShutdownToken {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MurShutdownToken")
+			.field("draining", &self.is_draining())
+			.field("cancelled", &self.is_cancelled())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::server::middleware::health::{MurHealthCheck, MurHealthStatus};
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn test_cancel_wakes_waiters() {
+		let token = MurShutdownToken::new();
+		let waiter = tokio::spawn({
+			let token = token.clone();
+			async move { token.cancelled().await }
+		});
+		tokio::task::yield_now().await;
+		assert!(!token.is_cancelled());
+
+		token.cancel();
+		tokio::time::timeout(Duration::from_secs(1), waiter)
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(token.is_draining());
+		tokio::time::timeout(Duration::from_secs(1), token.cancelled())
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_readiness_is_down_while_draining() {
+		let token = MurShutdownToken::new();
+		let health = MurHealthCheck::builder()
+			.shutdown_token(token.clone())
+			.build();
+		assert_eq!(health.check_readiness().await.status, MurHealthStatus::Up);
+		assert_eq!(health.check_liveness().await.status, MurHealthStatus::Up);
+
+		token.drain();
+		assert!(!token.is_cancelled());
+		assert_eq!(health.check_readiness().await.status, MurHealthStatus::Down);
+		assert_eq!(health.check_liveness().await.status, MurHealthStatus::Up);
+	}
+}
@@ -175,78 +175,86 @@ fn responder_blocks_dangerous_redirect_schemes() {
 
 const WINDOW: Duration = Duration::from_secs(60);
 
-#[test]
-fn fixed_window_allows_quota_then_blocks() {
+async fn allowed(store: &dyn MurThrottlerStore, key: &str, limit: u64, window: Duration) -> bool {
+	store
+		.check_and_update(key, limit, window)
+		.await
+		.expect("in-process stores never fail")
+		.allowed
+}
+
+#[tokio::test]
+async fn fixed_window_allows_quota_then_blocks() {
 	let store = InMemoryStore::new();
 	for _ in 0..3 {
-		let (allowed, _, _) = store.check_and_update("k", 3, WINDOW);
-		assert!(allowed);
+		assert!(allowed(&store, "k", 3, WINDOW).await);
 	}
-	let (allowed, remaining, _) = store.check_and_update("k", 3, WINDOW);
-	assert!(!allowed, "4th request must be blocked");
-	assert_eq!(remaining, 0);
+	let result = store.check_and_update("k", 3, WINDOW).await.unwrap();
+	assert!(!result.allowed, "4th request must be blocked");
+	assert_eq!(result.remaining, 0);
+	assert!(result.retry_after > 0);
 }
 
-#[test]
-fn fixed_window_keys_are_independent() {
+#[tokio::test]
+async fn fixed_window_keys_are_independent() {
 	let store = InMemoryStore::new();
 	// Exhaust key "a".
 	for _ in 0..3 {
-		store.check_and_update("a", 3, WINDOW);
+		store.check_and_update("a", 3, WINDOW).await.unwrap();
 	}
-	assert!(!store.check_and_update("a", 3, WINDOW).0);
+	assert!(!allowed(&store, "a", 3, WINDOW).await);
 	// Key "b" still has full quota.
-	assert!(store.check_and_update("b", 3, WINDOW).0);
+	assert!(allowed(&store, "b", 3, WINDOW).await);
 }
 
-#[test]
-fn fixed_window_reset_restores_quota() {
+#[tokio::test]
+async fn fixed_window_reset_restores_quota() {
 	let store = InMemoryStore::new();
 	for _ in 0..3 {
-		store.check_and_update("k", 3, WINDOW);
+		store.check_and_update("k", 3, WINDOW).await.unwrap();
 	}
-	assert!(!store.check_and_update("k", 3, WINDOW).0);
-	store.reset("k");
+	assert!(!allowed(&store, "k", 3, WINDOW).await);
+	store.reset("k").await.unwrap();
 	assert!(
-		store.check_and_update("k", 3, WINDOW).0,
+		allowed(&store, "k", 3, WINDOW).await,
 		"reset should restore quota"
 	);
 }
 
-#[test]
-fn token_bucket_allows_quota_then_blocks() {
+#[tokio::test]
+async fn token_bucket_allows_quota_then_blocks() {
 	let store = TokenBucketStore::new();
 	for _ in 0..3 {
-		assert!(store.check_and_update("k", 3, WINDOW).0);
+		assert!(allowed(&store, "k", 3, WINDOW).await);
 	}
 	assert!(
-		!store.check_and_update("k", 3, WINDOW).0,
+		!allowed(&store, "k", 3, WINDOW).await,
 		"bucket should be empty"
 	);
 }
 
-#[test]
-fn token_bucket_refills_over_time() {
+#[tokio::test]
+async fn token_bucket_refills_over_time() {
 	// Small window so a brief sleep refills at least one token.
 	let store = TokenBucketStore::new();
 	let window = Duration::from_millis(300);
 	for _ in 0..2 {
-		assert!(store.check_and_update("k", 2, window).0);
+		assert!(allowed(&store, "k", 2, window).await);
 	}
-	assert!(!store.check_and_update("k", 2, window).0);
+	assert!(!allowed(&store, "k", 2, window).await);
 
-	std::thread::sleep(Duration::from_millis(400));
+	tokio::time::sleep(Duration::from_millis(400)).await;
 	assert!(
-		store.check_and_update("k", 2, window).0,
+		allowed(&store, "k", 2, window).await,
 		"a token should have refilled after the window elapsed"
 	);
 }
 
-#[test]
-fn sliding_window_allows_quota_then_blocks() {
+#[tokio::test]
+async fn sliding_window_allows_quota_then_blocks() {
 	let store = SlidingWindowStore::new();
 	for _ in 0..3 {
-		assert!(store.check_and_update("k", 3, WINDOW).0);
+		assert!(allowed(&store, "k", 3, WINDOW).await);
 	}
-	assert!(!store.check_and_update("k", 3, WINDOW).0);
+	assert!(!allowed(&store, "k", 3, WINDOW).await);
 }
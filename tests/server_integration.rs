@@ -25,15 +25,32 @@
 //! Each `#[tokio::test]` spins its own server on an ephemeral port so the suite
 //! runs in parallel without interfering.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use aws_lc_rs::digest::{SHA256, digest};
+use aws_lc_rs::rand::SystemRandom;
+use aws_lc_rs::rsa::KeySize;
+use aws_lc_rs::signature::{KeyPair, RSA_PKCS1_SHA256, RsaKeyPair};
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+
 use http_body_util::{BodyExt, Full};
 use hyper::Request;
 use hyper::body::Bytes;
 use hyper_util::rt::TokioIo;
-use murgamu::{MurServer, MurServerConfig, MurServerRunner, MurThrottler};
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, SanType};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use murgamu::server::router::open_api::mur_open_api::MurOpenApi;
+use murgamu::server::security::tls::MurTlsConfig;
+use murgamu::{
+	MurApiKey, MurApiKeyAuth, MurCookieKeys, MurCsrf, MurHttpResponse, MurListener,
+	MurMemoryApiKeyStore, MurMetricsModule, MurOidcModule, MurRequestContext, MurServer,
+	MurServerConfig, MurServerRunner, MurSessionModule, MurThrottler, MurTrailingSlash,
+	MurVersionDeprecation, MurVersioning,
+};
 use tokio::net::TcpStream;
 
 // ===========================================================================
@@ -302,1123 +319,4197 @@ mod app {
 	#[module(controllers: [SecureController])]
 	pub struct SecureModule;
 
-	// ---- interceptor (uses #[interceptor] macro + custom before/after) ------
+	// ---- per-route throttling ----------------------------------------------
 
-	#[interceptor]
-	pub struct StampInterceptor;
+	#[derive(Clone)]
+	pub struct ThrottledController;
 
-	impl MurInterceptor for StampInterceptor {
-		fn before(&self, ctx: &MurRequestContext) -> MurInterceptorFuture {
-			let deny = ctx.has_header("x-deny");
-			Box::pin(async move {
-				if deny {
-					Err(MurError::unauthorized("denied by interceptor"))
-				} else {
-					Ok(())
-				}
-			})
+	#[controller("/limited")]
+	#[throttle("burst")]
+	impl ThrottledController {
+		pub fn new() -> Self {
+			Self
 		}
 
-		fn after(
-			&self,
-			_ctx: &MurRequestContext,
-			response: MurRes,
-		) -> Pin<Box<dyn Future<Output = MurRes> + Send>> {
-			Box::pin(async move { response.with_header("X-Intercepted", "true") })
+		#[post("/login")]
+		#[throttle(limit = 2, per = "1m", key = "ip")]
+		async fn login(&self) -> MurRes {
+			mur_json!({ "ok": true })
 		}
 
-		fn name(&self) -> &str {
-			"StampInterceptor"
+		#[get("/search")]
+		async fn search(&self) -> MurRes {
+			mur_json!({ "results": [] })
 		}
-	}
-
-	// ---- interceptor with DI (verifies #[interceptor] generates factory) -----
-
-	/// Reads a value from an injected service and stamps it as a response header.
-	#[interceptor]
-	pub struct DependencyInterceptor {
-		greeter: GreeterService,
-	}
 
-	impl MurInterceptor for DependencyInterceptor {
-		fn after(
-			&self,
-			_ctx: &MurRequestContext,
-			response: MurRes,
-		) -> Pin<Box<dyn Future<Output = MurRes> + Send>> {
-			let stamp = self.greeter.greet("interceptor");
-			Box::pin(async move { response.with_header("X-DI-Interceptor", stamp) })
+		#[get("/free")]
+		#[skip_throttle]
+		async fn free(&self) -> MurRes {
+			mur_json!({ "ok": true })
 		}
 	}
 
-	// ---- no-op interceptor (verifies default trait methods still work) -------
+	#[module(controllers: [ThrottledController])]
+	pub struct ThrottledModule;
 
-	/// Default `before`/`after` do nothing; only the name is customized.
-	#[interceptor]
-	pub struct NoopInterceptor;
+	// ---- exception filter -------------------------------------------------
 
-	impl MurInterceptor for NoopInterceptor {
-		fn name(&self) -> &str {
-			"NoopInterceptor"
+	/// Renders `MurError::Custom` errors, echoing the request ID it was given.
+	pub struct CustomErrorFilter;
+
+	impl MurExceptionFilter for CustomErrorFilter {
+		fn can_handle(&self, error: &MurError) -> bool {
+			matches!(error, MurError::Custom(..))
+		}
+
+		fn catch(&self, error: MurError, ctx: &MurRequestContext) -> MurRes {
+			MurHttpResponse::status(error.status_code()).json(serde_json::json!({
+				"filtered": true,
+				"request_id": ctx.request_id(),
+			}))
 		}
 	}
 
-	// ---- middleware (manual impl) ----------------------------------------
+	// ---- per-route timeouts -------------------------------------------------
 
 	#[derive(Clone)]
-	pub struct StampMiddleware;
+	pub struct TimeoutController;
 
-	impl MurMiddleware for StampMiddleware {
-		fn handle(&self, ctx: MurRequestContext, next: MurNext) -> MurFuture {
-			Box::pin(async move {
-				if ctx.has_header("x-mw-block") {
-					return MurHttpResponse::forbidden()
-						.json(serde_json::json!({ "error": "blocked by middleware" }));
-				}
-				next.run(ctx).await.with_header("X-Middleware", "on")
-			})
+	#[controller("/deadline")]
+	#[timeout("5s")]
+	impl TimeoutController {
+		pub fn new() -> Self {
+			Self
 		}
 
-		fn name(&self) -> &str {
-			"StampMiddleware"
+		#[get("/budget")]
+		async fn budget(&self, ctx: MurRequestContext) -> MurRes {
+			let deadline = ctx.deadline().expect("deadline set");
+			mur_json!({ "budget_ms": deadline.budget().as_millis() as u64 })
 		}
-	}
-}
 
-// ===========================================================================
-// Test harness: ephemeral server + minimal HTTP client
-// ===========================================================================
+		#[get("/slow")]
+		#[timeout("50ms")]
+		async fn slow(&self) -> MurRes {
+			tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+			mur_json!({ "ok": true })
+		}
+	}
 
-/// A running server bound to an ephemeral port. Dropping it signals shutdown.
-struct TestServer {
-	addr: SocketAddr,
-	shutdown: Option<tokio::sync::oneshot::Sender<()>>,
-}
+	#[module(controllers: [TimeoutController])]
+	pub struct TimeoutModule;
 
-impl TestServer {
-	async fn start(runner: MurServerRunner) -> Self {
-		let addr = runner.addr();
-		let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+	// ---- pipes --------------------------------------------------------------
 
-		tokio::spawn(async move {
-			let _ = runner
-				.run_until(async move {
-					let _ = rx.await;
-				})
-				.await;
-		});
+	#[derive(Deserialize, Serialize)]
+	pub struct SignupDto {
+		pub name: String,
+		pub age: u32,
+	}
 
-		// Wait until the listener is accepting connections.
-		for _ in 0..200 {
-			if TcpStream::connect(addr).await.is_ok() {
-				return Self {
-					addr,
-					shutdown: Some(tx),
-				};
+	impl MurValidate for SignupDto {
+		fn validate(&self) -> Result<(), String> {
+			if self.age < 18 {
+				return Err("age must be at least 18".to_string());
 			}
-			tokio::time::sleep(Duration::from_millis(10)).await;
+			Ok(())
 		}
-		panic!("server did not become ready on {addr}");
 	}
 
-	async fn send(
-		&self,
-		method: &str,
-		path: &str,
-		headers: &[(&str, &str)],
-		body: Vec<u8>,
-	) -> TestResponse {
-		raw_request(self.addr, method, path, headers, body).await
-	}
+	#[derive(Clone)]
+	pub struct PipeController;
 
-	async fn get(&self, path: &str) -> TestResponse {
-		self.send("GET", path, &[], Vec::new()).await
-	}
+	#[controller("/pipes")]
+	#[use_pipes(TrimPipe)]
+	impl PipeController {
+		pub fn new() -> Self {
+			Self
+		}
 
-	async fn get_with(&self, path: &str, headers: &[(&str, &str)]) -> TestResponse {
-		self.send("GET", path, headers, Vec::new()).await
-	}
+		#[get("/items/:id")]
+		async fn item(&self, #[param] #[use_pipe(ParseIntPipe)] id: i64) -> MurRes {
+			mur_json!({ "id": id })
+		}
 
-	async fn post_json(&self, path: &str, body: &str) -> TestResponse {
-		self.send(
-			"POST",
-			path,
-			&[("content-type", "application/json")],
-			body.as_bytes().to_vec(),
-		)
-		.await
+		#[get("/list")]
+		async fn list(
+			&self,
+			#[queryparam] #[use_pipe(DefaultValuePipe::new(20))] limit: u32,
+		) -> MurRes {
+			mur_json!({ "limit": limit })
+		}
+
+		#[get("/orders/:order")]
+		#[use_pipes(ParseUuidPipe::new().version(4))]
+		async fn order(&self, #[param] order: String) -> MurRes {
+			mur_json!({ "order": order })
+		}
+
+		#[post("/signup")]
+		async fn signup(&self, #[body] #[validate] dto: SignupDto) -> MurRes {
+			mur_json!({ "name": dto.name, "age": dto.age })
+		}
 	}
-}
 
-impl Drop for TestServer {
-	fn drop(&mut self) {
-		if let Some(tx) = self.shutdown.take() {
-			let _ = tx.send(());
+	#[module(controllers: [PipeController])]
+	pub struct PipeModule;
+
+	/// Reports pipe failures with their own body, proving they go through
+	/// the exception filters.
+	pub struct PipeErrorFilter;
+
+	impl MurExceptionFilter for PipeErrorFilter {
+		fn can_handle(&self, error: &MurError) -> bool {
+			matches!(error, MurError::BadRequest(..))
+		}
+
+		fn catch(&self, error: MurError, _ctx: &MurRequestContext) -> MurRes {
+			MurHttpResponse::status(StatusCode::UNPROCESSABLE_ENTITY).json(serde_json::json!({
+				"pipe_error": error.to_string(),
+			}))
 		}
 	}
-}
 
-struct TestResponse {
-	status: u16,
-	headers: HashMap<String, String>,
-	body: Vec<u8>,
-}
+	// ---- problem details ----------------------------------------------------
 
-impl TestResponse {
-	fn json(&self) -> serde_json::Value {
-		serde_json::from_slice(&self.body).unwrap_or(serde_json::Value::Null)
+	#[derive(Deserialize, Serialize)]
+	pub struct AccountDto {
+		pub email: String,
+		pub age: u32,
 	}
 
-	fn text(&self) -> String {
-		String::from_utf8_lossy(&self.body).to_string()
+	impl MurValidate for AccountDto {
+		fn field_errors(&self) -> Vec<MurFieldError> {
+			let mut errors = Vec::new();
+			if !self.email.contains('@') {
+				errors.push(MurFieldError::new("email", "must be an email address"));
+			}
+			if self.age < 18 {
+				errors.push(MurFieldError::new("age", "must be at least 18"));
+			}
+			errors
+		}
 	}
 
-	fn header(&self, name: &str) -> Option<&str> {
-		self.headers.get(&name.to_lowercase()).map(|s| s.as_str())
+	#[derive(Clone)]
+	pub struct ProblemController;
+
+	#[controller("/problems")]
+	impl ProblemController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/orders/:id")]
+		async fn order(&self, #[param] id: u32) -> MurRes {
+			MurError::not_found(format!("order {id} not found")).into()
+		}
+
+		#[get("/credit")]
+		async fn credit(&self) -> MurRes {
+			MurProblem::new(StatusCode::FORBIDDEN)
+				.type_uri("https://example.com/probs/out-of-credit")
+				.title("You do not have enough credit.")
+				.detail("Your current balance is 30, but that costs 50.")
+				.extension("balance", 30)
+				.into()
+		}
+
+		#[get("/strict")]
+		#[set_metadata(MurErrorFormat::Problem)]
+		async fn strict(&self) -> MurRes {
+			MurError::forbidden("not for you").into()
+		}
+
+		#[post("/accounts")]
+		async fn accounts(&self, #[body] #[validate] dto: AccountDto) -> MurRes {
+			mur_json!({ "email": dto.email })
+		}
+
+		#[delete("/archive")]
+		async fn archive(&self) -> MurRes {
+			MurError::gone("archive removed").into()
+		}
 	}
-}
 
-/// Performs a single HTTP/1.1 request over a fresh connection and collects the
-/// full response.
-async fn raw_request(
-	addr: SocketAddr,
-	method: &str,
-	path: &str,
-	headers: &[(&str, &str)],
-	body: Vec<u8>,
-) -> TestResponse {
-	let stream = TcpStream::connect(addr).await.expect("connect");
-	let io = TokioIo::new(stream);
+	#[module(controllers: [ProblemController])]
+	pub struct ProblemModule;
 
-	let (mut sender, conn): (hyper::client::conn::http1::SendRequest<Full<Bytes>>, _) =
-		hyper::client::conn::http1::handshake(io)
-			.await
-			.expect("handshake");
+	/// Answers `410 Gone` errors with the request the filter was given.
+	pub struct RequestEchoFilter;
 
-	tokio::spawn(async move {
-		let _ = conn.await;
-	});
+	impl MurExceptionFilter for RequestEchoFilter {
+		fn can_handle(&self, error: &MurError) -> bool {
+			error.status_code() == StatusCode::GONE
+		}
 
-	let mut builder = Request::builder()
-		.method(method)
-		.uri(path)
-		.header("Host", addr.to_string());
-	for (k, v) in headers {
-		builder = builder.header(*k, *v);
+		fn catch(&self, error: MurError, ctx: &MurRequestContext) -> MurRes {
+			MurHttpResponse::status(error.status_code()).json(serde_json::json!({
+				"method": ctx.method().as_str(),
+				"path": ctx.path(),
+				"route": ctx.route_pattern(),
+			}))
+		}
 	}
-	let req = builder
-		.body(Full::new(Bytes::from(body)))
-		.expect("build request");
 
-	let res = sender.send_request(req).await.expect("send request");
-	let status = res.status().as_u16();
+	// ---- constrained path parameters ---------------------------------------
 
-	let mut hmap = HashMap::new();
-	for (k, v) in res.headers() {
-		hmap.insert(
-			k.as_str().to_lowercase(),
-			v.to_str().unwrap_or("").to_string(),
-		);
+	#[derive(Clone)]
+	pub struct TypedPathController;
+
+	#[controller("/typed")]
+	impl TypedPathController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get(r"/users/:id(\d+)")]
+		async fn by_id(&self, #[param] id: u64) -> MurRes {
+			mur_json!({ "by": "id", "id": id })
+		}
+
+		#[get("/users/:name")]
+		async fn by_name(&self, #[param] name: String) -> MurRes {
+			mur_json!({ "by": "name", "name": name })
+		}
+
+		#[get("/posts/:page(uint)?")]
+		async fn posts(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "page": ctx.param_as::<u32>("page").unwrap_or(1) })
+		}
+
+		#[get("/files/:name.:ext")]
+		async fn file(&self, #[param] name: String, #[param] ext: String) -> MurRes {
+			mur_json!({ "name": name, "ext": ext })
+		}
 	}
 
-	let body = res
-		.into_body()
-		.collect()
-		.await
-		.expect("collect body")
-		.to_bytes()
-		.to_vec();
+	#[module(controllers: [TypedPathController])]
+	pub struct TypedPathModule;
 
-	TestResponse {
-		status,
-		headers: hmap,
-		body,
+	#[derive(Clone)]
+	pub struct AmbiguousController;
+
+	#[controller("/ambiguous")]
+	impl AmbiguousController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/:id")]
+		async fn by_id(&self) -> MurRes {
+			mur_json!({})
+		}
+
+		#[get("/:name")]
+		async fn by_name(&self) -> MurRes {
+			mur_json!({})
+		}
 	}
-}
 
-fn free_addr() -> SocketAddr {
-	let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind ephemeral");
-	let addr = listener.local_addr().expect("local_addr");
-	drop(listener);
-	addr
-}
+	#[module(controllers: [AmbiguousController])]
+	pub struct AmbiguousModule;
 
-// ---- server flavours used by the tests ------------------------------------
+	// ---- prefixes and hosts -------------------------------------------------
 
-/// Full-featured functional server: all routes public, interceptor + mw active.
-async fn functional_server() -> TestServer {
-	let addr = free_addr();
-	let runner = MurServer::new()
+	#[derive(Clone)]
+	pub struct StatsController;
+
+	#[controller("/stats")]
+	impl StatsController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get]
+		async fn stats(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "route": ctx.route_pattern() })
+		}
+
+		#[get("/:day")]
+		async fn by_day(&self, #[param] day: u32) -> MurRes {
+			mur_json!({ "day": day })
+		}
+	}
+
+	#[module(prefix: "/admin", controllers: [StatsController])]
+	pub struct AdminScopeModule;
+
+	#[derive(Clone)]
+	pub struct LivenessController;
+
+	#[controller("/healthz")]
+	impl LivenessController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/live")]
+		async fn live(&self) -> MurRes {
+			mur_json!({ "status": "UP" })
+		}
+	}
+
+	#[derive(Clone)]
+	pub struct TenantHomeController;
+
+	#[controller("/home", host = ":tenant.example.com")]
+	impl TenantHomeController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get]
+		async fn home(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "tenant": ctx.host_param("tenant") })
+		}
+	}
+
+	#[derive(Clone)]
+	pub struct PlainHomeController;
+
+	#[controller("/home")]
+	impl PlainHomeController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get]
+		async fn home(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "tenant": ctx.host_param("tenant") })
+		}
+	}
+
+	#[module(controllers: [LivenessController, TenantHomeController, PlainHomeController])]
+	pub struct SiteModule;
+
+	// ---- api versioning -----------------------------------------------------
+
+	#[derive(Clone)]
+	pub struct VersionedUsersController;
+
+	#[controller("/vusers", version = "2")]
+	impl VersionedUsersController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get(version = "1")]
+		async fn list_v1(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "handler": "v1", "version": ctx.api_version() })
+		}
+
+		#[get]
+		async fn list(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "handler": "v2", "version": ctx.api_version() })
+		}
+
+		#[get("/ping", version = [])]
+		async fn ping(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "handler": "neutral", "version": ctx.api_version() })
+		}
+	}
+
+	#[module(controllers: [VersionedUsersController])]
+	pub struct VersionedModule;
+
+	// ---- content negotiation ------------------------------------------------
+
+	#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+	#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+	pub struct Reading {
+		pub sensor: String,
+		pub value: f64,
+	}
+
+	#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+	#[derive(Clone)]
+	pub struct ReadingsController;
+
+	#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+	#[controller("/readings")]
+	impl ReadingsController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[post]
+		async fn record(&self, reading: MurPayload<Reading>) -> MurRes {
+			MurRes::negotiated(reading.into_inner())
+		}
+	}
+
+	#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+	#[module(controllers: [ReadingsController])]
+	pub struct ReadingsModule;
+
+	// ---- sessions and signed cookies ------------------------------------------
+
+	#[derive(Clone)]
+	pub struct AccountController;
+
+	#[controller("/account")]
+	impl AccountController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[post("/login")]
+		async fn login(&self, #[session] session: MurSession) -> MurRes {
+			session.regenerate();
+			if let Err(e) = session.insert("user", "ana") {
+				return e.into();
+			}
+			session.flash("notice", "Welcome back");
+			MurRes::ok(serde_json::json!({ "ok": true }))
+		}
+
+		#[get("/me")]
+		async fn me(&self, session: MurSession) -> MurRes {
+			let visits = session.get::<u64>("visits").unwrap_or(0) + 1;
+			if session.contains("user")
+				&& let Err(e) = session.insert("visits", visits)
+			{
+				return e.into();
+			}
+			MurRes::ok(serde_json::json!({
+				"user": session.get::<String>("user"),
+				"visits": visits,
+				"notice": session.get_flash("notice"),
+			}))
+		}
+
+		#[post("/logout")]
+		async fn logout(&self, #[session] session: MurSession) -> MurRes {
+			session.destroy();
+			MurRes::ok(serde_json::json!({ "ok": true }))
+		}
+
+		#[get("/theme")]
+		async fn theme(&self, ctx: MurRequestContext) -> MurRes {
+			let mut jar = ctx.cookie_jar();
+			let theme = jar.get_signed("theme");
+			if let Err(e) = jar.add_signed(MurCookie::new("theme", "dark").path("/")) {
+				return e.into();
+			}
+			MurRes::ok(serde_json::json!({ "theme": theme })).with_cookie_jar(&jar)
+		}
+	}
+
+	#[module(controllers: [AccountController])]
+	pub struct AccountModule;
+
+	// ---- CSRF ---------------------------------------------------------------
+
+	#[derive(Clone)]
+	pub struct CsrfFormController;
+
+	#[controller("/forms")]
+	impl CsrfFormController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/profile")]
+		async fn form(&self, csrf: MurCsrfToken) -> MurRes {
+			MurRes::html(format!("<form method=\"post\">{}</form>", csrf.hidden_input()))
+		}
+
+		#[post("/profile")]
+		async fn save(&self) -> MurRes {
+			MurRes::ok(serde_json::json!({ "saved": true }))
+		}
+
+		#[post("/webhook")]
+		#[skip_csrf]
+		async fn webhook(&self) -> MurRes {
+			MurRes::ok(serde_json::json!({ "received": true }))
+		}
+	}
+
+	#[module(controllers: [CsrfFormController])]
+	pub struct CsrfFormModule;
+
+	// ---- OpenID Connect -----------------------------------------------------
+
+	#[derive(Clone)]
+	pub struct OidcProfileController;
+
+	#[controller("/oidc")]
+	impl OidcProfileController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/me")]
+		async fn me(&self, ctx: MurRequestContext) -> MurRes {
+			let oidc = ctx.service_required::<MurOidcClient>();
+			match oidc.identity(&ctx).await {
+				Ok(Some(identity)) => MurRes::ok(serde_json::json!({
+					"sub": identity.subject(),
+					"email": identity.claims.email,
+					"access_token": identity.tokens.access_token,
+				})),
+				Ok(None) => MurRes::ok(serde_json::json!({ "sub": null })),
+				Err(e) => e.into(),
+			}
+		}
+	}
+
+	#[module(controllers: [OidcProfileController])]
+	pub struct OidcProfileModule;
+
+	// ---- API keys -----------------------------------------------------------
+
+	#[derive(Clone)]
+	pub struct OrdersApiController;
+
+	#[controller("/api/orders")]
+	#[use_guards(MurApiKeyGuard, MurApiKeyScopeGuard)]
+	impl OrdersApiController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/")]
+		#[set_metadata(MurApiKeyScopes::new(["orders:read"]))]
+		async fn list(&self, key: MurApiKeyIdentity) -> MurRes {
+			mur_json!({ "key": key.id, "tier": key.tier })
+		}
+
+		#[delete("/:id")]
+		#[set_metadata(MurApiKeyScopes::new(["orders:write"]))]
+		async fn remove(&self, #[param] id: u32) -> MurRes {
+			mur_json!({ "removed": id })
+		}
+
+		#[post("/export")]
+		#[set_metadata(MurApiKeyScopes::new(["orders:read"]))]
+		#[throttle(limit = 1, per = "1m", key = "api_key")]
+		async fn export(&self) -> MurRes {
+			mur_json!({ "exported": true })
+		}
+	}
+
+	#[module(controllers: [OrdersApiController])]
+	pub struct OrdersApiModule;
+
+	// ---- mutual TLS ---------------------------------------------------------
+
+	#[derive(Clone)]
+	pub struct MtlsController;
+
+	#[controller("/mtls")]
+	impl MtlsController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/whoami")]
+		async fn whoami(&self, ctx: MurRequestContext) -> MurRes {
+			let cert = ctx.client_cert();
+			mur_json!({
+				"cn": cert.and_then(|c| c.common_name.clone()),
+				"sans": cert.map(|c| c.sans()).unwrap_or_default(),
+			})
+		}
+
+		#[get("/billing")]
+		#[use_guards(MurClientCertGuard)]
+		#[set_metadata(MurClientCertPatterns::new().san("spiffe://prod/billing/*"))]
+		async fn billing(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "fingerprint": ctx.client_cert().map(|c| c.fingerprint.clone()) })
+		}
+	}
+
+	#[module(controllers: [MtlsController])]
+	pub struct MtlsModule;
+
+	// ---- route metadata -----------------------------------------------------
+
+	pub struct RequiredScope(pub &'static str);
+
+	pub struct FeatureFlag(pub &'static str);
+
+	impl MurDecorator for FeatureFlag {
+		fn metadata(&self) -> HashMap<String, String> {
+			HashMap::from([("feature".to_string(), self.0.to_string())])
+		}
+	}
+
+	/// Requires the `x-scope` header to match the route's `RequiredScope`.
+	#[guard]
+	pub struct ScopeGuard;
+
+	#[allow(dead_code)] // constructed by the guard factory via field-init
+	impl ScopeGuard {
+		pub fn new() -> Self {
+			Self
+		}
+
+		pub async fn can_activate(&self, ctx: &MurRequestContext) -> bool {
+			match ctx.route_metadata::<RequiredScope>() {
+				Some(scope) => ctx.header("x-scope") == Some(scope.0),
+				None => true,
+			}
+		}
+	}
+
+	#[derive(Clone)]
+	pub struct MetadataController;
+
+	#[controller("/meta")]
+	#[set_metadata(area = "reports")]
+	impl MetadataController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/reports/:id")]
+		#[set_metadata(RequiredScope("reports:read"))]
+		async fn report(&self, ctx: MurRequestContext) -> MurRes {
+			let route = ctx.matched_route().expect("matched route");
+			mur_json!({
+				"pattern": ctx.route_pattern(),
+				"controller": ctx.controller_name(),
+				"handler": route.handler_name(),
+				"area": ctx.route_metadata_value("area"),
+			})
+		}
+
+		#[get("/beta")]
+		#[set_metadata(area = "labs")]
+		#[decorate(FeatureFlag("beta"))]
+		async fn beta(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({
+				"area": ctx.route_metadata_value("area"),
+				"feature": ctx.route_metadata_value("feature"),
+				"flag": ctx.route_metadata::<FeatureFlag>().map(|flag| flag.0),
+				"scope": ctx.route_metadata::<RequiredScope>().map(|scope| scope.0),
+			})
+		}
+	}
+
+	#[module(controllers: [MetadataController])]
+	pub struct MetadataModule;
+
+	// ---- around interceptors ------------------------------------------------
+
+	/// Adds its label to the `x-trail` request header on the way in and sets
+	/// `X-Last-Exit` on the way out.
+	pub struct OrderInterceptor(pub &'static str);
+
+	impl MurInterceptor for OrderInterceptor {
+		fn intercept<'a>(
+			&'a self,
+			mut ctx: MurRequestContext,
+			next: MurCallHandler,
+		) -> MurInterceptFuture<'a> {
+			Box::pin(async move {
+				let trail = format!("{}{}>", ctx.header("x-trail").unwrap_or_default(), self.0);
+				ctx.parts
+					.headers
+					.insert("x-trail", trail.parse().expect("header value"));
+				next.run(ctx).await.with_header("X-Last-Exit", self.0)
+			})
+		}
+	}
+
+	/// Runs the handler again when it answers 503, and answers `x-short`
+	/// requests without running it.
+	pub struct RetryInterceptor;
+
+	impl MurInterceptor for RetryInterceptor {
+		fn intercept<'a>(
+			&'a self,
+			ctx: MurRequestContext,
+			next: MurCallHandler,
+		) -> MurInterceptFuture<'a> {
+			Box::pin(async move {
+				if ctx.has_header("x-short") {
+					return mur_json!({ "short": true });
+				}
+				let response = next.clone().run(ctx.clone()).await;
+				if response.status_code() == StatusCode::SERVICE_UNAVAILABLE {
+					return next.run(ctx).await.with_header("X-Retried", "1");
+				}
+				response
+			})
+		}
+	}
+
+	pub static FLAKY_CALLS: std::sync::atomic::AtomicUsize =
+		std::sync::atomic::AtomicUsize::new(0);
+
+	#[derive(Clone)]
+	pub struct AroundController;
+
+	#[controller("/around")]
+	impl AroundController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/trail")]
+		async fn trail(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "trail": ctx.header("x-trail") })
+		}
+
+		#[get("/flaky")]
+		async fn flaky(&self) -> MurRes {
+			let call = FLAKY_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+			if call.is_multiple_of(2) {
+				return MurHttpResponse::status(StatusCode::SERVICE_UNAVAILABLE)
+					.json(serde_json::json!({ "call": call }));
+			}
+			mur_json!({ "call": call })
+		}
+	}
+
+	#[module(controllers: [AroundController])]
+	pub struct AroundModule;
+
+	// ---- shutdown lifecycle -------------------------------------------------
+
+	#[derive(Clone)]
+	pub struct LifecycleController;
+
+	#[controller("/lifecycle")]
+	impl LifecycleController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/ready")]
+		async fn ready(&self, ctx: MurRequestContext) -> MurRes {
+			let shutdown = ctx.shutdown_token().expect("shutdown token");
+			murgamu::server::middleware::health::MurHealthCheck::builder()
+				.readiness_path("/lifecycle/ready")
+				.shutdown_token(shutdown)
+				.build()
+				.handle_request(ctx.path())
+				.await
+		}
+
+		#[get("/ready-unwired")]
+		async fn ready_unwired(&self, ctx: MurRequestContext) -> MurRes {
+			murgamu::server::middleware::health::MurHealthCheck::builder()
+				.readiness_path("/lifecycle/ready-unwired")
+				.build()
+				.handle_ctx(&ctx)
+				.await
+		}
+
+		#[get("/wait")]
+		async fn wait(&self, ctx: MurRequestContext) -> MurRes {
+			let shutdown = ctx.shutdown_token().expect("shutdown token");
+			shutdown.cancelled().await;
+			mur_json!({ "cancelled": shutdown.is_cancelled() })
+		}
+	}
+
+	#[module(controllers: [LifecycleController])]
+	pub struct LifecycleModule;
+
+	// ---- application metrics ------------------------------------------------
+
+	#[injectable]
+	pub struct CheckoutStats {
+		metrics: MurMetrics,
+	}
+
+	#[allow(dead_code)] // constructed by the DI factory via field-init
+	impl CheckoutStats {
+		pub fn new(metrics: MurMetrics) -> Self {
+			Self { metrics }
+		}
+
+		pub fn placed(&self, tier: &str) {
+			self.metrics
+				.counter("orders_placed_total", "Orders placed.", &["tier"])
+				.inc(&[tier]);
+		}
+	}
+
+	#[derive(Clone)]
+	pub struct CheckoutController {
+		stats: CheckoutStats,
+	}
+
+	#[controller("/checkout")]
+	impl CheckoutController {
+		pub fn new(stats: CheckoutStats) -> Self {
+			Self { stats }
+		}
+
+		#[post("/:tier")]
+		async fn place(&self, #[param] tier: String) -> MurRes {
+			self.stats.placed(&tier);
+			mur_json!({ "placed": tier })
+		}
+	}
+
+	#[module(controllers: [CheckoutController], providers: [CheckoutStats])]
+	pub struct CheckoutModule;
+
+	// ---- controller and handler guards/interceptors ------------------------
+
+	/// Only provided by `ScopedModule`, so the guard and interceptor below
+	/// can only be built from that module's container.
+	#[injectable]
+	pub struct TenantDirectory;
+
+	#[allow(dead_code)] // constructed by the DI factory via field-init
+	impl TenantDirectory {
+		pub fn new() -> Self {
+			Self
+		}
+
+		pub fn knows(&self, tenant: &str) -> bool {
+			tenant == "acme"
+		}
+	}
+
+	#[guard]
+	pub struct TenantGuard {
+		directory: TenantDirectory,
+	}
+
+	impl TenantGuard {
+		pub async fn can_activate(&self, ctx: &MurRequestContext) -> bool {
+			ctx.header("x-tenant")
+				.is_some_and(|tenant| self.directory.knows(tenant))
+		}
+	}
+
+	#[guard]
+	pub struct AdminRoleGuard;
+
+	#[allow(dead_code)] // constructed by the guard factory via field-init
+	impl AdminRoleGuard {
+		pub fn new() -> Self {
+			Self
+		}
+
+		pub async fn can_activate(&self, ctx: &MurRequestContext) -> bool {
+			ctx.header("x-role") == Some("admin")
+		}
+	}
+
+	#[interceptor]
+	pub struct ControllerTrail {
+		directory: TenantDirectory,
+	}
+
+	impl MurInterceptor for ControllerTrail {
+		fn intercept<'a>(
+			&'a self,
+			ctx: MurRequestContext,
+			next: MurCallHandler,
+		) -> MurInterceptFuture<'a> {
+			let known = ctx
+				.header("x-tenant")
+				.is_some_and(|tenant| self.directory.knows(tenant));
+			let inner = OrderInterceptor("controller");
+			Box::pin(async move {
+				inner
+					.intercept(ctx, next)
+					.await
+					.with_header("X-Tenant-Known", known.to_string())
+			})
+		}
+	}
+
+	#[interceptor]
+	pub struct HandlerTrail;
+
+	impl MurInterceptor for HandlerTrail {
+		fn intercept<'a>(
+			&'a self,
+			ctx: MurRequestContext,
+			next: MurCallHandler,
+		) -> MurInterceptFuture<'a> {
+			Box::pin(async move { OrderInterceptor("handler").intercept(ctx, next).await })
+		}
+	}
+
+	#[derive(Clone)]
+	pub struct ScopedController;
+
+	#[controller("/scoped")]
+	#[use_guards(TenantGuard)]
+	#[use_interceptors(ControllerTrail)]
+	impl ScopedController {
+		pub fn new() -> Self {
+			Self
+		}
+
+		#[get("/trail")]
+		#[use_interceptors(HandlerTrail)]
+		async fn trail(&self, ctx: MurRequestContext) -> MurRes {
+			mur_json!({ "trail": ctx.header("x-trail") })
+		}
+
+		#[delete("/tenant")]
+		#[use_guards(AdminRoleGuard)]
+		async fn remove(&self) -> MurRes {
+			mur_json!({ "removed": true })
+		}
+	}
+
+	#[module(controllers: [ScopedController], providers: [TenantDirectory])]
+	pub struct ScopedModule;
+
+	// ---- interceptor (uses #[interceptor] macro + custom before/after) ------
+
+	#[interceptor]
+	pub struct StampInterceptor;
+
+	impl MurInterceptor for StampInterceptor {
+		fn before(&self, ctx: &MurRequestContext) -> MurInterceptorFuture {
+			let deny = ctx.has_header("x-deny");
+			Box::pin(async move {
+				if deny {
+					Err(MurError::unauthorized("denied by interceptor"))
+				} else {
+					Ok(())
+				}
+			})
+		}
+
+		fn after(
+			&self,
+			_ctx: &MurRequestContext,
+			response: MurRes,
+		) -> Pin<Box<dyn Future<Output = MurRes> + Send>> {
+			Box::pin(async move { response.with_header("X-Intercepted", "true") })
+		}
+
+		fn name(&self) -> &str {
+			"StampInterceptor"
+		}
+	}
+
+	// ---- interceptor with DI (verifies #[interceptor] generates factory) -----
+
+	/// Reads a value from an injected service and stamps it as a response header.
+	#[interceptor]
+	pub struct DependencyInterceptor {
+		greeter: GreeterService,
+	}
+
+	impl MurInterceptor for DependencyInterceptor {
+		fn after(
+			&self,
+			_ctx: &MurRequestContext,
+			response: MurRes,
+		) -> Pin<Box<dyn Future<Output = MurRes> + Send>> {
+			let stamp = self.greeter.greet("interceptor");
+			Box::pin(async move { response.with_header("X-DI-Interceptor", stamp) })
+		}
+	}
+
+	// ---- no-op interceptor (verifies default trait methods still work) -------
+
+	/// Default `before`/`after` do nothing; only the name is customized.
+	#[interceptor]
+	pub struct NoopInterceptor;
+
+	impl MurInterceptor for NoopInterceptor {
+		fn name(&self) -> &str {
+			"NoopInterceptor"
+		}
+	}
+
+	// ---- middleware (manual impl) ----------------------------------------
+
+	#[derive(Clone)]
+	pub struct StampMiddleware;
+
+	impl MurMiddleware for StampMiddleware {
+		fn handle(&self, ctx: MurRequestContext, next: MurNext) -> MurFuture {
+			Box::pin(async move {
+				if ctx.has_header("x-mw-block") {
+					return MurHttpResponse::forbidden()
+						.json(serde_json::json!({ "error": "blocked by middleware" }));
+				}
+				next.run(ctx).await.with_header("X-Middleware", "on")
+			})
+		}
+
+		fn name(&self) -> &str {
+			"StampMiddleware"
+		}
+	}
+
+	// ---- module middleware (manual impl) ---------------------------------
+
+	#[derive(Clone)]
+	pub struct GateMiddleware;
+
+	impl MurMiddleware for GateMiddleware {
+		fn handle(&self, ctx: MurRequestContext, next: MurNext) -> MurFuture {
+			Box::pin(async move {
+				if ctx.has_header("x-gate-close") {
+					return MurHttpResponse::forbidden()
+						.json(serde_json::json!({ "error": "closed by gate" }));
+				}
+				next.run(ctx).await
+			})
+		}
+
+		fn name(&self) -> &str {
+			"GateMiddleware"
+		}
+	}
+
+	pub struct GateModule;
+
+	impl MurModuleConfig for GateModule {
+		type Options = ();
+
+		fn with_options(_options: ()) -> Self {
+			Self
+		}
+	}
+
+	impl MurModule for GateModule {
+		fn name(&self) -> &str {
+			"GateModule"
+		}
+
+		fn exports(&self) -> Vec<std::any::TypeId> {
+			Vec::new()
+		}
+
+		fn imports(&self) -> Vec<Arc<dyn MurModule>> {
+			Vec::new()
+		}
+
+		fn middleware(&self) -> Vec<Box<dyn MurMiddleware + Send + Sync>> {
+			vec![Box::new(GateMiddleware)]
+		}
+	}
+}
+
+// ===========================================================================
+// Test harness: ephemeral server + minimal HTTP client
+// ===========================================================================
+
+/// A running server bound to an ephemeral port. Dropping it signals shutdown.
+struct TestServer {
+	addr: SocketAddr,
+	shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl TestServer {
+	async fn start(runner: MurServerRunner) -> Self {
+		let addr = runner.addr();
+		let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+		tokio::spawn(async move {
+			let _ = runner
+				.run_until(async move {
+					let _ = rx.await;
+				})
+				.await;
+		});
+
+		// Wait until the listener is accepting connections.
+		for _ in 0..200 {
+			if TcpStream::connect(addr).await.is_ok() {
+				return Self {
+					addr,
+					shutdown: Some(tx),
+				};
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+		panic!("server did not become ready on {addr}");
+	}
+
+	async fn send(
+		&self,
+		method: &str,
+		path: &str,
+		headers: &[(&str, &str)],
+		body: Vec<u8>,
+	) -> TestResponse {
+		raw_request(self.addr, method, path, headers, body).await
+	}
+
+	async fn get(&self, path: &str) -> TestResponse {
+		self.send("GET", path, &[], Vec::new()).await
+	}
+
+	async fn get_with(&self, path: &str, headers: &[(&str, &str)]) -> TestResponse {
+		self.send("GET", path, headers, Vec::new()).await
+	}
+
+	async fn post_json(&self, path: &str, body: &str) -> TestResponse {
+		self.send(
+			"POST",
+			path,
+			&[("content-type", "application/json")],
+			body.as_bytes().to_vec(),
+		)
+		.await
+	}
+}
+
+impl Drop for TestServer {
+	fn drop(&mut self) {
+		if let Some(tx) = self.shutdown.take() {
+			let _ = tx.send(());
+		}
+	}
+}
+
+struct TestResponse {
+	status: u16,
+	headers: HashMap<String, String>,
+	body: Vec<u8>,
+}
+
+impl TestResponse {
+	fn json(&self) -> serde_json::Value {
+		serde_json::from_slice(&self.body).unwrap_or(serde_json::Value::Null)
+	}
+
+	fn text(&self) -> String {
+		String::from_utf8_lossy(&self.body).to_string()
+	}
+
+	fn header(&self, name: &str) -> Option<&str> {
+		self.headers.get(&name.to_lowercase()).map(|s| s.as_str())
+	}
+}
+
+/// Performs a single HTTP/1.1 request over a fresh connection and collects the
+/// full response.
+async fn raw_request(
+	addr: SocketAddr,
+	method: &str,
+	path: &str,
+	headers: &[(&str, &str)],
+	body: Vec<u8>,
+) -> TestResponse {
+	let stream = TcpStream::connect(addr).await.expect("connect");
+	let io = TokioIo::new(stream);
+
+	let (mut sender, conn): (hyper::client::conn::http1::SendRequest<Full<Bytes>>, _) =
+		hyper::client::conn::http1::handshake(io)
+			.await
+			.expect("handshake");
+
+	tokio::spawn(async move {
+		let _ = conn.await;
+	});
+
+	let mut builder = Request::builder().method(method).uri(path);
+	if !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("host")) {
+		builder = builder.header("Host", addr.to_string());
+	}
+	for (k, v) in headers {
+		builder = builder.header(*k, *v);
+	}
+	let req = builder
+		.body(Full::new(Bytes::from(body)))
+		.expect("build request");
+
+	let res = sender.send_request(req).await.expect("send request");
+	let status = res.status().as_u16();
+
+	let mut hmap = HashMap::new();
+	for (k, v) in res.headers() {
+		hmap.insert(
+			k.as_str().to_lowercase(),
+			v.to_str().unwrap_or("").to_string(),
+		);
+	}
+
+	let body = res
+		.into_body()
+		.collect()
+		.await
+		.expect("collect body")
+		.to_bytes()
+		.to_vec();
+
+	TestResponse {
+		status,
+		headers: hmap,
+		body,
+	}
+}
+
+fn free_addr() -> SocketAddr {
+	let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind ephemeral");
+	let addr = listener.local_addr().expect("local_addr");
+	drop(listener);
+	addr
+}
+
+// ---- server flavours used by the tests ------------------------------------
+
+/// Full-featured functional server: all routes public, interceptor + mw active.
+async fn functional_server() -> TestServer {
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.global_interceptor(app::StampInterceptor)
+		.middleware(app::StampMiddleware)
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind functional server");
+	TestServer::start(runner).await
+}
+
+/// Server with a global DI-backed interceptor to verify the factory generated by `#[interceptor]`.
+///
+/// `DependencyInterceptor` injects `GreeterService` from the container.
+/// `GreeterService` is registered directly (not via module) so it is visible
+/// to global factories that run after module setup.
+async fn di_interceptor_server() -> TestServer {
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.service(app::GreeterService::new())
+		.global_interceptor(app::NoopInterceptor)
+		.module(app::AppModule::new())
+		.interceptor::<app::DependencyInterceptor>()
+		.bind(addr)
+		.expect("bind di-interceptor server");
+	TestServer::start(runner).await
+}
+
+/// Server with a global authentication guard and protected routes.
+async fn auth_server() -> TestServer {
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.guard::<app::AuthGuard>()
+		.module(app::SecureModule::new())
+		.bind(addr)
+		.expect("bind auth server");
+	TestServer::start(runner).await
+}
+
+// ===========================================================================
+// Functional tests
+// ===========================================================================
+
+#[tokio::test]
+async fn get_returns_json_body() {
+	let server = functional_server().await;
+	let res = server.get("/api/hello").await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.header("content-type"), Some("application/json"));
+	assert!(res.json()["message"].as_str().unwrap().contains("Hello"));
+}
+
+#[tokio::test]
+async fn path_param_is_parsed() {
+	let server = functional_server().await;
+	let res = server.get("/api/users/42").await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["id"], 42);
+}
+
+#[tokio::test]
+async fn invalid_path_param_is_rejected() {
+	let server = functional_server().await;
+	let res = server.get("/api/users/not-a-number").await;
+
+	// u32 parse failure -> 400 Bad Request
+	assert_eq!(res.status, 400);
+}
+
+#[tokio::test]
+async fn query_params_are_parsed() {
+	let server = functional_server().await;
+	let res = server.get("/api/search?term=rust&limit=5").await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["term"], "rust");
+	assert_eq!(res.json()["limit"], 5);
+}
+
+#[tokio::test]
+async fn query_params_use_defaults_when_missing() {
+	let server = functional_server().await;
+	let res = server.get("/api/search").await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["term"], "none");
+	assert_eq!(res.json()["limit"], 10);
+}
+
+#[tokio::test]
+async fn post_json_body_is_deserialized() {
+	let server = functional_server().await;
+	let res = server
+		.post_json("/api/echo", r#"{"name":"ada","value":7}"#)
+		.await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["name"], "ada");
+	assert_eq!(res.json()["value"], 7);
+}
+
+#[tokio::test]
+async fn malformed_json_body_is_rejected() {
+	let server = functional_server().await;
+	let res = server.post_json("/api/echo", "{ this is not json").await;
+
+	assert_eq!(res.status, 400);
+}
+
+#[tokio::test]
+async fn post_form_body_is_deserialized() {
+	let server = functional_server().await;
+	let res = server
+		.send(
+			"POST",
+			"/api/form",
+			&[("content-type", "application/x-www-form-urlencoded")],
+			b"name=grace&value=99".to_vec(),
+		)
+		.await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["name"], "grace");
+	assert_eq!(res.json()["value"], 99);
+}
+
+#[tokio::test]
+async fn put_patch_delete_reach_handlers() {
+	let server = functional_server().await;
+
+	let put = server.send("PUT", "/api/items/1", &[], Vec::new()).await;
+	assert_eq!(put.status, 200);
+	assert_eq!(put.json()["updated"], 1);
+
+	let patch = server.send("PATCH", "/api/items/2", &[], Vec::new()).await;
+	assert_eq!(patch.status, 200);
+	assert_eq!(patch.json()["patched"], 2);
+
+	let del = server.send("DELETE", "/api/items/3", &[], Vec::new()).await;
+	assert_eq!(del.status, 200);
+	assert_eq!(del.json()["deleted"], 3);
+}
+
+#[tokio::test]
+async fn head_falls_back_to_get_handler() {
+	let server = functional_server().await;
+	let res = server.send("HEAD", "/api/hello", &[], Vec::new()).await;
+
+	assert_eq!(res.status, 200);
+}
+
+#[tokio::test]
+async fn options_lists_allowed_methods() {
+	let server = functional_server().await;
+	let res = server
+		.send("OPTIONS", "/api/items/1", &[], Vec::new())
+		.await;
+
+	assert_eq!(res.status, 204);
+	let allow = res.header("allow").unwrap_or_default();
+	assert!(allow.contains("PUT"), "Allow header was: {allow}");
+	assert!(allow.contains("DELETE"), "Allow header was: {allow}");
+}
+
+#[tokio::test]
+async fn unknown_route_returns_404_json() {
+	let server = functional_server().await;
+	// Use a prefix with no registered controller so nothing (not even the
+	// `/api/:slug` catch route) matches.
+	let res = server.get("/totally/unknown/path").await;
+
+	assert_eq!(res.status, 404);
+	assert_eq!(res.json()["status"], 404);
+}
+
+#[tokio::test]
+async fn text_and_html_content_types() {
+	let server = functional_server().await;
+
+	let text = server.get("/api/text").await;
+	assert_eq!(text.status, 200);
+	assert!(
+		text.header("content-type")
+			.unwrap_or_default()
+			.contains("text/plain")
+	);
+	assert_eq!(text.text(), "plain text body");
+
+	let html = server.get("/api/html").await;
+	assert_eq!(html.status, 200);
+	assert!(
+		html.header("content-type")
+			.unwrap_or_default()
+			.contains("text/html")
+	);
+}
+
+#[tokio::test]
+async fn error_type_maps_to_status_codes() {
+	let server = functional_server().await;
+
+	let conflict = server.get("/api/conflict").await;
+	assert_eq!(conflict.status, 409);
+
+	let teapot = server.get("/api/teapot").await;
+	assert_eq!(teapot.status, 418);
+}
+
+#[tokio::test]
+async fn dependency_injection_resolves_nested_services() {
+	// /api/hello exercises ApiController -> UserService -> GreeterService.
+	let server = functional_server().await;
+	let res = server.get("/api/users/5").await;
+
+	assert_eq!(res.status, 200);
+	let describe = res.json()["describe"].as_str().unwrap().to_string();
+	assert!(describe.contains("user #5"), "got: {describe}");
+}
+
+#[tokio::test]
+async fn interceptor_after_adds_header() {
+	let server = functional_server().await;
+	let res = server.get("/api/hello").await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.header("x-intercepted"), Some("true"));
+}
+
+#[tokio::test]
+async fn interceptor_before_can_short_circuit() {
+	let server = functional_server().await;
+	let res = server.get_with("/api/hello", &[("x-deny", "1")]).await;
+
+	assert_eq!(res.status, 401);
+}
+
+// --- #[interceptor] macro-specific tests ------------------------------------
+
+/// Verifies that a unit-struct interceptor created with `#[interceptor]` and a
+/// no-op `impl MurInterceptor` does not alter the response.
+#[tokio::test]
+async fn interceptor_macro_noop_does_not_affect_response() {
+	let server = di_interceptor_server().await;
+	let res = server.get("/api/hello").await;
+
+	assert_eq!(res.status, 200);
+}
+
+/// Verifies that an interceptor created with `#[interceptor]` that injects a
+/// service via DI resolves the dependency and stamps a response header.
+#[tokio::test]
+async fn interceptor_macro_with_di_stamps_header() {
+	let server = di_interceptor_server().await;
+	let res = server.get("/api/hello").await;
+
+	assert_eq!(res.status, 200);
+	let header = res.header("x-di-interceptor").unwrap_or_default();
+	assert!(
+		header.contains("interceptor"),
+		"expected greeter output in X-DI-Interceptor, got: {header}"
+	);
+}
+
+/// Verifies that a DI-backed interceptor can still short-circuit requests via
+/// `before` (the supertrait bound is satisfied even without the macro generating
+/// an `impl MurInterceptor` block).
+#[tokio::test]
+async fn interceptor_macro_noop_passes_requests_through() {
+	let server = di_interceptor_server().await;
+
+	let ok = server.get("/api/hello").await;
+	assert_eq!(ok.status, 200);
+
+	let not_found = server.get("/no-such-route").await;
+	assert_eq!(not_found.status, 404);
+}
+
+#[tokio::test]
+async fn middleware_adds_header() {
+	let server = functional_server().await;
+	let res = server.get("/api/hello").await;
+
+	assert_eq!(res.header("x-middleware"), Some("on"));
+}
+
+#[tokio::test]
+async fn middleware_can_short_circuit() {
+	let server = functional_server().await;
+	let res = server.get_with("/api/hello", &[("x-mw-block", "1")]).await;
+
+	assert_eq!(res.status, 403);
+	assert_eq!(res.json()["error"], "blocked by middleware");
+}
+
+// ===========================================================================
+// Security tests
+// ===========================================================================
+
+#[tokio::test]
+async fn public_route_bypasses_auth() {
+	let server = auth_server().await;
+	let res = server.get("/secure/open").await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["scope"], "public");
+}
+
+#[tokio::test]
+async fn protected_route_without_token_is_rejected() {
+	let server = auth_server().await;
+	let res = server.get("/secure/me").await;
+
+	// Guard returns false -> default rejection is 403 Forbidden.
+	assert_eq!(res.status, 403);
+}
+
+#[tokio::test]
+async fn protected_route_with_invalid_token_is_rejected() {
+	let server = auth_server().await;
+	let res = server
+		.get_with("/secure/me", &[("authorization", "Bearer nope")])
+		.await;
+
+	assert_eq!(res.status, 403);
+}
+
+#[tokio::test]
+async fn protected_route_with_valid_token_is_allowed() {
+	let server = auth_server().await;
+	let res = server
+		.get_with("/secure/me", &[("authorization", "Bearer user-token")])
+		.await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["scope"], "authenticated");
+}
+
+#[tokio::test]
+async fn role_protected_route_denies_insufficient_role() {
+	let server = auth_server().await;
+	let res = server
+		.get_with("/secure/admin", &[("authorization", "Bearer user-token")])
+		.await;
+
+	assert_eq!(res.status, 403);
+}
+
+#[tokio::test]
+async fn role_protected_route_allows_matching_role() {
+	let server = auth_server().await;
+	let res = server
+		.get_with("/secure/admin", &[("authorization", "Bearer admin-token")])
+		.await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["scope"], "admin");
+}
+
+#[tokio::test]
+async fn body_size_limit_returns_413() {
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.default_public_routes()
+		.configure(MurServerConfig::default().no_logging().body_size_limit(64))
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind body-limit server");
+	let server = TestServer::start(runner).await;
+
+	// Build a JSON body well over the 64-byte limit.
+	let big_name = "x".repeat(512);
+	let body = format!(r#"{{"name":"{big_name}","value":1}}"#);
+	let res = server.post_json("/api/echo", &body).await;
+
+	assert_eq!(res.status, 413);
+}
+
+#[tokio::test]
+async fn body_within_limit_is_accepted() {
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.default_public_routes()
+		.configure(
+			MurServerConfig::default()
+				.no_logging()
+				.body_size_limit(4096),
+		)
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind body-limit server");
+	let server = TestServer::start(runner).await;
+
+	let res = server
+		.post_json("/api/echo", r#"{"name":"ok","value":1}"#)
+		.await;
+	assert_eq!(res.status, 200);
+}
+
+#[tokio::test]
+async fn rate_limiter_blocks_after_quota() {
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.middleware(MurThrottler::new().global().requests(3).per_minutes(1))
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind rate-limit server");
+	let server = TestServer::start(runner).await;
+
+	// First 3 requests pass.
+	for _ in 0..3 {
+		let res = server.get("/api/hello").await;
+		assert_eq!(res.status, 200);
+	}
+
+	// 4th exceeds the quota.
+	let blocked = server.get("/api/hello").await;
+	assert_eq!(blocked.status, 429);
+	assert!(blocked.header("retry-after").is_some());
+}
+
+#[tokio::test]
+async fn cors_permissive_allows_any_origin() {
+	let server = functional_server().await;
+	let res = server
+		.get_with("/api/hello", &[("origin", "http://anywhere.test")])
+		.await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.header("access-control-allow-origin"), Some("*"));
+}
+
+async fn cors_restricted_server() -> TestServer {
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.cors(vec!["http://allowed.test".to_string()])
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind cors server");
+	TestServer::start(runner).await
+}
+
+#[tokio::test]
+async fn cors_restricted_reflects_allowed_origin() {
+	let server = cors_restricted_server().await;
+	let res = server
+		.get_with("/api/hello", &[("origin", "http://allowed.test")])
+		.await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(
+		res.header("access-control-allow-origin"),
+		Some("http://allowed.test")
+	);
+}
+
+#[tokio::test]
+async fn cors_restricted_rejects_unknown_origin() {
+	let server = cors_restricted_server().await;
+	let res = server
+		.get_with("/api/hello", &[("origin", "http://evil.test")])
+		.await;
+
+	assert_eq!(res.status, 403);
+}
+
+#[tokio::test]
+async fn cors_preflight_returns_no_content_with_headers() {
+	let server = cors_restricted_server().await;
+	let res = server
+		.send(
+			"OPTIONS",
+			"/api/hello",
+			&[
+				("origin", "http://allowed.test"),
+				("access-control-request-method", "GET"),
+			],
+			Vec::new(),
+		)
+		.await;
+
+	assert_eq!(res.status, 204);
+	assert_eq!(
+		res.header("access-control-allow-origin"),
+		Some("http://allowed.test")
+	);
+	assert!(
+		res.header("access-control-allow-methods")
+			.unwrap_or_default()
+			.contains("GET")
+	);
+}
+
+#[tokio::test]
+async fn internal_errors_do_not_leak_details() {
+	let server = functional_server().await;
+	let res = server.get("/api/boom").await;
+
+	assert_eq!(res.status, 500);
+	let body = res.text();
+	assert!(
+		!body.contains("hunter2"),
+		"internal error detail leaked to client: {body}"
+	);
+	assert!(body.contains("Internal Server Error"));
+}
+
+// ===========================================================================
+// Routing edge cases
+// ===========================================================================
+
+#[tokio::test]
+async fn wildcard_matches_exactly_one_segment() {
+	let server = functional_server().await;
+
+	let ok = server.get("/api/files/doc.txt").await;
+	assert_eq!(ok.status, 200);
+	assert_eq!(ok.json()["matched"], "wildcard");
+
+	// `*` consumes a single segment only; a deeper path must not match.
+	let deep = server.get("/api/files/a/b").await;
+	assert_eq!(deep.status, 404);
+}
+
+#[tokio::test]
+async fn catch_all_captures_remaining_path() {
+	let server = functional_server().await;
+	let res = server.get("/api/tree/a/b/c").await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["rest"], "a/b/c");
+}
+
+#[tokio::test]
+async fn static_route_beats_dynamic_param() {
+	let server = functional_server().await;
+
+	let stat = server.get("/api/profile").await;
+	assert_eq!(stat.status, 200);
+	assert_eq!(stat.json()["route"], "static");
+
+	let dynamic = server.get("/api/anything-else").await;
+	assert_eq!(dynamic.status, 200);
+	assert_eq!(dynamic.json()["route"], "dynamic");
+	assert_eq!(dynamic.json()["slug"], "anything-else");
+}
+
+#[tokio::test]
+async fn trailing_slash_is_normalized() {
+	let server = functional_server().await;
+	let res = server.get("/api/hello/").await;
+	assert_eq!(res.status, 200);
+}
+
+#[tokio::test]
+async fn wrong_method_on_existing_path_is_405() {
+	let server = functional_server().await;
+	// `/api/hello` is GET-only; POST has no matching route.
+	let res = server.send("POST", "/api/hello", &[], Vec::new()).await;
+	assert_eq!(res.status, 405);
+	assert_eq!(res.header("allow"), Some("GET, HEAD, OPTIONS"));
+	assert_eq!(res.json()["status"], 405);
+
+	let res = server.send("POST", "/api/items/1", &[], Vec::new()).await;
+	assert_eq!(res.status, 405);
+	assert_eq!(res.header("allow"), Some("PUT, PATCH, DELETE, OPTIONS"));
+}
+
+#[tokio::test]
+async fn empty_body_to_json_route_is_rejected() {
+	let server = functional_server().await;
+	let res = server.send("POST", "/api/echo", &[], Vec::new()).await;
+	assert_eq!(res.status, 400);
+}
+
+#[tokio::test]
+async fn json_content_type_with_charset_still_parses() {
+	let server = functional_server().await;
+	let res = server
+		.send(
+			"POST",
+			"/api/echo",
+			&[("content-type", "application/json; charset=utf-8")],
+			br#"{"name":"x","value":1}"#.to_vec(),
+		)
+		.await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["name"], "x");
+}
+
+// ===========================================================================
+// Cookies
+// ===========================================================================
+
+#[tokio::test]
+async fn set_cookie_carries_security_attributes() {
+	let server = functional_server().await;
+	let res = server.get("/api/cookie").await;
+
+	assert_eq!(res.status, 200);
+	let cookie = res.header("set-cookie").expect("Set-Cookie present");
+	assert!(cookie.contains("sid=secret123"), "cookie: {cookie}");
+	assert!(cookie.contains("HttpOnly"), "cookie: {cookie}");
+	assert!(cookie.contains("Secure"), "cookie: {cookie}");
+	assert!(cookie.contains("SameSite=Strict"), "cookie: {cookie}");
+}
+
+// ===========================================================================
+// Security headers (opt-in middleware)
+// ===========================================================================
+
+async fn security_headers_server() -> TestServer {
+	use murgamu::server::security::headers::{MurSecurityHeaders, XFrameOptions};
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.middleware(
+			MurSecurityHeaders::new()
+				.x_content_type_options(true)
+				.x_frame_options(XFrameOptions::Deny),
+		)
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind security-headers server");
+	TestServer::start(runner).await
+}
+
+#[tokio::test]
+async fn security_headers_are_applied_when_enabled() {
+	let server = security_headers_server().await;
+	let res = server.get("/api/hello").await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.header("x-content-type-options"), Some("nosniff"));
+	assert_eq!(res.header("x-frame-options"), Some("DENY"));
+}
+
+#[tokio::test]
+async fn security_headers_absent_by_default() {
+	// Documents that the framework does NOT add security headers unless the
+	// MurSecurityHeaders middleware is explicitly installed.
+	let server = functional_server().await;
+	let res = server.get("/api/hello").await;
+	assert!(res.header("x-frame-options").is_none());
+	assert!(res.header("x-content-type-options").is_none());
+}
+
+// ===========================================================================
+// Rate limiting: keying & spoofability
+// ===========================================================================
+
+async fn ip_throttled_server() -> TestServer {
+	let addr = free_addr();
+	let runner = MurServer::new()
 		.no_logging()
 		.default_public_routes()
-		.global_interceptor(app::StampInterceptor)
-		.middleware(app::StampMiddleware)
+		.middleware(MurThrottler::new().by_ip().requests(2).per_minutes(1))
 		.module(app::AppModule::new())
 		.bind(addr)
-		.expect("bind functional server");
+		.expect("bind ip-throttled server");
+	TestServer::start(runner).await
+}
+
+#[tokio::test]
+async fn rate_limit_is_per_forwarded_ip() {
+	let server = ip_throttled_server().await;
+	let ip_a = [("x-forwarded-for", "10.0.0.1")];
+
+	assert_eq!(server.get_with("/api/hello", &ip_a).await.status, 200);
+	assert_eq!(server.get_with("/api/hello", &ip_a).await.status, 200);
+	// Third request from the same IP exceeds the quota.
+	assert_eq!(server.get_with("/api/hello", &ip_a).await.status, 429);
+}
+
+#[tokio::test]
+async fn rate_limit_is_bypassable_by_spoofing_forwarded_ip() {
+	// SECURITY NOTE: IP-based throttling trusts `X-Forwarded-For` verbatim, so
+	// a client that rotates the header gets a fresh bucket each time. This test
+	// documents that bypass — deployments must only trust XFF from a known proxy.
+	let server = ip_throttled_server().await;
+
+	for i in 0..6 {
+		let spoofed = format!("10.0.0.{i}");
+		let headers = [("x-forwarded-for", spoofed.as_str())];
+		let res = server.get_with("/api/hello", &headers).await;
+		assert_eq!(
+			res.status, 200,
+			"spoofed IP {spoofed} should get its own bucket"
+		);
+	}
+}
+
+// ===========================================================================
+// Robustness: large/odd inputs must not crash the server
+// ===========================================================================
+
+#[tokio::test]
+async fn very_long_query_string_does_not_crash() {
+	let server = functional_server().await;
+	let long = "a".repeat(8192);
+	let res = server.get(&format!("/api/search?term={long}")).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["term"], long);
+}
+
+#[tokio::test]
+async fn deeply_nested_catch_all_path_is_handled() {
+	let server = functional_server().await;
+	let deep = (0..200)
+		.map(|i| i.to_string())
+		.collect::<Vec<_>>()
+		.join("/");
+	let res = server.get(&format!("/api/tree/{deep}")).await;
+	assert_eq!(res.status, 200);
+}
+
+#[tokio::test]
+async fn many_distinct_routes_remain_independently_resolvable() {
+	let server = functional_server().await;
+	// Hit a spread of routes back-to-back; each must resolve to its own handler.
+	assert_eq!(server.get("/api/text").await.text(), "plain text body");
+	assert_eq!(server.get("/api/users/9").await.json()["id"], 9);
+	assert_eq!(server.get("/api/profile").await.json()["route"], "static");
+	assert_eq!(server.get("/api/conflict").await.status, 409);
+}
+
+// ===========================================================================
+// Performance / load (generous ceilings to avoid CI flakiness)
+// ===========================================================================
+
+#[tokio::test]
+async fn perf_handles_concurrent_requests() {
+	let server = functional_server().await;
+	let addr = server.addr;
+
+	let start = std::time::Instant::now();
+	let mut set = tokio::task::JoinSet::new();
+	for _ in 0..200 {
+		set.spawn(async move { raw_request(addr, "GET", "/api/hello", &[], Vec::new()).await });
+	}
+
+	let mut ok = 0;
+	while let Some(res) = set.join_next().await {
+		if res.expect("task panicked").status == 200 {
+			ok += 1;
+		}
+	}
+	let elapsed = start.elapsed();
+
+	assert_eq!(ok, 200, "all concurrent requests should succeed");
+	assert!(
+		elapsed < Duration::from_secs(30),
+		"200 concurrent requests took too long: {elapsed:?}"
+	);
+	eprintln!("perf: 200 concurrent requests in {elapsed:?}");
+}
+
+#[tokio::test]
+async fn perf_sequential_throughput_is_reasonable() {
+	let server = functional_server().await;
+
+	let n = 300;
+	let start = std::time::Instant::now();
+	for _ in 0..n {
+		let res = server.get("/api/hello").await;
+		assert_eq!(res.status, 200);
+	}
+	let elapsed = start.elapsed();
+
+	assert!(
+		elapsed < Duration::from_secs(30),
+		"{n} sequential requests took too long: {elapsed:?}"
+	);
+	let rps = n as f64 / elapsed.as_secs_f64();
+	eprintln!("perf: {n} sequential requests in {elapsed:?} (~{rps:.0} req/s)");
+}
+
+// ===========================================================================
+// Timeout middleware
+// ===========================================================================
+
+async fn timeout_server() -> TestServer {
+	use murgamu::server::middleware::timeout::MurTimeout;
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.middleware(MurTimeout::from_millis(80))
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind timeout server");
+	TestServer::start(runner).await
+}
+
+#[tokio::test]
+async fn slow_handler_times_out() {
+	let server = timeout_server().await;
+	// /api/slow sleeps 400ms, well past the 80ms budget.
+	let res = server.get("/api/slow").await;
+	assert_eq!(res.status, 408);
+}
+
+#[tokio::test]
+async fn fast_handler_under_timeout_succeeds() {
+	let server = timeout_server().await;
+	let res = server.get("/api/hello").await;
+	assert_eq!(res.status, 200);
+}
+
+// ===========================================================================
+// Compression middleware
+// ===========================================================================
+
+async fn compression_server() -> TestServer {
+	use murgamu::server::middleware::compression::MurCompression;
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.middleware(MurCompression::gzip_only())
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind compression server");
 	TestServer::start(runner).await
 }
 
-/// Server with a global DI-backed interceptor to verify the factory generated by `#[interceptor]`.
-///
-/// `DependencyInterceptor` injects `GreeterService` from the container.
-/// `GreeterService` is registered directly (not via module) so it is visible
-/// to global factories that run after module setup.
-async fn di_interceptor_server() -> TestServer {
+#[tokio::test]
+async fn large_response_is_gzip_compressed_when_accepted() {
+	let server = compression_server().await;
+	let res = server
+		.get_with("/api/large", &[("accept-encoding", "gzip")])
+		.await;
+
+	assert_eq!(res.status, 200);
+	assert_eq!(res.header("content-encoding"), Some("gzip"));
+	// Highly repetitive ~4KB JSON must shrink dramatically.
+	assert!(
+		res.body.len() < 4096,
+		"compressed body len: {}",
+		res.body.len()
+	);
+
+	// The payload must be a valid gzip stream that restores the original JSON.
+	use std::io::Read;
+	let mut decoder = flate2::read::GzDecoder::new(&res.body[..]);
+	let mut restored = String::new();
+	decoder.read_to_string(&mut restored).expect("valid gzip");
+	let value: serde_json::Value = serde_json::from_str(&restored).expect("valid json");
+	assert_eq!(value["data"].as_str().unwrap().len(), 4096);
+}
+
+#[tokio::test]
+async fn response_not_compressed_without_accept_encoding() {
+	let server = compression_server().await;
+	let res = server.get("/api/large").await;
+
+	assert_eq!(res.status, 200);
+	assert!(res.header("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn small_response_is_not_compressed() {
+	let server = compression_server().await;
+	// /api/hello is far below the 860-byte min_size threshold.
+	let res = server
+		.get_with("/api/hello", &[("accept-encoding", "gzip")])
+		.await;
+
+	assert_eq!(res.status, 200);
+	assert!(res.header("content-encoding").is_none());
+}
+
+// ===========================================================================
+// HTTP caching (ETag middleware + cache interceptor)
+// ===========================================================================
+
+async fn etag_server() -> TestServer {
+	let addr = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.middleware(murgamu::MurETag::new())
+		.module(app::AppModule::new())
+		.bind(addr)
+		.expect("bind etag server");
+	TestServer::start(runner).await
+}
+
+#[tokio::test]
+async fn etag_is_added_and_revalidated_with_304() {
+	let server = etag_server().await;
+	let first = server.get("/api/hello").await;
+	assert_eq!(first.status, 200);
+	let etag = first.header("etag").expect("etag header").to_string();
+	assert!(etag.starts_with("W/\""));
+
+	let second = server
+		.get_with("/api/hello", &[("if-none-match", etag.as_str())])
+		.await;
+	assert_eq!(second.status, 304);
+	assert!(second.body.is_empty());
+	assert_eq!(second.header("etag"), Some(etag.as_str()));
+
+	let stale = server
+		.get_with("/api/hello", &[("if-none-match", "W/\"other\"")])
+		.await;
+	assert_eq!(stale.status, 200);
+}
+
+async fn cache_server(cache: murgamu::MurCache) -> TestServer {
 	let addr = free_addr();
 	let runner = MurServer::new()
 		.no_logging()
 		.default_public_routes()
-		.service(app::GreeterService::new())
-		.global_interceptor(app::NoopInterceptor)
+		.global_interceptor(murgamu::MurCacheInterceptor::new(cache).tag("api"))
 		.module(app::AppModule::new())
-		.interceptor::<app::DependencyInterceptor>()
 		.bind(addr)
-		.expect("bind di-interceptor server");
+		.expect("bind cache server");
 	TestServer::start(runner).await
 }
 
-/// Server with a global authentication guard and protected routes.
-async fn auth_server() -> TestServer {
+#[tokio::test]
+async fn cache_interceptor_serves_hits_until_invalidated() {
+	let cache = murgamu::MurCache::in_memory();
+	let server = cache_server(cache.clone()).await;
+
+	let miss = server.get("/api/users/7").await;
+	assert_eq!(miss.status, 200);
+	assert_eq!(miss.header("x-cache"), Some("MISS"));
+
+	let hit = server.get("/api/users/7").await;
+	assert_eq!(hit.status, 200);
+	assert_eq!(hit.header("x-cache"), Some("HIT"));
+	assert_eq!(hit.json()["id"], 7);
+
+	assert_eq!(cache.invalidate_tag("api"), 1);
+	let after = server.get("/api/users/7").await;
+	assert_eq!(after.header("x-cache"), Some("MISS"));
+}
+
+#[tokio::test]
+async fn cache_interceptor_skips_non_get_requests() {
+	let server = cache_server(murgamu::MurCache::in_memory()).await;
+	let res = server.post_json("/api/echo", r#"{"name":"a","value":1}"#).await;
+	assert!(res.header("x-cache").is_none());
+}
+
+// ===========================================================================
+// Rate limiting: per-route policies
+// ===========================================================================
+
+async fn route_throttled_server() -> TestServer {
 	let addr = free_addr();
 	let runner = MurServer::new()
 		.no_logging()
-		.guard::<app::AuthGuard>()
-		.module(app::SecureModule::new())
+		.default_public_routes()
+		.throttle_policy("burst", murgamu::MurThrottlePolicy::per_minute(3))
+		.middleware(MurThrottler::new().by_ip().requests(1).per_minutes(1))
+		.module(app::ThrottledModule::new())
 		.bind(addr)
-		.expect("bind auth server");
+		.expect("bind route-throttled server");
 	TestServer::start(runner).await
 }
 
+#[tokio::test]
+async fn inline_route_policy_limits_handler() {
+	let server = route_throttled_server().await;
+
+	// The route policy (2/min) replaces the global throttler (1/min).
+	for remaining in ["1", "0"] {
+		let res = server.send("POST", "/limited/login", &[], Vec::new()).await;
+		assert_eq!(res.status, 200);
+		assert_eq!(res.header("x-ratelimit-remaining"), Some(remaining));
+	}
+
+	let res = server.send("POST", "/limited/login", &[], Vec::new()).await;
+	assert_eq!(res.status, 429);
+	assert!(res.header("retry-after").is_some());
+	assert_eq!(
+		res.header("ratelimit-policy"),
+		Some("\"POST /limited/login\";q=2;w=60")
+	);
+}
+
+#[tokio::test]
+async fn controller_named_policy_applies_to_handlers() {
+	let server = route_throttled_server().await;
+
+	let res = server.get("/limited/search").await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.header("ratelimit-policy"), Some("\"burst\";q=3;w=60"));
+	let rate_limit = res.header("ratelimit").expect("RateLimit header");
+	assert!(rate_limit.starts_with("\"burst\";r=2;t="), "{rate_limit}");
+
+	assert_eq!(server.get("/limited/search").await.status, 200);
+	assert_eq!(server.get("/limited/search").await.status, 200);
+	assert_eq!(server.get("/limited/search").await.status, 429);
+}
+
+#[tokio::test]
+async fn skip_throttle_bypasses_global_throttler() {
+	let server = route_throttled_server().await;
+
+	for _ in 0..4 {
+		let res = server.get("/limited/free").await;
+		assert_eq!(res.status, 200);
+		assert!(res.header("ratelimit").is_none());
+	}
+}
+
+#[tokio::test]
+async fn route_policies_honor_fail_open_on_store_errors() {
+	let unreachable = murgamu::RedisThrottlerStore::new(free_addr().to_string());
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.throttle_policy("burst", murgamu::MurThrottlePolicy::per_minute(3).fail_closed())
+		.throttle_store(unreachable)
+		.module(app::ThrottledModule::new())
+		.bind(free_addr())
+		.expect("bind route-throttled server");
+	let server = TestServer::start(runner).await;
+
+	// The inline policy keeps the default and lets requests through.
+	let res = server.send("POST", "/limited/login", &[], Vec::new()).await;
+	assert_eq!(res.status, 200);
+	assert!(res.header("ratelimit").is_none());
+
+	assert_eq!(server.get("/limited/search").await.status, 503);
+}
+
+#[tokio::test]
+async fn unknown_throttle_policy_fails_at_bind() {
+	let result = MurServer::new()
+		.no_logging()
+		.module(app::ThrottledModule::new())
+		.bind(free_addr());
+
+	let err = result.err().expect("bind must fail");
+	assert!(err.to_string().contains("burst"), "{err}");
+}
+
 // ===========================================================================
-// Functional tests
+// Timeouts and deadlines
 // ===========================================================================
 
+async fn deadline_server(timeout: Option<murgamu::MurTimeout>) -> TestServer {
+	let mut server = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.module(app::TimeoutModule::new());
+	if let Some(timeout) = timeout {
+		server = server.middleware(timeout);
+	}
+	let runner = server.bind(free_addr()).expect("bind deadline server");
+	TestServer::start(runner).await
+}
+
 #[tokio::test]
-async fn get_returns_json_body() {
-	let server = functional_server().await;
-	let res = server.get("/api/hello").await;
+async fn route_timeout_applies_without_middleware() {
+	let server = deadline_server(None).await;
 
+	let res = server.get("/deadline/budget").await;
 	assert_eq!(res.status, 200);
-	assert_eq!(res.header("content-type"), Some("application/json"));
-	assert!(res.json()["message"].as_str().unwrap().contains("Hello"));
+	assert_eq!(res.json()["budget_ms"], 5000);
+
+	let res = server.get("/deadline/slow").await;
+	assert_eq!(res.status, 408);
 }
 
 #[tokio::test]
-async fn path_param_is_parsed() {
-	let server = functional_server().await;
-	let res = server.get("/api/users/42").await;
+async fn inbound_timeout_header_shortens_deadline() {
+	let timeout = murgamu::MurTimeout::from_secs(30).log(false);
+	let metrics = std::sync::Arc::clone(timeout.metrics());
+	let server = deadline_server(Some(timeout)).await;
+
+	let res = server
+		.get_with("/deadline/budget", &[("grpc-timeout", "1500m")])
+		.await;
+	assert_eq!(res.json()["budget_ms"], 1500);
+
+	// Larger than the route's own timeout: ignored.
+	let res = server
+		.get_with("/deadline/budget", &[("x-request-timeout", "60")])
+		.await;
+	assert_eq!(res.json()["budget_ms"], 5000);
+
+	assert_eq!(server.get("/deadline/slow").await.status, 408);
+	assert_eq!(metrics.requests(), 3);
+	assert_eq!(metrics.inbound(), 1);
+	assert_eq!(metrics.timed_out(), 1);
+}
+
+// ===========================================================================
+// Request IDs
+// ===========================================================================
+
+async fn request_id_server() -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.middleware(murgamu::MurRequestId::new())
+		.exception_filter(app::CustomErrorFilter)
+		.module(app::AppModule::new())
+		.bind(free_addr())
+		.expect("bind request-id server");
+	TestServer::start(runner).await
+}
 
+#[tokio::test]
+async fn request_id_is_generated_and_added_to_error_bodies() {
+	let server = request_id_server().await;
+
+	let res = server.get("/api/conflict").await;
+	assert_eq!(res.status, 409);
+	let id = res.header("x-request-id").expect("X-Request-Id").to_string();
+	assert_eq!(id.len(), 36, "{id}");
+	assert_eq!(res.json()["request_id"], id.as_str());
+
+	let other = server.get("/api/conflict").await;
+	assert_ne!(other.header("x-request-id"), Some(id.as_str()));
+}
+
+#[tokio::test]
+async fn incoming_request_id_is_reused() {
+	let server = request_id_server().await;
+
+	let res = server
+		.get_with("/api/conflict", &[("x-request-id", "upstream-42")])
+		.await;
+	assert_eq!(res.header("x-request-id"), Some("upstream-42"));
+	assert_eq!(res.json()["request_id"], "upstream-42");
+
+	// Malformed IDs are replaced rather than echoed.
+	let res = server
+		.get_with("/api/conflict", &[("x-request-id", "a\"b")])
+		.await;
+	assert_ne!(res.header("x-request-id"), Some("a\"b"));
+}
+
+#[tokio::test]
+async fn exception_filters_see_request_id() {
+	let server = request_id_server().await;
+
+	let res = server
+		.get_with("/api/teapot", &[("x-request-id", "filter-1")])
+		.await;
+	assert_eq!(res.status, 418);
+	assert_eq!(res.json()["filtered"], true);
+	assert_eq!(res.json()["request_id"], "filter-1");
+	assert_eq!(res.header("x-request-id"), Some("filter-1"));
+}
+
+// ---------------------------------------------------------------------------
+// Pipes
+// ---------------------------------------------------------------------------
+
+async fn pipe_server() -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.pipe::<murgamu::TrimPipe>()
+		.exception_filter(app::PipeErrorFilter)
+		.module(app::PipeModule::new())
+		.bind(free_addr())
+		.expect("bind pipe server");
+	TestServer::start(runner).await
+}
+
+#[tokio::test]
+async fn param_pipe_parses_and_rejects_through_filters() {
+	let server = pipe_server().await;
+
+	let res = server.get("/pipes/items/42").await;
 	assert_eq!(res.status, 200);
 	assert_eq!(res.json()["id"], 42);
+
+	let res = server.get("/pipes/items/abc").await;
+	assert_eq!(res.status, 422);
+	let message = res.json()["pipe_error"].as_str().unwrap().to_string();
+	assert!(message.contains("numeric string is expected"), "{message}");
 }
 
 #[tokio::test]
-async fn invalid_path_param_is_rejected() {
-	let server = functional_server().await;
-	let res = server.get("/api/users/not-a-number").await;
+async fn default_value_pipe_fills_missing_query_param() {
+	let server = pipe_server().await;
 
-	// u32 parse failure -> 400 Bad Request
-	assert_eq!(res.status, 400);
+	assert_eq!(server.get("/pipes/list").await.json()["limit"], 20);
+	assert_eq!(server.get("/pipes/list?limit=5").await.json()["limit"], 5);
 }
 
 #[tokio::test]
-async fn query_params_are_parsed() {
-	let server = functional_server().await;
-	let res = server.get("/api/search?term=rust&limit=5").await;
+async fn method_pipes_run_after_global_and_controller_pipes() {
+	let server = pipe_server().await;
 
+	let res = server
+		.get("/pipes/orders/6F9619FF-8B86-4011-B42D-00C04FC964FF")
+		.await;
 	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["term"], "rust");
-	assert_eq!(res.json()["limit"], 5);
+	assert_eq!(res.json()["order"], "6f9619ff-8b86-4011-b42d-00c04fc964ff");
+
+	let res = server.get("/pipes/orders/not-a-uuid").await;
+	assert_eq!(res.status, 422);
 }
 
 #[tokio::test]
-async fn query_params_use_defaults_when_missing() {
-	let server = functional_server().await;
-	let res = server.get("/api/search").await;
+async fn validation_pipe_checks_trimmed_body() {
+	let server = pipe_server().await;
 
+	let res = server
+		.post_json("/pipes/signup", r#"{"name":"  Ada  ","age":36}"#)
+		.await;
 	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["term"], "none");
-	assert_eq!(res.json()["limit"], 10);
+	assert_eq!(res.json()["name"], "Ada");
+
+	let res = server
+		.post_json("/pipes/signup", r#"{"name":"Bob","age":12}"#)
+		.await;
+	assert_eq!(res.status, 422);
+	let message = res.json()["pipe_error"].as_str().unwrap().to_string();
+	assert!(message.contains("age must be at least 18"), "{message}");
+}
+
+// ---------------------------------------------------------------------------
+// Route metadata
+// ---------------------------------------------------------------------------
+
+async fn metadata_server() -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.guard::<app::ScopeGuard>()
+		.module(app::MetadataModule::new())
+		.bind(free_addr())
+		.expect("bind metadata server");
+	TestServer::start(runner).await
 }
 
 #[tokio::test]
-async fn post_json_body_is_deserialized() {
-	let server = functional_server().await;
+async fn guard_reads_typed_route_metadata() {
+	let server = metadata_server().await;
+
+	let res = server.get("/meta/reports/7").await;
+	assert_eq!(res.status, 403);
+
 	let res = server
-		.post_json("/api/echo", r#"{"name":"ada","value":7}"#)
+		.get_with("/meta/reports/7", &[("x-scope", "reports:read")])
 		.await;
+	assert_eq!(res.status, 200);
+	let body = res.json();
+	assert_eq!(body["pattern"], "/meta/reports/:id");
+	assert!(
+		body["controller"].as_str().unwrap().ends_with("MetadataController"),
+		"{body}"
+	);
+	assert_eq!(body["handler"], "report");
+	assert_eq!(body["area"], "reports");
+}
+
+#[tokio::test]
+async fn method_metadata_and_decorators_override_controller_metadata() {
+	let server = metadata_server().await;
 
+	let res = server.get("/meta/beta").await;
 	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["name"], "ada");
-	assert_eq!(res.json()["value"], 7);
+	let body = res.json();
+	assert_eq!(body["area"], "labs");
+	assert_eq!(body["feature"], "beta");
+	assert_eq!(body["flag"], "beta");
+	assert!(body["scope"].is_null());
+}
+
+// ---------------------------------------------------------------------------
+// Around interceptors
+// ---------------------------------------------------------------------------
+
+async fn around_server() -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.global_interceptor(app::OrderInterceptor("outer"))
+		.global_interceptor(app::OrderInterceptor("inner"))
+		.global_interceptor(app::RetryInterceptor)
+		.module(app::AroundModule::new())
+		.bind(free_addr())
+		.expect("bind around server");
+	TestServer::start(runner).await
 }
 
 #[tokio::test]
-async fn malformed_json_body_is_rejected() {
-	let server = functional_server().await;
-	let res = server.post_json("/api/echo", "{ this is not json").await;
+async fn interceptors_nest_around_the_handler() {
+	let server = around_server().await;
 
-	assert_eq!(res.status, 400);
+	let res = server.get("/around/trail").await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["trail"], "outer>inner>");
+	assert_eq!(res.header("x-last-exit"), Some("outer"));
 }
 
 #[tokio::test]
-async fn post_form_body_is_deserialized() {
-	let server = functional_server().await;
-	let res = server
-		.send(
-			"POST",
-			"/api/form",
-			&[("content-type", "application/x-www-form-urlencoded")],
-			b"name=grace&value=99".to_vec(),
-		)
-		.await;
+async fn around_interceptor_can_retry_and_short_circuit() {
+	let server = around_server().await;
 
+	let res = server.get("/around/flaky").await;
 	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["name"], "grace");
-	assert_eq!(res.json()["value"], 99);
+	assert_eq!(res.header("x-retried"), Some("1"));
+
+	let res = server.get_with("/around/flaky", &[("x-short", "1")]).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["short"], true);
+	assert_eq!(res.header("x-last-exit"), Some("outer"));
+}
+
+// ---------------------------------------------------------------------------
+// Controller and handler guards/interceptors
+// ---------------------------------------------------------------------------
+
+async fn scoped_server() -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.global_interceptor(app::OrderInterceptor("global"))
+		.module(app::ScopedModule::new())
+		.bind(free_addr())
+		.expect("bind scoped server");
+	TestServer::start(runner).await
 }
 
 #[tokio::test]
-async fn put_patch_delete_reach_handlers() {
-	let server = functional_server().await;
+async fn use_guards_resolve_from_the_module_container() {
+	let server = scoped_server().await;
 
-	let put = server.send("PUT", "/api/items/1", &[], Vec::new()).await;
-	assert_eq!(put.status, 200);
-	assert_eq!(put.json()["updated"], 1);
+	let res = server.get("/scoped/trail").await;
+	assert_eq!(res.status, 403);
 
-	let patch = server.send("PATCH", "/api/items/2", &[], Vec::new()).await;
-	assert_eq!(patch.status, 200);
-	assert_eq!(patch.json()["patched"], 2);
+	let res = server.get_with("/scoped/trail", &[("x-tenant", "other")]).await;
+	assert_eq!(res.status, 403);
 
-	let del = server.send("DELETE", "/api/items/3", &[], Vec::new()).await;
-	assert_eq!(del.status, 200);
-	assert_eq!(del.json()["deleted"], 3);
+	let tenant = [("x-tenant", "acme")];
+	let res = server.send("DELETE", "/scoped/tenant", &tenant, Vec::new()).await;
+	assert_eq!(res.status, 403);
+
+	let admin = [("x-tenant", "acme"), ("x-role", "admin")];
+	let res = server.send("DELETE", "/scoped/tenant", &admin, Vec::new()).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["removed"], true);
+}
+
+#[tokio::test]
+async fn use_interceptors_nest_inside_global_interceptors() {
+	let server = scoped_server().await;
+
+	let res = server.get_with("/scoped/trail", &[("x-tenant", "acme")]).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["trail"], "global>controller>handler>");
+	assert_eq!(res.header("x-last-exit"), Some("global"));
+	assert_eq!(res.header("x-tenant-known"), Some("true"));
+}
+
+// ---------------------------------------------------------------------------
+// Problem details
+// ---------------------------------------------------------------------------
+
+async fn problem_server() -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.exception_filter(app::RequestEchoFilter)
+		.module(app::ProblemModule::new())
+		.bind(free_addr())
+		.expect("bind problem server");
+	TestServer::start(runner).await
+}
+
+#[tokio::test]
+async fn errors_are_negotiated_as_problem_details() {
+	let server = problem_server().await;
+
+	let res = server.get("/problems/orders/7").await;
+	assert_eq!(res.status, 404);
+	assert_eq!(res.header("content-type"), Some("application/json"));
+	assert_eq!(res.json()["kind"], "not_found");
+
+	let accept = [("accept", "application/problem+json, application/json;q=0.5")];
+	let res = server.get_with("/problems/orders/7", &accept).await;
+	assert_eq!(res.status, 404);
+	assert_eq!(res.header("content-type"), Some("application/problem+json"));
+	let body = res.json();
+	assert_eq!(body["type"], "about:blank");
+	assert_eq!(body["title"], "Not Found");
+	assert_eq!(body["status"], 404);
+	assert_eq!(body["detail"], "order 7 not found");
+	assert_eq!(body["instance"], "/problems/orders/7");
+}
+
+#[tokio::test]
+async fn problems_and_per_route_formats_are_always_problem_json() {
+	let server = problem_server().await;
+
+	let res = server.get("/problems/credit").await;
+	assert_eq!(res.status, 403);
+	assert_eq!(res.header("content-type"), Some("application/problem+json"));
+	let body = res.json();
+	assert_eq!(body["type"], "https://example.com/probs/out-of-credit");
+	assert_eq!(body["title"], "You do not have enough credit.");
+	assert_eq!(body["balance"], 30);
+
+	let res = server.get("/problems/strict").await;
+	assert_eq!(res.status, 403);
+	assert_eq!(res.header("content-type"), Some("application/problem+json"));
+	assert_eq!(res.json()["detail"], "not for you");
+}
+
+#[tokio::test]
+async fn field_validation_errors_fill_the_errors_extension() {
+	let server = problem_server().await;
+
+	let res = server
+		.send(
+			"POST",
+			"/problems/accounts",
+			&[
+				("content-type", "application/json"),
+				("accept", "application/problem+json"),
+			],
+			br#"{"email":"nope","age":16}"#.to_vec(),
+		)
+		.await;
+	assert_eq!(res.status, 400);
+	let body = res.json();
+	assert_eq!(body["detail"], "Validation failed");
+	assert_eq!(body["errors"][0]["field"], "email");
+	assert_eq!(body["errors"][1]["field"], "age");
+	assert_eq!(body["errors"][1]["detail"], "must be at least 18");
+
+	let res = server
+		.post_json("/problems/accounts", r#"{"email":"a@b.c","age":16}"#)
+		.await;
+	assert_eq!(res.status, 400);
+	assert_eq!(res.json()["errors"][0]["field"], "age");
 }
 
 #[tokio::test]
-async fn head_falls_back_to_get_handler() {
-	let server = functional_server().await;
-	let res = server.send("HEAD", "/api/hello", &[], Vec::new()).await;
-
-	assert_eq!(res.status, 200);
+async fn exception_filters_receive_the_request_context() {
+	let server = problem_server().await;
+
+	let res = server.send("DELETE", "/problems/archive", &[], Vec::new()).await;
+	assert_eq!(res.status, 410);
+	let body = res.json();
+	assert_eq!(body["method"], "DELETE");
+	assert_eq!(body["path"], "/problems/archive");
+	assert_eq!(body["route"], "/problems/archive");
 }
 
-#[tokio::test]
-async fn options_lists_allowed_methods() {
-	let server = functional_server().await;
-	let res = server
-		.send("OPTIONS", "/api/items/1", &[], Vec::new())
-		.await;
+// ---------------------------------------------------------------------------
+// Fallbacks and trailing slashes
+// ---------------------------------------------------------------------------
 
-	assert_eq!(res.status, 204);
-	let allow = res.header("allow").unwrap_or_default();
-	assert!(allow.contains("PUT"), "Allow header was: {allow}");
-	assert!(allow.contains("DELETE"), "Allow header was: {allow}");
+async fn fallback_server(policy: MurTrailingSlash) -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.middleware(app::StampMiddleware)
+		.trailing_slash(policy)
+		.not_found(|ctx: MurRequestContext| async move {
+			MurHttpResponse::not_found().json(serde_json::json!({
+				"fallback": "root",
+				"path": ctx.path(),
+			}))
+		})
+		.not_found_for("/api", |ctx: MurRequestContext| async move {
+			MurHttpResponse::not_found().json(serde_json::json!({
+				"fallback": "api",
+				"method": ctx.method().as_str(),
+			}))
+		})
+		.module(app::AppModule::new())
+		.bind(free_addr())
+		.expect("bind fallback server");
+	TestServer::start(runner).await
 }
 
 #[tokio::test]
-async fn unknown_route_returns_404_json() {
-	let server = functional_server().await;
-	// Use a prefix with no registered controller so nothing (not even the
-	// `/api/:slug` catch route) matches.
-	let res = server.get("/totally/unknown/path").await;
+async fn not_found_handlers_run_through_middleware_per_prefix() {
+	let server = fallback_server(MurTrailingSlash::Lenient).await;
 
+	let res = server.get("/nowhere?x=1").await;
 	assert_eq!(res.status, 404);
-	assert_eq!(res.json()["status"], 404);
-}
+	assert_eq!(res.header("x-middleware"), Some("on"));
+	assert_eq!(res.json()["fallback"], "root");
+	assert_eq!(res.json()["path"], "/nowhere");
 
-#[tokio::test]
-async fn text_and_html_content_types() {
-	let server = functional_server().await;
+	let res = server.send("PUT", "/api/nothing/here", &[], Vec::new()).await;
+	assert_eq!(res.status, 404);
+	assert_eq!(res.json()["fallback"], "api");
+	assert_eq!(res.json()["method"], "PUT");
 
-	let text = server.get("/api/text").await;
-	assert_eq!(text.status, 200);
-	assert!(
-		text.header("content-type")
-			.unwrap_or_default()
-			.contains("text/plain")
-	);
-	assert_eq!(text.text(), "plain text body");
+	let res = server.get("/apiary").await;
+	assert_eq!(res.json()["fallback"], "root");
 
-	let html = server.get("/api/html").await;
-	assert_eq!(html.status, 200);
-	assert!(
-		html.header("content-type")
-			.unwrap_or_default()
-			.contains("text/html")
-	);
+	let res = server.get("/nowhere").await;
+	assert_eq!(res.header("x-middleware"), Some("on"));
+	let res = server.get_with("/nowhere", &[("x-mw-block", "1")]).await;
+	assert_eq!(res.status, 403);
 }
 
 #[tokio::test]
-async fn error_type_maps_to_status_codes() {
-	let server = functional_server().await;
+async fn trailing_slash_policies() {
+	let server = fallback_server(MurTrailingSlash::Lenient).await;
+	assert_eq!(server.get("/api/hello/").await.status, 200);
 
-	let conflict = server.get("/api/conflict").await;
-	assert_eq!(conflict.status, 409);
-
-	let teapot = server.get("/api/teapot").await;
-	assert_eq!(teapot.status, 418);
+	let server = fallback_server(MurTrailingSlash::Strict).await;
+	let res = server.get("/api/hello/").await;
+	assert_eq!(res.status, 404);
+	assert_eq!(res.json()["fallback"], "api");
+	assert_eq!(server.get("/api/hello").await.status, 200);
+
+	let server = fallback_server(MurTrailingSlash::Redirect).await;
+	let res = server.get("/api/items/3/?full=1").await;
+	assert_eq!(res.status, 308);
+	assert_eq!(res.header("location"), Some("/api/items/3?full=1"));
+	let res = server.get("/missing/").await;
+	assert_eq!(res.status, 404);
 }
 
+// ---------------------------------------------------------------------------
+// Constrained path parameters
+// ---------------------------------------------------------------------------
+
 #[tokio::test]
-async fn dependency_injection_resolves_nested_services() {
-	// /api/hello exercises ApiController -> UserService -> GreeterService.
-	let server = functional_server().await;
-	let res = server.get("/api/users/5").await;
+async fn constrained_params_fall_through_to_the_next_route() {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.module(app::TypedPathModule::new())
+		.bind(free_addr())
+		.expect("bind typed path server");
+	let server = TestServer::start(runner).await;
 
-	assert_eq!(res.status, 200);
-	let describe = res.json()["describe"].as_str().unwrap().to_string();
-	assert!(describe.contains("user #5"), "got: {describe}");
-}
+	let res = server.get("/typed/users/42").await;
+	assert_eq!(res.json()["by"], "id");
+	assert_eq!(res.json()["id"], 42);
+	let res = server.get("/typed/users/ada").await;
+	assert_eq!(res.json()["by"], "name");
 
-#[tokio::test]
-async fn interceptor_after_adds_header() {
-	let server = functional_server().await;
-	let res = server.get("/api/hello").await;
+	assert_eq!(server.get("/typed/posts").await.json()["page"], 1);
+	assert_eq!(server.get("/typed/posts/3").await.json()["page"], 3);
+	assert_eq!(server.get("/typed/posts/last").await.status, 404);
 
-	assert_eq!(res.status, 200);
-	assert_eq!(res.header("x-intercepted"), Some("true"));
+	let res = server.get("/typed/files/report.final.pdf").await;
+	assert_eq!(res.json()["name"], "report.final");
+	assert_eq!(res.json()["ext"], "pdf");
 }
 
 #[tokio::test]
-async fn interceptor_before_can_short_circuit() {
-	let server = functional_server().await;
-	let res = server.get_with("/api/hello", &[("x-deny", "1")]).await;
+async fn conflicting_routes_are_rejected_at_bind() {
+	let result = MurServer::new()
+		.no_logging()
+		.module(app::AmbiguousModule::new())
+		.bind(free_addr());
 
-	assert_eq!(res.status, 401);
+	let err = result.err().expect("conflicting routes must not bind");
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+	assert!(err.to_string().contains("/ambiguous/:id"), "{err}");
+	assert!(err.to_string().contains("/ambiguous/:name"), "{err}");
 }
 
-// --- #[interceptor] macro-specific tests ------------------------------------
+// ---------------------------------------------------------------------------
+// Prefixes and host routing
+// ---------------------------------------------------------------------------
+
+async fn prefixed_server() -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.global_prefix("/api")
+		.exclude_from_global_prefix(["/healthz"])
+		.module(app::AdminScopeModule::new())
+		.module(app::SiteModule::new())
+		.bind(free_addr())
+		.expect("bind prefixed server");
+	TestServer::start(runner).await
+}
 
-/// Verifies that a unit-struct interceptor created with `#[interceptor]` and a
-/// no-op `impl MurInterceptor` does not alter the response.
 #[tokio::test]
-async fn interceptor_macro_noop_does_not_affect_response() {
-	let server = di_interceptor_server().await;
-	let res = server.get("/api/hello").await;
+async fn global_and_module_prefixes_apply_to_controller_routes() {
+	let server = prefixed_server().await;
 
+	let res = server.get("/api/admin/stats").await;
 	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["route"], "/api/admin/stats");
+	assert_eq!(server.get("/admin/stats").await.status, 404);
+
+	assert_eq!(server.get("/healthz/live").await.status, 200);
+	assert_eq!(server.get("/api/healthz/live").await.status, 404);
 }
 
-/// Verifies that an interceptor created with `#[interceptor]` that injects a
-/// service via DI resolves the dependency and stamps a response header.
 #[tokio::test]
-async fn interceptor_macro_with_di_stamps_header() {
-	let server = di_interceptor_server().await;
-	let res = server.get("/api/hello").await;
+async fn host_patterns_route_by_subdomain() {
+	let server = prefixed_server().await;
 
+	let res = server
+		.get_with("/api/home", &[("Host", "Acme.example.com:8080")])
+		.await;
 	assert_eq!(res.status, 200);
-	let header = res.header("x-di-interceptor").unwrap_or_default();
-	assert!(
-		header.contains("interceptor"),
-		"expected greeter output in X-DI-Interceptor, got: {header}"
-	);
+	assert_eq!(res.json()["tenant"], "acme");
+
+	let res = server
+		.get_with("/api/home", &[("Host", "example.com")])
+		.await;
+	assert_eq!(res.status, 200);
+	assert!(res.json()["tenant"].is_null());
+	assert!(server.get("/api/home").await.json()["tenant"].is_null());
 }
 
-/// Verifies that a DI-backed interceptor can still short-circuit requests via
-/// `before` (the supertrait bound is satisfied even without the macro generating
-/// an `impl MurInterceptor` block).
-#[tokio::test]
-async fn interceptor_macro_noop_passes_requests_through() {
-	let server = di_interceptor_server().await;
+// ---------------------------------------------------------------------------
+// API versioning
+// ---------------------------------------------------------------------------
 
-	let ok = server.get("/api/hello").await;
-	assert_eq!(ok.status, 200);
+async fn versioned_server(versioning: MurVersioning) -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.versioning(versioning)
+		.open_api(MurOpenApi::new("Versioned", "2.0.0"))
+		.module(app::VersionedModule::new())
+		.bind(free_addr())
+		.expect("bind versioned server");
+	TestServer::start(runner).await
+}
 
-	let not_found = server.get("/no-such-route").await;
-	assert_eq!(not_found.status, 404);
+fn sunset() -> chrono::DateTime<chrono::Utc> {
+	chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2027, 1, 1, 0, 0, 0).unwrap()
 }
 
 #[tokio::test]
-async fn middleware_adds_header() {
-	let server = functional_server().await;
-	let res = server.get("/api/hello").await;
-
-	assert_eq!(res.header("x-middleware"), Some("on"));
+async fn header_versioning_selects_routes_and_announces_deprecation() {
+	let server = versioned_server(
+		MurVersioning::header("X-API-Version")
+			.default_version("2")
+			.deprecate("1", MurVersionDeprecation::new().sunset(sunset())),
+	)
+	.await;
+
+	let res = server.get_with("/vusers", &[("X-API-Version", "1")]).await;
+	assert_eq!(res.json()["handler"], "v1");
+	assert_eq!(res.json()["version"], "1");
+	assert_eq!(res.header("deprecation"), Some("true"));
+	assert_eq!(res.header("sunset"), Some("Fri, 01 Jan 2027 00:00:00 GMT"));
+
+	let res = server.get("/vusers").await;
+	assert_eq!(res.json()["handler"], "v2");
+	assert_eq!(res.header("deprecation"), None);
+
+	assert_eq!(server.get_with("/vusers", &[("X-API-Version", "3")]).await.status, 404);
+
+	let res = server.get_with("/vusers/ping", &[("X-API-Version", "1")]).await;
+	assert_eq!(res.json()["handler"], "neutral");
+	assert_eq!(res.json()["version"], "1");
+	assert_eq!(res.header("deprecation"), None);
 }
 
 #[tokio::test]
-async fn middleware_can_short_circuit() {
-	let server = functional_server().await;
-	let res = server.get_with("/api/hello", &[("x-mw-block", "1")]).await;
+async fn media_type_and_query_versioning() {
+	let server = versioned_server(MurVersioning::media_type()).await;
+	let res = server
+		.get_with("/vusers", &[("Accept", "application/json; v=1")])
+		.await;
+	assert_eq!(res.json()["handler"], "v1");
+	assert_eq!(server.get("/vusers").await.status, 404);
+	assert_eq!(server.get("/vusers/ping").await.json()["handler"], "neutral");
 
-	assert_eq!(res.status, 403);
-	assert_eq!(res.json()["error"], "blocked by middleware");
+	let server = versioned_server(MurVersioning::query("api-version")).await;
+	let res = server.get("/vusers?api-version=2").await;
+	assert_eq!(res.json()["handler"], "v2");
 }
 
-// ===========================================================================
-// Security tests
-// ===========================================================================
-
 #[tokio::test]
-async fn public_route_bypasses_auth() {
-	let server = auth_server().await;
-	let res = server.get("/secure/open").await;
-
-	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["scope"], "public");
+async fn uri_versioning_prefixes_paths_and_documents_versions() {
+	let server = versioned_server(
+		MurVersioning::uri()
+			.default_version("2")
+			.deprecate("1", MurVersionDeprecation::new()),
+	)
+	.await;
+
+	assert_eq!(server.get("/v1/vusers").await.json()["handler"], "v1");
+	assert_eq!(server.get("/v1/vusers").await.header("deprecation"), Some("true"));
+	assert_eq!(server.get("/v2/vusers").await.json()["version"], "2");
+	assert_eq!(server.get("/vusers").await.json()["handler"], "v2");
+	assert!(server.get("/vusers/ping").await.json()["version"].is_null());
+
+	let spec = server.get("/api-docs/openapi.json").await.json();
+	assert_eq!(spec["paths"]["/v1/vusers"]["get"]["deprecated"], true);
+	assert_eq!(spec["paths"]["/v2/vusers"]["get"]["x-api-versions"][0], "2");
+	assert!(spec["paths"]["/vusers/ping"]["get"]["x-api-versions"].is_null());
 }
 
 #[tokio::test]
-async fn protected_route_without_token_is_rejected() {
-	let server = auth_server().await;
-	let res = server.get("/secure/me").await;
+async fn versioned_routes_need_a_versioning_strategy() {
+	let result = MurServer::new()
+		.no_logging()
+		.module(app::VersionedModule::new())
+		.bind(free_addr());
 
-	// Guard returns false -> default rejection is 403 Forbidden.
-	assert_eq!(res.status, 403);
+	let err = result.err().expect("versioned routes must not bind");
+	assert!(err.to_string().contains("no versioning strategy"), "{err}");
 }
 
-#[tokio::test]
-async fn protected_route_with_invalid_token_is_rejected() {
-	let server = auth_server().await;
-	let res = server
-		.get_with("/secure/me", &[("authorization", "Bearer nope")])
-		.await;
+// ---------------------------------------------------------------------------
+// Content negotiation
+// ---------------------------------------------------------------------------
 
-	assert_eq!(res.status, 403);
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
+async fn readings_server() -> TestServer {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.module(app::ReadingsModule::new())
+		.bind(free_addr())
+		.expect("bind readings server");
+	TestServer::start(runner).await
 }
 
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
 #[tokio::test]
-async fn protected_route_with_valid_token_is_allowed() {
-	let server = auth_server().await;
+async fn payloads_are_read_and_written_in_negotiated_formats() {
+	let server = readings_server().await;
+	let reading = app::Reading {
+		sensor: "t1".to_string(),
+		value: 21.5,
+	};
+
 	let res = server
-		.get_with("/secure/me", &[("authorization", "Bearer user-token")])
+		.send(
+			"POST",
+			"/readings",
+			&[("content-type", "application/msgpack"), ("accept", "application/cbor")],
+			murgamu::MurBodyFormat::MessagePack.encode(&reading).unwrap(),
+		)
 		.await;
-
 	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["scope"], "authenticated");
-}
+	assert_eq!(res.header("content-type"), Some("application/cbor"));
+	assert_eq!(res.header("vary"), Some("Accept"));
+	let echoed: app::Reading = murgamu::MurBodyFormat::Cbor.decode(&res.body).unwrap();
+	assert_eq!(echoed, reading);
 
-#[tokio::test]
-async fn role_protected_route_denies_insufficient_role() {
-	let server = auth_server().await;
 	let res = server
-		.get_with("/secure/admin", &[("authorization", "Bearer user-token")])
+		.send(
+			"POST",
+			"/readings",
+			&[("accept", "application/xml;q=0.5, application/json;q=0.9")],
+			br#"{"sensor":"t1","value":21.5}"#.to_vec(),
+		)
 		.await;
+	assert_eq!(res.header("content-type"), Some("application/json"));
+	assert_eq!(res.json()["value"], 21.5);
 
-	assert_eq!(res.status, 403);
+	let res = server
+		.send(
+			"POST",
+			"/readings",
+			&[("content-type", "application/xml"), ("accept", "text/*, */*;q=0.1")],
+			b"<Reading><sensor>t1</sensor><value>21.5</value></Reading>".to_vec(),
+		)
+		.await;
+	assert_eq!(res.header("content-type"), Some("application/json"));
+	assert_eq!(res.json()["sensor"], "t1");
 }
 
+#[cfg(all(feature = "cbor", feature = "msgpack", feature = "xml"))]
 #[tokio::test]
-async fn role_protected_route_allows_matching_role() {
-	let server = auth_server().await;
+async fn unsupported_and_unacceptable_media_types_are_rejected() {
+	let server = readings_server().await;
+	let body = br#"{"sensor":"t1","value":1}"#.to_vec();
+
 	let res = server
-		.get_with("/secure/admin", &[("authorization", "Bearer admin-token")])
+		.send(
+			"POST",
+			"/readings",
+			&[("content-type", "text/csv")],
+			b"t1,1".to_vec(),
+		)
 		.await;
+	assert_eq!(res.status, 415);
 
-	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["scope"], "admin");
+	let res = server
+		.send(
+			"POST",
+			"/readings",
+			&[("content-type", "application/json"), ("accept", "text/html")],
+			body.clone(),
+		)
+		.await;
+	assert_eq!(res.status, 406);
+
+	let res = server
+		.send(
+			"POST",
+			"/readings",
+			&[("accept", "application/json;q=0, application/*;q=0.5")],
+			body,
+		)
+		.await;
+	assert_eq!(res.header("content-type"), Some("application/msgpack"));
 }
 
-#[tokio::test]
-async fn body_size_limit_returns_413() {
-	let addr = free_addr();
+// ---------------------------------------------------------------------------
+// Sessions and signed cookies
+// ---------------------------------------------------------------------------
+
+const COOKIE_SECRET: &str = "integration-test-secret-0123456789";
+
+async fn session_server() -> TestServer {
 	let runner = MurServer::new()
+		.no_logging()
 		.default_public_routes()
-		.configure(MurServerConfig::default().no_logging().body_size_limit(64))
-		.module(app::AppModule::new())
-		.bind(addr)
-		.expect("bind body-limit server");
-	let server = TestServer::start(runner).await;
-
-	// Build a JSON body well over the 64-byte limit.
-	let big_name = "x".repeat(512);
-	let body = format!(r#"{{"name":"{big_name}","value":1}}"#);
-	let res = server.post_json("/api/echo", &body).await;
+		.cookie_keys(MurCookieKeys::new(COOKIE_SECRET).unwrap())
+		.module(MurSessionModule::new())
+		.module(app::AccountModule::new())
+		.bind(free_addr())
+		.expect("bind session server");
+	TestServer::start(runner).await
+}
 
-	assert_eq!(res.status, 413);
+/// The `name=value` pair of a `Set-Cookie` header.
+fn cookie_pair(res: &TestResponse) -> String {
+	let set_cookie = res.header("set-cookie").expect("set-cookie");
+	set_cookie.split(';').next().unwrap().to_string()
 }
 
 #[tokio::test]
-async fn body_within_limit_is_accepted() {
-	let addr = free_addr();
-	let runner = MurServer::new()
-		.default_public_routes()
-		.configure(
-			MurServerConfig::default()
-				.no_logging()
-				.body_size_limit(4096),
-		)
-		.module(app::AppModule::new())
-		.bind(addr)
-		.expect("bind body-limit server");
-	let server = TestServer::start(runner).await;
+async fn sessions_survive_requests_and_rotate_on_login() {
+	let server = session_server().await;
+
+	let res = server.get("/account/me").await;
+	assert_eq!(res.json()["user"], serde_json::Value::Null);
+	assert!(res.header("set-cookie").is_none(), "empty sessions are not stored");
+
+	let res = server.send("POST", "/account/login", &[], Vec::new()).await;
+	let set_cookie = res.header("set-cookie").unwrap();
+	assert!(set_cookie.contains("HttpOnly"), "{set_cookie}");
+	assert!(set_cookie.contains("SameSite=Lax"), "{set_cookie}");
+	let cookie = cookie_pair(&res);
+	assert!(cookie.starts_with("mur.sid="));
+
+	let res = server.get_with("/account/me", &[("cookie", &cookie)]).await;
+	assert_eq!(res.json()["user"], "ana");
+	assert_eq!(res.json()["visits"], 1);
+	assert_eq!(res.json()["notice"], "Welcome back");
+	assert!(res.header("set-cookie").is_none());
+
+	let res = server.get_with("/account/me", &[("cookie", &cookie)]).await;
+	assert_eq!(res.json()["visits"], 2);
+	assert_eq!(res.json()["notice"], serde_json::Value::Null);
 
 	let res = server
-		.post_json("/api/echo", r#"{"name":"ok","value":1}"#)
+		.send("POST", "/account/login", &[("cookie", &cookie)], Vec::new())
 		.await;
-	assert_eq!(res.status, 200);
+	let rotated = cookie_pair(&res);
+	assert_ne!(rotated, cookie);
+	let res = server.get_with("/account/me", &[("cookie", &cookie)]).await;
+	assert_eq!(res.json()["user"], serde_json::Value::Null);
+	let res = server.get_with("/account/me", &[("cookie", &rotated)]).await;
+	assert_eq!(res.json()["visits"], 3);
+
+	let res = server
+		.send("POST", "/account/logout", &[("cookie", &rotated)], Vec::new())
+		.await;
+	assert!(res.header("set-cookie").unwrap().contains("Max-Age=0"));
+	let res = server.get_with("/account/me", &[("cookie", &rotated)]).await;
+	assert_eq!(res.json()["user"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn tampered_session_and_signed_cookies_are_ignored() {
+	let server = session_server().await;
+
+	let res = server.send("POST", "/account/login", &[], Vec::new()).await;
+	let cookie = cookie_pair(&res);
+	let (id, signature) = cookie.rsplit_once('.').unwrap();
+	let forged = format!("{}x.{}", id, signature);
+	let res = server.get_with("/account/me", &[("cookie", &forged)]).await;
+	assert_eq!(res.json()["user"], serde_json::Value::Null);
+
+	let res = server.get("/account/theme").await;
+	assert_eq!(res.json()["theme"], serde_json::Value::Null);
+	let theme = cookie_pair(&res);
+	assert!(theme.starts_with("theme=dark."), "{theme}");
+
+	let res = server.get_with("/account/theme", &[("cookie", &theme)]).await;
+	assert_eq!(res.json()["theme"], "dark");
+	let forged = theme.replacen("dark", "neon", 1);
+	let res = server.get_with("/account/theme", &[("cookie", &forged)]).await;
+	assert_eq!(res.json()["theme"], serde_json::Value::Null);
 }
 
 #[tokio::test]
-async fn rate_limiter_blocks_after_quota() {
-	let addr = free_addr();
+async fn malformed_session_cookie_starts_a_new_session() {
+	let dir = std::env::temp_dir().join(format!("murgamu-sessions-{}", uuid::Uuid::new_v4()));
 	let runner = MurServer::new()
 		.no_logging()
 		.default_public_routes()
-		.middleware(MurThrottler::new().global().requests(3).per_minutes(1))
-		.module(app::AppModule::new())
-		.bind(addr)
-		.expect("bind rate-limit server");
+		.module(MurSessionModule::new().store(murgamu::MurFileSessionStore::new(&dir)))
+		.module(app::AccountModule::new())
+		.bind(free_addr())
+		.expect("bind session server");
 	let server = TestServer::start(runner).await;
 
-	// First 3 requests pass.
-	for _ in 0..3 {
-		let res = server.get("/api/hello").await;
-		assert_eq!(res.status, 200);
-	}
+	let stale = [("cookie", "mur.sid=../x")];
+	let res = server.get_with("/account/me", &stale).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["user"], serde_json::Value::Null);
 
-	// 4th exceeds the quota.
-	let blocked = server.get("/api/hello").await;
-	assert_eq!(blocked.status, 429);
-	assert!(blocked.header("retry-after").is_some());
+	let res = server.send("POST", "/account/login", &stale, Vec::new()).await;
+	assert_eq!(res.status, 200);
+	let cookie = cookie_pair(&res);
+	assert_ne!(cookie, "mur.sid=../x");
+	let res = server.get_with("/account/me", &[("cookie", &cookie)]).await;
+	assert_eq!(res.json()["user"], "ana");
+
+	let _ = std::fs::remove_dir_all(&dir);
+}
+
+async fn gate_server(in_place: bool) -> TestServer {
+	let server = MurServer::new().no_logging().default_public_routes();
+	let server = if in_place {
+		server.module_in_place(app::GateModule)
+	} else {
+		server.module(app::GateModule)
+	};
+	let runner = server
+		.middleware(app::StampMiddleware)
+		.module(app::AppModule::new())
+		.bind(free_addr())
+		.expect("bind gate server");
+	TestServer::start(runner).await
 }
 
 #[tokio::test]
-async fn cors_permissive_allows_any_origin() {
-	let server = functional_server().await;
-	let res = server
-		.get_with("/api/hello", &[("origin", "http://anywhere.test")])
-		.await;
+async fn module_middleware_runs_after_global_middleware() {
+	let server = gate_server(false).await;
+	let res = server.get_with("/api/hello", &[("x-gate-close", "1")]).await;
+	assert_eq!(res.status, 403);
+	assert_eq!(res.json()["error"], "closed by gate");
+	assert_eq!(res.header("x-middleware"), Some("on"));
+}
 
+#[tokio::test]
+async fn module_in_place_middleware_runs_where_registered() {
+	let server = gate_server(true).await;
+	let res = server.get_with("/api/hello", &[("x-gate-close", "1")]).await;
+	assert_eq!(res.status, 403);
+	assert_eq!(res.header("x-middleware"), None);
+
+	let res = server.get("/api/hello").await;
 	assert_eq!(res.status, 200);
-	assert_eq!(res.header("access-control-allow-origin"), Some("*"));
+	assert_eq!(res.header("x-middleware"), Some("on"));
 }
 
-async fn cors_restricted_server() -> TestServer {
-	let addr = free_addr();
+// ---------------------------------------------------------------------------
+// CSRF protection
+// ---------------------------------------------------------------------------
+
+async fn csrf_server(csrf: MurCsrf) -> TestServer {
 	let runner = MurServer::new()
 		.no_logging()
 		.default_public_routes()
-		.cors(vec!["http://allowed.test".to_string()])
-		.module(app::AppModule::new())
-		.bind(addr)
-		.expect("bind cors server");
+		.module_in_place(MurSessionModule::new())
+		.middleware(csrf)
+		.module(app::CsrfFormModule::new())
+		.bind(free_addr())
+		.expect("bind csrf server");
 	TestServer::start(runner).await
 }
 
-#[tokio::test]
-async fn cors_restricted_reflects_allowed_origin() {
-	let server = cors_restricted_server().await;
-	let res = server
-		.get_with("/api/hello", &[("origin", "http://allowed.test")])
-		.await;
-
-	assert_eq!(res.status, 200);
-	assert_eq!(
-		res.header("access-control-allow-origin"),
-		Some("http://allowed.test")
-	);
+/// The token embedded in the rendered form.
+fn form_token(res: &TestResponse) -> String {
+	let html = res.text();
+	let start = html.find("value=\"").expect("hidden input") + "value=\"".len();
+	html[start..].split('"').next().unwrap().to_string()
 }
 
 #[tokio::test]
-async fn cors_restricted_rejects_unknown_origin() {
-	let server = cors_restricted_server().await;
+async fn csrf_double_submit_tokens_protect_forms() {
+	let server = csrf_server(MurCsrf::new()).await;
+
+	let res = server.get("/forms/profile").await;
+	let token = form_token(&res);
+	let cookie = cookie_pair(&res);
+	assert_eq!(cookie, format!("mur.csrf={}", token));
+
+	let form = format!("_csrf={}", token);
 	let res = server
-		.get_with("/api/hello", &[("origin", "http://evil.test")])
+		.send(
+			"POST",
+			"/forms/profile",
+			&[
+				("cookie", &cookie),
+				("content-type", "application/x-www-form-urlencoded"),
+				("origin", &format!("http://{}", server.addr)),
+			],
+			form.into_bytes(),
+		)
 		.await;
+	assert_eq!(res.status, 200, "{}", res.text());
 
+	let res = server
+		.send("POST", "/forms/profile", &[("cookie", &cookie)], Vec::new())
+		.await;
 	assert_eq!(res.status, 403);
-}
 
-#[tokio::test]
-async fn cors_preflight_returns_no_content_with_headers() {
-	let server = cors_restricted_server().await;
 	let res = server
 		.send(
-			"OPTIONS",
-			"/api/hello",
+			"POST",
+			"/forms/profile",
 			&[
-				("origin", "http://allowed.test"),
-				("access-control-request-method", "GET"),
+				("cookie", &cookie),
+				("x-csrf-token", &token),
+				("sec-fetch-site", "cross-site"),
 			],
 			Vec::new(),
 		)
 		.await;
+	assert_eq!(res.status, 403);
 
-	assert_eq!(res.status, 204);
-	assert_eq!(
-		res.header("access-control-allow-origin"),
-		Some("http://allowed.test")
-	);
-	assert!(
-		res.header("access-control-allow-methods")
-			.unwrap_or_default()
-			.contains("GET")
-	);
+	let res = server
+		.send(
+			"POST",
+			"/forms/webhook",
+			&[("origin", "https://billing.example")],
+			Vec::new(),
+		)
+		.await;
+	assert_eq!(res.status, 200);
 }
 
 #[tokio::test]
-async fn internal_errors_do_not_leak_details() {
-	let server = functional_server().await;
-	let res = server.get("/api/boom").await;
+async fn csrf_synchronizer_tokens_live_in_the_session() {
+	let server = csrf_server(MurCsrf::synchronizer()).await;
 
-	assert_eq!(res.status, 500);
-	let body = res.text();
-	assert!(
-		!body.contains("hunter2"),
-		"internal error detail leaked to client: {body}"
-	);
-	assert!(body.contains("Internal Server Error"));
+	let res = server.get("/forms/profile").await;
+	let token = form_token(&res);
+	let session = cookie_pair(&res);
+	assert!(session.starts_with("mur.sid="), "{session}");
+
+	let res = server.get_with("/forms/profile", &[("cookie", &session)]).await;
+	assert_eq!(form_token(&res), token);
+
+	let res = server
+		.send(
+			"POST",
+			"/forms/profile",
+			&[("cookie", &session), ("x-csrf-token", &token)],
+			Vec::new(),
+		)
+		.await;
+	assert_eq!(res.status, 200);
+
+	let res = server
+		.send("POST", "/forms/profile", &[("x-csrf-token", &token)], Vec::new())
+		.await;
+	assert_eq!(res.status, 403);
 }
 
-// ===========================================================================
-// Routing edge cases
-// ===========================================================================
+#[test]
+fn csrf_synchronizer_without_sessions_ahead_is_rejected_at_bind() {
+	let missing = MurServer::new()
+		.no_logging()
+		.middleware(MurCsrf::synchronizer())
+		.module(app::CsrfFormModule::new())
+		.bind(free_addr());
+	let err = missing.err().expect("csrf without sessions must not bind");
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+	assert!(err.to_string().contains("MurSessionMiddleware"), "{err}");
+
+	// Plain `module` middleware runs after global middleware, so too late.
+	let late = MurServer::new()
+		.no_logging()
+		.module(MurSessionModule::new())
+		.middleware(MurCsrf::synchronizer())
+		.module(app::CsrfFormModule::new())
+		.bind(free_addr());
+	assert!(late.is_err());
+}
 
-#[tokio::test]
-async fn wildcard_matches_exactly_one_segment() {
-	let server = functional_server().await;
+// ---------------------------------------------------------------------------
+// OpenID Connect login
+// ---------------------------------------------------------------------------
 
-	let ok = server.get("/api/files/doc.txt").await;
-	assert_eq!(ok.status, 200);
-	assert_eq!(ok.json()["matched"], "wildcard");
+/// An identity provider served from the test process: discovery, JWKS and a
+/// token endpoint issuing RS256 ID tokens. The user approving a login is
+/// played by [`MockIdp::authorize`].
+struct MockIdp {
+	addr: SocketAddr,
+	state: Arc<Mutex<MockIdpState>>,
+}
 
-	// `*` consumes a single segment only; a deeper path must not match.
-	let deep = server.get("/api/files/a/b").await;
-	assert_eq!(deep.status, 404);
+struct MockIdpState {
+	/// The published keys; the last one signs.
+	keys: Vec<(String, RsaKeyPair)>,
+	/// Authorization codes with their PKCE challenge and nonce.
+	codes: HashMap<String, (String, String)>,
+	refresh_tokens: HashSet<String>,
+	jwks_fetches: usize,
+	issued: usize,
+	expires_in: u64,
 }
 
-#[tokio::test]
-async fn catch_all_captures_remaining_path() {
-	let server = functional_server().await;
-	let res = server.get("/api/tree/a/b/c").await;
+impl MockIdp {
+	async fn start() -> Self {
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+			.await
+			.expect("bind idp");
+		let addr = listener.local_addr().expect("idp addr");
+		let state = Arc::new(Mutex::new(MockIdpState {
+			keys: Vec::new(),
+			codes: HashMap::new(),
+			refresh_tokens: HashSet::new(),
+			jwks_fetches: 0,
+			issued: 0,
+			expires_in: 3600,
+		}));
+
+		let shared = Arc::clone(&state);
+		tokio::spawn(async move {
+			while let Ok((stream, _)) = listener.accept().await {
+				let state = Arc::clone(&shared);
+				tokio::spawn(async move {
+					let service = hyper::service::service_fn(move |req| {
+						let state = Arc::clone(&state);
+						async move {
+							Ok::<_, std::convert::Infallible>(mock_idp(addr, &state, req).await)
+						}
+					});
+					let _ = hyper::server::conn::http1::Builder::new()
+						.serve_connection(TokioIo::new(stream), service)
+						.await;
+				});
+			}
+		});
 
-	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["rest"], "a/b/c");
+		let idp = Self { addr, state };
+		idp.rotate_key();
+		idp
+	}
+
+	fn issuer(&self) -> String {
+		format!("http://{}", self.addr)
+	}
+
+	/// Publishes a new signing key, keeping the old ones.
+	fn rotate_key(&self) {
+		let mut state = self.state.lock().unwrap();
+		let kid = format!("key-{}", state.keys.len() + 1);
+		let key = RsaKeyPair::generate(KeySize::Rsa2048).expect("rsa key");
+		state.keys.push((kid, key));
+	}
+
+	fn jwks_fetches(&self) -> usize {
+		self.state.lock().unwrap().jwks_fetches
+	}
+
+	/// Approves the login the user was redirected to, returning the callback
+	/// path the provider would send them back to.
+	fn authorize(&self, location: &str) -> String {
+		let (endpoint, query) = location.split_once('?').expect("authorization query");
+		assert_eq!(endpoint, format!("{}/authorize", self.issuer()));
+		let params: HashMap<String, String> = serde_urlencoded::from_str(query).unwrap();
+		assert_eq!(params["response_type"], "code");
+		assert_eq!(params["client_id"], "app");
+		assert_eq!(params["code_challenge_method"], "S256");
+		assert!(params["scope"].split(' ').any(|scope| scope == "openid"));
+
+		let mut state = self.state.lock().unwrap();
+		let code = format!("code-{}", state.codes.len() + state.issued);
+		state.codes.insert(
+			code.clone(),
+			(params["code_challenge"].clone(), params["nonce"].clone()),
+		);
+		let callback = params["redirect_uri"].split_once("/auth/").unwrap().1;
+		let query = serde_urlencoded::to_string([("code", &code), ("state", &params["state"])]);
+		format!("/auth/{}?{}", callback, query.unwrap())
+	}
 }
 
-#[tokio::test]
-async fn static_route_beats_dynamic_param() {
-	let server = functional_server().await;
+async fn mock_idp(
+	addr: SocketAddr,
+	state: &Mutex<MockIdpState>,
+	req: Request<hyper::body::Incoming>,
+) -> hyper::Response<Full<Bytes>> {
+	let issuer = format!("http://{}", addr);
+	let path = req.uri().path().to_string();
+	let auth = req
+		.headers()
+		.get("authorization")
+		.and_then(|value| value.to_str().ok())
+		.map(str::to_string);
+	let body = req.into_body().collect().await.unwrap().to_bytes();
+
+	let (status, json) = match path.as_str() {
+		"/.well-known/openid-configuration" => (
+			200,
+			serde_json::json!({
+				"issuer": issuer,
+				"authorization_endpoint": format!("{}/authorize", issuer),
+				"token_endpoint": format!("{}/token", issuer),
+				"jwks_uri": format!("{}/jwks", issuer),
+				"end_session_endpoint": format!("{}/logout", issuer),
+				"id_token_signing_alg_values_supported": ["RS256"],
+			}),
+		),
+		"/jwks" => {
+			let mut state = state.lock().unwrap();
+			state.jwks_fetches += 1;
+			let keys: Vec<_> = state
+				.keys
+				.iter()
+				.map(|(kid, key)| {
+					let public = key.public_key();
+					let n = public.modulus().big_endian_without_leading_zero();
+					let e = public.exponent().big_endian_without_leading_zero();
+					serde_json::json!({
+						"kty": "RSA",
+						"kid": kid,
+						"alg": "RS256",
+						"use": "sig",
+						"n": URL_SAFE_NO_PAD.encode(n),
+						"e": URL_SAFE_NO_PAD.encode(e),
+					})
+				})
+				.collect();
+			(200, serde_json::json!({ "keys": keys }))
+		}
+		"/token" => mock_token(&issuer, state, auth, &body),
+		_ => (404, serde_json::json!({ "error": "not_found" })),
+	};
+
+	hyper::Response::builder()
+		.status(status)
+		.header("content-type", "application/json")
+		.body(Full::new(Bytes::from(json.to_string())))
+		.unwrap()
+}
 
-	let stat = server.get("/api/profile").await;
-	assert_eq!(stat.status, 200);
-	assert_eq!(stat.json()["route"], "static");
+fn mock_token(
+	issuer: &str,
+	state: &Mutex<MockIdpState>,
+	auth: Option<String>,
+	body: &[u8],
+) -> (u16, serde_json::Value) {
+	let invalid_grant = serde_json::json!({ "error": "invalid_grant" });
+	if auth != Some(format!("Basic {}", STANDARD.encode("app:secret"))) {
+		return (401, serde_json::json!({ "error": "invalid_client" }));
+	}
+	let form: HashMap<String, String> = serde_urlencoded::from_bytes(body).unwrap();
+	let mut state = state.lock().unwrap();
 
-	let dynamic = server.get("/api/anything-else").await;
-	assert_eq!(dynamic.status, 200);
-	assert_eq!(dynamic.json()["route"], "dynamic");
-	assert_eq!(dynamic.json()["slug"], "anything-else");
+	let nonce = match form["grant_type"].as_str() {
+		"authorization_code" => {
+			let Some((challenge, nonce)) = state.codes.remove(&form["code"]) else {
+				return (400, invalid_grant);
+			};
+			let verifier = digest(&SHA256, form["code_verifier"].as_bytes());
+			if URL_SAFE_NO_PAD.encode(verifier) != challenge {
+				return (400, invalid_grant);
+			}
+			Some(nonce)
+		}
+		"refresh_token" if state.refresh_tokens.remove(&form["refresh_token"]) => None,
+		_ => return (400, invalid_grant),
+	};
+
+	state.issued += 1;
+	let now = std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.unwrap()
+		.as_secs();
+	let mut claims = serde_json::json!({
+		"iss": issuer,
+		"sub": "alice",
+		"aud": "app",
+		"email": "alice@example.com",
+		"iat": now,
+		"exp": now + 300,
+	});
+	if let Some(nonce) = nonce {
+		claims["nonce"] = serde_json::json!(nonce);
+	}
+
+	let (kid, key) = state.keys.last().unwrap();
+	let header = serde_json::json!({ "alg": "RS256", "kid": kid, "typ": "JWT" });
+	let input = format!(
+		"{}.{}",
+		URL_SAFE_NO_PAD.encode(header.to_string()),
+		URL_SAFE_NO_PAD.encode(claims.to_string())
+	);
+	let mut signature = vec![0; key.public_modulus_len()];
+	key.sign(&RSA_PKCS1_SHA256, &SystemRandom::new(), input.as_bytes(), &mut signature)
+		.unwrap();
+	let id_token = format!("{}.{}", input, URL_SAFE_NO_PAD.encode(signature));
+
+	let refresh_token = format!("rt-{}", state.issued);
+	state.refresh_tokens.insert(refresh_token.clone());
+	(
+		200,
+		serde_json::json!({
+			"access_token": format!("at-{}", state.issued),
+			"token_type": "Bearer",
+			"expires_in": state.expires_in,
+			"refresh_token": refresh_token,
+			"id_token": id_token,
+		}),
+	)
 }
 
-#[tokio::test]
-async fn trailing_slash_is_normalized() {
-	let server = functional_server().await;
-	let res = server.get("/api/hello/").await;
-	assert_eq!(res.status, 200);
+async fn oidc_server(idp: &MockIdp) -> TestServer {
+	let addr = free_addr();
+	let oidc = MurOidcModule::new(idp.issuer(), "app", format!("http://{}/auth/callback", addr))
+		.client_secret("secret")
+		.jwks_refresh_interval(Duration::ZERO);
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.module(MurSessionModule::new())
+		.module(oidc)
+		.module(app::OidcProfileModule::new())
+		.bind(addr)
+		.expect("bind oidc server");
+	TestServer::start(runner).await
 }
 
-#[tokio::test]
-async fn wrong_method_on_existing_path_is_404() {
-	let server = functional_server().await;
-	// `/api/hello` is GET-only; POST has no matching route.
-	let res = server.send("POST", "/api/hello", &[], Vec::new()).await;
-	assert_eq!(res.status, 404);
+/// Logs in through the provider, returning the session cookie.
+async fn oidc_login(server: &TestServer, idp: &MockIdp) -> String {
+	let res = server.get("/auth/login").await;
+	let cookie = cookie_pair(&res);
+	let callback = idp.authorize(res.header("location").unwrap());
+	let res = server.get_with(&callback, &[("cookie", &cookie)]).await;
+	assert_eq!(res.status, 302, "{}", res.text());
+	cookie_pair(&res)
 }
 
 #[tokio::test]
-async fn empty_body_to_json_route_is_rejected() {
-	let server = functional_server().await;
-	let res = server.send("POST", "/api/echo", &[], Vec::new()).await;
+async fn oidc_login_runs_the_code_flow_with_pkce() {
+	let idp = MockIdp::start().await;
+	let server = oidc_server(&idp).await;
+
+	let res = server.get("/auth/login?return_to=/oidc/me").await;
+	assert_eq!(res.status, 302);
+	let location = res.header("location").unwrap().to_string();
+	let cookie = cookie_pair(&res);
+	let callback = idp.authorize(&location);
+
+	let forged = callback.replace("state=", "state=x");
+	let res = server.get_with(&forged, &[("cookie", &cookie)]).await;
+	assert_eq!(res.status, 400);
+	let res = server.get(&callback).await;
 	assert_eq!(res.status, 400);
-}
 
-#[tokio::test]
-async fn json_content_type_with_charset_still_parses() {
-	let server = functional_server().await;
+	let res = server.get_with(&callback, &[("cookie", &cookie)]).await;
+	assert_eq!(res.status, 302, "{}", res.text());
+	assert_eq!(res.header("location"), Some("/oidc/me"));
+	let session = cookie_pair(&res);
+	assert_ne!(session, cookie);
+
+	let res = server.get_with("/oidc/me", &[("cookie", &session)]).await;
+	assert_eq!(res.json()["sub"], "alice");
+	assert_eq!(res.json()["email"], "alice@example.com");
+	let res = server.get_with(&callback, &[("cookie", &session)]).await;
+	assert_eq!(res.status, 400);
+
+	let res = server.get_with("/auth/logout", &[("cookie", &session)]).await;
+	assert_ne!(res.status, 303);
+	let res = server.get_with("/oidc/me", &[("cookie", &session)]).await;
+	assert_eq!(res.json()["sub"], "alice");
+
 	let res = server
-		.send(
-			"POST",
-			"/api/echo",
-			&[("content-type", "application/json; charset=utf-8")],
-			br#"{"name":"x","value":1}"#.to_vec(),
-		)
+		.send("POST", "/auth/logout", &[("cookie", &session)], Vec::new())
 		.await;
-	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["name"], "x");
+	assert_eq!(res.status, 303);
+	let location = res.header("location").unwrap();
+	assert!(location.starts_with(&format!("{}/logout?", idp.issuer())), "{location}");
+	assert!(location.contains("id_token_hint="));
+	let res = server.get_with("/oidc/me", &[("cookie", &session)]).await;
+	assert_eq!(res.json()["sub"], serde_json::Value::Null);
+
+	let res = server.get("/auth/login?return_to=https://evil.test").await;
+	let cookie = cookie_pair(&res);
+	let callback = idp.authorize(res.header("location").unwrap());
+	let res = server.get_with(&callback, &[("cookie", &cookie)]).await;
+	assert_eq!(res.header("location"), Some("/"));
 }
 
-// ===========================================================================
-// Cookies
-// ===========================================================================
-
 #[tokio::test]
-async fn set_cookie_carries_security_attributes() {
-	let server = functional_server().await;
-	let res = server.get("/api/cookie").await;
-
-	assert_eq!(res.status, 200);
-	let cookie = res.header("set-cookie").expect("Set-Cookie present");
-	assert!(cookie.contains("sid=secret123"), "cookie: {cookie}");
-	assert!(cookie.contains("HttpOnly"), "cookie: {cookie}");
-	assert!(cookie.contains("Secure"), "cookie: {cookie}");
-	assert!(cookie.contains("SameSite=Strict"), "cookie: {cookie}");
+async fn oidc_picks_up_rotated_keys_and_refreshes_tokens() {
+	let idp = MockIdp::start().await;
+	let server = oidc_server(&idp).await;
+
+	oidc_login(&server, &idp).await;
+	assert_eq!(idp.jwks_fetches(), 1);
+	idp.rotate_key();
+	idp.state.lock().unwrap().expires_in = 5;
+	let session = oidc_login(&server, &idp).await;
+	assert_eq!(idp.jwks_fetches(), 2);
+
+	let res = server.get_with("/oidc/me", &[("cookie", &session)]).await;
+	assert_eq!(res.json()["sub"], "alice");
+	assert_eq!(res.json()["access_token"], "at-3");
+	let res = server.get_with("/oidc/me", &[("cookie", &session)]).await;
+	assert_eq!(res.json()["access_token"], "at-4");
+
+	idp.state.lock().unwrap().refresh_tokens.clear();
+	let res = server.get_with("/oidc/me", &[("cookie", &session)]).await;
+	assert_eq!(res.json()["sub"], serde_json::Value::Null);
 }
 
-// ===========================================================================
-// Security headers (opt-in middleware)
-// ===========================================================================
-
-async fn security_headers_server() -> TestServer {
-	use murgamu::server::security::headers::{MurSecurityHeaders, XFrameOptions};
-	let addr = free_addr();
+async fn api_key_server() -> TestServer {
+	let store = MurMemoryApiKeyStore::new()
+		.with_key(MurApiKey::new("reader", "rk-1").scope("orders:read"))
+		.with_key(MurApiKey::new("admin", "ak-1").scope("*").tier("gold"))
+		.with_key(MurApiKey::new("gone", "gk-1").scope("*").expires_at(chrono::Utc::now()));
 	let runner = MurServer::new()
 		.no_logging()
 		.default_public_routes()
-		.middleware(
-			MurSecurityHeaders::new()
-				.x_content_type_options(true)
-				.x_frame_options(XFrameOptions::Deny),
-		)
-		.module(app::AppModule::new())
-		.bind(addr)
-		.expect("bind security-headers server");
+		.middleware(MurApiKeyAuth::new(store).query("api_key"))
+		.middleware(MurThrottler::new().requests(2).by_api_key().tier("gold", 4))
+		.module(app::OrdersApiModule::new())
+		.bind(free_addr())
+		.expect("bind api key server");
 	TestServer::start(runner).await
 }
 
 #[tokio::test]
-async fn security_headers_are_applied_when_enabled() {
-	let server = security_headers_server().await;
-	let res = server.get("/api/hello").await;
+async fn api_keys_authenticate_and_check_route_scopes() {
+	let server = api_key_server().await;
+
+	let res = server.get("/api/orders").await;
+	assert_eq!(res.status, 401);
+	let res = server.get_with("/api/orders", &[("x-api-key", "gk-1")]).await;
+	assert_eq!(res.status, 401);
+
+	let res = server.get_with("/api/orders", &[("x-api-key", "rk-1")]).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["key"], "reader");
+	let res = server.send("DELETE", "/api/orders/7", &[("x-api-key", "rk-1")], Vec::new()).await;
+	assert_eq!(res.status, 403);
+
+	let res = server.send("DELETE", "/api/orders/7?api_key=ak-1", &[], Vec::new()).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["removed"], 7);
+}
+
+#[tokio::test]
+async fn api_keys_are_throttled_per_key_and_tier() {
+	let server = api_key_server().await;
+
+	for _ in 0..2 {
+		let res = server.get_with("/api/orders", &[("x-api-key", "rk-1")]).await;
+		assert_eq!(res.status, 200);
+	}
+	let res = server.get_with("/api/orders", &[("x-api-key", "rk-1")]).await;
+	assert_eq!(res.status, 429);
+
+	for _ in 0..4 {
+		let res = server.get_with("/api/orders?api_key=ak-1", &[]).await;
+		assert_eq!(res.status, 200);
+		assert_eq!(res.json()["tier"], "gold");
+	}
+	let res = server.get_with("/api/orders?api_key=ak-1", &[]).await;
+	assert_eq!(res.status, 429);
+}
+
+#[tokio::test]
+async fn route_throttle_can_be_keyed_by_api_key() {
+	let server = api_key_server().await;
 
+	let res = server.send("POST", "/api/orders/export", &[("x-api-key", "rk-1")], Vec::new()).await;
 	assert_eq!(res.status, 200);
-	assert_eq!(res.header("x-content-type-options"), Some("nosniff"));
-	assert_eq!(res.header("x-frame-options"), Some("DENY"));
+	let res = server.send("POST", "/api/orders/export", &[("x-api-key", "rk-1")], Vec::new()).await;
+	assert_eq!(res.status, 429);
+
+	let res = server.send("POST", "/api/orders/export?api_key=ak-1", &[], Vec::new()).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["exported"], true);
 }
 
-#[tokio::test]
-async fn security_headers_absent_by_default() {
-	// Documents that the framework does NOT add security headers unless the
-	// MurSecurityHeaders middleware is explicitly installed.
-	let server = functional_server().await;
-	let res = server.get("/api/hello").await;
-	assert!(res.header("x-frame-options").is_none());
-	assert!(res.header("x-content-type-options").is_none());
+// ---- mutual TLS ------------------------------------------------------------
+
+type ClientIdentity = (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>);
+
+/// A server certificate plus a client CA that issues service certificates.
+struct MtlsPki {
+	server_cert: CertificateDer<'static>,
+	server_cert_pem: String,
+	server_key_pem: String,
+	ca_pem: String,
+	issuer: Issuer<'static, rcgen::KeyPair>,
 }
 
-// ===========================================================================
-// Rate limiting: keying & spoofability
-// ===========================================================================
+impl MtlsPki {
+	fn new() -> Self {
+		let server = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+		let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+		params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+		params.distinguished_name.push(DnType::CommonName, "Services CA");
+		let key = rcgen::KeyPair::generate().unwrap();
+		let ca_pem = params.self_signed(&key).unwrap().pem();
+		Self {
+			server_cert: server.cert.der().clone(),
+			server_cert_pem: server.cert.pem(),
+			server_key_pem: server.signing_key.serialize_pem(),
+			ca_pem,
+			issuer: Issuer::new(params, key),
+		}
+	}
 
-async fn ip_throttled_server() -> TestServer {
-	let addr = free_addr();
+	fn client(&self, service: &str) -> ClientIdentity {
+		let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+		params.distinguished_name.push(DnType::CommonName, service);
+		let uri = format!("spiffe://prod/{service}/api");
+		params.subject_alt_names.push(SanType::URI(uri.try_into().unwrap()));
+		let key = rcgen::KeyPair::generate().unwrap();
+		let cert = params.signed_by(&key, &self.issuer).unwrap();
+		let key = PrivateKeyDer::Pkcs8(key.serialize_der().into());
+		(vec![cert.der().clone()], key)
+	}
+}
+
+async fn mtls_server(pki: &MtlsPki, optional: bool) -> TestServer {
+	let config = MurTlsConfig::from_pem(&pki.server_cert_pem, &pki.server_key_pem)
+		.unwrap()
+		.client_ca_certs_pem(&pki.ca_pem)
+		.unwrap()
+		.client_auth_optional(optional);
 	let runner = MurServer::new()
 		.no_logging()
 		.default_public_routes()
-		.middleware(MurThrottler::new().by_ip().requests(2).per_minutes(1))
-		.module(app::AppModule::new())
-		.bind(addr)
-		.expect("bind ip-throttled server");
+		.module(app::MtlsModule::new())
+		.bind_tls(free_addr(), config)
+		.expect("bind mtls server");
 	TestServer::start(runner).await
 }
 
-#[tokio::test]
-async fn rate_limit_is_per_forwarded_ip() {
-	let server = ip_throttled_server().await;
-	let ip_a = [("x-forwarded-for", "10.0.0.1")];
+/// GETs `path` over TLS as `server_name`, trusting only `trusted` and
+/// presenting `identity` as the client certificate.
+async fn tls_get(
+	server: &TestServer,
+	server_name: &str,
+	trusted: &CertificateDer<'static>,
+	identity: Option<&ClientIdentity>,
+	path: &str,
+) -> Result<TestResponse, Box<dyn std::error::Error + Send + Sync>> {
+	let mut roots = rustls::RootCertStore::empty();
+	roots.add(trusted.clone())?;
+	let config = rustls::ClientConfig::builder().with_root_certificates(roots);
+	let config = match identity {
+		Some((chain, key)) => config.with_client_auth_cert(chain.clone(), key.clone_key())?,
+		None => config.with_no_client_auth(),
+	};
+	let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+	let stream = TcpStream::connect(server.addr).await?;
+	let server_name = ServerName::try_from(server_name.to_string())?;
+	let stream = connector.connect(server_name, stream).await?;
+
+	let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+	tokio::spawn(async move {
+		let _ = conn.await;
+	});
+	let req = Request::get(path)
+		.header("host", "localhost")
+		.body(Full::new(Bytes::new()))?;
+	let res = sender.send_request(req).await?;
+	let status = res.status().as_u16();
+	let body = res.into_body().collect().await?.to_bytes().to_vec();
+	Ok(TestResponse {
+		status,
+		headers: HashMap::new(),
+		body,
+	})
+}
 
-	assert_eq!(server.get_with("/api/hello", &ip_a).await.status, 200);
-	assert_eq!(server.get_with("/api/hello", &ip_a).await.status, 200);
-	// Third request from the same IP exceeds the quota.
-	assert_eq!(server.get_with("/api/hello", &ip_a).await.status, 429);
+async fn mtls_get(
+	server: &TestServer,
+	pki: &MtlsPki,
+	identity: Option<&ClientIdentity>,
+	path: &str,
+) -> Result<TestResponse, Box<dyn std::error::Error + Send + Sync>> {
+	tls_get(server, "localhost", &pki.server_cert, identity, path).await
 }
 
 #[tokio::test]
-async fn rate_limit_is_bypassable_by_spoofing_forwarded_ip() {
-	// SECURITY NOTE: IP-based throttling trusts `X-Forwarded-For` verbatim, so
-	// a client that rotates the header gets a fresh bucket each time. This test
-	// documents that bypass — deployments must only trust XFF from a known proxy.
-	let server = ip_throttled_server().await;
-
-	for i in 0..6 {
-		let spoofed = format!("10.0.0.{i}");
-		let headers = [("x-forwarded-for", spoofed.as_str())];
-		let res = server.get_with("/api/hello", &headers).await;
-		assert_eq!(
-			res.status, 200,
-			"spoofed IP {spoofed} should get its own bucket"
-		);
-	}
-}
+async fn mtls_client_certificates_reach_handlers_and_guards() {
+	let pki = MtlsPki::new();
+	let server = mtls_server(&pki, true).await;
 
-// ===========================================================================
-// Robustness: large/odd inputs must not crash the server
-// ===========================================================================
+	let res = mtls_get(&server, &pki, None, "/mtls/whoami").await.unwrap();
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["cn"], serde_json::Value::Null);
+	let res = mtls_get(&server, &pki, None, "/mtls/billing").await.unwrap();
+	assert_eq!(res.status, 403);
 
-#[tokio::test]
-async fn very_long_query_string_does_not_crash() {
-	let server = functional_server().await;
-	let long = "a".repeat(8192);
-	let res = server.get(&format!("/api/search?term={long}")).await;
+	let billing = pki.client("billing");
+	let res = mtls_get(&server, &pki, Some(&billing), "/mtls/whoami").await.unwrap();
+	assert_eq!(res.json()["cn"], "billing");
+	assert_eq!(res.json()["sans"], serde_json::json!(["spiffe://prod/billing/api"]));
+	let res = mtls_get(&server, &pki, Some(&billing), "/mtls/billing").await.unwrap();
 	assert_eq!(res.status, 200);
-	assert_eq!(res.json()["term"], long);
+	let expected: String = digest(&SHA256, &billing.0[0])
+		.as_ref()
+		.iter()
+		.map(|b| format!("{b:02x}"))
+		.collect();
+	assert_eq!(res.json()["fingerprint"], expected);
+
+	let reports = pki.client("reports");
+	let res = mtls_get(&server, &pki, Some(&reports), "/mtls/billing").await.unwrap();
+	assert_eq!(res.status, 403);
 }
 
 #[tokio::test]
-async fn deeply_nested_catch_all_path_is_handled() {
-	let server = functional_server().await;
-	let deep = (0..200)
-		.map(|i| i.to_string())
-		.collect::<Vec<_>>()
-		.join("/");
-	let res = server.get(&format!("/api/tree/{deep}")).await;
+async fn mtls_required_mode_rejects_missing_and_untrusted_certificates() {
+	let pki = MtlsPki::new();
+	let server = mtls_server(&pki, false).await;
+
+	assert!(mtls_get(&server, &pki, None, "/mtls/whoami").await.is_err());
+	let stranger = MtlsPki::new().client("billing");
+	assert!(mtls_get(&server, &pki, Some(&stranger), "/mtls/whoami").await.is_err());
+
+	let billing = pki.client("billing");
+	let res = mtls_get(&server, &pki, Some(&billing), "/mtls/billing").await.unwrap();
 	assert_eq!(res.status, 200);
 }
 
-#[tokio::test]
-async fn many_distinct_routes_remain_independently_resolvable() {
-	let server = functional_server().await;
-	// Hit a spread of routes back-to-back; each must resolve to its own handler.
-	assert_eq!(server.get("/api/text").await.text(), "plain text body");
-	assert_eq!(server.get("/api/users/9").await.json()["id"], 9);
-	assert_eq!(server.get("/api/profile").await.json()["route"], "static");
-	assert_eq!(server.get("/api/conflict").await.status, 409);
+// ---- TLS certificate selection and reload ----------------------------------
+
+/// Writes a fresh `localhost` certificate pair into `dir`, backdated by
+/// `age` seconds so the next write registers as a change.
+fn write_server_cert(dir: &std::path::Path, age: u64) -> CertificateDer<'static> {
+	let generated = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+	let modified = std::time::SystemTime::now() - Duration::from_secs(age);
+	let files = [
+		("cert.pem", generated.cert.pem()),
+		("key.pem", generated.signing_key.serialize_pem()),
+	];
+	for (name, pem) in files {
+		std::fs::write(dir.join(name), pem).unwrap();
+		let file = std::fs::File::options().write(true).open(dir.join(name)).unwrap();
+		file.set_modified(modified).unwrap();
+	}
+	generated.cert.der().clone()
 }
 
-// ===========================================================================
-// Performance / load (generous ceilings to avoid CI flakiness)
-// ===========================================================================
-
 #[tokio::test]
-async fn perf_handles_concurrent_requests() {
-	let server = functional_server().await;
-	let addr = server.addr;
-
-	let start = std::time::Instant::now();
-	let mut set = tokio::task::JoinSet::new();
-	for _ in 0..200 {
-		set.spawn(async move { raw_request(addr, "GET", "/api/hello", &[], Vec::new()).await });
-	}
+async fn tls_selects_certificates_by_sni_and_reloads_rotated_files() {
+	let dir = std::env::temp_dir().join(format!("mur-tls-{}", uuid::Uuid::new_v4()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let original = write_server_cert(&dir, 60);
+	let api = rcgen::generate_simple_self_signed(vec!["api.example.test".to_string()]).unwrap();
+	let config = MurTlsConfig::from_pem_files(dir.join("cert.pem"), dir.join("key.pem"))
+		.unwrap()
+		.sni_cert_pem("api.example.test", &api.cert.pem(), &api.signing_key.serialize_pem())
+		.unwrap()
+		.reload_interval(Duration::from_millis(20));
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.module(app::MtlsModule::new())
+		.bind_tls(free_addr(), config)
+		.expect("bind tls server");
+	let server = TestServer::start(runner).await;
 
-	let mut ok = 0;
-	while let Some(res) = set.join_next().await {
-		if res.expect("task panicked").status == 200 {
-			ok += 1;
+	let res = tls_get(&server, "localhost", &original, None, "/mtls/whoami").await;
+	assert_eq!(res.unwrap().status, 200);
+	let api_cert = api.cert.der().clone();
+	let res = tls_get(&server, "api.example.test", &api_cert, None, "/mtls/whoami").await;
+	assert_eq!(res.unwrap().status, 200);
+	let res = tls_get(&server, "api.example.test", &original, None, "/mtls/whoami").await;
+	assert!(res.is_err());
+
+	let rotated = write_server_cert(&dir, 0);
+	let mut reloaded = false;
+	for _ in 0..100 {
+		if tls_get(&server, "localhost", &rotated, None, "/mtls/whoami").await.is_ok() {
+			reloaded = true;
+			break;
 		}
+		tokio::time::sleep(Duration::from_millis(20)).await;
 	}
-	let elapsed = start.elapsed();
+	assert!(reloaded);
+	let res = tls_get(&server, "localhost", &original, None, "/mtls/whoami").await;
+	assert!(res.is_err());
+	let res = tls_get(&server, "api.example.test", &api_cert, None, "/mtls/whoami").await;
+	assert_eq!(res.unwrap().status, 200);
 
-	assert_eq!(ok, 200, "all concurrent requests should succeed");
-	assert!(
-		elapsed < Duration::from_secs(30),
-		"200 concurrent requests took too long: {elapsed:?}"
-	);
-	eprintln!("perf: 200 concurrent requests in {elapsed:?}");
+	std::fs::remove_dir_all(dir).unwrap();
 }
 
 #[tokio::test]
-async fn perf_sequential_throughput_is_reasonable() {
-	let server = functional_server().await;
+async fn listeners_scope_routes_and_body_limits() {
+	let admin = free_addr();
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.module(app::AppModule::new())
+		.module(app::AdminScopeModule::new())
+		.middleware(app::StampMiddleware)
+		.bind_listeners([
+			MurListener::tcp(free_addr())
+				.unwrap()
+				.deny_routes(["/admin"])
+				.body_limit(64),
+			MurListener::tcp(admin).unwrap().allow_routes(["/admin"]),
+		])
+		.expect("bind listeners");
+	let server = TestServer::start(runner).await;
 
-	let n = 300;
-	let start = std::time::Instant::now();
-	for _ in 0..n {
-		let res = server.get("/api/hello").await;
-		assert_eq!(res.status, 200);
-	}
-	let elapsed = start.elapsed();
+	assert_eq!(server.get("/api/hello").await.status, 200);
+	assert_eq!(server.get("/admin/stats").await.status, 404);
+	assert_eq!(server.get("//admin/stats/42").await.status, 404);
+	assert_eq!(server.get("/admin//stats/42").await.status, 404);
+	let blocked = [("x-mw-block", "1")];
+	assert_eq!(server.get_with("/admin/stats", &blocked).await.status, 404);
+	let res = raw_request(admin, "GET", "/admin/stats", &[], Vec::new()).await;
+	assert_eq!(res.status, 200);
+	let res = raw_request(admin, "GET", "//admin/stats/42", &[], Vec::new()).await;
+	assert_eq!(res.status, 200);
+	assert_eq!(res.json()["day"], 42);
+	let res = raw_request(admin, "GET", "/api/hello", &[], Vec::new()).await;
+	assert_eq!(res.status, 404);
 
-	assert!(
-		elapsed < Duration::from_secs(30),
-		"{n} sequential requests took too long: {elapsed:?}"
-	);
-	let rps = n as f64 / elapsed.as_secs_f64();
-	eprintln!("perf: {n} sequential requests in {elapsed:?} (~{rps:.0} req/s)");
+	let body = format!(r#"{{"name":"{}","value":1}}"#, "x".repeat(512));
+	assert_eq!(server.post_json("/api/echo", &body).await.status, 413);
 }
 
-// ===========================================================================
-// Timeout middleware
-// ===========================================================================
-
-async fn timeout_server() -> TestServer {
-	use murgamu::server::middleware::timeout::MurTimeout;
-	let addr = free_addr();
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_socket_and_https_redirect_listeners() {
+	let socket = std::env::temp_dir().join(format!("mur-{}.sock", uuid::Uuid::new_v4()));
+	let redirect = free_addr();
 	let runner = MurServer::new()
 		.no_logging()
 		.default_public_routes()
-		.middleware(MurTimeout::from_millis(80))
 		.module(app::AppModule::new())
-		.bind(addr)
-		.expect("bind timeout server");
-	TestServer::start(runner).await
-}
+		.listener(MurListener::unix(&socket))
+		.listener(MurListener::https_redirect(redirect, 8443).unwrap())
+		.bind(free_addr())
+		.expect("bind listeners");
+	let server = TestServer::start(runner).await;
 
-#[tokio::test]
-async fn slow_handler_times_out() {
-	let server = timeout_server().await;
-	// /api/slow sleeps 400ms, well past the 80ms budget.
-	let res = server.get("/api/slow").await;
-	assert_eq!(res.status, 408);
+	let headers = [("host", "example.com:8080")];
+	let res = raw_request(redirect, "GET", "/api/hello?x=1", &headers, Vec::new()).await;
+	assert_eq!(res.status, 308);
+	assert_eq!(
+		res.header("location"),
+		Some("https://example.com:8443/api/hello?x=1")
+	);
+
+	let stream = tokio::net::UnixStream::connect(&socket).await.expect("connect");
+	let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+		.await
+		.expect("handshake");
+	tokio::spawn(conn);
+	let req = Request::get("/api/hello")
+		.header("host", "localhost")
+		.body(Full::new(Bytes::new()))
+		.unwrap();
+	let res = sender.send_request(req).await.expect("send request");
+	assert_eq!(res.status(), 200);
+
+	drop(server);
+	for _ in 0..100 {
+		if !socket.exists() {
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	assert!(!socket.exists());
 }
 
+#[cfg(unix)]
 #[tokio::test]
-async fn fast_handler_under_timeout_succeeds() {
-	let server = timeout_server().await;
-	let res = server.get("/api/hello").await;
+async fn inherited_and_reuse_port_listeners_share_traffic() {
+	use std::os::fd::IntoRawFd;
+
+	let inherited = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+	let inherited_addr = inherited.local_addr().unwrap();
+	let addr = free_addr();
+	let server = |listeners: Vec<MurListener>| {
+		listeners
+			.into_iter()
+			.fold(MurServer::new(), MurServer::listener)
+			.no_logging()
+			.default_public_routes()
+			.module(app::AppModule::new())
+			.reuse_port()
+			.bind(addr)
+			.expect("bind reuse-port server")
+	};
+	let old = TestServer::start(server(vec![MurListener::inherited(inherited.into_raw_fd())])).await;
+	let new = TestServer::start(server(Vec::new())).await;
+
+	let res = raw_request(inherited_addr, "GET", "/api/hello", &[], Vec::new()).await;
 	assert_eq!(res.status, 200);
-}
+	assert_eq!(old.get("/api/hello").await.status, 200);
 
-// ===========================================================================
-// Compression middleware
-// ===========================================================================
+	drop(old);
+	tokio::time::sleep(Duration::from_millis(100)).await;
+	for _ in 0..20 {
+		assert_eq!(new.get("/api/hello").await.status, 200);
+	}
+}
 
-async fn compression_server() -> TestServer {
-	use murgamu::server::middleware::compression::MurCompression;
-	let addr = free_addr();
+#[cfg(unix)]
+#[tokio::test]
+async fn handoff_signal_drains_the_previous_server() {
+	let pid_file = std::env::temp_dir().join(format!("mur-{}.pid", uuid::Uuid::new_v4()));
 	let runner = MurServer::new()
 		.no_logging()
 		.default_public_routes()
-		.middleware(MurCompression::gzip_only())
 		.module(app::AppModule::new())
-		.bind(addr)
-		.expect("bind compression server");
-	TestServer::start(runner).await
+		.handoff_pid_file(&pid_file)
+		.bind(free_addr())
+		.expect("bind hand-off server");
+	let task = tokio::spawn(runner.run_until(std::future::pending()));
+
+	// The pid is written once the server listens for its successor.
+	let pid = std::process::id().to_string();
+	for _ in 0..200 {
+		if std::fs::read_to_string(&pid_file).is_ok_and(|p| p.trim() == pid) {
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	let status = std::process::Command::new("kill")
+		.args(["-USR2", &pid])
+		.status()
+		.unwrap();
+	assert!(status.success());
+
+	let result = tokio::time::timeout(Duration::from_secs(5), task).await;
+	assert!(result.expect("server drained").unwrap().is_ok());
+	assert!(!pid_file.exists());
 }
 
 #[tokio::test]
-async fn large_response_is_gzip_compressed_when_accepted() {
-	let server = compression_server().await;
-	let res = server
-		.get_with("/api/large", &[("accept-encoding", "gzip")])
-		.await;
-
-	assert_eq!(res.status, 200);
-	assert_eq!(res.header("content-encoding"), Some("gzip"));
-	// Highly repetitive ~4KB JSON must shrink dramatically.
-	assert!(
-		res.body.len() < 4096,
-		"compressed body len: {}",
-		res.body.len()
-	);
+async fn shutdown_drains_readiness_before_cancelling_handlers() {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.module(app::LifecycleModule::new())
+		.pre_stop_delay(Duration::from_millis(300))
+		.bind(free_addr())
+		.expect("bind lifecycle server");
+	let token = runner.shutdown_token();
+	let mut server = TestServer::start(runner).await;
+	assert_eq!(server.get("/lifecycle/ready").await.status, 200);
+	assert_eq!(server.get("/lifecycle/ready-unwired").await.status, 200);
 
-	// The payload must be a valid gzip stream that restores the original JSON.
-	use std::io::Read;
-	let mut decoder = flate2::read::GzDecoder::new(&res.body[..]);
-	let mut restored = String::new();
-	decoder.read_to_string(&mut restored).expect("valid gzip");
-	let value: serde_json::Value = serde_json::from_str(&restored).expect("valid json");
-	assert_eq!(value["data"].as_str().unwrap().len(), 4096);
-}
+	let addr = server.addr;
+	let waiting = tokio::spawn(async move {
+		raw_request(addr, "GET", "/lifecycle/wait", &[], Vec::new()).await
+	});
+	tokio::time::sleep(Duration::from_millis(50)).await;
+	server.shutdown.take().unwrap().send(()).unwrap();
+	for _ in 0..100 {
+		if token.is_draining() {
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(5)).await;
+	}
 
-#[tokio::test]
-async fn response_not_compressed_without_accept_encoding() {
-	let server = compression_server().await;
-	let res = server.get("/api/large").await;
+	// Still accepting during the pre-stop delay, but no longer ready.
+	assert_eq!(server.get("/lifecycle/ready").await.status, 503);
+	assert_eq!(server.get("/lifecycle/ready-unwired").await.status, 503);
+	assert!(!token.is_cancelled());
 
+	let res = tokio::time::timeout(Duration::from_secs(5), waiting).await;
+	let res = res.expect("handler notified").unwrap();
 	assert_eq!(res.status, 200);
-	assert!(res.header("content-encoding").is_none());
+	assert_eq!(res.json()["cancelled"], true);
+	assert!(token.is_cancelled());
 }
 
 #[tokio::test]
-async fn small_response_is_not_compressed() {
-	let server = compression_server().await;
-	// /api/hello is far below the 860-byte min_size threshold.
-	let res = server
-		.get_with("/api/hello", &[("accept-encoding", "gzip")])
-		.await;
+async fn metrics_module_reports_http_connection_and_custom_metrics() {
+	let runner = MurServer::new()
+		.no_logging()
+		.default_public_routes()
+		.module_in_place(MurMetricsModule::new())
+		.middleware(
+			MurThrottler::new()
+				.by_ip()
+				.requests(6)
+				.per_minutes(1)
+				.skip_path("/metrics"),
+		)
+		.module(app::AppModule::new())
+		.module(app::CheckoutModule::new())
+		.bind(free_addr())
+		.expect("bind metrics server");
+	let server = TestServer::start(runner).await;
 
+	assert_eq!(server.get("/api/users/1").await.status, 200);
+	assert_eq!(server.get("/api/users/2").await.status, 200);
+	let head = server.send("HEAD", "/api/users/1", &[], Vec::new()).await;
+	assert_eq!(head.status, 200);
+	let brew = server.send("BREW", "/api/users/1", &[], Vec::new()).await;
+	assert_eq!(brew.status, 405);
+	assert_eq!(server.get("/nope").await.status, 404);
+	let placed = server.send("POST", "/checkout/gold", &[], Vec::new()).await;
+	assert_eq!(placed.status, 200);
+	assert_eq!(server.get("/api/users/3").await.status, 429);
+
+	let res = server.get("/metrics").await;
 	assert_eq!(res.status, 200);
-	assert!(res.header("content-encoding").is_none());
+	assert_eq!(
+		res.header("content-type"),
+		Some("text/plain; version=0.0.4; charset=utf-8")
+	);
+	let body = res.text();
+	let lines: Vec<&str> = body.lines().collect();
+	for expected in [
+		"# TYPE murgamu_http_requests_total counter",
+		r#"murgamu_http_requests_total{method="GET",route="/api/users/:id",status="2xx"} 2"#,
+		r#"murgamu_http_requests_total{method="GET",route="/api/users/:id",status="4xx"} 1"#,
+		r#"murgamu_http_requests_total{method="GET",route="unmatched",status="4xx"} 1"#,
+		r#"murgamu_http_requests_total{method="HEAD",route="/api/users/:id",status="2xx"} 1"#,
+		r#"murgamu_http_requests_total{method="OTHER",route="unmatched",status="4xx"} 1"#,
+		r#"murgamu_http_requests_total{method="POST",route="/checkout/:tier",status="2xx"} 1"#,
+		r#"murgamu_http_rate_limited_total{route="/api/users/:id"} 1"#,
+		"# TYPE murgamu_http_request_duration_seconds histogram",
+		concat!(
+			"murgamu_http_request_duration_seconds_count",
+			r#"{method="GET",route="/api/users/:id",status="2xx"} 2"#
+		),
+		r#"orders_placed_total{tier="gold"} 1"#,
+	] {
+		assert!(lines.contains(&expected), "missing {expected:?} in:\n{body}");
+	}
+	let active = lines
+		.iter()
+		.find_map(|line| line.strip_prefix("murgamu_connections_active "))
+		.expect("connection gauge");
+	assert!(active.parse::<f64>().unwrap() >= 1.0);
+	let accepted = lines
+		.iter()
+		.find_map(|line| line.strip_prefix("murgamu_connections_accepted_total "))
+		.expect("connection counter");
+	assert!(accepted.parse::<f64>().unwrap() >= 6.0);
+	assert!(body.contains("murgamu_http_request_duration_seconds_bucket{"));
 }